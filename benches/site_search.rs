@@ -0,0 +1,122 @@
+use std::hint::black_box;
+
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{Criterion, criterion_group, criterion_main};
+use travelai::{
+    adapters::activities::paragliding::{evaluation_cache::EvaluationCache, site_evaluator},
+    domain::{
+        location::Location,
+        paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType, index::SiteIndex},
+        weather::{WeatherData, WeatherForecast},
+    },
+};
+
+const SITE_COUNT: usize = 30_000;
+
+/// Lays the sites out on a deterministic grid spanning roughly the Alps and the
+/// German uplands, so radius queries hit a realistic share of the dataset.
+fn synthetic_sites(count: usize) -> Vec<ParaglidingSite> {
+    let columns = (count as f64).sqrt().ceil() as usize;
+    (0..count)
+        .map(|i| {
+            let latitude = 44.0 + 11.0 * (i / columns) as f64 / columns as f64;
+            let longitude = 5.0 + 15.0 * (i % columns) as f64 / columns as f64;
+            let direction = (i * 37 % 360) as f64;
+            ParaglidingSite {
+                name: format!("site-{i}"),
+                launches: vec![ParaglidingLaunch {
+                    site_type: SiteType::Hang,
+                    location: Location::new(
+                        latitude,
+                        longitude,
+                        format!("launch-{i}"),
+                        "DE".into(),
+                    ),
+                    direction_degrees_start: direction,
+                    direction_degrees_stop: (direction + 90.0) % 360.0,
+                    elevation: 500.0 + (i % 1500) as f64,
+                }],
+                country: Some("DE".into()),
+                data_source: "bench".into(),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn seven_day_forecast(location: Location) -> WeatherForecast {
+    let start = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
+    WeatherForecast {
+        location,
+        grid_elevation: None,
+        fetched_at: None,
+        provider: None,
+        forecast: (0..7 * 24)
+            .map(|h| WeatherData {
+                timestamp: start + Duration::hours(h),
+                temperature: 18.0,
+                wind_speed_ms: (h % 9) as f32,
+                wind_direction: (h * 15 % 360) as u16,
+                wind_gust_ms: (h % 9) as f32 + 3.0,
+                precipitation: if h % 11 == 0 { 0.4 } else { 0.0 },
+                cloud_cover: (h % 100) as u8,
+                pressure: 1013.0,
+                visibility: 20.0,
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn bench_sites_within_radius(c: &mut Criterion) {
+    let sites = SiteIndex::new(synthetic_sites(SITE_COUNT));
+    let home = Location::new(50.7, 13.0, "Home".into(), "DE".into());
+
+    c.bench_function("sites_within_radius_150km_30k", |b| {
+        b.iter(|| black_box(&sites).within_radius(black_box(&home), 150.0))
+    });
+}
+
+fn bench_sites_along_route(c: &mut Criterion) {
+    let sites = SiteIndex::new(synthetic_sites(SITE_COUNT));
+    let route = [(48.1, 11.6), (47.3, 11.4), (46.5, 11.3)];
+
+    c.bench_function("sites_along_route_20km_30k", |b| {
+        b.iter(|| black_box(&sites).along_route(black_box(&route), 20.0))
+    });
+}
+
+fn bench_evaluate_site(c: &mut Criterion) {
+    let site = synthetic_sites(1).remove(0);
+    let forecast = seven_day_forecast(site.launches[0].location.clone());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    c.bench_function("evaluate_site_7_days_hourly", |b| {
+        b.to_async(&runtime)
+            .iter(|| site_evaluator::evaluate_site(black_box(&site), black_box(&forecast)))
+    });
+}
+
+/// A repeat evaluation of the same run, to compare with re-scoring above.
+fn bench_cached_evaluation(c: &mut Criterion) {
+    let site = synthetic_sites(1).remove(0);
+    let mut forecast = seven_day_forecast(site.launches[0].location.clone());
+    forecast.fetched_at = Some(Utc::now());
+    let evaluations = EvaluationCache::default();
+    evaluations.evaluate(&site, &forecast);
+
+    c.bench_function("evaluate_site_7_days_hourly_cached", |b| {
+        b.iter(|| evaluations.evaluate(black_box(&site), black_box(&forecast)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_sites_within_radius,
+    bench_sites_along_route,
+    bench_evaluate_site,
+    bench_cached_evaluation
+);
+criterion_main!(benches);
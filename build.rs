@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "grpc")]
+    tonic_prost_build::compile_protos("proto/travelai/v1/forecast.proto")?;
+    Ok(())
+}
@@ -0,0 +1,9 @@
+//! The scoring that needs no network: wind sectors and whether an hour is
+//! flyable at a launch. The server scores forecasts with it, and compiled to
+//! `wasm32-unknown-unknown` it lets the frontend map re-score sites as soon as
+//! a threshold is changed.
+
+pub mod score;
+pub mod sector;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
@@ -0,0 +1,222 @@
+//! Whether an hour is flyable at a launch. Speeds are in m/s, like the
+//! forecasts.
+
+use serde::{Deserialize, Serialize};
+
+use crate::sector;
+
+const KMH_PER_MS: f32 = 3.6;
+
+/// Wind at or above which a launch is not flyable. Missing fields keep their
+/// defaults when deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    pub max_wind_ms: f32,
+    pub max_gust_ms: f32,
+    /// Crosswind at which a launch stops counting as aligned. Crosswind makes
+    /// launching and soaring the ridge much harder than the same speed
+    /// straight in.
+    pub max_crosswind_ms: f32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            max_wind_ms: 25.0 / KMH_PER_MS,
+            max_gust_ms: 40.0 / KMH_PER_MS,
+            max_crosswind_ms: 12.0 / KMH_PER_MS,
+        }
+    }
+}
+
+/// The forecast for one hour, as far as scoring needs it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hour {
+    pub wind_speed_ms: f32,
+    pub wind_gust_ms: f32,
+    pub wind_direction: f64,
+    /// In mm
+    pub precipitation: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Launch {
+    pub direction_degrees_start: f64,
+    pub direction_degrees_stop: f64,
+    /// Winch launches need a tow crew and are never scored flyable
+    #[serde(default)]
+    pub winch: bool,
+}
+
+pub fn is_flyable(hour: &Hour, launch: &Launch, thresholds: &Thresholds) -> bool {
+    if launch.winch {
+        return false;
+    }
+    if hour.precipitation != 0.0 {
+        return false;
+    }
+    if hour.wind_speed_ms >= thresholds.max_wind_ms {
+        return false;
+    }
+    if hour.wind_gust_ms >= thresholds.max_gust_ms {
+        return false;
+    }
+    sector::wind_direction_in_sector(
+        hour.wind_direction,
+        launch.direction_degrees_start,
+        launch.direction_degrees_stop,
+    ) && wind_alignment(hour, launch, thresholds) > 0.0
+}
+
+/// 1 for wind straight into the launch, falling to 0 as the crosswind reaches
+/// the threshold. Launches open to every direction are always aligned.
+pub fn wind_alignment(hour: &Hour, launch: &Launch, thresholds: &Thresholds) -> f32 {
+    let Some(aspect) = sector::central_aspect(
+        launch.direction_degrees_start,
+        launch.direction_degrees_stop,
+    ) else {
+        return 1.0;
+    };
+    let crosswind = sector::crosswind(hour.wind_speed_ms, hour.wind_direction, aspect);
+    (1.0 - crosswind / thresholds.max_crosswind_ms).clamp(0.0, 1.0)
+}
+
+/// What rules out an hour at every launch of a site. Rain and wind rule out
+/// every launch at once, so they are named before the launch direction.
+/// Airspace is not among them: a restriction limits how high or where one
+/// flies, not whether one can, so it is only a hazard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Factor {
+    /// No launch is scored at all, e.g. only winch launches
+    NoFootLaunch,
+    Rain,
+    Wind,
+    Gusts,
+    /// No launch faces into the wind, or the crosswind is too strong
+    Direction,
+    /// The site is closed that day, which only the caller knows
+    Closure,
+    /// Too little of the hour is before sunset, which only the caller knows
+    Darkness,
+    /// Cumulus are expected to have overdeveloped by then, which only the
+    /// caller knows
+    Overdevelopment,
+}
+
+/// Why no launch is flyable in `hour`, or None if one is.
+pub fn limiting_factor(
+    hour: &Hour,
+    launches: &[Launch],
+    thresholds: &Thresholds,
+) -> Option<Factor> {
+    if launches
+        .iter()
+        .any(|launch| is_flyable(hour, launch, thresholds))
+    {
+        return None;
+    }
+    Some(if launches.iter().all(|launch| launch.winch) {
+        Factor::NoFootLaunch
+    } else if hour.precipitation != 0.0 {
+        Factor::Rain
+    } else if hour.wind_speed_ms >= thresholds.max_wind_ms {
+        Factor::Wind
+    } else if hour.wind_gust_ms >= thresholds.max_gust_ms {
+        Factor::Gusts
+    } else {
+        Factor::Direction
+    })
+}
+
+/// The best wind alignment among the launches flyable in `hour`, or None if
+/// none is.
+pub fn best_alignment(hour: &Hour, launches: &[Launch], thresholds: &Thresholds) -> Option<f32> {
+    launches
+        .iter()
+        .filter(|launch| is_flyable(hour, launch, thresholds))
+        .map(|launch| wind_alignment(hour, launch, thresholds))
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(wind_kmh: f32, direction: f64) -> Hour {
+        Hour {
+            wind_speed_ms: wind_kmh / KMH_PER_MS,
+            wind_gust_ms: wind_kmh / KMH_PER_MS,
+            wind_direction: direction,
+            precipitation: 0.0,
+        }
+    }
+
+    const WEST: Launch = Launch {
+        direction_degrees_start: 225.0,
+        direction_degrees_stop: 315.0,
+        winch: false,
+    };
+
+    #[test]
+    fn best_alignment_picks_the_launch_most_into_wind() {
+        let south = Launch {
+            direction_degrees_start: 135.0,
+            direction_degrees_stop: 225.0,
+            ..WEST
+        };
+        let thresholds = Thresholds::default();
+
+        let best = best_alignment(&hour(15.0, 250.0), &[south, WEST], &thresholds).unwrap();
+        assert!((best - wind_alignment(&hour(15.0, 250.0), &WEST, &thresholds)).abs() < 1e-6);
+        assert_eq!(
+            best_alignment(&hour(15.0, 90.0), &[south, WEST], &thresholds),
+            None
+        );
+    }
+
+    #[test]
+    fn limiting_factor_names_what_rules_out_the_hour() {
+        let thresholds = Thresholds::default();
+        let winch = Launch {
+            winch: true,
+            ..WEST
+        };
+        let factor =
+            |hour: Hour, launches: &[Launch]| limiting_factor(&hour, launches, &thresholds);
+
+        assert_eq!(factor(hour(15.0, 270.0), &[WEST]), None);
+        assert_eq!(
+            factor(hour(15.0, 270.0), &[winch]),
+            Some(Factor::NoFootLaunch)
+        );
+        assert_eq!(factor(hour(15.0, 270.0), &[]), Some(Factor::NoFootLaunch));
+        let rain = Hour {
+            precipitation: 0.4,
+            ..hour(30.0, 270.0)
+        };
+        assert_eq!(factor(rain, &[WEST]), Some(Factor::Rain));
+        assert_eq!(factor(hour(30.0, 270.0), &[WEST]), Some(Factor::Wind));
+        let gusty = Hour {
+            wind_gust_ms: 45.0 / KMH_PER_MS,
+            ..hour(15.0, 270.0)
+        };
+        assert_eq!(factor(gusty, &[WEST]), Some(Factor::Gusts));
+        assert_eq!(
+            factor(hour(15.0, 90.0), &[WEST, winch]),
+            Some(Factor::Direction)
+        );
+    }
+
+    #[test]
+    fn tighter_thresholds_rescore_the_same_hour() {
+        let strong = hour(20.0, 270.0);
+        assert!(is_flyable(&strong, &WEST, &Thresholds::default()));
+
+        let cautious = Thresholds {
+            max_wind_ms: 15.0 / KMH_PER_MS,
+            ..Thresholds::default()
+        };
+        assert!(!is_flyable(&strong, &WEST, &cautious));
+    }
+}
@@ -0,0 +1,32 @@
+//! Launch direction sectors, in degrees the wind blows from, clockwise from
+//! north.
+
+/// Whether wind from `wind_dir` blows into the sector from `start` clockwise
+/// to `stop`, edges excluded.
+pub fn wind_direction_in_sector(wind_dir: f64, start: f64, stop: f64) -> bool {
+    // start == stop is the conventional way to say "launchable from any direction"
+    // (e.g. a flat-top site). Without this branch the strict-< sector check would
+    // reject every wind, since `start < wind && wind < start` is never true.
+    if start == stop {
+        return true;
+    }
+    if start < stop {
+        start < wind_dir && wind_dir < stop
+    } else {
+        start < wind_dir || wind_dir < stop
+    }
+}
+
+/// Wind direction the sector faces straight into, its middle. None for
+/// sectors open to every direction.
+pub fn central_aspect(start: f64, stop: f64) -> Option<f64> {
+    let width = (stop - start).rem_euclid(360.0);
+    (width > 0.0).then(|| (start + width / 2.0).rem_euclid(360.0))
+}
+
+/// The part of `speed` blowing across a slope that faces into wind from
+/// `aspect`, to either side.
+pub fn crosswind(speed: f32, direction: f64, aspect: f64) -> f32 {
+    let off_axis = (direction - aspect).to_radians();
+    (speed * off_axis.sin() as f32).abs()
+}
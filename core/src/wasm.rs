@@ -0,0 +1,64 @@
+//! `extern "C"` exports for the browser, without a bindings generator. The
+//! caller copies a JSON [`Request`] into memory from [`alloc`], calls
+//! [`score`] and reads the JSON [`Response`] it points to, then frees both
+//! with [`dealloc`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::score::{self, Hour, Launch, Thresholds};
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    thresholds: Thresholds,
+    launches: Vec<Launch>,
+    hours: Vec<Hour>,
+}
+
+/// Per hour, the best wind alignment among the flyable launches, or null.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Response {
+    Scores(Vec<Option<f32>>),
+    Error { error: String },
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    Box::into_raw(vec![0u8; len].into_boxed_slice()).cast()
+}
+
+/// # Safety
+///
+/// `ptr` and `len` must come from [`alloc`] or [`score`] and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) });
+}
+
+/// Returns the response's address in the high and its length in the low 32
+/// bits.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` initialized bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn score(ptr: *const u8, len: usize) -> u64 {
+    let request = unsafe { std::slice::from_raw_parts(ptr, len) };
+    let response = match serde_json::from_slice::<Request>(request) {
+        Ok(request) => Response::Scores(
+            request
+                .hours
+                .iter()
+                .map(|hour| score::best_alignment(hour, &request.launches, &request.thresholds))
+                .collect(),
+        ),
+        Err(e) => Response::Error {
+            error: e.to_string(),
+        },
+    };
+    let json = serde_json::to_vec(&response).unwrap_or_default();
+    let len = json.len();
+    let ptr = Box::into_raw(json.into_boxed_slice()).cast::<u8>();
+    ((ptr as u64) << 32) | len as u64
+}
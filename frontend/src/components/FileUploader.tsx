@@ -22,6 +22,14 @@ export function FileUploader() {
       {result && (
         <div className="upload-result success">
           Imported {result.imported} sites
+          {result.skipped && result.skipped.length > 0 && (
+            <> ({result.skipped.length} records skipped)</>
+          )}
+        </div>
+      )}
+      {result?.schema_drift && (
+        <div className="upload-result error">
+          Many records could not be read. The DHV file format may have changed.
         </div>
       )}
       {error && (
@@ -56,7 +56,7 @@ describe("LocationPicker", () => {
   test("fetches elevation when map is clicked and calls onChange", async () => {
     (fetch as unknown as ReturnType<typeof vi.fn>).mockResolvedValue({
       ok: true,
-      json: async () => ({ elevation: 1700 }),
+      json: async () => ({ elevations: [1700] }),
     });
     const onChange = vi.fn();
     render(
@@ -82,8 +82,9 @@ describe("LocationPicker", () => {
       );
     });
 
-    const callUrl = (fetch as unknown as ReturnType<typeof vi.fn>).mock.calls[0]?.[0];
-    expect(callUrl).toContain("/api/elevation?latitude=48&longitude=11");
+    const [callUrl, init] = (fetch as unknown as ReturnType<typeof vi.fn>).mock.calls[0]!;
+    expect(callUrl).toContain("/api/elevation/batch");
+    expect(JSON.parse(init.body)).toEqual({ points: [[48, 11]] });
   });
 
   test("still notifies parent of new location even if elevation fetch fails", async () => {
@@ -3,14 +3,9 @@ import { MapContainer, TileLayer, Marker } from "react-leaflet";
 import type L from "leaflet";
 import "leaflet/dist/leaflet.css";
 import styles from "./LocationPicker.module.css";
-import { API } from "../config/api";
 import { ApiLocation } from "../hooks/useSites";
 import { MapClickHandler } from "../utils/leaflet";
-import { fetchJson } from "../utils/fetchJson";
-
-interface ElevationResponse {
-  elevation: number;
-}
+import { fetchElevation } from "../utils/elevation";
 
 interface LocationPickerProps {
   location: ApiLocation;
@@ -31,9 +26,9 @@ export function LocationPicker({ location, elevation, onChange }: LocationPicker
     onChange(newLocation, pickElev);
     setLoadingElevation(true);
     try {
-      const data = await fetchJson<ElevationResponse>(API.elevation(lat, lng));
-      setPickElev(data.elevation);
-      onChange(newLocation, data.elevation);
+      const elevation = await fetchElevation(lat, lng);
+      setPickElev(elevation);
+      onChange(newLocation, elevation);
     } catch (error) {
       console.error("Failed to fetch elevation:", error);
     } finally {
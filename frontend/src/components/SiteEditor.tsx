@@ -86,6 +86,8 @@ export function SiteEditor({ site, defaultCenter, onSave, onDelete, onCancel }:
   const [landings, setLandings] = useState<ApiLanding[]>(site.landings);
   const [parkingLocation, setParkingLocation] = useState(site.parking_location);
   const [muteAlerts, setMuteAlerts] = useState(site.mute_alerts || false);
+  const [winterOperable, setWinterOperable] = useState(site.winter_operable || false);
+  const [favorite, setFavorite] = useState(site.favorite || false);
   const [rating, setRating] = useState(site.rating || 0);
   const [preferredWeatherModel, setPreferredWeatherModel] = useState(site.preferred_weather_model);
 
@@ -145,8 +147,11 @@ export function SiteEditor({ site, defaultCenter, onSave, onDelete, onCancel }:
       data_source: site.data_source || "API",
       parking_location: parkingLocation || undefined,
       mute_alerts: muteAlerts || undefined,
+      winter_operable: winterOperable || undefined,
+      favorite: favorite || undefined,
       rating: rating > 0 ? rating : undefined,
       preferred_weather_model: preferredWeatherModel || undefined,
+      characteristics: site.characteristics,
     });
   };
 
@@ -194,6 +199,28 @@ export function SiteEditor({ site, defaultCenter, onSave, onDelete, onCancel }:
         </label>
       </div>
 
+      <div className={styles.formGroup}>
+        <label>
+          <input
+            type="checkbox"
+            checked={winterOperable}
+            onChange={(e) => setWinterOperable(e.target.checked)}
+          />
+          Launch usable with snow cover
+        </label>
+      </div>
+
+      <div className={styles.formGroup}>
+        <label>
+          <input
+            type="checkbox"
+            checked={favorite}
+            onChange={(e) => setFavorite(e.target.checked)}
+          />
+          Favorite (suggest alternatives when unflyable)
+        </label>
+      </div>
+
       <div className={styles.formGroup}>
         <label>Preferred Weather Model:</label>
         <select
@@ -13,6 +13,7 @@ export const API = {
   siteDelete: (name: string) => api(`/api/sites/${encodeURIComponent(name)}`),
   weatherModels: api("/api/weather-models"),
   elevation: (lat: number, lng: number) => api(`/api/elevation?latitude=${lat}&longitude=${lng}`),
+  elevationBatch: api("/api/elevation/batch"),
   flightAnalyze: api("/api/flights/analyze"),
   calendarRefresh: api("/api/calendar/refresh"),
 } as const;
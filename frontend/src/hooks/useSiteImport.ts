@@ -3,8 +3,16 @@ import { API } from "../config/api";
 import { fetchJson } from "../utils/fetchJson";
 import { sitesQueryKey } from "./useSites";
 
+export interface SkippedRecord {
+  index: number;
+  site_name: string | null;
+  error: string;
+}
+
 export interface ImportResponse {
   imported: number;
+  skipped?: SkippedRecord[];
+  schema_drift?: boolean;
 }
 
 export function useSiteImport() {
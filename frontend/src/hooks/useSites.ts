@@ -22,6 +22,12 @@ export interface ApiLanding {
   elevation: number;
 }
 
+export interface ApiSiteCharacteristics {
+  flight_rules: string | null;
+  going_there: string | null;
+  weather: string | null;
+}
+
 export interface ApiSite {
   name: string;
   country: string | null;
@@ -30,8 +36,11 @@ export interface ApiSite {
   data_source: string;
   parking_location?: ApiLocation;
   mute_alerts?: boolean;
+  winter_operable?: boolean;
+  favorite?: boolean;
   rating?: number;
   preferred_weather_model?: string;
+  characteristics?: ApiSiteCharacteristics;
 }
 
 export const sitesQueryKey = ["sites"] as const;
@@ -0,0 +1,50 @@
+import { describe, test, expect, beforeEach, vi, afterEach } from "vitest";
+import { fetchElevation } from "./elevation";
+
+describe("fetchElevation", () => {
+  beforeEach(() => {
+    vi.stubGlobal("fetch", vi.fn());
+  });
+
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  test("batches calls of the same tick into one request", async () => {
+    (fetch as unknown as ReturnType<typeof vi.fn>).mockResolvedValue({
+      ok: true,
+      json: async () => ({ elevations: [500, 1200] }),
+    });
+
+    const result = await Promise.all([fetchElevation(47.5, 10.25), fetchElevation(46.0, 8.0)]);
+
+    expect(result).toEqual([500, 1200]);
+    expect(fetch).toHaveBeenCalledTimes(1);
+    const [url, init] = (fetch as unknown as ReturnType<typeof vi.fn>).mock.calls[0]!;
+    expect(url).toBe("/api/elevation/batch");
+    expect(JSON.parse(init.body)).toEqual({ points: [[47.5, 10.25], [46.0, 8.0]] });
+  });
+
+  test("rejects every call of a failed batch", async () => {
+    (fetch as unknown as ReturnType<typeof vi.fn>).mockResolvedValue({
+      ok: false,
+      status: 500,
+      statusText: "Internal Server Error",
+      json: async () => ({}),
+    });
+
+    const results = await Promise.allSettled([fetchElevation(1, 2), fetchElevation(3, 4)]);
+    expect(results.map((r) => r.status)).toEqual(["rejected", "rejected"]);
+  });
+
+  test("rejects points the response has no elevation for", async () => {
+    (fetch as unknown as ReturnType<typeof vi.fn>).mockResolvedValue({
+      ok: true,
+      json: async () => ({ elevations: [500] }),
+    });
+
+    const results = await Promise.allSettled([fetchElevation(1, 2), fetchElevation(3, 4)]);
+    expect(results[0]).toEqual({ status: "fulfilled", value: 500 });
+    expect(results[1]!.status).toBe("rejected");
+  });
+});
@@ -0,0 +1,55 @@
+import { API } from "../config/api";
+import { fetchJson } from "./fetchJson";
+
+// The backend answers at most this many points per request.
+const MAX_POINTS_PER_REQUEST = 1000;
+
+interface ElevationBatchResponse {
+  elevations: number[];
+}
+
+interface Pending {
+  point: [number, number];
+  resolve: (elevation: number) => void;
+  reject: (error: unknown) => void;
+}
+
+let queue: Pending[] = [];
+
+/**
+ * Elevation of a point. Calls made in the same tick share one batch request,
+ * so a map showing hundreds of markers doesn't send hundreds of requests.
+ */
+export function fetchElevation(lat: number, lng: number): Promise<number> {
+  return new Promise((resolve, reject) => {
+    if (queue.length === 0) {
+      setTimeout(flush, 0);
+    }
+    queue.push({ point: [lat, lng], resolve, reject });
+  });
+}
+
+async function flush() {
+  const pending = queue;
+  queue = [];
+  for (let i = 0; i < pending.length; i += MAX_POINTS_PER_REQUEST) {
+    const chunk = pending.slice(i, i + MAX_POINTS_PER_REQUEST);
+    try {
+      const { elevations } = await fetchJson<ElevationBatchResponse>(API.elevationBatch, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ points: chunk.map((p) => p.point) }),
+      });
+      chunk.forEach((p, index) => {
+        const elevation = elevations[index];
+        if (elevation === undefined) {
+          p.reject(new Error(`No elevation returned for ${p.point.join(", ")}`));
+        } else {
+          p.resolve(elevation);
+        }
+      });
+    } catch (error) {
+      chunk.forEach((p) => p.reject(error));
+    }
+  }
+}
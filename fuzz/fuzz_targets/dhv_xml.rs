@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use travelai::adapters::activities::paragliding::dhv;
+
+// The parser reads user uploaded files, so any input must produce an error
+// rather than a panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(xml) = std::str::from_utf8(data) {
+        let _ = dhv::parse_sites_from_xml(xml);
+    }
+});
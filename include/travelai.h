@@ -0,0 +1,38 @@
+/*
+ * C API of the travelai forecast engine, for mobile apps. Build the library
+ * with
+ *
+ *   cargo rustc --lib --release --no-default-features --features ffi \
+ *     --crate-type staticlib
+ *
+ * Every function returning char * returns a JSON string the caller frees with
+ * travelai_string_free: the result, or {"error": {"code": ..., "message": ...}}.
+ */
+
+#ifndef TRAVELAI_H
+#define TRAVELAI_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct TravelAi TravelAi;
+
+/* Opens the forecast cache in data_dir. Returns NULL on failure. */
+TravelAi *travelai_open(const char *data_dir);
+
+void travelai_close(TravelAi *travelai);
+
+/* Hourly weather forecast for the coordinates. Blocks until it is fetched. */
+char *travelai_forecast(const TravelAi *travelai, double latitude, double longitude);
+
+/* Daily flyability of a site given as JSON, as returned by GET /sites. */
+char *travelai_site_forecast(const TravelAi *travelai, const char *site_json);
+
+void travelai_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -84,7 +84,7 @@ in {
         RestartSec = "10s";
       };
 
-      script = "${cfg.package}/bin/travelai";
+      script = "${cfg.package}/bin/travelai --serve-frontend ${cfg.package}/bin/frontend/dist";
     };
 
     users.users.travelai = lib.mkIf cfg.enable {
@@ -6,8 +6,10 @@
   openssl,
   nodejs,
   enableTLS ? false,
+  enableOtel ? true,
   basePath ? "./",
 }: let
+  features = ["http"] ++ lib.optional enableTLS "tls" ++ lib.optional enableOtel "otel";
   frontend = pkgs.buildNpmPackage {
     pname = "travelai-frontend";
     version = "0.1.0";
@@ -61,7 +63,7 @@ in
 
     buildPhase = ''
       runHook preBuild
-      cargo build --release ${lib.optionalString (!enableTLS) "--no-default-features --features=http"}
+      cargo build --release --no-default-features --features=${lib.concatStringsSep "," features}
       runHook postBuild
     '';
 
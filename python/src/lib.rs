@@ -0,0 +1,115 @@
+//! The analysis engine as the Python module `travelai`, to calibrate
+//! thresholds in notebooks against flight logs. Sites, forecasts and results
+//! are plain dicts and lists shaped like the JSON of the HTTP API.
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::{Serialize, de::DeserializeOwned};
+use travelai::{
+    adapters::activities::paragliding::site_evaluator::{self, Hysteresis, WindLimits},
+    domain::{
+        location::Location,
+        paragliding::{self, ParaglidingSite},
+        units::WindSpeed,
+        weather::WeatherForecast,
+    },
+};
+use travelai_core::score::{self, Hour, Launch, Thresholds};
+
+/// Converts through JSON, so Python sees the same field names as API clients.
+fn from_python<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Daily summaries of `site` for `forecast`, with the server's wind limits
+/// unless others are given. Every run of flyable hours is a window of its own
+/// unless the hours in a row that open and close one are given.
+#[pyfunction]
+#[pyo3(signature = (
+    site,
+    forecast,
+    max_wind_kmh=None,
+    max_gust_kmh=None,
+    window_open_hours=None,
+    window_close_hours=None,
+))]
+fn evaluate_site<'py>(
+    py: Python<'py>,
+    site: &Bound<'py, PyAny>,
+    forecast: &Bound<'py, PyAny>,
+    max_wind_kmh: Option<f32>,
+    max_gust_kmh: Option<f32>,
+    window_open_hours: Option<u32>,
+    window_close_hours: Option<u32>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let site: ParaglidingSite = from_python(site)?;
+    let forecast: WeatherForecast = from_python(forecast)?;
+    let defaults = WindLimits::default();
+    let limits = WindLimits {
+        max_wind: max_wind_kmh.map_or(defaults.max_wind, WindSpeed::from_kmh),
+        max_gust: max_gust_kmh.map_or(defaults.max_gust, WindSpeed::from_kmh),
+    };
+    let hysteresis = Hysteresis {
+        open_hours: window_open_hours
+            .unwrap_or(Hysteresis::NONE.open_hours)
+            .max(1),
+        close_hours: window_close_hours
+            .unwrap_or(Hysteresis::NONE.close_hours)
+            .max(1),
+    };
+    let result = site_evaluator::evaluate_site_with_limits(&site, &forecast, &limits, hysteresis);
+    to_python(py, &result.daily_summaries)
+}
+
+/// `[site, distance_km]` pairs for every site with a launch within
+/// `radius_km`, closest first.
+#[pyfunction]
+fn sites_within_radius<'py>(
+    py: Python<'py>,
+    sites: &Bound<'py, PyAny>,
+    latitude: f64,
+    longitude: f64,
+    radius_km: f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let sites: Vec<ParaglidingSite> = from_python(sites)?;
+    let center = Location::new(latitude, longitude, String::new(), String::new());
+    to_python(
+        py,
+        &paragliding::sites_within_radius(&sites, &center, radius_km),
+    )
+}
+
+/// Per hour, the best wind alignment among the flyable `launches`, or None.
+/// The quick way to try thresholds against hours known to be flown or not.
+#[pyfunction]
+#[pyo3(signature = (launches, hours, thresholds=None))]
+fn score_hours<'py>(
+    launches: &Bound<'py, PyAny>,
+    hours: &Bound<'py, PyAny>,
+    thresholds: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Vec<Option<f32>>> {
+    let launches: Vec<Launch> = from_python(launches)?;
+    let hours: Vec<Hour> = from_python(hours)?;
+    let thresholds: Thresholds = thresholds.map(from_python).transpose()?.unwrap_or_default();
+    Ok(hours
+        .iter()
+        .map(|hour| score::best_alignment(hour, &launches, &thresholds))
+        .collect())
+}
+
+#[pymodule(name = "travelai")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(evaluate_site, m)?)?;
+    m.add_function(wrap_pyfunction!(sites_within_radius, m)?)?;
+    m.add_function(wrap_pyfunction!(score_hours, m)?)?;
+    Ok(())
+}
@@ -1 +1,2 @@
 pub mod paragliding;
+pub mod plan_b;
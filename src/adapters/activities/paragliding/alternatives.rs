@@ -0,0 +1,489 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use futures::{StreamExt, stream};
+use serde::Serialize;
+use travelai_core::score::Factor;
+
+use crate::{
+    adapters::activities::paragliding::{
+        evaluation_cache::EvaluationCache,
+        outlook,
+        repository::ParaglidingSiteRepository,
+        site_evaluator::{DailySummary, FlyableRange, SiteEvaluationResult},
+    },
+    domain::{
+        activities::SiteError,
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, ParaglidingSiteProvider, crowding::Crowding,
+            degrees_to_compass,
+        },
+        ports::WeatherProvider,
+    },
+};
+
+/// Wind alignment from which the wind counts as blowing straight in
+const STRAIGHT_IN_ALIGNMENT: f32 = 0.8;
+/// Neighbour forecasts fetched at once, so a wide radius doesn't use up the
+/// weather provider's rate limit in one burst
+const CONCURRENT_FORECASTS: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alternative {
+    pub site: String,
+    /// Distance from the favorite's closest launch
+    pub distance_km: f64,
+    pub flyable_hours: usize,
+    pub ranges: Vec<FlyableRange>,
+    pub crowding: Crowding,
+    /// Why the site works on a day the favorite doesn't
+    pub reasons: Vec<String>,
+}
+
+/// Sites to fly instead on a day the favorite is unflyable, best first.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayAlternatives {
+    pub date: NaiveDate,
+    pub alternatives: Vec<Alternative>,
+}
+
+/// The alternatives, and the neighbours left out because they couldn't be
+/// scored, so an empty day can be told apart from missing weather.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Alternatives {
+    pub days: Vec<DayAlternatives>,
+    pub errors: Vec<SiteError>,
+}
+
+async fn evaluate(
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    site: &ParaglidingSite,
+    launch: &ParaglidingLaunch,
+) -> Result<Arc<SiteEvaluationResult>, SiteError> {
+    let forecast = outlook::site_forecast(weather, site, launch).await?;
+    Ok(evaluations.evaluate(site, &forecast))
+}
+
+/// For each day `evaluation` of the favorite `favorite` has no flyable window,
+/// the flyable sites within `radius_km` of it, best first, each with the
+/// reasons it works. Days without any are left out, as are sites that aren't
+/// favorites. Windows shorter than the configured minimum flyable hours don't
+/// count. With `prefer_quiet`, a quieter site goes first unless a busier one is
+/// clearly better. A few neighbours are evaluated at a time; those that
+/// couldn't be are reported.
+pub async fn find_alternatives(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    favorite: &ParaglidingSite,
+    evaluation: &SiteEvaluationResult,
+    radius_km: f64,
+    prefer_quiet: bool,
+) -> Result<Alternatives> {
+    let Some(center) = favorite.launches.first().map(|l| l.location.clone()) else {
+        return Ok(Alternatives::default());
+    };
+    if favorite.favorite != Some(true) {
+        return Ok(Alternatives::default());
+    }
+    let min_hours = site_repo
+        .get_settings()
+        .await?
+        .unwrap_or_default()
+        .minimum_flyable_hours;
+    let unflyable: Vec<&DailySummary> = evaluation
+        .daily_summaries
+        .iter()
+        .filter(|day| !day.is_flyable_day(min_hours))
+        .collect();
+    if unflyable.is_empty() {
+        return Ok(Alternatives::default());
+    }
+
+    let neighbours: Vec<(ParaglidingSite, f64)> = site_repo
+        .fetch_launches_within_radius(&center, radius_km)
+        .await
+        .into_iter()
+        .filter(|(site, _)| site.name != favorite.name && site.mute_alerts != Some(true))
+        .collect();
+    let pending: Vec<_> = neighbours
+        .iter()
+        .filter_map(|(site, distance_km)| {
+            let launch = site.launches.first()?;
+            Some(async move {
+                let result = evaluate(weather, evaluations, site, launch).await;
+                (site, *distance_km, result)
+            })
+        })
+        .collect();
+    let results: Vec<_> = stream::iter(pending)
+        .buffer_unordered(CONCURRENT_FORECASTS)
+        .collect()
+        .await;
+    let mut errors = Vec::new();
+    let mut neighbours: Vec<(&ParaglidingSite, f64, Arc<SiteEvaluationResult>)> = results
+        .into_iter()
+        .filter_map(|(site, distance_km, result)| match result {
+            Ok(result) => Some((site, distance_km, result)),
+            Err(e) => {
+                errors.push(e);
+                None
+            }
+        })
+        .collect();
+    // The forecasts come back in any order
+    neighbours.sort_by(|a, b| a.1.total_cmp(&b.1));
+    errors.sort_by(|a, b| a.site.cmp(&b.site));
+
+    let days = unflyable
+        .into_iter()
+        .filter_map(|favorite_day| {
+            let candidates = neighbours
+                .iter()
+                .filter_map(|(site, distance_km, result)| {
+                    let day = result
+                        .daily_summaries
+                        .iter()
+                        .find(|d| d.date == favorite_day.date)?;
+                    Some((*site, *distance_km, day))
+                })
+                .collect();
+            let alternatives =
+                rank_alternatives(favorite, favorite_day, candidates, min_hours, prefer_quiet);
+            (!alternatives.is_empty()).then_some(DayAlternatives {
+                date: favorite_day.date,
+                alternatives,
+            })
+        })
+        .collect();
+    Ok(Alternatives { days, errors })
+}
+
+/// Flyable hours a site must have beyond a quieter one to rank ahead of it
+/// when quiet sites are preferred, per step of crowding.
+fn crowding_penalty_hours(crowding: Crowding) -> usize {
+    match crowding {
+        Crowding::Low => 0,
+        Crowding::Moderate => 1,
+        Crowding::High => 2,
+    }
+}
+
+/// Keeps flyable candidates and orders them by flyable hours, then distance.
+/// With `prefer_quiet`, crowding costs hours first.
+fn rank_alternatives(
+    favorite: &ParaglidingSite,
+    favorite_day: &DailySummary,
+    candidates: Vec<(&ParaglidingSite, f64, &DailySummary)>,
+    min_hours: u32,
+    prefer_quiet: bool,
+) -> Vec<Alternative> {
+    let mut alternatives: Vec<Alternative> = candidates
+        .into_iter()
+        .filter(|(_, _, day)| day.is_flyable_day(min_hours))
+        .map(|(site, distance_km, day)| Alternative {
+            site: site.name.clone(),
+            distance_km,
+            flyable_hours: day.total_flyable_hours,
+            ranges: day.ranges.clone(),
+            crowding: day.crowding,
+            reasons: reasons(favorite, favorite_day, site, day),
+        })
+        .collect();
+    let rank_hours = |a: &Alternative| {
+        if prefer_quiet {
+            a.flyable_hours
+                .saturating_sub(crowding_penalty_hours(a.crowding))
+        } else {
+            a.flyable_hours
+        }
+    };
+    alternatives.sort_by(|a, b| {
+        rank_hours(b)
+            .cmp(&rank_hours(a))
+            .then(b.flyable_hours.cmp(&a.flyable_hours))
+            .then(a.distance_km.total_cmp(&b.distance_km))
+    });
+    alternatives
+}
+
+/// What rules out most hours of the favorite's day. Darkness is left out,
+/// as it ends the day at every site nearby alike.
+fn main_limit(day: &DailySummary) -> Option<Factor> {
+    let limits: Vec<Factor> = day
+        .hourly_scores
+        .iter()
+        .filter_map(|h| h.limiting_factor)
+        .filter(|factor| *factor != Factor::Darkness)
+        .collect();
+    limits
+        .iter()
+        .copied()
+        .max_by_key(|factor| limits.iter().filter(|f| *f == factor).count())
+}
+
+/// Why `site` works on `day` when `favorite` doesn't, in plain words.
+fn reasons(
+    favorite: &ParaglidingSite,
+    favorite_day: &DailySummary,
+    site: &ParaglidingSite,
+    day: &DailySummary,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    match main_limit(favorite_day) {
+        Some(Factor::Direction) => {
+            let facing = site
+                .launches
+                .iter()
+                .find_map(|l| l.central_aspect())
+                .map(|aspect| format!("Launch faces {}, ", degrees_to_compass(aspect)))
+                .unwrap_or_default();
+            reasons.push(format!(
+                "{facing}suits the wind that is wrong for {}",
+                favorite.name
+            ));
+        }
+        Some(Factor::Wind) => reasons.push(format!("Less wind than at {}", favorite.name)),
+        Some(Factor::Gusts) => reasons.push(format!("Less gusty than at {}", favorite.name)),
+        Some(Factor::Rain) => {
+            reasons.push(format!("Stays dry while it rains at {}", favorite.name))
+        }
+        Some(Factor::Closure) => reasons.push(format!("Open while {} is closed", favorite.name)),
+        Some(Factor::Overdevelopment) => reasons.push(format!(
+            "Stays flyable while {} overdevelops",
+            favorite.name
+        )),
+        Some(Factor::NoFootLaunch | Factor::Darkness) | None => {}
+    }
+    if let Some(window) = day
+        .ranges
+        .iter()
+        .max_by_key(|r| r.flyable_hours())
+        .map(FlyableRange::window)
+    {
+        reasons.push(format!(
+            "Flyable {} to {} UTC",
+            window.start.format("%H:%M"),
+            window.end.format("%H:%M")
+        ));
+    }
+    if day
+        .wind_alignment
+        .is_some_and(|a| a >= STRAIGHT_IN_ALIGNMENT)
+    {
+        reasons.push("Wind blows almost straight into launch".to_string());
+    }
+    if day.crowding == Crowding::Low {
+        reasons.push("Quiet launch expected".to_string());
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::{
+            activities::paragliding::site_evaluator::{HourlyScore, Hysteresis},
+            store::PersistentStore,
+        },
+        domain::{
+            error::{ErrorCode, TravelAiError},
+            location::Location,
+            paragliding::SiteType,
+            ports::MockWeatherProvider,
+            weather::{WeatherData, WeatherForecast},
+        },
+    };
+    use chrono::{TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, 13).unwrap()
+    }
+
+    fn site(name: &str, lat: f64, favorite: bool) -> ParaglidingSite {
+        ParaglidingSite {
+            name: name.into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(lat, 13.0, name.into(), "DE".into()),
+                // Only launchable in easterly wind.
+                direction_degrees_start: 45.0,
+                direction_degrees_stop: 135.0,
+                elevation: 500.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            favorite: favorite.then_some(true),
+            ..Default::default()
+        }
+    }
+
+    fn day(flyable_hours: u32) -> DailySummary {
+        let mut summary = DailySummary {
+            date: date(),
+            hourly_scores: (10..10 + flyable_hours)
+                .map(|h| HourlyScore {
+                    timestamp: Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap(),
+                    is_flyable: true,
+                    ..Default::default()
+                })
+                .collect(),
+            total_flyable_hours: flyable_hours as usize,
+            ..Default::default()
+        };
+        summary.calculate_flyable_time_ranges(Hysteresis::NONE);
+        summary
+    }
+
+    fn rank(
+        candidates: &[(ParaglidingSite, f64, DailySummary)],
+        prefer_quiet: bool,
+    ) -> Vec<Alternative> {
+        rank_alternatives(
+            &site("Favorite", 50.0, true),
+            &day(0),
+            candidates.iter().map(|(s, d, day)| (s, *d, day)).collect(),
+            2,
+            prefer_quiet,
+        )
+    }
+
+    fn names(ranked: Vec<Alternative>) -> Vec<String> {
+        ranked.into_iter().map(|a| a.site).collect()
+    }
+
+    #[test]
+    fn rank_prefers_more_hours_then_shorter_distance() {
+        let candidates = [
+            (site("Far", 50.0, false), 40.0, day(4)),
+            (site("Near", 50.0, false), 10.0, day(4)),
+            (site("Best", 50.0, false), 30.0, day(6)),
+            (site("Grounded", 50.0, false), 5.0, day(0)),
+            (site("Short", 50.0, false), 5.0, day(1)),
+        ];
+        assert_eq!(names(rank(&candidates, false)), vec!["Best", "Near", "Far"]);
+    }
+
+    #[test]
+    fn rank_prefers_quieter_sites_with_similar_hours() {
+        let crowded = |hours, crowding| DailySummary {
+            crowding,
+            ..day(hours)
+        };
+        let candidates = [
+            (site("Busy", 50.0, false), 10.0, crowded(6, Crowding::High)),
+            (site("Quiet", 50.0, false), 30.0, crowded(5, Crowding::Low)),
+            (
+                site("Packed", 50.0, false),
+                20.0,
+                crowded(9, Crowding::High),
+            ),
+        ];
+
+        assert_eq!(
+            names(rank(&candidates, false)),
+            vec!["Packed", "Busy", "Quiet"]
+        );
+        assert_eq!(
+            names(rank(&candidates, true)),
+            vec!["Packed", "Quiet", "Busy"]
+        );
+    }
+
+    #[tokio::test]
+    async fn unflyable_favorite_gets_flyable_neighbours() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = fjall::Database::builder(dir.path()).open().unwrap();
+        let ks = db
+            .keyspace("store", fjall::KeyspaceCreateOptions::default)
+            .unwrap();
+        let repo = ParaglidingSiteRepository::new(Arc::new(PersistentStore::from_keyspace(ks)));
+        repo.save_site(site("Favorite", 50.70, true)).await.unwrap();
+        repo.save_site(site("Neighbour", 50.75, false))
+            .await
+            .unwrap();
+        repo.save_site(site("Offline", 50.72, false)).await.unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather.expect_get_forecast().returning(|location, _| {
+            if location.name == "Offline" {
+                return Err(TravelAiError::new(
+                    ErrorCode::WeatherUnavailable,
+                    "Open-Meteo is down",
+                )
+                .into());
+            }
+            // Westerly wind at the favorite, easterly at the neighbour.
+            let wind_direction = if location.name == "Favorite" { 270 } else { 90 };
+            Ok(WeatherForecast {
+                location,
+                grid_elevation: None,
+                fetched_at: None,
+                provider: None,
+                forecast: (8..18)
+                    .map(|h| WeatherData {
+                        timestamp: Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap(),
+                        wind_speed_ms: 3.0,
+                        wind_direction,
+                        wind_gust_ms: 5.0,
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        });
+
+        let evaluations = EvaluationCache::default();
+        let favorite = site("Favorite", 50.70, true);
+        let evaluation = evaluate(&weather, &evaluations, &favorite, &favorite.launches[0])
+            .await
+            .unwrap();
+        let result = find_alternatives(
+            &repo,
+            &weather,
+            &evaluations,
+            &favorite,
+            &evaluation,
+            50.0,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].site, "Offline");
+        let result = result.days;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].date, date());
+        assert_eq!(result[0].alternatives.len(), 1);
+        let alternative = &result[0].alternatives[0];
+        assert_eq!(alternative.site, "Neighbour");
+        assert_eq!(
+            alternative.reasons[0],
+            "Launch faces E, suits the wind that is wrong for Favorite"
+        );
+
+        let neighbour = site("Neighbour", 50.75, false);
+        let evaluation = evaluate(&weather, &evaluations, &neighbour, &neighbour.launches[0])
+            .await
+            .unwrap();
+        assert!(
+            find_alternatives(
+                &repo,
+                &weather,
+                &evaluations,
+                &neighbour,
+                &evaluation,
+                50.0,
+                false
+            )
+            .await
+            .unwrap()
+            .days
+            .is_empty()
+        );
+    }
+}
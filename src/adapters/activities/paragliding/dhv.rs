@@ -1,27 +1,40 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
-use quick_xml::de::from_str;
-use serde::Deserialize;
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use quick_xml::{Reader, de::from_str, events::Event};
+use serde::{Deserialize, Serialize};
 use tracing;
 
 use crate::domain::{
     location::Location,
     paragliding::{
         ParaglidingLanding, ParaglidingLaunch, ParaglidingSite, ParaglidingSiteProvider, SiteType,
+        index::SiteIndex, remarks,
     },
+    ports::SiteSearchProvider,
 };
 use tracing::instrument;
 
 pub struct DhvParaglidingSiteProvider {
-    sites: Vec<ParaglidingSite>,
+    sites: SiteIndex,
 }
 
 impl DhvParaglidingSiteProvider {
+    /// Loads every export in `dir`, e.g. one each for DE, AT and CH. A site in
+    /// several exports is kept once, from the first file by name; see
+    /// [`duplicate_key`].
     #[instrument(skip_all)]
     pub fn new(dir: PathBuf) -> anyhow::Result<Self> {
-        let paths = fs::read_dir(&dir)?;
+        let mut paths: Vec<_> = fs::read_dir(&dir)?.collect();
+        paths.sort_by_key(|p| p.as_ref().ok().map(|entry| entry.file_name()));
+        let mut seen = HashSet::new();
         let sites: Vec<ParaglidingSite> = paths
+            .into_iter()
             .filter_map(|p| {
                 let path = match p {
                     Ok(path) => path,
@@ -34,6 +47,10 @@ impl DhvParaglidingSiteProvider {
                         return None;
                     }
                 };
+                // Compiled site files
+                if path.file_name().to_string_lossy().starts_with('.') {
+                    return None;
+                }
 
                 let dhv_sites: anyhow::Result<Vec<ParaglidingSite>> = load_sites(path.path());
                 match dhv_sites {
@@ -45,77 +62,242 @@ impl DhvParaglidingSiteProvider {
                 }
             })
             .flatten()
+            .filter(|site| seen.insert(duplicate_key(site)))
             .collect();
         tracing::info!(count = sites.len(), "Loaded flying sites");
-        Ok(DhvParaglidingSiteProvider { sites })
+        Ok(DhvParaglidingSiteProvider {
+            sites: SiteIndex::new(sites),
+        })
     }
 }
 
+/// Launches closer than this in both latitude and longitude, about 100 m,
+/// are the same launch
+const SAME_LAUNCH_DEGREES: f64 = 0.001;
+
+/// Sites in two exports are the same if their names and first launches match,
+/// so different sites that merely share a name are both kept.
+pub(crate) fn duplicate_key(site: &ParaglidingSite) -> (String, Option<(i64, i64)>) {
+    let launch = site.launches.first().map(|launch| {
+        (
+            (launch.location.latitude / SAME_LAUNCH_DEGREES).round() as i64,
+            (launch.location.longitude / SAME_LAUNCH_DEGREES).round() as i64,
+        )
+    });
+    (site.name.clone(), launch)
+}
+
+/// Bump whenever `ParaglidingSite` changes shape, so compiled files of older
+/// builds are parsed again instead of misread.
+const COMPILED_FORMAT_VERSION: u32 = 2;
+
+/// Parsed sites of one XML export, stored next to it as a hidden file. Loading
+/// it takes milliseconds where parsing the XML takes seconds.
+#[derive(Serialize, Deserialize)]
+struct CompiledSites {
+    version: u32,
+    /// Of the XML file the sites were parsed from
+    checksum: u64,
+    sites: Vec<ParaglidingSite>,
+}
+
 fn load_sites(xml_path: PathBuf) -> anyhow::Result<Vec<ParaglidingSite>> {
-    let xml_content = fs::read_to_string(xml_path)?;
-    parse_sites_from_xml(&xml_content)
+    let bytes = fs::read(&xml_path)?;
+    let checksum = fnv1a(&bytes);
+    let compiled_path = compiled_path(&xml_path);
+    if let Some(sites) = read_compiled(&compiled_path, checksum) {
+        tracing::debug!(path = ?compiled_path, count = sites.len(), "Loaded compiled sites");
+        return Ok(sites);
+    }
+
+    let xml_content = String::from_utf8(bytes)?;
+    let report = parse_sites(&xml_content, ParseMode::Lenient)?;
+    report.log(&xml_path.display().to_string());
+    // The directory may well be read-only, the sites are just parsed again then
+    if let Err(e) = write_compiled(&compiled_path, checksum, &report.sites) {
+        tracing::warn!(path = ?compiled_path, error = ?e, "Failed to write compiled sites");
+    }
+    Ok(report.sites)
 }
 
+fn compiled_path(xml_path: &Path) -> PathBuf {
+    let name = xml_path.file_name().unwrap_or_default().to_string_lossy();
+    xml_path.with_file_name(format!(".{}.sites", name))
+}
+
+fn read_compiled(path: &Path, checksum: u64) -> Option<Vec<ParaglidingSite>> {
+    let bytes = fs::read(path).ok()?;
+    let compiled: CompiledSites = postcard::from_bytes(&bytes).ok()?;
+    (compiled.version == COMPILED_FORMAT_VERSION && compiled.checksum == checksum)
+        .then_some(compiled.sites)
+}
+
+fn write_compiled(path: &Path, checksum: u64, sites: &[ParaglidingSite]) -> Result<()> {
+    let compiled = CompiledSites {
+        version: COMPILED_FORMAT_VERSION,
+        checksum,
+        sites: sites.to_vec(),
+    };
+    fs::write(path, postcard::to_stdvec(&compiled)?)?;
+    Ok(())
+}
+
+/// 64-bit FNV-1a, enough to notice a changed export.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Lenient parse that drops records it cannot read.
 pub fn parse_sites_from_xml(xml_content: &str) -> anyhow::Result<Vec<ParaglidingSite>> {
-    let dhv_xml: DHVXml = from_str(xml_content)?;
-    let sites: Vec<ParaglidingSite> = dhv_xml
-        .flying_sites
-        .sites
-        .into_iter()
-        .map(|dhv| dhv.into())
-        .collect();
-    Ok(sites)
+    Ok(parse_sites(xml_content, ParseMode::Lenient)?.sites)
 }
 
-impl ParaglidingSiteProvider for DhvParaglidingSiteProvider {
-    #[instrument(skip_all, fields(center_lat = %center.latitude, center_lon = %center.longitude, radius_km = radius_km))]
-    async fn fetch_launches_within_radius(
-        &self,
-        center: &Location,
-        radius_km: f64,
-    ) -> Vec<(ParaglidingSite, f64)> {
-        let mut results = Vec::new();
+// DHV renames fields now and then. A single odd record is expected, this many
+// failing means the format changed underneath us.
+const SCHEMA_DRIFT_FAILURE_RATIO: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Skip records that fail to parse and report them.
+    #[default]
+    Lenient,
+    /// Fail the whole file on the first bad record.
+    Strict,
+}
 
-        for site in &self.sites {
-            // Find the closest launch to the center point
-            let mut min_distance = f64::INFINITY;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRecord {
+    /// Position of the `FlyingSite` element in the file, starting at 0
+    pub index: usize,
+    pub site_name: Option<String>,
+    pub error: String,
+}
 
-            for launch in &site.launches {
-                let distance = center.distance_to(&launch.location);
-                if distance < min_distance {
-                    min_distance = distance;
-                }
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    pub sites: Vec<ParaglidingSite>,
+    pub skipped: Vec<SkippedRecord>,
+}
+
+impl ParseReport {
+    pub fn total_records(&self) -> usize {
+        self.sites.len() + self.skipped.len()
+    }
+
+    /// True when no records were found at all or too many of them failed.
+    pub fn schema_drift_suspected(&self) -> bool {
+        let total = self.total_records();
+        total == 0 || self.skipped.len() as f64 / total as f64 > SCHEMA_DRIFT_FAILURE_RATIO
+    }
+
+    pub fn log(&self, source: &str) {
+        tracing::info!(
+            source,
+            parsed = self.sites.len(),
+            skipped = self.skipped.len(),
+            "Parsed DHV sites"
+        );
+        for record in &self.skipped {
+            tracing::debug!(source, ?record, "Skipped DHV record");
+        }
+        if self.schema_drift_suspected() {
+            tracing::warn!(
+                source,
+                total = self.total_records(),
+                skipped = self.skipped.len(),
+                "Many DHV records failed to parse, the XML schema may have changed"
+            );
+        }
+    }
+}
+
+/// Parses each `FlyingSite` on its own so one malformed record does not take the
+/// whole file down with it. Malformed XML outside the records is still an error.
+pub fn parse_sites(xml_content: &str, mode: ParseMode) -> anyhow::Result<ParseReport> {
+    let mut reader = Reader::from_str(xml_content);
+    let mut report = ParseReport::default();
+    let mut index = 0;
+
+    loop {
+        let record = match reader.read_event()? {
+            Event::Eof => break,
+            Event::Start(e) if e.local_name().as_ref() == b"FlyingSite" => {
+                let end = e.to_end().into_owned();
+                let span = reader.read_to_end(end.name())?;
+                let inner = &xml_content[span.start as usize..span.end as usize];
+                parse_record(&format!("<FlyingSite>{inner}</FlyingSite>"))
             }
+            Event::Empty(e) if e.local_name().as_ref() == b"FlyingSite" => {
+                Err(("empty FlyingSite element".to_string(), None))
+            }
+            _ => continue,
+        };
 
-            // Include site if any launch is within radius
-            if min_distance <= radius_km {
-                results.push((site.clone(), min_distance));
+        match record {
+            Ok(site) => report.sites.push(site),
+            Err((error, site_name)) => {
+                if mode == ParseMode::Strict {
+                    bail!("DHV record {index} is invalid: {error}");
+                }
+                report.skipped.push(SkippedRecord {
+                    index,
+                    site_name,
+                    error,
+                });
             }
         }
+        index += 1;
+    }
 
-        // Sort by distance (closest first)
-        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        results
+    if mode == ParseMode::Strict && report.total_records() == 0 {
+        bail!("No FlyingSite records found");
     }
+    Ok(report)
+}
 
-    async fn fetch_all_sites(&self) -> Vec<ParaglidingSite> {
-        self.sites.clone()
+fn parse_record(xml: &str) -> std::result::Result<ParaglidingSite, (String, Option<String>)> {
+    let record: DHVFlyingSite = from_str(xml).map_err(|e| (e.to_string(), None))?;
+    let site: ParaglidingSite = record.into();
+    if site.launches.is_empty() && site.landings.is_empty() {
+        return Err(("no usable launch or landing".to_string(), Some(site.name)));
     }
+    Ok(site)
 }
 
-/// DHV XML structure for deserialization
-#[derive(Debug, Deserialize)]
-pub struct DHVXml {
-    #[serde(rename = "FlyingSites")]
-    pub flying_sites: DHVFlyingSites,
+#[async_trait]
+impl SiteSearchProvider for DhvParaglidingSiteProvider {
+    fn name(&self) -> &'static str {
+        "dhv"
+    }
+
+    async fn search_sites(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Result<Vec<(ParaglidingSite, f64)>> {
+        Ok(self.sites.within_radius(center, radius_km))
+    }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DHVFlyingSites {
-    #[serde(rename = "FlyingSite")]
-    pub sites: Vec<DHVFlyingSite>,
+impl ParaglidingSiteProvider for DhvParaglidingSiteProvider {
+    #[instrument(skip_all, fields(center_lat = %center.latitude, center_lon = %center.longitude, radius_km = radius_km))]
+    async fn fetch_launches_within_radius(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Vec<(ParaglidingSite, f64)> {
+        self.sites.within_radius(center, radius_km)
+    }
+
+    async fn fetch_all_sites(&self) -> Vec<ParaglidingSite> {
+        self.sites.sites().to_vec()
+    }
 }
 
+/// One `FlyingSite` record of the DHV XML export
 #[derive(Debug, Deserialize)]
 pub struct DHVFlyingSite {
     #[serde(rename = "SiteID")]
@@ -130,6 +312,9 @@ pub struct DHVFlyingSite {
     pub height_difference_max: Option<f64>,
     #[serde(rename = "SiteUrl")]
     pub site_url: Option<String>,
+    /// Free text, often with seasonal restrictions in German prose
+    #[serde(rename = "SiteRemarks")]
+    pub site_remarks: Option<String>,
     #[serde(rename = "Location")]
     pub locations: Vec<DHVLocation>,
 }
@@ -405,6 +590,126 @@ mod tests {
         assert_eq!(launch.direction_degrees_stop, 180.0);
         assert_eq!(launch.elevation, 500.0);
     }
+
+    #[test]
+    fn seasonal_restrictions_in_remarks_become_closures_to_review() {
+        let xml = document(&[record("Hill", "Coordinates").replace(
+            "<Location>",
+            "<SiteRemarks>Startverbot 1.3. bis 31.7. wegen Uhu-Brut</SiteRemarks><Location>",
+        )]);
+        let sites = parse_sites_from_xml(&xml).unwrap();
+        let closures = &sites[0].closures;
+        assert_eq!(closures.len(), 1);
+        assert_eq!(closures[0].reason, "bird protection");
+        assert!(closures[0].needs_review);
+    }
+
+    #[test]
+    fn sites_of_several_exports_are_merged_without_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = document(&[
+            record("Border", "Coordinates"),
+            record("Gaisberg", "Coordinates"),
+        ]);
+        fs::write(dir.path().join("at.xml"), at).unwrap();
+        let de = document(&[
+            record("Border", "Coordinates"),
+            record("Wank", "Coordinates"),
+            // Same name, another hill
+            record("Gaisberg", "Coordinates").replace("13.0,50.0", "11.2,47.6"),
+        ]);
+        fs::write(dir.path().join("de.xml"), de).unwrap();
+
+        let mut names: Vec<String> = load(dir.path()).into_iter().map(|s| s.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["Border", "Gaisberg", "Gaisberg", "Wank"]);
+    }
+
+    fn load(dir: &Path) -> Vec<ParaglidingSite> {
+        DhvParaglidingSiteProvider::new(dir.into())
+            .unwrap()
+            .sites
+            .sites()
+            .to_vec()
+    }
+
+    #[test]
+    fn compiled_sites_are_used_until_the_export_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let xml_path = dir.path().join("dhv.xml");
+        fs::write(&xml_path, document(&[record("Hill", "Coordinates")])).unwrap();
+
+        let sites = load(dir.path());
+        assert_eq!(sites.len(), 1);
+        let compiled = compiled_path(&xml_path);
+        assert!(compiled.exists());
+
+        // A compiled file matching the export is trusted as is
+        let checksum = fnv1a(&fs::read(&xml_path).unwrap());
+        let mut renamed = sites.clone();
+        renamed[0].name = "From compiled".into();
+        write_compiled(&compiled, checksum, &renamed).unwrap();
+        assert_eq!(load(dir.path())[0].name, "From compiled");
+
+        fs::write(&xml_path, document(&[record("Other", "Coordinates")])).unwrap();
+        assert_eq!(load(dir.path())[0].name, "Other");
+    }
+
+    fn record(name: &str, coordinates_tag: &str) -> String {
+        format!(
+            "<FlyingSite><SiteID>1</SiteID><SiteName>{name}</SiteName><Location>\
+             <{coordinates_tag}>13.0,50.0</{coordinates_tag}><LocationType>1</LocationType>\
+             <DirectionsText>S</DirectionsText></Location></FlyingSite>"
+        )
+    }
+
+    fn document(records: &[String]) -> String {
+        format!(
+            "<DHVXml><FlyingSites>{}</FlyingSites></DHVXml>",
+            records.concat()
+        )
+    }
+
+    #[test]
+    fn bad_record_is_skipped_and_reported() {
+        let mut records: Vec<String> = (0..9)
+            .map(|i| record(&format!("Hill {i}"), "Coordinates"))
+            .collect();
+        records.insert(3, record("Renamed", "Coords"));
+
+        let report = parse_sites(&document(&records), ParseMode::Lenient).unwrap();
+
+        assert_eq!(report.sites.len(), 9);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].index, 3);
+        assert!(!report.schema_drift_suspected());
+    }
+
+    #[test]
+    fn strict_mode_fails_on_first_bad_record() {
+        let records = vec![record("Hill", "Coordinates"), record("Renamed", "Coords")];
+        let err = parse_sites(&document(&records), ParseMode::Strict).unwrap_err();
+        assert!(err.to_string().contains("record 1"), "{err}");
+    }
+
+    #[test]
+    fn renamed_field_across_records_flags_schema_drift() {
+        let records: Vec<String> = (0..5)
+            .map(|i| record(&format!("Hill {i}"), "Coords"))
+            .collect();
+        let report = parse_sites(&document(&records), ParseMode::Lenient).unwrap();
+        assert!(report.sites.is_empty());
+        assert!(report.schema_drift_suspected());
+    }
+
+    #[test]
+    fn renamed_record_element_flags_schema_drift() {
+        let xml = "<DHVXml><FlyingSites><Site><Name>X</Name></Site></FlyingSites></DHVXml>";
+        let report = parse_sites(xml, ParseMode::Lenient).unwrap();
+        assert_eq!(report.total_records(), 0);
+        assert!(report.schema_drift_suspected());
+        assert!(parse_sites(xml, ParseMode::Strict).is_err());
+    }
 }
 
 impl From<DHVFlyingSite> for ParaglidingSite {
@@ -455,6 +760,12 @@ impl From<DHVFlyingSite> for ParaglidingSite {
             })
             .collect();
 
+        let closures = value
+            .site_remarks
+            .as_deref()
+            .map(remarks::seasonal_closures)
+            .unwrap_or_default();
+
         ParaglidingSite {
             name: value.site_name,
             launches,
@@ -465,6 +776,12 @@ impl From<DHVFlyingSite> for ParaglidingSite {
             mute_alerts: None,
             rating: None,
             preferred_weather_model: None,
+            winter_operable: None,
+            favorite: None,
+            characteristics: None,
+            closures,
+            source_url: value.site_url,
+            busy: None,
         }
     }
 }
@@ -0,0 +1,241 @@
+//! Site evaluations are pure, yet the same site is scored against the same
+//! forecast over and over, by the calendar job, the outlook and each API call.
+//! Results are kept per site and forecast run until either changes.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    adapters::activities::paragliding::site_evaluator::{
+        self, Hysteresis, SCORER_VERSION, SiteEvaluationResult, WindLimits,
+    },
+    domain::{paragliding::ParaglidingSite, weather::WeatherForecast},
+};
+
+/// Evaluations kept before the oldest are dropped, a few per site and run
+const CAPACITY: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EvaluationKey {
+    site: String,
+    /// Of the site as evaluated, so edits and overrides such as closures
+    /// count as a different site
+    site_fingerprint: u64,
+    /// Where the forecast is for, as the bits of its latitude and longitude
+    location: (u64, u64),
+    /// When the forecast was fetched, which only changes with a new run
+    fetched_at: DateTime<Utc>,
+    scorer_version: u32,
+}
+
+impl EvaluationKey {
+    fn new(site: &ParaglidingSite, forecast: &WeatherForecast) -> Option<Self> {
+        Some(Self {
+            site: site.name.clone(),
+            site_fingerprint: fingerprint(site)?,
+            location: (
+                forecast.location.latitude.to_bits(),
+                forecast.location.longitude.to_bits(),
+            ),
+            fetched_at: forecast.fetched_at?,
+            scorer_version: SCORER_VERSION,
+        })
+    }
+}
+
+fn fingerprint(site: &ParaglidingSite) -> Option<u64> {
+    let bytes = postcard::to_stdvec(site).ok()?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Evaluations with the default wind limits and the configured windows,
+/// shared through `AppState`.
+pub struct EvaluationCache {
+    hysteresis: Hysteresis,
+    entries: Mutex<Entries>,
+}
+
+/// Every flyable run is a window of its own.
+impl Default for EvaluationCache {
+    fn default() -> Self {
+        Self::new(Hysteresis::NONE)
+    }
+}
+
+#[derive(Default)]
+struct Entries {
+    results: HashMap<EvaluationKey, Arc<SiteEvaluationResult>>,
+    /// Keys oldest first
+    order: VecDeque<EvaluationKey>,
+}
+
+impl EvaluationCache {
+    /// Joins flyable hours into windows by `hysteresis`.
+    pub fn new(hysteresis: Hysteresis) -> Self {
+        Self {
+            hysteresis,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// How flyable hours are joined into windows
+    pub fn hysteresis(&self) -> Hysteresis {
+        self.hysteresis
+    }
+
+    /// The evaluation of `site` against `forecast`, reused while the site and
+    /// the forecast run stay the same. Forecasts without a fetch time are
+    /// evaluated every time.
+    pub fn evaluate(
+        &self,
+        site: &ParaglidingSite,
+        forecast: &WeatherForecast,
+    ) -> Arc<SiteEvaluationResult> {
+        self.get_or_evaluate(site, forecast, || {
+            site_evaluator::evaluate_site_with_limits(
+                site,
+                forecast,
+                &WindLimits::default(),
+                self.hysteresis,
+            )
+        })
+    }
+
+    fn get_or_evaluate(
+        &self,
+        site: &ParaglidingSite,
+        forecast: &WeatherForecast,
+        evaluate: impl FnOnce() -> SiteEvaluationResult,
+    ) -> Arc<SiteEvaluationResult> {
+        let Some(key) = EvaluationKey::new(site, forecast) else {
+            return Arc::new(evaluate());
+        };
+        if let Some(result) = self.entries.lock().unwrap().results.get(&key) {
+            tracing::debug!(site = %site.name, "Reusing site evaluation");
+            return result.clone();
+        }
+
+        let result = Arc::new(evaluate());
+        let mut entries = self.entries.lock().unwrap();
+        if entries
+            .results
+            .insert(key.clone(), result.clone())
+            .is_none()
+        {
+            entries.order.push_back(key);
+        }
+        while entries.order.len() > CAPACITY {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.results.remove(&oldest);
+            }
+        }
+        result
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().results.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{location::Location, weather::WeatherData};
+    use chrono::{TimeZone, Utc};
+    use std::cell::Cell;
+
+    fn site(name: &str) -> ParaglidingSite {
+        ParaglidingSite {
+            name: name.into(),
+            data_source: "test".into(),
+            ..Default::default()
+        }
+    }
+
+    /// A forecast fetched at `run` o'clock, or never if None
+    fn forecast(run: Option<u32>) -> WeatherForecast {
+        WeatherForecast {
+            location: Location::new(47.0, 11.0, "Hill".into(), "AT".into()),
+            grid_elevation: None,
+            fetched_at: run.map(|hour| Utc.with_ymd_and_hms(2026, 6, 13, hour, 0, 0).unwrap()),
+            provider: None,
+            forecast: vec![WeatherData {
+                timestamp: Utc.with_ymd_and_hms(2026, 6, 13, 12, 0, 0).unwrap(),
+                wind_speed_ms: 3.0,
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn result() -> SiteEvaluationResult {
+        SiteEvaluationResult {
+            daily_summaries: vec![],
+            field_availability: vec![],
+            provenance: Default::default(),
+            limits: Default::default(),
+        }
+    }
+
+    #[test]
+    fn same_site_and_run_are_evaluated_once() {
+        let cache = EvaluationCache::default();
+        let evaluations = Cell::new(0);
+        let evaluate = || {
+            evaluations.set(evaluations.get() + 1);
+            result()
+        };
+
+        cache.get_or_evaluate(&site("Hill"), &forecast(Some(6)), evaluate);
+        cache.get_or_evaluate(&site("Hill"), &forecast(Some(6)), evaluate);
+        assert_eq!(evaluations.get(), 1);
+
+        // A new run, another site and an override each need a fresh evaluation
+        cache.get_or_evaluate(&site("Hill"), &forecast(Some(7)), evaluate);
+        cache.get_or_evaluate(&site("Ridge"), &forecast(Some(6)), evaluate);
+        let mut closed = site("Hill");
+        closed.closures = vec!["06-01..06-30 raptor nesting".parse().unwrap()];
+        cache.get_or_evaluate(&closed, &forecast(Some(6)), evaluate);
+        assert_eq!(evaluations.get(), 4);
+        assert_eq!(cache.len(), 4);
+    }
+
+    #[test]
+    fn forecasts_without_fetch_time_are_not_kept() {
+        let cache = EvaluationCache::default();
+        let evaluations = Cell::new(0);
+        let evaluate = || {
+            evaluations.set(evaluations.get() + 1);
+            result()
+        };
+
+        cache.get_or_evaluate(&site("Hill"), &forecast(None), evaluate);
+        cache.get_or_evaluate(&site("Hill"), &forecast(None), evaluate);
+
+        assert_eq!(evaluations.get(), 2);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn oldest_evaluations_are_dropped_beyond_capacity() {
+        let cache = EvaluationCache::default();
+        for i in 0..CAPACITY + 10 {
+            cache.get_or_evaluate(&site(&format!("Hill {i}")), &forecast(Some(6)), result);
+        }
+        assert_eq!(cache.len(), CAPACITY);
+
+        let evaluated = Cell::new(false);
+        cache.get_or_evaluate(&site("Hill 0"), &forecast(Some(6)), || {
+            evaluated.set(true);
+            result()
+        });
+        assert!(evaluated.get());
+    }
+}
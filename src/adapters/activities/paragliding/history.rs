@@ -0,0 +1,545 @@
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adapters::{
+        activities::paragliding::{
+            site_evaluator::{DailySummary, FlyableRange, ScoreProvenance},
+            source,
+        },
+        cache::PersistentCache,
+    },
+    domain::activities::DayRating,
+};
+
+// Long enough to compare a weekend outlook across the whole preceding week.
+const HISTORY_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+// A site's newest run older than this at a point in time no longer counts as
+// part of the forecast then, e.g. because it left the search radius.
+const RUN_MAX_AGE: chrono::Duration = chrono::Duration::hours(24);
+
+/// Bump whenever `ForecastSnapshot` or anything in it changes in a way the
+/// JSON of older runs can't be read as, e.g. a new field without a default,
+/// and convert the older runs in [`StoredSnapshot::decode`].
+const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastSnapshot {
+    pub site: String,
+    pub generated_at: DateTime<Utc>,
+    pub daily_summaries: Vec<DailySummary>,
+    pub provenance: ScoreProvenance,
+}
+
+/// A run as stored: gzipped JSON, unlike the rest of the cache, so fields
+/// added with a default still read older runs and a month of runs of every
+/// site stays small, tagged with the format it was written in.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSnapshot {
+    version: u32,
+    json_gz: Vec<u8>,
+}
+
+impl StoredSnapshot {
+    fn encode(snapshot: &ForecastSnapshot) -> Result<Self> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serde_json::to_vec(snapshot)?)?;
+        Ok(Self {
+            version: SNAPSHOT_FORMAT_VERSION,
+            json_gz: encoder.finish()?,
+        })
+    }
+
+    fn decode(&self) -> Result<ForecastSnapshot> {
+        match self.version {
+            SNAPSHOT_FORMAT_VERSION => {
+                let mut json = Vec::new();
+                GzDecoder::new(self.json_gz.as_slice()).read_to_end(&mut json)?;
+                Ok(serde_json::from_slice(&json)?)
+            }
+            version => bail!("Unknown forecast history format {version}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForecastHistoryEntry {
+    pub generated_at: DateTime<Utc>,
+    pub summary: DailySummary,
+    pub provenance: ScoreProvenance,
+}
+
+pub struct ForecastHistory {
+    cache: Arc<PersistentCache>,
+}
+
+impl ForecastHistory {
+    pub fn new(cache: Arc<PersistentCache>) -> Self {
+        Self { cache }
+    }
+
+    fn site_prefix(site: &str) -> String {
+        format!("forecast_runs_{}_", site)
+    }
+
+    async fn store(&self, snapshot: &ForecastSnapshot) -> Result<()> {
+        let key = format!(
+            "{}{:020}",
+            Self::site_prefix(&snapshot.site),
+            snapshot.generated_at.timestamp()
+        );
+        self.cache
+            .put(&key, StoredSnapshot::encode(snapshot)?, HISTORY_TTL)
+            .await
+    }
+
+    #[tracing::instrument(skip(self, daily_summaries, provenance), fields(days = daily_summaries.len()))]
+    pub async fn record(
+        &self,
+        site: &str,
+        generated_at: DateTime<Utc>,
+        daily_summaries: &[DailySummary],
+        provenance: &ScoreProvenance,
+    ) -> Result<()> {
+        let snapshot = ForecastSnapshot {
+            site: site.to_string(),
+            generated_at,
+            daily_summaries: daily_summaries.to_vec(),
+            provenance: provenance.clone(),
+        };
+        self.store(&snapshot).await
+    }
+
+    /// All stored runs for a site, oldest first.
+    pub async fn snapshots(&self, site: &str) -> Result<Vec<ForecastSnapshot>> {
+        let stored: Vec<StoredSnapshot> = self
+            .cache
+            .get_all_starting_with(&Self::site_prefix(site))
+            .await?;
+        let mut snapshots: Vec<ForecastSnapshot> = stored
+            .iter()
+            .filter_map(|stored| {
+                stored
+                    .decode()
+                    .inspect_err(|e| tracing::warn!(site, error = ?e, "Skipping forecast run"))
+                    .ok()
+            })
+            .collect();
+        // The prefix of "Foo" also matches "Foo_Bar"; drop those.
+        snapshots.retain(|s| s.site == site);
+        snapshots.sort_by_key(|s| s.generated_at);
+        Ok(snapshots)
+    }
+
+    /// The newest run generated at or before `before`, if any.
+    pub async fn previous_run(
+        &self,
+        site: &str,
+        before: DateTime<Utc>,
+    ) -> Result<Option<ForecastSnapshot>> {
+        Ok(self
+            .snapshots(site)
+            .await?
+            .into_iter()
+            .rev()
+            .find(|s| s.generated_at <= before))
+    }
+
+    /// How the outlook for one day at one site evolved across forecast runs.
+    pub async fn day_history(
+        &self,
+        site: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<ForecastHistoryEntry>> {
+        Ok(self
+            .snapshots(site)
+            .await?
+            .into_iter()
+            .filter_map(|snapshot| {
+                let summary = snapshot
+                    .daily_summaries
+                    .into_iter()
+                    .find(|d| d.date == date)?;
+                Some(ForecastHistoryEntry {
+                    generated_at: snapshot.generated_at,
+                    summary,
+                    provenance: snapshot.provenance,
+                })
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteChange {
+    /// Only in the later run
+    Added,
+    /// Only in the earlier run
+    Removed,
+    Changed,
+}
+
+/// How a day of one site differs between two runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayDiff {
+    pub date: NaiveDate,
+    /// None if the day wasn't flyable
+    pub rating_before: Option<DayRating>,
+    pub rating_after: Option<DayRating>,
+    pub windows_before: Vec<FlyableRange>,
+    pub windows_after: Vec<FlyableRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SiteDiff {
+    pub site: String,
+    pub change: SiteChange,
+    /// The runs were scored by different rules, so the changes need not come
+    /// from the weather alone
+    pub scoring_changed: bool,
+    /// Days both runs cover whose rating or windows differ
+    pub days: Vec<DayDiff>,
+}
+
+/// The run of a site that made up the forecast at `at`: its newest snapshot
+/// generated by then, unless that is too old to still count.
+pub fn run_as_of(snapshots: &[ForecastSnapshot], at: DateTime<Utc>) -> Option<&ForecastSnapshot> {
+    snapshots
+        .iter()
+        .filter(|s| s.generated_at <= at && s.generated_at > at - RUN_MAX_AGE)
+        .max_by_key(|s| s.generated_at)
+}
+
+/// What changed for `site` from the run `before` to the run `after`, or
+/// `None` if nothing did. Days count as flyable from `min_hours`.
+pub fn diff(
+    site: &str,
+    before: Option<&ForecastSnapshot>,
+    after: Option<&ForecastSnapshot>,
+    min_hours: u32,
+) -> Option<SiteDiff> {
+    let change = match (before, after) {
+        (None, None) => return None,
+        (None, Some(_)) => SiteChange::Added,
+        (Some(_), None) => SiteChange::Removed,
+        (Some(_), Some(_)) => SiteChange::Changed,
+    };
+    let days: Vec<DayDiff> = before
+        .zip(after)
+        .into_iter()
+        .flat_map(|(before, after)| {
+            after.daily_summaries.iter().filter_map(|day| {
+                let previous = before.daily_summaries.iter().find(|d| d.date == day.date)?;
+                let rating =
+                    |d: &DailySummary| d.is_flyable_day(min_hours).then(|| source::day_rating(d));
+                let diff = DayDiff {
+                    date: day.date,
+                    rating_before: rating(previous),
+                    rating_after: rating(day),
+                    windows_before: previous.ranges.clone(),
+                    windows_after: day.ranges.clone(),
+                };
+                (diff.rating_before != diff.rating_after
+                    || diff.windows_before != diff.windows_after)
+                    .then_some(diff)
+            })
+        })
+        .collect();
+    if change == SiteChange::Changed && days.is_empty() {
+        return None;
+    }
+    Some(SiteDiff {
+        site: site.to_string(),
+        change,
+        scoring_changed: before
+            .zip(after)
+            .is_some_and(|(before, after)| before.provenance.scoring_differs(&after.provenance)),
+        days,
+    })
+}
+
+/// Share of hours on which two runs agree about flyability, or `None` if the runs
+/// have no hour of the day in common. A forecast that keeps flipping between runs
+/// is less trustworthy than its lead time alone suggests.
+pub fn stability(previous: &DailySummary, current: &DailySummary) -> Option<f32> {
+    let (compared, agreeing) = current
+        .hourly_scores
+        .iter()
+        .filter_map(|hour| {
+            previous
+                .hourly_scores
+                .iter()
+                .find(|p| p.timestamp == hour.timestamp)
+                .map(|p| p.is_flyable == hour.is_flyable)
+        })
+        .fold((0usize, 0usize), |(compared, agreeing), agrees| {
+            (compared + 1, agreeing + usize::from(agrees))
+        });
+
+    (compared > 0).then(|| agreeing as f32 / compared as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::activities::paragliding::site_evaluator::{HourlyScore, Hysteresis};
+    use chrono::{Datelike, TimeZone, Timelike};
+    use tempfile::TempDir;
+
+    fn fresh_history() -> (TempDir, ForecastHistory) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = fjall::Database::builder(dir.path()).open().unwrap();
+        let ks = db
+            .keyspace("cache", fjall::KeyspaceCreateOptions::default)
+            .unwrap();
+        let cache = Arc::new(PersistentCache::from_keyspace(ks));
+        (dir, ForecastHistory::new(cache))
+    }
+
+    fn summary(day: u32, flyable_hours: &[u32]) -> DailySummary {
+        let date = NaiveDate::from_ymd_opt(2026, 6, day).unwrap();
+        DailySummary {
+            date,
+            hourly_scores: (8..20)
+                .map(|h| HourlyScore {
+                    timestamp: Utc.with_ymd_and_hms(2026, 6, day, h, 0, 0).unwrap(),
+                    is_flyable: flyable_hours.contains(&h),
+                    ..Default::default()
+                })
+                .collect(),
+            total_flyable_hours: flyable_hours.len(),
+            ..Default::default()
+        }
+    }
+
+    fn run_at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, day, 6, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn day_history_returns_every_run_covering_the_date_oldest_first() {
+        let (_dir, history) = fresh_history();
+        history
+            .record(
+                "Hill",
+                run_at(11),
+                &[summary(12, &[]), summary(13, &[10])],
+                &ScoreProvenance::default(),
+            )
+            .await
+            .unwrap();
+        history
+            .record(
+                "Hill",
+                run_at(10),
+                &[summary(13, &[10, 11, 12])],
+                &ScoreProvenance::default(),
+            )
+            .await
+            .unwrap();
+        history
+            .record(
+                "Hill",
+                run_at(12),
+                &[summary(12, &[])],
+                &ScoreProvenance::default(),
+            )
+            .await
+            .unwrap();
+
+        let entries = history
+            .day_history("Hill", NaiveDate::from_ymd_opt(2026, 6, 13).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].generated_at, run_at(10));
+        assert_eq!(entries[0].summary.total_flyable_hours, 3);
+        assert_eq!(entries[1].generated_at, run_at(11));
+        assert_eq!(entries[1].summary.total_flyable_hours, 1);
+        assert_eq!(entries[1].summary.hourly_scores[0].timestamp.hour(), 8);
+    }
+
+    #[tokio::test]
+    async fn runs_of_an_unknown_format_are_skipped() {
+        let (_dir, history) = fresh_history();
+        history
+            .record("Hill", run_at(10), &[], &ScoreProvenance::default())
+            .await
+            .unwrap();
+        let newer = StoredSnapshot {
+            version: SNAPSHOT_FORMAT_VERSION + 1,
+            json_gz: vec![],
+        };
+        history
+            .cache
+            .put("forecast_runs_Hill_2", newer, HISTORY_TTL)
+            .await
+            .unwrap();
+
+        let snapshots = history.snapshots("Hill").await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].generated_at, run_at(10));
+    }
+
+    #[tokio::test]
+    async fn previous_run_picks_newest_run_not_after_cutoff() {
+        let (_dir, history) = fresh_history();
+        for day in [10, 11, 12] {
+            history
+                .record(
+                    "Hill",
+                    run_at(day),
+                    &[summary(13, &[])],
+                    &ScoreProvenance::default(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let previous = history
+            .previous_run("Hill", run_at(11) + chrono::Duration::hours(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous.generated_at, run_at(11));
+
+        let none = history.previous_run("Hill", run_at(9)).await.unwrap();
+        assert!(none.is_none());
+    }
+
+    #[test]
+    fn stability_is_one_for_identical_runs() {
+        let day = summary(13, &[10, 11, 12]);
+        assert_eq!(stability(&day, &day), Some(1.0));
+    }
+
+    #[test]
+    fn stability_drops_with_flipped_hours() {
+        let previous = summary(13, &[10, 11, 12, 13]);
+        let current = summary(13, &[14, 15, 16, 17]);
+        // 12 hours compared, 8 flipped.
+        let s = stability(&previous, &current).unwrap();
+        assert!((s - 4.0 / 12.0).abs() < 1e-6, "got {s}");
+    }
+
+    #[test]
+    fn stability_is_none_without_common_hours() {
+        assert_eq!(stability(&summary(12, &[]), &summary(13, &[])), None);
+    }
+
+    fn snapshot(generated_at: DateTime<Utc>, days: Vec<DailySummary>) -> ForecastSnapshot {
+        ForecastSnapshot {
+            site: "Hill".into(),
+            generated_at,
+            daily_summaries: days
+                .into_iter()
+                .map(|mut day| {
+                    day.calculate_flyable_time_ranges(Hysteresis::NONE);
+                    day
+                })
+                .collect(),
+            provenance: ScoreProvenance::default(),
+        }
+    }
+
+    #[test]
+    fn run_as_of_skips_runs_too_old_to_count() {
+        let runs = [snapshot(run_at(10), vec![]), snapshot(run_at(11), vec![])];
+        let hour = chrono::Duration::hours(1);
+        assert_eq!(
+            run_as_of(&runs, run_at(11) + hour).map(|s| s.generated_at),
+            Some(run_at(11))
+        );
+        assert_eq!(
+            run_as_of(&runs, run_at(10) + hour).map(|s| s.generated_at),
+            Some(run_at(10))
+        );
+        assert!(run_as_of(&runs, run_at(12) + hour).is_none());
+        assert!(run_as_of(&runs, run_at(9)).is_none());
+    }
+
+    #[test]
+    fn diff_lists_flipped_ratings_and_shifted_windows() {
+        let before = snapshot(
+            run_at(10),
+            vec![
+                summary(12, &[10, 11]),
+                summary(13, &[10, 11, 12, 13]),
+                summary(14, &[12, 13]),
+            ],
+        );
+        let after = snapshot(
+            run_at(11),
+            vec![
+                summary(12, &[10, 11]),
+                summary(13, &[]),
+                summary(14, &[14, 15]),
+            ],
+        );
+
+        let changed = diff("Hill", Some(&before), Some(&after), 2).unwrap();
+        assert_eq!(changed.change, SiteChange::Changed);
+        let dates: Vec<u32> = changed.days.iter().map(|d| d.date.day()).collect();
+        assert_eq!(dates, [13, 14]);
+        assert_eq!(changed.days[0].rating_before, Some(DayRating::Good));
+        assert_eq!(changed.days[0].rating_after, None);
+        assert_eq!(changed.days[1].rating_before, changed.days[1].rating_after);
+        assert_eq!(changed.days[1].windows_after[0].start.hour(), 14);
+
+        assert!(!changed.scoring_changed);
+        let mut rescored = after.clone();
+        rescored.provenance.scorer_version += 1;
+        assert!(
+            diff("Hill", Some(&before), Some(&rescored), 2)
+                .unwrap()
+                .scoring_changed
+        );
+
+        assert!(diff("Hill", Some(&before), Some(&before), 2).is_none());
+        assert_eq!(
+            diff("Hill", None, Some(&after), 2).map(|d| d.change),
+            Some(SiteChange::Added)
+        );
+        assert_eq!(
+            diff("Hill", Some(&before), None, 2).map(|d| d.change),
+            Some(SiteChange::Removed)
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshots_do_not_leak_between_sites_sharing_a_prefix() {
+        let (_dir, history) = fresh_history();
+        history
+            .record(
+                "Foo",
+                run_at(10),
+                &[summary(13, &[])],
+                &ScoreProvenance::default(),
+            )
+            .await
+            .unwrap();
+        history
+            .record(
+                "Foo_Bar",
+                run_at(10),
+                &[summary(13, &[])],
+                &ScoreProvenance::default(),
+            )
+            .await
+            .unwrap();
+
+        let snapshots = history.snapshots("Foo").await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].site, "Foo");
+    }
+}
@@ -0,0 +1,224 @@
+//! Replays recent weather against proposed launch settings, so an edit of a
+//! launch's direction sector or wind limits can be sanity-checked before it is
+//! saved.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adapters::activities::paragliding::site_evaluator::{self, Hysteresis, WindLimits},
+    domain::{
+        paragliding::ParaglidingSite, ports::WeatherHistoryProvider, units::WindSpeed,
+        weather::WeatherForecast,
+    },
+};
+
+pub const LOOKBACK_DAYS: u8 = 30;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LaunchEdit {
+    /// Index of the launch to change, the first by default
+    #[serde(default)]
+    pub launch: usize,
+    pub direction_degrees_start: f64,
+    pub direction_degrees_stop: f64,
+    pub max_wind_kmh: Option<f32>,
+    pub max_gust_kmh: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayComparison {
+    pub date: NaiveDate,
+    pub current_flyable_hours: usize,
+    pub proposed_flyable_hours: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchCheck {
+    pub days: Vec<DayComparison>,
+    /// Days with a window of at least the minimum flyable hours
+    pub current_flyable_days: usize,
+    pub proposed_flyable_days: usize,
+}
+
+/// Scores the last `LOOKBACK_DAYS` days of weather at the edited launch with
+/// and without `edit`, joining flyable hours into windows by `hysteresis`.
+/// None if the site has no launch at the edit's index.
+pub async fn check_launch_edit(
+    site: &ParaglidingSite,
+    history: &dyn WeatherHistoryProvider,
+    edit: &LaunchEdit,
+    min_flyable_hours: u32,
+    hysteresis: Hysteresis,
+) -> Result<Option<LaunchCheck>> {
+    let Some(launch) = site.launches.get(edit.launch) else {
+        return Ok(None);
+    };
+    let past = history
+        .get_past_weather(&launch.location, LOOKBACK_DAYS)
+        .await?;
+    Ok(compare(site, edit, &past, min_flyable_hours, hysteresis))
+}
+
+fn compare(
+    site: &ParaglidingSite,
+    edit: &LaunchEdit,
+    past: &WeatherForecast,
+    min_flyable_hours: u32,
+    hysteresis: Hysteresis,
+) -> Option<LaunchCheck> {
+    let mut proposed = site.clone();
+    let launch = proposed.launches.get_mut(edit.launch)?;
+    launch.direction_degrees_start = edit.direction_degrees_start;
+    launch.direction_degrees_stop = edit.direction_degrees_stop;
+    let defaults = WindLimits::default();
+    let limits = WindLimits {
+        max_wind: edit
+            .max_wind_kmh
+            .map_or(defaults.max_wind, WindSpeed::from_kmh),
+        max_gust: edit
+            .max_gust_kmh
+            .map_or(defaults.max_gust, WindSpeed::from_kmh),
+    };
+
+    let current = site_evaluator::evaluate_site_with_limits(site, past, &defaults, hysteresis);
+    let proposed = site_evaluator::evaluate_site_with_limits(&proposed, past, &limits, hysteresis);
+    let count_days = |days: &[site_evaluator::DailySummary]| {
+        days.iter()
+            .filter(|day| day.is_flyable_day(min_flyable_hours))
+            .count()
+    };
+    Some(LaunchCheck {
+        current_flyable_days: count_days(&current.daily_summaries),
+        proposed_flyable_days: count_days(&proposed.daily_summaries),
+        days: current
+            .daily_summaries
+            .iter()
+            .zip(&proposed.daily_summaries)
+            .map(|(current, proposed)| DayComparison {
+                date: current.date,
+                current_flyable_hours: current.total_flyable_hours,
+                proposed_flyable_hours: proposed.total_flyable_hours,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        location::Location,
+        paragliding::{ParaglidingLaunch, SiteType},
+        ports::MockWeatherHistoryProvider,
+        weather::WeatherData,
+    };
+    use chrono::{TimeZone, Utc};
+
+    fn site() -> ParaglidingSite {
+        let location = Location::new(50.0, 13.0, "Hill".into(), "DE".into());
+        ParaglidingSite {
+            name: "Hill".into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location,
+                direction_degrees_start: 225.0,
+                direction_degrees_stop: 315.0,
+                elevation: 500.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            ..Default::default()
+        }
+    }
+
+    /// Midday hours of June days, 10 km/h from `directions[day]`.
+    fn past(directions: &[u16]) -> WeatherForecast {
+        WeatherForecast {
+            location: Location::new(50.0, 13.0, "Hill".into(), "DE".into()),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: directions
+                .iter()
+                .enumerate()
+                .flat_map(|(day, &direction)| {
+                    (9..15).map(move |hour| WeatherData {
+                        timestamp: Utc
+                            .with_ymd_and_hms(2026, 6, day as u32 + 1, hour, 0, 0)
+                            .unwrap(),
+                        temperature: 20.0,
+                        dew_point: 5.0,
+                        wind_speed_ms: WindSpeed::from_kmh(10.0).ms(),
+                        wind_direction: direction,
+                        ..Default::default()
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn edit(start: f64, stop: f64) -> LaunchEdit {
+        LaunchEdit {
+            launch: 0,
+            direction_degrees_start: start,
+            direction_degrees_stop: stop,
+            max_wind_kmh: None,
+            max_gust_kmh: None,
+        }
+    }
+
+    #[test]
+    fn wider_sector_gains_the_days_it_now_covers() {
+        // West, north-west, north
+        let past = past(&[270, 300, 350]);
+        let check = compare(&site(), &edit(225.0, 20.0), &past, 2, Hysteresis::NONE).unwrap();
+
+        assert_eq!(check.current_flyable_days, 2);
+        assert_eq!(check.proposed_flyable_days, 3);
+        assert_eq!(check.days.len(), 3);
+        assert_eq!(check.days[2].current_flyable_hours, 0);
+        assert_eq!(check.days[2].proposed_flyable_hours, 6);
+    }
+
+    #[test]
+    fn lower_wind_limit_removes_days() {
+        let mut edit = edit(225.0, 315.0);
+        edit.max_wind_kmh = Some(8.0);
+        let check = compare(&site(), &edit, &past(&[270]), 2, Hysteresis::NONE).unwrap();
+
+        assert_eq!(check.current_flyable_days, 1);
+        assert_eq!(check.proposed_flyable_days, 0);
+    }
+
+    #[test]
+    fn unknown_launch_is_none() {
+        let mut edit = edit(0.0, 90.0);
+        edit.launch = 3;
+        assert!(compare(&site(), &edit, &past(&[270]), 2, Hysteresis::NONE).is_none());
+    }
+
+    #[tokio::test]
+    async fn weather_is_fetched_at_the_edited_launch() {
+        let mut site = site();
+        site.launches.push(ParaglidingLaunch {
+            location: Location::new(50.3, 13.4, "Valley".into(), "DE".into()),
+            ..site.launches[0].clone()
+        });
+        let mut history = MockWeatherHistoryProvider::new();
+        history
+            .expect_get_past_weather()
+            .withf(|location, days| location.name == "Valley" && *days == LOOKBACK_DAYS)
+            .times(1)
+            .returning(|_, _| Ok(past(&[270])));
+        let mut edit = edit(0.0, 90.0);
+        edit.launch = 1;
+
+        let check = check_launch_edit(&site, &history, &edit, 2, Hysteresis::NONE)
+            .await
+            .unwrap();
+
+        assert!(check.is_some());
+    }
+}
@@ -1,6 +1,13 @@
+pub mod alternatives;
 pub mod dhv;
+pub mod evaluation_cache;
 pub mod flightlog_scraper;
+pub mod history;
 pub mod kml;
+pub mod launch_check;
+pub mod outlook;
+pub mod paragliding_earth;
+pub mod provider_chain;
 pub mod repository;
 pub mod site_evaluator;
 pub mod source;
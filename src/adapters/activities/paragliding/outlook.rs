@@ -0,0 +1,412 @@
+//! A few-day outlook small enough for watch widgets and IoT displays: per day
+//! only the rating, the best site, its window and the wind there.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+
+use crate::{
+    adapters::activities::paragliding::{
+        evaluation_cache::EvaluationCache,
+        repository::ParaglidingSiteRepository,
+        site_evaluator::{DailySummary, FlyableRange},
+        source,
+    },
+    domain::{
+        activities::{DayRating, SiteError},
+        location::Location,
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, ParaglidingSiteProvider, degrees_to_compass,
+        },
+        ports::WeatherProvider,
+        units::WindSpeed,
+        weather::{WeatherData, WeatherForecast},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompactDay {
+    pub date: NaiveDate,
+    /// Missing when no site is flyable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<DayRating>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    /// Longest window of the best site in UTC hours, e.g. `10-14`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    /// Mean wind in that window, e.g. `SW 12` (km/h)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind: Option<String>,
+}
+
+/// A site's day, with the forecast hours it was scored on.
+#[derive(Debug, Clone)]
+pub struct SiteDay {
+    pub site: ParaglidingSite,
+    pub day: DailySummary,
+    pub hours: Vec<WeatherData>,
+    /// Enough flyable hours for the configured minimum
+    pub flyable: bool,
+}
+
+impl SiteDay {
+    pub fn rating(&self) -> DayRating {
+        source::day_rating(&self.day)
+    }
+
+    /// Longest flyable window, e.g. `10-14` in UTC hours
+    pub fn window(&self) -> Option<String> {
+        let range = self.longest_range()?;
+        Some(format!("{}-{}", range.start.hour(), range.end.hour() + 1))
+    }
+
+    /// Mean wind in the longest window, e.g. `SW 12` (km/h)
+    pub fn wind(&self) -> Option<String> {
+        self.longest_range()?;
+        let (direction, speed) = self.mean_wind()?;
+        Some(format!(
+            "{} {:.0}",
+            degrees_to_compass(direction),
+            WindSpeed::from_ms(speed as f32).kmh()
+        ))
+    }
+
+    /// Mean wind as direction in degrees and speed in m/s, in the longest
+    /// window or over the daylight hours of a day without one.
+    pub fn mean_wind(&self) -> Option<(f64, f64)> {
+        let hours: Vec<&WeatherData> = match self.longest_range() {
+            Some(range) => self
+                .hours
+                .iter()
+                .filter(|h| h.timestamp >= range.start && h.timestamp <= range.end)
+                .collect(),
+            None => self
+                .hours
+                .iter()
+                .filter(|h| {
+                    self.day
+                        .hourly_scores
+                        .iter()
+                        .any(|s| s.timestamp == h.timestamp)
+                })
+                .collect(),
+        };
+        mean_wind(&hours)
+    }
+
+    fn longest_range(&self) -> Option<&FlyableRange> {
+        self.day.ranges.iter().max_by_key(|r| r.flyable_hours())
+    }
+}
+
+/// The sites' days, and the sites left out because they couldn't be scored.
+#[derive(Debug, Clone, Default)]
+pub struct SiteDays {
+    pub days: Vec<SiteDay>,
+    pub errors: Vec<SiteError>,
+}
+
+/// The forecast at `launch` of `site`. A failure is logged and comes back as
+/// the site's [`SiteError`], for a caller scoring several sites to report.
+pub async fn site_forecast(
+    weather: &dyn WeatherProvider,
+    site: &ParaglidingSite,
+    launch: &ParaglidingLaunch,
+) -> Result<WeatherForecast, SiteError> {
+    weather
+        .get_forecast(
+            launch.location.clone(),
+            site.preferred_weather_model.clone(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::warn!(site = %site.name, error = ?e, "Failed to get weather forecast");
+            SiteError::new(&site.name, &e)
+        })
+}
+
+/// Every forecast day of the sites within the configured search radius of
+/// `home`, skipping muted sites.
+pub async fn site_days(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    home: &Location,
+) -> Result<SiteDays> {
+    let settings = site_repo.get_settings().await?.unwrap_or_default();
+    site_days_within(
+        site_repo,
+        weather,
+        evaluations,
+        home,
+        settings.search_radius_km,
+    )
+    .await
+}
+
+/// Like [`site_days`], within `radius_km` of `center` instead.
+pub async fn site_days_within(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    center: &Location,
+    radius_km: f64,
+) -> Result<SiteDays> {
+    let settings = site_repo.get_settings().await?.unwrap_or_default();
+    let sites = site_repo
+        .fetch_launches_within_radius(center, radius_km)
+        .await;
+
+    let mut days = SiteDays::default();
+    for (site, _distance) in sites {
+        if site.mute_alerts == Some(true) {
+            continue;
+        }
+        let Some(launch) = site.launches.first() else {
+            continue;
+        };
+        let forecast = match site_forecast(weather, &site, launch).await {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                days.errors.push(e);
+                continue;
+            }
+        };
+        let eval = evaluations.evaluate(&site, &forecast);
+        for day in &eval.daily_summaries {
+            days.days.push(SiteDay {
+                site: site.clone(),
+                flyable: day.is_flyable_day(settings.minimum_flyable_hours),
+                day: day.clone(),
+                hours: forecast.forecast.clone(),
+            });
+        }
+    }
+    Ok(days)
+}
+
+/// The sites' days on `date`, most flyable hours first.
+pub async fn days_on(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    home: &Location,
+    date: NaiveDate,
+) -> Result<SiteDays> {
+    let mut days = site_days(site_repo, weather, evaluations, home).await?;
+    days.days.retain(|d| d.day.date == date);
+    days.days
+        .sort_by_key(|d| std::cmp::Reverse(d.day.total_flyable_hours));
+    Ok(days)
+}
+
+/// The sites' days on any of `dates`.
+pub async fn days_in(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    home: &Location,
+    dates: &[NaiveDate],
+) -> Result<SiteDays> {
+    let mut days = site_days(site_repo, weather, evaluations, home).await?;
+    days.days.retain(|d| dates.contains(&d.day.date));
+    Ok(days)
+}
+
+/// Best site per day for the `days` days from `today`, looking at the sites
+/// within the configured search radius of `home`. Kept to the days for small
+/// displays, so sites that couldn't be scored aren't listed.
+pub async fn compact_outlook(
+    site_repo: &ParaglidingSiteRepository,
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    home: &Location,
+    today: NaiveDate,
+    days: usize,
+) -> Result<Vec<CompactDay>> {
+    let candidates = site_days(site_repo, weather, evaluations, home).await?;
+    Ok((0..days)
+        .filter_map(|offset| today.checked_add_days(chrono::Days::new(offset as u64)))
+        .map(|date| best_of_day(date, &candidates.days))
+        .collect())
+}
+
+fn best_of_day(date: NaiveDate, candidates: &[SiteDay]) -> CompactDay {
+    let best = candidates
+        .iter()
+        .filter(|c| c.flyable && c.day.date == date)
+        .max_by_key(|c| c.day.total_flyable_hours);
+    let Some(best) = best else {
+        return CompactDay {
+            date,
+            rating: None,
+            site: None,
+            window: None,
+            wind: None,
+        };
+    };
+
+    CompactDay {
+        date,
+        rating: Some(best.rating()),
+        site: Some(best.site.name.clone()),
+        window: best.window(),
+        wind: best.wind(),
+    }
+}
+
+/// Flyability of a single site, as published for smart-home displays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteState {
+    pub site: String,
+    /// Flyable hours on the next flyable day, 0 if there is none
+    pub flyable_hours: usize,
+    /// Score of that day from 0 to 1, 0 if there is none
+    pub score: f32,
+    /// Start of that day's longest window
+    pub window_start: Option<DateTime<Utc>>,
+    pub rating: Option<DayRating>,
+}
+
+/// State of `site` for the first day from `today` on with enough flyable hours.
+pub fn next_flyable(
+    site: &str,
+    days: &[DailySummary],
+    today: NaiveDate,
+    min_hours: u32,
+) -> SiteState {
+    let day = days
+        .iter()
+        .filter(|d| d.date >= today)
+        .find(|d| d.is_flyable_day(min_hours));
+    SiteState {
+        site: site.to_string(),
+        flyable_hours: day.map_or(0, |d| d.total_flyable_hours),
+        score: day.map_or(0.0, source::day_value),
+        window_start: day
+            .and_then(|d| d.ranges.iter().max_by_key(|r| r.flyable_hours()))
+            .map(|r| r.start),
+        rating: day.map(source::day_rating),
+    }
+}
+
+/// Vector mean of the directions, so 350° and 10° average to north instead of
+/// south, and mean speed in m/s.
+fn mean_wind(hours: &[&WeatherData]) -> Option<(f64, f64)> {
+    if hours.is_empty() {
+        return None;
+    }
+    let n = hours.len() as f64;
+    let (mut east, mut north, mut speed) = (0.0, 0.0, 0.0);
+    for h in hours {
+        let direction = f64::from(h.wind_direction).to_radians();
+        east += direction.sin();
+        north += direction.cos();
+        speed += f64::from(h.wind_speed_ms);
+    }
+    let direction = east.atan2(north).to_degrees();
+    Some((direction, speed / n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, 13).unwrap()
+    }
+
+    fn hour(h: u32, direction: u16, speed_ms: f32) -> WeatherData {
+        WeatherData {
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap(),
+            wind_direction: direction,
+            wind_speed_ms: speed_ms,
+            ..Default::default()
+        }
+    }
+
+    fn day(start: u32, end: u32) -> DailySummary {
+        DailySummary {
+            date: date(),
+            ranges: vec![FlyableRange {
+                start: Utc.with_ymd_and_hms(2026, 6, 13, start, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2026, 6, 13, end, 0, 0).unwrap(),
+            }],
+            total_flyable_hours: (end - start + 1) as usize,
+            ..Default::default()
+        }
+    }
+
+    fn candidate(name: &str, day: DailySummary, hours: Vec<WeatherData>) -> SiteDay {
+        SiteDay {
+            site: ParaglidingSite {
+                name: name.into(),
+                data_source: "test".into(),
+                ..Default::default()
+            },
+            day,
+            hours,
+            flyable: true,
+        }
+    }
+
+    #[test]
+    fn picks_site_with_most_flyable_hours() {
+        let hours = vec![hour(10, 350, 3.0), hour(11, 10, 3.0), hour(12, 10, 4.0)];
+        let candidates = vec![
+            candidate("Short", day(10, 11), hours.clone()),
+            candidate("Long", day(10, 12), hours),
+        ];
+
+        let compact = best_of_day(date(), &candidates);
+
+        assert_eq!(compact.site.as_deref(), Some("Long"));
+        assert_eq!(compact.rating, Some(DayRating::Fair));
+        assert_eq!(compact.window.as_deref(), Some("10-13"));
+        assert_eq!(compact.wind.as_deref(), Some("N 12"));
+    }
+
+    #[test]
+    fn day_without_flyable_site_is_only_a_date() {
+        let compact = best_of_day(date(), &[]);
+        let json = serde_json::to_string(&compact).unwrap();
+        assert_eq!(json, r#"{"date":"2026-06-13"}"#);
+    }
+
+    #[test]
+    fn three_days_stay_well_below_a_kilobyte() {
+        let hours = vec![hour(10, 225, 4.0)];
+        let candidates = vec![candidate("A rather long site name, Ost", day(9, 17), hours)];
+        let compact: Vec<CompactDay> = (0..3).map(|_| best_of_day(date(), &candidates)).collect();
+        assert!(serde_json::to_vec(&compact).unwrap().len() < 1024);
+    }
+
+    #[test]
+    fn next_flyable_skips_past_and_short_days() {
+        let mut past = day(10, 16);
+        past.date = date().pred_opt().unwrap();
+        let mut short = day(10, 10);
+        short.date = date();
+        let mut good = day(11, 14);
+        good.date = date().succ_opt().unwrap();
+
+        let state = next_flyable("Hill", &[past, short, good], date(), 2);
+
+        assert_eq!(state.flyable_hours, 4);
+        assert!((state.score - 4.0 / 7.5).abs() < 1e-5, "{}", state.score);
+        assert_eq!(state.rating, Some(DayRating::Good));
+        assert_eq!(
+            state.window_start,
+            Some(Utc.with_ymd_and_hms(2026, 6, 13, 11, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn no_flyable_day_means_no_window() {
+        let state = next_flyable("Hill", &[], date(), 2);
+        assert_eq!((state.flyable_hours, state.score), (0, 0.0));
+        assert!(state.window_start.is_none() && state.rating.is_none());
+    }
+}
@@ -0,0 +1,363 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Deserializer};
+use tracing::instrument;
+
+use crate::{
+    adapters::cache::PersistentCache,
+    domain::{
+        geo,
+        location::Location,
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, SiteType, characteristics::SiteCharacteristics,
+            sites_within_radius,
+        },
+        ports::SiteSearchProvider,
+    },
+};
+
+const BASE_URL: &str = "https://www.paraglidingearth.com/api/geojson/getAroundLatLngSites.php";
+pub const DATA_SOURCE: &str = "Paragliding Earth";
+const SITE_PAGE_URL: &str = "https://www.paraglidingearth.com/";
+// The API caps every answer at `limit` sites, so wide searches are split into
+// tiles no bigger than this, each of which stays below the cap in practice.
+const MAX_TILE_RADIUS_KM: f64 = 50.0;
+const RESULT_LIMIT: usize = 100;
+const CACHE_TTL: Duration = Duration::from_hours(24);
+
+pub struct ParaglidingEarthClient {
+    cache: Arc<PersistentCache>,
+    http: ClientWithMiddleware,
+}
+
+impl ParaglidingEarthClient {
+    pub fn new(cache: Arc<PersistentCache>, http: ClientWithMiddleware) -> Self {
+        Self { cache, http }
+    }
+
+    /// Sites with a launch within `radius_km` of `center`, closest first.
+    #[instrument(skip(self), fields(lat = center.latitude, lon = center.longitude))]
+    pub async fn search_sites(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Result<Vec<(ParaglidingSite, f64)>> {
+        let mut sites: HashMap<String, ParaglidingSite> = HashMap::new();
+        for (tile, tile_radius_km) in tiles(center, radius_km) {
+            for site in self.search_tile(&tile, tile_radius_km).await? {
+                sites.entry(site.name.clone()).or_insert(site);
+            }
+        }
+        let sites: Vec<ParaglidingSite> = sites.into_values().collect();
+        Ok(sites_within_radius(&sites, center, radius_km))
+    }
+
+    async fn search_tile(&self, center: &Location, radius_km: f64) -> Result<Vec<ParaglidingSite>> {
+        let cache_key = format!(
+            "paragliding_earth_{:.2}_{:.2}_{:.0}",
+            center.latitude, center.longitude, radius_km
+        );
+        if let Some(cached) = self.cache.get::<Vec<ParaglidingSite>>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let url = format!(
+            "{}?lat={}&lng={}&distance={}&limit={}&style=detailled",
+            BASE_URL, center.latitude, center.longitude, radius_km, RESULT_LIMIT
+        );
+        let response: FeatureCollection = self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Paragliding Earth response")?;
+        if response.features.len() >= RESULT_LIMIT {
+            tracing::warn!(
+                radius_km,
+                "Paragliding Earth result limit reached, sites may be missing"
+            );
+        }
+
+        let sites: Vec<ParaglidingSite> = response
+            .features
+            .into_iter()
+            .filter_map(Feature::into_site)
+            .collect();
+        self.cache.put(&cache_key, sites.clone(), CACHE_TTL).await?;
+        Ok(sites)
+    }
+}
+
+#[async_trait]
+impl SiteSearchProvider for ParaglidingEarthClient {
+    fn name(&self) -> &'static str {
+        "paragliding_earth"
+    }
+
+    async fn search_sites(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Result<Vec<(ParaglidingSite, f64)>> {
+        ParaglidingEarthClient::search_sites(self, center, radius_km).await
+    }
+}
+
+/// Centers and radii of the requests that together cover the circle around
+/// `center`. Small searches are a single request.
+fn tiles(center: &Location, radius_km: f64) -> Vec<(Location, f64)> {
+    if radius_km <= MAX_TILE_RADIUS_KM {
+        return vec![(center.clone(), radius_km)];
+    }
+
+    // Squares inscribed in the tile circles leave no gaps between neighbours.
+    let step_km = MAX_TILE_RADIUS_KM * std::f64::consts::SQRT_2;
+    let steps = (radius_km / step_km).ceil() as i32;
+
+    let mut tiles = Vec::new();
+    for row in -steps..=steps {
+        for column in -steps..=steps {
+            let north_km = f64::from(row) * step_km;
+            let east_km = f64::from(column) * step_km;
+            if north_km.hypot(east_km) > radius_km + MAX_TILE_RADIUS_KM {
+                continue;
+            }
+            let (latitude, longitude) = geo::destination(
+                center.coordinates(),
+                east_km.atan2(north_km).to_degrees(),
+                north_km.hypot(east_km),
+            );
+            let tile = Location::new(latitude, longitude, String::new(), String::new());
+            tiles.push((tile, MAX_TILE_RADIUS_KM));
+        }
+    }
+    tiles
+}
+
+#[derive(Debug, Deserialize)]
+struct FeatureCollection {
+    #[serde(default)]
+    features: Vec<Feature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Feature {
+    geometry: Geometry,
+    properties: Properties,
+}
+
+#[derive(Debug, Deserialize)]
+struct Geometry {
+    /// `[longitude, latitude]`
+    coordinates: Vec<f64>,
+}
+
+/// Orientation flags are 0 (not launchable), 1 (launchable) or 2 (good).
+#[derive(Debug, Deserialize)]
+struct Properties {
+    name: String,
+    #[serde(default, deserialize_with = "number_or_string")]
+    pge_site_id: Option<f64>,
+    #[serde(rename = "countryCode", default)]
+    country_code: Option<String>,
+    #[serde(default, deserialize_with = "number_or_string")]
+    takeoff_altitude: Option<f64>,
+    #[serde(default)]
+    flight_rules: Option<String>,
+    #[serde(default)]
+    going_there: Option<String>,
+    #[serde(default)]
+    weather: Option<String>,
+    #[serde(rename = "N", default, deserialize_with = "number_or_string")]
+    n: Option<f64>,
+    #[serde(rename = "NE", default, deserialize_with = "number_or_string")]
+    ne: Option<f64>,
+    #[serde(rename = "E", default, deserialize_with = "number_or_string")]
+    e: Option<f64>,
+    #[serde(rename = "SE", default, deserialize_with = "number_or_string")]
+    se: Option<f64>,
+    #[serde(rename = "S", default, deserialize_with = "number_or_string")]
+    s: Option<f64>,
+    #[serde(rename = "SW", default, deserialize_with = "number_or_string")]
+    sw: Option<f64>,
+    #[serde(rename = "W", default, deserialize_with = "number_or_string")]
+    w: Option<f64>,
+    #[serde(rename = "NW", default, deserialize_with = "number_or_string")]
+    nw: Option<f64>,
+}
+
+impl Properties {
+    fn launch_directions(&self) -> Vec<f64> {
+        [
+            (self.n, 0.0),
+            (self.ne, 45.0),
+            (self.e, 90.0),
+            (self.se, 135.0),
+            (self.s, 180.0),
+            (self.sw, 225.0),
+            (self.w, 270.0),
+            (self.nw, 315.0),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| flag.is_some_and(|f| f >= 1.0))
+        .map(|(_, degrees)| degrees)
+        .collect()
+    }
+}
+
+// The API is inconsistent about quoting numbers.
+fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(f64),
+        String(String),
+    }
+    Ok(match Option::<NumberOrString>::deserialize(deserializer)? {
+        Some(NumberOrString::Number(n)) => Some(n),
+        Some(NumberOrString::String(s)) => s.trim().parse().ok(),
+        None => None,
+    })
+}
+
+impl Feature {
+    fn into_site(self) -> Option<ParaglidingSite> {
+        let [longitude, latitude, ..] = self.geometry.coordinates[..] else {
+            return None;
+        };
+        let properties = self.properties;
+        let country = properties.country_code.clone().unwrap_or_default();
+        let location = Location::new(latitude, longitude, properties.name.clone(), country);
+        let elevation = properties.takeoff_altitude.unwrap_or(0.0);
+
+        // One launch per open compass sector, like the DHV import does.
+        let launches = properties
+            .launch_directions()
+            .into_iter()
+            .map(|degrees| ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: location.clone(),
+                direction_degrees_start: (degrees - 22.5_f64).rem_euclid(360.0),
+                direction_degrees_stop: (degrees + 22.5_f64).rem_euclid(360.0),
+                elevation,
+            })
+            .collect();
+
+        let characteristics = SiteCharacteristics {
+            flight_rules: properties.flight_rules,
+            going_there: properties.going_there,
+            weather: properties.weather,
+        };
+
+        Some(ParaglidingSite {
+            name: properties.name,
+            launches,
+            landings: vec![],
+            country: properties.country_code,
+            data_source: DATA_SOURCE.into(),
+            parking_location: None,
+            mute_alerts: None,
+            rating: None,
+            preferred_weather_model: None,
+            winter_operable: None,
+            favorite: None,
+            characteristics: (!characteristics.is_empty()).then_some(characteristics),
+            closures: vec![],
+            source_url: properties
+                .pge_site_id
+                .map(|id| format!("{}?site={}", SITE_PAGE_URL, id as u64)),
+            busy: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::paragliding::characteristics::SiteRule;
+
+    fn center() -> Location {
+        Location::new(47.0, 11.0, "Center".into(), "AT".into())
+    }
+
+    #[test]
+    fn small_radius_is_a_single_request() {
+        let tiles = tiles(&center(), 30.0);
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].1, 30.0);
+    }
+
+    #[test]
+    fn tiles_cover_the_whole_search_circle() {
+        let center = center();
+        let radius_km = 150.0;
+        let tiles = tiles(&center, radius_km);
+        assert!(tiles.len() > 1);
+
+        // Sample points on and inside the search circle.
+        for ring in [0.0, 0.5, 1.0] {
+            for step in 0..36 {
+                let km = radius_km * ring;
+                let (latitude, longitude) =
+                    geo::destination(center.coordinates(), f64::from(step * 10), km);
+                let point = Location::new(latitude, longitude, String::new(), String::new());
+                assert!(
+                    tiles
+                        .iter()
+                        .any(|(tile, r)| tile.distance_to(&point) <= r * 1.01),
+                    "point at {km} km, bearing {} deg not covered",
+                    step * 10
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn feature_maps_to_site_with_launch_per_open_sector() {
+        let collection: FeatureCollection = serde_json::from_str(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [11.2, 47.3] },
+                    "properties": {
+                        "name": "Hill",
+                        "pge_site_id": "4711",
+                        "countryCode": "at",
+                        "takeoff_altitude": "1650",
+                        "flight_rules": "Radio mandatory on 143.950",
+                        "going_there": "",
+                        "N": "0", "NE": "0", "E": 0, "SE": "1",
+                        "S": "2", "SW": "0", "W": "0", "NW": "0"
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let site = collection
+            .features
+            .into_iter()
+            .next()
+            .and_then(Feature::into_site)
+            .unwrap();
+        assert_eq!(site.name, "Hill");
+        assert_eq!(site.data_source, DATA_SOURCE);
+        assert_eq!(
+            site.source_url.as_deref(),
+            Some("https://www.paraglidingearth.com/?site=4711")
+        );
+        assert_eq!(site.launches.len(), 2);
+        assert_eq!(site.launches[0].location.latitude, 47.3);
+        assert_eq!(site.launches[0].elevation, 1650.0);
+        assert_eq!(site.launches[0].direction_degrees_start, 112.5);
+        assert_eq!(site.launches[1].direction_degrees_stop, 202.5);
+        let characteristics = site.characteristics.unwrap();
+        assert_eq!(characteristics.rules(), vec![SiteRule::RadioMandatory]);
+    }
+}
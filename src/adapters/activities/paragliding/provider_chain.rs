@@ -0,0 +1,295 @@
+//! Searches several site databases in order and merges what they find, e.g.
+//! DHV first and Paragliding Earth for everything DHV doesn't list.
+//!
+//! A provider that fails `max_failures` times in a row is skipped until its
+//! cooldown has passed, so an unreachable API doesn't slow down every search.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    adapters::activities::paragliding::dhv,
+    domain::{
+        location::{Location, normalize_place_name},
+        paragliding::ParaglidingSite,
+        ports::SiteSearchProvider,
+    },
+};
+
+const DEFAULT_MAX_FAILURES: u32 = 3;
+const DEFAULT_COOLDOWN: Duration = Duration::from_mins(5);
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProviderStatus {
+    pub name: &'static str,
+    pub enabled: bool,
+    pub consecutive_failures: u32,
+    /// Skipped until then after too many failures in a row
+    pub disabled_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+struct Entry {
+    provider: Arc<dyn SiteSearchProvider>,
+    status: Mutex<ProviderStatus>,
+}
+
+pub struct SiteProviderChain {
+    entries: Vec<Entry>,
+    max_failures: u32,
+    cooldown: Duration,
+}
+
+impl SiteProviderChain {
+    pub fn new(providers: Vec<Arc<dyn SiteSearchProvider>>) -> Self {
+        Self::with_health_policy(providers, DEFAULT_MAX_FAILURES, DEFAULT_COOLDOWN)
+    }
+
+    pub fn with_health_policy(
+        providers: Vec<Arc<dyn SiteSearchProvider>>,
+        max_failures: u32,
+        cooldown: Duration,
+    ) -> Self {
+        let entries = providers
+            .into_iter()
+            .map(|provider| Entry {
+                status: Mutex::new(ProviderStatus {
+                    name: provider.name(),
+                    enabled: true,
+                    ..Default::default()
+                }),
+                provider,
+            })
+            .collect();
+        Self {
+            entries,
+            max_failures,
+            cooldown,
+        }
+    }
+
+    /// Sites within `radius_km` of `center` from every available provider,
+    /// closest first. A site found by several providers is taken from the
+    /// first; sites that only share a name are all kept. Fails only if no
+    /// provider answered.
+    #[tracing::instrument(skip(self), fields(lat = center.latitude, lon = center.longitude))]
+    pub async fn search_sites(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Result<Vec<(ParaglidingSite, f64)>> {
+        let mut seen = HashSet::new();
+        let mut merged = Vec::new();
+        let mut answered = false;
+        for entry in &self.entries {
+            if !self.is_available(entry) {
+                continue;
+            }
+            match entry.provider.search_sites(center, radius_km).await {
+                Ok(sites) => {
+                    answered = true;
+                    self.record_success(entry);
+                    merged.extend(
+                        sites
+                            .into_iter()
+                            .filter(|(site, _)| seen.insert(duplicate_key(site))),
+                    );
+                }
+                Err(e) => {
+                    let provider = entry.provider.name();
+                    tracing::warn!(provider, error = ?e, "Site search failed");
+                    self.record_failure(entry, &e);
+                }
+            }
+        }
+        if !answered {
+            bail!("No site provider available");
+        }
+        merged.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(merged)
+    }
+
+    pub fn statuses(&self) -> Vec<ProviderStatus> {
+        let now = Utc::now();
+        self.entries
+            .iter()
+            .map(|entry| {
+                let mut status = entry.status.lock().unwrap().clone();
+                status.enabled = status.disabled_until.is_none_or(|until| until <= now);
+                status
+            })
+            .collect()
+    }
+
+    fn is_available(&self, entry: &Entry) -> bool {
+        let status = entry.status.lock().unwrap();
+        status
+            .disabled_until
+            .is_none_or(|until| until <= Utc::now())
+    }
+
+    fn record_success(&self, entry: &Entry) {
+        let mut status = entry.status.lock().unwrap();
+        status.consecutive_failures = 0;
+        status.disabled_until = None;
+        status.last_success = Some(Utc::now());
+    }
+
+    fn record_failure(&self, entry: &Entry, error: &anyhow::Error) {
+        let mut status = entry.status.lock().unwrap();
+        status.consecutive_failures += 1;
+        status.last_error = Some(format!("{:#}", error));
+        if status.consecutive_failures >= self.max_failures {
+            status.disabled_until = Some(Utc::now() + self.cooldown);
+            tracing::warn!(
+                provider = status.name,
+                failures = status.consecutive_failures,
+                "Disabling site provider for a while"
+            );
+        }
+    }
+}
+
+/// Like [`dhv::duplicate_key`], ignoring how providers spell the name.
+fn duplicate_key(site: &ParaglidingSite) -> (String, Option<(i64, i64)>) {
+    let (name, launch) = dhv::duplicate_key(site);
+    (normalize_place_name(&name), launch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        paragliding::{ParaglidingLaunch, SiteType},
+        ports::MockSiteSearchProvider,
+    };
+    use anyhow::anyhow;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn site(name: &str, source: &str) -> ParaglidingSite {
+        ParaglidingSite {
+            name: name.into(),
+            data_source: source.into(),
+            ..Default::default()
+        }
+    }
+
+    fn provider(name: &'static str, sites: Vec<(&'static str, f64)>) -> MockSiteSearchProvider {
+        let mut mock = MockSiteSearchProvider::new();
+        mock.expect_name().return_const(name);
+        mock.expect_search_sites().returning(move |_, _| {
+            Ok(sites
+                .iter()
+                .map(|(site_name, distance)| (site(site_name, name), *distance))
+                .collect())
+        });
+        mock
+    }
+
+    fn failing(name: &'static str, calls: Arc<AtomicUsize>) -> MockSiteSearchProvider {
+        let mut mock = MockSiteSearchProvider::new();
+        mock.expect_name().return_const(name);
+        mock.expect_search_sites().returning(move |_, _| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("timeout"))
+        });
+        mock
+    }
+
+    fn center() -> Location {
+        Location::new(47.5, 11.0, String::new(), String::new())
+    }
+
+    #[tokio::test]
+    async fn results_are_merged_with_the_first_provider_winning() {
+        let chain = SiteProviderChain::new(vec![
+            Arc::new(provider("dhv", vec![("Wank", 12.0)])),
+            Arc::new(provider("pge", vec![("wank", 12.1), ("Laber", 3.0)])),
+        ]);
+
+        let sites = chain.search_sites(&center(), 50.0).await.unwrap();
+        let found: Vec<(&str, &str)> = sites
+            .iter()
+            .map(|(s, _)| (s.name.as_str(), s.data_source.as_str()))
+            .collect();
+        assert_eq!(found, vec![("Laber", "pge"), ("Wank", "dhv")]);
+    }
+
+    #[tokio::test]
+    async fn different_sites_sharing_a_name_are_all_kept() {
+        let at = |latitude: f64, source: &str| ParaglidingSite {
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(latitude, 11.0, String::new(), String::new()),
+                direction_degrees_start: 180.0,
+                direction_degrees_stop: 270.0,
+                elevation: 1000.0,
+            }],
+            ..site("Sonnenhang", source)
+        };
+        let (north, south) = (at(47.6, "dhv"), at(47.4, "dhv"));
+        let mut dhv = MockSiteSearchProvider::new();
+        dhv.expect_name().return_const("dhv");
+        dhv.expect_search_sites()
+            .returning(move |_, _| Ok(vec![(north.clone(), 11.0), (south.clone(), 11.0)]));
+        let (same, elsewhere) = (at(47.6, "pge"), at(47.5, "pge"));
+        let mut pge = MockSiteSearchProvider::new();
+        pge.expect_name().return_const("pge");
+        pge.expect_search_sites()
+            .returning(move |_, _| Ok(vec![(same.clone(), 11.0), (elsewhere.clone(), 1.0)]));
+        let chain = SiteProviderChain::new(vec![Arc::new(dhv), Arc::new(pge)]);
+
+        let sites = chain.search_sites(&center(), 50.0).await.unwrap();
+        let found: Vec<(f64, &str)> = sites
+            .iter()
+            .map(|(s, _)| (s.launches[0].location.latitude, s.data_source.as_str()))
+            .collect();
+        assert_eq!(found, vec![(47.5, "pge"), (47.6, "dhv"), (47.4, "dhv")]);
+    }
+
+    #[tokio::test]
+    async fn failing_provider_is_skipped_after_max_failures() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = SiteProviderChain::with_health_policy(
+            vec![
+                Arc::new(provider("dhv", vec![("Wank", 12.0)])),
+                Arc::new(failing("pge", calls.clone())),
+            ],
+            2,
+            Duration::from_hours(1),
+        );
+
+        for _ in 0..4 {
+            assert_eq!(chain.search_sites(&center(), 50.0).await.unwrap().len(), 1);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let status = &chain.statuses()[1];
+        assert!(!status.enabled);
+        assert_eq!(status.consecutive_failures, 2);
+        assert_eq!(status.last_error.as_deref(), Some("timeout"));
+        assert!(chain.statuses()[0].last_success.is_some());
+    }
+
+    #[tokio::test]
+    async fn disabled_provider_is_retried_after_the_cooldown() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = SiteProviderChain::with_health_policy(
+            vec![Arc::new(failing("pge", calls.clone()))],
+            1,
+            Duration::ZERO,
+        );
+
+        assert!(chain.search_sites(&center(), 50.0).await.is_err());
+        assert!(chain.search_sites(&center(), 50.0).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,38 +1,163 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     adapters::store::PersistentStore,
+    config::SettingsOverrides,
     domain::{
         location::Location,
-        paragliding::{ParaglidingSite, ParaglidingSiteProvider, UserSettings},
+        paragliding::{
+            ParaglidingLanding, ParaglidingLaunch, ParaglidingSite, ParaglidingSiteProvider,
+            UserSettings, index::SiteIndex,
+        },
     },
 };
 
 const SETTINGS_KEY: &str = "user_settings";
+/// Outside the `site_` prefix, so it is not read as a site
+const FORMAT_VERSION_KEY: &str = "format_version_sites";
+
+/// Bump whenever `ParaglidingSite` changes shape and migrate the stored sites
+/// in [`ParaglidingSiteRepository::migrate`], since postcard can't tell an old
+/// record from a broken one.
+const SITE_FORMAT_VERSION: u32 = 1;
+
+/// `ParaglidingSite` as stored before the format was versioned.
+#[derive(Debug, Serialize, Deserialize)]
+struct SiteV0 {
+    name: String,
+    launches: Vec<ParaglidingLaunch>,
+    landings: Vec<ParaglidingLanding>,
+    country: Option<String>,
+    data_source: String,
+    parking_location: Option<Location>,
+    mute_alerts: Option<bool>,
+    rating: Option<u8>,
+    preferred_weather_model: Option<String>,
+}
+
+impl From<SiteV0> for ParaglidingSite {
+    fn from(site: SiteV0) -> Self {
+        ParaglidingSite {
+            name: site.name,
+            launches: site.launches,
+            landings: site.landings,
+            country: site.country,
+            data_source: site.data_source,
+            parking_location: site.parking_location,
+            mute_alerts: site.mute_alerts,
+            rating: site.rating,
+            preferred_weather_model: site.preferred_weather_model,
+            ..Default::default()
+        }
+    }
+}
 
 pub struct ParaglidingSiteRepository {
     store: Arc<PersistentStore>,
+    overrides: SettingsOverrides,
+    /// Built on the first area search, dropped whenever a site changes
+    index: Mutex<Option<Arc<SiteIndex>>>,
+    /// Counts site changes, so an index built while one happened isn't kept
+    site_changes: AtomicU64,
 }
 
 impl ParaglidingSiteRepository {
     pub fn new(store: Arc<PersistentStore>) -> Self {
-        Self { store }
+        Self {
+            store,
+            overrides: SettingsOverrides::default(),
+            index: Mutex::default(),
+            site_changes: AtomicU64::default(),
+        }
+    }
+
+    /// Applies the active profile's settings and watched sites on every read.
+    pub fn with_overrides(mut self, overrides: SettingsOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Rewrites sites stored by older builds in the current format. Run once
+    /// at startup, before anything reads a site.
+    pub async fn migrate(&self) -> Result<usize> {
+        let version: u32 = self.store.get(FORMAT_VERSION_KEY).await?.unwrap_or(0);
+        if version == SITE_FORMAT_VERSION {
+            return Ok(0);
+        }
+        let sites = self.store.get_all_starting_with::<SiteV0>("site_").await?;
+        let migrated = sites.len();
+        for site in sites {
+            self.save_site(site.into()).await?;
+        }
+        self.store
+            .put(FORMAT_VERSION_KEY, SITE_FORMAT_VERSION)
+            .await?;
+        tracing::info!(migrated, from = version, "Migrated stored sites");
+        Ok(migrated)
     }
 
-    pub async fn save_site(&self, site: ParaglidingSite) -> Result<()> {
+    /// Stores `site` without the profile's closures, which are added on read.
+    pub async fn save_site(&self, mut site: ParaglidingSite) -> Result<()> {
+        self.overrides.remove_from_site(&mut site);
         let key = format!("site_{}", site.name);
-        self.store.put(&key, site).await
+        self.store.put(&key, site).await?;
+        self.invalidate_index();
+        Ok(())
+    }
+
+    pub async fn get_site(&self, name: &str) -> Result<Option<ParaglidingSite>> {
+        let key = format!("site_{}", name);
+        let mut site = self.store.get::<ParaglidingSite>(&key).await?;
+        if let Some(site) = &mut site {
+            self.overrides.apply_to_site(site);
+        }
+        Ok(site)
     }
 
     pub async fn delete_site(&self, name: &str) -> Result<()> {
         let key = format!("site_{}", name);
-        self.store.remove(&key).await
+        self.store.remove(&key).await?;
+        self.invalidate_index();
+        Ok(())
+    }
+
+    /// Every site, indexed by its launches for area searches.
+    pub async fn site_index(&self) -> Result<Arc<SiteIndex>> {
+        if let Some(index) = self.index.lock().unwrap().as_ref() {
+            return Ok(index.clone());
+        }
+        let changes = self.site_changes.load(Ordering::Acquire);
+        let index = Arc::new(SiteIndex::new(self.all_sites().await?));
+        let mut cached = self.index.lock().unwrap();
+        if self.site_changes.load(Ordering::Acquire) == changes {
+            *cached = Some(index.clone());
+        }
+        Ok(index)
+    }
+
+    fn invalidate_index(&self) {
+        let mut cached = self.index.lock().unwrap();
+        self.site_changes.fetch_add(1, Ordering::AcqRel);
+        *cached = None;
     }
 
     pub async fn get_settings(&self) -> Result<Option<UserSettings>> {
-        self.store.get::<UserSettings>(SETTINGS_KEY).await
+        let stored = self.store.get::<UserSettings>(SETTINGS_KEY).await?;
+        Ok(self.overrides.apply(stored))
+    }
+
+    async fn all_sites(&self) -> Result<Vec<ParaglidingSite>> {
+        let mut sites: Vec<ParaglidingSite> = self.store.get_all_starting_with("site_").await?;
+        sites
+            .iter_mut()
+            .for_each(|site| self.overrides.apply_to_site(site));
+        Ok(sites)
     }
 
     pub async fn save_settings(&self, settings: &UserSettings) -> Result<()> {
@@ -46,42 +171,24 @@ impl ParaglidingSiteProvider for ParaglidingSiteRepository {
         center: &Location,
         radius_km: f64,
     ) -> Vec<(ParaglidingSite, f64)> {
-        let sites: Vec<ParaglidingSite> = match self.store.get_all_starting_with("site_").await {
-            Ok(sites) => sites,
+        let index = match self.site_index().await {
+            Ok(index) => index,
             Err(e) => {
                 tracing::error!(error = ?e, "Failed to fetch sites from store");
                 return vec![];
             }
         };
 
-        if sites.is_empty() {
+        if index.sites().is_empty() {
             tracing::info!("No sites found in store");
             return vec![];
         }
 
-        let mut results = Vec::new();
-
-        for site in &sites {
-            let mut min_distance = f64::INFINITY;
-
-            for launch in &site.launches {
-                let distance = center.distance_to(&launch.location);
-                if distance < min_distance {
-                    min_distance = distance;
-                }
-            }
-
-            if min_distance <= radius_km {
-                results.push((site.clone(), min_distance));
-            }
-        }
-
-        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
-        results
+        index.within_radius(center, radius_km)
     }
 
     async fn fetch_all_sites(&self) -> Vec<ParaglidingSite> {
-        match self.store.get_all_starting_with("site_").await {
+        match self.all_sites().await {
             Ok(sites) => sites,
             Err(e) => {
                 tracing::error!(error = ?e, "Failed to fetch all sites from store");
@@ -94,7 +201,8 @@ impl ParaglidingSiteProvider for ParaglidingSiteRepository {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::paragliding::{ParaglidingLaunch, SiteType};
+    use crate::domain::paragliding::{ParaglidingLaunch, SiteType, closure::Closure};
+    use chrono::NaiveDate;
     use tempfile::TempDir;
 
     fn fresh_repo() -> (TempDir, ParaglidingSiteRepository) {
@@ -117,13 +225,9 @@ mod tests {
                 direction_degrees_stop: 360.0,
                 elevation: 500.0,
             }],
-            landings: vec![],
             country: Some("DE".into()),
             data_source: "test".into(),
-            parking_location: None,
-            mute_alerts: None,
-            rating: None,
-            preferred_weather_model: None,
+            ..Default::default()
         }
     }
 
@@ -154,6 +258,88 @@ mod tests {
         assert!(got.is_none());
     }
 
+    #[tokio::test]
+    async fn profile_overrides_apply_on_read() {
+        let (_dir, repo) = fresh_repo();
+        let repo = repo.with_overrides(SettingsOverrides {
+            search_radius_km: Some(400.0),
+            watched_sites: Some(vec!["near".into()]),
+            ..Default::default()
+        });
+        repo.save_site(site_at("near", 50.71, 13.01)).await.unwrap();
+        repo.save_site(site_at("far", 52.5, 13.4)).await.unwrap();
+
+        let settings = repo.get_settings().await.unwrap().unwrap();
+        assert_eq!(settings.search_radius_km, 400.0);
+        assert_eq!(
+            settings.calendar_name,
+            UserSettings::default().calendar_name
+        );
+
+        let favorites: Vec<(String, Option<bool>)> = repo
+            .fetch_all_sites()
+            .await
+            .into_iter()
+            .map(|s| (s.name, s.favorite))
+            .collect();
+        assert_eq!(
+            favorites,
+            vec![("far".into(), Some(false)), ("near".into(), Some(true))]
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_rewrites_sites_of_older_builds() {
+        let (_dir, repo) = fresh_repo();
+        let old = SiteV0 {
+            name: "Old".into(),
+            launches: site_at("Old", 50.71, 13.0).launches,
+            landings: vec![],
+            country: Some("DE".into()),
+            data_source: "DHV".into(),
+            parking_location: None,
+            mute_alerts: Some(true),
+            rating: Some(4),
+            preferred_weather_model: None,
+        };
+        repo.store.put("site_Old", old).await.unwrap();
+
+        assert_eq!(repo.migrate().await.unwrap(), 1);
+        assert_eq!(repo.migrate().await.unwrap(), 0);
+
+        let site = repo.get_site("Old").await.unwrap().unwrap();
+        assert_eq!(site.rating, Some(4));
+        assert_eq!(site.mute_alerts, Some(true));
+        assert!(site.closures.is_empty());
+        assert_eq!(repo.fetch_all_sites().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn profile_closures_are_added_once_and_not_stored() {
+        let (_dir, repo) = fresh_repo();
+        let closure = Closure {
+            from: NaiveDate::from_ymd_opt(2000, 4, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2000, 7, 15).unwrap(),
+            yearly: true,
+            reason: "raptor nesting".into(),
+            needs_review: false,
+        };
+        let repo = repo.with_overrides(SettingsOverrides {
+            closures: vec![("Wank".into(), closure.clone())],
+            ..Default::default()
+        });
+        repo.save_site(site_at("Wank", 47.5, 11.1)).await.unwrap();
+
+        // A site read with the closure and saved again, e.g. after an edit
+        let site = repo.get_site("Wank").await.unwrap().unwrap();
+        repo.save_site(site).await.unwrap();
+
+        let site = repo.get_site("Wank").await.unwrap().unwrap();
+        assert_eq!(site.closures, [closure]);
+        let stored: ParaglidingSite = repo.store.get("site_Wank").await.unwrap().unwrap();
+        assert!(stored.closures.is_empty());
+    }
+
     #[tokio::test]
     async fn fetch_within_radius_filters_by_distance() {
         let (_dir, repo) = fresh_repo();
@@ -207,4 +393,27 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].0.name, "B");
     }
+
+    #[tokio::test]
+    async fn site_index_is_rebuilt_after_changes() {
+        let (_dir, repo) = fresh_repo();
+        repo.save_site(site_at("A", 50.71, 13.0)).await.unwrap();
+        let home = Location::new(50.7, 13.0, "Home".into(), "DE".into());
+        assert_eq!(
+            repo.fetch_launches_within_radius(&home, 50.0).await.len(),
+            1
+        );
+        let index = repo.site_index().await.unwrap();
+        assert!(Arc::ptr_eq(&index, &repo.site_index().await.unwrap()));
+
+        repo.save_site(site_at("B", 50.72, 13.0)).await.unwrap();
+        assert_eq!(
+            repo.fetch_launches_within_radius(&home, 50.0).await.len(),
+            2
+        );
+        repo.delete_site("A").await.unwrap();
+        let index = repo.site_index().await.unwrap();
+        assert_eq!(index.sites().len(), 1);
+        assert_eq!(index.sites()[0].name, "B");
+    }
 }
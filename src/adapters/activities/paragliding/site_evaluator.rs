@@ -1,73 +1,388 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use travelai_core::{
+    score::{self, Factor, Hour, Launch, Thresholds},
+    sector::wind_direction_in_sector,
+};
 
-use crate::domain::{
-    paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType},
-    weather::{self, WeatherData, WeatherForecast},
+use crate::{
+    adapters::activities::paragliding::evaluation_cache::EvaluationCache,
+    config::WindowConfig,
+    domain::{
+        activities::{self, DayRating, Score, TimeWindow},
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, SiteType,
+            characteristics::SiteRule,
+            crowding::{self, Crowding},
+        },
+        ports::EnsembleProvider,
+        sounding::Sounding,
+        units::{Temperature, WindSpeed},
+        weather::{
+            self, DRY_ADIABATIC_LAPSE_RATE_K_PER_M, ENVIRONMENTAL_LAPSE_RATE_K_PER_M,
+            EXTENDED_RANGE_MODEL, FieldAvailability, WeatherData, WeatherForecast,
+        },
+    },
 };
 
-#[derive(Debug, Clone)]
+/// Bump whenever a change to the scoring can change a result, so evaluations
+/// made by the old rules are not reused.
+pub const SCORER_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HourlyScore {
     pub timestamp: DateTime<Utc>,
     pub is_flyable: bool,
+    pub hazards: Vec<Hazard>,
+    /// What made the hour unflyable, None if it is flyable
+    pub limiting_factor: Option<Factor>,
+    /// Share of the ensemble members in which the hour is flyable, see
+    /// [`add_flyable_probabilities`]
+    pub flyable_probability: Option<f32>,
+    /// Top of the boundary layer, the highest a thermal can be expected to
+    /// take a pilot, in meters above sea level
+    pub climb_ceiling_m: Option<f64>,
+}
+
+/// Conditions worth an icon, independent of whether a launch works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Hazard {
+    Gusts,
+    Thunderstorm,
+    Foehn,
+    /// The site's flight rules mention restricted airspace or a height limit
+    Airspace,
+    Rain,
+    StrongValleyWind,
+    LowCloudbase,
+    /// Pressure falling fast enough that a front is on its way
+    FrontalPassage,
+}
+
+impl Hazard {
+    pub fn label(self) -> &'static str {
+        match self {
+            Hazard::Gusts => "gusts",
+            Hazard::Thunderstorm => "thunderstorm",
+            Hazard::Foehn => "foehn",
+            Hazard::Airspace => "airspace",
+            Hazard::Rain => "rain",
+            Hazard::StrongValleyWind => "strong valley wind",
+            Hazard::LowCloudbase => "low cloudbase",
+            Hazard::FrontalPassage => "frontal passage likely",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailySummary {
     pub date: NaiveDate,
     pub hourly_scores: Vec<HourlyScore>,
     pub ranges: Vec<FlyableRange>,
     pub total_flyable_hours: usize,
+    /// Every hazard of the day's hours, each once
+    pub hazards: Vec<Hazard>,
+    /// Flyable hours per part of the day, in local solar time
+    pub day_parts: Vec<DayPartSummary>,
+    pub likely_snow_covered: bool,
+    pub altitude_temperatures: Option<AltitudeTemperatures>,
+    /// Highest top of the boundary layer during daylight, in meters above sea level
+    pub boundary_layer_top_m: Option<f64>,
+    /// Whether the wind changes enough with height to make the air turbulent,
+    /// see [`wind_shear_warning`]
+    pub strong_wind_shear: bool,
+    /// Whether thermals take pilots far enough above launch in some flyable
+    /// hour to go cross-country, None without boundary layer data
+    pub xc_possible: Option<bool>,
+    /// Steepest 24 hour pressure change during daylight, in hPa scaled to sea level
+    pub pressure_tendency_hpa: Option<f32>,
+    /// Least stable air during daylight, see [`weather::stability_index`]
+    pub stability_index: Option<f32>,
+    /// Hour from which the cumulus are expected to overdevelop
+    pub overdevelopment_from: Option<DateTime<Utc>>,
+    pub crowding: Crowding,
+    pub warnings: Vec<String>,
+    /// Weather model behind the day's hours, if known
+    pub source_model: Option<String>,
+    /// How much to trust the day's forecast, from 0 to 1
+    pub confidence: f32,
+    /// How straight the wind blows into the best launch during flyable hours,
+    /// from 0 (crosswind at the limit) to 1 (straight in)
+    pub wind_alignment: Option<f32>,
+    /// Highest chance of precipitation during daylight in percent, if the
+    /// model provides it
+    pub precipitation_probability: Option<u8>,
+    /// Lowest height of the 0 °C isotherm during daylight, in meters above
+    /// sea level
+    pub freezing_level_m: Option<f64>,
+    /// Lowest lifted index around midday, see [`add_soundings`]
+    pub lifted_index: Option<f32>,
+    /// Highest usable lift around midday above the highest launch, the lower
+    /// of thermal top and cloud base, in meters
+    pub usable_lift_m: Option<f64>,
+}
+
+/// A day nothing is known about yet: no hours, no warnings and full
+/// confidence.
+impl Default for DailySummary {
+    fn default() -> Self {
+        DailySummary {
+            date: NaiveDate::default(),
+            hourly_scores: vec![],
+            ranges: vec![],
+            total_flyable_hours: 0,
+            hazards: vec![],
+            day_parts: vec![],
+            likely_snow_covered: false,
+            altitude_temperatures: None,
+            boundary_layer_top_m: None,
+            strong_wind_shear: false,
+            xc_possible: None,
+            pressure_tendency_hpa: None,
+            stability_index: None,
+            overdevelopment_from: None,
+            crowding: Crowding::Low,
+            warnings: vec![],
+            source_model: None,
+            confidence: 1.0,
+            wind_alignment: None,
+            precipitation_probability: None,
+            freezing_level_m: None,
+            lifted_index: None,
+            usable_lift_m: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayPart {
+    Morning,
+    Midday,
+    Afternoon,
+}
+
+// Local solar hours at which midday and afternoon start. East faces usually
+// work before midday, west faces after it.
+const MIDDAY_START_HOUR: f64 = 11.0;
+const AFTERNOON_START_HOUR: f64 = 15.0;
+
+impl DayPart {
+    pub const ALL: [DayPart; 3] = [DayPart::Morning, DayPart::Midday, DayPart::Afternoon];
+
+    /// Part of the day `timestamp` falls into at `longitude`, going by the sun
+    /// rather than by time zone.
+    pub fn at(timestamp: DateTime<Utc>, longitude: f64) -> DayPart {
+        let utc_hour = f64::from(timestamp.hour()) + f64::from(timestamp.minute()) / 60.0;
+        let solar_hour = (utc_hour + longitude / 15.0).rem_euclid(24.0);
+        if solar_hour < MIDDAY_START_HOUR {
+            DayPart::Morning
+        } else if solar_hour < AFTERNOON_START_HOUR {
+            DayPart::Midday
+        } else {
+            DayPart::Afternoon
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DayPart::Morning => "morning",
+            DayPart::Midday => "midday",
+            DayPart::Afternoon => "afternoon",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayPartSummary {
+    pub part: DayPart,
+    pub flyable_hours: usize,
+    /// Daylight hours in this part of the day
+    pub hours: usize,
+    /// How much to trust the forecast for this part of the day, from 0 to 1
+    pub confidence: f32,
+    /// Share of the ensemble members in which this part of the day is
+    /// flyable, see [`add_flyable_probabilities`]
+    pub flyable_probability: Option<f32>,
+    /// Mean wind alignment of the flyable hours, see [`score::best_alignment`]
+    pub wind_alignment: Option<f32>,
+    /// None without flyable hours, see [`DayPartSummary::score`]
+    pub rating: Option<DayRating>,
+}
+
+impl DayPartSummary {
+    /// How good this part of the day is on its own, so that east faces can
+    /// rate well in the morning and west faces in the afternoon. No flyable
+    /// hours, no score.
+    pub fn score(&self) -> Option<Score> {
+        if self.flyable_hours == 0 {
+            return None;
+        }
+        let mut factors = vec![
+            (
+                activities::Factor::FlyableShare,
+                1.0,
+                self.flyable_hours as f32 / self.hours as f32,
+            ),
+            (activities::Factor::ForecastConfidence, 1.0, self.confidence),
+        ];
+        factors.extend(
+            self.wind_alignment
+                .map(|a| (activities::Factor::WindAlignment, 1.0, a)),
+        );
+        factors.extend(
+            self.flyable_probability
+                .map(|p| (activities::Factor::FlyableChance, 1.0, p)),
+        );
+        Score::from_factors(factors, vec![])
+    }
+
+    fn rate(&mut self) {
+        self.rating = self.score().map(|s| s.rating());
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AltitudeTemperatures {
+    /// Coldest temperature at the highest launch during daylight, in Celsius
+    pub launch_temperature: f32,
+    /// Highest cloud base of the day, in meters above sea level
+    pub cloud_base_m: f64,
+    /// Temperature at that cloud base, in Celsius
+    pub cloud_base_temperature: f32,
 }
 
-#[derive(Debug, Clone)]
+/// Consecutive flyable hours, apart from dips too short to close the window
+/// (see [`Hysteresis`]). `start` and `end` are the starts of the first and the
+/// last flyable hour, so a range lasts until an hour after `end`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FlyableRange {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
 }
 
 impl FlyableRange {
-    pub fn is_at_least(&self, d: Duration) -> bool {
-        (self.end - self.start) >= d
+    /// Number of flyable hours in the range, both ends included.
+    pub fn flyable_hours(&self) -> u32 {
+        (self.end - self.start).num_hours() as u32 + 1
+    }
+
+    /// From the start of the first flyable hour to the end of the last, so
+    /// the window lasts [`Self::flyable_hours`].
+    pub fn window(&self) -> TimeWindow {
+        TimeWindow {
+            start: self.start,
+            end: self.end + Duration::hours(1),
+        }
+    }
+}
+
+/// How many hours in a row it takes to open or close a flyable window, so
+/// wind hovering around a limit doesn't split the day into scraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hysteresis {
+    /// Flyable hours in a row that open a window; shorter runs are dropped
+    pub open_hours: u32,
+    /// Unflyable hours in a row that close an open window; shorter dips are
+    /// bridged
+    pub close_hours: u32,
+}
+
+impl Hysteresis {
+    /// Every flyable run is a window of its own.
+    pub const NONE: Hysteresis = Hysteresis {
+        open_hours: 1,
+        close_hours: 1,
+    };
+}
+
+impl From<&WindowConfig> for Hysteresis {
+    fn from(config: &WindowConfig) -> Self {
+        Hysteresis {
+            open_hours: config.open_hours,
+            close_hours: config.close_hours,
+        }
     }
 }
 
 impl DailySummary {
-    pub fn calculate_flyable_time_ranges(&mut self) {
-        self.ranges.clear();
-
-        let mut flyable: Vec<&HourlyScore> =
-            self.hourly_scores.iter().filter(|h| h.is_flyable).collect();
-        flyable.sort_by_key(|h| h.timestamp);
-
-        let mut ranges: Vec<FlyableRange> = Vec::new();
-        let mut current_range: Option<Vec<&HourlyScore>> = None;
-
-        for score in flyable {
-            match &mut current_range {
-                Some(range_scores) => {
-                    let last_score = range_scores.last().unwrap();
-
-                    if score.timestamp == last_score.timestamp + Duration::hours(1) {
-                        range_scores.push(score);
-                    } else {
-                        let start = range_scores.first().unwrap().timestamp;
-                        let end = range_scores.last().unwrap().timestamp;
-                        ranges.push(FlyableRange { start, end });
-
-                        current_range = Some(vec![score]);
-                    }
-                }
-                None => {
-                    current_range = Some(vec![score]);
+    /// Parts of the day with flyable hours, if the day is only partly flyable.
+    pub fn flyable_day_parts(&self) -> Option<Vec<DayPart>> {
+        let flyable: Vec<DayPart> = self
+            .day_parts
+            .iter()
+            .filter(|p| p.flyable_hours > 0)
+            .map(|p| p.part)
+            .collect();
+        let daylight_parts = self.day_parts.iter().filter(|p| p.hours > 0).count();
+        (!flyable.is_empty() && flyable.len() < daylight_parts).then_some(flyable)
+    }
+
+    /// The best rated parts of the day with their rating, unless every part
+    /// with daylight rates the same.
+    pub fn best_day_parts(&self) -> Option<(Vec<DayPart>, DayRating)> {
+        let best = self.day_parts.iter().filter_map(|p| p.rating).max()?;
+        let daylight: Vec<&DayPartSummary> =
+            self.day_parts.iter().filter(|p| p.hours > 0).collect();
+        let parts: Vec<DayPart> = daylight
+            .iter()
+            .filter(|p| p.rating == Some(best))
+            .map(|p| p.part)
+            .collect();
+        (parts.len() < daylight.len()).then_some((parts, best))
+    }
+
+    /// Whether the day has a continuous window of at least `min_hours` flyable
+    /// hours. A lone flyable hour between bad ones doesn't make a flying day.
+    pub fn is_flyable_day(&self, min_hours: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|r| r.flyable_hours() >= min_hours.max(1))
+    }
+
+    pub fn calculate_flyable_time_ranges(&mut self, hysteresis: Hysteresis) {
+        let mut hours: Vec<&HourlyScore> = self.hourly_scores.iter().collect();
+        hours.sort_by_key(|h| h.timestamp);
+
+        // Consecutive hours alike in flyability, as (flyable, first, last)
+        let mut runs: Vec<(bool, DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        for hour in hours {
+            match runs.last_mut() {
+                Some((flyable, _, last))
+                    if *flyable == hour.is_flyable
+                        && hour.timestamp == *last + Duration::hours(1) =>
+                {
+                    *last = hour.timestamp
                 }
+                _ => runs.push((hour.is_flyable, hour.timestamp, hour.timestamp)),
             }
         }
+        let length = |first: DateTime<Utc>, last: DateTime<Utc>| (last - first).num_hours() + 1;
+        let follows =
+            |previous: DateTime<Utc>, next: DateTime<Utc>| next == previous + Duration::hours(1);
 
-        if let Some(range_scores) = current_range {
-            let start = range_scores.first().unwrap().timestamp;
-            let end = range_scores.last().unwrap().timestamp;
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i < runs.len() {
+            let (flyable, start, mut end) = runs[i];
+            i += 1;
+            if !flyable || length(start, end) < i64::from(hysteresis.open_hours) {
+                continue;
+            }
+            while let [
+                (false, dip_start, dip_end),
+                (true, next_start, next_end),
+                ..,
+            ] = runs[i..]
+            {
+                let short_dip = length(dip_start, dip_end) < i64::from(hysteresis.close_hours);
+                if !(short_dip && follows(end, dip_start) && follows(dip_end, next_start)) {
+                    break;
+                }
+                end = next_end;
+                i += 2;
+            }
             ranges.push(FlyableRange { start, end });
         }
 
@@ -75,53 +390,531 @@ impl DailySummary {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SiteEvaluationResult {
     pub daily_summaries: Vec<DailySummary>,
+    /// How many forecast hours had a real value for each field, to tell
+    /// scores built on stand-in values apart
+    pub field_availability: Vec<FieldAvailability>,
+    pub provenance: ScoreProvenance,
+    /// Wind limits the hours were scored against
+    #[serde(skip)]
+    pub limits: WindLimits,
+}
+
+/// What an evaluation was made from, so that a changed outlook can be put
+/// down to the scoring or to the weather.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreProvenance {
+    pub scorer_version: u32,
+    /// Fingerprint of the thresholds the hours were scored against and of
+    /// the hysteresis they were joined into windows with
+    pub decision_hash: String,
+    /// Id of the weather provider the forecast came from, e.g. `open_meteo`
+    pub provider: Option<String>,
+    /// Weather models of the forecast hours, in order of first use; hours
+    /// from the provider's default model count as [`DEFAULT_MODEL`]
+    pub models: Vec<String>,
 }
 
-const MAX_WIND_MS: f32 = 25.0 / 3.6;
-const MAX_GUST_MS: f32 = 40.0 / 3.6;
+/// Stands in the provenance for hours of a provider's default model
+pub const DEFAULT_MODEL: &str = "default";
 
-fn is_flyable(weather: &WeatherData, launch: &ParaglidingLaunch) -> bool {
-    if !matches!(launch.site_type, SiteType::Hang) {
-        return false;
+impl ScoreProvenance {
+    fn new(thresholds: &Thresholds, hysteresis: Hysteresis, forecast: &WeatherForecast) -> Self {
+        let mut models: Vec<String> = Vec::new();
+        for hour in &forecast.forecast {
+            let model = hour.model.as_deref().unwrap_or(DEFAULT_MODEL);
+            if !models.iter().any(|m| m == model) {
+                models.push(model.to_string());
+            }
+        }
+        Self {
+            scorer_version: SCORER_VERSION,
+            decision_hash: decision_hash(thresholds, hysteresis),
+            provider: forecast.provider.clone(),
+            models,
+        }
     }
-    if weather.precipitation != 0.0 {
-        return false;
+
+    /// Whether `other` was scored by different rules.
+    pub fn scoring_differs(&self, other: &ScoreProvenance) -> bool {
+        self.scorer_version != other.scorer_version || self.decision_hash != other.decision_hash
+    }
+}
+
+/// 64-bit FNV-1a of the thresholds and hysteresis in hex, stable across builds
+/// unlike the std hasher, since it is kept with the forecast history.
+fn decision_hash(thresholds: &Thresholds, hysteresis: Hysteresis) -> String {
+    let bytes = postcard::to_stdvec(&(thresholds, hysteresis)).unwrap_or_default();
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+const MAX_WIND: WindSpeed = WindSpeed::from_kmh(25.0);
+const MAX_GUST: WindSpeed = WindSpeed::from_kmh(40.0);
+
+/// Wind at or above which a launch is not flyable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindLimits {
+    pub max_wind: WindSpeed,
+    pub max_gust: WindSpeed,
+}
+
+impl Default for WindLimits {
+    fn default() -> Self {
+        Self {
+            max_wind: MAX_WIND,
+            max_gust: MAX_GUST,
+        }
+    }
+}
+
+impl WindLimits {
+    fn thresholds(&self) -> Thresholds {
+        Thresholds {
+            max_wind_ms: self.max_wind.ms(),
+            max_gust_ms: self.max_gust.ms(),
+            ..Thresholds::default()
+        }
     }
-    if weather.wind_speed_ms >= MAX_WIND_MS {
+}
+
+fn hour(weather: &WeatherData) -> Hour {
+    Hour {
+        wind_speed_ms: weather.wind_speed_ms,
+        wind_gust_ms: weather.wind_gust_ms,
+        wind_direction: f64::from(weather.wind_direction),
+        precipitation: weather.precipitation,
+    }
+}
+
+fn core_launch(launch: &ParaglidingLaunch) -> Launch {
+    Launch {
+        direction_degrees_start: launch.direction_degrees_start,
+        direction_degrees_stop: launch.direction_degrees_stop,
+        winch: !matches!(launch.site_type, SiteType::Hang),
+    }
+}
+// Launches above this are treated as mountain launches that can hold snow.
+const MOUNTAIN_LAUNCH_ELEVATION_M: f64 = 1000.0;
+const SNOW_DEPTH_THRESHOLD_M: f32 = 0.05;
+const RECENT_SNOWFALL_THRESHOLD_CM: f32 = 5.0;
+
+// Below this the cloud base is too close to launch for soaring.
+const LOW_CLOUD_BASE_AGL_M: f64 = 400.0;
+// Strong southerly flow aloft is the classic Foehn setup north of the Alps.
+const FOEHN_WIND_850HPA: WindSpeed = WindSpeed::from_kmh(50.0);
+const FOEHN_DIRECTIONS: (f64, f64) = (135.0, 225.0);
+
+fn hazards(weather: &WeatherData) -> Vec<Hazard> {
+    let mut hazards = Vec::new();
+    if weather.wind_gust() >= MAX_GUST {
+        hazards.push(Hazard::Gusts);
+    }
+    // Weather codes 95 to 99 are all described as thunderstorms.
+    if weather.description.starts_with("Thunderstorm") {
+        hazards.push(Hazard::Thunderstorm);
+    }
+    if let (Some(speed), Some(direction)) =
+        (weather.wind_speed_850hpa_ms, weather.wind_direction_850hpa)
+        && WindSpeed::from_ms(speed) >= FOEHN_WIND_850HPA
+        && wind_direction_in_sector(f64::from(direction), FOEHN_DIRECTIONS.0, FOEHN_DIRECTIONS.1)
+    {
+        hazards.push(Hazard::Foehn);
+    }
+    if weather.precipitation > 0.0 {
+        hazards.push(Hazard::Rain);
+    }
+    if weather.wind_speed() >= MAX_WIND {
+        hazards.push(Hazard::StrongValleyWind);
+    }
+    if weather::cloud_base_agl_m(weather.temperature, weather.dew_point) < LOW_CLOUD_BASE_AGL_M {
+        hazards.push(Hazard::LowCloudbase);
+    }
+    hazards
+}
+
+fn likely_snow_covered(
+    site: &ParaglidingSite,
+    day: &[WeatherData],
+    full_forecast: &[WeatherData],
+) -> bool {
+    if site.winter_operable == Some(true) {
         return false;
     }
-    if weather.wind_gust_ms >= MAX_GUST_MS {
+    let has_mountain_launch = site
+        .launches
+        .iter()
+        .any(|l| l.elevation >= MOUNTAIN_LAUNCH_ELEVATION_M);
+    if !has_mountain_launch {
         return false;
     }
-    wind_direction_in_sector(
-        weather.wind_direction as f64,
-        launch.direction_degrees_start,
-        launch.direction_degrees_stop,
-    )
+
+    let max_snow_depth = day.iter().map(|w| w.snow_depth_m).fold(0.0, f32::max);
+
+    // Snow that fell overnight is outside the daylight slice, so look back 24h
+    // from the day's last hour over the whole forecast.
+    let recent_snowfall: f32 = day
+        .iter()
+        .map(|w| w.timestamp)
+        .max()
+        .map(|until| {
+            full_forecast
+                .iter()
+                .filter(|w| w.timestamp > until - Duration::hours(24) && w.timestamp <= until)
+                .map(|w| w.snowfall_cm.max(0.0))
+                .sum()
+        })
+        .unwrap_or(0.0);
+
+    max_snow_depth >= SNOW_DEPTH_THRESHOLD_M || recent_snowfall >= RECENT_SNOWFALL_THRESHOLD_CM
 }
 
-fn wind_direction_in_sector(wind_dir: f64, start: f64, stop: f64) -> bool {
-    // start == stop is the conventional way to say "launchable from any direction"
-    // (e.g. a flat-top site). Without this branch the strict-< sector check would
-    // reject every wind, since `start < wind && wind < start` is never true.
-    if start == stop {
-        return true;
+// Below this a thermal topping out near cloud base can ice up wing and instruments.
+const ICING_TEMPERATURE: Temperature = Temperature::from_celsius(0.0);
+// Below this it is cold enough at launch that pilots should pack winter gear.
+const COLD_LAUNCH_TEMPERATURE: Temperature = Temperature::from_celsius(5.0);
+
+fn highest_launch_elevation(site: &ParaglidingSite) -> Option<f64> {
+    site.launches
+        .iter()
+        .map(|l| l.elevation)
+        .max_by(f64::total_cmp)
+}
+
+fn altitude_temperatures(
+    site: &ParaglidingSite,
+    day: &[WeatherData],
+    grid_elevation: Option<f64>,
+) -> Option<AltitudeTemperatures> {
+    let launch_elevation = highest_launch_elevation(site)?;
+    // Without the model's terrain height assume it already refers to the launch.
+    let grid_elevation = grid_elevation.unwrap_or(launch_elevation);
+
+    let launch_temperature = day
+        .iter()
+        .map(|w| {
+            weather::temperature_at_altitude(
+                w.temperature,
+                grid_elevation,
+                launch_elevation,
+                ENVIRONMENTAL_LAPSE_RATE_K_PER_M,
+            )
+        })
+        .min_by(f32::total_cmp)?;
+
+    let peak = day.iter().max_by(|a, b| {
+        weather::cloud_base_agl_m(a.temperature, a.dew_point)
+            .total_cmp(&weather::cloud_base_agl_m(b.temperature, b.dew_point))
+    })?;
+    let cloud_base_m = grid_elevation + weather::cloud_base_agl_m(peak.temperature, peak.dew_point);
+    let cloud_base_temperature = weather::temperature_at_altitude(
+        peak.temperature,
+        grid_elevation,
+        cloud_base_m,
+        DRY_ADIABATIC_LAPSE_RATE_K_PER_M,
+    );
+
+    Some(AltitudeTemperatures {
+        launch_temperature,
+        cloud_base_m,
+        cloud_base_temperature,
+    })
+}
+
+/// Thermals rarely climb past the top of the boundary layer, so its height above
+/// the launch is the working band for the day.
+fn boundary_layer_top_m(
+    site: &ParaglidingSite,
+    day: &[WeatherData],
+    grid_elevation: Option<f64>,
+) -> Option<f64> {
+    let ground = grid_elevation.or_else(|| highest_launch_elevation(site))?;
+    day.iter()
+        .filter_map(|w| w.boundary_layer_height_m)
+        .max_by(f32::total_cmp)
+        .map(|height| ground + f64::from(height))
+}
+
+// Climbing less than this above launch is local soaring at best.
+const XC_MIN_CLIMB_ABOVE_LAUNCH_M: f64 = 300.0;
+
+/// Whether the climb ceiling of any flyable hour is high enough above the
+/// highest launch to leave it. None if no flyable hour has a ceiling.
+fn xc_possible(site: &ParaglidingSite, hours: &[HourlyScore]) -> Option<bool> {
+    let launch = highest_launch_elevation(site)?;
+    hours
+        .iter()
+        .filter(|h| h.is_flyable)
+        .filter_map(|h| h.climb_ceiling_m)
+        .map(|ceiling| ceiling >= launch + XC_MIN_CLIMB_ABOVE_LAUNCH_M)
+        .reduce(|a, b| a || b)
+}
+
+// A difference this large between two heights means turbulent, hard to read
+// air even when the launch wind itself looks fine.
+const WIND_SHEAR_WARNING: WindSpeed = WindSpeed::from_ms(8.0);
+// A launch this little above the valley sits in the layer the gradient wind
+// punches down into, so less shear already makes it rough.
+const SMALL_HILL_HEIGHT_M: f64 = 300.0;
+const SMALL_HILL_WIND_SHEAR_WARNING: WindSpeed = WindSpeed::from_ms(5.0);
+
+/// Wind at one height: where it is, its speed in m/s and direction in degrees.
+type WindLevel = (&'static str, f32, u16);
+
+/// The hour's winds at 10 m, 80 m and 850 hPa, those the model provides,
+/// lowest first.
+fn wind_levels(hour: &WeatherData) -> Vec<WindLevel> {
+    [
+        (
+            "the surface",
+            Some(hour.wind_speed_ms),
+            Some(hour.wind_direction),
+        ),
+        ("80 m", hour.wind_speed_80m_ms, hour.wind_direction_80m),
+        (
+            "~1500 m",
+            hour.wind_speed_850hpa_ms,
+            hour.wind_direction_850hpa,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(level, speed, direction)| Some((level, speed?, direction?)))
+    .collect()
+}
+
+/// How far the highest launch rises above the lowest landing, or else above
+/// the model's ground.
+fn launch_height_above_valley(site: &ParaglidingSite, grid_elevation: Option<f64>) -> Option<f64> {
+    let launch = highest_launch_elevation(site)?;
+    let valley = site
+        .landings
+        .iter()
+        .map(|l| l.elevation)
+        .min_by(f64::total_cmp)
+        .or(grid_elevation)?;
+    Some(launch - valley)
+}
+
+/// Warns of the day's strongest speed or direction shear between any two of
+/// the 10 m, 80 m and 850 hPa winds, if it is strong enough to make the air
+/// turbulent. Small hills get the warning at less shear.
+fn wind_shear_warning(
+    site: &ParaglidingSite,
+    day: &[WeatherData],
+    grid_elevation: Option<f64>,
+) -> Option<String> {
+    let limit = match launch_height_above_valley(site, grid_elevation) {
+        Some(height) if height < SMALL_HILL_HEIGHT_M => SMALL_HILL_WIND_SHEAR_WARNING,
+        _ => WIND_SHEAR_WARNING,
+    };
+    let (hour, lower, upper, shear) = day
+        .iter()
+        .flat_map(|w| {
+            let levels = wind_levels(w);
+            let mut pairs = Vec::new();
+            for (i, lower) in levels.iter().enumerate() {
+                for upper in &levels[i + 1..] {
+                    let shear =
+                        weather::wind_vector_difference_ms(lower.1, lower.2, upper.1, upper.2);
+                    pairs.push((w, *lower, *upper, shear));
+                }
+            }
+            pairs
+        })
+        .max_by(|a, b| a.3.total_cmp(&b.3))?;
+    (WindSpeed::from_ms(shear) >= limit).then(|| {
+        format!(
+            "Turbulent wind shear: {} from {} at {} vs {} from {} at {} ({} UTC)",
+            WindSpeed::from_ms(upper.1),
+            WeatherData::wind_direction_to_cardinal(upper.2),
+            upper.0,
+            WindSpeed::from_ms(lower.1),
+            WeatherData::wind_direction_to_cardinal(lower.2),
+            lower.0,
+            hour.timestamp.format("%H:%M"),
+        )
+    })
+}
+
+// Enough CAPE for showers and thunderstorms to grow out of the thermals.
+const OVERDEVELOPMENT_CAPE_J_PER_KG: f32 = 500.0;
+// Cloud cover growing this much within the window means the cumulus spread out.
+const OVERDEVELOPMENT_CLOUD_GROWTH_PCT: i16 = 20;
+const OVERDEVELOPMENT_WINDOW_HOURS: usize = 2;
+
+/// Daylight an hour needs to be flown
+const MIN_DAYLIGHT: Duration = Duration::minutes(30);
+
+/// Rules out the hours with less than [`MIN_DAYLIGHT`] left before `sunset`,
+/// and the hours the wind allows from `overdevelopment` on, naming why.
+fn rule_out_hours(
+    hours: &mut [HourlyScore],
+    sunset: Option<DateTime<Utc>>,
+    overdevelopment: Option<DateTime<Utc>>,
+) {
+    for hour in hours {
+        let factor = if sunset.is_some_and(|sunset| sunset - hour.timestamp < MIN_DAYLIGHT) {
+            Factor::Darkness
+        } else if hour.is_flyable && overdevelopment.is_some_and(|from| hour.timestamp >= from) {
+            Factor::Overdevelopment
+        } else {
+            continue;
+        };
+        hour.is_flyable = false;
+        hour.limiting_factor = Some(factor);
+    }
+}
+
+/// First hour the day's cumulus are expected to overdevelop: the lifted
+/// condensation level is above the highest launch, so thermals build clouds
+/// rather than fog, CAPE is high, and cloud cover grows fast while the cloud
+/// base stops rising.
+fn overdevelopment_from(
+    site: &ParaglidingSite,
+    day: &[WeatherData],
+    grid_elevation: Option<f64>,
+) -> Option<DateTime<Utc>> {
+    let launch_elevation = highest_launch_elevation(site)?;
+    let ground = grid_elevation.unwrap_or(launch_elevation);
+    let cloud_base =
+        |w: &WeatherData| ground + weather::cloud_base_agl_m(w.temperature, w.dew_point);
+    day.windows(OVERDEVELOPMENT_WINDOW_HOURS + 1)
+        .find(|hours| {
+            let (before, now) = (&hours[0], &hours[OVERDEVELOPMENT_WINDOW_HOURS]);
+            let growth = i16::from(now.cloud_cover) - i16::from(before.cloud_cover);
+            now.cape
+                .is_some_and(|cape| cape >= OVERDEVELOPMENT_CAPE_J_PER_KG)
+                && cloud_base(now) > launch_elevation
+                && cloud_base(now) <= cloud_base(before)
+                && growth >= OVERDEVELOPMENT_CLOUD_GROWTH_PCT
+        })
+        .map(|hours| hours[OVERDEVELOPMENT_WINDOW_HOURS].timestamp)
+}
+
+/// Where the model forecasts the freezing level, it decides whether flying gets
+/// sub-zero rather than the cloud base temperature estimated from the lapse
+/// rate.
+fn temperature_warnings(
+    temperatures: &AltitudeTemperatures,
+    freezing_level_m: Option<f64>,
+    boundary_layer_top_m: Option<f64>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let launch = Temperature::from_celsius(temperatures.launch_temperature);
+    if launch < COLD_LAUNCH_TEMPERATURE {
+        warnings.push(format!("Cold at launch ({launch}), dress warm"));
+    }
+    if let Some(freezing_level) = freezing_level_m {
+        // Thermals end at the top of the boundary layer or at cloud base,
+        // whichever is lower; only at cloud base can the glider ice up.
+        let cloud_base_m = temperatures.cloud_base_m;
+        let flying_altitude_m =
+            boundary_layer_top_m.map_or(cloud_base_m, |top| top.min(cloud_base_m));
+        if freezing_level < flying_altitude_m {
+            warnings.push(if flying_altitude_m >= cloud_base_m {
+                format!(
+                    "Freezing level at {freezing_level:.0} m, below cloud base at {cloud_base_m:.0} m: dress warm, risk of icing"
+                )
+            } else {
+                format!(
+                    "Freezing level at {freezing_level:.0} m, below the expected flying altitude of {flying_altitude_m:.0} m, dress warm"
+                )
+            });
+        }
+        return warnings;
     }
-    if start < stop {
-        start < wind_dir && wind_dir < stop
-    } else {
-        start < wind_dir || wind_dir < stop
+    let cloud_base = Temperature::from_celsius(temperatures.cloud_base_temperature);
+    if cloud_base < ICING_TEMPERATURE {
+        warnings.push(format!(
+            "Sub-zero near cloud base ({cloud_base} at {:.0} m), risk of icing",
+            temperatures.cloud_base_m
+        ));
     }
+    warnings
 }
 
+// Skill drops roughly linearly after the first couple of days.
+const FULL_CONFIDENCE_DAYS: i64 = 2;
+const CONFIDENCE_LOSS_PER_DAY: f32 = 0.1;
+const MIN_CONFIDENCE: f32 = 0.4;
+// Extended range days only show a tendency.
+const EXTENDED_RANGE_CONFIDENCE: f32 = 0.15;
+
+fn forecast_confidence(lead_days: i64, model: Option<&str>) -> f32 {
+    if model == Some(EXTENDED_RANGE_MODEL) {
+        return EXTENDED_RANGE_CONFIDENCE;
+    }
+    let days_past_full = (lead_days - FULL_CONFIDENCE_DAYS).max(0) as f32;
+    (1.0 - days_past_full * CONFIDENCE_LOSS_PER_DAY).max(MIN_CONFIDENCE)
+}
+
+/// Site rules that matter on this particular day.
+fn site_rule_warnings(site: &ParaglidingSite, date: NaiveDate) -> Vec<String> {
+    let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+    site.characteristics
+        .iter()
+        .flat_map(|c| c.rules())
+        .filter(|rule| *rule == SiteRule::NoWeekendFlying && weekend)
+        .map(|rule| rule.warning().to_string())
+        .collect()
+}
+
+/// Evaluates `site` with the default wind limits and every flyable run as a
+/// window of its own. See [`EvaluationCache`] to reuse the result while the
+/// forecast run stays the same.
 pub async fn evaluate_site(
     site: &ParaglidingSite,
     forecast: &WeatherForecast,
+) -> SiteEvaluationResult {
+    evaluate_site_with_limits(site, forecast, &WindLimits::default(), Hysteresis::NONE)
+}
+
+/// Like [`evaluate_site`], taken from `evaluations`, with the chances of
+/// flyable weather across the members of `ensemble`, if there is one and it
+/// answers.
+pub async fn evaluate_site_with_ensemble(
+    evaluations: &EvaluationCache,
+    site: &ParaglidingSite,
+    forecast: &WeatherForecast,
+    ensemble: Option<&dyn EnsembleProvider>,
+) -> SiteEvaluationResult {
+    let mut result = SiteEvaluationResult::clone(&evaluations.evaluate(site, forecast));
+    if let Some(ensemble) = ensemble {
+        match ensemble.get_ensemble(&forecast.location).await {
+            Ok(members) => add_flyable_probabilities(&mut result, site, &members),
+            Err(e) => {
+                tracing::warn!(site = %site.name, error = ?e, "Failed to get ensemble forecast")
+            }
+        }
+    }
+    result
+}
+
+/// Like [`evaluate_site`], with other wind limits than the defaults and
+/// flyable hours joined into windows by `hysteresis`.
+#[tracing::instrument(skip_all, fields(site = %site.name))]
+pub fn evaluate_site_with_limits(
+    site: &ParaglidingSite,
+    forecast: &WeatherForecast,
+    limits: &WindLimits,
+    hysteresis: Hysteresis,
 ) -> SiteEvaluationResult {
     let daily_forecasts = split_forecast_by_days(forecast.clone());
+    let first_date = forecast
+        .forecast
+        .iter()
+        .map(|w| w.timestamp.date_naive())
+        .min();
+    let launches: Vec<Launch> = site.launches.iter().map(core_launch).collect();
+    let thresholds = limits.thresholds();
+    let airspace = site
+        .characteristics
+        .iter()
+        .any(|c| c.rules().contains(&SiteRule::AirspaceRestriction));
     let mut daily_summaries = Vec::new();
 
     for daily_forecast in daily_forecasts {
@@ -131,26 +924,278 @@ pub async fn evaluate_site(
 
         let date = daily_forecast.forecast[0].timestamp.date_naive();
         let mut hourly_scores = Vec::new();
+        let mut alignments = Vec::new();
+        let mut tendencies = Vec::new();
+        let ground = forecast
+            .grid_elevation
+            .or_else(|| highest_launch_elevation(site));
 
         for weather_data in &daily_forecast.forecast {
-            let any_flyable = site
-                .launches
-                .iter()
-                .any(|launch| is_flyable(weather_data, launch));
+            let hour = hour(weather_data);
+            let best_alignment = score::best_alignment(&hour, &launches, &thresholds);
+            let any_flyable = best_alignment.is_some();
+            alignments.extend(best_alignment.map(|a| (weather_data.timestamp, a)));
+
+            let mut hour_hazards = hazards(weather_data);
+            let tendency = weather::pressure_tendency_hpa(&forecast.forecast, weather_data);
+            if tendency.is_some_and(|t| t <= -FRONTAL_PRESSURE_FALL_HPA) {
+                hour_hazards.push(Hazard::FrontalPassage);
+            }
+            if airspace {
+                hour_hazards.push(Hazard::Airspace);
+            }
+            tendencies.extend(tendency);
 
             hourly_scores.push(HourlyScore {
                 timestamp: weather_data.timestamp,
                 is_flyable: any_flyable,
+                hazards: hour_hazards,
+                limiting_factor: score::limiting_factor(&hour, &launches, &thresholds),
+                flyable_probability: None,
+                climb_ceiling_m: ground
+                    .zip(weather_data.boundary_layer_height_m)
+                    .map(|(ground, height)| ground + f64::from(height)),
+            });
+        }
+
+        let overdevelopment =
+            overdevelopment_from(site, &daily_forecast.forecast, forecast.grid_elevation);
+        let sunset = weather::get_sunrise_sunset(&forecast.location, date)
+            .ok()
+            .map(|(_, sunset)| sunset);
+        rule_out_hours(&mut hourly_scores, sunset, overdevelopment);
+        alignments.retain(|(timestamp, _)| {
+            hourly_scores
+                .iter()
+                .any(|h| h.timestamp == *timestamp && h.is_flyable)
+        });
+
+        let closure = site.closure_on(date);
+        if closure.is_some() {
+            hourly_scores.iter_mut().for_each(|h| {
+                h.is_flyable = false;
+                h.limiting_factor = Some(Factor::Closure);
             });
+            alignments.clear();
         }
 
         let mut daily_summary = calculate_daily_summary(date, hourly_scores);
-        daily_summary.calculate_flyable_time_ranges();
+        daily_summary.likely_snow_covered =
+            likely_snow_covered(site, &daily_forecast.forecast, &forecast.forecast);
+        daily_summary.altitude_temperatures =
+            altitude_temperatures(site, &daily_forecast.forecast, forecast.grid_elevation);
+        daily_summary.crowding =
+            crowding::estimate_crowding(site, date, daily_summary.total_flyable_hours);
+        daily_summary.boundary_layer_top_m =
+            boundary_layer_top_m(site, &daily_forecast.forecast, forecast.grid_elevation);
+        daily_summary.xc_possible = xc_possible(site, &daily_summary.hourly_scores);
+        if daily_summary.xc_possible == Some(false)
+            && let Some(launch) = highest_launch_elevation(site)
+        {
+            daily_summary.warnings.push(format!(
+                "Thermals stay below {:.0} m, too low to go cross-country",
+                launch + XC_MIN_CLIMB_ABOVE_LAUNCH_M
+            ));
+        }
+        daily_summary.pressure_tendency_hpa = tendencies.into_iter().reduce(f32::min);
+        daily_summary.precipitation_probability = daily_forecast
+            .forecast
+            .iter()
+            .filter_map(|h| h.precipitation_probability)
+            .max();
+        daily_summary.freezing_level_m = daily_forecast
+            .forecast
+            .iter()
+            .filter_map(|h| h.freezing_level_m)
+            .min_by(f32::total_cmp)
+            .map(f64::from);
+        daily_summary.stability_index = forecast.grid_elevation.and_then(|elevation| {
+            daily_forecast
+                .forecast
+                .iter()
+                .filter_map(|h| weather::stability_index(h, elevation))
+                .reduce(f32::min)
+        });
+        if let Some(temperatures) = &daily_summary.altitude_temperatures {
+            daily_summary.warnings.extend(temperature_warnings(
+                temperatures,
+                daily_summary.freezing_level_m,
+                daily_summary.boundary_layer_top_m,
+            ));
+        }
+        if let Some(warning) =
+            wind_shear_warning(site, &daily_forecast.forecast, forecast.grid_elevation)
+        {
+            daily_summary.strong_wind_shear = true;
+            daily_summary.warnings.push(warning);
+        }
+        daily_summary.overdevelopment_from = overdevelopment;
+        if let Some(from) = daily_summary.overdevelopment_from {
+            daily_summary.warnings.push(format!(
+                "Fly before {} UTC, overdevelopment expected after",
+                from.format("%H:%M")
+            ));
+        }
+        daily_summary
+            .warnings
+            .extend(site_rule_warnings(site, date));
+        if let Some(closure) = closure {
+            daily_summary.warnings.push(if closure.needs_review {
+                format!(
+                    "Site closed: {} (read from the site remarks, not reviewed)",
+                    closure.reason
+                )
+            } else {
+                format!("Site closed: {}", closure.reason)
+            });
+        }
+        daily_summary.source_model = daily_forecast.forecast[0].model.clone();
+        let lead_days = first_date.map_or(0, |first| (date - first).num_days());
+        daily_summary.confidence =
+            forecast_confidence(lead_days, daily_summary.source_model.as_deref())
+                * weather::data_completeness(&daily_forecast.forecast);
+        daily_summary.day_parts = day_parts(
+            &daily_summary.hourly_scores,
+            &alignments,
+            forecast.location.longitude,
+            daily_summary.confidence,
+        );
+        daily_summary.wind_alignment = mean(alignments.iter().map(|(_, a)| *a));
+        daily_summary.calculate_flyable_time_ranges(hysteresis);
         daily_summaries.push(daily_summary);
     }
 
     daily_summaries.sort_by_key(|d| d.date);
-    SiteEvaluationResult { daily_summaries }
+    SiteEvaluationResult {
+        daily_summaries,
+        field_availability: weather::field_availability(&forecast.forecast),
+        provenance: ScoreProvenance::new(&thresholds, hysteresis, forecast),
+        limits: *limits,
+    }
+}
+
+// Flyable hours a part of the day needs in an ensemble member to count as
+// flyable in it, or all of them in a shorter part.
+const ENSEMBLE_PART_HOURS: usize = 2;
+
+/// Adds to each hour and part of the day the share of ensemble `members`, one
+/// forecast per member, in which it is flyable with the wind limits `result`
+/// was scored against.
+/// Hours and parts the members don't cover are left without.
+pub fn add_flyable_probabilities(
+    result: &mut SiteEvaluationResult,
+    site: &ParaglidingSite,
+    members: &[WeatherForecast],
+) {
+    let Some(longitude) = members.first().map(|m| m.location.longitude) else {
+        return;
+    };
+    let launches: Vec<Launch> = site.launches.iter().map(core_launch).collect();
+    let thresholds = result.limits.thresholds();
+    let flyable: Vec<HashMap<DateTime<Utc>, bool>> = members
+        .iter()
+        .map(|member| {
+            member
+                .forecast
+                .iter()
+                .map(|w| {
+                    let flyable = score::best_alignment(&hour(w), &launches, &thresholds);
+                    (w.timestamp, flyable.is_some())
+                })
+                .collect()
+        })
+        .collect();
+    let share = |votes: Vec<bool>| {
+        (!votes.is_empty())
+            .then(|| votes.iter().filter(|v| **v).count() as f32 / votes.len() as f32)
+    };
+
+    for day in &mut result.daily_summaries {
+        // A closure rules a day out whatever the weather
+        let closed = day
+            .hourly_scores
+            .iter()
+            .any(|h| h.limiting_factor == Some(Factor::Closure));
+        for score in &mut day.hourly_scores {
+            let dark = score.limiting_factor == Some(Factor::Darkness);
+            let votes = flyable
+                .iter()
+                .filter_map(|m| m.get(&score.timestamp))
+                .map(|flyable| *flyable && !closed && !dark)
+                .collect();
+            score.flyable_probability = share(votes);
+        }
+        for part in &mut day.day_parts {
+            let hours: Vec<DateTime<Utc>> = day
+                .hourly_scores
+                .iter()
+                .map(|h| h.timestamp)
+                .filter(|t| DayPart::at(*t, longitude) == part.part)
+                .collect();
+            let needed = ENSEMBLE_PART_HOURS.min(hours.len());
+            let votes = flyable
+                .iter()
+                .filter(|m| !hours.is_empty() && hours.iter().all(|t| m.contains_key(t)))
+                .map(|m| !closed && hours.iter().filter(|t| m[*t]).count() >= needed)
+                .collect();
+            part.flyable_probability = share(votes);
+            part.rate();
+        }
+    }
+}
+
+// From here on the air is unstable enough for thunderstorms.
+pub const THUNDERSTORM_LIFTED_INDEX: f32 = -3.0;
+
+/// Adds to each day the lifted index and the height of usable lift of the
+/// `soundings` around midday, when thermals peak, and flags thunderstorms if
+/// the air is unstable enough. Days the soundings don't cover are left without.
+pub fn add_soundings(
+    result: &mut SiteEvaluationResult,
+    site: &ParaglidingSite,
+    soundings: &[Sounding],
+) {
+    let Some(launch) = site
+        .launches
+        .iter()
+        .max_by(|a, b| a.elevation.total_cmp(&b.elevation))
+    else {
+        return;
+    };
+    let longitude = launch.location.longitude;
+    for day in &mut result.daily_summaries {
+        let analyses: Vec<_> = soundings
+            .iter()
+            .filter(|s| {
+                s.timestamp.date_naive() == day.date
+                    && DayPart::at(s.timestamp, longitude) == DayPart::Midday
+            })
+            .map(Sounding::analyze)
+            .collect();
+        day.lifted_index = analyses
+            .iter()
+            .filter_map(|a| a.lifted_index)
+            .reduce(f32::min);
+        day.usable_lift_m = analyses
+            .iter()
+            .map(|a| {
+                let top = a
+                    .thermal_top_m
+                    .map_or(a.cloud_base_m, |t| t.min(a.cloud_base_m));
+                (top - launch.elevation).max(0.0)
+            })
+            .reduce(f64::max);
+        if let Some(index) = day.lifted_index
+            && index <= THUNDERSTORM_LIFTED_INDEX
+        {
+            day.warnings
+                .push(format!("Lifted index {index:.0}, thunderstorms likely"));
+            if !day.hazards.contains(&Hazard::Thunderstorm) {
+                day.hazards.push(Hazard::Thunderstorm);
+                day.hazards.sort();
+            }
+        }
+    }
 }
 
 fn split_forecast_by_days(forecast: WeatherForecast) -> Vec<WeatherForecast> {
@@ -176,6 +1221,9 @@ fn split_forecast_by_days(forecast: WeatherForecast) -> Vec<WeatherForecast> {
             } else {
                 Some(WeatherForecast {
                     location: forecast.location.clone(),
+                    grid_elevation: forecast.grid_elevation,
+                    fetched_at: None,
+                    provider: None,
                     forecast: filtered_data,
                 })
             }
@@ -183,14 +1231,75 @@ fn split_forecast_by_days(forecast: WeatherForecast) -> Vec<WeatherForecast> {
         .collect()
 }
 
+// A 24 hour fall of this much, scaled to sea level, usually means a front.
+const FRONTAL_PRESSURE_FALL_HPA: f32 = 6.0;
+// Fronts tend to arrive later than forecast said, so the afternoon is the
+// least certain part of such a day.
+const FRONTAL_AFTERNOON_CONFIDENCE_FACTOR: f32 = 0.5;
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+/// Flyable hours and rating per part of the day, each trusted as much as the
+/// day as a whole except for the afternoon of a day with a front coming in.
+/// `alignments` are the wind alignments of the flyable hours.
+fn day_parts(
+    hourly_scores: &[HourlyScore],
+    alignments: &[(DateTime<Utc>, f32)],
+    longitude: f64,
+    confidence: f32,
+) -> Vec<DayPartSummary> {
+    let front = hourly_scores
+        .iter()
+        .any(|h| h.hazards.contains(&Hazard::FrontalPassage));
+    DayPart::ALL
+        .iter()
+        .map(|&part| {
+            let hours: Vec<&HourlyScore> = hourly_scores
+                .iter()
+                .filter(|h| DayPart::at(h.timestamp, longitude) == part)
+                .collect();
+            let mut summary = DayPartSummary {
+                part,
+                flyable_hours: hours.iter().filter(|h| h.is_flyable).count(),
+                hours: hours.len(),
+                confidence: if front && part == DayPart::Afternoon {
+                    confidence * FRONTAL_AFTERNOON_CONFIDENCE_FACTOR
+                } else {
+                    confidence
+                },
+                flyable_probability: None,
+                wind_alignment: mean(
+                    alignments
+                        .iter()
+                        .filter(|(t, _)| DayPart::at(*t, longitude) == part)
+                        .map(|(_, a)| *a),
+                ),
+                rating: None,
+            };
+            summary.rate();
+            summary
+        })
+        .collect()
+}
+
 fn calculate_daily_summary(date: NaiveDate, hourly_scores: Vec<HourlyScore>) -> DailySummary {
     let total_flyable_hours = hourly_scores.iter().filter(|h| h.is_flyable).count();
+    let mut hazards: Vec<Hazard> = hourly_scores
+        .iter()
+        .flat_map(|h| h.hazards.iter().copied())
+        .collect();
+    hazards.sort();
+    hazards.dedup();
 
     DailySummary {
         date,
         hourly_scores,
         total_flyable_hours,
-        ranges: vec![],
+        hazards,
+        ..Default::default()
     }
 }
 
@@ -199,11 +1308,64 @@ mod tests {
     use super::*;
     use crate::domain::{
         location::Location,
-        paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType},
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, SiteType, characteristics::SiteCharacteristics,
+        },
+        sounding::SoundingLevel,
+        weather::WeatherField,
     };
     use chrono::{TimeZone, Timelike};
+    use proptest::prelude::*;
     use rstest::rstest;
 
+    proptest! {
+        #[test]
+        fn equal_sector_edges_accept_any_wind(wind in 0..360u16, edge in 0..360u16) {
+            prop_assert!(wind_direction_in_sector(
+                f64::from(wind),
+                f64::from(edge),
+                f64::from(edge)
+            ));
+        }
+
+        // A sector and its complement split the circle, apart from the shared edges.
+        #[test]
+        fn sector_and_complement_partition_the_circle(
+            wind in 0..360u16,
+            start in 0..360u16,
+            stop in 0..360u16,
+        ) {
+            prop_assume!(start != stop && wind != start && wind != stop);
+            let (wind, start, stop) = (f64::from(wind), f64::from(start), f64::from(stop));
+            prop_assert_ne!(
+                wind_direction_in_sector(wind, start, stop),
+                wind_direction_in_sector(wind, stop, start)
+            );
+        }
+
+        #[test]
+        fn sector_check_is_rotation_invariant(
+            wind in 0..360u16,
+            start in 0..360u16,
+            stop in 0..360u16,
+            rotation in 0..360u16,
+        ) {
+            let rotate = |deg: u16| f64::from((deg + rotation) % 360);
+            prop_assert_eq!(
+                wind_direction_in_sector(f64::from(wind), f64::from(start), f64::from(stop)),
+                wind_direction_in_sector(rotate(wind), rotate(start), rotate(stop))
+            );
+        }
+    }
+
+    fn is_flyable(weather: &WeatherData, launch: &ParaglidingLaunch, limits: &WindLimits) -> bool {
+        score::is_flyable(&hour(weather), &core_launch(launch), &limits.thresholds())
+    }
+
+    fn wind_alignment(weather: &WeatherData, launch: &ParaglidingLaunch) -> f32 {
+        score::wind_alignment(&hour(weather), &core_launch(launch), &Thresholds::default())
+    }
+
     fn loc(lat: f64, lon: f64) -> Location {
         Location::new(lat, lon, "Test".into(), "Test".into())
     }
@@ -222,13 +1384,8 @@ mod tests {
         ParaglidingSite {
             name: "Test Site".into(),
             launches,
-            landings: vec![],
-            country: None,
             data_source: "test".into(),
-            parking_location: None,
-            mute_alerts: None,
-            rating: None,
-            preferred_weather_model: None,
+            ..Default::default()
         }
     }
 
@@ -236,6 +1393,7 @@ mod tests {
         WeatherData {
             timestamp: ts,
             temperature: 20.0,
+            dew_point: 12.0,
             wind_speed_ms: 3.0,
             wind_direction: 135,
             wind_gust_ms: 5.0,
@@ -243,7 +1401,7 @@ mod tests {
             cloud_cover: 0,
             pressure: 1013.0,
             visibility: 10.0,
-            description: String::new(),
+            ..Default::default()
         }
     }
 
@@ -277,19 +1435,54 @@ mod tests {
     }
 
     #[rstest]
-    #[case(Duration::hours(1), Duration::hours(2), false)]
-    #[case(Duration::hours(2), Duration::hours(2), true)]
-    #[case(Duration::hours(3), Duration::hours(2), true)]
-    fn flyable_range_is_at_least_is_inclusive(
-        #[case] range_len: Duration,
-        #[case] threshold: Duration,
-        #[case] expected: bool,
-    ) {
+    #[case(0, 1)]
+    #[case(1, 2)]
+    #[case(3, 4)]
+    fn flyable_range_window_lasts_its_flyable_hours(#[case] last: u32, #[case] hours: u32) {
         let r = FlyableRange {
             start: ts(10),
-            end: ts(10) + range_len,
+            end: ts(10 + last),
         };
-        assert_eq!(r.is_at_least(threshold), expected);
+        assert_eq!(r.flyable_hours(), hours);
+        assert_eq!(r.window().duration(), Duration::hours(i64::from(hours)));
+    }
+
+    #[test]
+    fn calm_dry_hour_has_no_hazards() {
+        assert!(hazards(&weather(ts(12))).is_empty());
+    }
+
+    #[test]
+    fn hazards_are_flagged_independently() {
+        let mut w = weather(ts(12));
+        w.wind_gust_ms = MAX_GUST.ms() + 1.0;
+        w.precipitation = 0.4;
+        w.description = "Thunderstorm with slight hail".into();
+        w.dew_point = w.temperature - 1.0;
+        assert_eq!(
+            hazards(&w),
+            vec![
+                Hazard::Gusts,
+                Hazard::Thunderstorm,
+                Hazard::Rain,
+                Hazard::LowCloudbase
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(20.0, 180, true)]
+    #[case(20.0, 270, false)]
+    #[case(8.0, 180, false)]
+    fn foehn_needs_strong_southerly_flow_aloft(
+        #[case] speed_ms: f32,
+        #[case] direction: u16,
+        #[case] expected: bool,
+    ) {
+        let mut w = weather(ts(12));
+        w.wind_speed_850hpa_ms = Some(speed_ms);
+        w.wind_direction_850hpa = Some(direction);
+        assert_eq!(hazards(&w).contains(&Hazard::Foehn), expected);
     }
 
     #[test]
@@ -297,31 +1490,31 @@ mod tests {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
         w.wind_direction = 180;
-        w.wind_speed_ms = MAX_WIND_MS - 0.01;
-        w.wind_gust_ms = MAX_GUST_MS - 0.01;
-        assert!(is_flyable(&w, &l));
+        w.wind_speed_ms = MAX_WIND.ms() - 0.01;
+        w.wind_gust_ms = MAX_GUST.ms() - 0.01;
+        assert!(is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
     fn is_flyable_rejects_wind_speed_just_at_limit() {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
-        w.wind_speed_ms = MAX_WIND_MS;
-        assert!(!is_flyable(&w, &l));
+        w.wind_speed_ms = MAX_WIND.ms();
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
     fn is_flyable_rejects_wind_gust_just_at_limit() {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
-        w.wind_gust_ms = MAX_GUST_MS;
-        assert!(!is_flyable(&w, &l));
+        w.wind_gust_ms = MAX_GUST.ms();
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
     fn max_wind_ms_pins_kmh_to_ms_conversion() {
-        assert!((MAX_WIND_MS - 25.0 / 3.6).abs() < 1e-6);
-        assert!((MAX_GUST_MS - 40.0 / 3.6).abs() < 1e-6);
+        assert!((MAX_WIND.ms() - 25.0 / 3.6).abs() < 1e-6);
+        assert!((MAX_GUST.ms() - 40.0 / 3.6).abs() < 1e-6);
     }
 
     #[tokio::test]
@@ -332,6 +1525,9 @@ mod tests {
 
         let forecast = WeatherForecast {
             location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
             forecast: vec![
                 weather(day + chrono::Duration::hours(1)),
                 weather(day + chrono::Duration::hours(12)),
@@ -358,7 +1554,7 @@ mod tests {
     fn is_flyable_winch_site_never_flyable() {
         let l = launch(0.0, 360.0, SiteType::Winch);
         let w = weather(ts(12));
-        assert!(!is_flyable(&w, &l));
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
@@ -366,23 +1562,23 @@ mod tests {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
         w.precipitation = 0.1;
-        assert!(!is_flyable(&w, &l));
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
     fn is_flyable_rejects_wind_at_limit() {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
-        w.wind_speed_ms = MAX_WIND_MS;
-        assert!(!is_flyable(&w, &l));
+        w.wind_speed_ms = MAX_WIND.ms();
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
     fn is_flyable_rejects_gust_at_limit() {
         let l = launch(0.0, 360.0, SiteType::Hang);
         let mut w = weather(ts(12));
-        w.wind_gust_ms = MAX_GUST_MS;
-        assert!(!is_flyable(&w, &l));
+        w.wind_gust_ms = MAX_GUST.ms();
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
@@ -390,7 +1586,7 @@ mod tests {
         let l = launch(90.0, 180.0, SiteType::Hang);
         let mut w = weather(ts(12));
         w.wind_direction = 45;
-        assert!(!is_flyable(&w, &l));
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
     }
 
     #[test]
@@ -401,13 +1597,38 @@ mod tests {
         w.wind_speed_ms = 3.0;
         w.wind_gust_ms = 5.0;
         w.precipitation = 0.0;
-        assert!(is_flyable(&w, &l));
+        assert!(is_flyable(&w, &l, &WindLimits::default()));
+    }
+
+    #[test]
+    fn is_flyable_rejects_strong_crosswind_inside_sector() {
+        let l = launch(90.0, 180.0, SiteType::Hang);
+        let mut w = weather(ts(12));
+        w.wind_speed_ms = WindSpeed::from_kmh(20.0).ms();
+        w.wind_direction = 135;
+        assert!(is_flyable(&w, &l, &WindLimits::default()));
+        w.wind_direction = 175;
+        assert!(!is_flyable(&w, &l, &WindLimits::default()));
+    }
+
+    #[test]
+    fn crosswind_lowers_wind_alignment() {
+        let l = launch(225.0, 315.0, SiteType::Hang);
+        let mut w = weather(ts(12));
+        w.wind_speed_ms = WindSpeed::from_kmh(15.0).ms();
+        w.wind_direction = 270;
+        assert_eq!(wind_alignment(&w, &l), 1.0);
+        // 15 km/h with 5 km/h across
+        w.wind_direction = 290;
+        assert!((wind_alignment(&w, &l) - 0.57).abs() < 0.01);
+        assert_eq!(wind_alignment(&w, &launch(0.0, 360.0, SiteType::Hang)), 1.0);
     }
 
     fn hourly(hour: u32, is_flyable: bool) -> HourlyScore {
         HourlyScore {
             timestamp: ts(hour),
             is_flyable,
+            ..Default::default()
         }
     }
 
@@ -415,22 +1636,231 @@ mod tests {
         DailySummary {
             date: ts(0).date_naive(),
             hourly_scores: scores,
-            ranges: vec![],
             total_flyable_hours: 0,
+            ..Default::default()
+        }
+    }
+
+    fn mountain_site(winter_operable: Option<bool>) -> ParaglidingSite {
+        let mut l = launch(0.0, 360.0, SiteType::Hang);
+        l.elevation = 1800.0;
+        let mut s = site(vec![l]);
+        s.winter_operable = winter_operable;
+        s
+    }
+
+    #[test]
+    fn snow_depth_flags_mountain_launch() {
+        let mut w = weather(ts(12));
+        w.snow_depth_m = 0.3;
+        let day = vec![w];
+        assert!(likely_snow_covered(&mountain_site(None), &day, &day));
+    }
+
+    #[test]
+    fn overnight_snowfall_flags_mountain_launch() {
+        let mut night = weather(ts(2));
+        night.snowfall_cm = 8.0;
+        let day = vec![weather(ts(12))];
+        let full = vec![night, weather(ts(12))];
+        assert!(likely_snow_covered(&mountain_site(None), &day, &full));
+    }
+
+    #[test]
+    fn winter_operable_site_is_never_flagged() {
+        let mut w = weather(ts(12));
+        w.snow_depth_m = 0.3;
+        let day = vec![w];
+        assert!(!likely_snow_covered(&mountain_site(Some(true)), &day, &day));
+    }
+
+    #[test]
+    fn low_launch_is_not_flagged_for_snow() {
+        let mut w = weather(ts(12));
+        w.snow_depth_m = 0.3;
+        let day = vec![w];
+        let s = site(vec![launch(0.0, 360.0, SiteType::Hang)]);
+        assert!(!likely_snow_covered(&s, &day, &day));
+    }
+
+    #[test]
+    fn altitude_temperatures_shift_grid_temperature_to_launch() {
+        let mut l = launch(0.0, 360.0, SiteType::Hang);
+        l.elevation = 1500.0;
+        let day = vec![weather(ts(12))];
+        let t = altitude_temperatures(&site(vec![l]), &day, Some(500.0)).unwrap();
+        // 1000 m above the grid cell at 6.5 K/km.
+        assert!(
+            (t.launch_temperature - 13.5).abs() < 1e-4,
+            "got {}",
+            t.launch_temperature
+        );
+        // 8 K spread -> 1000 m above the grid cell, cooled at 9.8 K/km.
+        assert!((t.cloud_base_m - 1500.0).abs() < 1e-6);
+        assert!((t.cloud_base_temperature - 10.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn altitude_temperatures_use_the_warmest_cloud_base_hour() {
+        let mut morning = weather(ts(8));
+        morning.temperature = 10.0;
+        morning.dew_point = 9.0;
+        let day = vec![morning, weather(ts(14))];
+        let s = site(vec![launch(0.0, 360.0, SiteType::Hang)]);
+        let t = altitude_temperatures(&s, &day, None).unwrap();
+        assert!((t.cloud_base_m - 1500.0).abs() < 1e-6);
+        assert!((t.launch_temperature - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn boundary_layer_top_is_daily_maximum_above_grid() {
+        let mut morning = weather(ts(9));
+        morning.boundary_layer_height_m = Some(400.0);
+        let mut afternoon = weather(ts(15));
+        afternoon.boundary_layer_height_m = Some(1600.0);
+        let day = vec![morning, afternoon, weather(ts(18))];
+        let s = site(vec![launch(0.0, 360.0, SiteType::Hang)]);
+
+        assert_eq!(boundary_layer_top_m(&s, &day, Some(300.0)), Some(1900.0));
+        assert_eq!(boundary_layer_top_m(&s, &day, None), Some(2100.0));
+        assert_eq!(
+            boundary_layer_top_m(&s, &[weather(ts(12))], Some(300.0)),
+            None
+        );
+    }
+
+    #[rstest]
+    // The launch is at 500 m, so cross-country needs a ceiling of 800 m
+    #[case::high_enough(&[(true, Some(700.0)), (true, Some(850.0))], Some(true))]
+    #[case::only_high_while_unflyable(&[(true, Some(700.0)), (false, Some(1500.0))], Some(false))]
+    #[case::no_ceiling(&[(true, None)], None)]
+    fn xc_needs_a_flyable_hour_climbing_300_m_above_launch(
+        #[case] hours: &[(bool, Option<f64>)],
+        #[case] expected: Option<bool>,
+    ) {
+        let s = site(vec![launch(0.0, 360.0, SiteType::Hang)]);
+        let hours: Vec<HourlyScore> = hours
+            .iter()
+            .map(|(is_flyable, climb_ceiling_m)| HourlyScore {
+                climb_ceiling_m: *climb_ceiling_m,
+                ..hourly(12, *is_flyable)
+            })
+            .collect();
+        assert_eq!(xc_possible(&s, &hours), expected);
+    }
+
+    #[test]
+    fn altitude_temperatures_need_a_launch() {
+        assert!(altitude_temperatures(&site(vec![]), &[weather(ts(12))], None).is_none());
+    }
+
+    #[test]
+    fn wind_shear_warns_only_above_threshold() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let mut calm = weather(ts(10));
+        calm.wind_speed_850hpa_ms = Some(6.0);
+        calm.wind_direction_850hpa = Some(135);
+        assert!(wind_shear_warning(&s, &[calm.clone()], Some(0.0)).is_none());
+
+        let mut sheared = weather(ts(14));
+        sheared.wind_speed_850hpa_ms = Some(10.0);
+        sheared.wind_direction_850hpa = Some(315);
+        let warning = wind_shear_warning(&s, &[calm, sheared], Some(0.0)).unwrap();
+        assert!(warning.contains("NW"), "{warning}");
+        assert!(warning.contains("14:00"), "{warning}");
+    }
+
+    #[test]
+    fn wind_shear_needs_altitude_wind() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        assert!(wind_shear_warning(&s, &[weather(ts(12))], Some(0.0)).is_none());
+    }
+
+    #[rstest]
+    // 500 m launch over a 0 m valley: only shear of 8 m/s counts.
+    #[case(0.0, false)]
+    // 150 m above the valley, a small hill the gradient wind reaches.
+    #[case(350.0, true)]
+    fn wind_shear_near_the_ground_hits_small_hills_sooner(
+        #[case] grid_elevation: f64,
+        #[case] warned: bool,
+    ) {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let mut hour = weather(ts(12));
+        // 3 m/s at 10 m, 9 m/s from the same side at 80 m.
+        hour.wind_speed_80m_ms = Some(9.0);
+        hour.wind_direction_80m = Some(135);
+        let warning = wind_shear_warning(&s, &[hour], Some(grid_elevation));
+        assert_eq!(warning.is_some(), warned, "{warning:?}");
+        if let Some(warning) = warning {
+            assert!(warning.contains("at 80 m vs"), "{warning}");
         }
     }
 
+    #[rstest]
+    #[case(15.0, 5.0, 0)]
+    #[case(2.0, 5.0, 1)]
+    #[case(15.0, -3.0, 1)]
+    #[case(-2.0, -8.0, 2)]
+    fn temperature_warnings_cases(
+        #[case] launch_temperature: f32,
+        #[case] cloud_base_temperature: f32,
+        #[case] expected: usize,
+    ) {
+        let warnings = temperature_warnings(
+            &AltitudeTemperatures {
+                launch_temperature,
+                cloud_base_m: 2500.0,
+                cloud_base_temperature,
+            },
+            None,
+            None,
+        );
+        assert_eq!(warnings.len(), expected, "{warnings:?}");
+    }
+
+    #[rstest]
+    #[case(3000.0, None, None)]
+    #[case(
+        2000.0,
+        None,
+        Some("Freezing level at 2000 m, below cloud base at 2500 m: dress warm, risk of icing")
+    )]
+    #[case(
+        2000.0,
+        Some(2200.0),
+        Some("Freezing level at 2000 m, below the expected flying altitude of 2200 m, dress warm")
+    )]
+    #[case(2300.0, Some(2200.0), None)]
+    fn forecast_freezing_level_replaces_the_estimated_cloud_base_temperature(
+        #[case] freezing_level_m: f64,
+        #[case] boundary_layer_top_m: Option<f64>,
+        #[case] expected: Option<&str>,
+    ) {
+        let warnings = temperature_warnings(
+            &AltitudeTemperatures {
+                launch_temperature: 12.0,
+                cloud_base_m: 2500.0,
+                // Estimated sub-zero, overruled by the forecast freezing level
+                cloud_base_temperature: -3.0,
+            },
+            Some(freezing_level_m),
+            boundary_layer_top_m,
+        );
+        assert_eq!(warnings, expected.into_iter().collect::<Vec<_>>());
+    }
+
     #[test]
     fn all_unflyable_produces_no_ranges() {
         let mut s = summary((6..20).map(|h| hourly(h, false)).collect());
-        s.calculate_flyable_time_ranges();
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
         assert!(s.ranges.is_empty());
     }
 
     #[test]
     fn single_flyable_hour_produces_one_range() {
         let mut s = summary(vec![hourly(10, true)]);
-        s.calculate_flyable_time_ranges();
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
         assert_eq!(s.ranges.len(), 1);
         assert_eq!(s.ranges[0].start, ts(10));
         assert_eq!(s.ranges[0].end, ts(10));
@@ -439,7 +1869,7 @@ mod tests {
     #[test]
     fn consecutive_flyable_hours_collapse_into_one_range() {
         let mut s = summary(vec![hourly(10, true), hourly(11, true), hourly(12, true)]);
-        s.calculate_flyable_time_ranges();
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
         assert_eq!(s.ranges.len(), 1);
         assert_eq!(s.ranges[0].start, ts(10));
         assert_eq!(s.ranges[0].end, ts(12));
@@ -454,19 +1884,120 @@ mod tests {
             hourly(13, true),
             hourly(14, true),
         ]);
-        s.calculate_flyable_time_ranges();
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
         assert_eq!(s.ranges.len(), 2);
         assert_eq!((s.ranges[0].start, s.ranges[0].end), (ts(10), ts(11)));
         assert_eq!((s.ranges[1].start, s.ranges[1].end), (ts(13), ts(14)));
     }
 
+    #[rstest]
+    // Gusts hovering around the limit: 1 means flyable, 0 unflyable from 8:00
+    #[case(Hysteresis::NONE, "1101101100", &[(8, 9), (11, 12), (14, 15)])]
+    #[case(Hysteresis { open_hours: 1, close_hours: 2 }, "1101101100", &[(8, 15)])]
+    #[case(Hysteresis { open_hours: 2, close_hours: 2 }, "1011011000", &[(10, 14)])]
+    #[case(Hysteresis { open_hours: 2, close_hours: 2 }, "1100110000", &[(8, 9), (12, 13)])]
+    #[case(Hysteresis { open_hours: 3, close_hours: 1 }, "1101101111", &[(14, 17)])]
+    fn hysteresis_joins_windows_and_drops_scraps(
+        #[case] hysteresis: Hysteresis,
+        #[case] flyable: &str,
+        #[case] expected: &[(u32, u32)],
+    ) {
+        let mut s = summary(
+            flyable
+                .chars()
+                .zip(8..)
+                .map(|(c, h)| hourly(h, c == '1'))
+                .collect(),
+        );
+        s.calculate_flyable_time_ranges(hysteresis);
+        let ranges: Vec<(u32, u32)> = s
+            .ranges
+            .iter()
+            .map(|r| (r.start.hour(), r.end.hour()))
+            .collect();
+        assert_eq!(ranges, expected);
+    }
+
     #[test]
     fn non_consecutive_flyable_timestamps_produce_separate_ranges() {
         let mut s = summary(vec![hourly(10, true), hourly(13, true)]);
-        s.calculate_flyable_time_ranges();
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
         assert_eq!(s.ranges.len(), 2);
     }
 
+    #[rstest]
+    #[case(8, 0.0, DayPart::Morning)]
+    #[case(11, 0.0, DayPart::Midday)]
+    #[case(15, 0.0, DayPart::Afternoon)]
+    // 13:00 UTC is mid-afternoon in solar time 45 degrees east.
+    #[case(13, 45.0, DayPart::Afternoon)]
+    #[case(13, -30.0, DayPart::Midday)]
+    fn day_part_follows_solar_time(
+        #[case] hour: u32,
+        #[case] longitude: f64,
+        #[case] expected: DayPart,
+    ) {
+        assert_eq!(DayPart::at(ts(hour), longitude), expected);
+    }
+
+    #[test]
+    fn flyable_day_parts_only_for_partly_flyable_days() {
+        let mut s = summary((6..20).map(|h| hourly(h, h < 10)).collect());
+        s.day_parts = day_parts(&s.hourly_scores, &[], 0.0, 1.0);
+        assert_eq!(s.day_parts[0].flyable_hours, 4);
+        assert_eq!(s.flyable_day_parts(), Some(vec![DayPart::Morning]));
+
+        let all_day: Vec<HourlyScore> = (6..20).map(|h| hourly(h, true)).collect();
+        s.day_parts = day_parts(&all_day, &[], 0.0, 1.0);
+        assert_eq!(s.flyable_day_parts(), None);
+    }
+
+    #[test]
+    fn day_parts_are_rated_on_their_own() {
+        // An east face: wind straight into launch in the morning, across it later.
+        let hours: Vec<HourlyScore> = (6..20).map(|h| hourly(h, h < 18)).collect();
+        let alignments: Vec<(DateTime<Utc>, f32)> = (6..18)
+            .map(|h| (ts(h), if h < 11 { 1.0 } else { 0.6 }))
+            .collect();
+        let mut s = summary(hours.clone());
+        s.day_parts = day_parts(&hours, &alignments, 0.0, 1.0);
+
+        let rating = |part| s.day_parts.iter().find(|p| p.part == part).unwrap().rating;
+        assert_eq!(rating(DayPart::Morning), Some(DayRating::Excellent));
+        assert_eq!(rating(DayPart::Midday), Some(DayRating::Good));
+        assert_eq!(rating(DayPart::Afternoon), Some(DayRating::Fair));
+        assert_eq!(
+            s.best_day_parts(),
+            Some((vec![DayPart::Morning], DayRating::Excellent))
+        );
+
+        s.day_parts = day_parts(&hours, &[], 0.0, 1.0);
+        assert_eq!(
+            s.best_day_parts(),
+            Some((
+                vec![DayPart::Morning, DayPart::Midday],
+                DayRating::Excellent
+            ))
+        );
+        assert_eq!(day_parts(&[], &[], 0.0, 1.0)[0].rating, None);
+    }
+
+    #[rstest]
+    #[case(&[10], 1, true)]
+    #[case(&[10], 2, false)]
+    #[case(&[8, 12, 15], 2, false)]
+    #[case(&[8, 12, 13], 2, true)]
+    #[case(&[], 0, false)]
+    fn is_flyable_day_needs_a_long_enough_window(
+        #[case] flyable: &[u32],
+        #[case] min_hours: u32,
+        #[case] expected: bool,
+    ) {
+        let mut s = summary(flyable.iter().map(|&h| hourly(h, true)).collect());
+        s.calculate_flyable_time_ranges(Hysteresis::NONE);
+        assert_eq!(s.is_flyable_day(min_hours), expected);
+    }
+
     #[tokio::test]
     async fn evaluate_site_emits_single_range_for_contiguous_flyable_window() {
         let l = launch(90.0, 180.0, SiteType::Hang);
@@ -474,6 +2005,9 @@ mod tests {
 
         let forecast = WeatherForecast {
             location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
             forecast: (4..22)
                 .map(|h| {
                     let mut w = weather(ts(h));
@@ -490,4 +2024,368 @@ mod tests {
         assert_eq!(day.ranges[0].start, ts(10));
         assert_eq!(day.ranges[0].end, ts(14));
     }
+
+    #[tokio::test]
+    async fn low_thermals_warning_survives_the_temperature_warnings() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let forecast = WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: Some(300.0),
+            fetched_at: None,
+            provider: None,
+            forecast: (4..22)
+                .map(|h| WeatherData {
+                    boundary_layer_height_m: Some(200.0),
+                    ..weather(ts(h))
+                })
+                .collect(),
+        };
+
+        let result = evaluate_site(&s, &forecast).await;
+
+        let day = &result.daily_summaries[0];
+        assert!(day.altitude_temperatures.is_some());
+        assert_eq!(day.xc_possible, Some(false));
+        assert!(
+            day.warnings
+                .iter()
+                .any(|w| w == "Thermals stay below 800 m, too low to go cross-country"),
+            "{:?}",
+            day.warnings
+        );
+    }
+
+    #[rstest]
+    #[case::spreading_cumulus(1000.0, 8.0, Some(13))]
+    #[case::little_cape(200.0, 8.0, None)]
+    #[case::base_below_launch(1000.0, 19.0, None)]
+    fn overdevelopment_needs_cape_spreading_cloud_and_a_base_above_launch(
+        #[case] cape: f32,
+        #[case] dew_point: f32,
+        #[case] expected: Option<u32>,
+    ) {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let day: Vec<WeatherData> = [(10, 10), (11, 15), (12, 25), (13, 50), (14, 90)]
+            .into_iter()
+            .map(|(h, cloud_cover)| WeatherData {
+                cloud_cover,
+                cape: Some(cape),
+                dew_point,
+                ..weather(ts(h))
+            })
+            .collect();
+        assert_eq!(
+            overdevelopment_from(&s, &day, Some(300.0)),
+            expected.map(ts)
+        );
+    }
+
+    #[test]
+    fn darkness_and_overdevelopment_rule_out_hours() {
+        let mut hours: Vec<HourlyScore> = [(12, true), (13, false), (14, true), (19, true)]
+            .into_iter()
+            .map(|(h, is_flyable)| HourlyScore {
+                timestamp: ts(h),
+                is_flyable,
+                limiting_factor: (!is_flyable).then_some(Factor::Rain),
+                ..Default::default()
+            })
+            .collect();
+
+        rule_out_hours(
+            &mut hours,
+            Some(ts(19) + Duration::minutes(20)),
+            Some(ts(13)),
+        );
+
+        let factors: Vec<Option<Factor>> = hours.iter().map(|h| h.limiting_factor).collect();
+        assert_eq!(
+            factors,
+            [
+                None,
+                Some(Factor::Rain),
+                Some(Factor::Overdevelopment),
+                Some(Factor::Darkness)
+            ]
+        );
+        assert_eq!(hours.iter().filter(|h| h.is_flyable).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn falling_pressure_warns_of_a_front_and_lowers_afternoon_confidence() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let start = ts(0);
+        let forecast = WeatherForecast {
+            location: loc(50.0, 0.0),
+            grid_elevation: Some(500.0),
+            fetched_at: None,
+            provider: None,
+            forecast: (0..48)
+                .map(|h| WeatherData {
+                    // Steady for a day, then falling 1 hPa an hour.
+                    pressure: 1013.0 - (h - 24).max(0) as f32,
+                    temperature_850hpa: Some(10.0),
+                    height_850hpa_m: Some(1500.0),
+                    ..weather(start + Duration::hours(h.into()))
+                })
+                .collect(),
+        };
+
+        let result = evaluate_site(&s, &forecast).await;
+        let [first, second] = &result.daily_summaries[..] else {
+            panic!("expected two days");
+        };
+        assert!(!first.hazards.contains(&Hazard::FrontalPassage));
+        assert!(second.hazards.contains(&Hazard::FrontalPassage));
+        assert!(second.pressure_tendency_hpa.unwrap() < -FRONTAL_PRESSURE_FALL_HPA);
+        // 20 °C lifted from 500 m to 1500 m is 10.2 °C, just warmer than the 10 °C there.
+        assert!((second.stability_index.unwrap() - -0.2).abs() < 1e-4);
+
+        let confidence = |part| {
+            second
+                .day_parts
+                .iter()
+                .find(|p| p.part == part)
+                .unwrap()
+                .confidence
+        };
+        assert_eq!(
+            confidence(DayPart::Afternoon),
+            confidence(DayPart::Morning) * FRONTAL_AFTERNOON_CONFIDENCE_FACTOR
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_fields_lower_confidence_and_show_in_availability() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let forecast = WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: (4..22)
+                .map(|h| WeatherData {
+                    missing: vec![WeatherField::WindGust, WeatherField::Visibility],
+                    ..weather(ts(h))
+                })
+                .collect(),
+        };
+
+        let result = evaluate_site(&s, &forecast).await;
+        let confidence = result.daily_summaries[0].confidence;
+        assert!((confidence - 7.0 / 9.0).abs() < 1e-6, "got {confidence}");
+        let gusts = result
+            .field_availability
+            .iter()
+            .find(|a| a.field == WeatherField::WindGust)
+            .unwrap();
+        assert_eq!((gusts.available_hours, gusts.hours), (0, 18));
+    }
+
+    #[tokio::test]
+    async fn closed_days_are_never_flyable_and_say_why() {
+        let mut s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        s.closures = vec!["06-01..06-30 raptor nesting".parse().unwrap()];
+        let forecast = WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: (4..22).map(|h| weather(ts(h))).collect(),
+        };
+
+        let result = evaluate_site(&s, &forecast).await;
+        let day = &result.daily_summaries[0];
+        assert_eq!(day.total_flyable_hours, 0);
+        assert!(day.ranges.is_empty());
+        assert!(
+            day.hourly_scores
+                .iter()
+                .all(|h| h.limiting_factor == Some(Factor::Closure))
+        );
+        assert!(
+            day.warnings
+                .contains(&"Site closed: raptor nesting".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn airspace_in_the_flight_rules_is_a_hazard() {
+        let mut s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        s.characteristics = Some(SiteCharacteristics {
+            flight_rules: Some("Stay out of the control zone of the airport".into()),
+            ..Default::default()
+        });
+        let forecast = WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: (4..22).map(|h| weather(ts(h))).collect(),
+        };
+
+        let result = evaluate_site(&s, &forecast).await;
+        let day = &result.daily_summaries[0];
+        assert!(day.total_flyable_hours > 0);
+        assert!(day.hazards.contains(&Hazard::Airspace));
+        assert!(
+            day.hourly_scores
+                .iter()
+                .all(|h| h.hazards.contains(&Hazard::Airspace))
+        );
+    }
+
+    #[test]
+    fn provenance_names_provider_and_models_and_changes_with_the_thresholds() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let forecast = WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: Some("open_meteo".into()),
+            forecast: (4..22)
+                .map(|h| WeatherData {
+                    model: (h >= 12).then(|| "icon_d2".to_string()),
+                    ..weather(ts(h))
+                })
+                .collect(),
+        };
+
+        let default =
+            evaluate_site_with_limits(&s, &forecast, &WindLimits::default(), Hysteresis::NONE)
+                .provenance;
+        assert_eq!(default.scorer_version, SCORER_VERSION);
+        assert_eq!(default.provider.as_deref(), Some("open_meteo"));
+        assert_eq!(default.models, vec![DEFAULT_MODEL, "icon_d2"]);
+
+        let strict = WindLimits {
+            max_wind: WindSpeed::from_kmh(15.0),
+            ..WindLimits::default()
+        };
+        let strict = evaluate_site_with_limits(&s, &forecast, &strict, Hysteresis::NONE).provenance;
+        assert!(strict.scoring_differs(&default));
+        assert!(!default.scoring_differs(&default.clone()));
+
+        let bridged = Hysteresis {
+            open_hours: 2,
+            close_hours: 2,
+        };
+        let bridged =
+            evaluate_site_with_limits(&s, &forecast, &WindLimits::default(), bridged).provenance;
+        assert!(bridged.scoring_differs(&default));
+    }
+
+    #[test]
+    fn ensemble_members_are_scored_against_the_limits_of_the_result() {
+        let s = site(vec![launch(90.0, 180.0, SiteType::Hang)]);
+        let member = |wind_ms: f32| WeatherForecast {
+            location: loc(50.0, 13.0),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: (4..22)
+                .map(|h| WeatherData {
+                    wind_speed_ms: wind_ms,
+                    ..weather(ts(h))
+                })
+                .collect(),
+        };
+        // 7 km/h stays below the strict limit, 11 km/h doesn't
+        let members = [member(2.0), member(3.0)];
+        let strict = WindLimits {
+            max_wind: WindSpeed::from_kmh(10.0),
+            ..WindLimits::default()
+        };
+
+        let mut result = evaluate_site_with_limits(&s, &members[1], &strict, Hysteresis::NONE);
+        add_flyable_probabilities(&mut result, &s, &members);
+
+        let noon = result.daily_summaries[0]
+            .hourly_scores
+            .iter()
+            .find(|h| h.timestamp == ts(12))
+            .unwrap();
+        assert!(!noon.is_flyable);
+        assert_eq!(noon.flyable_probability, Some(0.5));
+    }
+
+    #[rstest]
+    // 2026-06-13 is a Saturday, 2026-06-10 a Wednesday.
+    #[case(13, 1)]
+    #[case(10, 0)]
+    fn no_weekend_flying_rule_warns_on_weekends(#[case] day: u32, #[case] expected: usize) {
+        let mut s = site(vec![]);
+        s.characteristics = Some(SiteCharacteristics {
+            flight_rules: Some("No weekend flying from May to September".into()),
+            ..Default::default()
+        });
+        let date = NaiveDate::from_ymd_opt(2026, 6, day).unwrap();
+        assert_eq!(site_rule_warnings(&s, date).len(), expected);
+    }
+
+    #[rstest]
+    #[case(0, None, 1.0)]
+    #[case(2, Some("icon"), 1.0)]
+    #[case(4, None, 0.8)]
+    #[case(12, None, MIN_CONFIDENCE)]
+    #[case(8, Some(EXTENDED_RANGE_MODEL), EXTENDED_RANGE_CONFIDENCE)]
+    fn confidence_drops_with_lead_time(
+        #[case] lead_days: i64,
+        #[case] model: Option<&str>,
+        #[case] expected: f32,
+    ) {
+        let confidence = forecast_confidence(lead_days, model);
+        assert!((confidence - expected).abs() < 1e-6, "got {confidence}");
+    }
+
+    fn sounding_at(hour: u32, temperature_500hpa: f32) -> Sounding {
+        let level = |pressure_hpa, height_m, temperature| SoundingLevel {
+            pressure_hpa,
+            height_m,
+            temperature,
+            dew_point: temperature - 10.0,
+            wind_speed_ms: 5.0,
+            wind_direction: 270,
+        };
+        Sounding {
+            timestamp: ts(hour),
+            surface_elevation_m: 300.0,
+            surface_temperature: 24.0,
+            surface_dew_point: 14.0,
+            surface_wind_speed_ms: 2.0,
+            surface_wind_direction: 270,
+            levels: vec![
+                level(850.0, 1500.0, 10.0),
+                level(700.0, 3100.0, 4.0),
+                level(500.0, 5700.0, temperature_500hpa),
+            ],
+        }
+    }
+
+    #[test]
+    fn midday_soundings_set_lift_and_flag_thunderstorms() {
+        let site = site(vec![launch(0.0, 360.0, SiteType::Hang)]);
+        let mut result = SiteEvaluationResult {
+            daily_summaries: vec![summary(vec![])],
+            field_availability: vec![],
+            provenance: ScoreProvenance::default(),
+            limits: WindLimits::default(),
+        };
+        // The 06 UTC sounding is morning at 13° E and doesn't count
+        let soundings = [
+            sounding_at(6, -40.0),
+            sounding_at(12, -25.0),
+            sounding_at(13, -10.0),
+        ];
+
+        add_soundings(&mut result, &site, &soundings);
+
+        let day = &result.daily_summaries[0];
+        assert!(day.lifted_index.unwrap() <= THUNDERSTORM_LIFTED_INDEX);
+        assert!(day.lifted_index.unwrap() > -15.0);
+        // Cloud base 1250 m above the surface, 1050 m above the launch
+        assert_eq!(day.usable_lift_m, Some(1050.0));
+        assert_eq!(day.hazards, [Hazard::Thunderstorm]);
+        assert_eq!(day.warnings.len(), 1);
+    }
 }
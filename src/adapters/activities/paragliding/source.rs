@@ -2,34 +2,99 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Duration;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
 
 use crate::{
-    adapters::activities::paragliding::{repository::ParaglidingSiteRepository, site_evaluator},
+    adapters::activities::paragliding::{
+        evaluation_cache::EvaluationCache,
+        history::{self, ForecastHistory, ForecastSnapshot},
+        outlook,
+        repository::ParaglidingSiteRepository,
+        site_evaluator::{self, DailySummary, DayPart, SiteEvaluationResult},
+    },
     domain::{
-        activities::{ActivityKind, ActivitySuggestion, PlanningContext, TimeWindow, Timing},
-        paragliding::ParaglidingSiteProvider,
-        ports::{ActivitySource, WeatherProvider},
+        activities::{
+            ActivityKind, ActivitySuggestion, DayRating, Factor, PlanningContext, Score,
+            Suggestions, Timing,
+        },
+        paragliding::{ParaglidingSite, ParaglidingSiteProvider, crowding::Crowding},
+        ports::{ActivitySource, EnsembleProvider, SoundingProvider, WeatherProvider},
+        weather::{EXTENDED_RANGE_MODEL, WeatherForecast},
     },
 };
 
+// Compare against a run from roughly a day earlier rather than the run a few hours
+// ago, which shares most of its input data with the current one.
+const PREVIOUS_RUN_MIN_AGE: Duration = Duration::hours(20);
+const SNOW_COVER_PENALTY: f32 = 0.3;
+/// Chance of rain from which the description mentions it, in percent
+const NOTABLE_RAIN_CHANCE: u8 = 30;
+// All factors count the same for now.
+const FACTOR_WEIGHT: f32 = 1.0;
+/// Lift above launch from which thermals don't limit a day, in meters
+const GOOD_LIFT_M: f64 = 1500.0;
+const MIN_LIFT_SCORE: f32 = 0.3;
+/// Lifted index from which instability starts to cost, and the one at which
+/// it costs the most
+const UNSTABLE_LIFTED_INDEX: f32 = -2.0;
+const SEVERE_LIFTED_INDEX: f32 = -8.0;
+const MIN_STABILITY_SCORE: f32 = 0.2;
+/// Best a day with turbulent wind shear can score, below a good day
+const WIND_SHEAR_SCORE_CAP: f32 = 0.4;
+/// Flyable hours from which they don't limit a day; other factors aside, six
+/// hours rate excellent and four good
+const FULL_DAY_HOURS: f32 = 7.5;
+
 pub struct ParaglidingActivitySource {
     site_repo: Arc<ParaglidingSiteRepository>,
     weather: Arc<dyn WeatherProvider>,
+    history: Arc<ForecastHistory>,
+    evaluations: Arc<EvaluationCache>,
+    ensemble: Option<Arc<dyn EnsembleProvider>>,
+    soundings: Option<Arc<dyn SoundingProvider>>,
 }
 
 impl ParaglidingActivitySource {
     pub fn new(
         site_repo: Arc<ParaglidingSiteRepository>,
         weather: Arc<dyn WeatherProvider>,
+        history: Arc<ForecastHistory>,
     ) -> Self {
-        Self { site_repo, weather }
+        Self {
+            site_repo,
+            weather,
+            history,
+            evaluations: Arc::default(),
+            ensemble: None,
+            soundings: None,
+        }
+    }
+
+    /// Reuses the evaluations in `evaluations` instead of keeping its own.
+    pub fn with_evaluations(mut self, evaluations: Arc<EvaluationCache>) -> Self {
+        self.evaluations = evaluations;
+        self
+    }
+
+    /// Rates days by the chance of flyable weather across the `ensemble`
+    /// members, besides the single forecast.
+    pub fn with_ensemble(mut self, ensemble: Arc<dyn EnsembleProvider>) -> Self {
+        self.ensemble = Some(ensemble);
+        self
+    }
+
+    /// Rates days by how high the thermals go and how unstable the air is,
+    /// from the model soundings.
+    pub fn with_soundings(mut self, soundings: Arc<dyn SoundingProvider>) -> Self {
+        self.soundings = Some(soundings);
+        self
     }
 }
 
 #[async_trait]
 impl ActivitySource for ParaglidingActivitySource {
-    async fn suggest(&self, ctx: &PlanningContext) -> Result<Vec<ActivitySuggestion>> {
+    async fn suggest(&self, ctx: &PlanningContext) -> Result<Suggestions> {
         let settings = self.site_repo.get_settings().await?.unwrap_or_default();
         let min_duration = Duration::hours(settings.minimum_flyable_hours as i64);
 
@@ -38,7 +103,7 @@ impl ActivitySource for ParaglidingActivitySource {
             .fetch_launches_within_radius(&ctx.home, settings.search_radius_km)
             .await;
 
-        let mut out = Vec::new();
+        let mut out = Suggestions::default();
         for (site, _distance) in sites {
             if site.mute_alerts == Some(true) {
                 tracing::debug!(site = %site.name, "Skipping muted site");
@@ -48,40 +113,46 @@ impl ActivitySource for ParaglidingActivitySource {
                 continue;
             };
 
-            let forecast = match self
-                .weather
-                .get_forecast(launch.location.clone(), site.preferred_weather_model.clone())
-                .await
+            let forecast = match outlook::site_forecast(self.weather.as_ref(), &site, launch).await
             {
                 Ok(f) => f,
                 Err(e) => {
-                    tracing::warn!(
-                        site = %site.name,
-                        lat = %launch.location.latitude,
-                        lon = %launch.location.longitude,
-                        error = %e,
-                        "Failed to get weather forecast"
-                    );
+                    out.errors.push(e);
                     continue;
                 }
             };
 
-            let eval = site_evaluator::evaluate_site(&site, &forecast).await;
+            let eval = evaluate(
+                &self.evaluations,
+                &site,
+                &forecast,
+                self.ensemble.as_deref(),
+                self.soundings.as_deref(),
+            )
+            .await;
+            let now = Utc::now();
+            let previous_run = previous_run(&self.history, &site.name, now).await;
+            if let Err(e) = self
+                .history
+                .record(&site.name, now, &eval.daily_summaries, &eval.provenance)
+                .await
+            {
+                tracing::warn!(site = %site.name, error = ?e, "Failed to record forecast history");
+            }
             for day in eval.daily_summaries {
+                let score = day_score(&day, previous_run.as_ref());
+                let description = day_description(&day);
                 for range in day.ranges {
-                    out.push(ActivitySuggestion {
+                    out.suggestions.push(ActivitySuggestion {
                         kind: ActivityKind::Paragliding,
                         location: launch.location.clone(),
                         timing: Timing::Flexible {
-                            window: TimeWindow {
-                                start: range.start,
-                                end: range.end,
-                            },
+                            window: range.window(),
                             min_duration,
                         },
                         title: site.name.clone(),
-                        description: String::new(),
-                        score: None,
+                        description: description.clone(),
+                        score: score.clone(),
                     });
                 }
             }
@@ -91,28 +162,263 @@ impl ActivitySource for ParaglidingActivitySource {
     }
 }
 
+/// Evaluates `site` with the ensemble and the soundings, where there are
+/// any, the same way for the calendar and the API.
+pub async fn evaluate(
+    evaluations: &EvaluationCache,
+    site: &ParaglidingSite,
+    forecast: &WeatherForecast,
+    ensemble: Option<&dyn EnsembleProvider>,
+    soundings: Option<&dyn SoundingProvider>,
+) -> SiteEvaluationResult {
+    let mut eval =
+        site_evaluator::evaluate_site_with_ensemble(evaluations, site, forecast, ensemble).await;
+    if let Some(soundings) = soundings
+        && let Some(launch) = site.launches.first()
+    {
+        match soundings.get_soundings(&launch.location).await {
+            Ok(soundings) => site_evaluator::add_soundings(&mut eval, site, &soundings),
+            Err(e) => {
+                tracing::warn!(site = %site.name, error = ?e, "Failed to get soundings")
+            }
+        }
+    }
+    eval
+}
+
+/// The run from about a day before `now` that scores are compared with.
+async fn previous_run(
+    history: &ForecastHistory,
+    site: &str,
+    now: DateTime<Utc>,
+) -> Option<ForecastSnapshot> {
+    history
+        .previous_run(site, now - PREVIOUS_RUN_MIN_AGE)
+        .await
+        .inspect_err(|e| tracing::warn!(site, error = ?e, "Failed to load forecast history"))
+        .ok()
+        .flatten()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayScore {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub score: Score,
+}
+
+/// The score of each of `days` of `site`, as calendar events get it.
+pub async fn day_scores(
+    history: &ForecastHistory,
+    site: &str,
+    days: &[DailySummary],
+) -> Vec<DayScore> {
+    let previous_run = previous_run(history, site, Utc::now()).await;
+    days.iter()
+        .filter_map(|day| {
+            Some(DayScore {
+                date: day.date,
+                score: day_score(day, previous_run.as_ref())?,
+            })
+        })
+        .collect()
+}
+
+fn day_description(day: &DailySummary) -> String {
+    let mut lines = day.warnings.clone();
+    if day.source_model.as_deref() == Some(EXTENDED_RANGE_MODEL) {
+        lines.push("Long-range GFS tendency, expect changes".to_string());
+    }
+    if let Some(parts) = day.flyable_day_parts() {
+        let parts: Vec<&str> = parts.iter().map(DayPart::label).collect();
+        lines.push(format!("Flyable in the {}", parts.join(" and ")));
+    }
+    if let Some((parts, rating)) = day.best_day_parts() {
+        let parts: Vec<&str> = parts.iter().map(DayPart::label).collect();
+        lines.push(format!(
+            "Best in the {}, {}",
+            parts.join(" and "),
+            rating.label()
+        ));
+    }
+    let chances: Vec<String> = day
+        .day_parts
+        .iter()
+        .filter_map(|p| {
+            Some(format!(
+                "{} {:.0}%",
+                p.part.label(),
+                p.flyable_probability? * 100.0
+            ))
+        })
+        .collect();
+    if !chances.is_empty() {
+        lines.push(format!("Chance of a flyable {}", chances.join(", ")));
+    }
+    if let Some(chance) = day.precipitation_probability
+        && chance >= NOTABLE_RAIN_CHANCE
+    {
+        lines.push(format!("Chance of rain up to {chance}%"));
+    }
+    if day.crowding == Crowding::High {
+        lines.push("Expect a busy launch".to_string());
+    }
+    if let Some(lift) = day.usable_lift_m {
+        lines.push(format!("Thermals to about {:.0} m above launch", lift));
+    }
+    if let Some(top) = day.boundary_layer_top_m {
+        lines.push(format!("Boundary layer top around {:.0} m", top));
+    }
+    lines.join("\n")
+}
+
+/// Score of `day` on its own, without comparing it to an earlier run; 1 when
+/// nothing limits it.
+pub fn day_value(day: &DailySummary) -> f32 {
+    day_score(day, None).map_or(1.0, |score| score.value)
+}
+
+/// Rating of `day` on its own, like [`day_value`].
+pub fn day_rating(day: &DailySummary) -> DayRating {
+    day_score(day, None).map_or(DayRating::Excellent, |score| score.rating())
+}
+
+fn day_score(day: &DailySummary, previous_run: Option<&ForecastSnapshot>) -> Option<Score> {
+    let mut factors = Vec::new();
+    let mut reasons = Vec::new();
+
+    let hours = (day.total_flyable_hours as f32 / FULL_DAY_HOURS).min(1.0);
+    if hours < 1.0 {
+        factors.push((Factor::FlyableHours, FACTOR_WEIGHT, hours));
+        reasons.push(format!("{} flyable hours", day.total_flyable_hours));
+    }
+
+    if let Some(run) = previous_run
+        && let Some(previous) = run.daily_summaries.iter().find(|d| d.date == day.date)
+        && let Some(stability) = history::stability(previous, day)
+    {
+        factors.push((Factor::ForecastStability, FACTOR_WEIGHT, stability));
+        reasons.push(format!(
+            "{:.0}% of hours agree with the forecast from {}",
+            stability * 100.0,
+            run.generated_at.format("%a %H:%M")
+        ));
+    }
+
+    if day.likely_snow_covered {
+        factors.push((Factor::SnowCover, FACTOR_WEIGHT, SNOW_COVER_PENALTY));
+        reasons.push("Launch likely snow-covered".to_string());
+    }
+
+    if day.confidence < 1.0 {
+        factors.push((Factor::ForecastConfidence, FACTOR_WEIGHT, day.confidence));
+        reasons.push(format!(
+            "Forecast confidence {:.0}%",
+            day.confidence * 100.0
+        ));
+    }
+
+    if let Some(alignment) = day.wind_alignment
+        && alignment < 1.0
+    {
+        factors.push((Factor::WindAlignment, FACTOR_WEIGHT, alignment));
+        reasons.push(format!(
+            "Wind {:.0}% straight into launch",
+            alignment * 100.0
+        ));
+    }
+
+    if let Some((part, chance)) = best_chance(day) {
+        factors.push((Factor::FlyableChance, FACTOR_WEIGHT, chance));
+        reasons.push(format!(
+            "{:.0}% chance of a flyable {}",
+            chance * 100.0,
+            part.label()
+        ));
+    }
+
+    if let Some(chance) = day.precipitation_probability
+        && chance > 0
+    {
+        factors.push((
+            Factor::RainChance,
+            FACTOR_WEIGHT,
+            1.0 - f32::from(chance.min(100)) / 100.0,
+        ));
+        reasons.push(format!("{chance}% chance of rain"));
+    }
+
+    if let Some(lift) = day.usable_lift_m {
+        let raw = ((lift / GOOD_LIFT_M) as f32).clamp(MIN_LIFT_SCORE, 1.0);
+        if raw < 1.0 {
+            factors.push((Factor::ThermalHeight, FACTOR_WEIGHT, raw));
+            reasons.push(format!("Thermals only {:.0} m above launch", lift));
+        }
+    }
+
+    if let Some(index) = day.lifted_index
+        && index < UNSTABLE_LIFTED_INDEX
+    {
+        let share = (index - UNSTABLE_LIFTED_INDEX) / (SEVERE_LIFTED_INDEX - UNSTABLE_LIFTED_INDEX);
+        factors.push((
+            Factor::Instability,
+            FACTOR_WEIGHT,
+            (1.0 - share).clamp(MIN_STABILITY_SCORE, 1.0),
+        ));
+        reasons.push(format!("Unstable air, lifted index {index:.0}"));
+    }
+
+    let score = Score::from_factors(factors, reasons);
+    if !day.strong_wind_shear {
+        return score;
+    }
+    let score = score.unwrap_or(Score {
+        value: 1.0,
+        reasons: vec![],
+        breakdown: vec![],
+    });
+    Some(score.capped(
+        Factor::WindShear,
+        WIND_SHEAR_SCORE_CAP,
+        "Turbulent wind shear".to_string(),
+    ))
+}
+
+/// The part of the day most ensemble members find flyable, with their share.
+fn best_chance(day: &DailySummary) -> Option<(DayPart, f32)> {
+    day.day_parts
+        .iter()
+        .filter_map(|p| Some((p.part, p.flyable_probability?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        adapters::store::PersistentStore,
+        adapters::{cache::PersistentCache, store::PersistentStore},
         domain::{
+            activities::{SiteError, TimeWindow},
+            error::ErrorCode,
             location::Location,
-            paragliding::{
-                ParaglidingLaunch, ParaglidingSite, SiteType, UserSettings,
-            },
-            ports::MockWeatherProvider,
+            paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType, UserSettings},
+            ports::{MockEnsembleProvider, MockWeatherProvider},
             weather::{WeatherData, WeatherForecast},
         },
     };
     use anyhow::anyhow;
     use chrono::{TimeZone, Utc};
     use mockall::predicate::*;
+    use rstest::rstest;
     use tempfile::TempDir;
 
+    /// Hours factor of the five flyable hours of `flyable_window_forecast`
+    const WINDOW_HOURS: f32 = 5.0 / FULL_DAY_HOURS;
+
     struct TestRepo {
         _dir: TempDir,
         repo: Arc<ParaglidingSiteRepository>,
+        history: Arc<ForecastHistory>,
     }
 
     fn fresh_repo() -> TestRepo {
@@ -123,7 +429,17 @@ mod tests {
             .unwrap();
         let store = Arc::new(PersistentStore::from_keyspace(ks));
         let repo = Arc::new(ParaglidingSiteRepository::new(store));
-        TestRepo { _dir: dir, repo }
+        let cache_ks = db
+            .keyspace("cache", fjall::KeyspaceCreateOptions::default)
+            .unwrap();
+        let history = Arc::new(ForecastHistory::new(Arc::new(
+            PersistentCache::from_keyspace(cache_ks),
+        )));
+        TestRepo {
+            _dir: dir,
+            repo,
+            history,
+        }
     }
 
     fn home() -> Location {
@@ -138,13 +454,10 @@ mod tests {
         ParaglidingSite {
             name: name.into(),
             launches,
-            landings: vec![],
             country: Some("DE".into()),
             data_source: "test".into(),
-            parking_location: None,
             mute_alerts: mute,
-            rating: None,
-            preferred_weather_model: None,
+            ..Default::default()
         }
     }
 
@@ -169,7 +482,7 @@ mod tests {
             cloud_cover: 0,
             pressure: 1013.0,
             visibility: 10.0,
-            description: String::new(),
+            ..Default::default()
         }
     }
 
@@ -202,6 +515,9 @@ mod tests {
         let day = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
         WeatherForecast {
             location: site_loc(),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
             forecast: (4..22)
                 .map(|h| weather_at(day + chrono::Duration::hours(h), 50.0))
                 .collect(),
@@ -212,6 +528,9 @@ mod tests {
         let day = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
         WeatherForecast {
             location: site_loc(),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
             forecast: (4..22)
                 .map(|h| {
                     let wind = if (10..=14).contains(&h) { 3.0 } else { 50.0 };
@@ -235,8 +554,9 @@ mod tests {
             .expect_get_forecast()
             .returning(|_, _| Ok(bad_weather_forecast()));
 
-        let source = ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather));
-        let out = source.suggest(&ctx()).await.unwrap();
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
         assert!(out.is_empty(), "expected no suggestions, got {:?}", out);
     }
 
@@ -254,18 +574,133 @@ mod tests {
             .expect_get_forecast()
             .returning(|_, _| Ok(flyable_window_forecast()));
 
-        let source = ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather));
-        let out = source.suggest(&ctx()).await.unwrap();
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
         assert_eq!(out.len(), 1);
         let Timing::Flexible { window, .. } = &out[0].timing else {
             panic!("expected Flexible timing, got {:?}", out[0].timing);
         };
         let day = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
         assert_eq!(window.start, day + chrono::Duration::hours(10));
-        assert_eq!(window.end, day + chrono::Duration::hours(14));
+        assert_eq!(window.end, day + chrono::Duration::hours(15));
         assert_eq!(out[0].title, "S");
     }
 
+    #[tokio::test]
+    async fn evaluated_site_is_recorded_in_forecast_history() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        r.repo
+            .save_site(site("S", None, vec![hang_launch()]))
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather
+            .expect_get_forecast()
+            .returning(|_, _| Ok(flyable_window_forecast()));
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        source.suggest(&ctx()).await.unwrap();
+
+        let snapshots = r.history.snapshots("S").await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].daily_summaries.len(), 1);
+        assert_eq!(snapshots[0].daily_summaries[0].total_flyable_hours, 5);
+    }
+
+    #[tokio::test]
+    async fn score_reflects_agreement_with_previous_run() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        r.repo
+            .save_site(site("S", None, vec![hang_launch()]))
+            .await
+            .unwrap();
+
+        let previous = site_evaluator::evaluate_site(
+            &site("S", None, vec![hang_launch()]),
+            &flyable_window_forecast(),
+        )
+        .await;
+        r.history
+            .record(
+                "S",
+                Utc::now() - Duration::days(1),
+                &previous.daily_summaries,
+                &previous.provenance,
+            )
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather
+            .expect_get_forecast()
+            .returning(|_, _| Ok(flyable_window_forecast()));
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].score.as_ref().map(|s| s.value), Some(WINDOW_HOURS));
+    }
+
+    #[tokio::test]
+    async fn score_has_no_stability_without_previous_run() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        r.repo
+            .save_site(site("S", None, vec![hang_launch()]))
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather
+            .expect_get_forecast()
+            .returning(|_, _| Ok(flyable_window_forecast()));
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
+        let score = out[0].score.as_ref().unwrap();
+        assert_eq!(score.breakdown.len(), 1);
+        assert_eq!(score.breakdown[0].factor, Factor::FlyableHours);
+        assert_eq!(score.reasons, vec!["5 flyable hours"]);
+    }
+
+    #[tokio::test]
+    async fn snow_covered_mountain_launch_gets_penalised_score() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        let mut launch = hang_launch();
+        launch.elevation = 1800.0;
+        r.repo
+            .save_site(site("Alp", None, vec![launch]))
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather.expect_get_forecast().returning(|_, _| {
+            let mut forecast = flyable_window_forecast();
+            forecast
+                .forecast
+                .iter_mut()
+                .for_each(|w| w.snow_depth_m = 0.4);
+            Ok(forecast)
+        });
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
+        assert_eq!(out.len(), 1);
+        let score = out[0].score.as_ref().unwrap();
+        assert_eq!(score.value, WINDOW_HOURS * SNOW_COVER_PENALTY);
+        assert!(score.reasons.iter().any(|r| r.contains("snow")));
+        assert_eq!(score.breakdown[1].factor, Factor::SnowCover);
+    }
+
     #[tokio::test]
     async fn muted_site_is_skipped_without_calling_weather() {
         let r = fresh_repo();
@@ -278,8 +713,9 @@ mod tests {
         let mut weather = MockWeatherProvider::new();
         weather.expect_get_forecast().times(0);
 
-        let source = ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather));
-        let out = source.suggest(&ctx()).await.unwrap();
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
         assert!(out.is_empty());
     }
 
@@ -295,13 +731,14 @@ mod tests {
         let mut weather = MockWeatherProvider::new();
         weather.expect_get_forecast().times(0);
 
-        let source = ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather));
-        let out = source.suggest(&ctx()).await.unwrap();
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
         assert!(out.is_empty());
     }
 
     #[tokio::test]
-    async fn weather_error_skips_site_without_panicking() {
+    async fn weather_error_skips_site_and_reports_it() {
         let r = fresh_repo();
         seed_settings(&r.repo).await;
         r.repo
@@ -314,8 +751,168 @@ mod tests {
             .expect_get_forecast()
             .returning(|_, _| Err(anyhow!("upstream timeout")));
 
-        let source = ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather));
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
         let out = source.suggest(&ctx()).await.unwrap();
-        assert!(out.is_empty());
+        assert!(out.suggestions.is_empty());
+        assert_eq!(
+            out.errors,
+            [SiteError {
+                site: "S".into(),
+                code: ErrorCode::WeatherUnavailable,
+                error: "upstream timeout".into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn ensemble_members_rate_the_day_by_chance() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        r.repo
+            .save_site(site("S", None, vec![hang_launch()]))
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather
+            .expect_get_forecast()
+            .returning(|_, _| Ok(flyable_window_forecast()));
+        let mut ensemble = MockEnsembleProvider::new();
+        ensemble.expect_get_ensemble().returning(|_| {
+            let mut members = vec![flyable_window_forecast(); 3];
+            members.push(bad_weather_forecast());
+            Ok(members)
+        });
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone())
+                .with_ensemble(Arc::new(ensemble));
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
+        assert_eq!(out.len(), 1);
+        assert!(
+            out[0]
+                .description
+                .contains("Chance of a flyable morning 0%, midday 75%, afternoon 0%"),
+            "{}",
+            out[0].description
+        );
+        let score = out[0].score.as_ref().unwrap();
+        assert_eq!(score.value, WINDOW_HOURS * 0.75);
+        assert_eq!(
+            score.reasons,
+            vec!["5 flyable hours", "75% chance of a flyable midday"]
+        );
+    }
+
+    #[tokio::test]
+    async fn chance_of_rain_lowers_the_score() {
+        let r = fresh_repo();
+        seed_settings(&r.repo).await;
+        r.repo
+            .save_site(site("S", None, vec![hang_launch()]))
+            .await
+            .unwrap();
+
+        let mut weather = MockWeatherProvider::new();
+        weather.expect_get_forecast().returning(|_, _| {
+            let mut forecast = flyable_window_forecast();
+            for (i, hour) in forecast.forecast.iter_mut().enumerate() {
+                hour.precipitation_probability = Some(if i == 12 { 40 } else { 10 });
+            }
+            Ok(forecast)
+        });
+
+        let source =
+            ParaglidingActivitySource::new(r.repo.clone(), Arc::new(weather), r.history.clone());
+        let out = source.suggest(&ctx()).await.unwrap().suggestions;
+        assert_eq!(out.len(), 1);
+        assert!(
+            out[0].description.contains("Chance of rain up to 40%"),
+            "{}",
+            out[0].description
+        );
+        let score = out[0].score.as_ref().unwrap();
+        assert_eq!(score.value, WINDOW_HOURS * 0.6);
+        assert_eq!(score.reasons, vec!["5 flyable hours", "40% chance of rain"]);
+        assert_eq!(score.breakdown[1].factor, Factor::RainChance);
+    }
+
+    #[rstest]
+    #[case::high_and_stable(Some(2000.0), Some(1.0), None)]
+    #[case::low_thermals(Some(600.0), None, Some(0.4))]
+    #[case::thunderstorms(None, Some(-5.0), Some(0.5))]
+    fn soundings_score_thermal_height_and_instability(
+        #[case] usable_lift_m: Option<f64>,
+        #[case] lifted_index: Option<f32>,
+        #[case] expected: Option<f32>,
+    ) {
+        let day = DailySummary {
+            usable_lift_m,
+            lifted_index,
+            total_flyable_hours: 8,
+            ..Default::default()
+        };
+        let score = day_score(&day, None).map(|s| s.value);
+        match (score, expected) {
+            (Some(score), Some(expected)) => {
+                assert!((score - expected).abs() < 1e-5, "got {score}")
+            }
+            (score, expected) => assert_eq!(score, expected),
+        }
+    }
+
+    #[rstest]
+    #[case::no_other_factors(None, 0.4)]
+    #[case::likely_dry(Some(20), 0.4)]
+    #[case::already_below_the_cap(Some(80), 0.2)]
+    fn wind_shear_caps_the_score(#[case] rain_chance: Option<u8>, #[case] expected: f32) {
+        let day = DailySummary {
+            strong_wind_shear: true,
+            precipitation_probability: rain_chance,
+            total_flyable_hours: 8,
+            ..Default::default()
+        };
+        let score = day_score(&day, None).unwrap();
+        assert!((score.value - expected).abs() < 1e-5, "got {}", score.value);
+        assert!(score.reasons.contains(&"Turbulent wind shear".to_string()));
+    }
+
+    #[tokio::test]
+    async fn day_scores_serialize_their_breakdown() {
+        let r = fresh_repo();
+        let days = [
+            DailySummary {
+                likely_snow_covered: true,
+                total_flyable_hours: 8,
+                ..Default::default()
+            },
+            // Nothing to score
+            DailySummary {
+                total_flyable_hours: 8,
+                ..Default::default()
+            },
+        ];
+
+        let scores = day_scores(&r.history, "S", &days).await;
+
+        assert_eq!(scores.len(), 1);
+        let json = serde_json::to_value(&scores[0]).unwrap();
+        assert_eq!(json["value"], serde_json::json!(SNOW_COVER_PENALTY));
+        assert_eq!(json["breakdown"][0]["factor"], "SnowCover");
+        assert_eq!(json["reasons"][0], "Launch likely snow-covered");
+    }
+
+    #[rstest]
+    #[case(6, DayRating::Excellent)]
+    #[case(5, DayRating::Good)]
+    #[case(4, DayRating::Good)]
+    #[case(3, DayRating::Fair)]
+    fn flyable_hours_rate_a_day(#[case] hours: usize, #[case] expected: DayRating) {
+        let day = DailySummary {
+            total_flyable_hours: hours,
+            ..Default::default()
+        };
+        assert_eq!(day_rating(&day), expected);
     }
 }
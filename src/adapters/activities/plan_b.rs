@@ -0,0 +1,239 @@
+use std::{collections::BTreeMap, ops::RangeInclusive, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    adapters::activities::paragliding::site_evaluator::FlyableRange,
+    domain::{
+        activities::{
+            ActivityKind, ActivitySuggestion, PlanningContext, Suggestions, TimeWindow, Timing,
+        },
+        location::Location,
+        ports::{ActivitySource, WeatherProvider},
+        units::KMH_PER_MS,
+        weather::{self, WeatherData},
+    },
+};
+
+// Enough daytime rain to spoil a hike as well.
+const WET_DAY_PRECIPITATION_MM: f32 = 2.0;
+// Enough to inflate and hold a wing overhead, not so much that it drags you.
+const GROUNDHANDLING_WIND_KMH: RangeInclusive<f32> = 15.0..=25.0;
+// Mean daylight cloud cover up to which a day counts as sunny.
+const SUNNY_CLOUD_COVER: f32 = 50.0;
+const MIN_DURATION: Duration = Duration::hours(3);
+
+/// Suggests something to do on days when flying is off: rest when it rains,
+/// groundhandling on a flat field in 15–25 km/h of wind, else a hike when it
+/// is sunny. The planner drops these again for days that have a flying
+/// suggestion.
+pub struct PlanBActivitySource {
+    weather: Arc<dyn WeatherProvider>,
+}
+
+impl PlanBActivitySource {
+    pub fn new(weather: Arc<dyn WeatherProvider>) -> Self {
+        Self { weather }
+    }
+}
+
+#[async_trait]
+impl ActivitySource for PlanBActivitySource {
+    async fn suggest(&self, ctx: &PlanningContext) -> Result<Suggestions> {
+        let forecast = self.weather.get_forecast(ctx.home.clone(), None).await?;
+
+        let mut days: BTreeMap<NaiveDate, Vec<WeatherData>> = BTreeMap::new();
+        for hour in forecast.forecast {
+            if hour.timestamp >= ctx.horizon.start && hour.timestamp <= ctx.horizon.end {
+                days.entry(hour.timestamp.date_naive())
+                    .or_default()
+                    .push(hour);
+            }
+        }
+
+        let suggestions = days
+            .into_iter()
+            .filter_map(|(date, hours)| {
+                let (sunrise, sunset) = weather::get_sunrise_sunset(&ctx.home, date).ok()?;
+                let daylight: Vec<WeatherData> = hours
+                    .into_iter()
+                    .filter(|h| h.timestamp >= sunrise && h.timestamp <= sunset)
+                    .collect();
+                plan_b_for_day(&ctx.home, &daylight)
+            })
+            .collect();
+        Ok(Suggestions {
+            suggestions,
+            errors: vec![],
+        })
+    }
+}
+
+fn plan_b_for_day(home: &Location, daylight: &[WeatherData]) -> Option<ActivitySuggestion> {
+    let window = FlyableRange {
+        start: daylight.iter().map(|h| h.timestamp).min()?,
+        end: daylight.iter().map(|h| h.timestamp).max()?,
+    }
+    .window();
+    if window.duration() < MIN_DURATION {
+        return None;
+    }
+
+    let rain: f32 = daylight.iter().map(|h| h.precipitation.max(0.0)).sum();
+    let cloud_cover = daylight
+        .iter()
+        .map(|h| f32::from(h.cloud_cover))
+        .sum::<f32>()
+        / daylight.len() as f32;
+    let (kind, window, title, description) = if rain >= WET_DAY_PRECIPITATION_MM {
+        (
+            ActivityKind::Rest,
+            window,
+            "Plan B: rest day",
+            format!("Wet day, {:.0} mm of rain expected", rain),
+        )
+    } else if let Some(window) = groundhandling_window(daylight) {
+        (
+            ActivityKind::Groundhandling,
+            window,
+            "Plan B: groundhandling",
+            format!(
+                "{:.0}–{:.0} km/h of wind, good for groundhandling on a flat field",
+                GROUNDHANDLING_WIND_KMH.start(),
+                GROUNDHANDLING_WIND_KMH.end()
+            ),
+        )
+    } else if cloud_cover <= SUNNY_CLOUD_COVER {
+        (
+            ActivityKind::Hiking,
+            window,
+            "Plan B: hike",
+            "No flyable window, but sunny enough for a hike".to_string(),
+        )
+    } else {
+        return None;
+    };
+
+    Some(ActivitySuggestion {
+        kind,
+        location: home.clone(),
+        timing: Timing::Flexible {
+            window,
+            min_duration: MIN_DURATION,
+        },
+        title: title.to_string(),
+        description,
+        score: None,
+    })
+}
+
+/// Longest run of consecutive hours with wind for groundhandling, if it lasts
+/// long enough to be worth the drive to a field.
+fn groundhandling_window(daylight: &[WeatherData]) -> Option<TimeWindow> {
+    let mut best: Option<FlyableRange> = None;
+    let mut current: Option<FlyableRange> = None;
+    for hour in daylight {
+        if GROUNDHANDLING_WIND_KMH.contains(&(hour.wind_speed_ms * KMH_PER_MS)) {
+            let run = current.get_or_insert(FlyableRange {
+                start: hour.timestamp,
+                end: hour.timestamp,
+            });
+            run.end = hour.timestamp;
+            if best
+                .as_ref()
+                .is_none_or(|b| run.flyable_hours() > b.flyable_hours())
+            {
+                best = Some(run.clone());
+            }
+        } else {
+            current = None;
+        }
+    }
+    best.map(|run| run.window())
+        .filter(|w| w.duration() >= MIN_DURATION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn home() -> Location {
+        Location::new(50.7, 13.0, "Home".into(), "DE".into())
+    }
+
+    fn daylight(day: u32, rain_per_hour: f32) -> Vec<WeatherData> {
+        (8..18)
+            .map(|h| WeatherData {
+                timestamp: Utc.with_ymd_and_hms(2026, 6, day, h, 0, 0).unwrap(),
+                precipitation: rain_per_hour,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sunny_weekday_suggests_a_hike() {
+        let s = plan_b_for_day(&home(), &daylight(10, 0.0)).unwrap();
+        assert_eq!(s.kind, ActivityKind::Hiking);
+    }
+
+    #[test]
+    fn overcast_dry_day_gets_no_plan_b() {
+        let mut day = daylight(13, 0.0);
+        day.iter_mut().for_each(|h| h.cloud_cover = 90);
+        assert!(plan_b_for_day(&home(), &day).is_none());
+    }
+
+    #[test]
+    fn wet_day_suggests_rest() {
+        let s = plan_b_for_day(&home(), &daylight(14, 0.5)).unwrap();
+        assert_eq!(s.kind, ActivityKind::Rest);
+        assert!(s.description.contains("5 mm"), "{}", s.description);
+    }
+
+    #[test]
+    fn breezy_afternoon_suggests_groundhandling() {
+        let mut day = daylight(13, 0.0);
+        // 20 km/h from 12:00 to 16:00, too strong to fly but good on the ground.
+        day[4..9]
+            .iter_mut()
+            .for_each(|h| h.wind_speed_ms = 20.0 / KMH_PER_MS);
+        let s = plan_b_for_day(&home(), &day).unwrap();
+        assert_eq!(s.kind, ActivityKind::Groundhandling);
+        let Timing::Flexible { window, .. } = s.timing else {
+            panic!("expected a flexible timing");
+        };
+        assert_eq!(window.start, day[4].timestamp);
+        assert_eq!(window.end, day[9].timestamp);
+    }
+
+    #[test]
+    fn short_breezy_spell_is_no_groundhandling() {
+        let mut day = daylight(13, 0.0);
+        day[4..6]
+            .iter_mut()
+            .for_each(|h| h.wind_speed_ms = 20.0 / KMH_PER_MS);
+        let s = plan_b_for_day(&home(), &day).unwrap();
+        assert_eq!(s.kind, ActivityKind::Hiking);
+    }
+
+    #[test]
+    fn short_remaining_day_gets_no_plan_b() {
+        let day = daylight(13, 0.0);
+        assert!(plan_b_for_day(&home(), &day[8..]).is_none());
+    }
+
+    #[test]
+    fn window_lasts_until_the_end_of_the_last_hour() {
+        let day = daylight(13, 0.0);
+        let s = plan_b_for_day(&home(), &day[7..]).unwrap();
+        let Timing::Flexible { window, .. } = s.timing else {
+            panic!("expected a flexible timing");
+        };
+        assert_eq!(window.start, day[7].timestamp);
+        assert_eq!(window.end, day[9].timestamp + Duration::hours(1));
+    }
+}
@@ -0,0 +1,159 @@
+//! JSON error responses for the HTTP API: `{"code", "message", "context"}` with
+//! a status derived from the code.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+
+use crate::domain::error::{ErrorCode, TravelAiError};
+
+#[derive(Debug, Serialize)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, String>,
+    /// What is wrong with which request parameter
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        TravelAiError::new(code, message).into()
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl Display) -> Self {
+        self.context.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        status(self.code)
+    }
+}
+
+fn status(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidInput => StatusCode::BAD_REQUEST,
+        ErrorCode::InvalidCoordinates | ErrorCode::ValidationFailed => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+        ErrorCode::NotFound | ErrorCode::CalendarNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::Conflict | ErrorCode::JobAlreadyRunning | ErrorCode::JobNotConfigured => {
+            StatusCode::CONFLICT
+        }
+        ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::UnparsableUpload => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::WeatherUnavailable
+        | ErrorCode::GeocoderUnavailable
+        | ErrorCode::ElevationUnavailable
+        | ErrorCode::SoundingUnavailable
+        | ErrorCode::CalendarUnavailable
+        | ErrorCode::CalendarSyncFailed
+        | ErrorCode::NotificationUnavailable => StatusCode::BAD_GATEWAY,
+        ErrorCode::SiteDatabaseUnavailable | ErrorCode::CalendarAuthExpired => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        ErrorCode::Cache
+        | ErrorCode::Configuration
+        | ErrorCode::SchedulerJobFailed
+        | ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl From<TravelAiError> for ApiError {
+    fn from(error: TravelAiError) -> Self {
+        Self {
+            code: error.code,
+            message: error.message,
+            context: error.context,
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self)).into_response()
+    }
+}
+
+pub trait ApiResultExt<T> {
+    /// Turns a failure into an [`ApiError`] with `code` and `message`, unless it
+    /// holds a [`TravelAiError`], whose code is kept. The cause is logged, not
+    /// sent.
+    fn or_api(self, code: ErrorCode, message: &str) -> Result<T, ApiError>;
+}
+
+impl<T> ApiResultExt<T> for anyhow::Result<T> {
+    fn or_api(self, code: ErrorCode, message: &str) -> Result<T, ApiError> {
+        self.map_err(|error| {
+            tracing::error!(error = ?error, ?code, "{message}");
+            match TravelAiError::find(&error) {
+                Some(typed) => typed.clone().into(),
+                None => ApiError::new(code, message),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[tokio::test]
+    async fn response_carries_code_message_and_context() {
+        let response = ApiError::new(ErrorCode::NotFound, "Site not found")
+            .with_context("name", "Wank")
+            .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "not_found",
+                "message": "Site not found",
+                "context": { "name": "Wank" },
+            })
+        );
+    }
+
+    #[test]
+    fn typed_errors_keep_their_code() {
+        let typed: anyhow::Result<()> =
+            Err(TravelAiError::new(ErrorCode::InvalidCoordinates, "Latitude out of range").into());
+        let error = typed
+            .or_api(ErrorCode::WeatherUnavailable, "Forecast unavailable")
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidCoordinates);
+        assert_eq!(error.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let untyped: anyhow::Result<()> = Err(anyhow!("connection reset"));
+        let error = untyped
+            .or_api(
+                ErrorCode::SiteDatabaseUnavailable,
+                "Site database unavailable",
+            )
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::SiteDatabaseUnavailable);
+        assert_eq!(error.message, "Site database unavailable");
+        assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
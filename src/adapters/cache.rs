@@ -10,8 +10,9 @@ use tokio::task;
 
 #[derive(Serialize, Deserialize)]
 struct StoredEntry<T> {
-    value: T,
+    // First, so `remove_expired` can read it without knowing T
     expires_at: u64, // Unix timestamp (seconds)
+    value: T,
 }
 
 pub struct PersistentCache {
@@ -42,7 +43,7 @@ impl PersistentCache {
             .ok_or(anyhow!("TTL overflow"))?
             .duration_since(UNIX_EPOCH)?
             .as_secs();
-        let entry = StoredEntry { value, expires_at };
+        let entry = StoredEntry { expires_at, value };
         let bytes = postcard::to_stdvec(&entry)?;
 
         let _ = task::spawn_blocking(move || store.insert(key, bytes)).await?;
@@ -59,7 +60,16 @@ impl PersistentCache {
             task::spawn_blocking(move || get_from_store(store, key_bytes)).await??;
 
         if let Some(bytes) = maybe_bytes {
-            let entry: StoredEntry<T> = postcard::from_bytes(&bytes)?;
+            let entry: StoredEntry<T> = match postcard::from_bytes(&bytes) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    // Written by a build with a different schema for T; treat as a miss
+                    // so callers refetch instead of failing until the TTL runs out.
+                    tracing::debug!(key, error = ?e, "Dropping undecodable cache entry");
+                    self.remove(key).await?;
+                    return Ok(None);
+                }
+            };
             let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
             if now < entry.expires_at {
@@ -100,6 +110,48 @@ impl PersistentCache {
         Ok(result)
     }
 
+    /// Deletes every expired entry and returns how many there were. Reads
+    /// only drop the entries they come across, so without this expired ones
+    /// pile up.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn remove_expired(&self) -> Result<usize> {
+        let store = self.store.clone();
+        task::spawn_blocking(move || {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let mut expired = Vec::new();
+            for pair in store.iter() {
+                let (key, bytes) = pair.into_inner()?;
+                let expires_at = postcard::take_from_bytes::<u64>(&bytes).map(|(at, _)| at);
+                if expires_at.is_ok_and(|at| now >= at) {
+                    expired.push(key);
+                }
+            }
+            for key in &expired {
+                store.remove(key.clone())?;
+            }
+            Ok(expired.len())
+        })
+        .await?
+    }
+
+    /// Deletes every entry whose key starts with `prefix`, e.g. all cached
+    /// days of one calendar.
+    pub async fn remove_starting_with(&self, prefix: &str) -> Result<()> {
+        let store = self.store.clone();
+        let prefix = prefix.as_bytes().to_vec();
+        task::spawn_blocking(move || {
+            let keys = store
+                .prefix(prefix)
+                .map(|pair| pair.key())
+                .collect::<Result<Vec<_>, _>>()?;
+            for key in keys {
+                store.remove(key)?;
+            }
+            Ok(())
+        })
+        .await?
+    }
+
     pub async fn remove(&self, key: &str) -> Result<()> {
         let key = key.as_bytes().to_vec();
         let store = self.store.clone();
@@ -184,6 +236,19 @@ mod tests {
         assert!(bulk.is_empty());
     }
 
+    #[tokio::test]
+    async fn entry_with_incompatible_schema_is_treated_as_miss() {
+        let (_dir, cache) = fresh_cache();
+        cache
+            .put("k", 42u32, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let got: Option<String> = cache.get("k").await.unwrap();
+        assert!(got.is_none());
+        let raw: Option<u32> = cache.get("k").await.unwrap();
+        assert!(raw.is_none(), "undecodable entry should have been removed");
+    }
+
     #[tokio::test]
     async fn remove_actually_deletes_the_entry() {
         let (_dir, cache) = fresh_cache();
@@ -196,6 +261,38 @@ mod tests {
         assert!(got.is_none());
     }
 
+    #[tokio::test]
+    async fn remove_starting_with_keeps_other_keys() {
+        let (_dir, cache) = fresh_cache();
+        for key in ["busy_a_1", "busy_a_2", "busy_b_1"] {
+            cache.put(key, 1u32, Duration::from_secs(60)).await.unwrap();
+        }
+        cache.remove_starting_with("busy_a_").await.unwrap();
+
+        let left: Vec<u32> = cache.get_all_starting_with("busy_").await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(cache.get::<u32>("busy_b_1").await.unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn remove_expired_keeps_fresh_entries() {
+        let (_dir, cache) = fresh_cache();
+        cache
+            .put("fresh", 1u32, Duration::from_secs(60))
+            .await
+            .unwrap();
+        cache
+            .put("stale", "gone".to_string(), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.remove_expired().await.unwrap(), 1);
+
+        let fresh: Option<u32> = cache.get("fresh").await.unwrap();
+        assert_eq!(fresh, Some(1));
+        assert!(cache.store.get("stale").unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn put_overwrites_existing_entry_and_resets_ttl() {
         let (_dir, cache) = fresh_cache();
@@ -0,0 +1,549 @@
+//! Writes to a CalDAV (RFC 4791) server such as Nextcloud, Radicale or
+//! Fastmail. Calendars are collections below `CALDAV_URL`, named by their
+//! display name; each event is a resource named after its key, so writing it
+//! again replaces it.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use quick_xml::{Reader, escape::escape, events::Event};
+use reqwest::{
+    Method, Response, StatusCode,
+    header::{CONTENT_TYPE, HeaderValue},
+};
+use reqwest_middleware::ClientWithMiddleware;
+
+use crate::{
+    adapters::ics,
+    config::CalDavConfig,
+    domain::{
+        calendar::CalendarEvent,
+        error::{ErrorCode, TravelAiError},
+        ports::CalendarProvider,
+    },
+};
+
+const XML: &str = "application/xml; charset=utf-8";
+const CALENDAR: &str = "text/calendar; charset=utf-8";
+
+pub struct CalDavCalendar {
+    http: ClientWithMiddleware,
+    config: CalDavConfig,
+}
+
+/// One `response` of a multistatus answer
+#[derive(Debug, Default, PartialEq)]
+struct DavResource {
+    href: String,
+    display_name: Option<String>,
+    is_calendar: bool,
+    calendar_data: Option<String>,
+}
+
+impl CalDavCalendar {
+    pub fn new(http: ClientWithMiddleware, config: CalDavConfig) -> Self {
+        Self { http, config }
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        depth: Option<&str>,
+        body: Option<(&'static str, String)>,
+    ) -> Result<Response> {
+        let method = Method::from_bytes(method.as_bytes())?;
+        let mut request = self.http.request(method.clone(), url);
+        if let Some((username, password)) = &self.config.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        if let Some(depth) = depth {
+            request = request.header("Depth", depth);
+        }
+        if let Some((content_type, body)) = body {
+            request = request
+                .header(CONTENT_TYPE, HeaderValue::from_static(content_type))
+                .body(body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() || (method == Method::DELETE && status == StatusCode::NOT_FOUND) {
+            Ok(response)
+        } else {
+            Err(dav_error(status, &format!("CalDAV {method} {url} failed")).into())
+        }
+    }
+
+    /// The collection of the calendar with display name `name`.
+    async fn collection_url(&self, name: &str) -> Result<String> {
+        self.collections()
+            .await?
+            .into_iter()
+            .find(|c| c.display_name.as_deref() == Some(name))
+            .map(|c| self.absolute(&c.href))
+            .ok_or_else(|| {
+                TravelAiError::new(ErrorCode::CalendarNotFound, "Calendar not found")
+                    .with_context("name", name)
+                    .into()
+            })
+    }
+
+    async fn collections(&self) -> Result<Vec<DavResource>> {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:displayname/><d:resourcetype/></d:prop></d:propfind>"#;
+        let response = self
+            .send(
+                "PROPFIND",
+                &self.config.url,
+                Some("1"),
+                Some((XML, body.to_string())),
+            )
+            .await?;
+        Ok(multistatus(&response.text().await?)?
+            .into_iter()
+            .filter(|r| r.is_calendar)
+            .collect())
+    }
+
+    /// The event resources of the collection at `url`, only those overlapping
+    /// `range` if given. Within a range the server expands recurring events
+    /// into their occurrences, in UTC.
+    async fn events(
+        &self,
+        url: &str,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Result<Vec<DavResource>> {
+        let bounds = range.map(|(start, end)| {
+            format!(
+                r#"start="{}" end="{}""#,
+                start.format("%Y%m%dT%H%M%SZ"),
+                end.format("%Y%m%dT%H%M%SZ")
+            )
+        });
+        let (time_range, calendar_data) = match &bounds {
+            Some(bounds) => (
+                format!("<c:time-range {bounds}/>"),
+                format!("<c:calendar-data><c:expand {bounds}/></c:calendar-data>"),
+            ),
+            None => (String::new(), "<c:calendar-data/>".to_string()),
+        };
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+<d:prop><d:getetag/>{calendar_data}</d:prop>
+<c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT">{time_range}</c:comp-filter></c:comp-filter></c:filter>
+</c:calendar-query>"#
+        );
+        let response = self
+            .send("REPORT", url, Some("1"), Some((XML, body)))
+            .await?;
+        Ok(multistatus(&response.text().await?)?
+            .into_iter()
+            .filter(|r| !r.href.ends_with('/'))
+            .collect())
+    }
+
+    /// `href` as a full URL, for servers that answer with paths.
+    fn absolute(&self, href: &str) -> String {
+        if href.starts_with("http://") || href.starts_with("https://") {
+            return href.to_string();
+        }
+        let origin_end = self
+            .config
+            .url
+            .find("://")
+            .and_then(|scheme| {
+                let rest = scheme + 3;
+                self.config.url[rest..].find('/').map(|i| rest + i)
+            })
+            .unwrap_or(self.config.url.len());
+        format!("{}{}", &self.config.url[..origin_end], href)
+    }
+}
+
+/// Resource name of `event` in its collection, from its UID so that it is the
+/// same on every sync. Hashed, as keys contain slashes and site names.
+fn resource_name(event: &CalendarEvent) -> String {
+    let hash = ics::uid(event)
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+    format!("travelai-{hash:016x}.ics")
+}
+
+fn dav_error(status: StatusCode, message: &str) -> TravelAiError {
+    let code = match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorCode::CalendarAuthExpired,
+        StatusCode::NOT_FOUND => ErrorCode::CalendarNotFound,
+        StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+        _ => ErrorCode::CalendarUnavailable,
+    };
+    TravelAiError::new(code, message).with_context("status", status.as_u16())
+}
+
+/// The responses of a WebDAV multistatus body, whatever namespace prefixes
+/// the server uses.
+fn multistatus(xml: &str) -> Result<Vec<DavResource>> {
+    let mut reader = Reader::from_str(xml);
+    let mut resources = Vec::new();
+    let mut current: Option<DavResource> = None;
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if name == "response" {
+                    current = Some(DavResource::default());
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(e) => {
+                let in_resource_type = path.last().is_some_and(|p| p == "resourcetype");
+                if in_resource_type
+                    && e.local_name().as_ref() == b"calendar"
+                    && let Some(resource) = current.as_mut()
+                {
+                    resource.is_calendar = true;
+                }
+            }
+            Event::Text(e) => text.push_str(&e.xml_content()?),
+            Event::CData(e) => text.push_str(&e.xml_content()?),
+            Event::GeneralRef(e) => {
+                let reference = format!("&{};", e.decode()?);
+                text.push_str(&quick_xml::escape::unescape(&reference)?);
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                match (name.as_str(), current.as_mut()) {
+                    ("response", _) => resources.extend(current.take()),
+                    ("href", Some(r)) if r.href.is_empty() => r.href = value.trim().to_string(),
+                    ("displayname", Some(r)) => r.display_name = Some(value),
+                    ("calendar-data", Some(r)) => r.calendar_data = Some(value),
+                    ("calendar", Some(r)) if path.last().is_some_and(|p| p == "resourcetype") => {
+                        r.is_calendar = true
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(resources)
+}
+
+#[async_trait]
+impl CalendarProvider for CalDavCalendar {
+    async fn busy_periods(
+        &self,
+        calendars: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let collections = self.collections().await?;
+        let mut periods = Vec::new();
+        for name in calendars {
+            let Some(collection) = collections
+                .iter()
+                .find(|c| c.display_name.as_deref() == Some(name))
+            else {
+                tracing::warn!(name = %name, "Cant find calendar");
+                continue;
+            };
+            let url = self.absolute(&collection.href);
+            for resource in self.events(&url, Some((start, end))).await? {
+                periods.extend(
+                    ics::parse(resource.calendar_data.as_deref().unwrap_or_default())
+                        .iter()
+                        .filter(|e| e.has_overlap(start, end))
+                        .map(|e| (e.start_time, e.end_time)),
+                );
+            }
+        }
+        Ok(periods)
+    }
+
+    async fn get_calendar_names(&self) -> Result<Vec<String>> {
+        Ok(self
+            .collections()
+            .await?
+            .into_iter()
+            .filter_map(|c| c.display_name)
+            .collect())
+    }
+
+    async fn clear_calendar(&mut self, name: &str) -> Result<()> {
+        let url = self.collection_url(name).await?;
+        for event in self.events(&url, None).await? {
+            self.send("DELETE", &self.absolute(&event.href), None, None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        let url = self.collection_url(calendar).await?;
+        let body = ics::render(calendar, std::slice::from_ref(&event), Utc::now());
+        self.send(
+            "PUT",
+            &format!("{url}{}", resource_name(&event)),
+            None,
+            Some((CALENDAR, body)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn create_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        let url = self.collection_url(calendar).await?;
+        for event in events {
+            let body = ics::render(calendar, std::slice::from_ref(&event), Utc::now());
+            self.send(
+                "PUT",
+                &format!("{url}{}", resource_name(&event)),
+                None,
+                Some((CALENDAR, body)),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn create_calendar(&mut self, name: &str) -> Result<()> {
+        if self.get_calendar_names().await?.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        let slug: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:mkcalendar xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+<d:set><d:prop><d:displayname>{}</d:displayname></d:prop></d:set>
+</c:mkcalendar>"#,
+            escape(name)
+        );
+        let url = format!("{}{}/", self.config.url, slug);
+        self.send("MKCALENDAR", &url, None, Some((XML, body)))
+            .await
+            .map_err(|e| anyhow!(e).context(format!("Failed to create calendar {name}")))?;
+        Ok(())
+    }
+
+    async fn event_keys(&self, calendar: &str) -> Result<Vec<Option<String>>> {
+        let url = self.collection_url(calendar).await?;
+        Ok(self
+            .events(&url, None)
+            .await?
+            .iter()
+            .flat_map(|r| ics::parse(r.calendar_data.as_deref().unwrap_or_default()))
+            .map(|e| e.key)
+            .collect())
+    }
+
+    /// Events are stored under a name derived from their key, so a PUT
+    /// replaces the previous version.
+    async fn upsert_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        self.create_event(calendar, event).await
+    }
+
+    async fn upsert_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        self.create_events(calendar, events).await
+    }
+
+    async fn delete_events(&mut self, calendar: &str, keys: &[String]) -> Result<()> {
+        let url = self.collection_url(calendar).await?;
+        for resource in self.events(&url, None).await? {
+            let matches = ics::parse(resource.calendar_data.as_deref().unwrap_or_default())
+                .iter()
+                .any(|e| e.key.as_ref().is_some_and(|key| keys.contains(key)));
+            if matches {
+                self.send("DELETE", &self.absolute(&resource.href), None, None)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        Router,
+        extract::{Request, State},
+        http::{Method as HttpMethod, StatusCode as HttpStatus},
+        response::IntoResponse,
+    };
+    use chrono::TimeZone;
+    use tokio::net::TcpListener;
+
+    type Resources = Arc<Mutex<BTreeMap<String, String>>>;
+
+    const CALENDARS: &str = r#"<?xml version="1.0"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+ <D:response><D:href>/dav/</D:href>
+  <D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat>
+ </D:response>
+ <D:response><D:href>/dav/flying/</D:href>
+  <D:propstat><D:prop><D:displayname>Flying &amp; more</D:displayname>
+   <D:resourcetype><D:collection/><C:calendar/></D:resourcetype></D:prop></D:propstat>
+ </D:response>
+</D:multistatus>"#;
+
+    /// Just enough of a CalDAV server: one calendar, PUT, DELETE and a REPORT
+    /// that ignores filters.
+    async fn server(State(resources): State<Resources>, request: Request) -> impl IntoResponse {
+        let (parts, body) = request.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        let path = parts.uri.path().to_string();
+        let mut resources = resources.lock().unwrap();
+        match parts.method.as_str() {
+            "PROPFIND" => (HttpStatus::MULTI_STATUS, CALENDARS.to_string()),
+            "REPORT" => {
+                let responses: String = resources
+                    .iter()
+                    .map(|(href, data)| {
+                        format!(
+                            "<d:response><d:href>{href}</d:href><d:propstat><d:prop>\
+                             <cal:calendar-data><![CDATA[{data}]]></cal:calendar-data>\
+                             </d:prop></d:propstat></d:response>"
+                        )
+                    })
+                    .collect();
+                let xml = format!(
+                    r#"<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">{responses}</d:multistatus>"#
+                );
+                (HttpStatus::MULTI_STATUS, xml)
+            }
+            _ if parts.method == HttpMethod::PUT => {
+                let text = String::from_utf8(body.to_vec()).unwrap();
+                resources.insert(path, text);
+                (HttpStatus::CREATED, String::new())
+            }
+            _ if parts.method == HttpMethod::DELETE => match resources.remove(&path) {
+                Some(_) => (HttpStatus::NO_CONTENT, String::new()),
+                None => (HttpStatus::NOT_FOUND, String::new()),
+            },
+            _ => (HttpStatus::METHOD_NOT_ALLOWED, String::new()),
+        }
+    }
+
+    async fn calendar() -> (CalDavCalendar, Resources) {
+        let resources = Resources::default();
+        let app = Router::new().fallback(server).with_state(resources.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let http = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+        let config = CalDavConfig {
+            url: format!("http://{addr}/dav/"),
+            credentials: Some(("pilot".into(), "secret".into())),
+        };
+        (CalDavCalendar::new(http, config), resources)
+    }
+
+    fn event(start_h: u32, key: &str) -> CalendarEvent {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, start_h, 0, 0).unwrap();
+        CalendarEvent {
+            title: "Wank".into(),
+            start_time: start,
+            end_time: start + chrono::Duration::hours(2),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: Some(key.into()),
+            site: None,
+        }
+    }
+
+    #[test]
+    fn multistatus_finds_calendars_whatever_the_prefix() {
+        let resources = multistatus(CALENDARS).unwrap();
+        assert_eq!(resources.len(), 2);
+        assert!(!resources[0].is_calendar);
+        assert_eq!(
+            resources[1],
+            DavResource {
+                href: "/dav/flying/".into(),
+                display_name: Some("Flying & more".into()),
+                is_calendar: true,
+                calendar_data: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn events_are_put_under_stable_names_and_cleared() {
+        let (mut cal, resources) = calendar().await;
+        assert_eq!(cal.get_calendar_names().await.unwrap(), ["Flying & more"]);
+
+        cal.create_events(
+            "Flying & more",
+            vec![
+                event(10, "2026-06-13/Wank/1000"),
+                event(14, "2026-06-13/Wank/1400"),
+            ],
+        )
+        .await
+        .unwrap();
+        cal.create_event("Flying & more", event(10, "2026-06-13/Wank/1000"))
+            .await
+            .unwrap();
+
+        {
+            let resources = resources.lock().unwrap();
+            assert_eq!(resources.len(), 2, "{resources:?}");
+            let name = format!(
+                "/dav/flying/{}",
+                resource_name(&event(10, "2026-06-13/Wank/1000"))
+            );
+            let parsed = ics::parse(&resources[&name]);
+            assert_eq!(parsed[0].key.as_deref(), Some("2026-06-13/Wank/1000"));
+        }
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+        let flying = vec!["Flying & more".to_string()];
+        assert!(cal.is_busy(&flying, at(8), at(20)).await.unwrap());
+
+        cal.delete_events("Flying & more", &["2026-06-13/Wank/1400".into()])
+            .await
+            .unwrap();
+        assert_eq!(
+            cal.event_keys("Flying & more").await.unwrap(),
+            vec![Some("2026-06-13/Wank/1000".into())]
+        );
+
+        cal.clear_calendar("Flying & more").await.unwrap();
+        assert!(resources.lock().unwrap().is_empty());
+        assert!(!cal.is_busy(&flying, at(8), at(20)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn unknown_calendar_is_not_found() {
+        let (mut cal, _) = calendar().await;
+        let error = cal.create_event("Work", event(10, "k")).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<TravelAiError>().unwrap().code,
+            ErrorCode::CalendarNotFound
+        );
+    }
+}
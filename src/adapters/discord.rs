@@ -0,0 +1,90 @@
+//! Discord bot answering `!fly <place> [radius]` in the channels it can read
+//! and in direct messages, and posting the daily outlook to a channel. Needs
+//! the message content intent enabled in the developer portal.
+
+use std::num::NonZeroU64;
+
+use anyhow::Result;
+use serenity::{
+    Client,
+    all::{ChannelId, Context, EventHandler, GatewayIntents, Message},
+    async_trait,
+    http::Http,
+};
+
+use crate::{
+    app_state::AppState, application::chat_bot, config::DiscordConfig, domain::ports::Notifier,
+};
+
+/// Longest message Discord accepts
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+struct Handler {
+    state: AppState,
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn message(&self, ctx: Context, message: Message) {
+        if message.author.bot {
+            return;
+        }
+        let Some(command) = chat_bot::parse(&message.content) else {
+            return;
+        };
+        let reply = chat_bot::reply(&self.state, command).await;
+        if let Err(e) = message
+            .channel_id
+            .say(&ctx.http, chat_bot::fit(&reply, MAX_MESSAGE_CHARS))
+            .await
+        {
+            tracing::warn!(error = ?e, "Failed to answer on Discord");
+        }
+    }
+}
+
+/// Answers commands until the process exits.
+pub async fn run(state: AppState, config: DiscordConfig) {
+    let intents = GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT;
+    let client = Client::builder(&config.token, intents)
+        .event_handler(Handler { state })
+        .await;
+    let result = match client {
+        Ok(mut client) => client.start().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::error!(error = ?e, "Discord bot stopped");
+    }
+}
+
+/// Posts to one channel, without connecting to the gateway.
+pub struct DiscordNotifier {
+    http: Http,
+    channel_id: ChannelId,
+}
+
+impl DiscordNotifier {
+    pub fn new(config: &DiscordConfig, channel_id: NonZeroU64) -> Self {
+        Self {
+            http: Http::new(&config.token),
+            channel_id: ChannelId::from(channel_id),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn notify(&self, text: &str, _message_id: &str) -> Result<()> {
+        self.channel_id
+            .say(&self.http, chat_bot::fit(text, MAX_MESSAGE_CHARS))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use quick_xml::{Reader, events::Event};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::{
+    adapters::{cache::PersistentCache, open_meteo::openmeteo::weather_code_to_description},
+    domain::{
+        geo,
+        location::Location,
+        ports::WeatherProvider,
+        weather::{WeatherData, WeatherField, WeatherForecast, WeatherModel},
+    },
+};
+
+pub const MODEL_ID: &str = "dwd_mosmix";
+/// Names DWD in the provenance of scores
+pub const PROVIDER_ID: &str = "dwd";
+
+const STATION_CATALOG_URL: &str = "https://www.dwd.de/DE/leistungen/met_verfahren_mosmix/mosmix_stationskatalog.cfg?view=nasPublication&nn=16102";
+const STATION_FORECAST_URL: &str =
+    "https://opendata.dwd.de/weather/local_forecasts/mos/MOSMIX_L/single_stations";
+// MOSMIX is a station forecast; beyond this the nearest station says little
+// about the conditions at a launch.
+const MAX_STATION_DISTANCE_KM: f64 = 30.0;
+// MOSMIX_L is recomputed every six hours.
+const FORECAST_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+const CATALOG_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// MOSMIX elements behind the fields that get a stand-in when a station lacks them
+const ELEMENT_FIELDS: [(&str, WeatherField); 8] = [
+    ("Td", WeatherField::DewPoint),
+    ("FF", WeatherField::WindSpeed),
+    ("DD", WeatherField::WindDirection),
+    ("FX1", WeatherField::WindGust),
+    ("RR1c", WeatherField::Precipitation),
+    ("N", WeatherField::CloudCover),
+    ("PPPP", WeatherField::Pressure),
+    ("VV", WeatherField::Visibility),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MosmixStation {
+    pub id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: f64,
+}
+
+pub struct DwdMosmixClient {
+    cache: Arc<PersistentCache>,
+    http: ClientWithMiddleware,
+}
+
+impl DwdMosmixClient {
+    pub fn new(cache: Arc<PersistentCache>, http: ClientWithMiddleware) -> Self {
+        Self { cache, http }
+    }
+
+    async fn stations(&self) -> Result<Vec<MosmixStation>> {
+        let key = "mosmix_station_catalog";
+        if let Some(cached) = self.cache.get::<Vec<MosmixStation>>(key).await? {
+            return Ok(cached);
+        }
+
+        let catalog = self
+            .http
+            .get(STATION_CATALOG_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let stations = parse_station_catalog(&catalog);
+        if stations.is_empty() {
+            return Err(anyhow!("MOSMIX station catalog contained no stations"));
+        }
+
+        self.cache.put(key, stations.clone(), CATALOG_TTL).await?;
+        tracing::debug!(count = stations.len(), "Loaded MOSMIX station catalog");
+        Ok(stations)
+    }
+
+    async fn station_kml(&self, station_id: &str) -> Result<String> {
+        let url = format!(
+            "{}/{}/kml/MOSMIX_L_LATEST_{}.kmz",
+            STATION_FORECAST_URL, station_id, station_id
+        );
+        let kmz = self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(kmz))?;
+        let mut kml = String::new();
+        archive.by_index(0)?.read_to_string(&mut kml)?;
+        Ok(kml)
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for DwdMosmixClient {
+    #[instrument(skip_all, fields(lat = %source.latitude, lon = %source.longitude))]
+    async fn get_forecast(
+        &self,
+        source: Location,
+        _model: Option<String>,
+    ) -> Result<WeatherForecast> {
+        let stations = self.stations().await?;
+        let station = nearest_station(&stations, &source, MAX_STATION_DISTANCE_KM).ok_or(
+            anyhow!("No MOSMIX station within {MAX_STATION_DISTANCE_KM} km"),
+        )?;
+
+        let key = format!("weather_for_mosmix_{}", station.id);
+        if let Some(cached) = self.cache.get::<WeatherForecast>(&key).await? {
+            return Ok(cached);
+        }
+
+        let kml = self.station_kml(&station.id).await?;
+        let forecast = parse_kml(&kml)
+            .with_context(|| format!("Failed to parse MOSMIX forecast for {}", station.id))?
+            .into_forecast(source);
+
+        self.cache.put(&key, forecast.clone(), FORECAST_TTL).await?;
+        tracing::debug!(station = %station.id, "MOSMIX fetch successful");
+        Ok(forecast)
+    }
+
+    fn available_models(&self) -> Vec<WeatherModel> {
+        vec![WeatherModel {
+            id: MODEL_ID.to_string(),
+            name: "MOSMIX (DWD station forecast)".to_string(),
+        }]
+    }
+}
+
+fn nearest_station<'a>(
+    stations: &'a [MosmixStation],
+    location: &Location,
+    max_distance_km: f64,
+) -> Option<&'a MosmixStation> {
+    stations
+        .iter()
+        .map(|s| {
+            let distance = geo::distance_km(location.coordinates(), (s.latitude, s.longitude));
+            (s, distance)
+        })
+        .filter(|(_, distance)| *distance <= max_distance_km)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(s, _)| s)
+}
+
+/// The catalog writes coordinates as degrees.minutes, e.g. `50.43` is 50°43'.
+fn degrees_minutes_to_decimal(value: f64) -> f64 {
+    let degrees = value.abs().trunc();
+    let minutes = (value.abs() - degrees) * 100.0;
+    (degrees + minutes / 60.0).copysign(value)
+}
+
+/// Parses the fixed-width MOSMIX station catalog. Station names contain spaces,
+/// so the numeric columns are read from the end of each line.
+fn parse_station_catalog(catalog: &str) -> Vec<MosmixStation> {
+    catalog
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() < 5 {
+                return None;
+            }
+            let n = tokens.len();
+            let latitude: f64 = tokens[n - 3].parse().ok()?;
+            let longitude: f64 = tokens[n - 2].parse().ok()?;
+            let elevation: f64 = tokens[n - 1].parse().ok()?;
+            // The ICAO column is "----" for stations without one.
+            let name = tokens[2..n - 3].join(" ");
+            Some(MosmixStation {
+                id: tokens[0].to_string(),
+                name,
+                latitude: degrees_minutes_to_decimal(latitude),
+                longitude: degrees_minutes_to_decimal(longitude),
+                elevation,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct MosmixForecast {
+    station_elevation: Option<f64>,
+    time_steps: Vec<DateTime<Utc>>,
+    elements: HashMap<String, Vec<Option<f32>>>,
+}
+
+impl MosmixForecast {
+    fn value(&self, element: &str, i: usize) -> Option<f32> {
+        self.elements.get(element)?.get(i).copied().flatten()
+    }
+
+    fn into_forecast(self, location: Location) -> WeatherForecast {
+        let forecast = (0..self.time_steps.len())
+            .filter_map(|i| {
+                // Steps without a temperature are past the end of the forecast.
+                let temperature = WeatherData::kelvin_to_celsius(self.value("TTT", i)?);
+                let wind_speed_ms = self.value("FF", i).unwrap_or(0.0);
+                let weather_code = self.value("ww", i).unwrap_or(0.0) as u8;
+                Some(WeatherData {
+                    timestamp: self.time_steps[i],
+                    temperature,
+                    dew_point: self
+                        .value("Td", i)
+                        .map(WeatherData::kelvin_to_celsius)
+                        .unwrap_or(temperature),
+                    wind_speed_ms,
+                    wind_direction: self.value("DD", i).unwrap_or(0.0).round() as u16 % 360,
+                    wind_gust_ms: self.value("FX1", i).unwrap_or(wind_speed_ms),
+                    precipitation: self.value("RR1c", i).unwrap_or(0.0),
+                    precipitation_probability: self.value("wwP", i).map(|p| p.round() as u8),
+                    cloud_cover: self.value("N", i).unwrap_or(0.0).round() as u8,
+                    pressure: self.value("PPPP", i).map(|pa| pa / 100.0).unwrap_or(1013.0),
+                    visibility: self.value("VV", i).map(|m| m / 1000.0).unwrap_or(999.0),
+                    description: weather_code_to_description(weather_code).to_string(),
+                    // 1 kg/m² of snow water is roughly 1 cm of fresh snow.
+                    snowfall_cm: self.value("RRS1c", i).unwrap_or(0.0),
+                    snow_depth_m: 0.0,
+                    boundary_layer_height_m: None,
+                    wind_speed_80m_ms: None,
+                    wind_direction_80m: None,
+                    wind_speed_850hpa_ms: None,
+                    wind_direction_850hpa: None,
+                    temperature_850hpa: None,
+                    height_850hpa_m: None,
+                    cape: None,
+                    freezing_level_m: None,
+                    model: Some(MODEL_ID.to_string()),
+                    missing: ELEMENT_FIELDS
+                        .iter()
+                        .filter(|(element, _)| self.value(element, i).is_none())
+                        .map(|&(_, field)| field)
+                        .collect(),
+                })
+            })
+            .collect();
+
+        WeatherForecast {
+            location,
+            grid_elevation: self.station_elevation,
+            fetched_at: Some(Utc::now()),
+            provider: Some(PROVIDER_ID.to_string()),
+            forecast,
+        }
+    }
+}
+
+fn parse_kml(xml: &str) -> Result<MosmixForecast> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut forecast = MosmixForecast::default();
+    let mut in_time_step = false;
+    let mut in_value = false;
+    let mut in_coordinates = false;
+    let mut current_element: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"TimeStep" => in_time_step = true,
+                b"value" => in_value = true,
+                b"coordinates" => in_coordinates = true,
+                b"Forecast" => {
+                    current_element = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.local_name().as_ref() == b"elementName")
+                        .map(|a| String::from_utf8_lossy(&a.value).to_string());
+                }
+                _ => {}
+            },
+            Event::Text(e) => {
+                let text = e.xml_content()?;
+                if in_time_step {
+                    forecast
+                        .time_steps
+                        .push(DateTime::parse_from_rfc3339(text.trim())?.with_timezone(&Utc));
+                } else if in_value && let Some(element) = &current_element {
+                    let values = text
+                        .split_whitespace()
+                        .map(|v| v.parse::<f32>().ok())
+                        .collect();
+                    forecast.elements.insert(element.clone(), values);
+                } else if in_coordinates {
+                    forecast.station_elevation =
+                        text.trim().split(',').nth(2).and_then(|e| e.parse().ok());
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"TimeStep" => in_time_step = false,
+                b"value" => in_value = false,
+                b"coordinates" => in_coordinates = false,
+                b"Forecast" => current_element = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if forecast.time_steps.is_empty() {
+        return Err(anyhow!("MOSMIX document has no forecast time steps"));
+    }
+    Ok(forecast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const CATALOG: &str = "\
+ID    ICAO NAME                 LAT    LON     ELEV
+----- ---- -------------------- -----  ------- -----
+10578 ---- FICHTELBERG           50.26   12.57  1213
+10488 EDDC DRESDEN/KLOTZSCHE     51.08   13.45   230
+";
+
+    const KML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1" standalone="no"?>
+<kml:kml xmlns:dwd="https://opendata.dwd.de/weather/lib/pointforecast_dwd_extension_V1_0.xsd" xmlns:kml="http://www.opengis.net/kml/2.2">
+  <kml:Document>
+    <kml:ExtendedData>
+      <dwd:ProductDefinition>
+        <dwd:ForecastTimeSteps>
+          <dwd:TimeStep>2026-06-13T10:00:00.000Z</dwd:TimeStep>
+          <dwd:TimeStep>2026-06-13T11:00:00.000Z</dwd:TimeStep>
+          <dwd:TimeStep>2026-06-13T12:00:00.000Z</dwd:TimeStep>
+        </dwd:ForecastTimeSteps>
+      </dwd:ProductDefinition>
+    </kml:ExtendedData>
+    <kml:Placemark>
+      <kml:name>10578</kml:name>
+      <kml:ExtendedData>
+        <dwd:Forecast dwd:elementName="TTT">
+          <dwd:value>     288.15     290.15          -</dwd:value>
+        </dwd:Forecast>
+        <dwd:Forecast dwd:elementName="FF">
+          <dwd:value>       3.00       4.50          -</dwd:value>
+        </dwd:Forecast>
+        <dwd:Forecast dwd:elementName="PPPP">
+          <dwd:value>  101300.00  101200.00          -</dwd:value>
+        </dwd:Forecast>
+      </kml:ExtendedData>
+      <kml:Point>
+        <kml:coordinates>12.95,50.43,1213.0</kml:coordinates>
+      </kml:Point>
+    </kml:Placemark>
+  </kml:Document>
+</kml:kml>"#;
+
+    #[rstest]
+    #[case(50.26, 50.0 + 26.0 / 60.0)]
+    #[case(-8.40, -(8.0 + 40.0 / 60.0))]
+    #[case(13.0, 13.0)]
+    fn degrees_minutes_cases(#[case] input: f64, #[case] expected: f64) {
+        assert!((degrees_minutes_to_decimal(input) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn station_catalog_skips_headers_and_keeps_multi_word_names() {
+        let stations = parse_station_catalog(CATALOG);
+        assert_eq!(stations.len(), 2);
+        assert_eq!(stations[0].id, "10578");
+        assert_eq!(stations[0].elevation, 1213.0);
+        assert_eq!(stations[1].name, "DRESDEN/KLOTZSCHE");
+    }
+
+    #[test]
+    fn nearest_station_respects_max_distance() {
+        let stations = parse_station_catalog(CATALOG);
+        let oberwiesenthal = Location::new(50.42, 12.97, "Launch".into(), "DE".into());
+        let station = nearest_station(&stations, &oberwiesenthal, MAX_STATION_DISTANCE_KM);
+        assert_eq!(station.unwrap().id, "10578");
+
+        let berlin = Location::new(52.52, 13.40, "Berlin".into(), "DE".into());
+        assert!(nearest_station(&stations, &berlin, MAX_STATION_DISTANCE_KM).is_none());
+    }
+
+    #[test]
+    fn parse_kml_converts_units_and_drops_missing_steps() {
+        let location = Location::new(50.43, 12.95, "Fichtelberg".into(), "DE".into());
+        let forecast = parse_kml(KML).unwrap().into_forecast(location);
+
+        assert_eq!(forecast.grid_elevation, Some(1213.0));
+        assert_eq!(forecast.forecast.len(), 2);
+        let first = &forecast.forecast[0];
+        assert!((first.temperature - 15.0).abs() < 1e-3);
+        assert_eq!(first.wind_speed_ms, 3.0);
+        assert!((first.pressure - 1013.0).abs() < 1e-3);
+        assert_eq!(
+            forecast.forecast[1].timestamp,
+            DateTime::parse_from_rfc3339("2026-06-13T11:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_kml_rejects_documents_without_time_steps() {
+        assert!(parse_kml("<kml:kml xmlns:kml=\"x\"></kml:kml>").is_err());
+    }
+}
@@ -3,12 +3,16 @@ use lettre::{
     Message, Transport, transport::smtp::SmtpTransport,
     transport::smtp::authentication::Credentials,
 };
-use std::env;
+
+use crate::{
+    config,
+    domain::error::{ErrorCode, TravelAiError},
+};
 
 fn create_mailer() -> Result<SmtpTransport> {
-    let gmail_address = env::var("GMAIL_ADDRESS").context("Missing GMAIL_ADDRESS env var")?;
+    let gmail_address = config::var("GMAIL_ADDRESS").context("Missing GMAIL_ADDRESS env var")?;
     let gmail_app_password =
-        env::var("GMAIL_APP_PASSWORD").context("Missing GMAIL_APP_PASSWORD env var")?;
+        config::secret("GMAIL_APP_PASSWORD").context("Missing GMAIL_APP_PASSWORD env var")?;
 
     let credentials = Credentials::new(gmail_address, gmail_app_password);
 
@@ -21,8 +25,25 @@ fn create_mailer() -> Result<SmtpTransport> {
 
 pub async fn send_auth_link(url: &str) -> Result<()> {
     let notification_email =
-        env::var("NOTIFICATION_EMAIL").context("Missing NOTIFICATION_EMAIL env var")?;
-    let gmail_address = env::var("GMAIL_ADDRESS").context("Missing GMAIL_ADDRESS env var")?;
+        config::var("NOTIFICATION_EMAIL").context("Missing NOTIFICATION_EMAIL env var")?;
+    send(
+        &notification_email,
+        "Google Calendar Authentication Link",
+        &format!(
+            "Click the following link to authenticate with Google Calendar:\n\n{}\n\nAfter clicking, grant permissions and you'll be redirected back.",
+            url
+        ),
+    )
+    .await?;
+
+    tracing::info!("Sent authentication link email");
+
+    Ok(())
+}
+
+/// Sends a plain text email from the Gmail account.
+pub async fn send(to: &str, subject: &str, body: &str) -> Result<()> {
+    let gmail_address = config::var("GMAIL_ADDRESS").context("Missing GMAIL_ADDRESS env var")?;
 
     let email = Message::builder()
         .from(
@@ -30,22 +51,21 @@ pub async fn send_auth_link(url: &str) -> Result<()> {
                 .parse()
                 .context("Failed to parse from address")?,
         )
-        .to(
-            notification_email
-                .parse()
-                .context("Failed to parse to address")?,
-        )
-        .subject("Google Calendar Authentication Link")
-        .body(format!(
-            "Click the following link to authenticate with Google Calendar:\n\n{}\n\nAfter clicking, grant permissions and you'll be redirected back.",
-            url
-        ))?;
+        .to(to.parse().context("Failed to parse to address")?)
+        .subject(subject)
+        .body(body.to_string())?;
 
     let mailer = create_mailer()?;
 
-    mailer.send(&email).context("Failed to send email")?;
-
-    tracing::info!("Sent authentication link email");
+    mailer.send(&email).map_err(|e| {
+        if e.is_permanent() {
+            anyhow::Error::new(e).context("Failed to send email")
+        } else {
+            TravelAiError::new(ErrorCode::NotificationUnavailable, "Failed to send email")
+                .with_source(e)
+                .into()
+        }
+    })?;
 
     Ok(())
 }
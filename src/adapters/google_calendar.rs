@@ -1,34 +1,47 @@
-use std::{
-    hash::{DefaultHasher, Hash, Hasher},
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
-use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use futures::{StreamExt, TryStreamExt, stream};
 use google_apis_common::GetToken;
 use google_calendar3::{
     CalendarHub,
     api::{
-        CalendarList, Event, EventDateTime, FreeBusyRequest, FreeBusyRequestItem,
-        Scope,
+        CalendarList, Event, EventDateTime, EventExtendedProperties, EventReminder, EventReminders,
+        FreeBusyRequest, FreeBusyRequestItem, Scope,
     },
+    yup_oauth2,
 };
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use hyper_util::{
+    client::legacy::{Client, connect::HttpConnector},
+    rt::TokioExecutor,
+};
 use oauth2::{
     AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, RedirectUrl,
     Scope as OAuthScope, TokenResponse, TokenUrl, basic::BasicClient,
 };
+use tokio::sync::OnceCell;
 use tracing::instrument;
 
 use crate::{
     adapters::{cache::PersistentCache, email},
-    domain::{calendar::CalendarEvent, ports::CalendarProvider},
+    application::retry::Retry,
+    domain::{
+        calendar::{CalendarEvent, EventColor},
+        error::{ErrorCode, RETRY_AFTER_KEY, TravelAiError},
+        ports::CalendarProvider,
+    },
 };
 
 const TOKEN_CACHE_KEY: &str = "calendar_token";
+const EMAIL_ATTEMPTS: u32 = 3;
+/// Event requests in flight at once when clearing or filling a calendar, well
+/// below what Google's per-user rate limit allows
+const CONCURRENT_REQUESTS: usize = 8;
+/// Attempts per event request, for riding out a rate limit or quota error
+const REQUEST_ATTEMPTS: u32 = 4;
 
 const SCOPES: [&str; 3] = [
     "https://www.googleapis.com/auth/calendar.calendarlist.readonly",
@@ -42,6 +55,34 @@ pub struct WebFlowAuthenticator {
     cache: Arc<PersistentCache>,
 }
 
+/// State of the stored Google sign-in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenHealth {
+    Valid,
+    /// Nobody signed in yet
+    Missing,
+    /// No refresh token, and the access token runs out soon
+    Expiring,
+    /// Google refused the refresh token, e.g. because access was revoked
+    Revoked,
+}
+
+impl TokenHealth {
+    /// Why signing in again is needed, if it is.
+    pub fn problem(self) -> Option<&'static str> {
+        match self {
+            TokenHealth::Valid => None,
+            TokenHealth::Missing => Some("nobody is signed in to Google Calendar"),
+            TokenHealth::Expiring => Some("the Google Calendar sign-in expires soon"),
+            TokenHealth::Revoked => Some("Google Calendar access was revoked or has expired"),
+        }
+    }
+}
+
+/// How long before a token without refresh token expires that it counts as
+/// expiring
+const TOKEN_EXPIRY_WARNING: chrono::Duration = chrono::Duration::hours(2);
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StoredToken {
     pub access_token: String,
@@ -99,9 +140,14 @@ impl WebFlowAuthenticator {
             let (auth_url, csrf_state) = self.build_authorization_url();
 
             tracing::info!("Sending authentication URL via email");
-            email::send_auth_link(&auth_url)
-                .await
-                .context("Failed to send auth email")?;
+            let mut retry = Retry::new("auth email", EMAIL_ATTEMPTS);
+            loop {
+                match email::send_auth_link(&auth_url).await {
+                    Err(e) if retry.wait(&e).await => continue,
+                    result => break result,
+                }
+            }
+            .context("Failed to send auth email")?;
 
             let _ = csrf_state;
 
@@ -126,7 +172,10 @@ impl WebFlowAuthenticator {
             .exchange_code(AuthorizationCode::new(code.to_string()))
             .request_async(oauth2::reqwest::async_http_client)
             .await
-            .context("Failed to exchange code for token")?;
+            .map_err(|e| {
+                TravelAiError::new(ErrorCode::CalendarAuthExpired, "Google sign-in failed")
+                    .with_source(e)
+            })?;
 
         let access_token = token_response.access_token().secret().clone();
         let refresh_token = token_response.refresh_token().map(|t| t.secret().clone());
@@ -163,7 +212,19 @@ impl WebFlowAuthenticator {
             .exchange_refresh_token(&oauth2::RefreshToken::new(refresh_token.to_string()))
             .request_async(oauth2::reqwest::async_http_client)
             .await
-            .context("Failed to refresh token")?;
+            .map_err(|e| match e {
+                // Google answered and refused, e.g. because access was revoked
+                oauth2::RequestTokenError::ServerResponse(_) => TravelAiError::new(
+                    ErrorCode::CalendarAuthExpired,
+                    "Google access expired, sign in again",
+                )
+                .with_source(e),
+                _ => TravelAiError::new(
+                    ErrorCode::CalendarUnavailable,
+                    "Failed to reach Google to refresh access",
+                )
+                .with_source(e),
+            })?;
 
         let access_token = token_response.access_token().secret().clone();
         let new_refresh_token = token_response
@@ -195,6 +256,33 @@ impl WebFlowAuthenticator {
         Ok(stored_token)
     }
 
+    /// Whether the stored sign-in still works, found by refreshing it, so a
+    /// revoked one is noticed before a sync fails on it.
+    pub async fn check(&self) -> Result<TokenHealth> {
+        let Some(token) = self.cache.get::<StoredToken>(TOKEN_CACHE_KEY).await? else {
+            return Ok(TokenHealth::Missing);
+        };
+        let Some(refresh_token) = token.refresh_token else {
+            let expires_soon = token.expiry <= (Utc::now() + TOKEN_EXPIRY_WARNING).timestamp();
+            return Ok(if expires_soon {
+                TokenHealth::Expiring
+            } else {
+                TokenHealth::Valid
+            });
+        };
+        match self.refresh_token(&refresh_token).await {
+            Ok(_) => Ok(TokenHealth::Valid),
+            Err(e)
+                if TravelAiError::find(&e)
+                    .is_some_and(|e| e.code == ErrorCode::CalendarAuthExpired) =>
+            {
+                tracing::warn!(error = ?e, "Google refused the refresh token");
+                Ok(TokenHealth::Revoked)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     async fn get_token_internal(&self) -> Result<Option<String>> {
         let token = self
             .cache
@@ -264,8 +352,112 @@ impl Clone for WebFlowAuthenticator {
     }
 }
 
-pub type CalendarHubType =
-    CalendarHub<HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>>;
+type ServiceAccountAuthenticator =
+    yup_oauth2::authenticator::Authenticator<HttpsConnector<HttpConnector>>;
+
+/// Signs in with a service account key instead of a pilot's browser sign-in,
+/// for club servers nobody is around to sign in on.
+pub struct ServiceAccount {
+    key_file: PathBuf,
+    subject: Option<String>,
+    /// Built on first use, then reused so its access token is too
+    authenticator: OnceCell<ServiceAccountAuthenticator>,
+}
+
+impl ServiceAccount {
+    /// Acts as `subject` if given, which needs domain-wide delegation of the
+    /// calendar scopes to the account.
+    pub fn new(key_file: PathBuf, subject: Option<String>) -> Self {
+        Self {
+            key_file,
+            subject,
+            authenticator: OnceCell::new(),
+        }
+    }
+
+    async fn authenticator(&self) -> Result<ServiceAccountAuthenticator> {
+        self.authenticator
+            .get_or_try_init(|| async {
+                let key = yup_oauth2::read_service_account_key(&self.key_file)
+                    .await
+                    .map_err(|e| {
+                        TravelAiError::new(
+                            ErrorCode::Configuration,
+                            "Failed to read the Google service account key",
+                        )
+                        .with_source(e)
+                        .with_context("key_file", self.key_file.display())
+                    })?;
+                let mut builder = yup_oauth2::ServiceAccountAuthenticator::with_client(
+                    key,
+                    yup_oauth2::CustomHyperClientBuilder::from(https_client()?),
+                );
+                if let Some(subject) = &self.subject {
+                    builder = builder.subject(subject);
+                }
+                builder.build().await.map_err(|e| {
+                    TravelAiError::new(
+                        ErrorCode::Configuration,
+                        "Invalid Google service account key",
+                    )
+                    .with_source(e)
+                    .into()
+                })
+            })
+            .await
+            .cloned()
+    }
+}
+
+/// Where the Google backend gets its access tokens.
+#[derive(Clone)]
+pub enum GoogleAuth {
+    OAuth(Arc<WebFlowAuthenticator>),
+    ServiceAccount(ServiceAccountAuthenticator),
+}
+
+impl GoogleAuth {
+    pub async fn service_account(account: &ServiceAccount) -> Result<Self> {
+        Ok(GoogleAuth::ServiceAccount(account.authenticator().await?))
+    }
+}
+
+impl GetToken for GoogleAuth {
+    fn get_token<'a>(
+        &'a self,
+        scopes: &'a [&str],
+    ) -> std::pin::Pin<
+        Box<
+            dyn std::future::Future<
+                    Output = Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>,
+                > + Send
+                + 'a,
+        >,
+    > {
+        match self {
+            GoogleAuth::OAuth(auth) => auth.get_token(scopes),
+            GoogleAuth::ServiceAccount(auth) => auth.get_token(scopes),
+        }
+    }
+}
+
+/// An HTTPS client sending `B` bodies, which differ between the calendar hub
+/// and the service account sign-in.
+fn https_client<B>() -> Result<Client<HttpsConnector<HttpConnector>, B>>
+where
+    B: hyper::body::Body + Send,
+    B::Data: Send,
+{
+    let connector = HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .context("Failed to build HTTPS connector")?
+        .https_only()
+        .enable_http2()
+        .build();
+    Ok(Client::builder(TokioExecutor::new()).build(connector))
+}
+
+pub type CalendarHubType = CalendarHub<HttpsConnector<HttpConnector>>;
 
 pub struct GoogleCalendar {
     hub: CalendarHubType,
@@ -273,20 +465,8 @@ pub struct GoogleCalendar {
 }
 
 impl GoogleCalendar {
-    pub async fn new(
-        auth: Arc<WebFlowAuthenticator>,
-        cache: Arc<PersistentCache>,
-    ) -> Result<Self> {
-        let connector = HttpsConnectorBuilder::new()
-            .with_native_roots()
-            .context("Failed to build HTTPS connector")?
-            .https_only()
-            .enable_http2()
-            .build();
-
-        let hyper_client = Client::builder(TokioExecutor::new()).build(connector);
-        let auth = (*auth).clone();
-        let hub = CalendarHub::new(hyper_client, auth);
+    pub async fn new(auth: GoogleAuth, cache: Arc<PersistentCache>) -> Result<Self> {
+        let hub = CalendarHub::new(https_client()?, auth);
         Ok(GoogleCalendar { hub, cache })
     }
 
@@ -298,7 +478,7 @@ impl GoogleCalendar {
         }
 
         let list = self.get_calendar_list().await?;
-        let lists = list.items.ok_or(anyhow!("Empty calendar list"))?;
+        let lists = list.items.ok_or_else(|| calendar_not_found(name))?;
         let result = lists
             .iter()
             .filter(|l| {
@@ -319,10 +499,190 @@ impl GoogleCalendar {
                 .await?;
             Ok(id.to_owned())
         } else {
-            Err(anyhow!("Calendar id not found for name {}", name))
+            Err(calendar_not_found(name).into())
+        }
+    }
+
+    async fn delete_event(&self, calendar_id: &str, event_id: &str) -> Result<()> {
+        let mut retry = Retry::new("calendar event delete", REQUEST_ATTEMPTS);
+        loop {
+            let outcome = self
+                .hub
+                .events()
+                .delete(calendar_id, event_id)
+                .add_scope(Scope::AppCreated)
+                .doit()
+                .await
+                .map_err(|e| anyhow::Error::from(calendar_error(e, "Failed to delete event")));
+            match outcome {
+                Err(e) if retry.wait(&e).await => continue,
+                outcome => return outcome.map(|_| ()),
+            }
+        }
+    }
+
+    async fn insert_event(&self, calendar_id: &str, event: Event) -> Result<()> {
+        let mut retry = Retry::new("calendar event insert", REQUEST_ATTEMPTS);
+        loop {
+            let outcome = self
+                .hub
+                .events()
+                .insert(event.clone(), calendar_id)
+                .add_scope(Scope::AppCreated)
+                .doit()
+                .await
+                .map_err(|e| anyhow::Error::from(calendar_error(e, "Failed to create event")));
+            match outcome {
+                Err(e) if retry.wait(&e).await => continue,
+                outcome => return outcome.map(|_| ()),
+            }
+        }
+    }
+
+    async fn update_event(&self, calendar_id: &str, event_id: &str, event: Event) -> Result<()> {
+        let mut retry = Retry::new("calendar event update", REQUEST_ATTEMPTS);
+        loop {
+            let outcome = self
+                .hub
+                .events()
+                .update(event.clone(), calendar_id, event_id)
+                .add_scope(Scope::AppCreated)
+                .doit()
+                .await
+                .map_err(|e| anyhow::Error::from(calendar_error(e, "Failed to update event")));
+            match outcome {
+                Err(e) if retry.wait(&e).await => continue,
+                outcome => return outcome.map(|_| ()),
+            }
+        }
+    }
+
+    /// Key and id of every event in the calendar with `calendar_id`.
+    async fn list_events(&self, calendar_id: &str) -> Result<Vec<(Option<String>, String)>> {
+        let mut page_token: Option<String> = None;
+        let mut events = Vec::new();
+        loop {
+            let mut request = self
+                .hub
+                .events()
+                .list(calendar_id)
+                .add_scope(Scope::AppCreated);
+            if let Some(ref token) = page_token {
+                request = request.page_token(token);
+            }
+            let (_, list) = request
+                .doit()
+                .await
+                .map_err(|e| calendar_error(e, "Failed to list events"))?;
+
+            for e in list.items.unwrap_or_default() {
+                let key = e
+                    .extended_properties
+                    .as_ref()
+                    .and_then(|p| p.private.as_ref()?.get(EVENT_KEY_PROPERTY).cloned());
+                match e.id {
+                    Some(event_id) => events.push((key, event_id)),
+                    None => tracing::warn!(event = ?e.summary, "Event has no event_id"),
+                }
+            }
+            page_token = list.next_page_token;
+            if page_token.is_none() {
+                return Ok(events);
+            }
         }
     }
 
+    /// Deletes the events with `event_ids`, several at a time.
+    async fn delete_all(&self, calendar_id: &str, event_ids: Vec<String>) -> Result<()> {
+        stream::iter(event_ids)
+            .map(|event_id| async move { self.delete_event(calendar_id, &event_id).await })
+            .buffer_unordered(CONCURRENT_REQUESTS)
+            .try_collect::<Vec<()>>()
+            .await
+            .map(|_| ())
+    }
+
+    /// Busy periods of the calendars with `calendar_ids` on the days from
+    /// `start` to `end`. Days not cached yet are queried together.
+    async fn query_busy_periods(
+        &self,
+        calendar_ids: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Period>> {
+        let days = days_between(start, end);
+        let mut periods = Vec::new();
+        let mut missing: Vec<(&String, NaiveDate)> = Vec::new();
+        for id in calendar_ids {
+            for &day in &days {
+                match self.cache.get::<Vec<Period>>(&busy_key(id, day)).await? {
+                    Some(cached) => periods.extend(cached),
+                    None => missing.push((id, day)),
+                }
+            }
+        }
+        let (Some(first), Some(last)) = (
+            missing.iter().map(|(_, day)| *day).min(),
+            missing.iter().map(|(_, day)| *day).max(),
+        ) else {
+            return Ok(periods);
+        };
+
+        let mut ids: Vec<&String> = missing.iter().map(|(id, _)| *id).collect();
+        ids.dedup();
+        let (_, response) = self
+            .hub
+            .freebusy()
+            .query(FreeBusyRequest {
+                items: Some(
+                    ids.iter()
+                        .map(|id| FreeBusyRequestItem {
+                            id: Some(id.to_string()),
+                        })
+                        .collect(),
+                ),
+                time_min: Some(midnight(first)),
+                time_max: Some(midnight(last + Days::new(1))),
+                group_expansion_max: None,
+                calendar_expansion_max: None,
+                time_zone: None,
+            })
+            .add_scope(Scope::Freebusy)
+            .doit()
+            .await
+            .map_err(|e| calendar_error(e, "Failed to query busy times"))?;
+
+        let calendars = response.calendars.unwrap_or_default();
+        for (id, day) in missing {
+            let Some(calendar) = calendars.get(id) else {
+                continue;
+            };
+            if let Some(errors) = calendar.errors.as_ref().filter(|e| !e.is_empty()) {
+                tracing::warn!(calendar = %id, ?errors, "Busy times of calendar unavailable");
+                continue;
+            }
+            let busy: Vec<Period> = calendar
+                .busy
+                .iter()
+                .flatten()
+                .filter_map(|period| Some((period.start?, period.end?)))
+                .collect();
+            let on_day = periods_on(&busy, day);
+            self.cache
+                .put(&busy_key(id, day), on_day.clone(), BUSY_TTL)
+                .await?;
+            periods.extend(on_day);
+        }
+        Ok(periods)
+    }
+
+    /// Drops the cached busy days of a calendar the app just wrote to.
+    async fn forget_busy_periods(&self, calendar_id: &str) -> Result<()> {
+        self.cache
+            .remove_starting_with(&busy_prefix(calendar_id))
+            .await
+    }
+
     async fn get_calendar_list(&self) -> Result<CalendarList> {
         let (_, lists) = self
             .hub
@@ -330,7 +690,8 @@ impl GoogleCalendar {
             .list()
             .add_scope(Scope::CalendarlistReadonly)
             .doit()
-            .await?;
+            .await
+            .map_err(|e| calendar_error(e, "Failed to list calendars"))?;
         Ok(lists)
     }
 }
@@ -338,150 +699,129 @@ impl GoogleCalendar {
 #[async_trait]
 impl CalendarProvider for GoogleCalendar {
     #[instrument(skip(self))]
-    async fn is_busy(
+    async fn busy_periods(
         &self,
-        calendars: &Vec<String>,
+        calendars: &[String],
         start: DateTime<Utc>,
         end: DateTime<Utc>,
-    ) -> Result<bool> {
-        let items = futures::future::join_all(
-            calendars
-                .iter()
-                .map(async |n| {
-                    let id = match self.get_id_for_name(n).await {
-                        Ok(id) => Some(id),
-                        Err(err) => {
-                            tracing::warn!(name = %n, error = ?err, "Cant get id for calendar");
-                            None
-                        }
-                    };
-                    FreeBusyRequestItem { id }
-                })
-                .collect::<Vec<_>>(),
-        )
-        .await;
-
-        let start_weekday = start.weekday().num_days_from_monday() as u64;
-        let end_weekday = end.weekday().num_days_from_monday() as u64;
-        let week_start_datetime = start.date_naive().and_time(NaiveTime::MIN).and_utc()
-            - Duration::from_hours(24u64 * start_weekday);
-        let week_end_datetime = end
-            .date_naive()
-            .and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap())
-            .and_utc()
-            + Duration::from_hours(24u64 * (7u64 - end_weekday));
-
-        let mut hasher = DefaultHasher::new();
-        calendars.hash(&mut hasher);
-        week_start_datetime.hash(&mut hasher);
-        week_end_datetime.hash(&mut hasher);
-        let cache_key = format!("Calendar_free_busy_hash_{}", hasher.finish());
-
-        let busy = {
-            if let Some(busy) = self.cache.get(&cache_key).await? {
-                busy
-            } else {
-                let (_, busy) = self
-                    .hub
-                    .freebusy()
-                    .query(FreeBusyRequest {
-                        items: Some(items.clone()),
-                        time_min: Some(week_start_datetime),
-                        time_max: Some(week_end_datetime),
-                        group_expansion_max: None,
-                        calendar_expansion_max: None,
-                        time_zone: None,
-                    })
-                    .add_scope(Scope::Freebusy)
-                    .doit()
-                    .await?;
-
-                self.cache
-                    .put(&cache_key, busy.clone(), Duration::from_mins(5))
-                    .await?;
-                busy
-            }
-        };
-
-        let mut b: bool = false;
-
-        if let Some(freebusy) = busy.calendars {
-            b = items
-                .iter()
-                .filter_map(|i| i.id.clone())
-                .filter_map(|i| {
-                    if let Some(fb) = freebusy.get(&i) {
-                        return fb.busy.clone();
-                    }
+    ) -> Result<Vec<Period>> {
+        let ids: Vec<String> = futures::future::join_all(calendars.iter().map(async |n| {
+            match self.get_id_for_name(n).await {
+                Ok(id) => Some(id),
+                Err(err) => {
+                    tracing::warn!(name = %n, error = ?err, "Cant get id for calendar");
                     None
-                })
-                .flatten()
-                .any(|tp| start < tp.end.unwrap() && end > tp.start.unwrap());
-        }
+                }
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut busy = self.query_busy_periods(&ids, start, end).await?;
+        busy.retain(|(busy_start, busy_end)| start < *busy_end && end > *busy_start);
         tracing::debug!(
             start = %start,
             end = %end,
-            busy = b,
-            "Range busy/free check"
+            periods = busy.len(),
+            "Busy periods queried"
         );
-        Ok(b)
+        Ok(busy)
     }
 
     #[instrument(skip(self), fields(calendar = %name))]
     async fn clear_calendar(&mut self, name: &str) -> anyhow::Result<()> {
         let calendar_id = self.get_id_for_name(name).await?;
-        let mut page_token: Option<String> = None;
-        let mut counter = 0;
-
-        loop {
-            let mut request = self
-                .hub
-                .events()
-                .list(&calendar_id)
-                .add_scope(Scope::AppCreated);
+        let event_ids: Vec<String> = self
+            .list_events(&calendar_id)
+            .await?
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect();
+        let counter = event_ids.len();
+        let outcome = self.delete_all(&calendar_id, event_ids).await;
+        self.forget_busy_periods(&calendar_id).await?;
+        outcome?;
+        tracing::info!(cleared = counter, "Cleared events");
+        Ok(())
+    }
 
-            if let Some(ref token) = page_token {
-                request = request.page_token(token);
-            }
+    #[instrument(skip(self), fields(calendar = %calendar))]
+    async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        let id = self.get_id_for_name(calendar).await?;
+        let outcome = self.insert_event(&id, event.into()).await;
+        self.forget_busy_periods(&id).await?;
+        outcome
+    }
 
-            let (_, list) = request.doit().await?;
+    #[instrument(skip(self, events), fields(calendar = %calendar, event_count = events.len()))]
+    async fn create_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        let id = self.get_id_for_name(calendar).await?;
+        let this = &*self;
+        let outcome = stream::iter(events)
+            .map(|event| this.insert_event(&id, event.into()))
+            .buffer_unordered(CONCURRENT_REQUESTS)
+            .try_collect::<Vec<()>>()
+            .await;
+        self.forget_busy_periods(&id).await?;
+        outcome.map(|_| ())
+    }
 
-            if let Some(events) = list.items {
-                for e in events {
-                    if let Some(event_id) = e.id {
-                        self.hub
-                            .events()
-                            .delete(&calendar_id, &event_id)
-                            .add_scope(Scope::AppCreated)
-                            .doit()
-                            .await?;
-                        counter += 1;
-                    } else {
-                        tracing::warn!(event = ?e, "Event has no event_id");
-                    }
-                }
-            }
+    #[instrument(skip(self), fields(calendar = %calendar))]
+    async fn event_keys(&self, calendar: &str) -> Result<Vec<Option<String>>> {
+        let id = self.get_id_for_name(calendar).await?;
+        Ok(self
+            .list_events(&id)
+            .await?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    }
 
-            page_token = list.next_page_token;
-            if page_token.is_none() {
-                break;
-            }
-        }
+    async fn upsert_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        self.upsert_events(calendar, vec![event]).await
+    }
 
-        tracing::info!(cleared = counter, "Cleared events");
-        Ok(())
+    /// Updates events whose key is already in the calendar in place, so
+    /// edits like a changed window don't give them a new id.
+    #[instrument(skip(self, events), fields(calendar = %calendar, event_count = events.len()))]
+    async fn upsert_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        let id = self.get_id_for_name(calendar).await?;
+        let existing: HashMap<String, String> = self
+            .list_events(&id)
+            .await?
+            .into_iter()
+            .filter_map(|(key, event_id)| Some((key?, event_id)))
+            .collect();
+        let (this, id, existing) = (&*self, &id, &existing);
+        let outcome = stream::iter(events)
+            .map(|event| async move {
+                match event.key.as_ref().and_then(|key| existing.get(key)) {
+                    Some(event_id) => this.update_event(id, event_id, event.into()).await,
+                    None => this.insert_event(id, event.into()).await,
+                }
+            })
+            .buffer_unordered(CONCURRENT_REQUESTS)
+            .try_collect::<Vec<()>>()
+            .await;
+        self.forget_busy_periods(id).await?;
+        outcome.map(|_| ())
     }
 
-    #[instrument(skip(self), fields(calendar = %calendar))]
-    async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+    #[instrument(skip(self, keys), fields(calendar = %calendar, key_count = keys.len()))]
+    async fn delete_events(&mut self, calendar: &str, keys: &[String]) -> Result<()> {
         let id = self.get_id_for_name(calendar).await?;
-        self.hub
-            .events()
-            .insert(event.into(), &id)
-            .add_scope(Scope::AppCreated)
-            .doit()
-            .await?;
-        Ok(())
+        let event_ids = self
+            .list_events(&id)
+            .await?
+            .into_iter()
+            .filter(|(key, _)| key.as_ref().is_some_and(|key| keys.contains(key)))
+            .map(|(_, event_id)| event_id)
+            .collect();
+        let outcome = self.delete_all(&id, event_ids).await;
+        self.forget_busy_periods(&id).await?;
+        outcome
     }
 
     #[instrument(skip(self))]
@@ -512,7 +852,8 @@ impl CalendarProvider for GoogleCalendar {
             .insert(cal)
             .add_scope(Scope::AppCreated)
             .doit()
-            .await?;
+            .await
+            .map_err(|e| calendar_error(e, "Failed to create calendar"))?;
 
         if let Some(id) = cal.id {
             let key = format!("calendar_name_id_map_{}", name);
@@ -528,14 +869,151 @@ impl From<CalendarEvent> for Event {
     fn from(value: CalendarEvent) -> Self {
         let mut event = Event::default();
         event.summary = Some(value.title);
-        event.start = Some(to_event_time(value.start_time));
-        event.end = Some(to_event_time(value.end_time));
+        if value.is_all_day {
+            event.start = Some(to_event_date(value.start_time));
+            event.end = Some(to_event_date(value.end_time));
+        } else {
+            event.start = Some(to_event_time(value.start_time));
+            event.end = Some(to_event_time(value.end_time));
+        }
         event.location = value.location;
         event.description = value.body;
+        event.color_id = value.color.map(|color| color_id(color).to_string());
+        event.extended_properties = value.key.map(|key| EventExtendedProperties {
+            private: Some(HashMap::from([(EVENT_KEY_PROPERTY.to_string(), key)])),
+            shared: None,
+        });
+        if !value.reminder_minutes.is_empty() {
+            event.reminders = Some(EventReminders {
+                use_default: Some(false),
+                overrides: Some(
+                    value
+                        .reminder_minutes
+                        .into_iter()
+                        .map(|minutes| EventReminder {
+                            method: Some("popup".to_string()),
+                            minutes: Some(minutes as i32),
+                        })
+                        .collect(),
+                ),
+            });
+        }
         event
     }
 }
 
+/// Private extended property holding `CalendarEvent::key`.
+const EVENT_KEY_PROPERTY: &str = "travelaiKey";
+
+fn calendar_not_found(name: &str) -> TravelAiError {
+    TravelAiError::new(ErrorCode::CalendarNotFound, "Calendar not found").with_context("name", name)
+}
+
+/// Reasons Google gives for refusing a request until a rate limit or quota
+/// allows it again
+const QUOTA_REASONS: [&str; 3] = [
+    "rateLimitExceeded",
+    "userRateLimitExceeded",
+    "quotaExceeded",
+];
+
+/// Tells failures that signing in again fixes apart from Google being
+/// unreachable or refusing the request, and rate limits from both.
+fn calendar_error(error: google_calendar3::Error, message: &str) -> TravelAiError {
+    let mut retry_after = None;
+    let code = match &error {
+        google_calendar3::Error::MissingToken(_) | google_calendar3::Error::MissingAPIKey => {
+            ErrorCode::CalendarAuthExpired
+        }
+        google_calendar3::Error::Failure(response) if response.status() == 401 => {
+            ErrorCode::CalendarAuthExpired
+        }
+        google_calendar3::Error::Failure(response) if response.status() == 429 => {
+            retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok()?.parse::<u64>().ok());
+            ErrorCode::RateLimited
+        }
+        google_calendar3::Error::BadRequest(body) => {
+            let quota = body["error"]["errors"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|e| QUOTA_REASONS.contains(&e["reason"].as_str().unwrap_or_default()));
+            match body["error"]["code"].as_u64() {
+                Some(401) => ErrorCode::CalendarAuthExpired,
+                Some(404) => ErrorCode::CalendarNotFound,
+                Some(403) if quota => ErrorCode::RateLimited,
+                Some(429) => ErrorCode::RateLimited,
+                _ => ErrorCode::CalendarUnavailable,
+            }
+        }
+        _ => ErrorCode::CalendarUnavailable,
+    };
+    let error = TravelAiError::new(code, message).with_source(anyhow!("{error}"));
+    match retry_after {
+        Some(secs) => error.with_context(RETRY_AFTER_KEY, secs),
+        None => error,
+    }
+}
+
+/// Start and end of a busy period
+type Period = (DateTime<Utc>, DateTime<Utc>);
+
+/// Busy times change whenever someone edits a calendar, so they are only
+/// trusted for a short while.
+const BUSY_TTL: Duration = Duration::from_mins(5);
+
+fn busy_prefix(calendar_id: &str) -> String {
+    format!("calendar_busy_{calendar_id}_")
+}
+
+fn busy_key(calendar_id: &str, day: NaiveDate) -> String {
+    format!("{}{day}", busy_prefix(calendar_id))
+}
+
+fn midnight(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// The UTC days the range from `start` to `end` touches, at least that of
+/// `start`.
+fn days_between(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<NaiveDate> {
+    let first = start.date_naive();
+    first
+        .iter_days()
+        .take_while(|day| *day == first || midnight(*day) < end)
+        .collect()
+}
+
+/// The periods of `busy` that overlap `day`.
+fn periods_on(busy: &[Period], day: NaiveDate) -> Vec<Period> {
+    let (day_start, day_end) = (midnight(day), midnight(day + Days::new(1)));
+    busy.iter()
+        .filter(|(start, end)| *start < day_end && *end > day_start)
+        .copied()
+        .collect()
+}
+
+/// Google's fixed event palette: Basil, Banana and Tangerine.
+fn color_id(color: EventColor) -> &'static str {
+    match color {
+        EventColor::Green => "10",
+        EventColor::Yellow => "5",
+        EventColor::Orange => "6",
+    }
+}
+
+/// All-day events carry dates only, the end date is exclusive.
+fn to_event_date(time: DateTime<Utc>) -> EventDateTime {
+    EventDateTime {
+        date: Some(time.date_naive()),
+        date_time: None,
+        time_zone: None,
+    }
+}
+
 fn to_event_time(time: DateTime<Utc>) -> EventDateTime {
     EventDateTime {
         date: None,
@@ -543,3 +1021,110 @@ fn to_event_time(time: DateTime<Utc>) -> EventDateTime {
         time_zone: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn event_carries_color_and_popup_reminders() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap();
+        let event: Event = CalendarEvent {
+            title: "Site".into(),
+            start_time: start,
+            end_time: start + chrono::Duration::hours(4),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: Some(EventColor::Green),
+            reminder_minutes: vec![18 * 60],
+            key: Some("2026-06-13/Site/1000".into()),
+            site: None,
+        }
+        .into();
+
+        assert_eq!(event.color_id.as_deref(), Some("10"));
+        let reminders = event.reminders.unwrap();
+        assert_eq!(reminders.use_default, Some(false));
+        let overrides = reminders.overrides.unwrap();
+        assert_eq!(overrides[0].minutes, Some(1080));
+        assert_eq!(overrides[0].method.as_deref(), Some("popup"));
+        let properties = event.extended_properties.unwrap().private.unwrap();
+        assert_eq!(properties[EVENT_KEY_PROPERTY], "2026-06-13/Site/1000");
+    }
+
+    #[test]
+    fn busy_days_are_those_the_range_touches() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2026, 6, day).unwrap();
+        assert_eq!(days_between(at(13, 10), at(13, 14)), [date(13)]);
+        assert_eq!(days_between(at(13, 22), at(14, 0)), [date(13)]);
+        assert_eq!(
+            days_between(at(13, 22), at(15, 1)),
+            [date(13), date(14), date(15)]
+        );
+
+        let busy = [(at(12, 20), at(13, 2)), (at(13, 9), at(13, 10))];
+        assert_eq!(periods_on(&busy, date(13)), busy);
+        assert_eq!(periods_on(&busy, date(12)), [busy[0]]);
+        assert!(periods_on(&busy, date(14)).is_empty());
+    }
+
+    #[test]
+    fn quota_errors_are_rate_limits() {
+        let refused = |code: u64, reason: &str| {
+            let body = serde_json::json!({
+                "error": {"code": code, "errors": [{"reason": reason}]}
+            });
+            calendar_error(google_calendar3::Error::BadRequest(body), "failed").code
+        };
+        assert_eq!(refused(403, "rateLimitExceeded"), ErrorCode::RateLimited);
+        assert_eq!(
+            refused(403, "userRateLimitExceeded"),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(refused(429, "rateLimitExceeded"), ErrorCode::RateLimited);
+        assert_eq!(refused(403, "forbidden"), ErrorCode::CalendarUnavailable);
+        assert_eq!(refused(401, "authError"), ErrorCode::CalendarAuthExpired);
+    }
+
+    #[test]
+    fn all_day_event_uses_dates() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
+        let event: Event = CalendarEvent {
+            title: "Flyable".into(),
+            start_time: start,
+            end_time: start + chrono::Duration::days(1),
+            is_all_day: true,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: None,
+            site: None,
+        }
+        .into();
+
+        let (start, end) = (event.start.unwrap(), event.end.unwrap());
+        assert_eq!(start.date, chrono::NaiveDate::from_ymd_opt(2026, 6, 13));
+        assert_eq!(end.date, chrono::NaiveDate::from_ymd_opt(2026, 6, 14));
+        assert!(start.date_time.is_none());
+    }
+
+    #[tokio::test]
+    async fn unreadable_service_account_key_is_a_configuration_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("key.json");
+        std::fs::write(&key_file, "{\"type\": \"service_account\"}").unwrap();
+
+        for key_file in [key_file, dir.path().join("missing.json")] {
+            let account = ServiceAccount::new(key_file, Some("club@example.org".into()));
+            let error = GoogleAuth::service_account(&account).await.err().unwrap();
+            assert_eq!(
+                TravelAiError::find(&error).map(|e| e.code),
+                Some(ErrorCode::Configuration)
+            );
+        }
+    }
+}
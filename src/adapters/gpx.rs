@@ -0,0 +1,152 @@
+//! Renders the sites flyable on a day as GPX 1.1 waypoints, for flight
+//! instruments and OsmAnd. Launch names carry the score, e.g.
+//! `Wank 6h excellent`, since most devices show nothing but the name.
+
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+use quick_xml::escape::escape;
+
+use crate::{adapters::activities::paragliding::outlook::SiteDay, domain::location::Location};
+
+pub fn render(date: NaiveDate, sites: &[SiteDay]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<gpx version=\"1.1\" creator=\"travelai\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+    let _ = writeln!(
+        out,
+        "  <metadata><name>Flyable sites {date}</name></metadata>"
+    );
+    for flyable in sites {
+        let site = &flyable.site;
+        let score = format!(
+            "{}h {}",
+            flyable.day.total_flyable_hours,
+            flyable.rating().label()
+        );
+        let mut description = format!("Flyable {score}");
+        if let Some(window) = flyable.window() {
+            let _ = write!(description, ", {window} UTC");
+        }
+        if let Some(wind) = flyable.wind() {
+            let _ = write!(description, ", wind {wind} km/h");
+        }
+        for (index, launch) in site.launches.iter().enumerate() {
+            let name = format!("{}{} {score}", site.name, number(index));
+            push_waypoint(
+                &mut out,
+                &launch.location,
+                launch.elevation,
+                &name,
+                &description,
+                "launch",
+            );
+        }
+        for (index, landing) in site.landings.iter().enumerate() {
+            let name = format!("{}{} LZ", site.name, number(index));
+            let description = format!("Landing of {}", site.name);
+            push_waypoint(
+                &mut out,
+                &landing.location,
+                landing.elevation,
+                &name,
+                &description,
+                "landing",
+            );
+        }
+    }
+    out.push_str("</gpx>\n");
+    out
+}
+
+/// ` #2` for the second and later launches or landings of a site.
+fn number(index: usize) -> String {
+    if index == 0 {
+        String::new()
+    } else {
+        format!(" #{}", index + 1)
+    }
+}
+
+fn push_waypoint(
+    out: &mut String,
+    location: &Location,
+    elevation: f64,
+    name: &str,
+    description: &str,
+    kind: &str,
+) {
+    let _ = writeln!(
+        out,
+        "  <wpt lat=\"{:.6}\" lon=\"{:.6}\"><ele>{elevation:.0}</ele><name>{}</name><desc>{}</desc><type>{kind}</type></wpt>",
+        location.latitude,
+        location.longitude,
+        escape(name),
+        escape(description),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::activities::paragliding::site_evaluator::{DailySummary, FlyableRange},
+        domain::paragliding::{ParaglidingLanding, ParaglidingLaunch, ParaglidingSite, SiteType},
+    };
+    use chrono::{TimeZone, Utc};
+
+    fn location(latitude: f64, longitude: f64) -> Location {
+        Location::new(latitude, longitude, "Test".into(), "DE".into())
+    }
+
+    fn flyable() -> SiteDay {
+        let date = NaiveDate::from_ymd_opt(2026, 6, 13).unwrap();
+        let launch = |latitude| ParaglidingLaunch {
+            site_type: SiteType::Hang,
+            location: location(latitude, 11.0),
+            direction_degrees_start: 180.0,
+            direction_degrees_stop: 270.0,
+            elevation: 1750.0,
+        };
+        SiteDay {
+            site: ParaglidingSite {
+                name: "Wank & Co".into(),
+                launches: vec![launch(47.5), launch(47.51)],
+                landings: vec![ParaglidingLanding {
+                    location: location(47.49, 11.1),
+                    elevation: 720.0,
+                }],
+                country: Some("DE".into()),
+                data_source: "test".into(),
+                ..Default::default()
+            },
+            day: DailySummary {
+                date,
+                ranges: vec![FlyableRange {
+                    start: Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap(),
+                    end: Utc.with_ymd_and_hms(2026, 6, 13, 15, 0, 0).unwrap(),
+                }],
+                total_flyable_hours: 6,
+                ..Default::default()
+            },
+            hours: vec![],
+            flyable: true,
+        }
+    }
+
+    #[test]
+    fn launches_carry_the_score_and_landings_follow() {
+        let gpx = render(NaiveDate::from_ymd_opt(2026, 6, 13).unwrap(), &[flyable()]);
+
+        assert!(gpx.contains("<name>Flyable sites 2026-06-13</name>"));
+        assert!(gpx.contains(
+            "<wpt lat=\"47.500000\" lon=\"11.000000\"><ele>1750</ele><name>Wank &amp; Co 6h excellent</name><desc>Flyable 6h excellent, 10-16 UTC</desc><type>launch</type></wpt>"
+        ));
+        assert!(gpx.contains("<name>Wank &amp; Co #2 6h excellent</name>"));
+        assert!(gpx.contains("<name>Wank &amp; Co LZ</name>"));
+        assert_eq!(gpx.matches("<wpt ").count(), 3);
+        assert!(gpx.trim_end().ends_with("</gpx>"));
+    }
+}
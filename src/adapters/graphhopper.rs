@@ -1,4 +1,4 @@
-use std::{env, sync::Arc, time::Duration as StdDuration};
+use std::{sync::Arc, time::Duration as StdDuration};
 
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
@@ -10,6 +10,7 @@ use tracing::instrument;
 
 use crate::{
     adapters::cache::PersistentCache,
+    config,
     domain::{location::Location, ports::RoutingProvider},
 };
 
@@ -35,7 +36,7 @@ impl Routing {
             source.longitude,
             destination.latitude,
             destination.longitude,
-            env::var("GRAPHHOPPER_API_KEY").context("Missing GRAPHHOPPER_API_KEY env var")?
+            config::secret("GRAPHHOPPER_API_KEY").context("Missing GRAPHHOPPER_API_KEY env var")?
         );
         let response = self.http.get(url).send().await?;
         let response: ApiResponse = response.json().await?;
@@ -0,0 +1,445 @@
+use std::net::SocketAddr;
+
+use anyhow::{Result, anyhow};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, transport::Server};
+use tracing::instrument;
+use travelai_core::score::Factor;
+
+use crate::{
+    adapters::activities::paragliding::{
+        site_evaluator::{AltitudeTemperatures, DailySummary, DayPart, Hazard},
+        source,
+    },
+    app_state::AppState,
+    config,
+    domain::{
+        activities::{DayRating, SiteError},
+        location::Location,
+        paragliding::{
+            ParaglidingLaunch, ParaglidingSite, ParaglidingSiteProvider, SiteType,
+            crowding::Crowding,
+        },
+    },
+};
+
+pub mod proto {
+    tonic::include_proto!("travelai.v1");
+}
+
+use proto::{
+    flyability_service_server::{FlyabilityService, FlyabilityServiceServer},
+    forecast_service_server::{ForecastService, ForecastServiceServer},
+    site_service_server::{SiteService, SiteServiceServer},
+};
+
+// Sites are evaluated one after another; a small buffer is enough to keep the
+// evaluation ahead of a slow client without piling up forecasts in memory.
+const STREAM_BUFFER: usize = 4;
+
+pub async fn run(state: AppState) {
+    let config = config::GrpcConfig::load();
+    let addr: SocketAddr = format!("0.0.0.0:{}", config.port).parse().unwrap();
+    tracing::info!(addr = %addr, "Starting gRPC server");
+
+    Server::builder()
+        .add_service(SiteServiceServer::new(GrpcApi::new(state.clone())))
+        .add_service(ForecastServiceServer::new(GrpcApi::new(state.clone())))
+        .add_service(FlyabilityServiceServer::new(GrpcApi::new(state)))
+        .serve(addr)
+        .await
+        .expect("gRPC server error");
+}
+
+pub struct GrpcApi {
+    state: AppState,
+}
+
+impl GrpcApi {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+async fn forecast_site(
+    state: &AppState,
+    site: &ParaglidingSite,
+    distance_km: f64,
+) -> Result<proto::SiteForecast> {
+    let launch = site
+        .launches
+        .first()
+        .ok_or(anyhow!("Site {} has no launch", site.name))?;
+    let forecast = state
+        .weather
+        .get_forecast(
+            launch.location.clone(),
+            site.preferred_weather_model.clone(),
+        )
+        .await?;
+    let eval = source::evaluate(
+        &state.evaluations,
+        site,
+        &forecast,
+        state.ensemble.as_deref(),
+        Some(state.sounding.as_ref()),
+    )
+    .await;
+
+    Ok(proto::SiteForecast {
+        site: site.name.clone(),
+        distance_km,
+        daily_summaries: eval.daily_summaries.iter().map(Into::into).collect(),
+        error: None,
+    })
+}
+
+fn required_center(center: Option<proto::Location>) -> Result<Location, Status> {
+    center
+        .map(Into::into)
+        .ok_or_else(|| Status::invalid_argument("center is required"))
+}
+
+#[tonic::async_trait]
+impl SiteService for GrpcApi {
+    #[instrument(skip(self, request))]
+    async fn list_sites(
+        &self,
+        request: Request<proto::ListSitesRequest>,
+    ) -> Result<Response<proto::ListSitesResponse>, Status> {
+        let request = request.into_inner();
+        let sites = match request.center {
+            Some(center) => self
+                .state
+                .site_repo
+                .fetch_launches_within_radius(&center.into(), request.radius_km)
+                .await
+                .into_iter()
+                .map(|(site, _distance)| site)
+                .collect(),
+            None => self.state.site_repo.fetch_all_sites().await,
+        };
+        Ok(Response::new(proto::ListSitesResponse {
+            sites: sites.iter().map(Into::into).collect(),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl ForecastService for GrpcApi {
+    #[instrument(skip(self, request), fields(site = %request.get_ref().site))]
+    async fn get_forecast(
+        &self,
+        request: Request<proto::ForecastRequest>,
+    ) -> Result<Response<proto::SiteForecast>, Status> {
+        let name = request.into_inner().site;
+        let site = self
+            .state
+            .site_repo
+            .fetch_all_sites()
+            .await
+            .into_iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| Status::not_found(format!("Unknown site {name}")))?;
+
+        let forecast = forecast_site(&self.state, &site, 0.0).await.map_err(|e| {
+            tracing::error!(error = ?e, "Failed to forecast site");
+            Status::internal("Failed to forecast site")
+        })?;
+        Ok(Response::new(forecast))
+    }
+}
+
+#[tonic::async_trait]
+impl FlyabilityService for GrpcApi {
+    type StreamFlyabilityStream = ReceiverStream<Result<proto::SiteForecast, Status>>;
+
+    #[instrument(skip(self, request), fields(radius_km = request.get_ref().radius_km))]
+    async fn stream_flyability(
+        &self,
+        request: Request<proto::FlyabilityRequest>,
+    ) -> Result<Response<Self::StreamFlyabilityStream>, Status> {
+        let request = request.into_inner();
+        let center = required_center(request.center)?;
+        let sites = self
+            .state
+            .site_repo
+            .fetch_launches_within_radius(&center, request.radius_km)
+            .await;
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            for (site, distance) in sites {
+                if site.mute_alerts == Some(true) {
+                    continue;
+                }
+                let forecast = match forecast_site(&state, &site, distance).await {
+                    Ok(forecast) => forecast,
+                    Err(e) => {
+                        tracing::warn!(site = %site.name, error = ?e, "Failed to forecast site in stream");
+                        proto::SiteForecast {
+                            site: site.name.clone(),
+                            distance_km: distance,
+                            daily_summaries: vec![],
+                            error: Some(SiteError::new(&site.name, &e).into()),
+                        }
+                    }
+                };
+                if tx.send(Ok(forecast)).await.is_err() {
+                    // Client went away; stop evaluating.
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+impl From<SiteError> for proto::SiteError {
+    fn from(value: SiteError) -> Self {
+        proto::SiteError {
+            code: serde_json::to_value(value.code)
+                .ok()
+                .and_then(|code| code.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            message: value.error,
+        }
+    }
+}
+
+impl From<&Location> for proto::Location {
+    fn from(value: &Location) -> Self {
+        proto::Location {
+            latitude: value.latitude,
+            longitude: value.longitude,
+            name: value.name.clone(),
+            country: value.country.clone(),
+        }
+    }
+}
+
+impl From<proto::Location> for Location {
+    fn from(value: proto::Location) -> Self {
+        Location::new(value.latitude, value.longitude, value.name, value.country)
+    }
+}
+
+impl From<&ParaglidingLaunch> for proto::Launch {
+    fn from(value: &ParaglidingLaunch) -> Self {
+        let site_type = match value.site_type {
+            SiteType::Hang => proto::SiteType::Hang,
+            SiteType::Winch => proto::SiteType::Winch,
+        };
+        proto::Launch {
+            site_type: site_type.into(),
+            location: Some((&value.location).into()),
+            direction_degrees_start: value.direction_degrees_start,
+            direction_degrees_stop: value.direction_degrees_stop,
+            elevation: value.elevation,
+        }
+    }
+}
+
+impl From<&ParaglidingSite> for proto::Site {
+    fn from(value: &ParaglidingSite) -> Self {
+        proto::Site {
+            name: value.name.clone(),
+            launches: value.launches.iter().map(Into::into).collect(),
+            country: value.country.clone(),
+            data_source: value.data_source.clone(),
+            rating: value.rating.map(u32::from),
+            preferred_weather_model: value.preferred_weather_model.clone(),
+            winter_operable: value.winter_operable,
+            favorite: value.favorite,
+        }
+    }
+}
+
+impl From<&AltitudeTemperatures> for proto::AltitudeTemperatures {
+    fn from(value: &AltitudeTemperatures) -> Self {
+        proto::AltitudeTemperatures {
+            launch_temperature: value.launch_temperature,
+            cloud_base_m: value.cloud_base_m,
+            cloud_base_temperature: value.cloud_base_temperature,
+        }
+    }
+}
+
+impl From<Crowding> for proto::Crowding {
+    fn from(value: Crowding) -> Self {
+        match value {
+            Crowding::Low => proto::Crowding::Low,
+            Crowding::Moderate => proto::Crowding::Moderate,
+            Crowding::High => proto::Crowding::High,
+        }
+    }
+}
+
+impl From<DayRating> for proto::DayRating {
+    fn from(value: DayRating) -> Self {
+        match value {
+            DayRating::Fair => proto::DayRating::Fair,
+            DayRating::Good => proto::DayRating::Good,
+            DayRating::Excellent => proto::DayRating::Excellent,
+        }
+    }
+}
+
+impl From<DayPart> for proto::DayPart {
+    fn from(value: DayPart) -> Self {
+        match value {
+            DayPart::Morning => proto::DayPart::Morning,
+            DayPart::Midday => proto::DayPart::Midday,
+            DayPart::Afternoon => proto::DayPart::Afternoon,
+        }
+    }
+}
+
+impl From<Hazard> for proto::Hazard {
+    fn from(value: Hazard) -> Self {
+        match value {
+            Hazard::Gusts => proto::Hazard::Gusts,
+            Hazard::Thunderstorm => proto::Hazard::Thunderstorm,
+            Hazard::Foehn => proto::Hazard::Foehn,
+            Hazard::Airspace => proto::Hazard::Airspace,
+            Hazard::Rain => proto::Hazard::Rain,
+            Hazard::StrongValleyWind => proto::Hazard::StrongValleyWind,
+            Hazard::LowCloudbase => proto::Hazard::LowCloudbase,
+            Hazard::FrontalPassage => proto::Hazard::FrontalPassage,
+        }
+    }
+}
+
+impl From<Factor> for proto::LimitingFactor {
+    fn from(value: Factor) -> Self {
+        match value {
+            Factor::NoFootLaunch => proto::LimitingFactor::NoFootLaunch,
+            Factor::Rain => proto::LimitingFactor::Rain,
+            Factor::Wind => proto::LimitingFactor::Wind,
+            Factor::Gusts => proto::LimitingFactor::Gusts,
+            Factor::Direction => proto::LimitingFactor::Direction,
+            Factor::Closure => proto::LimitingFactor::Closure,
+            Factor::Darkness => proto::LimitingFactor::Darkness,
+            Factor::Overdevelopment => proto::LimitingFactor::Overdevelopment,
+        }
+    }
+}
+
+fn proto_hazards(hazards: &[Hazard]) -> Vec<i32> {
+    hazards
+        .iter()
+        .map(|&h| proto::Hazard::from(h).into())
+        .collect()
+}
+
+impl From<&DailySummary> for proto::DailySummary {
+    fn from(value: &DailySummary) -> Self {
+        proto::DailySummary {
+            date: value.date.to_string(),
+            hourly_scores: value
+                .hourly_scores
+                .iter()
+                .map(|h| proto::HourlyScore {
+                    timestamp: h.timestamp.timestamp(),
+                    is_flyable: h.is_flyable,
+                    hazards: proto_hazards(&h.hazards),
+                    limiting_factor: h
+                        .limiting_factor
+                        .map(|f| proto::LimitingFactor::from(f).into()),
+                    flyable_probability: h.flyable_probability,
+                })
+                .collect(),
+            ranges: value
+                .ranges
+                .iter()
+                .map(|r| proto::FlyableRange {
+                    start: r.start.timestamp(),
+                    end: r.end.timestamp(),
+                })
+                .collect(),
+            total_flyable_hours: value.total_flyable_hours as u32,
+            hazards: proto_hazards(&value.hazards),
+            day_parts: value
+                .day_parts
+                .iter()
+                .map(|p| proto::DayPartSummary {
+                    part: proto::DayPart::from(p.part).into(),
+                    flyable_hours: p.flyable_hours as u32,
+                    hours: p.hours as u32,
+                    confidence: p.confidence,
+                    flyable_probability: p.flyable_probability,
+                    rating: p.rating.map(|r| proto::DayRating::from(r).into()),
+                })
+                .collect(),
+            likely_snow_covered: value.likely_snow_covered,
+            altitude_temperatures: value.altitude_temperatures.as_ref().map(Into::into),
+            boundary_layer_top_m: value.boundary_layer_top_m,
+            crowding: proto::Crowding::from(value.crowding).into(),
+            source_model: value.source_model.clone(),
+            confidence: value.confidence,
+            warnings: value.warnings.clone(),
+            precipitation_probability: value.precipitation_probability.map(u32::from),
+            freezing_level_m: value.freezing_level_m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::activities::paragliding::site_evaluator::{FlyableRange, HourlyScore};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn site_conversion_keeps_launch_details() {
+        let site = ParaglidingSite {
+            name: "Hill".into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Winch,
+                location: Location::new(50.7, 13.0, "Launch".into(), "DE".into()),
+                direction_degrees_start: 200.0,
+                direction_degrees_stop: 280.0,
+                elevation: 650.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            rating: Some(4),
+            ..Default::default()
+        };
+
+        let proto_site = proto::Site::from(&site);
+
+        assert_eq!(proto_site.rating, Some(4));
+        let launch = &proto_site.launches[0];
+        assert_eq!(launch.site_type(), proto::SiteType::Winch);
+        assert_eq!(launch.location.as_ref().unwrap().latitude, 50.7);
+        assert_eq!(launch.direction_degrees_stop, 280.0);
+    }
+
+    #[test]
+    fn daily_summary_conversion_uses_unix_seconds_and_iso_dates() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap();
+        let summary = DailySummary {
+            date: NaiveDate::from_ymd_opt(2026, 6, 13).unwrap(),
+            hourly_scores: vec![HourlyScore {
+                timestamp: start,
+                is_flyable: true,
+                ..Default::default()
+            }],
+            ranges: vec![FlyableRange { start, end: start }],
+            total_flyable_hours: 1,
+            warnings: vec!["Cold at launch (2°C), dress warm".into()],
+            ..Default::default()
+        };
+
+        let proto_summary = proto::DailySummary::from(&summary);
+
+        assert_eq!(proto_summary.date, "2026-06-13");
+        assert_eq!(proto_summary.hourly_scores[0].timestamp, start.timestamp());
+        assert_eq!(proto_summary.ranges[0].end, start.timestamp());
+        assert_eq!(proto_summary.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+//! Today's flying as a Home Assistant REST sensor, so a dashboard needs a
+//! `value_template` and `json_attributes_path` rather than parsing forecasts.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    adapters::activities::paragliding::outlook::SiteDay,
+    domain::activities::{DayRating, SiteError},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sensor {
+    /// Flyable hours of the day's best site, 0 if none is flyable
+    pub state: usize,
+    pub attributes: Attributes,
+}
+
+/// Null rather than missing when nothing is flyable, so templates can test
+/// for `none`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Attributes {
+    pub date: NaiveDate,
+    pub rating: Option<DayRating>,
+    pub site: Option<String>,
+    /// Longest window of the best site in UTC hours, e.g. `10-14`
+    pub window: Option<String>,
+    /// Mean wind in that window, e.g. `SW 12` (km/h)
+    pub wind: Option<String>,
+    pub hazards: Vec<&'static str>,
+    pub flyable_sites: usize,
+    /// Sites that couldn't be scored, so a dashboard can tell a calm day
+    /// from missing weather
+    pub errors: Vec<SiteError>,
+}
+
+/// The sensor for `date`, from the sites' summaries on it.
+pub fn sensor(date: NaiveDate, sites: &[SiteDay], errors: &[SiteError]) -> Sensor {
+    let flyable: Vec<&SiteDay> = sites
+        .iter()
+        .filter(|d| d.flyable && d.day.date == date)
+        .collect();
+    let best = flyable.iter().max_by(|a, b| {
+        a.day
+            .total_flyable_hours
+            .cmp(&b.day.total_flyable_hours)
+            .then_with(|| b.site.name.cmp(&a.site.name))
+    });
+
+    Sensor {
+        state: best.map_or(0, |d| d.day.total_flyable_hours),
+        attributes: Attributes {
+            date,
+            rating: best.map(|d| d.rating()),
+            site: best.map(|d| d.site.name.clone()),
+            window: best.and_then(|d| d.window()),
+            wind: best.and_then(|d| d.wind()),
+            hazards: best.map_or_else(Vec::new, |d| {
+                d.day.hazards.iter().map(|h| h.label()).collect()
+            }),
+            flyable_sites: flyable.len(),
+            errors: errors.to_vec(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::{
+            activities::paragliding::site_evaluator::Hazard,
+            report::tests::{date, site_day},
+        },
+        domain::error::ErrorCode,
+    };
+
+    #[test]
+    fn state_is_the_best_sites_flyable_hours() {
+        let sites = vec![
+            site_day("Hörnle", 13, 3, vec![]),
+            site_day("Wank", 13, 6, vec![Hazard::Gusts]),
+            site_day("Wank", 14, 1, vec![]),
+        ];
+
+        let errors = [SiteError {
+            site: "Brauneck".into(),
+            code: ErrorCode::RateLimited,
+            error: "Open-Meteo rate limit reached".into(),
+        }];
+
+        let sensor = sensor(date(13), &sites, &errors);
+
+        assert_eq!(
+            serde_json::to_value(&sensor).unwrap(),
+            serde_json::json!({
+                "state": 6,
+                "attributes": {
+                    "date": "2026-06-13",
+                    "rating": "excellent",
+                    "site": "Wank",
+                    "window": "10-16",
+                    "wind": "SW 11",
+                    "hazards": ["gusts"],
+                    "flyable_sites": 2,
+                    "errors": [{
+                        "site": "Brauneck",
+                        "code": "rate_limited",
+                        "error": "Open-Meteo rate limit reached",
+                    }],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn days_without_a_flyable_site_are_zero() {
+        let sites = vec![site_day("Wank", 14, 1, vec![])];
+
+        let sensor = sensor(date(14), &sites, &[]);
+
+        assert_eq!(sensor.state, 0);
+        assert_eq!(sensor.attributes.site, None);
+        assert_eq!(sensor.attributes.flyable_sites, 0);
+    }
+}
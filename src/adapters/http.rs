@@ -1,26 +1,52 @@
 use axum::{
     Router,
-    body::Body,
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
-    routing::{delete, get, post, put},
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post, put},
 };
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tower_http::limit::RequestBodyLimitLayer;
 use tracing::instrument;
 
 use crate::{
     adapters::{
-        activities::paragliding::dhv,
-        google_calendar::GoogleCalendar,
+        activities::paragliding::{
+            alternatives::{self, DayAlternatives},
+            dhv::{self, ParseMode, SkippedRecord},
+            history::{self, SiteDiff},
+            launch_check::{self, LaunchCheck, LaunchEdit},
+            outlook::{self, CompactDay, SiteDay, SiteDays},
+            provider_chain::ProviderStatus,
+            site_evaluator::SiteEvaluationResult,
+            source::{self, DayScore},
+        },
+        api_error::{ApiError, ApiResultExt},
+        gpx, home_assistant, ics, kml, nominatim, report,
+        validation::{self, ValidQuery, Validate, Validator},
+        xctrack,
     },
     app_state::AppState,
-    application::{calendar_job, flight_analytics},
+    application::{
+        area_forecast_job::AreaForecastRequest,
+        calendar_job, flight_analytics,
+        geocoding::{self, Place},
+        scheduler::{ForecastJob, Job, JobStatus},
+    },
+    config::CalendarConfig,
     domain::{
-        location::Location,
-        paragliding::{ParaglidingSite, ParaglidingSiteProvider, UserSettings, flight::Track},
-        ports::CalendarProvider,
+        activities::SiteError,
+        error::{ErrorCode, TravelAiError},
+        location::{GeocodeCandidate, Location},
+        paragliding::{
+            ParaglidingSite, ParaglidingSiteProvider, UserSettings, characteristics::SiteRule,
+            flight::Track, index::SiteAlongRoute,
+        },
+        regions::{self, REGIONS, Region},
+        sounding::{Sounding, SoundingAnalysis, WindAtHeight},
         weather::WeatherModel,
     },
 };
@@ -30,20 +56,310 @@ pub struct ElevationResponse {
     pub elevation: f64,
 }
 
+#[derive(Deserialize)]
+pub struct ElevationBatchRequest {
+    /// Points as `[latitude, longitude]`
+    points: Vec<(f64, f64)>,
+}
+
+impl Validate for ElevationBatchRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.points("points", &self.points);
+    }
+}
+
+#[derive(Serialize)]
+pub struct ElevationBatchResponse {
+    /// Elevations in the order of the requested points
+    elevations: Vec<f64>,
+}
+
 #[derive(Deserialize)]
 pub struct ElevationQuery {
     latitude: f64,
     longitude: f64,
 }
 
+impl Validate for ElevationQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+    }
+}
+
 #[derive(Deserialize)]
 pub struct GeocodeQuery {
     name: String,
 }
 
+impl Validate for GeocodeQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.check("name", !self.name.trim().is_empty(), "must not be empty");
+    }
+}
+
 #[derive(Serialize)]
 pub struct GeocodeResponse {
-    results: Vec<Location>,
+    results: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+pub struct LocationForecastQuery {
+    location: String,
+    /// Index into the geocoding candidates, required if the name is ambiguous
+    candidate: Option<usize>,
+    model: Option<String>,
+}
+
+impl Validate for LocationForecastQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "location",
+            !self.location.trim().is_empty(),
+            "must not be empty",
+        );
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReverseGeocodeQuery {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Validate for ReverseGeocodeQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SitesQuery {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    radius_km: Option<f64>,
+    /// Comma-separated country codes, e.g. `DE,AT`
+    country: Option<String>,
+}
+
+impl Validate for SitesQuery {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(latitude) = self.latitude {
+            v.latitude("latitude", latitude);
+        }
+        if let Some(longitude) = self.longitude {
+            v.longitude("longitude", longitude);
+        }
+        if let Some(radius_km) = self.radius_km {
+            v.radius("radius_km", radius_km);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SiteSearchQuery {
+    latitude: f64,
+    longitude: f64,
+    radius_km: f64,
+    country: Option<String>,
+}
+
+impl Validate for SiteSearchQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+        v.radius("radius_km", self.radius_km);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PolygonSearchRequest {
+    /// Corners as `[latitude, longitude]`
+    polygon: Vec<(f64, f64)>,
+}
+
+impl Validate for PolygonSearchRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "polygon",
+            self.polygon.len() >= 3,
+            "needs at least 3 corners",
+        );
+        v.points("polygon", &self.polygon);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RouteSearchRequest {
+    /// Points of the route as `[latitude, longitude]`
+    route: Vec<(f64, f64)>,
+    corridor_km: f64,
+}
+
+impl Validate for RouteSearchRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check("route", !self.route.is_empty(), "needs at least one point");
+        v.points("route", &self.route);
+        v.radius("corridor_km", self.corridor_km);
+    }
+}
+
+impl Validate for AreaForecastRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+        v.radius("radius_km", self.radius_km);
+        if let Some(days) = self.days {
+            v.check(
+                "days",
+                (1..=validation::MAX_FORECAST_DAYS).contains(&days),
+                format!("must be between 1 and {}", validation::MAX_FORECAST_DAYS),
+            );
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SubmittedForecast {
+    id: String,
+}
+
+#[derive(Serialize)]
+struct SiteSearchResult {
+    #[serde(flatten)]
+    site: ParaglidingSite,
+    distance_km: f64,
+}
+
+#[derive(Serialize)]
+struct SiteDetail {
+    #[serde(flatten)]
+    site: ParaglidingSite,
+    /// Restrictions found in the site's flight rules
+    rules: Vec<SiteRule>,
+    warnings: Vec<&'static str>,
+}
+
+#[derive(Deserialize)]
+pub struct SiteForecastQuery {
+    /// How far from a favorite to look for sites to fly instead
+    alternatives_radius_km: Option<f64>,
+    /// Rank quieter alternatives with similar hours first
+    #[serde(default)]
+    prefer_quiet: bool,
+}
+
+impl Validate for SiteForecastQuery {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(radius_km) = self.alternatives_radius_km {
+            v.radius("alternatives_radius_km", radius_km);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SiteForecast {
+    #[serde(flatten)]
+    evaluation: SiteEvaluationResult,
+    /// For a favorite, the sites to fly instead on its unflyable days
+    alternatives: Vec<DayAlternatives>,
+    /// Nearby sites left out of the alternatives because they couldn't be
+    /// scored
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    alternative_errors: Vec<SiteError>,
+    /// Each day's score with the factors behind it
+    scores: Vec<DayScore>,
+}
+
+#[derive(Deserialize)]
+pub struct SoundingQuery {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Validate for SoundingQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WindProfileQuery {
+    #[serde(alias = "lat")]
+    latitude: f64,
+    #[serde(alias = "lon")]
+    longitude: f64,
+    time: DateTime<Utc>,
+    /// Band to report in meters above sea level, defaults to the whole profile
+    from_m: Option<f64>,
+    to_m: Option<f64>,
+    step_m: Option<f64>,
+}
+
+impl Validate for WindProfileQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("latitude", self.latitude);
+        v.longitude("longitude", self.longitude);
+        if let Some(step_m) = self.step_m {
+            v.check("step_m", step_m > 0.0, "must be positive");
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WindProfile {
+    /// Model hour closest to the requested time
+    timestamp: DateTime<Utc>,
+    winds: Vec<WindAtHeight>,
+}
+
+#[derive(Serialize)]
+struct AnalyzedSounding {
+    #[serde(flatten)]
+    sounding: Sounding,
+    analysis: SoundingAnalysis,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastHistoryQuery {
+    site: Option<String>,
+    /// A place name instead of `site`, for the closest site to it
+    location: Option<String>,
+    /// Index into the geocoding candidates, required if the name is ambiguous
+    candidate: Option<usize>,
+    date: NaiveDate,
+}
+
+impl Validate for ForecastHistoryQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "site",
+            self.site.is_some() != self.location.is_some(),
+            "give either site or location",
+        );
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ForecastDiffQuery {
+    location: String,
+    /// Index into the geocoding candidates, required if the name is ambiguous
+    candidate: Option<usize>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+impl Validate for ForecastDiffQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "location",
+            !self.location.trim().is_empty(),
+            "must not be empty",
+        );
+        v.check("to", self.to > self.from, "must be after from");
+    }
 }
 
 #[derive(Serialize)]
@@ -73,51 +389,284 @@ impl From<UserSettings> for UserSettingsResponse {
     }
 }
 
+// Enough for a map view, small enough to keep one request below a few seconds
+const MAX_ELEVATION_POINTS: usize = 1000;
+
+#[instrument(skip_all, fields(points = request.points.len()))]
+async fn get_elevations(
+    State(state): State<AppState>,
+    Json(request): Json<ElevationBatchRequest>,
+) -> Result<Json<ElevationBatchResponse>, ApiError> {
+    if request.points.len() > MAX_ELEVATION_POINTS {
+        return Err(ApiError::new(
+            ErrorCode::PayloadTooLarge,
+            format!("At most {MAX_ELEVATION_POINTS} points per request"),
+        )
+        .with_context("points", request.points.len()));
+    }
+    validation::validate(&request, state.api_limits)?;
+    let elevations = state.geo.fetch_elevations(&request.points).await.or_api(
+        ErrorCode::ElevationUnavailable,
+        "Elevation service unavailable",
+    )?;
+    Ok(Json(ElevationBatchResponse { elevations }))
+}
+
 #[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
 async fn get_elevation(
     State(state): State<AppState>,
-    Query(query): Query<ElevationQuery>,
-) -> Result<Json<ElevationResponse>, StatusCode> {
+    ValidQuery(query): ValidQuery<ElevationQuery>,
+) -> Result<Json<ElevationResponse>, ApiError> {
     let elevation = state
         .geo
         .fetch_elevation(query.latitude, query.longitude)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .or_api(
+            ErrorCode::ElevationUnavailable,
+            "Elevation service unavailable",
+        )?;
     Ok(Json(ElevationResponse { elevation }))
 }
 
+#[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
+async fn get_soundings(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<SoundingQuery>,
+) -> Result<Json<Vec<AnalyzedSounding>>, ApiError> {
+    let location = Location::new(
+        query.latitude,
+        query.longitude,
+        String::new(),
+        String::new(),
+    );
+    let soundings = state
+        .sounding
+        .get_soundings(&location)
+        .await
+        .or_api(ErrorCode::SoundingUnavailable, "Soundings unavailable")?;
+    Ok(Json(
+        soundings
+            .into_iter()
+            .map(|sounding| AnalyzedSounding {
+                analysis: sounding.analyze(),
+                sounding,
+            })
+            .collect(),
+    ))
+}
+
+#[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
+async fn get_wind_profile(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<WindProfileQuery>,
+) -> Result<Json<WindProfile>, ApiError> {
+    let location = Location::new(
+        query.latitude,
+        query.longitude,
+        String::new(),
+        String::new(),
+    );
+    let soundings = state
+        .sounding
+        .get_soundings(&location)
+        .await
+        .or_api(ErrorCode::SoundingUnavailable, "Soundings unavailable")?;
+    let sounding = soundings
+        .into_iter()
+        .min_by_key(|s| (s.timestamp - query.time).abs())
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No sounding for this location"))?;
+
+    let from_m = query.from_m.unwrap_or(sounding.surface_elevation_m + 10.0);
+    let to_m = query
+        .to_m
+        .or_else(|| sounding.levels.last().map(|l| l.height_m))
+        .unwrap_or(from_m);
+    Ok(Json(WindProfile {
+        timestamp: sounding.timestamp,
+        winds: sounding.wind_profile(from_m, to_m, query.step_m.unwrap_or(100.0)),
+    }))
+}
+
+#[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
+async fn search_paragliding_earth(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<SiteSearchQuery>,
+) -> Result<Json<Vec<SiteSearchResult>>, ApiError> {
+    let center = Location::new(
+        query.latitude,
+        query.longitude,
+        String::new(),
+        String::new(),
+    );
+    let sites = state
+        .paragliding_earth
+        .search_sites(&center, query.radius_km)
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Paragliding Earth unavailable",
+        )?;
+    let countries = countries(query.country.as_deref());
+    Ok(Json(
+        sites
+            .into_iter()
+            .filter(|(site, _)| site.in_countries(&countries))
+            .map(|(site, distance_km)| SiteSearchResult { site, distance_km })
+            .collect(),
+    ))
+}
+
+/// Sites from every site database, DHV before Paragliding Earth.
+#[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
+async fn search_sites(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<SiteSearchQuery>,
+) -> Result<Json<Vec<SiteSearchResult>>, ApiError> {
+    let center = Location::new(
+        query.latitude,
+        query.longitude,
+        String::new(),
+        String::new(),
+    );
+    let sites = state
+        .site_providers
+        .search_sites(&center, query.radius_km)
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "No site database available",
+        )?;
+    let countries = countries(query.country.as_deref());
+    Ok(Json(
+        sites
+            .into_iter()
+            .filter(|(site, _)| site.in_countries(&countries))
+            .map(|(site, distance_km)| SiteSearchResult { site, distance_km })
+            .collect(),
+    ))
+}
+
+#[instrument(skip(state))]
+async fn get_provider_statuses(State(state): State<AppState>) -> Json<Vec<ProviderStatus>> {
+    Json(state.site_providers.statuses())
+}
+
 #[instrument(skip(state, query), fields(name = %query.name))]
 async fn geocode(
     State(state): State<AppState>,
-    Query(query): Query<GeocodeQuery>,
-) -> Result<Json<GeocodeResponse>, StatusCode> {
-    let locations = state
-        .geo
-        .geocode(&query.name)
+    ValidQuery(query): ValidQuery<GeocodeQuery>,
+) -> Result<Json<GeocodeResponse>, ApiError> {
+    if let Some((latitude, longitude)) = nominatim::parse_coordinates(&query.name) {
+        let location = state
+            .reverse_geo
+            .reverse_geocode(latitude, longitude)
+            .await
+            .or_api(ErrorCode::GeocoderUnavailable, "Geocoder unavailable")?;
+        let candidate = GeocodeCandidate {
+            location,
+            admin_area: None,
+        };
+        return Ok(Json(describe_candidates(&state, vec![candidate]).await));
+    }
+
+    let candidates = geocoding::candidates(&state, &query.name)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(GeocodeResponse { results: locations }))
+        .or_api(ErrorCode::GeocoderUnavailable, "Geocoder unavailable")?;
+    Ok(Json(describe_candidates(&state, candidates).await))
+}
+
+async fn describe_candidates(
+    state: &AppState,
+    candidates: Vec<GeocodeCandidate>,
+) -> GeocodeResponse {
+    GeocodeResponse {
+        results: geocoding::describe(state, candidates).await,
+    }
+}
+
+/// The place `name` stands for. An ambiguous name without `candidate` is
+/// answered with 409 and the candidates to choose from, as the `Err` response.
+async fn pick_location(
+    state: &AppState,
+    name: &str,
+    candidate: Option<usize>,
+) -> Result<Result<Location, Response>, ApiError> {
+    let mut candidates = geocoding::candidates(state, name)
+        .await
+        .or_api(ErrorCode::GeocoderUnavailable, "Geocoder unavailable")?;
+
+    let index = match candidate {
+        Some(index) => index,
+        None if candidates.len() > 1 => {
+            let response = describe_candidates(state, candidates).await;
+            return Ok(Err((StatusCode::CONFLICT, Json(response)).into_response()));
+        }
+        None => 0,
+    };
+    if index >= candidates.len() {
+        return Err(TravelAiError::not_found("Candidate", index)
+            .with_context("candidates", candidates.len())
+            .into());
+    }
+    Ok(Ok(candidates.swap_remove(index).location))
+}
+
+/// Forecast for a place name, see [`pick_location`].
+#[instrument(skip(state, query), fields(location = %query.location, candidate = ?query.candidate))]
+async fn get_location_forecast(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<LocationForecastQuery>,
+) -> Result<Response, ApiError> {
+    let location = match pick_location(&state, &query.location, query.candidate).await? {
+        Ok(location) => location,
+        Err(ambiguous) => return Ok(ambiguous),
+    };
+
+    let forecast = state
+        .weather
+        .get_forecast(location, query.model)
+        .await
+        .or_api(
+            ErrorCode::WeatherUnavailable,
+            "Weather forecast unavailable",
+        )?;
+    Ok(Json(forecast).into_response())
+}
+
+#[instrument(skip(state, query), fields(lat = query.latitude, lon = query.longitude))]
+async fn reverse_geocode(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<ReverseGeocodeQuery>,
+) -> Result<Json<Location>, ApiError> {
+    let location = state
+        .reverse_geo
+        .reverse_geocode(query.latitude, query.longitude)
+        .await
+        .or_api(ErrorCode::GeocoderUnavailable, "Geocoder unavailable")?;
+    Ok(Json(location))
 }
 
 #[instrument(skip(state))]
 async fn get_settings(
     State(state): State<AppState>,
-) -> Result<Json<UserSettingsResponse>, StatusCode> {
-    let cal = GoogleCalendar::new(state.auth.clone(), state.cache.clone())
+) -> Result<Json<UserSettingsResponse>, ApiError> {
+    let config = CalendarConfig::load().or_api(
+        ErrorCode::Configuration,
+        "Calendar configuration is invalid",
+    )?;
+    let cal = calendar_job::open_calendar(&state, &config, config.backends[0])
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .or_api(ErrorCode::CalendarUnavailable, "Calendar unavailable")?;
 
     let calendars = cal
         .get_calendar_names()
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .or_api(ErrorCode::CalendarUnavailable, "Calendar unavailable")?;
 
-    let mut settings: UserSettingsResponse = match state
-        .site_repo
-        .get_settings()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-    {
+    let mut settings: UserSettingsResponse = match state.site_repo.get_settings().await.or_api(
+        ErrorCode::SiteDatabaseUnavailable,
+        "Settings could not be loaded",
+    )? {
         Some(s) => s.into(),
         None => UserSettings::default().into(),
     };
@@ -129,12 +678,11 @@ async fn get_settings(
 async fn save_settings(
     State(state): State<AppState>,
     Json(settings): Json<UserSettings>,
-) -> Result<StatusCode, StatusCode> {
-    state
-        .site_repo
-        .save_settings(&settings)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+) -> Result<StatusCode, ApiError> {
+    state.site_repo.save_settings(&settings).await.or_api(
+        ErrorCode::SiteDatabaseUnavailable,
+        "Settings could not be saved",
+    )?;
     Ok(StatusCode::OK)
 }
 
@@ -142,7 +690,16 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/sites", get(get_sites))
         .route("/sites", put(update_site))
-        .route("/sites/{site_name}", delete(delete_site))
+        .route("/sites/within-polygon", post(search_sites_in_polygon))
+        .route("/sites/along-route", post(search_sites_along_route))
+        .route("/regions", get(get_regions))
+        .route("/regions/{name}/sites", get(get_region_sites))
+        .route("/sites/paragliding-earth", get(search_paragliding_earth))
+        .route("/sites/search", get(search_sites))
+        .route("/providers/status", get(get_provider_statuses))
+        .route("/sites/{site_name}", get(get_site).delete(delete_site))
+        .route("/sites/{site_name}/forecast", get(get_site_forecast))
+        .route("/sites/{site_name}/validate", post(validate_launch_edit))
         .route(
             "/sites/import",
             post(import_sites).layer(RequestBodyLimitLayer::new(50 * 1024 * 1024)),
@@ -152,126 +709,730 @@ pub fn router() -> Router<AppState> {
             post(analyze_flight).layer(RequestBodyLimitLayer::new(50 * 1024 * 1024)),
         )
         .route("/elevation", get(get_elevation))
+        .route("/elevation/batch", post(get_elevations))
+        .route("/sounding", get(get_soundings))
+        .route("/wind-profile", get(get_wind_profile))
         .route("/geocode", get(geocode))
+        .route("/reverse-geocode", get(reverse_geocode))
+        .route("/forecast", get(get_location_forecast))
         .route("/settings", get(get_settings))
         .route("/settings", put(save_settings))
         .route("/weather-models", get(get_weather_models))
+        .route("/forecast/history", get(get_forecast_history))
+        .route("/forecast/diff", get(get_forecast_diff))
+        .route("/outlook/compact", get(get_compact_outlook))
+        .route("/forecast/today.gpx", get(get_today_gpx))
+        .route("/forecast/today.kml", get(get_today_kml))
+        .route("/forecast/today.xctsk", get(get_today_xctsk))
+        .route("/forecast/today.wpt", get(get_today_wpt))
+        .route("/forecast/weekend.pdf", get(get_weekend_pdf))
+        .route("/forecast/markdown", get(get_weekend_markdown))
+        .route("/forecast/jobs", post(submit_forecast_job))
+        .route("/forecast/jobs/{id}", get(get_forecast_job))
+        .route(
+            "/integrations/home-assistant",
+            get(get_home_assistant_sensor),
+        )
         .route("/calendar/refresh", post(trigger_calendar_job))
+        .route("/feeds/{token}/flyable.ics", get(get_ics_feed))
+        .route("/jobs", get(get_jobs))
+        .route("/jobs/{name}/run", post(run_job))
+}
+
+#[instrument(skip(state))]
+async fn get_jobs(State(state): State<AppState>) -> Json<Vec<JobStatus>> {
+    Json(state.scheduler.statuses())
+}
+
+/// Starts a job in the background, whether it is scheduled or not. Answers 409
+/// while the previous run is still going.
+#[instrument(skip(state))]
+async fn run_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let Some(job) = Job::from_name(&name) else {
+        return Err(TravelAiError::not_found("Job", name).into());
+    };
+    state
+        .scheduler
+        .trigger(job, &state)
+        .map_err(|_| ApiError::new(ErrorCode::JobAlreadyRunning, "Job is already running"))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Starts scoring every site within a radius, which can take longer than a
+/// request may when the weather isn't cached. Answers 202 with the id to poll
+/// `GET /forecast/jobs/{id}` with, or 429 while too many are running.
+#[instrument(skip_all, fields(radius_km = request.radius_km))]
+async fn submit_forecast_job(
+    State(state): State<AppState>,
+    Json(request): Json<AreaForecastRequest>,
+) -> Result<(StatusCode, Json<SubmittedForecast>), ApiError> {
+    validation::validate(&request, state.api_limits)?;
+    let id = state
+        .scheduler
+        .submit_forecast(request, &state)
+        .map_err(|_| {
+            ApiError::new(
+                ErrorCode::RateLimited,
+                "Too many forecasts are being computed, try again shortly",
+            )
+        })?;
+    Ok((StatusCode::ACCEPTED, Json(SubmittedForecast { id })))
+}
+
+/// Progress of a submitted forecast, with its sites once it is done.
+#[instrument(skip(state))]
+async fn get_forecast_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ForecastJob>, ApiError> {
+    state
+        .scheduler
+        .forecast(&id)
+        .map(Json)
+        .ok_or_else(|| TravelAiError::not_found("Forecast job", id).into())
+}
+
+/// Planned suggestions as an ICS feed for calendar subscriptions. Unknown
+/// tokens get the same 404 as a disabled feed.
+#[instrument(skip_all)]
+async fn get_ics_feed(State(state): State<AppState>, Path(token): Path<String>) -> Response {
+    let config = match CalendarConfig::load() {
+        Ok(config) => config,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    match config.feed_token {
+        Some(expected) if tokens_match(&expected, &token) => {}
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    }
+
+    let events = match calendar_job::preview(&state).await {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::error!(error = ?e, "Failed to plan events for ICS feed");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let body = ics::render("Flyable days", &events, Utc::now());
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+/// Compares without exiting early, so response times don't leak the token.
+fn tokens_match(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[derive(Deserialize, Debug)]
+struct CalendarRefreshQuery {
+    #[serde(default)]
+    dry_run: bool,
 }
 
+impl Validate for CalendarRefreshQuery {}
+
+/// Starts a calendar sync in the background. With `dry_run=true` the sync runs
+/// against an in-memory calendar and the events it would create are returned.
 #[instrument(skip(state))]
-async fn trigger_calendar_job(State(state): State<AppState>) -> StatusCode {
-    tokio::spawn(async move {
-        if let Err(e) = calendar_job::run(&state).await {
-            tracing::error!(error = ?e, "Manual calendar job trigger failed");
+async fn trigger_calendar_job(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<CalendarRefreshQuery>,
+) -> Result<Response, ApiError> {
+    if query.dry_run {
+        let events = calendar_job::dry_run(&state)
+            .await
+            .or_api(ErrorCode::CalendarSyncFailed, "Calendar dry run failed")?;
+        return Ok(Json(events).into_response());
+    }
+    state
+        .scheduler
+        .trigger(Job::CalendarSync, &state)
+        .map_err(|_| {
+            ApiError::new(
+                ErrorCode::JobAlreadyRunning,
+                "Calendar sync is already running",
+            )
+        })?;
+    Ok(StatusCode::ACCEPTED.into_response())
+}
+
+/// With `latitude`, `longitude` and `radius_km` set only sites within the
+/// radius are returned, closest first; `country` limits them to the given
+/// countries. Clients accepting `application/x-ndjson` get one site per line,
+/// so they can render them before the whole list has arrived.
+#[instrument(skip(state, query, headers))]
+async fn get_sites(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ValidQuery(query): ValidQuery<SitesQuery>,
+) -> Response {
+    let sites = match (query.latitude, query.longitude, query.radius_km) {
+        (Some(latitude), Some(longitude), Some(radius_km)) => {
+            let center = Location::new(latitude, longitude, String::new(), String::new());
+            state
+                .site_repo
+                .fetch_launches_within_radius(&center, radius_km)
+                .await
+                .into_iter()
+                .map(|(site, _distance)| site)
+                .collect()
         }
+        _ => state.site_repo.fetch_all_sites().await,
+    };
+    let countries = countries(query.country.as_deref());
+    let sites: Vec<ParaglidingSite> = sites
+        .into_iter()
+        .filter(|site| site.in_countries(&countries))
+        .collect();
+    if !accepts(&headers, NDJSON) {
+        return Json(sites).into_response();
+    }
+
+    let lines = futures::stream::iter(sites).map(|site| {
+        serde_json::to_vec(&site).map(|mut line| {
+            line.push(b'\n');
+            Bytes::from(line)
+        })
     });
-    StatusCode::ACCEPTED
+    ([(header::CONTENT_TYPE, NDJSON)], Body::from_stream(lines)).into_response()
+}
+
+const NDJSON: &str = "application/x-ndjson";
+
+/// Whether the `Accept` header lists `media_type`.
+fn accepts(headers: &HeaderMap, media_type: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            range
+                .split(';')
+                .next()
+                .is_some_and(|range| range.trim().eq_ignore_ascii_case(media_type))
+        })
+}
+
+/// Country codes of a `country` query parameter; empty if it is missing.
+fn countries(param: Option<&str>) -> Vec<String> {
+    param
+        .into_iter()
+        .flat_map(|param| param.split(','))
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[instrument(skip_all, fields(corners = request.polygon.len()))]
+async fn search_sites_in_polygon(
+    State(state): State<AppState>,
+    Json(request): Json<PolygonSearchRequest>,
+) -> Result<Json<Vec<ParaglidingSite>>, ApiError> {
+    validation::validate(&request, state.api_limits)?;
+    let sites = state
+        .site_repo
+        .site_index()
+        .await
+        .or_api(ErrorCode::Internal, "Failed to read sites")?;
+    Ok(Json(sites.within_polygon(&request.polygon)))
+}
+
+/// Sites within `corridor_km` of a route, e.g. the drive to a holiday, in the
+/// order they are passed.
+#[instrument(skip_all, fields(points = request.route.len(), corridor_km = request.corridor_km))]
+async fn search_sites_along_route(
+    State(state): State<AppState>,
+    Json(request): Json<RouteSearchRequest>,
+) -> Result<Json<Vec<SiteAlongRoute>>, ApiError> {
+    validation::validate(&request, state.api_limits)?;
+    let sites = state
+        .site_repo
+        .site_index()
+        .await
+        .or_api(ErrorCode::Internal, "Failed to read sites")?;
+    Ok(Json(sites.along_route(&request.route, request.corridor_km)))
+}
+
+#[instrument]
+async fn get_regions() -> Json<&'static [Region]> {
+    Json(REGIONS)
+}
+
+/// Sites with a launch inside the region.
+#[instrument(skip(state))]
+async fn get_region_sites(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<ParaglidingSite>>, ApiError> {
+    let region =
+        regions::find_region(&name).ok_or_else(|| TravelAiError::not_found("Region", &name))?;
+    let sites = state
+        .site_repo
+        .site_index()
+        .await
+        .or_api(ErrorCode::Internal, "Failed to read sites")?;
+    Ok(Json(region.sites(&sites)))
+}
+
+/// Best site, window and wind for the next three days around home, small
+/// enough for watch widgets.
+#[instrument(skip(state))]
+async fn get_compact_outlook(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CompactDay>>, ApiError> {
+    let home = home(&state).await?;
+    let outlook = outlook::compact_outlook(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &home,
+        Utc::now().date_naive(),
+        3,
+    )
+    .await
+    .or_api(
+        ErrorCode::WeatherUnavailable,
+        "Outlook could not be computed",
+    )?;
+    Ok(Json(outlook))
+}
+
+/// Today's flyable launches and their landings as GPX waypoints, best first.
+#[instrument(skip(state))]
+async fn get_today_gpx(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let today = Utc::now().date_naive();
+    let sites: Vec<SiteDay> = days_on(&state, today)
+        .await?
+        .days
+        .into_iter()
+        .filter(|d| d.flyable)
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gpx+xml"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"flyable-today.gpx\"",
+            ),
+        ],
+        gpx::render(today, &sites),
+    )
+        .into_response())
+}
+
+/// Every site around home with its launch sectors and today's wind, for
+/// Google Earth.
+#[instrument(skip(state))]
+async fn get_today_kml(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let today = Utc::now().date_naive();
+    let sites = days_on(&state, today).await?.days;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/vnd.google-earth.kml+xml"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"sites-today.kml\"",
+            ),
+        ],
+        kml::render(today, &sites),
+    )
+        .into_response())
+}
+
+/// Task from the launch of today's best site to its landing, for XCTrack and
+/// flyXC.
+#[instrument(skip(state))]
+async fn get_today_xctsk(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let best = best_today(&state).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/xctsk"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"best-today.xctsk\"",
+            ),
+        ],
+        xctrack::task(&best.site).to_string(),
+    )
+        .into_response())
 }
 
+/// Launches and landings of today's best site as a `$FormatGEO` waypoint file.
 #[instrument(skip(state))]
-async fn get_sites(State(state): State<AppState>) -> Result<Json<Vec<ParaglidingSite>>, StatusCode> {
-    let sites = state.site_repo.fetch_all_sites().await;
-    Ok(Json(sites))
+async fn get_today_wpt(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let best = best_today(&state).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"best-today.wpt\"",
+            ),
+        ],
+        xctrack::waypoints(&best.site),
+    )
+        .into_response())
+}
+
+/// One-page briefing of the coming weekend for the club notice board.
+#[instrument(skip(state))]
+async fn get_weekend_pdf(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let home = home(&state).await?;
+    let days = report::weekend(Utc::now().date_naive());
+    let sites = weekend_days(&state, &home, &days).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"weekend-briefing.pdf\"",
+            ),
+        ],
+        report::render(&home, &days, &sites.days, &sites.errors),
+    )
+        .into_response())
+}
+
+/// The weekend outlook as Markdown for club group chats.
+async fn get_weekend_markdown(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let home = home(&state).await?;
+    let days = report::weekend(Utc::now().date_naive());
+    let sites = weekend_days(&state, &home, &days).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+        report::markdown::render(&days, &sites.days, &sites.errors),
+    )
+        .into_response())
+}
+
+/// Today's best site as a Home Assistant REST sensor.
+#[instrument(skip(state))]
+async fn get_home_assistant_sensor(
+    State(state): State<AppState>,
+) -> Result<Json<home_assistant::Sensor>, ApiError> {
+    let today = Utc::now().date_naive();
+    let sites = days_on(&state, today).await?;
+    Ok(Json(home_assistant::sensor(
+        today,
+        &sites.days,
+        &sites.errors,
+    )))
+}
+
+async fn weekend_days(
+    state: &AppState,
+    home: &Location,
+    days: &[NaiveDate],
+) -> Result<SiteDays, ApiError> {
+    outlook::days_in(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        home,
+        days,
+    )
+    .await
+    .or_api(ErrorCode::WeatherUnavailable, "Sites could not be scored")
+}
+
+/// The flyable site with the most flyable hours today.
+async fn best_today(state: &AppState) -> Result<SiteDay, ApiError> {
+    days_on(state, Utc::now().date_naive())
+        .await?
+        .days
+        .into_iter()
+        .find(|d| d.flyable)
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No site is flyable today"))
+}
+
+async fn days_on(state: &AppState, date: NaiveDate) -> Result<SiteDays, ApiError> {
+    let home = home(state).await?;
+    outlook::days_on(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &home,
+        date,
+    )
+    .await
+    .or_api(ErrorCode::WeatherUnavailable, "Sites could not be scored")
+}
+
+/// The home location from the settings.
+async fn home(state: &AppState) -> Result<Location, ApiError> {
+    let settings = state
+        .site_repo
+        .get_settings()
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Settings could not be loaded",
+        )?
+        .unwrap_or_default();
+    Ok(Location::new(
+        settings.location_latitude,
+        settings.location_longitude,
+        settings.location_name,
+        String::new(),
+    ))
 }
 
 #[instrument(skip(state, site), fields(site = %site.name))]
 async fn update_site(
     State(state): State<AppState>,
     Json(site): Json<ParaglidingSite>,
-) -> Result<StatusCode, StatusCode> {
-    state
-        .site_repo
-        .save_site(site)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+) -> Result<StatusCode, ApiError> {
+    state.site_repo.save_site(site).await.or_api(
+        ErrorCode::SiteDatabaseUnavailable,
+        "Site could not be saved",
+    )?;
     Ok(StatusCode::OK)
 }
 
 #[instrument(skip(state), fields(site = %site_name))]
-async fn delete_site(
+async fn get_site(
     State(state): State<AppState>,
     Path(site_name): Path<String>,
-) -> Result<StatusCode, StatusCode> {
-    state
+) -> Result<Json<SiteDetail>, ApiError> {
+    let site = state
+        .site_repo
+        .get_site(&site_name)
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Site could not be loaded",
+        )?
+        .ok_or_else(|| TravelAiError::not_found("Site", &site_name))?;
+    let rules = site
+        .characteristics
+        .as_ref()
+        .map(|c| c.rules())
+        .unwrap_or_default();
+    Ok(Json(SiteDetail {
+        warnings: rules.iter().map(SiteRule::warning).collect(),
+        rules,
+        site,
+    }))
+}
+
+/// Daily summaries with flyable hours and windows for the site's first launch,
+/// and for a favorite the nearby sites to fly on the days it can't be flown.
+#[instrument(skip(state, query), fields(site = %site_name))]
+async fn get_site_forecast(
+    State(state): State<AppState>,
+    Path(site_name): Path<String>,
+    ValidQuery(query): ValidQuery<SiteForecastQuery>,
+) -> Result<Json<SiteForecast>, ApiError> {
+    let site = state
+        .site_repo
+        .get_site(&site_name)
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Site could not be loaded",
+        )?
+        .ok_or_else(|| TravelAiError::not_found("Site", &site_name))?;
+    let launch = site
+        .launches
+        .first()
+        .ok_or_else(|| TravelAiError::not_found("Launch", &site_name))?;
+    let forecast = state
+        .weather
+        .get_forecast(
+            launch.location.clone(),
+            site.preferred_weather_model.clone(),
+        )
+        .await
+        .or_api(
+            ErrorCode::WeatherUnavailable,
+            "Weather forecast unavailable",
+        )?;
+    let evaluation = source::evaluate(
+        &state.evaluations,
+        &site,
+        &forecast,
+        state.ensemble.as_deref(),
+        Some(state.sounding.as_ref()),
+    )
+    .await;
+    let scores = source::day_scores(
+        &state.forecast_history,
+        &site.name,
+        &evaluation.daily_summaries,
+    )
+    .await;
+    let alternatives = alternatives::find_alternatives(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &site,
+        &evaluation,
+        query.alternatives_radius_km.unwrap_or(50.0),
+        query.prefer_quiet,
+    )
+    .await
+    .or_api(
+        ErrorCode::SiteDatabaseUnavailable,
+        "Alternatives could not be computed",
+    )?;
+    Ok(Json(SiteForecast {
+        evaluation,
+        alternatives: alternatives.days,
+        alternative_errors: alternatives.errors,
+        scores,
+    }))
+}
+
+/// How the last 30 days would have scored with a launch's proposed direction
+/// sector and wind limits, next to how they scored with the current ones.
+#[instrument(skip(state, edit), fields(site = %site_name, launch = edit.launch))]
+async fn validate_launch_edit(
+    State(state): State<AppState>,
+    Path(site_name): Path<String>,
+    Json(edit): Json<LaunchEdit>,
+) -> Result<Json<LaunchCheck>, ApiError> {
+    let limits = [edit.max_wind_kmh, edit.max_gust_kmh];
+    if limits.iter().flatten().any(|kmh| *kmh <= 0.0) {
+        return Err(TravelAiError::invalid_input("Wind limits must be positive").into());
+    }
+    let site = state
         .site_repo
-        .delete_site(&site_name)
+        .get_site(&site_name)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Site could not be loaded",
+        )?
+        .ok_or_else(|| TravelAiError::not_found("Site", &site_name))?;
+    let settings = state
+        .site_repo
+        .get_settings()
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Settings could not be loaded",
+        )?
+        .unwrap_or_default();
+    let check = launch_check::check_launch_edit(
+        &site,
+        state.weather_history.as_ref(),
+        &edit,
+        settings.minimum_flyable_hours,
+        state.evaluations.hysteresis(),
+    )
+    .await
+    .or_api(ErrorCode::WeatherUnavailable, "Past weather unavailable")?
+    .ok_or_else(|| TravelAiError::not_found("Launch", edit.launch))?;
+    Ok(Json(check))
+}
+
+#[instrument(skip(state), fields(site = %site_name))]
+async fn delete_site(
+    State(state): State<AppState>,
+    Path(site_name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state.site_repo.delete_site(&site_name).await.or_api(
+        ErrorCode::SiteDatabaseUnavailable,
+        "Site could not be deleted",
+    )?;
     Ok(StatusCode::OK)
 }
 
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    mode: ParseMode,
+}
+
+impl Validate for ImportQuery {}
+
 #[derive(Serialize, Deserialize)]
 pub struct ImportResponse {
     pub imported: usize,
+    pub skipped: Vec<SkippedRecord>,
+    /// Set when so many records failed that the DHV format has likely changed
+    pub schema_drift: bool,
 }
 
-#[instrument(skip(state, body))]
+#[instrument(skip(state, query, body), fields(mode = ?query.mode))]
 async fn import_sites(
     State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<ImportQuery>,
     body: Body,
-) -> Result<Json<ImportResponse>, StatusCode> {
+) -> Result<Json<ImportResponse>, ApiError> {
     tracing::info!("Starting DHV file import");
 
     let bytes = axum::body::to_bytes(body, 50 * 1024 * 1024)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "Failed to read request body");
-            StatusCode::BAD_REQUEST
+            ApiError::new(ErrorCode::InvalidInput, "Request body could not be read")
         })?;
 
     tracing::info!(bytes = bytes.len(), "Read request body");
 
     let xml_content = String::from_utf8(bytes.to_vec()).map_err(|e| {
         tracing::error!(error = ?e, "Request body is not valid UTF-8");
-        StatusCode::BAD_REQUEST
+        ApiError::new(ErrorCode::InvalidInput, "Upload must be UTF-8")
     })?;
 
-    let mut imported_count = 0;
+    let report = dhv::parse_sites(&xml_content, query.mode).map_err(|e| {
+        tracing::error!(error = ?e, "Failed to parse XML");
+        ApiError::new(ErrorCode::UnparsableUpload, "Not a valid DHV site export")
+    })?;
+    report.log("upload");
 
-    match dhv::parse_sites_from_xml(&xml_content) {
-        Ok(sites) => {
-            tracing::info!(parsed_sites = sites.len(), "Parsed sites from XML");
-            for site in sites {
-                if let Err(e) = state.site_repo.save_site(site).await {
-                    tracing::warn!(error = ?e, "Failed to save site");
-                } else {
-                    imported_count += 1;
-                }
-            }
-        }
-        Err(e) => {
-            tracing::error!(error = ?e, "Failed to parse XML");
+    let schema_drift = report.schema_drift_suspected();
+    let mut imported_count = 0;
+    for site in report.sites {
+        if let Err(e) = state.site_repo.save_site(site).await {
+            tracing::warn!(error = ?e, "Failed to save site");
+        } else {
+            imported_count += 1;
         }
     }
 
     tracing::info!(imported = imported_count, "Import complete");
     Ok(Json(ImportResponse {
         imported: imported_count,
+        skipped: report.skipped,
+        schema_drift,
     }))
 }
 
 #[instrument(skip(body))]
-async fn analyze_flight(body: Body) -> Result<Json<flight_analytics::FlightAnalysis>, StatusCode> {
+async fn analyze_flight(body: Body) -> Result<Json<flight_analytics::FlightAnalysis>, ApiError> {
     tracing::info!("Starting flight analysis");
 
     let bytes = axum::body::to_bytes(body, 50 * 1024 * 1024)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "Failed to read request body");
-            StatusCode::BAD_REQUEST
+            ApiError::new(ErrorCode::InvalidInput, "Request body could not be read")
         })?;
 
     tracing::info!(bytes = bytes.len(), "Read request body");
 
     let kml_content = String::from_utf8(bytes.to_vec()).map_err(|e| {
         tracing::error!(error = ?e, "Request body is not valid UTF-8");
-        StatusCode::BAD_REQUEST
+        ApiError::new(ErrorCode::InvalidInput, "Upload must be UTF-8")
     })?;
 
     let track = Track::from_kml(&kml_content).map_err(|e| {
         tracing::error!(error = ?e, "Failed to parse KML");
-        StatusCode::BAD_REQUEST
+        ApiError::new(ErrorCode::UnparsableUpload, "Not a valid KML track")
     })?;
 
     tracing::info!(points = track.points.len(), "Parsed track");
@@ -293,3 +1454,89 @@ async fn get_weather_models(State(state): State<AppState>) -> Json<WeatherModels
         models: state.weather.available_models(),
     })
 }
+
+/// What changed for the sites within the search radius of a place between the
+/// forecast runs current at `from` and at `to`.
+#[instrument(skip(state, query), fields(location = %query.location, from = %query.from, to = %query.to))]
+async fn get_forecast_diff(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<ForecastDiffQuery>,
+) -> Result<Response, ApiError> {
+    let location = match pick_location(&state, &query.location, query.candidate).await? {
+        Ok(location) => location,
+        Err(ambiguous) => return Ok(ambiguous),
+    };
+    let settings = state
+        .site_repo
+        .get_settings()
+        .await
+        .or_api(
+            ErrorCode::SiteDatabaseUnavailable,
+            "Settings could not be loaded",
+        )?
+        .unwrap_or_default();
+
+    let mut diffs: Vec<SiteDiff> = Vec::new();
+    for (site, _distance) in state
+        .site_repo
+        .fetch_launches_within_radius(&location, settings.search_radius_km)
+        .await
+    {
+        let snapshots = state
+            .forecast_history
+            .snapshots(&site.name)
+            .await
+            .or_api(ErrorCode::Cache, "Forecast history unavailable")?;
+        diffs.extend(history::diff(
+            &site.name,
+            history::run_as_of(&snapshots, query.from),
+            history::run_as_of(&snapshots, query.to),
+            settings.minimum_flyable_hours,
+        ));
+    }
+    Ok(Json(diffs).into_response())
+}
+
+/// How the forecast of a day at a site, or at the site closest to a place
+/// within the search radius, changed from run to run.
+#[instrument(skip(state, query), fields(site = ?query.site, location = ?query.location, date = %query.date))]
+async fn get_forecast_history(
+    State(state): State<AppState>,
+    ValidQuery(query): ValidQuery<ForecastHistoryQuery>,
+) -> Result<Response, ApiError> {
+    let site = match (query.site, query.location) {
+        (Some(site), _) => site,
+        (None, Some(name)) => {
+            let location = match pick_location(&state, &name, query.candidate).await? {
+                Ok(location) => location,
+                Err(ambiguous) => return Ok(ambiguous),
+            };
+            let settings = state
+                .site_repo
+                .get_settings()
+                .await
+                .or_api(
+                    ErrorCode::SiteDatabaseUnavailable,
+                    "Settings could not be loaded",
+                )?
+                .unwrap_or_default();
+            let (site, _distance) = state
+                .site_repo
+                .fetch_launches_within_radius(&location, settings.search_radius_km)
+                .await
+                .into_iter()
+                .next()
+                .ok_or_else(|| TravelAiError::not_found("Site near", &name))?;
+            site.name
+        }
+        (None, None) => {
+            return Err(TravelAiError::invalid_input("Give either site or location").into());
+        }
+    };
+    let entries = state
+        .forecast_history
+        .day_history(&site, query.date)
+        .await
+        .or_api(ErrorCode::Cache, "Forecast history unavailable")?;
+    Ok(Json(entries).into_response())
+}
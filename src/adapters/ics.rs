@@ -0,0 +1,340 @@
+//! Renders calendar events as an iCalendar (RFC 5545) feed, so calendar apps
+//! can subscribe to the suggestions without any OAuth, and reads back the
+//! events it rendered for the ICS file and CalDAV backends.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::domain::calendar::{CalendarEvent, EventColor};
+
+const PRODID: &str = "-//travelai//flyable//EN";
+/// Lines longer than this many octets are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+pub fn render(calendar_name: &str, events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(calendar_name)));
+    for event in events {
+        push_event(&mut out, event, now);
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn push_event(out: &mut String, event: &CalendarEvent, now: DateTime<Utc>) {
+    push_line(out, "BEGIN:VEVENT");
+    push_line(out, &format!("UID:{}", escape(&uid(event))));
+    push_line(out, &format!("DTSTAMP:{}", date_time(now)));
+    if event.is_all_day {
+        push_line(
+            out,
+            &format!("DTSTART;VALUE=DATE:{}", date(event.start_time)),
+        );
+        push_line(out, &format!("DTEND;VALUE=DATE:{}", date(event.end_time)));
+    } else {
+        push_line(out, &format!("DTSTART:{}", date_time(event.start_time)));
+        push_line(out, &format!("DTEND:{}", date_time(event.end_time)));
+    }
+    push_line(out, &format!("SUMMARY:{}", escape(&event.title)));
+    if let Some(location) = &event.location {
+        push_line(out, &format!("LOCATION:{}", escape(location)));
+    }
+    if let Some(body) = &event.body {
+        push_line(out, &format!("DESCRIPTION:{}", escape(body)));
+    }
+    if let Some(color) = event.color {
+        push_line(out, &format!("COLOR:{}", color_name(color)));
+    }
+    for minutes in &event.reminder_minutes {
+        push_line(out, "BEGIN:VALARM");
+        push_line(out, "ACTION:DISPLAY");
+        push_line(out, &format!("DESCRIPTION:{}", escape(&event.title)));
+        push_line(out, &format!("TRIGGER:-PT{minutes}M"));
+        push_line(out, "END:VALARM");
+    }
+    push_line(out, "END:VEVENT");
+}
+
+const UID_SUFFIX: &str = "@travelai";
+
+/// Stable across renders so subscribed clients update events instead of
+/// duplicating them.
+pub fn uid(event: &CalendarEvent) -> String {
+    match &event.key {
+        Some(key) => format!("{key}{UID_SUFFIX}"),
+        None => format!(
+            "{}/{}{UID_SUFFIX}",
+            date_time(event.start_time),
+            event.title
+        ),
+    }
+}
+
+/// The calendar name of a feed rendered by [`render`].
+pub fn calendar_name(text: &str) -> Option<String> {
+    unfold(text)
+        .iter()
+        .find_map(|line| line.strip_prefix("X-WR-CALNAME:").map(unescape))
+}
+
+/// The events of a feed rendered by [`render`]. Events with times this
+/// module doesn't write, such as local times with a `TZID`, are skipped.
+pub fn parse(text: &str) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut event: Option<CalendarEvent> = None;
+    let mut in_alarm = false;
+    for line in unfold(text) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name, event.as_mut()) {
+            ("BEGIN", _) if value == "VEVENT" => event = Some(empty_event()),
+            ("BEGIN", Some(_)) if value == "VALARM" => in_alarm = true,
+            ("END", Some(_)) if value == "VALARM" => in_alarm = false,
+            ("END", Some(_)) if value == "VEVENT" => {
+                events.extend(event.take().filter(|e| e.start_time < e.end_time))
+            }
+            ("TRIGGER", Some(e)) if in_alarm => e.reminder_minutes.extend(
+                value
+                    .strip_prefix("-PT")
+                    .and_then(|v| v.strip_suffix('M'))
+                    .and_then(|v| v.parse::<u32>().ok()),
+            ),
+            (_, Some(_)) if in_alarm => {}
+            ("UID", Some(e)) => {
+                let uid = unescape(value);
+                e.key = uid.strip_suffix(UID_SUFFIX).map(str::to_string);
+            }
+            ("DTSTART" | "DTEND", Some(e)) => {
+                let all_day = params.contains("VALUE=DATE");
+                let Some(time) = parse_time(value, all_day) else {
+                    event = None;
+                    continue;
+                };
+                e.is_all_day = all_day;
+                if name == "DTSTART" {
+                    e.start_time = time;
+                } else {
+                    e.end_time = time;
+                }
+            }
+            ("SUMMARY", Some(e)) => e.title = unescape(value),
+            ("LOCATION", Some(e)) => e.location = Some(unescape(value)),
+            ("DESCRIPTION", Some(e)) => e.body = Some(unescape(value)),
+            ("COLOR", Some(e)) => e.color = parse_color(value),
+            _ => {}
+        }
+    }
+    events
+}
+
+fn empty_event() -> CalendarEvent {
+    CalendarEvent {
+        title: String::new(),
+        start_time: DateTime::<Utc>::MIN_UTC,
+        end_time: DateTime::<Utc>::MIN_UTC,
+        is_all_day: false,
+        location: None,
+        body: None,
+        color: None,
+        reminder_minutes: vec![],
+        key: None,
+        site: None,
+    }
+}
+
+fn parse_time(value: &str, all_day: bool) -> Option<DateTime<Utc>> {
+    if all_day {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// CSS colour names, as RFC 7986 asks for.
+fn color_name(color: EventColor) -> &'static str {
+    match color {
+        EventColor::Green => "green",
+        EventColor::Yellow => "yellow",
+        EventColor::Orange => "orange",
+    }
+}
+
+fn parse_color(value: &str) -> Option<EventColor> {
+    [EventColor::Green, EventColor::Yellow, EventColor::Orange]
+        .into_iter()
+        .find(|c| color_name(*c).eq_ignore_ascii_case(value))
+}
+
+fn date_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn date(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%d").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Logical lines of `text`, with folded continuation lines joined back.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Appends `line` with CRLF, folding it so no physical line exceeds 75 octets.
+/// Folds never split a UTF-8 character.
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space of a continuation line counts towards its length.
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn event() -> CalendarEvent {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap();
+        CalendarEvent {
+            title: "Hill, north".into(),
+            start_time: start,
+            end_time: start + Duration::hours(4),
+            is_all_day: false,
+            location: None,
+            body: Some("Flyable in the afternoon\nExpect a busy launch".into()),
+            color: None,
+            reminder_minutes: vec![],
+            key: Some("2026-06-13/Hill/1000".into()),
+            site: None,
+        }
+    }
+
+    #[test]
+    fn renders_timed_event_with_escaped_text() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 12, 8, 0, 0).unwrap();
+        let ics = render("Paragliding", &[event()], now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:2026-06-13/Hill/1000@travelai\r\n"));
+        assert!(ics.contains("DTSTAMP:20260612T080000Z\r\n"));
+        assert!(ics.contains("DTSTART:20260613T100000Z\r\nDTEND:20260613T140000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Hill\\, north\r\n"));
+        assert!(ics.contains("DESCRIPTION:Flyable in the afternoon\\nExpect a busy launch\r\n"));
+    }
+
+    #[test]
+    fn all_day_event_uses_dates() {
+        let mut event = event();
+        event.is_all_day = true;
+        event.start_time = Utc.with_ymd_and_hms(2026, 6, 13, 0, 0, 0).unwrap();
+        event.end_time = event.start_time + Duration::days(1);
+
+        let ics = render("Paragliding", &[event], Utc::now());
+
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260613\r\nDTEND;VALUE=DATE:20260614\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_folded_on_char_boundaries() {
+        let mut out = String::new();
+        push_line(&mut out, &format!("SUMMARY:{}", "🟢".repeat(40)));
+
+        for line in out.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(line.len() <= MAX_LINE_OCTETS, "{} octets", line.len());
+        }
+        let unfolded = out.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("SUMMARY:{}\r\n", "🟢".repeat(40)));
+    }
+
+    #[test]
+    fn parse_reads_back_rendered_events() {
+        let mut timed = event();
+        timed.title = "🟢".repeat(40);
+        timed.color = Some(EventColor::Green);
+        timed.reminder_minutes = vec![120];
+        timed.location = Some("Hill; north".into());
+        let mut all_day = event();
+        all_day.is_all_day = true;
+        all_day.start_time = Utc.with_ymd_and_hms(2026, 6, 14, 0, 0, 0).unwrap();
+        all_day.end_time = all_day.start_time + Duration::days(1);
+        all_day.key = None;
+
+        let ics = render(
+            "Paragliding, Alps",
+            &[timed.clone(), all_day.clone()],
+            Utc::now(),
+        );
+        let events = parse(&ics);
+
+        assert_eq!(calendar_name(&ics).as_deref(), Some("Paragliding, Alps"));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, timed.title);
+        assert_eq!(events[0].start_time, timed.start_time);
+        assert_eq!(events[0].end_time, timed.end_time);
+        assert_eq!(events[0].location, timed.location);
+        assert_eq!(events[0].body, timed.body);
+        assert_eq!(events[0].color, Some(EventColor::Green));
+        assert_eq!(events[0].reminder_minutes, vec![120]);
+        assert_eq!(events[0].key, timed.key);
+        assert!(events[1].is_all_day);
+        assert_eq!(events[1].start_time, all_day.start_time);
+        assert_eq!(
+            events[1].key.as_deref(),
+            Some("20260614T000000Z/Hill, north")
+        );
+    }
+
+    #[test]
+    fn events_in_local_time_are_skipped() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:x\r\n\
+                   DTSTART;TZID=Europe/Berlin:20260613T100000\r\n\
+                   DTEND;TZID=Europe/Berlin:20260613T120000\r\nSUMMARY:Work\r\n\
+                   END:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert!(parse(ics).is_empty());
+    }
+}
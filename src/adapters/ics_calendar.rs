@@ -0,0 +1,236 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    adapters::ics,
+    domain::{calendar::CalendarEvent, ports::CalendarProvider},
+};
+
+/// Calendars as iCalendar files in a directory, one `<name>.ics` per calendar,
+/// for calendar apps that subscribe to a file or to a web server serving the
+/// directory. Every write rewrites the whole file.
+pub struct IcsFileCalendar {
+    dir: PathBuf,
+}
+
+impl IcsFileCalendar {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create calendar directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.ics", file_stem(name)))
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<CalendarEvent>> {
+        match fs::read_to_string(self.path(name)) {
+            Ok(text) => Ok(ics::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Err(anyhow!("Calendar {} not found", name))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes to a temporary file first, so subscribers never see half a
+    /// calendar.
+    fn write(&self, name: &str, events: &[CalendarEvent]) -> Result<()> {
+        let path = self.path(name);
+        let partial = path.with_extension("ics.partial");
+        fs::write(&partial, ics::render(name, events, Utc::now()))?;
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+}
+
+/// `name` with everything but letters, digits, spaces, `-` and `_` replaced,
+/// so it can't leave the directory.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn is_ics(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "ics")
+}
+
+#[async_trait]
+impl CalendarProvider for IcsFileCalendar {
+    async fn busy_periods(
+        &self,
+        calendars: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let mut periods = Vec::new();
+        for name in calendars {
+            match self.read(name) {
+                Ok(events) => periods.extend(
+                    events
+                        .iter()
+                        .filter(|e| e.has_overlap(start, end))
+                        .map(|e| (e.start_time, e.end_time)),
+                ),
+                Err(e) => tracing::warn!(name = %name, error = ?e, "Cant read calendar"),
+            }
+        }
+        Ok(periods)
+    }
+
+    async fn get_calendar_names(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if is_ics(&path) {
+                names.extend(ics::calendar_name(&fs::read_to_string(&path)?));
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    async fn clear_calendar(&mut self, name: &str) -> Result<()> {
+        self.read(name)?;
+        self.write(name, &[])
+    }
+
+    async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        self.create_events(calendar, vec![event]).await
+    }
+
+    async fn create_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        let mut all = self.read(calendar)?;
+        all.extend(events);
+        self.write(calendar, &all)
+    }
+
+    async fn create_calendar(&mut self, name: &str) -> Result<()> {
+        if self.path(name).exists() {
+            return Ok(());
+        }
+        self.write(name, &[])
+    }
+
+    async fn event_keys(&self, calendar: &str) -> Result<Vec<Option<String>>> {
+        Ok(self.read(calendar)?.into_iter().map(|e| e.key).collect())
+    }
+
+    async fn upsert_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        self.upsert_events(calendar, vec![event]).await
+    }
+
+    async fn upsert_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        let mut all = self.read(calendar)?;
+        for event in events {
+            match all
+                .iter_mut()
+                .find(|e| e.key.is_some() && e.key == event.key)
+            {
+                Some(existing) => *existing = event,
+                None => all.push(event),
+            }
+        }
+        self.write(calendar, &all)
+    }
+
+    async fn delete_events(&mut self, calendar: &str, keys: &[String]) -> Result<()> {
+        let mut all = self.read(calendar)?;
+        all.retain(|e| e.key.as_ref().is_none_or(|key| !keys.contains(key)));
+        self.write(calendar, &all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(start_h: u32, end_h: u32) -> CalendarEvent {
+        CalendarEvent {
+            title: "evt".into(),
+            start_time: Utc.with_ymd_and_hms(2026, 6, 13, start_h, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2026, 6, 13, end_h, 0, 0).unwrap(),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: Some(format!("2026-06-13/evt/{start_h}")),
+            site: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn events_are_kept_in_one_file_per_calendar() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cal = IcsFileCalendar::new(dir.path()).unwrap();
+        assert!(cal.create_event("Flying", event(10, 12)).await.is_err());
+
+        cal.create_calendar("Flying/Alps").await.unwrap();
+        cal.create_events("Flying/Alps", vec![event(10, 12), event(14, 16)])
+            .await
+            .unwrap();
+
+        // A second instance reads what the first wrote
+        let cal = IcsFileCalendar::new(dir.path()).unwrap();
+        let text = fs::read_to_string(dir.path().join("Flying_Alps.ics")).unwrap();
+        assert_eq!(ics::parse(&text).len(), 2);
+        assert_eq!(cal.get_calendar_names().await.unwrap(), vec!["Flying/Alps"]);
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+        let flying = vec!["Flying/Alps".to_string()];
+        assert!(cal.is_busy(&flying, at(11), at(13)).await.unwrap());
+        assert!(!cal.is_busy(&flying, at(12), at(14)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn clearing_keeps_the_calendar() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cal = IcsFileCalendar::new(dir.path()).unwrap();
+        cal.create_calendar("Flying").await.unwrap();
+        cal.create_event("Flying", event(10, 12)).await.unwrap();
+
+        cal.clear_calendar("Flying").await.unwrap();
+
+        assert!(cal.read("Flying").unwrap().is_empty());
+        assert_eq!(cal.get_calendar_names().await.unwrap(), vec!["Flying"]);
+    }
+
+    #[tokio::test]
+    async fn upserting_replaces_events_with_the_same_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cal = IcsFileCalendar::new(dir.path()).unwrap();
+        cal.create_calendar("Flying").await.unwrap();
+        cal.create_events("Flying", vec![event(10, 12), event(14, 16)])
+            .await
+            .unwrap();
+
+        let moved = CalendarEvent {
+            end_time: Utc.with_ymd_and_hms(2026, 6, 13, 13, 0, 0).unwrap(),
+            ..event(10, 12)
+        };
+        cal.upsert_event("Flying", moved.clone()).await.unwrap();
+        cal.delete_events("Flying", &["2026-06-13/evt/14".into()])
+            .await
+            .unwrap();
+
+        let events = cal.read("Flying").unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].end_time, moved.end_time);
+    }
+}
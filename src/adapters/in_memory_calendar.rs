@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::domain::{calendar::CalendarEvent, ports::CalendarProvider};
+
+/// Calendars kept in memory. Backs tests and dry runs of the calendar sync,
+/// which show what would be written without touching Google.
+#[derive(Debug, Default)]
+pub struct InMemoryCalendar {
+    calendars: BTreeMap<String, Vec<CalendarEvent>>,
+}
+
+impl InMemoryCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event, creating the calendar if needed. Useful to seed busy times.
+    pub fn with_event(mut self, calendar: &str, event: CalendarEvent) -> Self {
+        self.calendars
+            .entry(calendar.to_string())
+            .or_default()
+            .push(event);
+        self
+    }
+
+    pub fn events(&self, calendar: &str) -> &[CalendarEvent] {
+        self.calendars
+            .get(calendar)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl CalendarProvider for InMemoryCalendar {
+    async fn busy_periods(
+        &self,
+        calendars: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        Ok(calendars
+            .iter()
+            .flat_map(|name| self.events(name))
+            .filter(|event| event.has_overlap(start, end))
+            .map(|event| (event.start_time, event.end_time))
+            .collect())
+    }
+
+    async fn get_calendar_names(&self) -> Result<Vec<String>> {
+        Ok(self.calendars.keys().cloned().collect())
+    }
+
+    async fn clear_calendar(&mut self, name: &str) -> Result<()> {
+        self.calendars
+            .get_mut(name)
+            .ok_or_else(|| anyhow!("Calendar {} not found", name))?
+            .clear();
+        Ok(())
+    }
+
+    async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        self.calendars
+            .get_mut(calendar)
+            .ok_or_else(|| anyhow!("Calendar {} not found", calendar))?
+            .push(event);
+        Ok(())
+    }
+
+    async fn create_calendar(&mut self, name: &str) -> Result<()> {
+        self.calendars.entry(name.to_string()).or_default();
+        Ok(())
+    }
+
+    async fn event_keys(&self, calendar: &str) -> Result<Vec<Option<String>>> {
+        Ok(self
+            .events(calendar)
+            .iter()
+            .map(|e| e.key.clone())
+            .collect())
+    }
+
+    async fn upsert_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()> {
+        let events = self
+            .calendars
+            .get_mut(calendar)
+            .ok_or_else(|| anyhow!("Calendar {} not found", calendar))?;
+        match events
+            .iter_mut()
+            .find(|e| e.key.is_some() && e.key == event.key)
+        {
+            Some(existing) => *existing = event,
+            None => events.push(event),
+        }
+        Ok(())
+    }
+
+    async fn delete_events(&mut self, calendar: &str, keys: &[String]) -> Result<()> {
+        self.calendars
+            .get_mut(calendar)
+            .ok_or_else(|| anyhow!("Calendar {} not found", calendar))?
+            .retain(|e| e.key.as_ref().is_none_or(|key| !keys.contains(key)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Timelike};
+
+    fn event(start_h: u32, end_h: u32) -> CalendarEvent {
+        CalendarEvent {
+            title: "evt".into(),
+            start_time: Utc.with_ymd_and_hms(2026, 6, 13, start_h, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2026, 6, 13, end_h, 0, 0).unwrap(),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: None,
+            site: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn busy_only_within_listed_calendars() {
+        let cal = InMemoryCalendar::new()
+            .with_event("Work", event(10, 12))
+            .with_event("Hobby", event(14, 16));
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+
+        let work = vec!["Work".to_string()];
+        assert!(cal.is_busy(&work, at(11), at(13)).await.unwrap());
+        assert!(!cal.is_busy(&work, at(14), at(15)).await.unwrap());
+        assert!(!cal.is_busy(&vec![], at(11), at(13)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn events_need_an_existing_calendar() {
+        let mut cal = InMemoryCalendar::new();
+        assert!(cal.create_event("Flying", event(10, 12)).await.is_err());
+
+        cal.create_calendar("Flying").await.unwrap();
+        cal.create_event("Flying", event(10, 12)).await.unwrap();
+        assert_eq!(cal.events("Flying").len(), 1);
+
+        cal.clear_calendar("Flying").await.unwrap();
+        assert!(cal.events("Flying").is_empty());
+        assert_eq!(cal.get_calendar_names().await.unwrap(), vec!["Flying"]);
+    }
+
+    #[tokio::test]
+    async fn events_with_a_known_key_are_replaced() {
+        let keyed = |start_h, end_h, key: &str| CalendarEvent {
+            key: Some(key.into()),
+            site: None,
+            ..event(start_h, end_h)
+        };
+        let mut cal = InMemoryCalendar::new().with_event("Flying", event(8, 9));
+        cal.upsert_events("Flying", vec![keyed(10, 12, "a"), keyed(14, 16, "b")])
+            .await
+            .unwrap();
+
+        cal.upsert_event("Flying", keyed(11, 13, "a"))
+            .await
+            .unwrap();
+        cal.delete_events("Flying", &["b".into(), "c".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cal.event_keys("Flying").await.unwrap(),
+            vec![None, Some("a".into())]
+        );
+        assert_eq!(cal.events("Flying")[1].start_time.hour(), 11);
+    }
+}
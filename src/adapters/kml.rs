@@ -0,0 +1,193 @@
+//! Renders the sites' day as KML for trip briefings in Google Earth: per launch
+//! a wedge over its launchable wind sector, green if the site is flyable and
+//! red if not, and an arrow of the day's forecast wind blowing into it.
+
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+use quick_xml::escape::escape;
+
+use crate::{
+    adapters::activities::paragliding::outlook::SiteDay,
+    domain::{geo, paragliding::ParaglidingLaunch, units::WindSpeed},
+};
+
+/// Radius of the sector wedges
+const WEDGE_KM: f64 = 0.6;
+/// Angle between the points approximating a wedge's arc
+const ARC_STEP_DEGREES: f64 = 10.0;
+/// Arrow length per m/s of wind, so 5 m/s (18 km/h) draws 0.75 km
+const ARROW_KM_PER_MS: f64 = 0.15;
+const ARROW_HEAD_DEGREES: f64 = 25.0;
+const ARROW_HEAD_SHARE: f64 = 0.3;
+
+/// KML colors are `aabbggrr`.
+const STYLES: &str = r#"    <Style id="flyable"><LineStyle><color>ff00aa00</color></LineStyle><PolyStyle><color>8000ff00</color></PolyStyle></Style>
+    <Style id="not-flyable"><LineStyle><color>ff0000aa</color></LineStyle><PolyStyle><color>600000ff</color></PolyStyle></Style>
+    <Style id="wind"><LineStyle><color>ffff5500</color><width>3</width></LineStyle></Style>
+"#;
+
+pub fn render(date: NaiveDate, sites: &[SiteDay]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n  <Document>\n");
+    let _ = writeln!(out, "    <name>Sites {date}</name>");
+    out.push_str(STYLES);
+    for site_day in sites {
+        push_site(&mut out, site_day);
+    }
+    out.push_str("  </Document>\n</kml>\n");
+    out
+}
+
+fn push_site(out: &mut String, site_day: &SiteDay) {
+    let site = &site_day.site;
+    let wind = site_day.mean_wind();
+    let mut description = if site_day.flyable {
+        format!(
+            "Flyable {}h, {}",
+            site_day.day.total_flyable_hours,
+            site_day.rating().label()
+        )
+    } else {
+        "Not flyable".to_string()
+    };
+    if let Some(window) = site_day.window() {
+        let _ = write!(description, ", {window} UTC");
+    }
+    if let Some((direction, speed)) = wind {
+        let _ = write!(
+            description,
+            ", wind from {direction:.0}° at {:.0} km/h",
+            WindSpeed::from_ms(speed as f32).kmh()
+        );
+    }
+
+    let _ = writeln!(out, "    <Folder><name>{}</name>", escape(&site.name));
+    let style = if site_day.flyable {
+        "flyable"
+    } else {
+        "not-flyable"
+    };
+    for launch in &site.launches {
+        let _ = writeln!(
+            out,
+            "      <Placemark><name>{}</name><description>{}</description><styleUrl>#{style}</styleUrl><Polygon><outerBoundaryIs><LinearRing><coordinates>{}</coordinates></LinearRing></outerBoundaryIs></Polygon></Placemark>",
+            escape(&site.name),
+            escape(&description),
+            coordinates(&wedge(launch)),
+        );
+        if let Some((direction, speed)) = wind {
+            let lines: Vec<String> = arrow(launch, direction, speed)
+                .iter()
+                .map(|line| {
+                    format!(
+                        "<LineString><coordinates>{}</coordinates></LineString>",
+                        coordinates(line)
+                    )
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "      <Placemark><name>Wind</name><styleUrl>#wind</styleUrl><MultiGeometry>{}</MultiGeometry></Placemark>",
+                lines.concat()
+            );
+        }
+    }
+    out.push_str("    </Folder>\n");
+}
+
+/// Closed outline from the launch out along its sector, clockwise from
+/// `direction_degrees_start` to `direction_degrees_stop`. A sector open to
+/// every direction is a full circle.
+fn wedge(launch: &ParaglidingLaunch) -> Vec<(f64, f64)> {
+    let center = launch.location.coordinates();
+    let start = launch.direction_degrees_start;
+    let width = (launch.direction_degrees_stop - start).rem_euclid(360.0);
+    let width = if width == 0.0 { 360.0 } else { width };
+    let steps = (width / ARC_STEP_DEGREES).ceil().max(1.0) as usize;
+    let mut points = vec![center];
+    points.extend((0..=steps).map(|i| {
+        let bearing = start + width * i as f64 / steps as f64;
+        geo::destination(center, bearing, WEDGE_KM)
+    }));
+    points.push(center);
+    points
+}
+
+/// Shaft from upwind to the launch and the two strokes of the head at the
+/// launch, for wind from `direction` at `speed_ms`.
+fn arrow(launch: &ParaglidingLaunch, direction: f64, speed_ms: f64) -> [Vec<(f64, f64)>; 2] {
+    let tip = launch.location.coordinates();
+    let length = speed_ms * ARROW_KM_PER_MS;
+    let tail = geo::destination(tip, direction, length);
+    let head = length * ARROW_HEAD_SHARE;
+    [
+        vec![tail, tip],
+        vec![
+            geo::destination(tip, direction - ARROW_HEAD_DEGREES, head),
+            tip,
+            geo::destination(tip, direction + ARROW_HEAD_DEGREES, head),
+        ],
+    ]
+}
+
+/// `lon,lat` pairs as KML wants them.
+fn coordinates(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(latitude, longitude)| format!("{longitude:.6},{latitude:.6}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{location::Location, paragliding::SiteType};
+
+    fn launch(start: f64, stop: f64) -> ParaglidingLaunch {
+        ParaglidingLaunch {
+            site_type: SiteType::Hang,
+            location: Location::new(47.5, 11.0, "Wank".into(), "DE".into()),
+            direction_degrees_start: start,
+            direction_degrees_stop: stop,
+            elevation: 1750.0,
+        }
+    }
+
+    #[test]
+    fn wedge_spans_the_sector_clockwise() {
+        let points = wedge(&launch(225.0, 315.0));
+        let center = (47.5, 11.0);
+        assert_eq!(points.first(), Some(&center));
+        assert_eq!(points.last(), Some(&center));
+        assert_eq!(points.len(), 12);
+
+        let first = geo::initial_bearing(center, points[1]);
+        let fifth = geo::initial_bearing(center, points[5]);
+        let last = geo::initial_bearing(center, points[10]);
+        assert!((first - 225.0).abs() < 0.1, "{first}");
+        assert!((fifth - 265.0).abs() < 0.1, "{fifth}");
+        assert!((last - 315.0).abs() < 0.1, "{last}");
+    }
+
+    #[test]
+    fn sector_across_north_and_open_sector_wrap_around() {
+        let across_north = wedge(&launch(315.0, 45.0));
+        let sixth = geo::initial_bearing((47.5, 11.0), across_north[6]);
+        assert!((sixth - 5.0).abs() < 0.1, "{sixth}");
+
+        let open = wedge(&launch(90.0, 90.0));
+        assert_eq!(open.len(), 36 + 3);
+    }
+
+    #[test]
+    fn arrow_points_downwind_into_the_launch() {
+        let [shaft, _] = arrow(&launch(225.0, 315.0), 270.0, 5.0);
+        let tip = (47.5, 11.0);
+        assert_eq!(shaft[1], tip);
+        assert!((geo::initial_bearing(tip, shaft[0]) - 270.0).abs() < 0.1);
+        assert!((geo::distance_km(tip, shaft[0]) - 0.75).abs() < 0.01);
+    }
+}
@@ -0,0 +1,68 @@
+//! Posts to a Matrix room through the client-server API, as a bot user whose
+//! access token is configured. Works with any homeserver, including a club's
+//! own, and with rooms bridged elsewhere.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest_middleware::ClientWithMiddleware;
+use serde_json::json;
+
+use crate::{config::MatrixConfig, domain::ports::Notifier};
+
+pub struct MatrixNotifier {
+    http: ClientWithMiddleware,
+    config: MatrixConfig,
+}
+
+impl MatrixNotifier {
+    pub fn new(http: ClientWithMiddleware, config: MatrixConfig) -> Self {
+        Self { http, config }
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    /// The message id is the transaction id, so the homeserver posts a
+    /// retried message once.
+    async fn notify(&self, text: &str, message_id: &str) -> Result<()> {
+        self.http
+            .put(send_url(
+                &self.config.homeserver,
+                &self.config.room_id,
+                message_id,
+            ))
+            .bearer_auth(&self.config.access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(json!({ "msgtype": "m.text", "body": text }).to_string())
+            .send()
+            .await?
+            .error_for_status()
+            .context("Matrix homeserver refused the message")?;
+        Ok(())
+    }
+}
+
+fn send_url(homeserver: &str, room_id: &str, transaction_id: &str) -> String {
+    format!(
+        "{homeserver}/_matrix/client/v3/rooms/{}/send/m.room.message/{transaction_id}",
+        urlencoding::encode(room_id)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn room_id_is_escaped_in_the_send_url() {
+        assert_eq!(
+            send_url("https://matrix.example.org", "!club:example.org", "42"),
+            "https://matrix.example.org/_matrix/client/v3/rooms/%21club%3Aexample.org/send/m.room.message/42"
+        );
+    }
+}
@@ -1,8 +1,33 @@
 pub mod activities;
+pub mod api_error;
 pub mod cache;
+pub mod caldav;
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod dwd_mosmix;
 pub mod email;
 pub mod google_calendar;
+pub mod gpx;
 pub mod graphhopper;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod home_assistant;
 pub mod http;
+pub mod ics;
+pub mod ics_calendar;
+pub mod in_memory_calendar;
+pub mod kml;
+pub mod matrix;
+pub mod mqtt;
+pub mod nominatim;
 pub mod open_meteo;
+pub mod rate_limit;
+#[cfg(feature = "record-replay")]
+pub mod record_replay;
+pub mod report;
+pub mod singleflight;
 pub mod store;
+pub mod systemd;
+pub mod validation;
+pub mod weather_providers;
+pub mod xctrack;
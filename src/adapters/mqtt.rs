@@ -0,0 +1,287 @@
+//! MQTT publisher for smart-home brokers like Mosquitto that Home Assistant
+//! listens to: connects, publishes retained QoS 1 messages, waits for the
+//! broker to acknowledge them and disconnects.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Result, bail};
+use rumqttc::{
+    AsyncClient, ConnectionError, Event, MqttOptions, Outgoing, Packet, QoS, TlsConfiguration,
+    Transport,
+};
+use rustls::{ClientConfig, RootCertStore};
+use tokio::time::timeout;
+
+use crate::domain::error::{ErrorCode, TravelAiError};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(60);
+/// Longest a whole publish may take, from connecting to the last ack
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+const PLAIN_PORT: u16 = 1883;
+const TLS_PORT: u16 = 8883;
+/// Strings in MQTT packets carry a two-byte length
+const MAX_STRING_BYTES: usize = u16::MAX as usize;
+/// Payloads of the outlook are short, this leaves plenty of room
+const MAX_PACKET_BYTES: usize = 64 * 1024;
+
+pub struct MqttPublisher {
+    addr: String,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    tls: bool,
+}
+
+impl MqttPublisher {
+    /// Connects to `addr` (`host:port`, the port defaulting to 1883, or 8883
+    /// with `tls`) and verifies its certificate against the system's roots.
+    pub fn new(
+        addr: String,
+        client_id: String,
+        credentials: Option<(String, String)>,
+        tls: bool,
+    ) -> Self {
+        Self {
+            addr,
+            client_id,
+            credentials,
+            tls,
+        }
+    }
+
+    fn options(&self) -> Result<MqttOptions> {
+        check_length("client id", &self.client_id)?;
+        let (host, port) = match self.addr.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse()?),
+            None if self.tls => (self.addr.as_str(), TLS_PORT),
+            None => (self.addr.as_str(), PLAIN_PORT),
+        };
+        let mut options = MqttOptions::new(&self.client_id, host, port);
+        options
+            .set_keep_alive(KEEP_ALIVE)
+            .set_max_packet_size(MAX_PACKET_BYTES, MAX_PACKET_BYTES);
+        if let Some((user, password)) = &self.credentials {
+            check_length("user name", user)?;
+            check_length("password", password)?;
+            options.set_credentials(user, password);
+        }
+        if self.tls {
+            options.set_transport(Transport::tls_with_config(tls_config()?));
+        }
+        Ok(options)
+    }
+
+    /// Publishes every `(topic, payload)` pair as a retained message, so new
+    /// subscribers get the current state right away.
+    #[tracing::instrument(skip_all, fields(broker = %self.addr, count = messages.len()))]
+    pub async fn publish_retained(&self, messages: &[(String, String)]) -> Result<()> {
+        for (topic, _) in messages {
+            check_length("topic", topic)?;
+        }
+        let (client, mut eventloop) = AsyncClient::new(self.options()?, messages.len().max(1));
+        for (topic, payload) in messages {
+            client
+                .publish(topic, QoS::AtLeastOnce, true, payload.as_bytes())
+                .await?;
+        }
+
+        // Disconnect only once the broker has every message
+        let exchange = async {
+            let mut acked = 0;
+            if messages.is_empty() {
+                client.disconnect().await?;
+            }
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::PubAck(_))) => {
+                        acked += 1;
+                        if acked == messages.len() {
+                            client.disconnect().await?;
+                        }
+                    }
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => return Ok(()),
+                    Ok(_) => {}
+                    Err(e) => return Err(connection_error(&self.addr, e)),
+                }
+            }
+        };
+        timeout(PUBLISH_TIMEOUT, exchange).await.map_err(|e| {
+            TravelAiError::new(
+                ErrorCode::NotificationUnavailable,
+                "MQTT broker did not acknowledge the messages",
+            )
+            .with_context("broker", &self.addr)
+            .with_source(e)
+        })?
+    }
+}
+
+fn connection_error(broker: &str, error: ConnectionError) -> anyhow::Error {
+    match error {
+        // Wrong credentials or client id, trying again won't help
+        ConnectionError::ConnectionRefused(code) => {
+            anyhow::anyhow!("MQTT broker refused the connection with code {code:?}")
+        }
+        e => TravelAiError::new(
+            ErrorCode::NotificationUnavailable,
+            "Failed to reach MQTT broker",
+        )
+        .with_context("broker", broker)
+        .with_source(e)
+        .into(),
+    }
+}
+
+/// Refuses strings MQTT can't carry instead of truncating their length.
+fn check_length(what: &str, s: &str) -> Result<()> {
+    if s.len() > MAX_STRING_BYTES {
+        return Err(TravelAiError::invalid_input(format!(
+            "MQTT {what} is longer than {MAX_STRING_BYTES} bytes"
+        ))
+        .with_context("length", s.len())
+        .into());
+    }
+    Ok(())
+}
+
+fn tls_config() -> Result<TlsConfiguration> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    if roots.add_parsable_certificates(native.certs).0 == 0 {
+        bail!("No trusted root certificates found for MQTT over TLS");
+    }
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    Ok(TlsConfiguration::Rustls(Arc::new(config)))
+}
+
+/// Turns a site name into a single topic level: lowercase, no wildcards or
+/// separators.
+pub fn topic_level(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    /// One packet as its first header byte and body.
+    async fn read_packet(socket: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+        let header = socket.read_u8().await.ok()?;
+        let (mut len, mut shift) = (0usize, 0);
+        loop {
+            let byte = socket.read_u8().await.ok()?;
+            len |= usize::from(byte & 0x7F) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0; len];
+        socket.read_exact(&mut body).await.ok()?;
+        Some((header, body))
+    }
+
+    /// Accepts one client with `return_code`, acks its publishes and returns
+    /// them as (header, topic, payload) once it disconnects.
+    async fn broker(
+        return_code: u8,
+    ) -> (String, tokio::task::JoinHandle<Vec<(u8, String, String)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut published = Vec::new();
+            while let Some((header, body)) = read_packet(&mut socket).await {
+                match header >> 4 {
+                    1 => socket
+                        .write_all(&[0x20, 0x02, 0x00, return_code])
+                        .await
+                        .unwrap(),
+                    3 => {
+                        let topic_len = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                        let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                        let id = &body[2 + topic_len..4 + topic_len];
+                        let payload = String::from_utf8(body[4 + topic_len..].to_vec()).unwrap();
+                        socket.write_all(&[0x40, 0x02, id[0], id[1]]).await.unwrap();
+                        published.push((header, topic, payload));
+                    }
+                    14 => break,
+                    _ => {}
+                }
+            }
+            published
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn topic_level_strips_separators_and_wildcards() {
+        assert_eq!(topic_level("Hohe Wand/Ost #1"), "hohe_wand_ost__1");
+    }
+
+    #[tokio::test]
+    async fn publishes_retained_and_waits_for_acks() {
+        let (addr, broker) = broker(0).await;
+
+        let publisher = MqttPublisher::new(addr, "travelai".into(), None, false);
+        publisher
+            .publish_retained(&[
+                ("paragliding/hill/rating".into(), "good".into()),
+                ("paragliding/hill/score".into(), "0.67".into()),
+            ])
+            .await
+            .unwrap();
+
+        let published = broker.await.unwrap();
+        assert_eq!(
+            published,
+            vec![
+                (0x33, "paragliding/hill/rating".into(), "good".into()),
+                (0x33, "paragliding/hill/score".into(), "0.67".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_an_error() {
+        // Bad user name or password
+        let (addr, _broker) = broker(4).await;
+
+        let publisher = MqttPublisher::new(addr, "travelai".into(), None, false);
+        assert!(publisher.publish_retained(&[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn strings_too_long_for_mqtt_are_refused() {
+        let long = "a".repeat(MAX_STRING_BYTES + 1);
+        let publisher = MqttPublisher::new(
+            "127.0.0.1:1".into(),
+            "travelai".into(),
+            Some(("pilot".into(), long.clone())),
+            false,
+        );
+        let error = publisher.publish_retained(&[]).await.unwrap_err();
+        assert_eq!(
+            TravelAiError::find(&error).unwrap().code,
+            ErrorCode::InvalidInput
+        );
+
+        let publisher = MqttPublisher::new("127.0.0.1:1".into(), "travelai".into(), None, false);
+        let error = publisher
+            .publish_retained(&[(long, "x".into())])
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("topic"), "{error}");
+    }
+}
@@ -0,0 +1,214 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use tokio::{sync::Mutex, time::Instant};
+use tracing::instrument;
+
+use crate::{
+    adapters::cache::PersistentCache,
+    domain::{
+        location::{Location, is_valid_latitude, is_valid_longitude},
+        ports::ReverseGeoProvider,
+    },
+};
+
+const BASE_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+const USER_AGENT: &str = "travelai/0.1 (+https://github.com/thriemer/paragliding-calendar)";
+// Nominatim's usage policy allows at most one request per second per application.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct NominatimClient {
+    cache: Arc<PersistentCache>,
+    http: ClientWithMiddleware,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl NominatimClient {
+    pub fn new(cache: Arc<PersistentCache>, http: ClientWithMiddleware) -> Self {
+        Self {
+            cache,
+            http,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    async fn throttle(&self) {
+        let mut last_request = self.last_request.lock().await;
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < MIN_REQUEST_INTERVAL {
+                tokio::time::sleep(MIN_REQUEST_INTERVAL - elapsed).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+}
+
+#[async_trait]
+impl ReverseGeoProvider for NominatimClient {
+    #[instrument(skip(self))]
+    async fn reverse_geocode(&self, latitude: f64, longitude: f64) -> Result<Location> {
+        let rounded_lat = (latitude * 1000.0).round() / 1000.0;
+        let rounded_lon = (longitude * 1000.0).round() / 1000.0;
+        let cache_key = format!("reverse_geocode_{}_{}", rounded_lat, rounded_lon);
+
+        if let Some(cached) = self.cache.get::<Location>(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        self.throttle().await;
+
+        let url = format!(
+            "{}?lat={}&lon={}&format=jsonv2&zoom=14&accept-language=en",
+            BASE_URL, latitude, longitude
+        );
+        let response: ReverseResponse = self
+            .http
+            .get(url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Nominatim reverse geocoding response")?;
+
+        let location = response
+            .into_location(latitude, longitude)
+            .ok_or(anyhow!("Nominatim returned no place for the coordinates"))?;
+
+        self.cache
+            .put(
+                &cache_key,
+                location.clone(),
+                Duration::from_secs(365 * 24 * 60 * 60),
+            )
+            .await?;
+
+        tracing::debug!(name = %location.name, "Reverse geocoding successful");
+        Ok(location)
+    }
+}
+
+/// Parses inputs like `"50.7, 13.0"` or `"50.7 13.0"` into latitude/longitude.
+pub fn parse_coordinates(input: &str) -> Option<(f64, f64)> {
+    let mut parts = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty());
+    let latitude: f64 = parts.next()?.parse().ok()?;
+    let longitude: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    if !is_valid_latitude(latitude) || !is_valid_longitude(longitude) {
+        return None;
+    }
+    Some((latitude, longitude))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReverseResponse {
+    name: Option<String>,
+    display_name: Option<String>,
+    address: Option<Address>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Address {
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    hamlet: Option<String>,
+    municipality: Option<String>,
+    country: Option<String>,
+}
+
+impl ReverseResponse {
+    fn into_location(self, latitude: f64, longitude: f64) -> Option<Location> {
+        let address = self.address;
+        let settlement = address.as_ref().and_then(|a| {
+            a.city
+                .clone()
+                .or_else(|| a.town.clone())
+                .or_else(|| a.village.clone())
+                .or_else(|| a.hamlet.clone())
+                .or_else(|| a.municipality.clone())
+        });
+        let name = settlement
+            .or(self.name.filter(|n| !n.is_empty()))
+            .or(self.display_name)?;
+        let country = address
+            .and_then(|a| a.country)
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        Some(Location::new(latitude, longitude, name, country))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    fn client() -> (TempDir, NominatimClient) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = fjall::Database::builder(dir.path()).open().unwrap();
+        let ks = db
+            .keyspace("cache", fjall::KeyspaceCreateOptions::default)
+            .unwrap();
+        let cache = Arc::new(PersistentCache::from_keyspace(ks));
+        let http = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+        (dir, NominatimClient::new(cache, http))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn back_to_back_requests_are_spaced_by_the_minimum_interval() {
+        let (_dir, client) = client();
+        let start = Instant::now();
+        client.throttle().await;
+        let first = Instant::now();
+        client.throttle().await;
+        let second = Instant::now();
+
+        assert_eq!(first, start);
+        assert!(second - first >= MIN_REQUEST_INTERVAL);
+    }
+
+    #[rstest]
+    #[case("50.7, 13.0", Some((50.7, 13.0)))]
+    #[case("50.7,13.0", Some((50.7, 13.0)))]
+    #[case("  -33.9 151.2 ", Some((-33.9, 151.2)))]
+    #[case("Gornau/Erz", None)]
+    #[case("50.7", None)]
+    #[case("95.0, 13.0", None)]
+    #[case("50.7, 13.0, 5", None)]
+    fn parse_coordinates_cases(#[case] input: &str, #[case] expected: Option<(f64, f64)>) {
+        assert_eq!(parse_coordinates(input), expected);
+    }
+
+    #[test]
+    fn into_location_prefers_settlement_over_display_name() {
+        let response: ReverseResponse = serde_json::from_str(
+            r#"{
+                "name": "Gornauer Straße",
+                "display_name": "Gornauer Straße, Gornau/Erzgeb., Sachsen, Deutschland",
+                "address": { "village": "Gornau/Erzgeb.", "country": "Germany" }
+            }"#,
+        )
+        .unwrap();
+        let location = response.into_location(50.7, 13.0).unwrap();
+        assert_eq!(location.name, "Gornau/Erzgeb.");
+        assert_eq!(location.country, "Germany");
+        assert_eq!(location.latitude, 50.7);
+    }
+
+    #[test]
+    fn into_location_is_none_for_error_response() {
+        let response: ReverseResponse =
+            serde_json::from_str(r#"{ "error": "Unable to geocode" }"#).unwrap();
+        assert!(response.into_location(0.0, 0.0).is_none());
+    }
+}
@@ -1,26 +1,226 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::{
-    adapters::cache::PersistentCache,
+    adapters::{cache::PersistentCache, singleflight::SingleFlight},
     domain::{
-        location::Location,
-        ports::{GeoProvider, WeatherProvider},
-        weather::{WeatherForecast, WeatherModel},
+        location::{GeocodeCandidate, Location, normalize_place_name},
+        ports::{
+            EnsembleProvider, GeoProvider, SoundingProvider, WeatherHistoryProvider,
+            WeatherProvider,
+        },
+        sounding::{PRESSURE_LEVELS_HPA, Sounding},
+        weather::{EXTENDED_RANGE_MODEL, WeatherData, WeatherForecast, WeatherModel},
     },
 };
 
+/// Names Open-Meteo in the provenance of scores
+pub const PROVIDER_ID: &str = "open_meteo";
+/// How far the regular models reach. Longer horizons are filled from GFS.
+pub const PRIMARY_FORECAST_DAYS: u8 = 7;
+pub const MAX_FORECAST_DAYS: u8 = 16;
+/// How far back `past_days` reaches
+const MAX_PAST_DAYS: u8 = 92;
+// Past hours hardly change; only the last few get filled in.
+const PAST_WEATHER_TTL: Duration = Duration::from_hours(6);
+// Ensembles run every six to twelve hours.
+const ENSEMBLE_TTL: Duration = Duration::from_hours(3);
+/// Hourly variables of each ensemble member, what deciding flyability needs
+const ENSEMBLE_VARIABLES: [&str; 4] = [
+    "wind_speed_10m",
+    "wind_gusts_10m",
+    "wind_direction_10m",
+    "precipitation",
+];
+
+/// How long a forecast day stays cached, by how far ahead it is. Today's hours
+/// change with every model run, a day a week out hardly does.
+#[derive(Debug, Clone, Copy)]
+pub struct ForecastTtlPolicy {
+    /// TTL of today
+    pub today: Duration,
+    /// TTL of `far_from_day` and any later day
+    pub far: Duration,
+    pub far_from_day: u8,
+}
+
+impl Default for ForecastTtlPolicy {
+    fn default() -> Self {
+        Self {
+            today: Duration::from_hours(1),
+            far: Duration::from_hours(12),
+            far_from_day: 6,
+        }
+    }
+}
+
+impl ForecastTtlPolicy {
+    /// Grows linearly from `today` to `far`.
+    pub fn ttl(&self, lead_days: u8) -> Duration {
+        if lead_days >= self.far_from_day {
+            return self.far;
+        }
+        let share = f64::from(lead_days) / f64::from(self.far_from_day);
+        self.today + self.far.saturating_sub(self.today).mul_f64(share)
+    }
+}
+
+/// One day of a cached forecast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedDay {
+    /// First day of the fetch this day came from; days of different fetches
+    /// only fit together if it matches
+    base: NaiveDate,
+    grid_elevation: Option<f64>,
+    fetched_at: DateTime<Utc>,
+    hours: Vec<WeatherData>,
+}
+
 pub struct OpenMeteoClient {
     cache: Arc<PersistentCache>,
+    http: ClientWithMiddleware,
+    forecast_days: u8,
+    ttl: ForecastTtlPolicy,
+    flights: SingleFlight<WeatherForecast>,
 }
 
 impl OpenMeteoClient {
-    pub fn new(cache: Arc<PersistentCache>) -> Self {
-        Self { cache }
+    pub fn new(cache: Arc<PersistentCache>, http: ClientWithMiddleware, forecast_days: u8) -> Self {
+        Self {
+            cache,
+            http,
+            forecast_days: forecast_days.clamp(1, MAX_FORECAST_DAYS),
+            ttl: ForecastTtlPolicy::default(),
+            flights: SingleFlight::new(),
+        }
     }
+
+    /// Fetches and caches the first `days` days, returning them per day.
+    async fn fetch_days(
+        &self,
+        key: &str,
+        source: &Location,
+        model: Option<&str>,
+        days: u8,
+    ) -> Result<Vec<CachedDay>> {
+        let forecast = self.fetch_forecast(source.clone(), model, days).await?;
+        let fetched = split_days(forecast, days);
+        for (lead, day) in fetched.iter().enumerate() {
+            let lead = lead as u8;
+            // A day the API left out is retried soon
+            let ttl = if day.hours.is_empty() {
+                self.ttl.today
+            } else {
+                self.ttl.ttl(lead)
+            };
+            self.cache
+                .put(&day_key(key, lead), day.clone(), ttl)
+                .await?;
+        }
+        Ok(fetched)
+    }
+
+    async fn fetch_forecast(
+        &self,
+        source: Location,
+        model: Option<&str>,
+        days: u8,
+    ) -> Result<WeatherForecast> {
+        let primary_days = days.min(PRIMARY_FORECAST_DAYS);
+        let forecast = self
+            .request_forecast(source.clone(), model, primary_days)
+            .await?;
+        if days <= PRIMARY_FORECAST_DAYS {
+            return Ok(forecast);
+        }
+
+        let request = self.request_forecast(source, Some(EXTENDED_RANGE_MODEL), days);
+        match request.await {
+            Ok(extended) => Ok(extend_forecast(forecast, extended)),
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to fetch extended range forecast");
+                Ok(forecast)
+            }
+        }
+    }
+    /// Identical concurrent requests, e.g. from several sites at the same launch
+    /// or a page load during the calendar sync, share one upstream call.
+    async fn request_forecast(
+        &self,
+        location: Location,
+        model: Option<&str>,
+        days: u8,
+    ) -> Result<WeatherForecast> {
+        let url = forecast_url(&location, model, days);
+        let fetch = get_forecast_raw(
+            self.http.clone(),
+            url.clone(),
+            location,
+            model.map(String::from),
+        );
+        self.flights.run(&url, fetch).await
+    }
+}
+
+fn day_key(key: &str, lead: u8) -> String {
+    format!("{}_day{}", key, lead)
+}
+
+/// Lead days that are missing or belong to a different fetch than today's.
+fn stale_days(days: &[Option<CachedDay>]) -> Vec<u8> {
+    let base = days
+        .first()
+        .and_then(|day| day.as_ref())
+        .map(|day| day.base);
+    (0..days.len() as u8)
+        .filter(|&lead| match (&days[lead as usize], base) {
+            (Some(day), Some(base)) => day.base != base,
+            _ => true,
+        })
+        .collect()
+}
+
+/// Splits `forecast` into `days` days, starting with the date of its first hour.
+fn split_days(forecast: WeatherForecast, days: u8) -> Vec<CachedDay> {
+    let base = forecast
+        .forecast
+        .first()
+        .map(|hour| hour.timestamp.date_naive())
+        .unwrap_or_else(|| Utc::now().date_naive());
+    (0..days)
+        .map(|lead| {
+            let date = base + Days::new(u64::from(lead));
+            CachedDay {
+                base,
+                grid_elevation: forecast.grid_elevation,
+                fetched_at: forecast.fetched_at.unwrap_or_else(Utc::now),
+                hours: forecast
+                    .forecast
+                    .iter()
+                    .filter(|hour| hour.timestamp.date_naive() == date)
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Appends the hours of `extended` that lie past the end of `forecast`.
+fn extend_forecast(mut forecast: WeatherForecast, extended: WeatherForecast) -> WeatherForecast {
+    let last = forecast.forecast.iter().map(|h| h.timestamp).max();
+    forecast.forecast.extend(
+        extended
+            .forecast
+            .into_iter()
+            .filter(|h| last.is_none_or(|last| h.timestamp > last)),
+    );
+    forecast
 }
 
 #[async_trait]
@@ -37,16 +237,46 @@ impl WeatherProvider for OpenMeteoClient {
             .unwrap_or_default();
         let key = format!("weather_for_{}{}", source.to_key(), model_suffix);
 
-        if let Some(cached) = self.cache.get::<WeatherForecast>(&key).await? {
-            return Ok(cached);
+        let mut days = Vec::with_capacity(self.forecast_days as usize);
+        for lead in 0..self.forecast_days {
+            days.push(self.cache.get::<CachedDay>(&day_key(&key, lead)).await?);
+        }
+        // Days are cached separately, so only the expired ones and those before
+        // them are fetched again. Fetches start today, so after midnight the
+        // cached days no longer line up and are fetched again as well.
+        if let Some(last) = stale_days(&days).last() {
+            let fetched = self
+                .fetch_days(&key, &source, model.as_deref(), last + 1)
+                .await?;
+            let base = fetched.first().map(|day| day.base);
+            let rest = days.split_off(fetched.len());
+            let still_fits = rest
+                .iter()
+                .all(|day| day.as_ref().is_some_and(|day| Some(day.base) == base));
+            days = if still_fits {
+                fetched.into_iter().map(Some).chain(rest).collect()
+            } else {
+                self.fetch_days(&key, &source, model.as_deref(), self.forecast_days)
+                    .await?
+                    .into_iter()
+                    .map(Some)
+                    .collect()
+            };
+            tracing::debug!(
+                location = %source.to_key(),
+                days = last + 1,
+                "Weather fetch successful"
+            );
         }
 
-        let forecast = get_forecast_raw(source.clone(), model.as_deref()).await?;
-        self.cache
-            .put(&key, forecast.clone(), Duration::from_hours(6u64))
-            .await?;
-        tracing::debug!(location = %source.to_key(), "Weather fetch successful");
-        Ok(forecast)
+        let days: Vec<CachedDay> = days.into_iter().flatten().collect();
+        Ok(WeatherForecast {
+            location: source,
+            grid_elevation: days.iter().find_map(|day| day.grid_elevation),
+            fetched_at: days.iter().map(|day| day.fetched_at).max(),
+            provider: Some(PROVIDER_ID.to_string()),
+            forecast: days.into_iter().flat_map(|day| day.hours).collect(),
+        })
     }
 
     fn available_models(&self) -> Vec<WeatherModel> {
@@ -79,85 +309,361 @@ impl WeatherProvider for OpenMeteoClient {
     }
 }
 
+#[async_trait]
+impl WeatherHistoryProvider for OpenMeteoClient {
+    #[instrument(skip_all, fields(lat = %location.latitude, lon = %location.longitude, days))]
+    async fn get_past_weather(&self, location: &Location, days: u8) -> Result<WeatherForecast> {
+        let days = days.min(MAX_PAST_DAYS);
+        let key = format!("past_weather_for_{}_{}", location.to_key(), days);
+        if let Some(cached) = self.cache.get::<WeatherForecast>(&key).await? {
+            return Ok(cached);
+        }
+
+        let url = format!("{}&past_days={}", forecast_url(location, None, 1), days);
+        let mut past = get_forecast_raw(self.http.clone(), url, location.clone(), None).await?;
+        let now = Utc::now();
+        past.forecast.retain(|hour| hour.timestamp < now);
+        self.cache.put(&key, past.clone(), PAST_WEATHER_TTL).await?;
+        Ok(past)
+    }
+
+    /// Past hours are replaced by the analysis of each new model run, which
+    /// brings in what stations measured, so they are never cached.
+    #[instrument(skip_all, fields(lat = %location.latitude, lon = %location.longitude, hours))]
+    async fn get_recent_weather(&self, location: &Location, hours: u8) -> Result<Vec<WeatherData>> {
+        let url = format!(
+            "{}&past_hours={}&forecast_hours=0",
+            forecast_url(location, None, 1),
+            hours
+        );
+        let mut recent = get_forecast_raw(self.http.clone(), url, location.clone(), None)
+            .await?
+            .forecast;
+        let now = Utc::now();
+        recent.retain(|hour| hour.timestamp <= now);
+        let skip = recent.len().saturating_sub(usize::from(hours));
+        Ok(recent.split_off(skip))
+    }
+}
+
+#[async_trait]
+impl SoundingProvider for OpenMeteoClient {
+    #[instrument(skip_all, fields(lat = %location.latitude, lon = %location.longitude))]
+    async fn get_soundings(&self, location: &Location) -> Result<Vec<Sounding>> {
+        // Pressure level data is coarse, so nearby requests share one grid cell.
+        let cell = sounding_grid_cell(location);
+        let key = format!("sounding_for_{}", cell.to_key());
+
+        if let Some(cached) = self.cache.get::<Vec<Sounding>>(&key).await? {
+            return Ok(cached);
+        }
+
+        let soundings = get_soundings_raw(&self.http, &cell).await?;
+        self.cache
+            .put(&key, soundings.clone(), Duration::from_hours(3u64))
+            .await?;
+        tracing::debug!(count = soundings.len(), "Sounding fetch successful");
+        Ok(soundings)
+    }
+}
+
+/// Open-Meteo's ensemble forecasts of one model, e.g. `icon_seamless`.
+pub struct OpenMeteoEnsembleClient {
+    cache: Arc<PersistentCache>,
+    http: ClientWithMiddleware,
+    model: String,
+    forecast_days: u8,
+}
+
+impl OpenMeteoEnsembleClient {
+    pub fn new(
+        cache: Arc<PersistentCache>,
+        http: ClientWithMiddleware,
+        model: String,
+        forecast_days: u8,
+    ) -> Self {
+        Self {
+            cache,
+            http,
+            model,
+            forecast_days: forecast_days.clamp(1, MAX_FORECAST_DAYS),
+        }
+    }
+}
+
+#[async_trait]
+impl EnsembleProvider for OpenMeteoEnsembleClient {
+    #[instrument(skip_all, fields(lat = %location.latitude, lon = %location.longitude, model = %self.model))]
+    async fn get_ensemble(&self, location: &Location) -> Result<Vec<WeatherForecast>> {
+        let key = format!("ensemble_{}_{}", self.model, location.to_key());
+        if let Some(cached) = self.cache.get::<Vec<WeatherForecast>>(&key).await? {
+            return Ok(cached);
+        }
+
+        let response: openmeteo::EnsembleResponse = self
+            .http
+            .get(ensemble_url(location, &self.model, self.forecast_days))
+            .send()
+            .await?
+            .json()
+            .await
+            .with_context(|| "Failed to parse OpenMeteo ensemble response")?;
+        let members = response.to_members(location);
+        self.cache.put(&key, members.clone(), ENSEMBLE_TTL).await?;
+        tracing::debug!(members = members.len(), "Ensemble fetch successful");
+        Ok(members)
+    }
+}
+
+fn ensemble_url(location: &Location, model: &str, forecast_days: u8) -> String {
+    format!(
+        "https://ensemble-api.open-meteo.com/v1/ensemble?latitude={}&longitude={}&hourly={}&models={}&timezone=GMT&forecast_days={}&wind_speed_unit=ms",
+        location.latitude,
+        location.longitude,
+        ENSEMBLE_VARIABLES.join(","),
+        model,
+        forecast_days
+    )
+}
+
 #[async_trait]
 impl GeoProvider for OpenMeteoClient {
     #[instrument(skip(self), fields(location_name = %location_name))]
-    async fn geocode(&self, location_name: &str) -> Result<Vec<Location>> {
-        geocode_raw(location_name).await
+    async fn geocode(&self, location_name: &str) -> Result<Vec<GeocodeCandidate>> {
+        let query = normalize_place_name(location_name);
+        let key = format!("{}{}", GEOCODE_KEY_PREFIX, query);
+
+        if let Some(cached) = self.cache.get::<CachedGeocode>(&key).await? {
+            return Ok(cached.results);
+        }
+
+        let mut results = geocode_raw(&self.http, location_name).await?;
+        // Stable, so places matching equally well keep the geocoder's order.
+        results.sort_by_key(|c| match_rank(&query, &normalize_place_name(&c.location.name)));
+        // Only remember hits, a typo should not stick around for a year.
+        if !results.is_empty() {
+            let cached = CachedGeocode {
+                query,
+                results: results.clone(),
+            };
+            self.cache.put(&key, cached, GEOCODE_TTL).await?;
+        }
+        Ok(results)
     }
 
     #[instrument(skip(self))]
     async fn fetch_elevation(&self, latitude: f64, longitude: f64) -> Result<f64> {
-        let rounded_lat = (latitude * 1000.0).round() / 1000.0;
-        let rounded_lon = (longitude * 1000.0).round() / 1000.0;
-        let cache_key = format!("elevation_{}_{}", rounded_lat, rounded_lon);
+        self.fetch_elevations(&[(latitude, longitude)])
+            .await?
+            .pop()
+            .ok_or(anyhow!("No elevation provided in response"))
+    }
 
-        if let Some(cached) = self.cache.get::<f64>(&cache_key).await? {
-            return Ok(cached);
+    #[instrument(skip_all, fields(points = points.len()))]
+    async fn fetch_elevations(&self, points: &[(f64, f64)]) -> Result<Vec<f64>> {
+        let rounded: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(lat, lon)| {
+                (
+                    round_elevation_coordinate(lat),
+                    round_elevation_coordinate(lon),
+                )
+            })
+            .collect();
+
+        let mut known: HashMap<String, f64> = HashMap::new();
+        let mut missing: Vec<(f64, f64)> = Vec::new();
+        for &point in &rounded {
+            let key = elevation_key(point);
+            if known.contains_key(&key) || missing.contains(&point) {
+                continue;
+            }
+            match self.cache.get::<f64>(&key).await? {
+                Some(elevation) => {
+                    known.insert(key, elevation);
+                }
+                None => missing.push(point),
+            }
         }
 
-        let url = format!(
-            "https://api.open-meteo.com/v1/elevation?latitude={}&longitude={}",
-            latitude, longitude
-        );
+        // One request per chunk instead of one per point
+        for chunk in missing.chunks(ELEVATION_BATCH_SIZE) {
+            let response = self.http.get(elevation_url(chunk)).send().await?;
+            let data: serde_json::Value = response.json().await?;
+            let elevations = data["elevation"]
+                .as_array()
+                .filter(|values| values.len() == chunk.len())
+                .ok_or(anyhow!("No elevation provided in response"))?;
+            for (&point, value) in chunk.iter().zip(elevations) {
+                let elevation = value.as_f64().ok_or(anyhow!("Elevation is not a number"))?;
+                let key = elevation_key(point);
+                let _ = self.cache.put(&key, elevation, ELEVATION_TTL).await;
+                known.insert(key, elevation);
+            }
+        }
+
+        Ok(rounded
+            .into_iter()
+            .map(|point| known[&elevation_key(point)])
+            .collect())
+    }
+}
 
-        let response = reqwest::get(&url).await?;
-        let data: serde_json::Value = response.json().await?;
+/// The elevation API takes at most this many coordinates per request.
+const ELEVATION_BATCH_SIZE: usize = 100;
+const ELEVATION_TTL: Duration = Duration::from_hours(365 * 24);
 
-        let elevation = data["elevation"]
-            .as_array()
-            .and_then(|arr| arr.first())
-            .and_then(|v| v.as_f64())
-            .ok_or(anyhow!("No elevation provided in response"))?;
+/// Rounded to about 100 m, so close points share a cache entry.
+fn round_elevation_coordinate(degrees: f64) -> f64 {
+    (degrees * 1000.0).round() / 1000.0
+}
 
-        let _ = self
-            .cache
-            .put(
-                &cache_key,
-                elevation,
-                std::time::Duration::from_secs(365 * 24 * 60 * 60),
-            )
-            .await;
+fn elevation_key((latitude, longitude): (f64, f64)) -> String {
+    format!("elevation_{}_{}", latitude, longitude)
+}
 
-        Ok(elevation)
-    }
+fn elevation_url(points: &[(f64, f64)]) -> String {
+    let join = |coordinate: fn(&(f64, f64)) -> f64| {
+        points
+            .iter()
+            .map(|point| coordinate(point).to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        "https://api.open-meteo.com/v1/elevation?latitude={}&longitude={}",
+        join(|point| point.0),
+        join(|point| point.1)
+    )
 }
 
-async fn get_forecast_raw(location: Location, model: Option<&str>) -> Result<WeatherForecast> {
+fn forecast_url(location: &Location, model: Option<&str>, forecast_days: u8) -> String {
     let mut url = format!(
-        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly=temperature_2m,windspeed_10m,winddirection_10m,windgusts_10m,precipitation,cloudcover,surface_pressure,visibility,weathercode&timezone=auto&forecast_days=7&wind_speed_unit=ms",
-        location.latitude, location.longitude
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly=temperature_2m,dewpoint_2m,windspeed_10m,winddirection_10m,windgusts_10m,precipitation,precipitation_probability,cloudcover,surface_pressure,visibility,weathercode,snow_depth,snowfall,boundary_layer_height,wind_speed_80m,wind_direction_80m,wind_speed_850hPa,wind_direction_850hPa,temperature_850hPa,geopotential_height_850hPa,cape,freezing_level_height&timezone=auto&forecast_days={}&wind_speed_unit=ms",
+        location.latitude, location.longitude, forecast_days
     );
 
     if let Some(model) = model {
         url.push_str(&format!("&models={}", model));
     }
+    url
+}
 
-    let response = reqwest::get(url).await?;
+async fn get_forecast_raw(
+    http: ClientWithMiddleware,
+    url: String,
+    location: Location,
+    model: Option<String>,
+) -> Result<WeatherForecast> {
+    let response = http.get(url).send().await?;
 
     let forecast_response: openmeteo::ForecastResponse = response
         .json()
         .await
         .with_context(|| "Failed to parse OpenMeteo forecast response")?;
 
-    let forecast = WeatherForecast::from_openmeteo(&forecast_response, location);
+    let mut forecast = WeatherForecast::from_openmeteo(&forecast_response, location);
+    forecast.fetched_at = Some(Utc::now());
+    for hour in &mut forecast.forecast {
+        hour.model = model.clone();
+    }
     Ok(forecast)
 }
 
-async fn geocode_raw(location_name: &str) -> Result<Vec<Location>> {
+const SOUNDING_GRID_DEG: f64 = 0.1;
+
+fn sounding_grid_cell(location: &Location) -> Location {
+    let snap = |degrees: f64| (degrees / SOUNDING_GRID_DEG).round() * SOUNDING_GRID_DEG;
+    Location::new(
+        snap(location.latitude),
+        snap(location.longitude),
+        String::new(),
+        String::new(),
+    )
+}
+
+async fn get_soundings_raw(
+    http: &ClientWithMiddleware,
+    location: &Location,
+) -> Result<Vec<Sounding>> {
+    let mut hourly: Vec<String> = [
+        "temperature_2m",
+        "dewpoint_2m",
+        "wind_speed_10m",
+        "wind_direction_10m",
+    ]
+    .map(String::from)
+    .to_vec();
+    for level in PRESSURE_LEVELS_HPA {
+        for variable in [
+            "temperature",
+            "dewpoint",
+            "geopotential_height",
+            "wind_speed",
+            "wind_direction",
+        ] {
+            hourly.push(format!("{}_{}hPa", variable, level));
+        }
+    }
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly={}&timezone=GMT&forecast_days=3&wind_speed_unit=ms",
+        location.latitude,
+        location.longitude,
+        hourly.join(",")
+    );
+
+    let response: openmeteo::SoundingResponse = http
+        .get(url)
+        .send()
+        .await?
+        .json()
+        .await
+        .with_context(|| "Failed to parse OpenMeteo sounding response")?;
+
+    Ok(response.to_soundings())
+}
+
+const GEOCODE_KEY_PREFIX: &str = "geocode_";
+// Place names do not move.
+const GEOCODE_TTL: Duration = Duration::from_hours(365 * 24);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedGeocode {
+    /// Normalized query the results belong to
+    query: String,
+    results: Vec<GeocodeCandidate>,
+}
+
+/// How well a place called `name` matches `query`, both normalized, best
+/// first: the same name, then one the query begins with or that begins with
+/// the query ("Gornau" for "gornau erz"), then any other the geocoder found.
+fn match_rank(query: &str, name: &str) -> u8 {
+    if name == query {
+        0
+    } else if query.starts_with(name) || name.starts_with(query) {
+        1
+    } else {
+        2
+    }
+}
+
+async fn geocode_raw(
+    http: &ClientWithMiddleware,
+    location_name: &str,
+) -> Result<Vec<GeocodeCandidate>> {
     let url = format!(
         "https://geocoding-api.open-meteo.com/v1/search?name={}&count=5&language=en&format=json",
         urlencoding::encode(location_name)
     );
 
-    let response = reqwest::get(url).await?;
+    let response = http.get(url).send().await?;
 
     let openmeteo_response: openmeteo::GeocodingResponse = response
         .json()
         .await
         .with_context(|| "Failed to parse OpenMeteo geocoding response")?;
 
-    let geocoding_results: Vec<Location> = openmeteo_response
+    let geocoding_results: Vec<GeocodeCandidate> = openmeteo_response
         .results
         .unwrap_or_default()
         .into_iter()
@@ -172,17 +678,23 @@ async fn geocode_raw(location_name: &str) -> Result<Vec<Location>> {
     Ok(geocoding_results)
 }
 
-mod openmeteo {
+pub(crate) mod openmeteo {
+    use std::collections::HashMap;
+
     use chrono::Utc;
     use serde::Deserialize;
 
-    use super::{Location, WeatherForecast};
-    use crate::domain::weather::WeatherData;
+    use super::{GeocodeCandidate, Location, WeatherForecast};
+    use crate::domain::{
+        sounding::{PRESSURE_LEVELS_HPA, Sounding, SoundingLevel},
+        weather::{WeatherData, WeatherField},
+    };
 
     #[derive(Debug, Deserialize)]
     pub struct ForecastResponse {
         pub latitude: f64,
         pub longitude: f64,
+        pub elevation: Option<f64>,
         pub timezone: String,
         pub timezone_abbreviation: String,
         pub hourly: Option<HourlyData>,
@@ -194,21 +706,186 @@ mod openmeteo {
     pub struct HourlyData {
         pub time: Vec<String>,
         #[serde(rename = "temperature_2m")]
-        pub temperature: Option<Vec<f32>>,
+        pub temperature: Option<Vec<Option<f32>>>,
+        #[serde(rename = "dewpoint_2m")]
+        pub dew_point: Option<Vec<Option<f32>>>,
         #[serde(rename = "windspeed_10m")]
-        pub wind_speed: Option<Vec<f32>>,
+        pub wind_speed: Option<Vec<Option<f32>>>,
         #[serde(rename = "winddirection_10m")]
-        pub wind_direction: Option<Vec<u16>>,
+        pub wind_direction: Option<Vec<Option<u16>>>,
         #[serde(rename = "windgusts_10m")]
-        pub wind_gusts: Option<Vec<f32>>,
-        pub precipitation: Option<Vec<f32>>,
+        pub wind_gusts: Option<Vec<Option<f32>>>,
+        pub precipitation: Option<Vec<Option<f32>>>,
+        pub precipitation_probability: Option<Vec<Option<u8>>>,
         #[serde(rename = "cloudcover")]
-        pub cloud_cover: Option<Vec<u8>>,
+        pub cloud_cover: Option<Vec<Option<u8>>>,
         #[serde(rename = "surface_pressure")]
-        pub pressure: Option<Vec<f32>>,
-        pub visibility: Option<Vec<f32>>,
+        pub pressure: Option<Vec<Option<f32>>>,
+        pub visibility: Option<Vec<Option<f32>>>,
         #[serde(rename = "weathercode")]
         pub weather_code: Option<Vec<u8>>,
+        pub snow_depth: Option<Vec<Option<f32>>>,
+        pub snowfall: Option<Vec<Option<f32>>>,
+        pub boundary_layer_height: Option<Vec<Option<f32>>>,
+        pub wind_speed_80m: Option<Vec<Option<f32>>>,
+        pub wind_direction_80m: Option<Vec<Option<u16>>>,
+        #[serde(rename = "wind_speed_850hPa")]
+        pub wind_speed_850hpa: Option<Vec<Option<f32>>>,
+        #[serde(rename = "wind_direction_850hPa")]
+        pub wind_direction_850hpa: Option<Vec<Option<u16>>>,
+        #[serde(rename = "temperature_850hPa")]
+        pub temperature_850hpa: Option<Vec<Option<f32>>>,
+        #[serde(rename = "geopotential_height_850hPa")]
+        pub height_850hpa: Option<Vec<Option<f32>>>,
+        pub cape: Option<Vec<Option<f32>>>,
+        #[serde(rename = "freezing_level_height")]
+        pub freezing_level: Option<Vec<Option<f32>>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SoundingResponse {
+        pub elevation: Option<f64>,
+        pub hourly: SoundingHourlyData,
+    }
+
+    /// Pressure level variables are named per level, e.g. `temperature_850hPa`.
+    #[derive(Debug, Deserialize)]
+    pub struct SoundingHourlyData {
+        pub time: Vec<String>,
+        #[serde(flatten)]
+        pub values: HashMap<String, Vec<Option<f32>>>,
+    }
+
+    impl SoundingResponse {
+        fn value(&self, variable: &str, i: usize) -> Option<f32> {
+            self.hourly.values.get(variable)?.get(i).copied().flatten()
+        }
+
+        pub fn to_soundings(&self) -> Vec<Sounding> {
+            let surface_elevation_m = self.elevation.unwrap_or(0.0);
+            self.hourly
+                .time
+                .iter()
+                .enumerate()
+                .filter_map(|(i, time)| {
+                    let timestamp = chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")
+                        .ok()?
+                        .and_utc();
+                    let surface_temperature = self.value("temperature_2m", i)?;
+                    let levels = PRESSURE_LEVELS_HPA
+                        .iter()
+                        .filter_map(|level| {
+                            let temperature = self.value(&format!("temperature_{level}hPa"), i)?;
+                            Some(SoundingLevel {
+                                pressure_hpa: f32::from(*level),
+                                height_m: f64::from(
+                                    self.value(&format!("geopotential_height_{level}hPa"), i)?,
+                                ),
+                                temperature,
+                                dew_point: self
+                                    .value(&format!("dewpoint_{level}hPa"), i)
+                                    .unwrap_or(temperature),
+                                wind_speed_ms: self
+                                    .value(&format!("wind_speed_{level}hPa"), i)
+                                    .unwrap_or(0.0),
+                                wind_direction: self
+                                    .value(&format!("wind_direction_{level}hPa"), i)
+                                    .unwrap_or(0.0)
+                                    .round() as u16,
+                            })
+                        })
+                        // Levels like 1000 hPa lie underground over mountains.
+                        .filter(|level| level.height_m > surface_elevation_m)
+                        .collect();
+
+                    Some(Sounding {
+                        timestamp,
+                        surface_elevation_m,
+                        surface_temperature,
+                        surface_dew_point: self
+                            .value("dewpoint_2m", i)
+                            .unwrap_or(surface_temperature),
+                        surface_wind_speed_ms: self.value("wind_speed_10m", i).unwrap_or(0.0),
+                        surface_wind_direction: self
+                            .value("wind_direction_10m", i)
+                            .unwrap_or(0.0)
+                            .round() as u16,
+                        levels,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct EnsembleResponse {
+        pub hourly: EnsembleHourlyData,
+    }
+
+    /// Each variable comes once for the control run, e.g. `wind_speed_10m`,
+    /// and once per member, e.g. `wind_speed_10m_member01`.
+    #[derive(Debug, Deserialize)]
+    pub struct EnsembleHourlyData {
+        pub time: Vec<String>,
+        #[serde(flatten)]
+        pub values: HashMap<String, Vec<Option<f32>>>,
+    }
+
+    impl EnsembleResponse {
+        /// One forecast per member, the control run first. Hours a member
+        /// has no wind for are left out.
+        pub fn to_members(&self, location: &Location) -> Vec<WeatherForecast> {
+            let mut suffixes: Vec<&str> = self
+                .hourly
+                .values
+                .keys()
+                .filter_map(|key| key.strip_prefix("wind_speed_10m"))
+                .collect();
+            suffixes.sort();
+            suffixes
+                .into_iter()
+                .map(|suffix| WeatherForecast {
+                    location: location.clone(),
+                    grid_elevation: None,
+                    fetched_at: None,
+                    provider: None,
+                    forecast: (0..self.hourly.time.len())
+                        .filter_map(|i| self.member_hour(suffix, i))
+                        .collect(),
+                })
+                .collect()
+        }
+
+        fn member_hour(&self, suffix: &str, i: usize) -> Option<WeatherData> {
+            let value = |variable: &str| {
+                self.hourly
+                    .values
+                    .get(&format!("{variable}{suffix}"))?
+                    .get(i)
+                    .copied()
+                    .flatten()
+            };
+            let timestamp =
+                chrono::NaiveDateTime::parse_from_str(&self.hourly.time[i], "%Y-%m-%dT%H:%M")
+                    .ok()?
+                    .and_utc();
+            let wind_speed_ms = value("wind_speed_10m")?;
+            // Not every ensemble model has gusts
+            let gust = value("wind_gusts_10m");
+            Some(WeatherData {
+                timestamp,
+                wind_speed_ms,
+                wind_gust_ms: gust.unwrap_or(wind_speed_ms),
+                wind_direction: value("wind_direction_10m")?.round() as u16,
+                precipitation: value("precipitation").unwrap_or(0.0),
+                missing: gust
+                    .is_none()
+                    .then_some(WeatherField::WindGust)
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            })
+        }
     }
 
     #[derive(Debug, Deserialize)]
@@ -261,16 +938,18 @@ mod openmeteo {
         pub country: Option<String>,
         pub admin1: Option<String>,
         pub admin2: Option<String>,
-        pub timezone: Option<String>,
     }
 
-    impl Into<Location> for GeocodingResult {
-        fn into(self) -> Location {
-            Location {
-                latitude: self.latitude,
-                longitude: self.longitude,
-                name: self.name,
-                country: self.country.unwrap_or("Unknown".into()),
+    impl From<GeocodingResult> for GeocodeCandidate {
+        fn from(result: GeocodingResult) -> Self {
+            GeocodeCandidate {
+                location: Location {
+                    latitude: result.latitude,
+                    longitude: result.longitude,
+                    name: result.name,
+                    country: result.country.unwrap_or("Unknown".into()),
+                },
+                admin_area: result.admin1.or(result.admin2),
             }
         }
     }
@@ -310,6 +989,21 @@ mod openmeteo {
         }
     }
 
+    /// Value of `values` at hour `i`, noting `field` as missing if the grid
+    /// cell has none.
+    fn hourly_value<T: Copy>(
+        values: Option<&Vec<Option<T>>>,
+        i: usize,
+        field: WeatherField,
+        missing: &mut Vec<WeatherField>,
+    ) -> Option<T> {
+        let value = values.and_then(|values| values.get(i).copied().flatten());
+        if value.is_none() {
+            missing.push(field);
+        }
+        value
+    }
+
     impl WeatherForecast {
         #[must_use]
         pub fn from_openmeteo(response: &ForecastResponse, location: Location) -> Self {
@@ -323,72 +1017,134 @@ mod openmeteo {
                         chrono::NaiveDateTime::parse_from_str(&hourly.time[i], "%Y-%m-%dT%H:%M")
                             .map_or_else(|_| Utc::now(), |dt| dt.and_utc());
 
-                    let temperature = *hourly
-                        .temperature
+                    let mut missing = Vec::new();
+                    let mut value = |values, field| hourly_value(values, i, field, &mut missing);
+                    let temperature = value(hourly.temperature.as_ref(), WeatherField::Temperature)
+                        .unwrap_or(-999.0);
+                    let dew_point = value(hourly.dew_point.as_ref(), WeatherField::DewPoint)
+                        .unwrap_or(temperature);
+                    let wind_speed = value(hourly.wind_speed.as_ref(), WeatherField::WindSpeed)
+                        .unwrap_or(-999.0);
+                    let wind_gust =
+                        value(hourly.wind_gusts.as_ref(), WeatherField::WindGust).unwrap_or(-999.0);
+                    let precipitation =
+                        value(hourly.precipitation.as_ref(), WeatherField::Precipitation)
+                            .unwrap_or(-999.0);
+                    let pressure =
+                        value(hourly.pressure.as_ref(), WeatherField::Pressure).unwrap_or(-999.0);
+                    let visibility = value(hourly.visibility.as_ref(), WeatherField::Visibility)
+                        .unwrap_or(999.0);
+                    let wind_direction = hourly_value(
+                        hourly.wind_direction.as_ref(),
+                        i,
+                        WeatherField::WindDirection,
+                        &mut missing,
+                    )
+                    .unwrap_or(0);
+                    let cloud_cover = hourly_value(
+                        hourly.cloud_cover.as_ref(),
+                        i,
+                        WeatherField::CloudCover,
+                        &mut missing,
+                    )
+                    .unwrap_or(0);
+
+                    let weather_code = *hourly
+                        .weather_code
                         .as_ref()
-                        .and_then(|temps| temps.get(i))
-                        .unwrap_or(&-999.0);
+                        .and_then(|codes| codes.get(i))
+                        .unwrap_or(&0);
+
+                    let description = weather_code_to_description(weather_code).to_string();
 
-                    let wind_speed = *hourly
-                        .wind_speed
+                    let precipitation_probability = hourly
+                        .precipitation_probability
                         .as_ref()
-                        .and_then(|speeds| speeds.get(i))
-                        .unwrap_or(&-999.0);
+                        .and_then(|chances| chances.get(i).copied().flatten());
 
-                    let wind_direction = *hourly
-                        .wind_direction
+                    let snow_depth = hourly
+                        .snow_depth
                         .as_ref()
-                        .and_then(|dirs| dirs.get(i))
-                        .unwrap_or(&0);
+                        .and_then(|depths| depths.get(i).copied().flatten())
+                        .unwrap_or(0.0);
 
-                    let wind_gust = *hourly
-                        .wind_gusts
+                    let snowfall = hourly
+                        .snowfall
                         .as_ref()
-                        .and_then(|gusts| gusts.get(i))
-                        .unwrap_or(&-999.0);
+                        .and_then(|falls| falls.get(i).copied().flatten())
+                        .unwrap_or(0.0);
 
-                    let precipitation = *hourly
-                        .precipitation
+                    let boundary_layer_height = hourly
+                        .boundary_layer_height
                         .as_ref()
-                        .and_then(|precip| precip.get(i))
-                        .unwrap_or(&-999.0);
-                    let cloud_cover = *hourly
-                        .cloud_cover
+                        .and_then(|heights| heights.get(i).copied().flatten());
+
+                    let wind_speed_80m = hourly
+                        .wind_speed_80m
                         .as_ref()
-                        .and_then(|clouds| clouds.get(i))
-                        .unwrap_or(&0);
+                        .and_then(|speeds| speeds.get(i).copied().flatten());
 
-                    let pressure = *hourly
-                        .pressure
+                    let wind_direction_80m = hourly
+                        .wind_direction_80m
                         .as_ref()
-                        .and_then(|press| press.get(i))
-                        .unwrap_or(&-999.0);
+                        .and_then(|dirs| dirs.get(i).copied().flatten());
 
-                    let visibility = *hourly
-                        .visibility
+                    let wind_speed_850hpa = hourly
+                        .wind_speed_850hpa
                         .as_ref()
-                        .and_then(|vis| vis.get(i))
-                        .unwrap_or(&999.0);
+                        .and_then(|speeds| speeds.get(i).copied().flatten());
 
-                    let weather_code = *hourly
-                        .weather_code
+                    let wind_direction_850hpa = hourly
+                        .wind_direction_850hpa
                         .as_ref()
-                        .and_then(|codes| codes.get(i))
-                        .unwrap_or(&0);
+                        .and_then(|dirs| dirs.get(i).copied().flatten());
 
-                    let description = weather_code_to_description(weather_code).to_string();
+                    let temperature_850hpa = hourly
+                        .temperature_850hpa
+                        .as_ref()
+                        .and_then(|temps| temps.get(i).copied().flatten());
+
+                    let height_850hpa = hourly
+                        .height_850hpa
+                        .as_ref()
+                        .and_then(|heights| heights.get(i).copied().flatten());
+
+                    let cape = hourly
+                        .cape
+                        .as_ref()
+                        .and_then(|capes| capes.get(i).copied().flatten());
+
+                    let freezing_level = hourly
+                        .freezing_level
+                        .as_ref()
+                        .and_then(|heights| heights.get(i).copied().flatten());
 
                     let weather_data = WeatherData {
                         timestamp,
                         temperature,
+                        dew_point,
                         wind_speed_ms: wind_speed,
                         wind_direction,
                         wind_gust_ms: wind_gust,
                         precipitation,
+                        precipitation_probability,
                         cloud_cover,
                         pressure,
                         visibility,
                         description,
+                        snow_depth_m: snow_depth,
+                        snowfall_cm: snowfall,
+                        boundary_layer_height_m: boundary_layer_height,
+                        wind_speed_80m_ms: wind_speed_80m,
+                        wind_direction_80m,
+                        wind_speed_850hpa_ms: wind_speed_850hpa,
+                        wind_direction_850hpa,
+                        temperature_850hpa,
+                        height_850hpa_m: height_850hpa,
+                        cape,
+                        freezing_level_m: freezing_level,
+                        model: None,
+                        missing,
                     };
 
                     forecasts.push(weather_data);
@@ -397,8 +1153,250 @@ mod openmeteo {
 
             Self {
                 location,
+                grid_elevation: response.elevation,
+                fetched_at: None,
+                provider: None,
                 forecast: forecasts,
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::weather::{WeatherData, WeatherField};
+    use chrono::{TimeZone, Utc};
+
+    fn forecast(days: std::ops::Range<u32>, model: &str) -> WeatherForecast {
+        WeatherForecast {
+            location: Location::new(50.0, 13.0, "Test".into(), "DE".into()),
+            grid_elevation: None,
+            fetched_at: None,
+            provider: None,
+            forecast: days
+                .map(|day| WeatherData {
+                    timestamp: Utc.with_ymd_and_hms(2026, 6, day, 12, 0, 0).unwrap(),
+                    model: Some(model.into()),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn cached_day(base: u32) -> Option<CachedDay> {
+        Some(CachedDay {
+            base: NaiveDate::from_ymd_opt(2026, 6, base).unwrap(),
+            grid_elevation: None,
+            fetched_at: Utc::now(),
+            hours: vec![],
+        })
+    }
+
+    #[test]
+    fn ttl_grows_with_lead_time() {
+        let policy = ForecastTtlPolicy::default();
+        assert_eq!(policy.ttl(0), Duration::from_hours(1));
+        assert_eq!(policy.ttl(3), Duration::from_mins(390));
+        assert_eq!(policy.ttl(6), Duration::from_hours(12));
+        assert_eq!(policy.ttl(15), Duration::from_hours(12));
+    }
+
+    #[test]
+    fn stale_days_are_missing_or_from_another_fetch() {
+        let days = vec![cached_day(13), None, cached_day(13), cached_day(12)];
+        assert_eq!(stale_days(&days), vec![1, 3]);
+
+        let days = vec![None, cached_day(13)];
+        assert_eq!(stale_days(&days), vec![0, 1]);
+    }
+
+    #[test]
+    fn split_days_starts_at_the_first_hour() {
+        let days = split_days(forecast(13..15, "icon"), 3);
+
+        assert_eq!(days.len(), 3);
+        assert!(
+            days.iter()
+                .all(|d| d.base == NaiveDate::from_ymd_opt(2026, 6, 13).unwrap())
+        );
+        assert_eq!(
+            days.iter().map(|d| d.hours.len()).collect::<Vec<_>>(),
+            vec![1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn geocoding_result_keeps_the_admin_area() {
+        let response: openmeteo::GeocodingResponse = serde_json::from_str(
+            r#"{"results": [
+                {"name": "Gornau", "latitude": 50.76, "longitude": 13.03,
+                 "country": "Germany", "admin1": "Saxony"},
+                {"name": "Gornau", "latitude": 54.1, "longitude": 10.9, "admin2": "Ostholstein"}
+            ]}"#,
+        )
+        .unwrap();
+        let candidates: Vec<GeocodeCandidate> = response
+            .results
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        assert_eq!(candidates[0].admin_area.as_deref(), Some("Saxony"));
+        assert_eq!(candidates[1].admin_area.as_deref(), Some("Ostholstein"));
+        assert_eq!(candidates[1].location.country, "Unknown");
+    }
+
+    #[test]
+    fn match_rank_puts_exact_names_before_partial_and_other_places() {
+        let rank = |query: &str, name: &str| {
+            match_rank(&normalize_place_name(query), &normalize_place_name(name))
+        };
+        assert_eq!(rank("Gornau/Erz", "gornau erz"), 0);
+        assert_eq!(rank("Gornau/Erz", "Gornau"), 1);
+        assert_eq!(rank("Born", "Bern"), 2);
+    }
+
+    #[test]
+    fn extended_range_only_fills_days_after_the_primary_forecast() {
+        let merged = extend_forecast(
+            forecast(1..8, "icon"),
+            forecast(1..17, EXTENDED_RANGE_MODEL),
+        );
+        assert_eq!(merged.forecast.len(), 16);
+        assert_eq!(merged.forecast[6].model.as_deref(), Some("icon"));
+        assert_eq!(
+            merged.forecast[7].model.as_deref(),
+            Some(EXTENDED_RANGE_MODEL)
+        );
+    }
+
+    #[test]
+    fn null_hourly_values_are_noted_as_missing() {
+        let response: openmeteo::ForecastResponse = serde_json::from_str(
+            r#"{
+                "latitude": 50.0,
+                "longitude": 13.0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly": {
+                    "time": ["2026-06-13T12:00", "2026-06-13T13:00"],
+                    "temperature_2m": [18.0, 19.0],
+                    "windspeed_10m": [2.5, 3.0],
+                    "windgusts_10m": [5.0, null],
+                    "precipitation_probability": [35, null],
+                    "visibility": [null, null]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let forecast = WeatherForecast::from_openmeteo(
+            &response,
+            Location::new(50.0, 13.0, "Test".into(), "DE".into()),
+        );
+
+        let missing: Vec<&[WeatherField]> = forecast
+            .forecast
+            .iter()
+            .map(|h| h.missing.as_slice())
+            .collect();
+        assert!(missing[0].contains(&WeatherField::Visibility));
+        assert!(!missing[0].contains(&WeatherField::WindGust));
+        assert!(missing[1].contains(&WeatherField::WindGust));
+        assert!(!missing[1].contains(&WeatherField::Temperature));
+        assert_eq!(forecast.forecast[1].visibility, 999.0);
+        assert_eq!(forecast.forecast[0].precipitation_probability, Some(35));
+        assert_eq!(forecast.forecast[1].precipitation_probability, None);
+    }
+
+    #[test]
+    fn sounding_response_drops_levels_below_ground() {
+        let response: openmeteo::SoundingResponse = serde_json::from_str(
+            r#"{
+                "elevation": 900.0,
+                "hourly": {
+                    "time": ["2026-06-13T12:00"],
+                    "temperature_2m": [18.0],
+                    "dewpoint_2m": [8.0],
+                    "wind_speed_10m": [2.5],
+                    "wind_direction_10m": [230.0],
+                    "temperature_1000hPa": [21.0],
+                    "geopotential_height_1000hPa": [110.0],
+                    "temperature_850hPa": [12.0],
+                    "dewpoint_850hPa": [4.0],
+                    "geopotential_height_850hPa": [1520.0],
+                    "wind_speed_850hPa": [6.5],
+                    "wind_direction_850hPa": [255.0],
+                    "temperature_700hPa": [null],
+                    "geopotential_height_700hPa": [3100.0]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let soundings = response.to_soundings();
+
+        assert_eq!(soundings.len(), 1);
+        let sounding = &soundings[0];
+        assert_eq!(sounding.surface_elevation_m, 900.0);
+        assert_eq!(sounding.surface_dew_point, 8.0);
+        assert_eq!(sounding.surface_wind_speed_ms, 2.5);
+        assert_eq!(sounding.surface_wind_direction, 230);
+        assert_eq!(sounding.levels.len(), 1);
+        assert_eq!(sounding.levels[0].pressure_hpa, 850.0);
+        assert_eq!(sounding.levels[0].wind_direction, 255);
+    }
+
+    #[test]
+    fn nearby_locations_share_a_sounding_grid_cell() {
+        let a = Location::new(50.712, 13.049, "A".into(), "DE".into());
+        let b = Location::new(50.688, 12.961, "B".into(), "DE".into());
+        assert_eq!(
+            sounding_grid_cell(&a).to_key(),
+            sounding_grid_cell(&b).to_key()
+        );
+    }
+
+    #[test]
+    fn elevation_batch_lists_coordinates_comma_separated() {
+        let points = [(50.712, 13.049), (47.5, -11.25)];
+        assert_eq!(
+            elevation_url(&points),
+            "https://api.open-meteo.com/v1/elevation?latitude=50.712,47.5&longitude=13.049,-11.25"
+        );
+        assert_eq!(round_elevation_coordinate(50.71249), 50.712);
+    }
+
+    #[test]
+    fn ensemble_response_is_split_into_members() {
+        let response: openmeteo::EnsembleResponse = serde_json::from_str(
+            r#"{
+                "hourly": {
+                    "time": ["2026-06-13T12:00", "2026-06-13T13:00"],
+                    "wind_speed_10m": [3.0, 4.0],
+                    "wind_gusts_10m": [5.0, 6.0],
+                    "wind_direction_10m": [270.0, 280.0],
+                    "precipitation": [0.0, 0.2],
+                    "wind_speed_10m_member01": [8.0, null],
+                    "wind_direction_10m_member01": [90.0, 95.0],
+                    "precipitation_member01": [1.5, 0.0]
+                }
+            }"#,
+        )
+        .unwrap();
+        let location = Location::new(47.0, 11.0, "Hill".into(), "AT".into());
+
+        let members = response.to_members(&location);
+
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].forecast.len(), 2);
+        assert_eq!(members[0].forecast[1].wind_gust_ms, 6.0);
+        assert_eq!(members[0].forecast[1].wind_direction, 280);
+        // The member lacks gusts and its second hour lacks wind
+        assert_eq!(members[1].forecast.len(), 1);
+        assert_eq!(members[1].forecast[0].wind_gust_ms, 8.0);
+        assert_eq!(members[1].forecast[0].missing, vec![WeatherField::WindGust]);
+        assert_eq!(members[1].forecast[0].precipitation, 1.5);
+    }
+}
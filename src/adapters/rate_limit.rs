@@ -0,0 +1,190 @@
+//! Per-client request budgets for the API, so one misbehaving frontend can't use
+//! up the Open-Meteo quota everyone shares. Clients with a configured
+//! `X-Api-Key` get that key's budget; everyone else is limited per address.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use opentelemetry::{KeyValue, global, metrics::Counter};
+
+use crate::{
+    adapters::api_error::ApiError,
+    config::RateLimitConfig,
+    domain::error::{ErrorCode, RETRY_AFTER_KEY},
+};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// Above this many tracked clients, buckets that have refilled are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    requests: Counter<u64>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let requests = global::meter("travelai")
+            .u64_counter("api.requests")
+            .with_description("API requests by client and whether they were rate limited")
+            .build();
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            requests,
+        }
+    }
+
+    /// Takes one request from the client's budget, or returns how long until
+    /// the next one is available.
+    fn check(&self, api_key: Option<&str>, address: &str, now: Instant) -> Result<(), Duration> {
+        let (bucket, per_minute, label) =
+            match api_key.and_then(|key| Some((key, *self.config.keys.get(key)?))) {
+                Some((key, per_minute)) => (format!("key:{key}"), per_minute, masked(key)),
+                None => match self.config.per_minute {
+                    Some(per_minute) => (format!("addr:{address}"), per_minute, "anonymous".into()),
+                    None => return Ok(()),
+                },
+            };
+        let result = self.take(bucket, per_minute, now);
+        let outcome = if result.is_ok() { "allowed" } else { "limited" };
+        self.requests.add(
+            1,
+            &[
+                KeyValue::new("client", label),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+        result
+    }
+
+    fn take(&self, bucket: String, per_minute: u32, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(per_minute);
+        let per_second = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, b| now.duration_since(b.updated) < Duration::from_secs(60));
+        }
+
+        let bucket = buckets.entry(bucket).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        if per_second == 0.0 {
+            return Err(Duration::from_secs(60));
+        }
+        Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+    }
+}
+
+/// Enough of an API key to tell clients apart in metrics without exposing it.
+fn masked(key: &str) -> String {
+    let prefix: String = key.chars().take(4).collect();
+    format!("{prefix}…")
+}
+
+/// Middleware answering requests over budget with 429 and `Retry-After`.
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let api_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok());
+    let address = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_default();
+
+    if let Err(retry_after) = limiter.check(api_key, &address, Instant::now()) {
+        let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+        tracing::warn!(address, retry_after = seconds, "Rate limit exceeded");
+        let mut response = ApiError::new(ErrorCode::RateLimited, "Too many requests")
+            .with_context(RETRY_AFTER_KEY, seconds)
+            .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        return response;
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_minute: Option<u32>, keys: &[(&str, u32)]) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            per_minute,
+            keys: keys.iter().map(|(k, l)| (k.to_string(), *l)).collect(),
+        })
+    }
+
+    #[test]
+    fn budget_refills_over_time() {
+        let limiter = limiter(Some(60), &[]);
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check(None, "10.0.0.1", start).is_ok());
+        }
+        let retry_after = limiter.check(None, "10.0.0.1", start).unwrap_err();
+        assert_eq!(retry_after.as_secs(), 1);
+
+        assert!(limiter.check(None, "10.0.0.2", start).is_ok());
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check(None, "10.0.0.1", later).is_ok());
+        assert!(limiter.check(None, "10.0.0.1", later).is_err());
+    }
+
+    #[test]
+    fn listed_keys_get_their_own_budget() {
+        let limiter = limiter(Some(1), &[("frontend", 3)]);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check(Some("frontend"), "10.0.0.1", now).is_ok());
+        }
+        assert!(limiter.check(Some("frontend"), "10.0.0.1", now).is_err());
+
+        // Unknown keys count against the address
+        assert!(limiter.check(Some("made-up"), "10.0.0.1", now).is_ok());
+        assert!(limiter.check(None, "10.0.0.1", now).is_err());
+    }
+
+    #[test]
+    fn without_a_default_only_listed_keys_are_limited() {
+        let limiter = limiter(None, &[("widget", 0)]);
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.check(None, "10.0.0.1", now).is_ok());
+        }
+        assert!(limiter.check(Some("widget"), "10.0.0.1", now).is_err());
+    }
+}
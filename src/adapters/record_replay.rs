@@ -0,0 +1,181 @@
+//! Records upstream HTTP responses into fixture files and answers from them
+//! later, so the forecast pipeline can be tested end to end without network.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use reqwest::{Method, Request, Response, ResponseBuilderExt, Url};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Pass requests through and write every response to a fixture
+    Record,
+    /// Answer from fixtures only, a missing fixture is an error
+    Replay,
+}
+
+pub struct RecordReplay {
+    dir: PathBuf,
+    mode: Mode,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    url: String,
+    status: u16,
+    body: String,
+}
+
+impl RecordReplay {
+    pub fn new(dir: impl Into<PathBuf>, mode: Mode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// Reads `HTTP_FIXTURES`, e.g. `record:tests/fixtures/http` or
+    /// `replay:tests/fixtures/http`.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var("HTTP_FIXTURES").ok()?;
+        let (mode, dir) = value.split_once(':')?;
+        let mode = match mode {
+            "record" => Mode::Record,
+            "replay" => Mode::Replay,
+            _ => return None,
+        };
+        tracing::warn!(?mode, dir, "HTTP record/replay enabled");
+        Some(Self::new(dir, mode))
+    }
+
+    fn fixture_path(&self, method: &Method, url: &Url) -> PathBuf {
+        self.dir.join(fixture_name(method, url))
+    }
+}
+
+/// Readable prefix from host and path, made unique by a hash of the full request.
+/// The hash is FNV-1a so names stay stable across Rust versions.
+fn fixture_name(method: &Method, url: &Url) -> String {
+    let readable: String = format!("{}{}", url.host_str().unwrap_or_default(), url.path())
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in format!("{} {}", method, url).bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{}_{:016x}.json", readable, hash)
+}
+
+fn read_fixture(path: &Path) -> anyhow::Result<Fixture> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("No HTTP fixture at {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid fixture {}", path.display()))
+}
+
+fn write_fixture(path: &Path, fixture: &Fixture) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(fixture)?)?;
+    Ok(())
+}
+
+fn into_response(fixture: Fixture, url: Url) -> anyhow::Result<Response> {
+    let response = http::Response::builder()
+        .status(fixture.status)
+        .url(url)
+        .body(fixture.body)
+        .map_err(|e| anyhow!(e))?;
+    Ok(Response::from(response))
+}
+
+#[async_trait]
+impl Middleware for RecordReplay {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().clone();
+        let url = req.url().clone();
+        let path = self.fixture_path(&method, &url);
+
+        // Fixtures are small, blocking file access is fine for tests.
+        match self.mode {
+            Mode::Replay => {
+                let fixture = read_fixture(&path)?;
+                Ok(into_response(fixture, url)?)
+            }
+            Mode::Record => {
+                let response = next.run(req, extensions).await?;
+                let fixture = Fixture {
+                    method: method.to_string(),
+                    url: url.to_string(),
+                    status: response.status().as_u16(),
+                    body: response.text().await?,
+                };
+                write_fixture(&path, &fixture)?;
+                tracing::debug!(path = %path.display(), "Recorded HTTP fixture");
+                Ok(into_response(fixture, url)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_name_is_readable_and_depends_on_the_query() {
+        let a = Url::parse("https://api.open-meteo.com/v1/forecast?latitude=50.7").unwrap();
+        let b = Url::parse("https://api.open-meteo.com/v1/forecast?latitude=47.1").unwrap();
+        let name = fixture_name(&Method::GET, &a);
+        assert!(
+            name.starts_with("api_open_meteo_com_v1_forecast_"),
+            "{name}"
+        );
+        assert!(name.ends_with(".json"));
+        assert_ne!(name, fixture_name(&Method::GET, &b));
+        assert_eq!(name, fixture_name(&Method::GET, &a));
+    }
+
+    #[tokio::test]
+    async fn replays_a_recorded_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = Url::parse("https://example.com/data?x=1").unwrap();
+        let recorder = RecordReplay::new(dir.path(), Mode::Replay);
+        let fixture = Fixture {
+            method: "GET".into(),
+            url: url.to_string(),
+            status: 200,
+            body: r#"{"value": 42}"#.into(),
+        };
+        write_fixture(&recorder.fixture_path(&Method::GET, &url), &fixture).unwrap();
+
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(recorder)
+            .build();
+        let body: serde_json::Value = client.get(url).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body["value"], 42);
+    }
+
+    #[tokio::test]
+    async fn replay_without_fixture_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(RecordReplay::new(dir.path(), Mode::Replay))
+            .build();
+        assert!(client.get("https://example.com/").send().await.is_err());
+    }
+}
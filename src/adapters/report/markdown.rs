@@ -0,0 +1,185 @@
+//! The weekend outlook as a short text for club group chats: per day an emoji
+//! rating and the best sites with their windows. Sticks to what Markdown and
+//! WhatsApp both render, `*bold*` and `- ` lists.
+
+use chrono::NaiveDate;
+
+use crate::{
+    adapters::{
+        activities::paragliding::{outlook::SiteDay, site_evaluator::DayPart},
+        report::span,
+    },
+    domain::activities::{DayRating, SiteError},
+};
+
+/// Sites listed per day, best first
+const MAX_SITES: usize = 3;
+const NOT_FLYABLE: &str = "🔴";
+
+/// The weekend outlook for `days`, from the sites' summaries on them, naming
+/// the sites in `errors` that couldn't be scored at the end.
+pub fn render(days: &[NaiveDate], sites: &[SiteDay], errors: &[SiteError]) -> String {
+    let title = match span(days) {
+        Some(span) => format!("Weekend outlook {span}"),
+        None => "Weekend outlook".to_string(),
+    };
+    outlook(&title, days, sites, errors)
+}
+
+/// Like [`render`], for any `days` under another `title`.
+pub fn outlook(title: &str, days: &[NaiveDate], sites: &[SiteDay], errors: &[SiteError]) -> String {
+    let mut lines = vec![
+        format!("*{title}*"),
+        "Hours are UTC, wind in km/h".to_string(),
+    ];
+
+    for date in days {
+        let mut flyable: Vec<&SiteDay> = sites
+            .iter()
+            .filter(|d| d.flyable && d.day.date == *date)
+            .collect();
+        flyable.sort_by(|a, b| {
+            b.day
+                .total_flyable_hours
+                .cmp(&a.day.total_flyable_hours)
+                .then_with(|| a.site.name.cmp(&b.site.name))
+        });
+
+        lines.push(String::new());
+        let heading = date.format("%A %-d %b");
+        let Some(best) = flyable.first() else {
+            lines.push(format!("{NOT_FLYABLE} *{heading}* not flyable"));
+            continue;
+        };
+        lines.push(format!(
+            "{} *{heading}* {}",
+            emoji(best.rating()),
+            best.rating().label()
+        ));
+        lines.extend(flyable.iter().take(MAX_SITES).map(|d| site_line(d)));
+        if flyable.len() > MAX_SITES {
+            lines.push(format!(
+                "- and {} more flyable sites",
+                flyable.len() - MAX_SITES
+            ));
+        }
+    }
+    if !errors.is_empty() {
+        let names: Vec<&str> = errors.iter().map(|e| e.site.as_str()).collect();
+        lines.push(String::new());
+        lines.push(format!("_Couldn't be scored: {}_", names.join(", ")));
+    }
+    lines.join("\n")
+}
+
+/// E.g. `- Wank: 6h 10-16, SW 11, best in the morning ⚠️ gusts`
+fn site_line(day: &SiteDay) -> String {
+    let mut line = format!("- {}: {}h", day.site.name, day.day.total_flyable_hours);
+    if let Some(window) = day.window() {
+        line.push_str(&format!(" {window}"));
+    }
+    if let Some(wind) = day.wind() {
+        line.push_str(&format!(", {wind}"));
+    }
+    if let Some((parts, _)) = day.day.best_day_parts() {
+        let parts: Vec<&str> = parts.iter().map(DayPart::label).collect();
+        line.push_str(&format!(", best in the {}", parts.join(" and ")));
+    }
+    if !day.day.hazards.is_empty() {
+        let hazards: Vec<&str> = day.day.hazards.iter().map(|h| h.label()).collect();
+        line.push_str(&format!(" ⚠️ {}", hazards.join(", ")));
+    }
+    line
+}
+
+fn emoji(rating: DayRating) -> &'static str {
+    match rating {
+        DayRating::Excellent => "🟢",
+        DayRating::Good => "🟡",
+        DayRating::Fair => "🟠",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::{
+            activities::paragliding::site_evaluator::{DayPartSummary, Hazard},
+            report::tests::{date, site_day},
+        },
+        domain::error::ErrorCode,
+    };
+
+    #[test]
+    fn outlook_lists_the_best_sites_per_day() {
+        let sites = vec![
+            site_day("Wank", 13, 6, vec![Hazard::Gusts]),
+            site_day("Hörnle", 13, 3, vec![]),
+            site_day("Wank", 14, 1, vec![]),
+        ];
+
+        let errors = [SiteError {
+            site: "Brauneck".into(),
+            code: ErrorCode::RateLimited,
+            error: "Open-Meteo rate limit reached".into(),
+        }];
+
+        let text = render(&[date(13), date(14)], &sites, &errors);
+
+        assert_eq!(
+            text,
+            "*Weekend outlook Sat 13 Jun - Sun 14 Jun 2026*\n\
+             Hours are UTC, wind in km/h\n\
+             \n\
+             🟢 *Saturday 13 Jun* excellent\n\
+             - Wank: 6h 10-16, SW 11 ⚠️ gusts\n\
+             - Hörnle: 3h 10-13, SW 11\n\
+             \n\
+             🔴 *Sunday 14 Jun* not flyable\n\
+             \n\
+             _Couldn't be scored: Brauneck_"
+        );
+    }
+
+    #[test]
+    fn only_the_top_sites_are_listed() {
+        let sites: Vec<SiteDay> = ["A", "B", "C", "D", "E"]
+            .into_iter()
+            .map(|name| site_day(name, 13, 4, vec![]))
+            .collect();
+
+        let text = render(&[date(13)], &sites, &[]);
+
+        assert!(text.contains("🟡 *Saturday 13 Jun* good"), "{text}");
+        assert!(text.contains("- C: 4h"));
+        assert!(!text.contains("- D: 4h"));
+        assert!(text.ends_with("- and 2 more flyable sites"));
+    }
+
+    #[test]
+    fn site_line_names_the_best_part_of_the_day() {
+        let mut day = site_day("Wank", 13, 6, vec![]);
+        day.day.day_parts = [
+            (DayPart::Morning, Some(DayRating::Excellent)),
+            (DayPart::Midday, Some(DayRating::Good)),
+            (DayPart::Afternoon, None),
+        ]
+        .into_iter()
+        .map(|(part, rating)| DayPartSummary {
+            part,
+            flyable_hours: usize::from(rating.is_some()),
+            hours: 3,
+            confidence: 1.0,
+            flyable_probability: None,
+            wind_alignment: None,
+            rating,
+        })
+        .collect();
+
+        assert_eq!(
+            site_line(&day),
+            "- Wank: 6h 10-16, SW 11, best in the morning"
+        );
+    }
+}
@@ -0,0 +1,647 @@
+//! One-page PDF briefing of a weekend for the club notice board: a sketch map
+//! of the sites around home, a table of sites by day with their flyable hours,
+//! hourly charts of the top pick and the hazards to expect.
+
+pub mod markdown;
+pub mod pdf;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Timelike, Utc};
+
+use crate::{
+    adapters::activities::paragliding::outlook::SiteDay,
+    domain::{
+        activities::{DayRating, SiteError},
+        geo,
+        location::Location,
+    },
+};
+use pdf::{BLACK, BLUE, GREEN, GREY, LIGHT_GREY, Page, RED, Rgb};
+
+const MARGIN: f64 = 40.0;
+const RIGHT: f64 = pdf::WIDTH - MARGIN;
+/// Sites in the table and numbered on the map, best first
+const MAX_SITES: usize = 12;
+const MAX_HAZARD_LINES: usize = 8;
+/// Sites named in the line about those that couldn't be scored
+const MAX_ERROR_SITES: usize = 6;
+const ROW_HEIGHT: f64 = 14.0;
+const MAP_TOP: f64 = 90.0;
+const MAP_WIDTH: f64 = 220.0;
+const MAP_HEIGHT: f64 = 170.0;
+const CHART_LEFT: f64 = 75.0;
+const CHART_HEIGHT: f64 = 70.0;
+/// Lowest top of the wind chart, so a calm day doesn't look stormy
+const MIN_WIND_SCALE_KMH: f64 = 30.0;
+
+const EXCELLENT: Rgb = Rgb(0.55, 0.85, 0.55);
+const GOOD: Rgb = Rgb(0.75, 0.92, 0.7);
+const FAIR: Rgb = Rgb(0.95, 0.95, 0.7);
+const FLYABLE_HOUR: Rgb = Rgb(0.88, 0.96, 0.86);
+
+/// The days of the weekend `today` is in or leads up to, from `today` on:
+/// Saturday and Sunday, or only Sunday on a Sunday.
+pub fn weekend(today: NaiveDate) -> Vec<NaiveDate> {
+    let until_sunday = 7 - u64::from(today.weekday().number_from_monday());
+    let sunday = today + Days::new(until_sunday);
+    [sunday - Days::new(1), sunday]
+        .into_iter()
+        .filter(|day| *day >= today)
+        .collect()
+}
+
+/// First to last of `days`, e.g. `Sat 13 Jun - Sun 14 Jun 2026`.
+fn span(days: &[NaiveDate]) -> Option<String> {
+    match (days.first(), days.last()) {
+        (Some(first), Some(last)) if first != last => Some(format!(
+            "{} - {}",
+            first.format("%a %-d %b"),
+            last.format("%a %-d %b %Y")
+        )),
+        (Some(day), _) => Some(day.format("%a %-d %b %Y").to_string()),
+        _ => None,
+    }
+}
+
+/// A site with its summary on each of the weekend's days.
+struct Row<'a> {
+    site: &'a SiteDay,
+    days: Vec<Option<&'a SiteDay>>,
+}
+
+impl Row<'_> {
+    fn flyable_hours(&self) -> usize {
+        self.days
+            .iter()
+            .flatten()
+            .filter(|d| d.flyable)
+            .map(|d| d.day.total_flyable_hours)
+            .sum()
+    }
+
+    fn hazards(&self) -> usize {
+        self.days
+            .iter()
+            .flatten()
+            .map(|d| d.day.hazards.len())
+            .sum()
+    }
+}
+
+/// The briefing for `days` as a PDF, from the sites' summaries on them,
+/// naming the sites in `errors` that couldn't be scored under the title.
+pub fn render(
+    home: &Location,
+    days: &[NaiveDate],
+    sites: &[SiteDay],
+    errors: &[SiteError],
+) -> Vec<u8> {
+    let rows = rows(days, sites);
+    let mut page = Page::new();
+
+    let title = match span(days) {
+        Some(span) => format!("Weekend briefing {span}"),
+        None => "Weekend briefing".to_string(),
+    };
+    page.text(MARGIN, 50.0, 18.0, true, &title);
+    let around = if home.name.is_empty() {
+        format!("{:.3}, {:.3}", home.latitude, home.longitude)
+    } else {
+        home.name.clone()
+    };
+    page.text(
+        MARGIN,
+        68.0,
+        10.0,
+        false,
+        &format!("{} sites around {around}. Hours are UTC.", rows.len()),
+    );
+    if let Some(line) = errors_line(errors) {
+        page.text(MARGIN, 80.0, 8.0, false, &line);
+    }
+
+    map(&mut page, home, &rows);
+    let top = top_pick(sites);
+    top_pick_summary(&mut page, top);
+    let mut y = table(&mut page, days, &rows, MAP_TOP + MAP_HEIGHT + 30.0);
+    if let Some(top) = top {
+        y = charts(&mut page, top, y + 20.0);
+    }
+    hazards(&mut page, &rows, y + 20.0);
+    page.into_pdf()
+}
+
+/// E.g. `Couldn't be scored: Wank, Hörnle`, None if every site could be.
+fn errors_line(errors: &[SiteError]) -> Option<String> {
+    if errors.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = errors
+        .iter()
+        .take(MAX_ERROR_SITES)
+        .map(|e| e.site.as_str())
+        .collect();
+    let mut line = format!("Couldn't be scored: {}", names.join(", "));
+    if errors.len() > MAX_ERROR_SITES {
+        line.push_str(&format!(" and {} more", errors.len() - MAX_ERROR_SITES));
+    }
+    Some(line)
+}
+
+/// One row per site, most flyable hours over the weekend first.
+fn rows<'a>(days: &[NaiveDate], sites: &'a [SiteDay]) -> Vec<Row<'a>> {
+    let mut rows: Vec<Row> = Vec::new();
+    for site_day in sites {
+        let Some(index) = days.iter().position(|d| *d == site_day.day.date) else {
+            continue;
+        };
+        let row = match rows
+            .iter_mut()
+            .position(|r| r.site.site.name == site_day.site.name)
+        {
+            Some(row) => &mut rows[row],
+            None => {
+                rows.push(Row {
+                    site: site_day,
+                    days: vec![None; days.len()],
+                });
+                rows.last_mut().expect("just pushed")
+            }
+        };
+        row.days[index] = Some(site_day);
+    }
+    rows.sort_by(|a, b| {
+        b.flyable_hours()
+            .cmp(&a.flyable_hours())
+            .then_with(|| a.site.site.name.cmp(&b.site.site.name))
+    });
+    rows
+}
+
+/// The flyable site day with the most flyable hours, the earlier on a tie.
+fn top_pick(sites: &[SiteDay]) -> Option<&SiteDay> {
+    sites
+        .iter()
+        .filter(|d| d.flyable)
+        .min_by_key(|d| (std::cmp::Reverse(d.day.total_flyable_hours), d.day.date))
+}
+
+/// Home and the sites' launches north up in an equirectangular projection,
+/// which is close enough over a search radius.
+fn map(page: &mut Page, home: &Location, rows: &[Row]) {
+    page.fill_rect(MARGIN, MAP_TOP, MAP_WIDTH, MAP_HEIGHT, LIGHT_GREY);
+    page.stroke_rect(MARGIN, MAP_TOP, MAP_WIDTH, MAP_HEIGHT, GREY);
+
+    let launches: Vec<(usize, &Row, (f64, f64))> = rows
+        .iter()
+        .take(MAX_SITES)
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let launch = row.site.site.launches.first()?;
+            Some((
+                index,
+                row,
+                (launch.location.latitude, launch.location.longitude),
+            ))
+        })
+        .collect();
+    let home_point = (home.latitude, home.longitude);
+    let points: Vec<(f64, f64)> = std::iter::once(home_point)
+        .chain(launches.iter().map(|(_, _, point)| *point))
+        .collect();
+
+    let fold = |f: fn(f64, f64) -> f64, start: f64, pick: fn(&(f64, f64)) -> f64| {
+        points.iter().map(pick).fold(start, f)
+    };
+    let (south, north) = (
+        fold(f64::min, f64::INFINITY, |p| p.0),
+        fold(f64::max, f64::NEG_INFINITY, |p| p.0),
+    );
+    let (west, east) = (
+        fold(f64::min, f64::INFINITY, |p| p.1),
+        fold(f64::max, f64::NEG_INFINITY, |p| p.1),
+    );
+    let (mid_lat, mid_lon) = ((south + north) / 2.0, (west + east) / 2.0);
+    let shrink = mid_lat.to_radians().cos();
+    // Points per degree of latitude, leaving room for labels at the edges
+    let padding = 20.0;
+    let scale = f64::min(
+        (MAP_WIDTH - 2.0 * padding) / ((east - west) * shrink).max(0.01),
+        (MAP_HEIGHT - 2.0 * padding) / (north - south).max(0.01),
+    );
+    let project = |(latitude, longitude): (f64, f64)| {
+        (
+            MARGIN + MAP_WIDTH / 2.0 + (longitude - mid_lon) * shrink * scale,
+            MAP_TOP + MAP_HEIGHT / 2.0 - (latitude - mid_lat) * scale,
+        )
+    };
+
+    for (index, row, point) in &launches {
+        let (x, y) = project(*point);
+        let color = if row.flyable_hours() > 0 { GREEN } else { GREY };
+        page.dot(x, y, 3.0, color);
+        page.text(x + 4.0, y + 3.0, 7.0, false, &(index + 1).to_string());
+    }
+    let (x, y) = project(home_point);
+    page.dot(x, y, 3.5, BLACK);
+    page.text(x + 5.0, y + 3.0, 7.0, true, "Home");
+
+    let half_width = MAP_WIDTH / 2.0 / (shrink * scale);
+    let across = geo::distance_km(
+        (mid_lat, mid_lon - half_width),
+        (mid_lat, mid_lon + half_width),
+    );
+    page.text(
+        MARGIN + 4.0,
+        MAP_TOP + MAP_HEIGHT - 5.0,
+        7.0,
+        false,
+        &format!("{across:.0} km across, green flyable"),
+    );
+}
+
+fn top_pick_summary(page: &mut Page, top: Option<&SiteDay>) {
+    let x = MARGIN + MAP_WIDTH + 25.0;
+    page.text(x, MAP_TOP + 10.0, 12.0, true, "Top pick");
+    let Some(top) = top else {
+        page.text(
+            x,
+            MAP_TOP + 28.0,
+            10.0,
+            false,
+            "No site is flyable this weekend.",
+        );
+        return;
+    };
+    let mut lines = vec![
+        top.day.date.format("%A %-d %B").to_string(),
+        format!(
+            "{}h flyable, {}",
+            top.day.total_flyable_hours,
+            top.rating().label()
+        ),
+    ];
+    if let Some(window) = top.window() {
+        lines.push(format!("Window {window} UTC"));
+    }
+    if let Some(wind) = top.wind() {
+        lines.push(format!("Wind {wind} km/h"));
+    }
+    if let Some(launch) = top.site.launches.first() {
+        lines.push(format!(
+            "Launch {:.0} m, {:.0}-{:.0}°",
+            launch.elevation, launch.direction_degrees_start, launch.direction_degrees_stop
+        ));
+    }
+    page.text(x, MAP_TOP + 28.0, 11.0, true, &truncate(&top.site.name, 40));
+    for (index, line) in lines.iter().enumerate() {
+        page.text(x, MAP_TOP + 44.0 + index as f64 * 13.0, 10.0, false, line);
+    }
+}
+
+/// Sites by day, returning the bottom of the table.
+fn table(page: &mut Page, days: &[NaiveDate], rows: &[Row], top: f64) -> f64 {
+    let first_day = 300.0;
+    let column = (RIGHT - first_day) / days.len().max(1) as f64;
+    page.text(MARGIN, top, 9.0, true, "#");
+    page.text(MARGIN + 18.0, top, 9.0, true, "Site");
+    for (index, day) in days.iter().enumerate() {
+        let x = first_day + index as f64 * column;
+        page.text(
+            x + 4.0,
+            top,
+            9.0,
+            true,
+            &day.format("%a %-d %b").to_string(),
+        );
+    }
+    page.line((MARGIN, top + 4.0), (RIGHT, top + 4.0), 0.5, GREY);
+
+    let mut y = top + 4.0;
+    for (index, row) in rows.iter().take(MAX_SITES).enumerate() {
+        let baseline = y + ROW_HEIGHT - 4.0;
+        page.text(MARGIN, baseline, 9.0, false, &(index + 1).to_string());
+        page.text(
+            MARGIN + 18.0,
+            baseline,
+            9.0,
+            false,
+            &truncate(&row.site.site.name, 45),
+        );
+        for (day, site_day) in row.days.iter().enumerate() {
+            let x = first_day + day as f64 * column;
+            let (color, label) = match site_day {
+                Some(d) if d.flyable => (
+                    match d.rating() {
+                        DayRating::Excellent => EXCELLENT,
+                        DayRating::Good => GOOD,
+                        DayRating::Fair => FAIR,
+                    },
+                    format!("{}h {}", d.day.total_flyable_hours, d.rating().label()),
+                ),
+                Some(d) => (LIGHT_GREY, format!("{}h", d.day.total_flyable_hours)),
+                None => (LIGHT_GREY, "no forecast".to_string()),
+            };
+            page.fill_rect(x + 1.0, y + 1.0, column - 2.0, ROW_HEIGHT - 2.0, color);
+            page.text(x + 4.0, baseline, 9.0, false, &label);
+        }
+        y += ROW_HEIGHT;
+    }
+    if rows.len() > MAX_SITES {
+        y += ROW_HEIGHT;
+        page.text(
+            MARGIN + 18.0,
+            y - 4.0,
+            9.0,
+            false,
+            &format!("and {} more sites", rows.len() - MAX_SITES),
+        );
+    }
+    y
+}
+
+/// Wind and cloud cover of the top pick's daylight hours, flyable hours shaded.
+/// Returns the bottom of the charts.
+fn charts(page: &mut Page, top: &SiteDay, y: f64) -> f64 {
+    let hours: Vec<_> = top
+        .day
+        .hourly_scores
+        .iter()
+        .filter_map(|score| {
+            let weather = top.hours.iter().find(|h| h.timestamp == score.timestamp)?;
+            Some((score.timestamp, score.is_flyable, weather))
+        })
+        .collect();
+    if hours.is_empty() {
+        return y;
+    }
+    page.text(
+        MARGIN,
+        y,
+        12.0,
+        true,
+        &format!(
+            "{}, {}",
+            truncate(&top.site.name, 40),
+            top.day.date.format("%A")
+        ),
+    );
+
+    let times: Vec<DateTime<Utc>> = hours.iter().map(|h| h.0).collect();
+    let flyable: Vec<bool> = hours.iter().map(|h| h.1).collect();
+    let wind: Vec<f64> = hours
+        .iter()
+        .map(|h| f64::from(h.2.wind_speed().kmh()))
+        .collect();
+    let gusts: Vec<f64> = hours
+        .iter()
+        .map(|h| f64::from(h.2.wind_gust().kmh()))
+        .collect();
+    let clouds: Vec<f64> = hours.iter().map(|h| f64::from(h.2.cloud_cover)).collect();
+    let wind_scale = gusts
+        .iter()
+        .chain(&wind)
+        .fold(MIN_WIND_SCALE_KMH, |max, v| max.max(*v));
+    let wind_scale = (wind_scale / 10.0).ceil() * 10.0;
+
+    let wind_top = y + 12.0;
+    chart(
+        page,
+        wind_top,
+        &times,
+        &flyable,
+        &[("wind km/h", BLUE, &wind), ("gusts", RED, &gusts)],
+        wind_scale,
+    );
+    let cloud_top = wind_top + CHART_HEIGHT + 22.0;
+    chart(
+        page,
+        cloud_top,
+        &times,
+        &flyable,
+        &[("cloud cover %", GREY, &clouds)],
+        100.0,
+    );
+    cloud_top + CHART_HEIGHT + 12.0
+}
+
+type Series<'a> = (&'a str, Rgb, &'a [f64]);
+
+fn chart(
+    page: &mut Page,
+    top: f64,
+    times: &[DateTime<Utc>],
+    flyable: &[bool],
+    series: &[Series],
+    scale: f64,
+) {
+    let width = RIGHT - CHART_LEFT;
+    let step = width / times.len() as f64;
+    let bottom = top + CHART_HEIGHT;
+    for (index, _) in flyable.iter().enumerate().filter(|(_, f)| **f) {
+        page.fill_rect(
+            CHART_LEFT + index as f64 * step,
+            top,
+            step,
+            CHART_HEIGHT,
+            FLYABLE_HOUR,
+        );
+    }
+    page.stroke_rect(CHART_LEFT, top, width, CHART_HEIGHT, GREY);
+    page.text(MARGIN, top + 7.0, 7.0, false, &format!("{scale:.0}"));
+    page.text(MARGIN, bottom, 7.0, false, "0");
+    for (index, time) in times.iter().enumerate().filter(|(i, _)| i % 2 == 0) {
+        let x = CHART_LEFT + index as f64 * step;
+        page.text(x + 2.0, bottom + 9.0, 7.0, false, &time.hour().to_string());
+    }
+
+    let mut legend = CHART_LEFT;
+    for (label, color, values) in series {
+        let point = |(index, value): (usize, &f64)| {
+            (
+                CHART_LEFT + (index as f64 + 0.5) * step,
+                bottom - value.clamp(0.0, scale) / scale * CHART_HEIGHT,
+            )
+        };
+        let points: Vec<(f64, f64)> = values.iter().enumerate().map(point).collect();
+        for pair in points.windows(2) {
+            page.line(pair[0], pair[1], 1.2, *color);
+        }
+        for (x, y) in &points {
+            page.dot(*x, *y, 1.5, *color);
+        }
+        page.line((legend, top - 4.0), (legend + 10.0, top - 4.0), 1.5, *color);
+        page.text(legend + 13.0, top - 2.0, 7.0, false, label);
+        legend += 70.0;
+    }
+}
+
+/// Each site day's hazards on a line, in table order.
+fn hazards(page: &mut Page, rows: &[Row], top: f64) {
+    page.text(MARGIN, top, 12.0, true, "Hazards");
+    let mut lines: Vec<String> = rows
+        .iter()
+        .take(MAX_SITES)
+        .filter(|row| row.hazards() > 0)
+        .flat_map(|row| row.days.iter().flatten())
+        .filter(|d| !d.day.hazards.is_empty())
+        .map(|d| {
+            let hazards: Vec<&str> = d.day.hazards.iter().map(|h| h.label()).collect();
+            format!(
+                "{}  {}: {}",
+                d.day.date.format("%a"),
+                truncate(&d.site.name, 40),
+                hazards.join(", ")
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push("None forecast at these sites.".to_string());
+    } else if lines.len() > MAX_HAZARD_LINES {
+        let more = lines.len() - MAX_HAZARD_LINES + 1;
+        lines.truncate(MAX_HAZARD_LINES - 1);
+        lines.push(format!("and {more} more"));
+    }
+    for (index, line) in lines.iter().enumerate() {
+        page.text(MARGIN, top + 16.0 + index as f64 * 12.0, 9.0, false, line);
+    }
+}
+
+/// At most `max` characters, since Helvetica widths aren't known here.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max - 3).collect();
+    short.push_str("...");
+    short
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        adapters::activities::paragliding::site_evaluator::{
+            DailySummary, FlyableRange, Hazard, HourlyScore,
+        },
+        domain::{
+            error::ErrorCode,
+            paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType},
+            weather::WeatherData,
+        },
+    };
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    pub(crate) fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, day).unwrap()
+    }
+
+    #[rstest]
+    #[case(10, vec![13, 14])]
+    #[case(13, vec![13, 14])]
+    #[case(14, vec![14])]
+    fn weekend_is_the_coming_saturday_and_sunday(#[case] today: u32, #[case] days: Vec<u32>) {
+        let expected: Vec<NaiveDate> = days.into_iter().map(date).collect();
+        assert_eq!(weekend(date(today)), expected);
+    }
+
+    pub(crate) fn site_day(
+        name: &str,
+        day: u32,
+        flyable_hours: u32,
+        hazards: Vec<Hazard>,
+    ) -> SiteDay {
+        let at = |hour| Utc.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap();
+        let hours: Vec<WeatherData> = (9..18)
+            .map(|hour| WeatherData {
+                timestamp: at(hour),
+                wind_direction: 225,
+                wind_speed_ms: 3.0,
+                wind_gust_ms: 6.0,
+                cloud_cover: 40,
+                ..Default::default()
+            })
+            .collect();
+        let end = 10 + flyable_hours;
+        SiteDay {
+            site: ParaglidingSite {
+                name: name.into(),
+                launches: vec![ParaglidingLaunch {
+                    site_type: SiteType::Hang,
+                    location: Location::new(47.5, 11.1, name.into(), "DE".into()),
+                    direction_degrees_start: 180.0,
+                    direction_degrees_stop: 270.0,
+                    elevation: 1750.0,
+                }],
+                country: Some("DE".into()),
+                data_source: "test".into(),
+                ..Default::default()
+            },
+            day: DailySummary {
+                date: date(day),
+                hourly_scores: (9..18)
+                    .map(|hour| HourlyScore {
+                        timestamp: at(hour),
+                        is_flyable: (10..end).contains(&hour),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ranges: vec![FlyableRange {
+                    start: at(10),
+                    end: at(end - 1),
+                }],
+                total_flyable_hours: flyable_hours as usize,
+                hazards,
+                ..Default::default()
+            },
+            hours,
+            flyable: flyable_hours >= 2,
+        }
+    }
+
+    #[test]
+    fn rows_put_the_most_flyable_site_first() {
+        let sites = vec![
+            site_day("Short", 13, 2, vec![]),
+            site_day("Long", 13, 3, vec![]),
+            site_day("Short", 14, 4, vec![]),
+            site_day("Long", 14, 1, vec![]),
+        ];
+        let rows = rows(&[date(13), date(14)], &sites);
+        let names: Vec<&str> = rows.iter().map(|r| r.site.site.name.as_str()).collect();
+        assert_eq!(names, ["Short", "Long"]);
+        assert_eq!(rows[1].flyable_hours(), 3);
+        assert_eq!(
+            top_pick(&sites).map(|d| (d.site.name.as_str(), d.day.date)),
+            Some(("Short", date(14)))
+        );
+    }
+
+    #[test]
+    fn briefing_shows_table_top_pick_and_hazards() {
+        let sites = vec![
+            site_day("Wank", 13, 6, vec![Hazard::Gusts, Hazard::Thunderstorm]),
+            site_day("Wank", 14, 0, vec![]),
+        ];
+        let home = Location::new(47.6, 11.0, "Garmisch".into(), "DE".into());
+        let errors = [SiteError {
+            site: "Brauneck".into(),
+            code: ErrorCode::WeatherUnavailable,
+            error: "Open-Meteo is down".into(),
+        }];
+        let pdf = render(&home, &[date(13), date(14)], &sites, &errors);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        let text: String = pdf.iter().map(|&b| b as char).collect();
+        for expected in [
+            "(Weekend briefing Sat 13 Jun - Sun 14 Jun 2026) Tj",
+            "(6h excellent) Tj",
+            "(Window 10-16 UTC) Tj",
+            "(Sat  Wank: gusts, thunderstorm) Tj",
+            "(wind km/h) Tj",
+            "(Couldn't be scored: Brauneck) Tj",
+        ] {
+            assert!(text.contains(expected), "missing {expected}");
+        }
+    }
+}
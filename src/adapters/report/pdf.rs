@@ -0,0 +1,180 @@
+//! Just enough PDF for a one-page report: text in Helvetica, filled and
+//! stroked rectangles, lines and dots on an A4 page, uncompressed. Coordinates
+//! are in points from the top left, unlike PDF's bottom left.
+
+use std::fmt::Write;
+
+/// A4 in points
+pub const WIDTH: f64 = 595.0;
+pub const HEIGHT: f64 = 842.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub f64, pub f64, pub f64);
+
+pub const BLACK: Rgb = Rgb(0.0, 0.0, 0.0);
+pub const GREY: Rgb = Rgb(0.6, 0.6, 0.6);
+pub const LIGHT_GREY: Rgb = Rgb(0.92, 0.92, 0.92);
+pub const GREEN: Rgb = Rgb(0.2, 0.65, 0.25);
+pub const RED: Rgb = Rgb(0.8, 0.2, 0.15);
+pub const BLUE: Rgb = Rgb(0.15, 0.35, 0.75);
+
+/// Bézier control point distance for a quarter circle of radius 1
+const KAPPA: f64 = 0.5523;
+
+#[derive(Debug, Default)]
+pub struct Page {
+    content: String,
+}
+
+impl Page {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text with its baseline at `y`.
+    pub fn text(&mut self, x: f64, y: f64, size: f64, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        let _ = writeln!(
+            self.content,
+            "0 g BT /{font} {size:.1} Tf {x:.2} {:.2} Td ({}) Tj ET",
+            HEIGHT - y,
+            escape(text)
+        );
+    }
+
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Rgb) {
+        let _ = writeln!(
+            self.content,
+            "{} rg {x:.2} {:.2} {width:.2} {height:.2} re f",
+            fill(color),
+            HEIGHT - y - height
+        );
+    }
+
+    pub fn stroke_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Rgb) {
+        let _ = writeln!(
+            self.content,
+            "{} RG 0.5 w {x:.2} {:.2} {width:.2} {height:.2} re S",
+            fill(color),
+            HEIGHT - y - height
+        );
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, color: Rgb) {
+        let _ = writeln!(
+            self.content,
+            "{} RG {width:.2} w {:.2} {:.2} m {:.2} {:.2} l S",
+            fill(color),
+            from.0,
+            HEIGHT - from.1,
+            to.0,
+            HEIGHT - to.1
+        );
+    }
+
+    pub fn dot(&mut self, x: f64, y: f64, radius: f64, color: Rgb) {
+        let (y, r, k) = (HEIGHT - y, radius, radius * KAPPA);
+        // Four quarter circles counterclockwise from the right, as control
+        // point, control point, end point.
+        let quarters = [
+            [(x + r, y + k), (x + k, y + r), (x, y + r)],
+            [(x - k, y + r), (x - r, y + k), (x - r, y)],
+            [(x - r, y - k), (x - k, y - r), (x, y - r)],
+            [(x + k, y - r), (x + r, y - k), (x + r, y)],
+        ];
+        let _ = write!(self.content, "{} rg {:.2} {y:.2} m", fill(color), x + r);
+        for points in quarters {
+            for (px, py) in points {
+                let _ = write!(self.content, " {px:.2} {py:.2}");
+            }
+            self.content.push_str(" c");
+        }
+        self.content.push_str(" f\n");
+    }
+
+    /// The page as a complete PDF file.
+    pub fn into_pdf(self) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {WIDTH} {HEIGHT}] /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>"
+            ),
+            font("Helvetica"),
+            font("Helvetica-Bold"),
+        ];
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n{object}\nendobj\n", index + 1).as_bytes());
+        }
+        // Text is already Latin-1 bytes, one per char, see `escape`.
+        let stream: Vec<u8> = self.content.chars().map(|c| c as u8).collect();
+        offsets.push(out.len());
+        out.extend(format!("6 0 obj\n<< /Length {} >>\nstream\n", stream.len()).as_bytes());
+        out.extend(&stream);
+        out.extend(b"\nendstream\nendobj\n");
+
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 2
+        );
+        out.extend(trailer.as_bytes());
+        out
+    }
+}
+
+fn font(name: &str) -> String {
+    format!("<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>")
+}
+
+fn fill(Rgb(r, g, b): Rgb) -> String {
+    format!("{r:.3} {g:.3} {b:.3}")
+}
+
+/// A PDF string body: delimiters escaped, and anything outside Latin-1, which
+/// the standard fonts can't show, replaced by `?`.
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            '–' | '—' => "-".to_string(),
+            c if (c as u32) < 0x20 || (c as u32) > 0xff => "?".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xref_points_at_each_object() {
+        let mut page = Page::new();
+        page.text(40.0, 60.0, 12.0, true, "Grüße (Wank) \\ 5 km/h – ✓");
+        page.dot(100.0, 100.0, 3.0, GREEN);
+        let pdf = page.into_pdf();
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        let latin1: String = pdf.iter().map(|&b| b as char).collect();
+        assert!(latin1.contains("(Grüße \\(Wank\\) \\\\ 5 km/h - ?) Tj"));
+
+        let xref = latin1.find("\nxref\n").unwrap() + 1;
+        let entries = latin1[xref..].lines().skip(3).take(6);
+        for (index, entry) in entries.enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            let object = format!("{} 0 obj", index + 1);
+            assert_eq!(&pdf[offset..offset + object.len()], object.as_bytes());
+        }
+        let startxref: usize = latin1.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert_eq!(&pdf[startxref..startxref + 4], b"xref");
+    }
+}
@@ -0,0 +1,117 @@
+//! Request coalescing: concurrent calls with the same key share one upstream
+//! request instead of each firing their own.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Result, anyhow};
+use futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
+
+type Flight<T> = Shared<BoxFuture<'static, Result<T, Arc<anyhow::Error>>>>;
+
+pub struct SingleFlight<T> {
+    in_flight: Mutex<HashMap<String, Flight<T>>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Runs `fetch`, unless a call with the same `key` is already in flight, in
+    /// which case its result is shared.
+    pub async fn run<F>(&self, key: &str, fetch: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let flight = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(flight) => {
+                    tracing::debug!(key, "Joining in-flight request");
+                    flight.clone()
+                }
+                None => {
+                    let flight = fetch
+                        .map(|result| result.map_err(Arc::new))
+                        .boxed()
+                        .shared();
+                    in_flight.insert(key.to_string(), flight.clone());
+                    flight
+                }
+            }
+        };
+
+        let result = flight.clone().await;
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(key).is_some_and(|f| f.ptr_eq(&flight)) {
+            in_flight.remove(key);
+        }
+        result.map_err(|e| anyhow!("{:#}", e))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_fetch() {
+        let flights = SingleFlight::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetch = || {
+            let calls = calls.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(42)
+            }
+        };
+
+        let results =
+            futures::future::join_all((0..20).map(|_| flights.run("cell", fetch()))).await;
+
+        assert!(results.iter().all(|r| *r.as_ref().unwrap() == 42));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn finished_flights_are_not_reused() {
+        let flights = SingleFlight::new();
+        assert_eq!(flights.run("cell", async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(flights.run("cell", async { Ok(2) }).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn errors_reach_every_caller() {
+        let flights: SingleFlight<u32> = SingleFlight::new();
+        let failing = || async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err(anyhow!("upstream down"))
+        };
+        let (a, b) = tokio::join!(
+            flights.run("cell", failing()),
+            flights.run("cell", failing())
+        );
+        assert!(a.unwrap_err().to_string().contains("upstream down"));
+        assert!(b.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! `sd_notify` for `Type=notify` units: tells systemd when the service is
+//! ready and when it starts stopping. Does nothing outside systemd.
+
+use std::{env, ffi::OsStr, io};
+
+/// Sends `state`, e.g. `READY=1`, to the socket in `NOTIFY_SOCKET`.
+pub fn notify(state: &str) {
+    let Some(socket) = env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    if let Err(e) = send(&socket, state) {
+        tracing::warn!(error = ?e, state, "Failed to notify systemd");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send(socket: &OsStr, state: &str) -> io::Result<()> {
+    use std::os::{
+        linux::net::SocketAddrExt,
+        unix::{
+            ffi::OsStrExt,
+            net::{SocketAddr, UnixDatagram},
+        },
+    };
+
+    let sender = UnixDatagram::unbound()?;
+    // A leading `@` names a socket in the abstract namespace
+    match socket.as_bytes().strip_prefix(b"@") {
+        Some(name) => sender.send_to_addr(state.as_bytes(), &SocketAddr::from_abstract_name(name)?),
+        None => sender.send_to(state.as_bytes(), socket),
+    }?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send(_socket: &OsStr, _state: &str) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixDatagram;
+
+    #[test]
+    fn sends_state_to_the_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let systemd = UnixDatagram::bind(&path).unwrap();
+
+        send(path.as_os_str(), "READY=1").unwrap();
+
+        let mut buf = [0u8; 16];
+        let len = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+    }
+}
@@ -0,0 +1,203 @@
+//! Checks request parameters before they reach a weather or geocoding API, and
+//! answers bad ones with 422 and what is wrong with each field.
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use chrono::{Days, NaiveDate};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    adapters::api_error::{ApiError, FieldError},
+    app_state::AppState,
+    config::ApiLimitsConfig,
+    domain::{
+        error::ErrorCode,
+        location::{is_valid_latitude, is_valid_longitude},
+    },
+};
+
+/// Open-Meteo forecasts reach this many days ahead
+pub const MAX_FORECAST_DAYS: u64 = 16;
+
+pub trait Validate {
+    fn validate(&self, _validator: &mut Validator) {}
+}
+
+/// Query parameters that were deserialized and passed [`Validate`].
+pub struct ValidQuery<T>(pub T);
+
+impl<T> FromRequestParts<AppState> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let Query(query) =
+            Query::<T>::from_request_parts(parts, state)
+                .await
+                .map_err(|rejection| {
+                    ApiError::new(ErrorCode::ValidationFailed, rejection.body_text())
+                })?;
+        validate(&query, state.api_limits)?;
+        Ok(Self(query))
+    }
+}
+
+/// Validates a request body, for handlers taking JSON.
+pub fn validate<T: Validate>(value: &T, limits: ApiLimitsConfig) -> Result<(), ApiError> {
+    let mut validator = Validator::new(limits);
+    value.validate(&mut validator);
+    validator.finish()
+}
+
+pub struct Validator {
+    limits: ApiLimitsConfig,
+    errors: Vec<FieldError>,
+    only_coordinates: bool,
+}
+
+impl Validator {
+    pub fn new(limits: ApiLimitsConfig) -> Self {
+        Self {
+            limits,
+            errors: Vec::new(),
+            only_coordinates: true,
+        }
+    }
+
+    pub fn check(&mut self, field: &str, valid: bool, message: impl Into<String>) {
+        if !valid {
+            self.only_coordinates = false;
+            self.fail(field, message.into());
+        }
+    }
+
+    pub fn latitude(&mut self, field: &str, latitude: f64) {
+        if !is_valid_latitude(latitude) {
+            self.fail(field, "must be between -90 and 90".into());
+        }
+    }
+
+    pub fn longitude(&mut self, field: &str, longitude: f64) {
+        if !is_valid_longitude(longitude) {
+            self.fail(field, "must be between -180 and 180".into());
+        }
+    }
+
+    /// A `[latitude, longitude]` pair, e.g. one corner of a polygon.
+    pub fn point(&mut self, field: &str, (latitude, longitude): (f64, f64)) {
+        if !is_valid_latitude(latitude) || !is_valid_longitude(longitude) {
+            self.fail(
+                field,
+                "must be [latitude, longitude] within ±90° and ±180°".into(),
+            );
+        }
+    }
+
+    /// Every point of a list, reported as `field[index]`.
+    pub fn points(&mut self, field: &str, points: &[(f64, f64)]) {
+        for (index, point) in points.iter().enumerate() {
+            self.point(&format!("{field}[{index}]"), *point);
+        }
+    }
+
+    pub fn radius(&mut self, field: &str, radius_km: f64) {
+        let max = self.limits.max_radius_km;
+        self.check(
+            field,
+            (0.0..=max).contains(&radius_km),
+            format!("must be between 0 and {max} km"),
+        );
+    }
+
+    /// A day the forecast reaches, from `today` up to [`MAX_FORECAST_DAYS`] ahead.
+    pub fn forecast_date(&mut self, field: &str, date: NaiveDate, today: NaiveDate) {
+        let last = today + Days::new(MAX_FORECAST_DAYS);
+        self.check(
+            field,
+            (today..=last).contains(&date),
+            format!("must be between {today} and {last}"),
+        );
+    }
+
+    pub fn finish(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let (code, message) = if self.only_coordinates {
+            (ErrorCode::InvalidCoordinates, "Invalid coordinates")
+        } else {
+            (ErrorCode::ValidationFailed, "Invalid request parameters")
+        };
+        let mut error = ApiError::new(code, message);
+        error.fields = self.errors;
+        Err(error)
+    }
+
+    fn fail(&mut self, field: &str, message: String) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator() -> Validator {
+        Validator::new(ApiLimitsConfig {
+            max_radius_km: 200.0,
+        })
+    }
+
+    #[test]
+    fn valid_parameters_pass() {
+        let mut v = validator();
+        v.latitude("latitude", -90.0);
+        v.longitude("longitude", 180.0);
+        v.radius("radius_km", 200.0);
+        assert!(v.finish().is_ok());
+    }
+
+    #[test]
+    fn bad_coordinates_are_reported_per_field() {
+        let mut v = validator();
+        v.latitude("latitude", 91.0);
+        v.longitude("longitude", 10.0);
+        v.point("points[1]", (45.0, -181.0));
+        let error = v.finish().unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::InvalidCoordinates);
+        let fields: Vec<&str> = error.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, vec!["latitude", "points[1]"]);
+    }
+
+    #[test]
+    fn radius_above_the_configured_maximum_fails_validation() {
+        let mut v = validator();
+        v.latitude("latitude", 95.0);
+        v.radius("radius_km", 201.0);
+        let error = v.finish().unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::ValidationFailed);
+        assert_eq!(error.fields[1].message, "must be between 0 and 200 km");
+    }
+
+    #[test]
+    fn forecast_date_must_be_within_the_forecast_range() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        let mut v = validator();
+        v.forecast_date("date", NaiveDate::from_ymd_opt(2026, 7, 17).unwrap(), today);
+        assert!(v.finish().is_ok());
+
+        let mut v = validator();
+        v.forecast_date("date", NaiveDate::from_ymd_opt(2026, 7, 18).unwrap(), today);
+        v.forecast_date("date", NaiveDate::from_ymd_opt(2026, 6, 30).unwrap(), today);
+        assert_eq!(v.finish().unwrap_err().fields.len(), 2);
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+
+use crate::domain::{
+    location::Location,
+    ports::WeatherProvider,
+    weather::{WeatherForecast, WeatherModel},
+};
+
+/// Dispatches each request to the provider offering the requested model. Requests
+/// without a model, or for a model nobody offers, go to the first provider.
+pub struct WeatherProviders {
+    providers: Vec<Arc<dyn WeatherProvider>>,
+}
+
+impl WeatherProviders {
+    pub fn new(providers: Vec<Arc<dyn WeatherProvider>>) -> Self {
+        Self { providers }
+    }
+
+    fn provider_for(&self, model: Option<&str>) -> Option<&Arc<dyn WeatherProvider>> {
+        model
+            .and_then(|model| {
+                self.providers
+                    .iter()
+                    .find(|p| p.available_models().iter().any(|m| m.id == model))
+            })
+            .or(self.providers.first())
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for WeatherProviders {
+    async fn get_forecast(
+        &self,
+        source: Location,
+        model: Option<String>,
+    ) -> Result<WeatherForecast> {
+        self.provider_for(model.as_deref())
+            .ok_or(anyhow!("No weather provider configured"))?
+            .get_forecast(source, model)
+            .await
+    }
+
+    fn available_models(&self) -> Vec<WeatherModel> {
+        self.providers
+            .iter()
+            .flat_map(|p| p.available_models())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::MockWeatherProvider;
+
+    fn provider(model_id: &'static str, grid_elevation: f64) -> Arc<dyn WeatherProvider> {
+        let mut mock = MockWeatherProvider::new();
+        mock.expect_available_models().returning(move || {
+            vec![WeatherModel {
+                id: model_id.to_string(),
+                name: model_id.to_string(),
+            }]
+        });
+        mock.expect_get_forecast().returning(move |location, _| {
+            Ok(WeatherForecast {
+                location,
+                grid_elevation: Some(grid_elevation),
+                fetched_at: None,
+                provider: None,
+                forecast: vec![],
+            })
+        });
+        Arc::new(mock)
+    }
+
+    fn loc() -> Location {
+        Location::new(50.0, 13.0, "Test".into(), "DE".into())
+    }
+
+    #[tokio::test]
+    async fn routes_by_model_and_falls_back_to_first_provider() {
+        let providers = WeatherProviders::new(vec![provider("icon", 1.0), provider("mosmix", 2.0)]);
+
+        let routed = providers
+            .get_forecast(loc(), Some("mosmix".into()))
+            .await
+            .unwrap();
+        assert_eq!(routed.grid_elevation, Some(2.0));
+
+        let default = providers.get_forecast(loc(), None).await.unwrap();
+        assert_eq!(default.grid_elevation, Some(1.0));
+
+        let unknown = providers
+            .get_forecast(loc(), Some("nope".into()))
+            .await
+            .unwrap();
+        assert_eq!(unknown.grid_elevation, Some(1.0));
+    }
+
+    #[test]
+    fn available_models_lists_every_provider() {
+        let providers = WeatherProviders::new(vec![provider("icon", 1.0), provider("mosmix", 2.0)]);
+        let ids: Vec<String> = providers
+            .available_models()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(ids, vec!["icon", "mosmix"]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Exports a site for XCTrack and flyXC: a task (`.xctsk`) from the launch to
+//! its landing field, and its launches and landings as a `$FormatGEO`
+//! waypoint file (`.wpt`), which both import.
+
+use std::fmt::Write;
+
+use serde_json::{Value, json};
+
+use crate::domain::{location::Location, paragliding::ParaglidingSite};
+
+/// Cylinder radius around the launch, as most competitions use
+const TAKEOFF_RADIUS_M: u32 = 400;
+/// Small enough to end the task on the landing field itself
+const LANDING_RADIUS_M: u32 = 200;
+
+/// XCTrack task format version 1, taking off at the first launch and ending
+/// at the first landing. Without a landing it is just the takeoff.
+pub fn task(site: &ParaglidingSite) -> Value {
+    let mut turnpoints = Vec::new();
+    if let Some(launch) = site.launches.first() {
+        turnpoints.push(json!({
+            "type": "TAKEOFF",
+            "radius": TAKEOFF_RADIUS_M,
+            "waypoint": waypoint(&site.name, &launch.location, launch.elevation),
+        }));
+    }
+    if let Some(landing) = site.landings.first() {
+        turnpoints.push(json!({
+            "radius": LANDING_RADIUS_M,
+            "waypoint": waypoint(&format!("{} LZ", site.name), &landing.location, landing.elevation),
+        }));
+    }
+    let mut task = json!({
+        "taskType": "CLASSIC",
+        "version": 1,
+        "earthModel": "WGS84",
+        "turnpoints": turnpoints,
+    });
+    if !site.landings.is_empty() {
+        task["goal"] = json!({ "type": "CYLINDER" });
+    }
+    task
+}
+
+fn waypoint(name: &str, location: &Location, elevation: f64) -> Value {
+    json!({
+        "name": name,
+        "lat": location.latitude,
+        "lon": location.longitude,
+        "altSmoothed": elevation.round() as i64,
+    })
+}
+
+/// Every launch and landing of `site` in `$FormatGEO`, with short codes like
+/// `L01` and `LZ01` as names and the site in the description.
+pub fn waypoints(site: &ParaglidingSite) -> String {
+    let mut out = String::from("$FormatGEO\r\n");
+    for (index, launch) in site.launches.iter().enumerate() {
+        push_geo(
+            &mut out,
+            &format!("L{:02}", index + 1),
+            &launch.location,
+            launch.elevation,
+            &site.name,
+        );
+    }
+    for (index, landing) in site.landings.iter().enumerate() {
+        push_geo(
+            &mut out,
+            &format!("LZ{:02}", index + 1),
+            &landing.location,
+            landing.elevation,
+            &format!("{} landing", site.name),
+        );
+    }
+    out
+}
+
+fn push_geo(out: &mut String, code: &str, location: &Location, elevation: f64, description: &str) {
+    let _ = write!(
+        out,
+        "{code:<8}{}    {}  {:>5.0}  {}\r\n",
+        dms(location.latitude, 'N', 'S', 2),
+        dms(location.longitude, 'E', 'W', 3),
+        elevation,
+        // Lines are the only separator, so a description can't span several.
+        description.replace(['\r', '\n'], " "),
+    );
+}
+
+/// E.g. `N 47 30 00.00`, with degrees padded to `width` digits.
+fn dms(degrees: f64, positive: char, negative: char, width: usize) -> String {
+    let hemisphere = if degrees < 0.0 { negative } else { positive };
+    // Round once, in hundredths of a second, so 59.999" carries into the minute.
+    let hundredths = (degrees.abs() * 360_000.0).round() as u64;
+    let (whole, rest) = (hundredths / 360_000, hundredths % 360_000);
+    let (minutes, seconds) = (rest / 6_000, rest % 6_000);
+    format!(
+        "{hemisphere} {whole:0width$} {minutes:02} {:02}.{:02}",
+        seconds / 100,
+        seconds % 100
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::paragliding::{ParaglidingLanding, ParaglidingLaunch, SiteType};
+
+    fn site() -> ParaglidingSite {
+        ParaglidingSite {
+            name: "Wank".into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(47.5, 11.125, "Wank".into(), "DE".into()),
+                direction_degrees_start: 180.0,
+                direction_degrees_stop: 270.0,
+                elevation: 1750.4,
+            }],
+            landings: vec![ParaglidingLanding {
+                location: Location::new(47.4925, -0.5, "LZ".into(), "DE".into()),
+                elevation: 720.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn task_flies_from_takeoff_to_the_landing() {
+        let task = task(&site());
+        assert_eq!(task["taskType"], "CLASSIC");
+        assert_eq!(task["turnpoints"][0]["type"], "TAKEOFF");
+        assert_eq!(task["turnpoints"][0]["waypoint"]["altSmoothed"], 1750);
+        assert_eq!(task["turnpoints"][1]["waypoint"]["name"], "Wank LZ");
+        assert_eq!(task["goal"]["type"], "CYLINDER");
+
+        let mut no_landing = site();
+        no_landing.landings.clear();
+        let task = super::task(&no_landing);
+        assert_eq!(task["turnpoints"].as_array().unwrap().len(), 1);
+        assert!(task.get("goal").is_none());
+    }
+
+    #[test]
+    fn waypoints_are_in_geo_format() {
+        let wpt = waypoints(&site());
+        let lines: Vec<&str> = wpt.lines().collect();
+        assert_eq!(lines[0], "$FormatGEO");
+        assert_eq!(
+            lines[1],
+            "L01     N 47 30 00.00    E 011 07 30.00   1750  Wank"
+        );
+        assert_eq!(
+            lines[2],
+            "LZ01    N 47 29 33.00    W 000 30 00.00    720  Wank landing"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::Result;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
@@ -7,30 +7,68 @@ use reqwest_tracing::TracingMiddleware;
 
 use crate::{
     adapters::{
-        activities::paragliding::{
-            repository::ParaglidingSiteRepository, source::ParaglidingActivitySource,
+        activities::{
+            paragliding::{
+                dhv::DhvParaglidingSiteProvider, evaluation_cache::EvaluationCache,
+                history::ForecastHistory, paragliding_earth::ParaglidingEarthClient,
+                provider_chain::SiteProviderChain, repository::ParaglidingSiteRepository,
+                source::ParaglidingActivitySource,
+            },
+            plan_b::PlanBActivitySource,
         },
         cache::PersistentCache,
-        google_calendar::WebFlowAuthenticator,
+        dwd_mosmix::DwdMosmixClient,
+        google_calendar::{ServiceAccount, WebFlowAuthenticator},
         graphhopper::Routing,
-        open_meteo::OpenMeteoClient,
+        matrix::MatrixNotifier,
+        nominatim::NominatimClient,
+        open_meteo::{OpenMeteoClient, OpenMeteoEnsembleClient},
         store::PersistentStore,
+        weather_providers::WeatherProviders,
+    },
+    application::{Planner, scheduler::Scheduler},
+    config::{
+        self, ApiLimitsConfig, EnsembleConfig, ForecastConfig, GoogleAuthConfig, MatrixConfig,
+        MqttConfig, SchedulerConfig, SettingsOverrides, WindowConfig,
+    },
+    domain::ports::{
+        ActivitySource, EnsembleProvider, GeoProvider, Notifier, ReverseGeoProvider,
+        RoutingProvider, SiteSearchProvider, SoundingProvider, WeatherHistoryProvider,
+        WeatherProvider,
     },
-    application::Planner,
-    domain::ports::{ActivitySource, GeoProvider, RoutingProvider, WeatherProvider},
 };
 
+#[cfg(feature = "discord")]
+use crate::adapters::discord::DiscordNotifier;
+
 #[derive(Clone)]
 pub struct AppState {
     pub cache: Arc<PersistentCache>,
     pub store: Arc<PersistentStore>,
     pub http: ClientWithMiddleware,
     pub site_repo: Arc<ParaglidingSiteRepository>,
+    pub forecast_history: Arc<ForecastHistory>,
+    pub paragliding_earth: Arc<ParaglidingEarthClient>,
+    /// DHV, if `SITE_IMPORT_DIR` is set, then Paragliding Earth
+    pub site_providers: Arc<SiteProviderChain>,
     pub auth: Arc<WebFlowAuthenticator>,
+    /// Signs in to Google instead of `auth` if `GOOGLE_AUTH=service_account`
+    pub service_account: Option<Arc<ServiceAccount>>,
     pub routing: Arc<dyn RoutingProvider>,
     pub weather: Arc<dyn WeatherProvider>,
     pub geo: Arc<dyn GeoProvider>,
+    pub sounding: Arc<dyn SoundingProvider>,
+    pub weather_history: Arc<dyn WeatherHistoryProvider>,
+    /// Ensemble forecasts, if `ENSEMBLE_MODEL` is set
+    pub ensemble: Option<Arc<dyn EnsembleProvider>>,
+    pub reverse_geo: Arc<dyn ReverseGeoProvider>,
     pub planner: Arc<Planner>,
+    /// Site evaluations of the current forecast runs
+    pub evaluations: Arc<EvaluationCache>,
+    /// Chats the daily outlook is posted to
+    pub notifiers: Vec<Arc<dyn Notifier>>,
+    pub scheduler: Arc<Scheduler>,
+    pub api_limits: ApiLimitsConfig,
 }
 
 impl AppState {
@@ -43,9 +81,25 @@ impl AppState {
 
         let http = build_http_client();
 
-        let client_id = env::var("GOOGLE_CLIENT_ID").expect("Missing GOOGLE_CLIENT_ID");
-        let client_secret = env::var("GOOGLE_CLIENT_SECRET").expect("Missing GOOGLE_CLIENT_SECRET");
-        let redirect_uri = env::var("OAUTH_REDIRECT_URL").unwrap_or_else(|_| {
+        let service_account = match GoogleAuthConfig::load()? {
+            GoogleAuthConfig::OAuth => None,
+            GoogleAuthConfig::ServiceAccount { key_file, subject } => {
+                Some(Arc::new(ServiceAccount::new(key_file, subject)))
+            }
+        };
+        // The OAuth client is only needed when a pilot signs in
+        let (client_id, client_secret) = if service_account.is_some() {
+            (
+                config::var("GOOGLE_CLIENT_ID").unwrap_or_default(),
+                config::secret("GOOGLE_CLIENT_SECRET").unwrap_or_default(),
+            )
+        } else {
+            (
+                config::var("GOOGLE_CLIENT_ID").expect("Missing GOOGLE_CLIENT_ID"),
+                config::secret("GOOGLE_CLIENT_SECRET").expect("Missing GOOGLE_CLIENT_SECRET"),
+            )
+        };
+        let redirect_uri = config::var("OAUTH_REDIRECT_URL").unwrap_or_else(|_| {
             "https://linus-x1.bangus-firefighter.ts.net:8080/oauth/callback".to_string()
         });
         let auth = Arc::new(WebFlowAuthenticator::new(
@@ -55,35 +109,109 @@ impl AppState {
             cache.clone(),
         ));
 
-        let routing: Arc<dyn RoutingProvider> =
-            Arc::new(Routing::new(cache.clone(), http.clone()));
+        let routing: Arc<dyn RoutingProvider> = Arc::new(Routing::new(cache.clone(), http.clone()));
 
-        let open_meteo = Arc::new(OpenMeteoClient::new(cache.clone()));
-        let weather: Arc<dyn WeatherProvider> = open_meteo.clone();
+        let forecast_config = ForecastConfig::load();
+        let open_meteo = Arc::new(OpenMeteoClient::new(
+            cache.clone(),
+            http.clone(),
+            forecast_config.days,
+        ));
+        let mosmix = Arc::new(DwdMosmixClient::new(cache.clone(), http.clone()));
+        let weather: Arc<dyn WeatherProvider> =
+            Arc::new(WeatherProviders::new(vec![open_meteo.clone(), mosmix]));
+        let sounding: Arc<dyn SoundingProvider> = open_meteo.clone();
+        let weather_history: Arc<dyn WeatherHistoryProvider> = open_meteo.clone();
         let geo: Arc<dyn GeoProvider> = open_meteo;
+        let ensemble: Option<Arc<dyn EnsembleProvider>> =
+            EnsembleConfig::load().model.map(|model| {
+                Arc::new(OpenMeteoEnsembleClient::new(
+                    cache.clone(),
+                    http.clone(),
+                    model,
+                    forecast_config.days,
+                )) as Arc<dyn EnsembleProvider>
+            });
+        let reverse_geo: Arc<dyn ReverseGeoProvider> =
+            Arc::new(NominatimClient::new(cache.clone(), http.clone()));
 
-        let site_repo = Arc::new(ParaglidingSiteRepository::new(store.clone()));
+        let site_repo = Arc::new(
+            ParaglidingSiteRepository::new(store.clone())
+                .with_overrides(SettingsOverrides::load()?),
+        );
+        let forecast_history = Arc::new(ForecastHistory::new(cache.clone()));
+        let paragliding_earth = Arc::new(ParaglidingEarthClient::new(cache.clone(), http.clone()));
+        let scheduler_config = SchedulerConfig::load()?;
+        let mut providers: Vec<Arc<dyn SiteSearchProvider>> = Vec::new();
+        if let Some(dir) = &scheduler_config.site_import_dir {
+            match DhvParaglidingSiteProvider::new(dir.clone()) {
+                Ok(dhv) => providers.push(Arc::new(dhv)),
+                Err(e) => tracing::warn!(error = ?e, "Failed to load DHV sites for search"),
+            }
+        }
+        providers.push(paragliding_earth.clone());
+        let site_providers = Arc::new(SiteProviderChain::new(providers));
 
-        let paragliding_source: Arc<dyn ActivitySource> = Arc::new(
-            ParaglidingActivitySource::new(site_repo.clone(), weather.clone()),
+        let evaluations = Arc::new(EvaluationCache::new((&WindowConfig::load()).into()));
+        let mut paragliding_source = ParaglidingActivitySource::new(
+            site_repo.clone(),
+            weather.clone(),
+            forecast_history.clone(),
         );
-        let planner = Arc::new(Planner::new(vec![paragliding_source], routing.clone()));
+        if let Some(ensemble) = &ensemble {
+            paragliding_source = paragliding_source.with_ensemble(ensemble.clone());
+        }
+        paragliding_source = paragliding_source
+            .with_soundings(sounding.clone())
+            .with_evaluations(evaluations.clone());
+        let paragliding_source: Arc<dyn ActivitySource> = Arc::new(paragliding_source);
+        let plan_b_source: Arc<dyn ActivitySource> =
+            Arc::new(PlanBActivitySource::new(weather.clone()));
+        let planner = Arc::new(Planner::new(
+            vec![paragliding_source, plan_b_source],
+            routing.clone(),
+        ));
+
+        let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+        #[cfg(feature = "discord")]
+        if let Some(config) = config::DiscordConfig::load()?
+            && let Some(channel_id) = config.channel_id
+        {
+            notifiers.push(Arc::new(DiscordNotifier::new(&config, channel_id)));
+        }
+        if let Some(config) = MatrixConfig::load()? {
+            notifiers.push(Arc::new(MatrixNotifier::new(http.clone(), config)));
+        }
+
+        let scheduler = Arc::new(Scheduler::new(scheduler_config, MqttConfig::load()));
 
         Ok(Self {
             cache,
             store,
             http,
             site_repo,
+            forecast_history,
+            paragliding_earth,
+            site_providers,
             auth,
+            service_account,
             routing,
             weather,
             geo,
+            sounding,
+            weather_history,
+            ensemble,
+            reverse_geo,
             planner,
+            evaluations,
+            notifiers,
+            scheduler,
+            api_limits: ApiLimitsConfig::load(),
         })
     }
 }
 
-fn build_http_client() -> ClientWithMiddleware {
+pub(crate) fn build_http_client() -> ClientWithMiddleware {
     let retry_policy = ExponentialBackoff::builder()
         .base(3)
         .retry_bounds(
@@ -91,7 +219,14 @@ fn build_http_client() -> ClientWithMiddleware {
             std::time::Duration::from_mins(30),
         )
         .build_with_max_retries(5);
-    ClientBuilder::new(reqwest::Client::new())
+    let builder = ClientBuilder::new(reqwest::Client::new());
+    // Outermost, so replayed responses skip tracing and retries.
+    #[cfg(feature = "record-replay")]
+    let builder = match crate::adapters::record_replay::RecordReplay::from_env() {
+        Some(record_replay) => builder.with(record_replay),
+        None => builder,
+    };
+    builder
         .with(TracingMiddleware::default())
         .with(RetryTransientMiddleware::new_with_policy(retry_policy))
         .build()
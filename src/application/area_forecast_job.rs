@@ -0,0 +1,214 @@
+//! Scores every site in an area for the coming days. With the weather not
+//! cached yet, a large area takes longer than an HTTP request may, so it is
+//! started through the scheduler and polled for, see
+//! [`Scheduler::submit_forecast`](crate::application::scheduler::Scheduler::submit_forecast).
+
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adapters::activities::paragliding::{
+        evaluation_cache::EvaluationCache, outlook, site_evaluator::DailySummary,
+    },
+    app_state::AppState,
+    domain::{
+        activities::SiteError,
+        error::TravelAiError,
+        location::Location,
+        paragliding::{ParaglidingSite, ParaglidingSiteProvider},
+        ports::WeatherProvider,
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AreaForecastRequest {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+    /// Days from today, 7 if missing
+    pub days: Option<u64>,
+}
+
+/// The sites of an area that could be scored, and why the others couldn't,
+/// so a ranking missing some sites says so.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ParaglidingForecast {
+    pub sites: Vec<SiteForecast>,
+    pub errors: Vec<SiteError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteForecast {
+    pub site: String,
+    pub distance_km: f64,
+    pub daily_summaries: Vec<DailySummary>,
+}
+
+/// How many of the area's sites have been scored so far.
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub sites_done: AtomicUsize,
+    pub sites_total: AtomicUsize,
+}
+
+const DEFAULT_DAYS: u64 = 7;
+
+/// The sites within the radius, nearest first, each with its summaries of
+/// the requested days. Fails only if no site could be scored at all.
+#[tracing::instrument(skip_all, fields(radius_km = request.radius_km, site_count = tracing::field::Empty))]
+pub async fn run(
+    state: &AppState,
+    request: &AreaForecastRequest,
+    progress: &Progress,
+) -> Result<ParaglidingForecast> {
+    let center = Location::new(
+        request.latitude,
+        request.longitude,
+        String::new(),
+        String::new(),
+    );
+    let sites = state
+        .site_repo
+        .fetch_launches_within_radius(&center, request.radius_km)
+        .await;
+    tracing::Span::current().record("site_count", sites.len());
+
+    let today = Utc::now().date_naive();
+    let dates = today..today + Days::new(request.days.unwrap_or(DEFAULT_DAYS));
+    let forecast = forecast_sites(
+        state.weather.as_ref(),
+        &state.evaluations,
+        &sites,
+        dates,
+        progress,
+    )
+    .await;
+    if forecast.sites.is_empty()
+        && let Some(first) = forecast.errors.first()
+    {
+        return Err(TravelAiError::new(
+            first.code,
+            format!(
+                "Failed to get the forecast of all {} sites",
+                forecast.errors.len()
+            ),
+        )
+        .into());
+    }
+    Ok(forecast)
+}
+
+async fn forecast_sites(
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    sites: &[(ParaglidingSite, f64)],
+    dates: Range<NaiveDate>,
+    progress: &Progress,
+) -> ParaglidingForecast {
+    progress.sites_done.store(0, Ordering::Relaxed);
+    progress.sites_total.store(sites.len(), Ordering::Relaxed);
+
+    let mut forecast = ParaglidingForecast::default();
+    for (site, distance_km) in sites {
+        let Some(launch) = site.launches.first() else {
+            progress.sites_done.fetch_add(1, Ordering::Relaxed);
+            continue;
+        };
+        match outlook::site_forecast(weather, site, launch).await {
+            Ok(weather) => {
+                let eval = evaluations.evaluate(site, &weather);
+                forecast.sites.push(SiteForecast {
+                    site: site.name.clone(),
+                    distance_km: *distance_km,
+                    daily_summaries: eval
+                        .daily_summaries
+                        .iter()
+                        .filter(|d| dates.contains(&d.date))
+                        .cloned()
+                        .collect(),
+                });
+            }
+            Err(e) => forecast.errors.push(e),
+        }
+        progress.sites_done.fetch_add(1, Ordering::Relaxed);
+    }
+    forecast
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        error::ErrorCode,
+        paragliding::{ParaglidingLaunch, SiteType},
+        ports::MockWeatherProvider,
+        weather::WeatherForecast,
+    };
+
+    fn site(name: &str) -> (ParaglidingSite, f64) {
+        let site = ParaglidingSite {
+            name: name.into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(47.5, 11.1, name.into(), "DE".into()),
+                direction_degrees_start: 180.0,
+                direction_degrees_stop: 270.0,
+                elevation: 1750.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            ..Default::default()
+        };
+        (site, 10.0)
+    }
+
+    #[tokio::test]
+    async fn sites_without_weather_are_reported_with_their_error() {
+        let mut weather = MockWeatherProvider::new();
+        weather.expect_get_forecast().returning(|location, _| {
+            if location.name == "Wank" {
+                return Err(TravelAiError::new(
+                    ErrorCode::RateLimited,
+                    "Open-Meteo rate limit reached",
+                )
+                .into());
+            }
+            Ok(WeatherForecast {
+                location,
+                grid_elevation: None,
+                fetched_at: None,
+                provider: None,
+                forecast: vec![],
+            })
+        });
+        let today = NaiveDate::from_ymd_opt(2026, 6, 13).unwrap();
+        let progress = Progress::default();
+
+        let forecast = forecast_sites(
+            &weather,
+            &EvaluationCache::default(),
+            &[site("Hörnle"), site("Wank")],
+            today..today + Days::new(3),
+            &progress,
+        )
+        .await;
+
+        let scored: Vec<&str> = forecast.sites.iter().map(|s| s.site.as_str()).collect();
+        assert_eq!(scored, ["Hörnle"]);
+        assert_eq!(
+            forecast.errors,
+            [SiteError {
+                site: "Wank".into(),
+                code: ErrorCode::RateLimited,
+                error: "Open-Meteo rate limit reached".into(),
+            }]
+        );
+        assert_eq!(progress.sites_done.load(Ordering::Relaxed), 2);
+    }
+}
@@ -1,28 +1,176 @@
-use anyhow::Result;
-use chrono::{Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 
 use crate::{
-    adapters::google_calendar::GoogleCalendar,
+    adapters::{
+        caldav::CalDavCalendar,
+        google_calendar::{GoogleAuth, GoogleCalendar},
+        ics_calendar::IcsFileCalendar,
+        in_memory_calendar::InMemoryCalendar,
+    },
     app_state::AppState,
+    application::{
+        event_formatter::{self, SiteLinks},
+        event_recheck_job,
+        retry::Retry,
+    },
+    config::{CalendarBackend, CalendarConfig, EventGranularity},
     domain::{
-        activities::{ActivitySuggestion, PlanningContext, TimeWindow, Timing},
+        activities::{
+            ActivitySuggestion, PlanningContext, SiteError, Suggestions, TimeWindow, Timing,
+        },
         calendar::CalendarEvent,
+        error::{ErrorCode, TravelAiError},
         location::Location,
-        paragliding::UserSettings,
+        paragliding::{ParaglidingSiteProvider, UserSettings},
         ports::CalendarProvider,
     },
 };
 
+/// Writing is idempotent, so a backend that was unreachable is tried once more.
+const WRITE_ATTEMPTS: u32 = 2;
+
+/// Plans once and writes the same events to every configured calendar backend.
+/// A failing backend is logged and skipped, the run only fails if none succeeds.
+/// Busy times come from the first backend that could be opened. Returns the
+/// sites left out because they couldn't be scored.
 #[tracing::instrument(skip_all, fields(event_count = tracing::field::Empty))]
-pub async fn run(state: &AppState) -> Result<()> {
-    let settings = match state.site_repo.get_settings().await? {
+pub async fn run(state: &AppState) -> Result<Vec<SiteError>> {
+    let settings = load_settings(state).await?;
+
+    let config = CalendarConfig::load()?;
+
+    let mut calendars = Vec::new();
+    let mut last_error = None;
+    for &backend in &config.backends {
+        match open_calendar(state, &config, backend).await {
+            Ok(cal) => calendars.push((backend, cal)),
+            Err(e) => {
+                tracing::error!(error = ?e, ?backend, "Failed to open calendar");
+                last_error = Some(e);
+            }
+        }
+    }
+    let Some((_, primary)) = calendars.first_mut() else {
+        return Err(sync_failed("No calendar backend available", last_error).into());
+    };
+
+    let (events, errors) = plan_events(state, &settings, &config, primary.as_mut()).await?;
+
+    let mut written = 0;
+    for (backend, cal) in &mut calendars {
+        let mut retry = Retry::new("calendar write", WRITE_ATTEMPTS);
+        let outcome = loop {
+            match write_events(cal.as_mut(), &settings.calendar_name, &events).await {
+                Err(e) if retry.wait(&e).await => continue,
+                outcome => break outcome,
+            }
+        };
+        match outcome {
+            Ok(()) => written += 1,
+            Err(e) => {
+                tracing::error!(error = ?e, ?backend, "Failed to write calendar events");
+                last_error = Some(e);
+            }
+        }
+    }
+    if written == 0 {
+        let message = "Writing events failed for every calendar backend";
+        return Err(sync_failed(message, last_error).into());
+    }
+
+    if let Err(e) = event_recheck_job::remember(state, &settings.calendar_name, &events).await {
+        tracing::warn!(error = ?e, "Failed to keep events for the pre-start check");
+    }
+
+    tracing::Span::current().record("event_count", events.len());
+    tracing::info!(
+        event_count = events.len(),
+        backends = written,
+        calendar = %settings.calendar_name,
+        "Created events in calendar"
+    );
+
+    Ok(errors)
+}
+
+/// A failed sync, keeping the last backend's error as the cause so a more
+/// specific code such as an expired sign-in can still be found.
+fn sync_failed(message: &str, cause: Option<anyhow::Error>) -> TravelAiError {
+    let error = TravelAiError::new(ErrorCode::CalendarSyncFailed, message);
+    match cause {
+        Some(cause) => error.with_source(cause),
+        None => error,
+    }
+}
+
+/// Plans against an empty in-memory calendar and returns the events a real run
+/// would write. Google is never contacted, so busy times are not considered.
+pub async fn preview(state: &AppState) -> Result<Vec<CalendarEvent>> {
+    let settings = load_settings(state).await?;
+    let config = CalendarConfig::load()?;
+    let mut cal = InMemoryCalendar::new();
+    let (events, _) = plan_events(state, &settings, &config, &mut cal).await?;
+    Ok(events)
+}
+
+/// Like [`preview`], but logs every event that would be created.
+#[tracing::instrument(skip_all)]
+pub async fn dry_run(state: &AppState) -> Result<Vec<CalendarEvent>> {
+    let settings = load_settings(state).await?;
+    let events = preview(state).await?;
+
+    for event in &events {
+        tracing::info!(calendar = %settings.calendar_name, "Would create event: {}", event);
+    }
+    Ok(events)
+}
+
+/// Opens one calendar backend from `CALENDAR_PROVIDERS`.
+pub async fn open_calendar(
+    state: &AppState,
+    config: &CalendarConfig,
+    backend: CalendarBackend,
+) -> Result<Box<dyn CalendarProvider>> {
+    Ok(match backend {
+        CalendarBackend::Google => {
+            let auth = match &state.service_account {
+                Some(account) => GoogleAuth::service_account(account).await?,
+                None => GoogleAuth::OAuth(state.auth.clone()),
+            };
+            Box::new(GoogleCalendar::new(auth, state.cache.clone()).await?)
+        }
+        CalendarBackend::Ics => Box::new(IcsFileCalendar::new(&config.ics_dir)?),
+        CalendarBackend::CalDav => {
+            let caldav = config
+                .caldav
+                .clone()
+                .context("CALENDAR_PROVIDERS lists caldav but CALDAV_URL is not set")?;
+            Box::new(CalDavCalendar::new(state.http.clone(), caldav))
+        }
+    })
+}
+
+async fn load_settings(state: &AppState) -> Result<UserSettings> {
+    Ok(match state.site_repo.get_settings().await? {
         Some(s) => s,
         None => {
             tracing::warn!("No settings found, using defaults");
             UserSettings::default()
         }
-    };
+    })
+}
 
+/// Plans the next two weeks around the busy times in `cal` and turns the
+/// suggestions into calendar events, with the sites that couldn't be scored.
+async fn plan_events(
+    state: &AppState,
+    settings: &UserSettings,
+    config: &CalendarConfig,
+    cal: &mut dyn CalendarProvider,
+) -> Result<(Vec<CalendarEvent>, Vec<SiteError>)> {
     let home = Location::new(
         settings.location_latitude,
         settings.location_longitude,
@@ -30,14 +178,6 @@ pub async fn run(state: &AppState) -> Result<()> {
         "".to_string(),
     );
 
-    let mut cal = match GoogleCalendar::new(state.auth.clone(), state.cache.clone()).await {
-        Ok(cal) => cal,
-        Err(e) => {
-            tracing::error!(error = ?e, "Failed to create Google Calendar");
-            return Err(e);
-        }
-    };
-
     cal.create_calendar(&settings.calendar_name).await?;
 
     let mut conflict_calendars = cal.get_calendar_names().await?;
@@ -53,48 +193,507 @@ pub async fn run(state: &AppState) -> Result<()> {
         conflict_calendars,
     };
 
-    let suggestions = state.planner.plan(&ctx, &cal).await?;
+    let Suggestions {
+        mut suggestions,
+        errors,
+    } = state.planner.plan(&ctx, cal).await?;
 
-    if let Err(e) = cal.clear_calendar(&settings.calendar_name).await {
-        tracing::error!(
-            calendar = %settings.calendar_name,
-            error = ?e,
-            "Failed to clear calendar"
-        );
-        return Err(e);
+    let mut events = Vec::with_capacity(suggestions.len());
+    if config.day_summaries {
+        let (summaries, kept) = summarize_days(suggestions);
+        events.extend(summaries);
+        suggestions = kept;
+    }
+    let source_urls: HashMap<String, Option<String>> = state
+        .site_repo
+        .fetch_all_sites()
+        .await
+        .into_iter()
+        .map(|site| (site.name, site.source_url))
+        .collect();
+    for (key, s) in merge_suggestions(suggestions, config.granularity) {
+        let place = match state
+            .reverse_geo
+            .reverse_geocode(s.location.latitude, s.location.longitude)
+            .await
+        {
+            Ok(place) => Some(place.name),
+            Err(e) => {
+                tracing::warn!(error = ?e, site = %s.title, "Failed to resolve place name");
+                None
+            }
+        };
+        let links = SiteLinks {
+            source_url: source_urls.get(&s.title).cloned().flatten(),
+            forecast_url: config
+                .public_base_url
+                .as_deref()
+                .and_then(|base| event_formatter::forecast_url(base, &s.title)),
+        };
+        events.push(event_formatter::suggestion_to_event(
+            s,
+            key,
+            place,
+            config.reminder_hours_before,
+            &links,
+        ));
     }
+    Ok((events, errors))
+}
 
-    let mut event_counter = 0;
-    for s in suggestions {
-        let event = suggestion_to_event(s);
-        if let Err(e) = cal.create_event(&settings.calendar_name, event).await {
-            tracing::error!(error = ?e, "Failed to create event");
-            return Err(e);
+/// Replaces the events of `calendar` in every configured backend. Fails only
+/// if none could be written.
+pub async fn replace_calendar(
+    state: &AppState,
+    calendar: &str,
+    events: &[CalendarEvent],
+) -> Result<()> {
+    each_backend(state, calendar, Write::Replace(events)).await
+}
+
+/// Writes `changed` and deletes the events keyed `deleted` from `calendar`
+/// in every configured backend, leaving its other events alone. Fails only
+/// if none could be written.
+pub async fn update_events(
+    state: &AppState,
+    calendar: &str,
+    changed: &[CalendarEvent],
+    deleted: &[String],
+) -> Result<()> {
+    each_backend(state, calendar, Write::Update { changed, deleted }).await
+}
+
+/// What [`each_backend`] writes to a calendar.
+#[derive(Clone, Copy)]
+enum Write<'a> {
+    Replace(&'a [CalendarEvent]),
+    Update {
+        changed: &'a [CalendarEvent],
+        deleted: &'a [String],
+    },
+}
+
+/// Applies `write` to `calendar` in every configured backend. Fails only if
+/// it failed on all.
+async fn each_backend(state: &AppState, calendar: &str, write: Write<'_>) -> Result<()> {
+    let config = CalendarConfig::load()?;
+    let mut written = 0;
+    let mut last_error = None;
+    for &backend in &config.backends {
+        let outcome = match open_calendar(state, &config, backend).await {
+            Ok(mut cal) => match write {
+                Write::Replace(events) => write_events(cal.as_mut(), calendar, events).await,
+                Write::Update { changed, deleted } => {
+                    update_keyed(cal.as_mut(), calendar, changed, deleted).await
+                }
+            },
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(()) => written += 1,
+            Err(e) => {
+                tracing::error!(error = ?e, ?backend, calendar, "Failed to write calendar events");
+                last_error = Some(e);
+            }
         }
-        event_counter += 1;
     }
+    match last_error {
+        Some(e) if written == 0 => Err(e.context(format!("Failed to write calendar {calendar}"))),
+        _ => Ok(()),
+    }
+}
 
-    tracing::Span::current().record("event_count", event_counter);
-    tracing::info!(
-        event_count = event_counter,
-        calendar = %settings.calendar_name,
-        "Created events in calendar"
-    );
+/// Deletes the events keyed `deleted` and updates `changed` in place.
+async fn update_keyed(
+    cal: &mut dyn CalendarProvider,
+    calendar: &str,
+    changed: &[CalendarEvent],
+    deleted: &[String],
+) -> Result<()> {
+    cal.delete_events(calendar, deleted).await?;
+    cal.upsert_events(calendar, changed.to_vec()).await
+}
+
+/// Replaces the events in `calendar` with `events`: events whose key is
+/// already there are updated in place, the others created, and those no longer
+/// planned deleted. A calendar holding events without key is cleared once and
+/// refilled, as they can't be matched.
+#[tracing::instrument(skip(cal, events), fields(event_count = events.len()))]
+pub async fn write_events(
+    cal: &mut dyn CalendarProvider,
+    calendar: &str,
+    events: &[CalendarEvent],
+) -> Result<()> {
+    cal.create_calendar(calendar).await?;
+    let existing = cal.event_keys(calendar).await?;
+    let keyed = existing.iter().all(Option::is_some) && events.iter().all(|e| e.key.is_some());
+    if !keyed {
+        // Events without a key can't be matched, so start over once.
+        if let Err(e) = cal.clear_calendar(calendar).await {
+            tracing::error!(calendar, error = ?e, "Failed to clear calendar");
+            return Err(e);
+        }
+        if let Err(e) = cal.create_events(calendar, events.to_vec()).await {
+            tracing::error!(error = ?e, "Failed to create events");
+            return Err(e);
+        }
+        return Ok(());
+    }
 
+    let stale: Vec<String> = existing
+        .into_iter()
+        .flatten()
+        .filter(|key| !events.iter().any(|e| e.key.as_ref() == Some(key)))
+        .collect();
+    if let Err(e) = cal.delete_events(calendar, &stale).await {
+        tracing::error!(calendar, error = ?e, "Failed to delete stale events");
+        return Err(e);
+    }
+    if let Err(e) = cal.upsert_events(calendar, events.to_vec()).await {
+        tracing::error!(error = ?e, "Failed to write events");
+        return Err(e);
+    }
     Ok(())
 }
 
-fn suggestion_to_event(s: ActivitySuggestion) -> CalendarEvent {
-    let (start, end) = match s.timing {
-        Timing::Flexible { window, .. } => (window.start, window.end),
-        Timing::Fixed { start, end } => (start, end),
+/// Sites per day that still get timed events next to the all-day summary
+const TOP_SITES_PER_DAY: usize = 3;
+
+/// Groups flying suggestions by day into one all-day event per day and keeps
+/// only the best window of the top sites for timed events. Other activities
+/// are kept as they are.
+fn summarize_days(
+    suggestions: Vec<ActivitySuggestion>,
+) -> (Vec<CalendarEvent>, Vec<ActivitySuggestion>) {
+    let mut days: BTreeMap<NaiveDate, Vec<ActivitySuggestion>> = BTreeMap::new();
+    let mut kept = Vec::new();
+    for s in suggestions {
+        if !s.kind.is_plan_b() {
+            days.entry(start_of(&s.timing).date_naive())
+                .or_default()
+                .push(s);
+        } else {
+            kept.push(s);
+        }
+    }
+
+    let mut summaries = Vec::with_capacity(days.len());
+    for (date, mut day) in days {
+        // Best first, so the first window seen per site is its best one.
+        day.sort_by(|a, b| {
+            score_value(b)
+                .total_cmp(&score_value(a))
+                .then_with(|| duration_of(&b.timing).cmp(&duration_of(&a.timing)))
+        });
+        let mut sites: Vec<String> = Vec::new();
+        for s in day {
+            if sites.contains(&s.title) {
+                continue;
+            }
+            sites.push(s.title.clone());
+            if sites.len() <= TOP_SITES_PER_DAY {
+                kept.push(s);
+            }
+        }
+        summaries.push(event_formatter::day_summary_event(date, &sites));
+    }
+    (summaries, kept)
+}
+
+/// Merges flying suggestions per site and day or per day, depending on
+/// `granularity`, and pairs each result with its event key. Keys only depend
+/// on date, site and start, so the same plan always yields the same keys.
+fn merge_suggestions(
+    suggestions: Vec<ActivitySuggestion>,
+    granularity: EventGranularity,
+) -> Vec<(String, ActivitySuggestion)> {
+    let window_key = |s: &ActivitySuggestion| {
+        let start = start_of(&s.timing);
+        format!(
+            "{}/{}/{}",
+            start.date_naive(),
+            s.title,
+            start.format("%H%M")
+        )
     };
-    CalendarEvent {
-        title: s.title.clone(),
-        start_time: start,
-        end_time: end,
-        is_all_day: false,
-        location: Some(s.title),
-        body: Some(format!("Last updated (Utc): {}", Utc::now())),
+    if granularity == EventGranularity::Window {
+        return suggestions
+            .into_iter()
+            .map(|s| (window_key(&s), s))
+            .collect();
+    }
+
+    let mut groups: BTreeMap<String, Vec<ActivitySuggestion>> = BTreeMap::new();
+    let mut out = Vec::new();
+    for s in suggestions {
+        if s.kind.is_plan_b() {
+            out.push((window_key(&s), s));
+            continue;
+        }
+        let date = start_of(&s.timing).date_naive();
+        let key = match granularity {
+            EventGranularity::Site => format!("{}/{}", date, s.title),
+            _ => format!("{}/flying", date),
+        };
+        groups.entry(key).or_default().push(s);
+    }
+
+    for (key, mut group) in groups {
+        group.sort_by_key(|s| start_of(&s.timing));
+        let start = start_of(&group[0].timing);
+        let end = group
+            .iter()
+            .map(|s| end_of(&s.timing))
+            .max()
+            .unwrap_or(start);
+        let mut sites: Vec<&str> = Vec::new();
+        for s in &group {
+            if !sites.contains(&s.title.as_str()) {
+                sites.push(&s.title);
+            }
+        }
+        let title = match granularity {
+            EventGranularity::Day if sites.len() > 1 => format!("Flying: {}", sites.join(", ")),
+            _ => group[0].title.clone(),
+        };
+        let windows: Vec<String> = group
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}: {} - {} UTC",
+                    s.title,
+                    start_of(&s.timing).format("%H:%M"),
+                    end_of(&s.timing).format("%H:%M")
+                )
+            })
+            .collect();
+
+        let mut merged = group
+            .into_iter()
+            .max_by(|a, b| score_value(a).total_cmp(&score_value(b)))
+            .expect("groups are never empty");
+        merged.timing = match merged.timing {
+            Timing::Flexible { min_duration, .. } => Timing::Flexible {
+                window: TimeWindow { start, end },
+                min_duration,
+            },
+            Timing::Fixed { .. } => Timing::Fixed { start, end },
+        };
+        merged.title = title;
+        merged.description = if merged.description.is_empty() {
+            windows.join("\n")
+        } else {
+            format!("{}\n\n{}", windows.join("\n"), merged.description)
+        };
+        out.push((key, merged));
+    }
+    out
+}
+
+fn score_value(s: &ActivitySuggestion) -> f32 {
+    s.score.as_ref().map_or(1.0, |score| score.value)
+}
+
+fn start_of(timing: &Timing) -> DateTime<Utc> {
+    match timing {
+        Timing::Flexible { window, .. } => window.start,
+        Timing::Fixed { start, .. } => *start,
+    }
+}
+
+fn end_of(timing: &Timing) -> DateTime<Utc> {
+    match timing {
+        Timing::Flexible { window, .. } => window.end,
+        Timing::Fixed { end, .. } => *end,
+    }
+}
+
+fn duration_of(timing: &Timing) -> Duration {
+    match timing {
+        Timing::Flexible { window, .. } => window.duration(),
+        Timing::Fixed { start, end } => *end - *start,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::activities::{ActivityKind, Score};
+    use chrono::{TimeZone, Timelike};
+
+    fn suggestion(site: &str, start_h: u32, end_h: u32, score: Option<f32>) -> ActivitySuggestion {
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+        ActivitySuggestion {
+            kind: ActivityKind::Paragliding,
+            location: Location::new(50.7, 13.0, site.into(), "DE".into()),
+            timing: Timing::Fixed {
+                start: at(start_h),
+                end: at(end_h),
+            },
+            title: site.into(),
+            description: String::new(),
+            score: score.map(|value| Score {
+                value,
+                reasons: vec![],
+                breakdown: vec![],
+            }),
+        }
+    }
+
+    fn event(key: Option<&str>, start_h: u32) -> CalendarEvent {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, start_h, 0, 0).unwrap();
+        CalendarEvent {
+            title: "Wank".into(),
+            start_time: start,
+            end_time: start + Duration::hours(2),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: key.map(str::to_string),
+            site: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn writing_updates_events_by_key_and_drops_stale_ones() {
+        let mut cal = InMemoryCalendar::new();
+        write_events(
+            &mut cal,
+            "Flying",
+            &[event(Some("a"), 10), event(Some("b"), 14)],
+        )
+        .await
+        .unwrap();
+
+        write_events(
+            &mut cal,
+            "Flying",
+            &[event(Some("a"), 11), event(Some("c"), 16)],
+        )
+        .await
+        .unwrap();
+
+        let events = cal.events("Flying");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].key.as_deref(), Some("a"));
+        assert_eq!(events[0].start_time.hour(), 11);
+        assert_eq!(events[1].key.as_deref(), Some("c"));
+    }
+
+    #[tokio::test]
+    async fn calendars_with_keyless_events_are_refilled() {
+        let mut cal = InMemoryCalendar::new().with_event("Flying", event(None, 8));
+
+        write_events(&mut cal, "Flying", &[event(Some("a"), 10)])
+            .await
+            .unwrap();
+
+        assert_eq!(cal.event_keys("Flying").await.unwrap(), [Some("a".into())]);
+    }
+
+    #[tokio::test]
+    async fn updating_leaves_other_events_alone() {
+        let mut cal = InMemoryCalendar::new()
+            .with_event("Flying", event(None, 8))
+            .with_event("Flying", event(Some("a"), 10))
+            .with_event("Flying", event(Some("b"), 14));
+
+        update_keyed(&mut cal, "Flying", &[event(Some("a"), 11)], &["b".into()])
+            .await
+            .unwrap();
+
+        let events = cal.events("Flying");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].key, None);
+        assert_eq!(events[1].key.as_deref(), Some("a"));
+        assert_eq!(events[1].start_time.hour(), 11);
+    }
+
+    #[test]
+    fn day_summary_counts_sites_and_keeps_best_window_of_top_sites() {
+        let suggestions = vec![
+            suggestion("A", 10, 12, Some(0.9)),
+            suggestion("A", 13, 17, Some(0.9)),
+            suggestion("B", 10, 12, Some(0.4)),
+            suggestion("C", 11, 13, Some(0.7)),
+            suggestion("D", 11, 13, Some(0.6)),
+        ];
+
+        let (summaries, kept) = summarize_days(suggestions);
+
+        assert_eq!(summaries.len(), 1);
+        assert!(summaries[0].is_all_day);
+        assert_eq!(summaries[0].title, "🟢 Flyable: 4 sites");
+        let kept: Vec<(&str, DateTime<Utc>)> = kept
+            .iter()
+            .map(|s| (s.title.as_str(), start_of(&s.timing)))
+            .collect();
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+        assert_eq!(kept, vec![("A", at(13)), ("C", at(11)), ("D", at(11))]);
+    }
+
+    #[test]
+    fn plan_b_is_not_summarized() {
+        let mut hike = suggestion("Hike", 10, 14, None);
+        hike.kind = ActivityKind::Hiking;
+
+        let (summaries, kept) = summarize_days(vec![hike]);
+
+        assert!(summaries.is_empty());
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn window_granularity_keys_each_window() {
+        let merged = merge_suggestions(
+            vec![suggestion("A", 10, 12, None), suggestion("A", 14, 16, None)],
+            EventGranularity::Window,
+        );
+        let keys: Vec<&str> = merged.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["2026-06-13/A/1000", "2026-06-13/A/1400"]);
+    }
+
+    #[test]
+    fn site_granularity_spans_all_windows_of_a_site() {
+        let merged = merge_suggestions(
+            vec![
+                suggestion("A", 14, 16, Some(0.5)),
+                suggestion("B", 11, 13, None),
+                suggestion("A", 10, 12, Some(0.9)),
+            ],
+            EventGranularity::Site,
+        );
+
+        assert_eq!(merged.len(), 2);
+        let (key, a) = &merged[0];
+        assert_eq!(key, "2026-06-13/A");
+        let at = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+        assert_eq!((start_of(&a.timing), end_of(&a.timing)), (at(10), at(16)));
+        assert_eq!(a.score.as_ref().map(|s| s.value), Some(0.9));
+        assert!(
+            a.description
+                .starts_with("A: 10:00 - 12:00 UTC\nA: 14:00 - 16:00 UTC")
+        );
+        assert_eq!(merged[1].0, "2026-06-13/B");
+    }
+
+    #[test]
+    fn day_granularity_yields_one_event_per_day() {
+        let mut hike = suggestion("Hike", 9, 15, None);
+        hike.kind = ActivityKind::Hiking;
+        let merged = merge_suggestions(
+            vec![
+                suggestion("A", 10, 12, None),
+                suggestion("B", 13, 17, None),
+                hike,
+            ],
+            EventGranularity::Day,
+        );
+
+        let keys: Vec<&str> = merged.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["2026-06-13/Hike/0900", "2026-06-13/flying"]);
+        assert_eq!(merged[1].1.title, "Flying: A, B");
     }
 }
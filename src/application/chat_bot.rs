@@ -0,0 +1,263 @@
+//! What the chat bots answer and post, whichever chat they run in: the ranked
+//! outlook around a place for `!fly <place> [radius]`, and around home for the
+//! daily update.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+
+use crate::{
+    adapters::{activities::paragliding::outlook, report::markdown},
+    app_state::AppState,
+    application::retry::Retry,
+    domain::{
+        error::{ErrorCode, TravelAiError},
+        location::Location,
+        ports::Notifier,
+    },
+};
+
+/// Days an answer covers, from today
+const OUTLOOK_DAYS: u64 = 3;
+const POST_ATTEMPTS: u32 = 2;
+const USAGE: &str = "Usage: `!fly <place> [radius km]`, e.g. `!fly Garmisch 50`";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Fly {
+        place: String,
+        radius_km: Option<f64>,
+    },
+    /// `!fly` without a place
+    Usage,
+}
+
+/// The command in `message`, None if it isn't one.
+pub fn parse(message: &str) -> Option<Command> {
+    let rest = message.trim().strip_prefix("!fly")?;
+    if rest.chars().next().is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+    let mut words: Vec<&str> = rest.split_whitespace().collect();
+    let radius_km = words
+        .last()
+        .and_then(|w| w.trim_end_matches("km").parse::<f64>().ok())
+        .filter(|r| *r > 0.0);
+    if radius_km.is_some() {
+        words.pop();
+    }
+    if words.is_empty() {
+        return Some(Command::Usage);
+    }
+    Some(Command::Fly {
+        place: words.join(" "),
+        radius_km,
+    })
+}
+
+/// The answer to `command`, failures included, to post as is.
+pub async fn reply(state: &AppState, command: Command) -> String {
+    let Command::Fly { place, radius_km } = command else {
+        return USAGE.to_string();
+    };
+    match fly(state, &place, radius_km).await {
+        Ok(answer) => answer,
+        Err(e) => {
+            tracing::warn!(place, error = ?e, "Failed to answer !fly");
+            format!("Could not get the forecast around {place}, try again later")
+        }
+    }
+}
+
+async fn fly(state: &AppState, place: &str, radius_km: Option<f64>) -> Result<String> {
+    let Some(candidate) = state.geo.geocode(place).await?.into_iter().next() else {
+        return Ok(format!("Don't know where {place} is"));
+    };
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let radius_km = radius_km
+        .unwrap_or(settings.search_radius_km)
+        .min(state.api_limits.max_radius_km);
+    let location = candidate.location;
+    let days = upcoming_days(Utc::now().date_naive());
+    let sites = outlook::site_days_within(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &location,
+        radius_km,
+    )
+    .await?;
+    Ok(markdown::outlook(
+        &format!("Flying within {radius_km:.0} km of {}", location.name),
+        &days,
+        &sites.days,
+        &sites.errors,
+    ))
+}
+
+/// The outlook of the next days around home.
+pub async fn daily_update(state: &AppState) -> Result<String> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let home = Location::new(
+        settings.location_latitude,
+        settings.location_longitude,
+        settings.location_name.clone(),
+        String::new(),
+    );
+    let days = upcoming_days(Utc::now().date_naive());
+    let sites = outlook::site_days(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &home,
+    )
+    .await?;
+    Ok(markdown::outlook(
+        &format!("Flying around {}", settings.location_name),
+        &days,
+        &sites.days,
+        &sites.errors,
+    ))
+}
+
+/// Posts the [`daily_update`] to every configured chat, failing if any
+/// didn't get it.
+pub async fn post_update(state: &AppState) -> Result<()> {
+    if state.notifiers.is_empty() {
+        return Err(TravelAiError::new(
+            ErrorCode::JobNotConfigured,
+            "Neither DISCORD_CHANNEL_ID nor MATRIX_ROOM_ID is set",
+        )
+        .into());
+    }
+    let update = daily_update(state).await?;
+    notify_all(&state.notifiers, &update).await
+}
+
+async fn notify_all(notifiers: &[Arc<dyn Notifier>], text: &str) -> Result<()> {
+    // Made once, so a retried post isn't taken for a new message
+    let message_id = Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .to_string();
+    let mut failed = Vec::new();
+    for notifier in notifiers {
+        let mut retry = Retry::new("chat update", POST_ATTEMPTS);
+        let outcome = loop {
+            match notifier.notify(text, &message_id).await {
+                Err(e) if retry.wait(&e).await => continue,
+                outcome => break outcome,
+            }
+        };
+        if let Err(e) = outcome {
+            tracing::error!(chat = notifier.name(), error = ?e, "Failed to post the outlook");
+            failed.push(notifier.name());
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("Failed to post the outlook to {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Today and the days after it the answers cover.
+fn upcoming_days(today: NaiveDate) -> Vec<NaiveDate> {
+    (0..OUTLOOK_DAYS)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .collect()
+}
+
+/// `text` cut at a line break to at most `max_chars` characters, for chats
+/// that limit the length of a message.
+pub fn fit(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut fitted = String::new();
+    for line in text.lines() {
+        let length = fitted.chars().count() + line.chars().count() + 1;
+        if length + 1 > max_chars {
+            break;
+        }
+        fitted.push_str(line);
+        fitted.push('\n');
+    }
+    fitted.push('…');
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::MockNotifier;
+    use rstest::rstest;
+
+    fn fly_command(place: &str, radius_km: Option<f64>) -> Option<Command> {
+        Some(Command::Fly {
+            place: place.into(),
+            radius_km,
+        })
+    }
+
+    #[rstest]
+    #[case("!fly Garmisch", fly_command("Garmisch", None))]
+    #[case("  !fly Bad Tölz 40 ", fly_command("Bad Tölz", Some(40.0)))]
+    #[case("!fly Annecy 25km", fly_command("Annecy", Some(25.0)))]
+    #[case("!fly", Some(Command::Usage))]
+    #[case("!fly 50", Some(Command::Usage))]
+    #[case("!flying high", None)]
+    #[case("anyone flying today?", None)]
+    fn fly_commands_are_recognized(#[case] message: &str, #[case] expected: Option<Command>) {
+        assert_eq!(parse(message), expected);
+    }
+
+    fn notifier(name: &'static str, works: bool) -> Arc<dyn Notifier> {
+        let mut notifier = MockNotifier::new();
+        notifier.expect_name().return_const(name);
+        notifier.expect_notify().times(1).returning(move |_, _| {
+            if works {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("room not found"))
+            }
+        });
+        Arc::new(notifier)
+    }
+
+    #[tokio::test]
+    async fn one_failing_chat_does_not_keep_the_others_from_the_update() {
+        let notifiers = [notifier("discord", false), notifier("matrix", true)];
+        let error = notify_all(&notifiers, "outlook").await.unwrap_err();
+        assert_eq!(error.to_string(), "Failed to post the outlook to discord");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_retried_post_keeps_its_message_id() {
+        let ids = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut notifier = MockNotifier::new();
+        notifier.expect_name().return_const("matrix");
+        notifier.expect_notify().times(2).returning({
+            let ids = ids.clone();
+            move |_, id| {
+                let mut ids = ids.lock().unwrap();
+                ids.push(id.to_string());
+                if ids.len() == 1 {
+                    Err(TravelAiError::new(ErrorCode::NotificationUnavailable, "timed out").into())
+                } else {
+                    Ok(())
+                }
+            }
+        });
+
+        notify_all(&[Arc::new(notifier)], "outlook").await.unwrap();
+        let ids = ids.lock().unwrap();
+        assert_eq!(ids[0], ids[1]);
+    }
+
+    #[test]
+    fn long_texts_are_cut_at_a_line_break() {
+        assert_eq!(fit("short", 10), "short");
+        assert_eq!(fit("first line\nsecond line", 15), "first line\n…");
+    }
+}
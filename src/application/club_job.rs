@@ -0,0 +1,339 @@
+//! Club mode: one shared calendar with every flyable site day for the whole
+//! club, and a message per member listing only tomorrow's sites that suit
+//! their skill level. Beginners only hear about calm days without hazards at
+//! the school sites, where training can be supervised.
+
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+
+use crate::{
+    adapters::{
+        activities::paragliding::{
+            outlook::{self, SiteDay},
+            site_evaluator::{FlyableRange, Hazard},
+        },
+        email,
+        mqtt::{self, MqttPublisher},
+    },
+    app_state::AppState,
+    application::{calendar_job, retry::Retry},
+    config::{ClubConfig, MqttConfig},
+    domain::{
+        calendar::{CalendarEvent, EventColor},
+        location::Location,
+        paragliding::club::{Contact, SkillLevel},
+        units::WindSpeed,
+    },
+};
+
+/// Days ahead the shared calendar covers
+const CALENDAR_DAYS: u64 = 7;
+const SEND_ATTEMPTS: u32 = 2;
+
+/// Strongest mean wind and gusts in the flyable window a skill level is told
+/// about
+const BEGINNER_WIND: (WindSpeed, WindSpeed) =
+    (WindSpeed::from_kmh(15.0), WindSpeed::from_kmh(20.0));
+const INTERMEDIATE_WIND: (WindSpeed, WindSpeed) =
+    (WindSpeed::from_kmh(25.0), WindSpeed::from_kmh(35.0));
+
+#[tracing::instrument(skip_all, fields(member_count = club.members.len()))]
+pub async fn run(state: &AppState, club: &ClubConfig, mqtt: Option<&MqttConfig>) -> Result<()> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let home = Location::new(
+        settings.location_latitude,
+        settings.location_longitude,
+        settings.location_name.clone(),
+        String::new(),
+    );
+    let today = Utc::now().date_naive();
+    let end = today + Days::new(CALENDAR_DAYS);
+    let days: Vec<SiteDay> = outlook::site_days(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &home,
+    )
+    .await?
+    .days
+    .into_iter()
+    .filter(|d| d.flyable && d.day.date >= today && d.day.date < end)
+    .collect();
+
+    write_calendar(state, club, &days).await?;
+
+    let tomorrow = today + Days::new(1);
+    let mut mqtt_messages = Vec::new();
+    let mut failed = 0;
+    for member in &club.members {
+        let sites: Vec<&SiteDay> = days
+            .iter()
+            .filter(|d| d.day.date == tomorrow && suits(member.skill, d, &club.school_sites))
+            .collect();
+        if sites.is_empty() {
+            continue;
+        }
+        let (subject, body) = message(tomorrow, &sites);
+        match &member.contact {
+            Contact::Email(address) => {
+                let mut retry = Retry::new("club email", SEND_ATTEMPTS);
+                let outcome = loop {
+                    match email::send(address, &subject, &body).await {
+                        Err(e) if retry.wait(&e).await => continue,
+                        outcome => break outcome,
+                    }
+                };
+                if let Err(e) = outcome {
+                    tracing::error!(member = %member.name, error = ?e, "Failed to notify club member");
+                    failed += 1;
+                }
+            }
+            Contact::Mqtt => match mqtt {
+                Some(config) => mqtt_messages.push((
+                    format!(
+                        "{}/club/{}",
+                        config.topic_prefix,
+                        mqtt::topic_level(&member.name)
+                    ),
+                    body,
+                )),
+                None => {
+                    tracing::warn!(member = %member.name, "MQTT_BROKER is not set, cannot notify club member");
+                    failed += 1;
+                }
+            },
+        }
+    }
+
+    if let (Some(config), false) = (mqtt, mqtt_messages.is_empty()) {
+        let publisher = MqttPublisher::new(
+            config.broker.clone(),
+            config.client_id.clone(),
+            config.credentials.clone(),
+            config.tls,
+        );
+        let mut retry = Retry::new("club MQTT publish", SEND_ATTEMPTS);
+        loop {
+            match publisher.publish_retained(&mqtt_messages).await {
+                Err(e) if retry.wait(&e).await => continue,
+                result => break result?,
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{failed} of {} club members could not be notified",
+            club.members.len()
+        );
+    }
+    tracing::info!(site_days = days.len(), "Broadcast to the club");
+    Ok(())
+}
+
+/// Replaces the club calendar with the flyable site days.
+async fn write_calendar(state: &AppState, club: &ClubConfig, days: &[SiteDay]) -> Result<()> {
+    let events: Vec<CalendarEvent> = days
+        .iter()
+        .filter_map(|d| event(d, &club.school_sites))
+        .collect();
+    calendar_job::replace_calendar(state, &club.calendar_name, &events).await
+}
+
+/// Whether a pilot of `skill` should hear about the flyable `site_day`.
+fn suits(skill: SkillLevel, site_day: &SiteDay, school_sites: &[String]) -> bool {
+    if !site_day.flyable {
+        return false;
+    }
+    match skill {
+        SkillLevel::Beginner => {
+            school_sites.contains(&site_day.site.name)
+                && site_day.day.hazards.is_empty()
+                && wind_within(site_day, BEGINNER_WIND)
+        }
+        SkillLevel::Intermediate => {
+            !site_day
+                .day
+                .hazards
+                .iter()
+                .any(|h| matches!(h, Hazard::Thunderstorm | Hazard::Foehn))
+                && wind_within(site_day, INTERMEDIATE_WIND)
+        }
+        SkillLevel::Advanced => true,
+    }
+}
+
+/// Whether wind and gusts stay within `(mean, gust)` throughout the longest
+/// flyable window.
+fn wind_within(site_day: &SiteDay, (mean, gust): (WindSpeed, WindSpeed)) -> bool {
+    let Some(range) = longest_range(site_day) else {
+        return false;
+    };
+    site_day
+        .hours
+        .iter()
+        .filter(|h| h.timestamp >= range.start && h.timestamp <= range.end)
+        .all(|h| h.wind_speed() <= mean && h.wind_gust() <= gust)
+}
+
+fn longest_range(site_day: &SiteDay) -> Option<&FlyableRange> {
+    site_day.day.ranges.iter().max_by_key(|r| r.flyable_hours())
+}
+
+/// The site day's longest window in the shared calendar, naming the skill
+/// levels it suits.
+fn event(site_day: &SiteDay, school_sites: &[String]) -> Option<CalendarEvent> {
+    let range = longest_range(site_day)?;
+    let rating = site_day.rating();
+    let levels: Vec<&str> = SkillLevel::ALL
+        .into_iter()
+        .filter(|skill| suits(*skill, site_day, school_sites))
+        .map(SkillLevel::label)
+        .collect();
+    let mut body = format!("Flyable {}h", site_day.day.total_flyable_hours);
+    if let Some(wind) = site_day.wind() {
+        body.push_str(&format!(", wind {wind} km/h"));
+    }
+    body.push_str(&format!("\nFor: {}", levels.join(", ")));
+    Some(CalendarEvent {
+        title: format!("{} ({})", site_day.site.name, rating.label()),
+        start_time: range.start,
+        end_time: range.window().end,
+        is_all_day: false,
+        location: Some(site_day.site.name.clone()),
+        body: Some(body),
+        color: Some(EventColor::from(rating)),
+        reminder_minutes: vec![],
+        key: Some(format!("{}/{}", site_day.day.date, site_day.site.name)),
+        site: Some(site_day.site.name.clone()),
+    })
+}
+
+/// Subject and text telling a member about `sites` on `date`.
+fn message(date: NaiveDate, sites: &[&SiteDay]) -> (String, String) {
+    let names: Vec<&str> = sites.iter().map(|d| d.site.name.as_str()).collect();
+    let subject = format!("Flyable {}: {}", date.format("%a %-d %b"), names.join(", "));
+    let lines: Vec<String> = sites
+        .iter()
+        .map(|d| {
+            let mut line = format!(
+                "{}: {}h {}",
+                d.site.name,
+                d.day.total_flyable_hours,
+                d.rating().label()
+            );
+            if let Some(window) = d.window() {
+                line.push_str(&format!(", {window} UTC"));
+            }
+            if let Some(wind) = d.wind() {
+                line.push_str(&format!(", wind {wind} km/h"));
+            }
+            line
+        })
+        .collect();
+    (subject, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::activities::paragliding::site_evaluator::DailySummary,
+        domain::{paragliding::ParaglidingSite, weather::WeatherData},
+    };
+    use chrono::{DateTime, TimeZone};
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 13, hour, 0, 0).unwrap()
+    }
+
+    fn site_day(name: &str, wind_kmh: f32, gust_kmh: f32, hazards: Vec<Hazard>) -> SiteDay {
+        SiteDay {
+            site: ParaglidingSite {
+                name: name.into(),
+                data_source: "test".into(),
+                ..Default::default()
+            },
+            day: DailySummary {
+                date: at(0).date_naive(),
+                ranges: vec![FlyableRange {
+                    start: at(10),
+                    end: at(13),
+                }],
+                total_flyable_hours: 4,
+                hazards,
+                ..Default::default()
+            },
+            hours: (10..14)
+                .map(|hour| WeatherData {
+                    timestamp: at(hour),
+                    wind_direction: 270,
+                    wind_speed_ms: WindSpeed::from_kmh(wind_kmh).ms(),
+                    wind_gust_ms: WindSpeed::from_kmh(gust_kmh).ms(),
+                    ..Default::default()
+                })
+                .collect(),
+            flyable: true,
+        }
+    }
+
+    fn levels(site_day: &SiteDay) -> Vec<SkillLevel> {
+        let school = vec!["School".to_string()];
+        SkillLevel::ALL
+            .into_iter()
+            .filter(|skill| suits(*skill, site_day, &school))
+            .collect()
+    }
+
+    #[test]
+    fn beginners_only_hear_about_calm_school_days() {
+        use SkillLevel::*;
+        assert_eq!(
+            levels(&site_day("School", 10.0, 15.0, vec![])),
+            [Beginner, Intermediate, Advanced]
+        );
+        assert_eq!(
+            levels(&site_day("Ridge", 10.0, 15.0, vec![])),
+            [Intermediate, Advanced]
+        );
+        assert_eq!(
+            levels(&site_day("School", 20.0, 25.0, vec![])),
+            [Intermediate, Advanced]
+        );
+        assert_eq!(
+            levels(&site_day("School", 10.0, 15.0, vec![Hazard::Gusts])),
+            [Intermediate, Advanced]
+        );
+        assert_eq!(
+            levels(&site_day("School", 10.0, 15.0, vec![Hazard::Foehn])),
+            [Advanced]
+        );
+        assert_eq!(levels(&site_day("Ridge", 28.0, 40.0, vec![])), [Advanced]);
+
+        let mut grounded = site_day("School", 10.0, 15.0, vec![]);
+        grounded.flyable = false;
+        assert!(levels(&grounded).is_empty());
+    }
+
+    #[test]
+    fn shared_event_names_the_suitable_levels() {
+        let event = event(&site_day("School", 10.0, 15.0, vec![]), &["School".into()]).unwrap();
+        assert_eq!(event.title, "School (good)");
+        assert_eq!((event.start_time, event.end_time), (at(10), at(14)));
+        assert_eq!(event.key.as_deref(), Some("2026-06-13/School"));
+        assert!(
+            event
+                .body
+                .unwrap()
+                .ends_with("For: beginner, intermediate, advanced")
+        );
+    }
+
+    #[test]
+    fn message_lists_window_and_wind() {
+        let school = site_day("School", 10.0, 15.0, vec![]);
+        let (subject, body) = message(at(0).date_naive(), &[&school]);
+        assert_eq!(subject, "Flyable Sat 13 Jun: School");
+        assert_eq!(body, "School: 4h good, 10-14 UTC, wind W 10 km/h");
+    }
+}
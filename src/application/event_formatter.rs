@@ -0,0 +1,186 @@
+//! Turns planned suggestions into the calendar events the sync writes.
+
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use reqwest::Url;
+
+use crate::domain::{
+    activities::{ActivitySuggestion, DayRating, Score, Timing},
+    calendar::{CalendarEvent, EventColor},
+    location::Location,
+};
+
+/// Pages about a suggestion's site, linked from the event description.
+#[derive(Debug, Clone, Default)]
+pub struct SiteLinks {
+    /// The site at its data source, e.g. DHV or Paragliding Earth
+    pub source_url: Option<String>,
+    /// Hourly flyability of the site in this app
+    pub forecast_url: Option<String>,
+}
+
+/// Hourly forecast endpoint of `site` below the public `base_url`.
+pub fn forecast_url(base_url: &str, site: &str) -> Option<String> {
+    let mut url = Url::parse(base_url).ok()?;
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .extend(["api", "sites", site, "forecast"]);
+    Some(url.into())
+}
+
+/// Colors the event by the suggestion's rating and adds a reminder for days
+/// rated good or better when `reminder_hours_before` is set.
+pub fn suggestion_to_event(
+    s: ActivitySuggestion,
+    key: String,
+    place: Option<String>,
+    reminder_hours_before: Option<u32>,
+    links: &SiteLinks,
+) -> CalendarEvent {
+    let rating = s.score.as_ref().map(Score::rating);
+    let reminder_minutes = match (rating, reminder_hours_before) {
+        (Some(rating), Some(hours)) if rating >= DayRating::Good => vec![hours * 60],
+        _ => vec![],
+    };
+    let (start, end) = match s.timing {
+        Timing::Flexible { window, .. } => (window.start, window.end),
+        Timing::Fixed { start, end } => (start, end),
+    };
+    let site = (!s.kind.is_plan_b()).then(|| s.title.clone());
+    let mut body = String::new();
+    if !s.description.is_empty() {
+        body.push_str(&s.description);
+        body.push_str("\n\n");
+    }
+    for line in link_lines(&s.location, links) {
+        body.push_str(&line);
+        body.push('\n');
+    }
+    body.push('\n');
+    body.push_str(&format!("Last updated (Utc): {}", Utc::now()));
+    CalendarEvent {
+        title: s.title.clone(),
+        start_time: start,
+        end_time: end,
+        is_all_day: false,
+        location: Some(match place {
+            Some(place) => format!("{}, {}", s.title, place),
+            None => s.title,
+        }),
+        body: Some(body),
+        color: rating.map(EventColor::from),
+        reminder_minutes,
+        key: Some(key),
+        site,
+    }
+}
+
+/// All-day event listing the flyable sites of `date`.
+pub fn day_summary_event(date: NaiveDate, sites: &[String]) -> CalendarEvent {
+    let start = date.and_time(NaiveTime::MIN).and_utc();
+    let noun = if sites.len() == 1 { "site" } else { "sites" };
+    CalendarEvent {
+        title: format!("🟢 Flyable: {} {}", sites.len(), noun),
+        start_time: start,
+        end_time: start + Duration::days(1),
+        is_all_day: true,
+        location: None,
+        body: Some(sites.join("\n")),
+        color: None,
+        reminder_minutes: vec![],
+        key: Some(format!("{}/summary", date)),
+        site: None,
+    }
+}
+
+fn link_lines(launch: &Location, links: &SiteLinks) -> Vec<String> {
+    let (lat, lon) = (launch.latitude, launch.longitude);
+    let mut lines = vec![
+        format!(
+            "Google Maps: https://www.google.com/maps/search/?api=1&query={:.5},{:.5}",
+            lat, lon
+        ),
+        format!(
+            "OpenStreetMap: https://www.openstreetmap.org/?mlat={:.5}&mlon={:.5}#map=15/{:.5}/{:.5}",
+            lat, lon, lat, lon
+        ),
+    ];
+    if let Some(url) = &links.source_url {
+        lines.push(format!("Site info: {}", url));
+    }
+    if let Some(url) = &links.forecast_url {
+        lines.push(format!("Hourly forecast: {}", url));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::activities::ActivityKind;
+    use chrono::TimeZone;
+
+    #[test]
+    fn forecast_url_escapes_the_site_name() {
+        assert_eq!(
+            forecast_url("https://example.com/", "Hohe Wand/Ost").as_deref(),
+            Some("https://example.com/api/sites/Hohe%20Wand%2FOst/forecast")
+        );
+        assert!(forecast_url("not a url", "Site").is_none());
+    }
+
+    #[test]
+    fn description_links_launch_and_site_pages() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap();
+        let suggestion = ActivitySuggestion {
+            kind: ActivityKind::Paragliding,
+            location: Location::new(50.7, 13.0, "Launch".into(), "DE".into()),
+            timing: Timing::Fixed {
+                start,
+                end: start + Duration::hours(4),
+            },
+            title: "Site".into(),
+            description: "Flyable in the afternoon".into(),
+            score: None,
+        };
+        let links = SiteLinks {
+            source_url: Some("https://www.dhv.de/site/1".into()),
+            forecast_url: Some("https://example.com/api/sites/Site/forecast".into()),
+        };
+
+        let event = suggestion_to_event(suggestion, "key".into(), None, None, &links);
+
+        let body = event.body.unwrap();
+        assert!(body.starts_with("Flyable in the afternoon\n\n"));
+        assert!(body.contains("query=50.70000,13.00000"));
+        assert!(body.contains("openstreetmap.org/?mlat=50.70000&mlon=13.00000"));
+        assert!(body.contains("Site info: https://www.dhv.de/site/1"));
+        assert!(body.contains("Hourly forecast: https://example.com/api/sites/Site/forecast"));
+    }
+
+    #[test]
+    fn flying_events_name_their_site_and_plan_b_events_none() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap();
+        let suggestion = |kind| ActivitySuggestion {
+            kind,
+            location: Location::new(50.7, 13.0, "Launch".into(), "DE".into()),
+            timing: Timing::Fixed {
+                start,
+                end: start + Duration::hours(4),
+            },
+            title: "Hohe Wand/Ost".into(),
+            description: String::new(),
+            score: None,
+        };
+        let event = |kind| {
+            let key = "2026-06-13/Hohe Wand/Ost/1000".to_string();
+            suggestion_to_event(suggestion(kind), key, None, None, &SiteLinks::default())
+        };
+
+        assert_eq!(
+            event(ActivityKind::Paragliding).site.as_deref(),
+            Some("Hohe Wand/Ost")
+        );
+        assert_eq!(event(ActivityKind::Hiking).site, None);
+    }
+}
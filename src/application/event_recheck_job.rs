@@ -0,0 +1,337 @@
+//! Re-checks flying events shortly before they start, since the forecast
+//! behind an event can be hours old by then. If the wind at the launch over
+//! the last hours, fetched fresh, is already too strong or heading there, or the
+//! latest forecast no longer has the window, the event is cancelled or cut to
+//! what is left, and the pilot gets an email saying why. Only the changed
+//! events are touched in the calendar.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    adapters::{
+        activities::paragliding::site_evaluator::{HourlyScore, WindLimits},
+        email,
+    },
+    app_state::AppState,
+    application::calendar_job,
+    config,
+    domain::{calendar::CalendarEvent, weather::WeatherData},
+};
+
+/// How long before the start an event is checked
+const LEAD_TIME_HOURS: i64 = 2;
+/// Past hours the wind trend is taken from
+const RECENT_HOURS: u8 = 3;
+/// Keys of the events already checked, so each is only checked once
+const CHECKED_KEY: &str = "event_recheck_done";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Keep,
+    /// Only part of the event is still flyable
+    Shorten {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        reason: String,
+    },
+    Cancel {
+        reason: String,
+    },
+}
+
+fn events_key(calendar: &str) -> String {
+    format!("synced_events_{calendar}")
+}
+
+/// Keeps the events just written to `calendar` for the check before they start.
+pub async fn remember(state: &AppState, calendar: &str, events: &[CalendarEvent]) -> Result<()> {
+    state
+        .store
+        .put(&events_key(calendar), events.to_vec())
+        .await
+}
+
+#[tracing::instrument(skip_all, fields(checked = tracing::field::Empty))]
+pub async fn run(state: &AppState) -> Result<()> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let calendar = settings.calendar_name;
+    // Events remembered before they named their site don't decode, and are
+    // replaced by the next calendar sync.
+    let Some(mut events) = state
+        .store
+        .get::<Vec<CalendarEvent>>(&events_key(&calendar))
+        .await
+        .ok()
+        .flatten()
+    else {
+        return Ok(());
+    };
+    let mut checked: Vec<String> = state.store.get(CHECKED_KEY).await?.unwrap_or_default();
+    checked.retain(|key| events.iter().any(|e| e.key.as_ref() == Some(key)));
+
+    let now = Utc::now();
+    let mut changes = Vec::new();
+    let mut shortened = Vec::new();
+    let mut cancelled = Vec::new();
+    for event in &mut events {
+        let Some(key) = event.key.clone() else {
+            continue;
+        };
+        let due = !event.is_all_day
+            && event.start_time > now
+            && event.start_time <= now + Duration::hours(LEAD_TIME_HOURS);
+        if !due || checked.contains(&key) {
+            continue;
+        }
+        let Some(verdict) = check_event(state, event).await else {
+            continue;
+        };
+        checked.push(key.clone());
+        match verdict {
+            Verdict::Keep => {}
+            Verdict::Shorten { start, end, reason } => {
+                changes.push(format!(
+                    "{} is now {} - {} UTC: {reason}",
+                    event.title,
+                    start.format("%H:%M"),
+                    end.format("%H:%M")
+                ));
+                event.start_time = start;
+                event.end_time = end;
+                let note = format!("Shortened at {} UTC: {reason}", now.format("%H:%M"));
+                event.body = Some(match event.body.take() {
+                    Some(body) => format!("{note}\n\n{body}"),
+                    None => note,
+                });
+                shortened.push(event.clone());
+            }
+            Verdict::Cancel { reason } => {
+                changes.push(format!("{} is cancelled: {reason}", event.title));
+                cancelled.push(key);
+            }
+        }
+    }
+    tracing::Span::current().record("checked", checked.len());
+
+    if !changes.is_empty() {
+        events.retain(|e| e.key.as_ref().is_none_or(|key| !cancelled.contains(key)));
+        calendar_job::update_events(state, &calendar, &shortened, &cancelled).await?;
+        remember(state, &calendar, &events).await?;
+        notify(&changes).await;
+    }
+    state.store.put(CHECKED_KEY, checked).await
+}
+
+/// The verdict on `event` from its site's recent and latest forecast wind.
+/// None if the event isn't about a known site or its weather can't be had.
+async fn check_event(state: &AppState, event: &CalendarEvent) -> Option<Verdict> {
+    let site = state
+        .site_repo
+        .get_site(event.site.as_deref()?)
+        .await
+        .ok()??;
+    let launch = site.launches.first()?;
+
+    let recent = match state
+        .weather_history
+        .get_recent_weather(&launch.location, RECENT_HOURS)
+        .await
+    {
+        Ok(recent) => recent,
+        Err(e) => {
+            tracing::warn!(site = %site.name, error = ?e, "Failed to get recent wind");
+            vec![]
+        }
+    };
+    let forecast = match state
+        .weather
+        .get_forecast(
+            launch.location.clone(),
+            site.preferred_weather_model.clone(),
+        )
+        .await
+    {
+        Ok(forecast) => forecast,
+        Err(e) => {
+            tracing::warn!(site = %site.name, error = ?e, "Failed to get weather forecast");
+            return None;
+        }
+    };
+    let hours: Vec<HourlyScore> = state
+        .evaluations
+        .evaluate(&site, &forecast)
+        .daily_summaries
+        .iter()
+        .flat_map(|day| day.hourly_scores.iter().cloned())
+        .collect();
+
+    Some(recheck(
+        event.start_time,
+        event.end_time,
+        &recent,
+        &hours,
+        &WindLimits::default(),
+    ))
+}
+
+/// Cancels if the recent wind, or its trend carried on to the start,
+/// reaches the limits, or if no hour of the event is flyable any more;
+/// shortens to the longest flyable run if only part of it is.
+fn recheck(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    recent: &[WeatherData],
+    hours: &[HourlyScore],
+    limits: &WindLimits,
+) -> Verdict {
+    if let (Some(first), Some(last)) = (recent.first(), recent.last()) {
+        let (wind, gust) = (last.wind_speed().kmh(), last.wind_gust().kmh());
+        let (max_wind, max_gust) = (limits.max_wind.kmh(), limits.max_gust.kmh());
+        if wind >= max_wind || gust >= max_gust {
+            return Verdict::Cancel {
+                reason: format!("recent wind {wind:.0} km/h, gusts {gust:.0} km/h"),
+            };
+        }
+        let span = (last.timestamp - first.timestamp).num_minutes() as f32 / 60.0;
+        if span > 0.0 {
+            let ahead = (start - last.timestamp).num_minutes() as f32 / 60.0;
+            let project = |from: f32, to: f32| to + (to - from) / span * ahead;
+            let wind_then = project(first.wind_speed().kmh(), wind);
+            let gust_then = project(first.wind_gust().kmh(), gust);
+            if wind_then >= max_wind || gust_then >= max_gust {
+                return Verdict::Cancel {
+                    reason: format!(
+                        "recent wind rising to {wind:.0} km/h, gusts {gust:.0} km/h, heading past the limits by {} UTC",
+                        start.format("%H:%M")
+                    ),
+                };
+            }
+        }
+    }
+
+    let within: Vec<&HourlyScore> = hours
+        .iter()
+        .filter(|h| h.timestamp >= start && h.timestamp < end)
+        .collect();
+    // Longest run of consecutive flyable hours, as (first, last)
+    let mut best: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+    let mut run: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+    for hour in &within {
+        run = match (hour.is_flyable, run) {
+            (false, _) => None,
+            (true, Some((first, last))) if hour.timestamp - last == Duration::hours(1) => {
+                Some((first, hour.timestamp))
+            }
+            (true, _) => Some((hour.timestamp, hour.timestamp)),
+        };
+        if let Some((first, last)) = run
+            && best.is_none_or(|(b_first, b_last)| last - first > b_last - b_first)
+        {
+            best = Some((first, last));
+        }
+    }
+
+    match best {
+        None if within.is_empty() => Verdict::Keep,
+        None => Verdict::Cancel {
+            reason: "the latest forecast has no flyable hour left".to_string(),
+        },
+        Some((first, last)) if first <= start && last + Duration::hours(1) >= end => Verdict::Keep,
+        Some((first, last)) => Verdict::Shorten {
+            start: first.max(start),
+            end: (last + Duration::hours(1)).min(end),
+            reason: "the latest forecast only keeps part of the window".to_string(),
+        },
+    }
+}
+
+/// Emails `changes` to `NOTIFICATION_EMAIL`, if set.
+async fn notify(changes: &[String]) {
+    let Ok(to) = config::var("NOTIFICATION_EMAIL") else {
+        tracing::warn!("NOTIFICATION_EMAIL is not set, not sending event changes");
+        return;
+    };
+    let subject = if changes.len() == 1 {
+        "Flying event changed".to_string()
+    } else {
+        format!("{} flying events changed", changes.len())
+    };
+    if let Err(e) = email::send(&to, &subject, &changes.join("\n")).await {
+        tracing::error!(error = ?e, "Failed to send event changes");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 13, hour, 0, 0).unwrap()
+    }
+
+    fn recent(hour: u32, wind_kmh: f32, gust_kmh: f32) -> WeatherData {
+        WeatherData {
+            timestamp: at(hour),
+            wind_speed_ms: wind_kmh / 3.6,
+            wind_gust_ms: gust_kmh / 3.6,
+            ..Default::default()
+        }
+    }
+
+    fn scores(flyable: &[u32]) -> Vec<HourlyScore> {
+        (8..18)
+            .map(|hour| HourlyScore {
+                timestamp: at(hour),
+                is_flyable: flyable.contains(&hour),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn check(recent: &[WeatherData], flyable: &[u32]) -> Verdict {
+        recheck(
+            at(11),
+            at(15),
+            recent,
+            &scores(flyable),
+            &WindLimits::default(),
+        )
+    }
+
+    #[test]
+    fn steady_wind_and_forecast_keep_the_event() {
+        let calm = [recent(7, 10.0, 15.0), recent(8, 10.0, 16.0)];
+        assert_eq!(check(&calm, &[11, 12, 13, 14]), Verdict::Keep);
+    }
+
+    #[test]
+    fn strong_or_rising_recent_wind_cancels() {
+        let strong = [recent(7, 20.0, 30.0), recent(8, 27.0, 35.0)];
+        assert!(matches!(
+            check(&strong, &[11, 12, 13, 14]),
+            Verdict::Cancel { reason } if reason.starts_with("recent wind 27 km/h")
+        ));
+
+        // 5 km/h more each hour reaches 30 km/h by 11:00.
+        let rising = [recent(6, 5.0, 10.0), recent(8, 15.0, 20.0)];
+        assert!(matches!(
+            check(&rising, &[11, 12, 13, 14]),
+            Verdict::Cancel { reason } if reason.contains("rising")
+        ));
+    }
+
+    #[test]
+    fn fresh_forecast_shortens_or_cancels() {
+        assert_eq!(
+            check(&[], &[12, 13]),
+            Verdict::Shorten {
+                start: at(12),
+                end: at(14),
+                reason: "the latest forecast only keeps part of the window".into(),
+            }
+        );
+        assert!(matches!(check(&[], &[9, 16]), Verdict::Cancel { .. }));
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+
+use crate::{
+    app_state::AppState,
+    domain::{location::Location, paragliding::ParaglidingSiteProvider},
+};
+
+/// Fetches the forecast of every site within the search radius and of every
+/// favorite, so pages and the calendar sync don't wait for the weather APIs.
+/// Forecasts still cached are left alone.
+#[tracing::instrument(skip_all, fields(site_count = tracing::field::Empty))]
+pub async fn run(state: &AppState) -> Result<()> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let home = Location::new(
+        settings.location_latitude,
+        settings.location_longitude,
+        settings.location_name.clone(),
+        String::new(),
+    );
+
+    let mut sites: Vec<_> = state
+        .site_repo
+        .fetch_launches_within_radius(&home, settings.search_radius_km)
+        .await
+        .into_iter()
+        .map(|(site, _distance)| site)
+        .collect();
+    for site in state.site_repo.fetch_all_sites().await {
+        if site.favorite == Some(true) && !sites.iter().any(|s| s.name == site.name) {
+            sites.push(site);
+        }
+    }
+    tracing::Span::current().record("site_count", sites.len());
+
+    let mut failed = 0;
+    for site in &sites {
+        let Some(launch) = site.launches.first() else {
+            continue;
+        };
+        if let Err(e) = state
+            .weather
+            .get_forecast(
+                launch.location.clone(),
+                site.preferred_weather_model.clone(),
+            )
+            .await
+        {
+            tracing::warn!(site = %site.name, error = ?e, "Failed to refresh weather forecast");
+            failed += 1;
+        }
+    }
+    if failed > 0 && failed == sites.len() {
+        anyhow::bail!("Failed to refresh the forecast of all {} sites", failed);
+    }
+    Ok(())
+}
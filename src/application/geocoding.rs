@@ -0,0 +1,66 @@
+//! The places a name stands for, told apart for the user, for the API and the
+//! `geocode` command alike.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        location::{GeocodeCandidate, Location},
+        paragliding::{ParaglidingSiteProvider, sites_within_radius},
+        regions,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct Place {
+    /// Pass as `candidate` to pick this place in `/forecast`
+    pub index: usize,
+    #[serde(flatten)]
+    pub location: Location,
+    pub admin_area: Option<String>,
+    /// Closest known site, to tell the place that matters for flying apart
+    pub nearest_site: Option<NearestSite>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NearestSite {
+    pub name: String,
+    pub distance_km: f64,
+}
+
+/// A flying region of that name, else the geocoder's matches.
+pub async fn candidates(state: &AppState, name: &str) -> Result<Vec<GeocodeCandidate>> {
+    if let Some(region) = regions::find_region(name) {
+        return Ok(vec![GeocodeCandidate {
+            location: region.center_location(),
+            admin_area: None,
+        }]);
+    }
+    state.geo.geocode(name).await
+}
+
+/// `candidates` in order, each with its index and the known site closest to it.
+pub async fn describe(state: &AppState, candidates: Vec<GeocodeCandidate>) -> Vec<Place> {
+    let sites = state.site_repo.fetch_all_sites().await;
+    candidates
+        .into_iter()
+        .enumerate()
+        .map(|(index, candidate)| {
+            let nearest_site = sites_within_radius(&sites, &candidate.location, f64::INFINITY)
+                .into_iter()
+                .next()
+                .map(|(site, distance_km)| NearestSite {
+                    name: site.name,
+                    distance_km,
+                });
+            Place {
+                index,
+                location: candidate.location,
+                admin_area: candidate.admin_area,
+                nearest_site,
+            }
+        })
+        .collect()
+}
@@ -1,5 +1,18 @@
+pub mod area_forecast_job;
 pub mod calendar_job;
+pub mod chat_bot;
+pub mod club_job;
+pub mod event_formatter;
+pub mod event_recheck_job;
 pub mod flight_analytics;
+pub mod forecast_job;
+pub mod geocoding;
+pub mod mqtt_job;
 pub mod planner;
+pub mod retry;
+pub mod scheduler;
+pub mod site_update_job;
+pub mod tandem_job;
+pub mod token_check_job;
 
 pub use planner::Planner;
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::{
+    adapters::{
+        activities::paragliding::outlook::{self, SiteState},
+        mqtt::{self, MqttPublisher},
+    },
+    app_state::AppState,
+    application::retry::Retry,
+    config::MqttConfig,
+    domain::{activities::DayRating, paragliding::ParaglidingSiteProvider},
+};
+
+const PUBLISH_ATTEMPTS: u32 = 3;
+
+/// Publishes the flyability of every favorite site, e.g. for a Home Assistant
+/// LED indicator.
+#[tracing::instrument(skip_all, fields(site_count = tracing::field::Empty))]
+pub async fn run(state: &AppState, config: &MqttConfig) -> Result<()> {
+    let min_hours = state
+        .site_repo
+        .get_settings()
+        .await?
+        .unwrap_or_default()
+        .minimum_flyable_hours;
+    let today = Utc::now().date_naive();
+
+    let mut states = Vec::new();
+    for site in state.site_repo.fetch_all_sites().await {
+        if site.favorite != Some(true) {
+            continue;
+        }
+        let Some(launch) = site.launches.first() else {
+            continue;
+        };
+        let Ok(forecast) = outlook::site_forecast(state.weather.as_ref(), &site, launch).await
+        else {
+            continue;
+        };
+        let eval = state.evaluations.evaluate(&site, &forecast);
+        states.push(outlook::next_flyable(
+            &site.name,
+            &eval.daily_summaries,
+            today,
+            min_hours,
+        ));
+    }
+    tracing::Span::current().record("site_count", states.len());
+
+    let publisher = MqttPublisher::new(
+        config.broker.clone(),
+        config.client_id.clone(),
+        config.credentials.clone(),
+        config.tls,
+    );
+    let messages = messages(&config.topic_prefix, &states);
+    let mut retry = Retry::new("MQTT publish", PUBLISH_ATTEMPTS);
+    loop {
+        match publisher.publish_retained(&messages).await {
+            Err(e) if retry.wait(&e).await => continue,
+            result => return result,
+        }
+    }
+}
+
+/// `<prefix>/<site>/score` (0 to 1), `/flyable_hours`, `/window_start` and
+/// `/rating` per site. Sites without a flyable day publish `0`, `0`, an empty
+/// window and `none`.
+fn messages(prefix: &str, states: &[SiteState]) -> Vec<(String, String)> {
+    states
+        .iter()
+        .flat_map(|s| {
+            let base = format!("{}/{}", prefix, mqtt::topic_level(&s.site));
+            let rating = s.rating.map_or("none", DayRating::label);
+            [
+                (format!("{}/score", base), format!("{:.2}", s.score)),
+                (
+                    format!("{}/flyable_hours", base),
+                    s.flyable_hours.to_string(),
+                ),
+                (
+                    format!("{}/window_start", base),
+                    s.window_start.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                ),
+                (format!("{}/rating", base), rating.to_string()),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn four_topics_per_site() {
+        let states = vec![
+            SiteState {
+                site: "Hohe Wand".into(),
+                flyable_hours: 5,
+                score: 0.667,
+                window_start: Some(Utc.with_ymd_and_hms(2026, 6, 13, 10, 0, 0).unwrap()),
+                rating: Some(DayRating::Good),
+            },
+            SiteState {
+                site: "Hill".into(),
+                flyable_hours: 0,
+                score: 0.0,
+                window_start: None,
+                rating: None,
+            },
+        ];
+
+        let messages = messages("paragliding", &states);
+
+        let pairs: Vec<(&str, &str)> = messages
+            .iter()
+            .map(|(t, p)| (t.as_str(), p.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("paragliding/hohe_wand/score", "0.67"),
+                ("paragliding/hohe_wand/flyable_hours", "5"),
+                (
+                    "paragliding/hohe_wand/window_start",
+                    "2026-06-13T10:00:00+00:00"
+                ),
+                ("paragliding/hohe_wand/rating", "good"),
+                ("paragliding/hill/score", "0.00"),
+                ("paragliding/hill/flyable_hours", "0"),
+                ("paragliding/hill/window_start", ""),
+                ("paragliding/hill/rating", "none"),
+            ]
+        );
+    }
+}
@@ -1,13 +1,13 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use anyhow::Result;
-use chrono::{DateTime, Duration, TimeDelta, Utc};
+use chrono::{DateTime, Duration, NaiveDate, TimeDelta, Utc};
 use futures::future;
 
 use tracing::{Span, instrument};
 
 use crate::domain::{
-    activities::{ActivitySuggestion, PlanningContext, TimeWindow, Timing},
+    activities::{ActivitySuggestion, PlanningContext, Suggestions, TimeWindow, Timing},
     ports::{ActivitySource, CalendarProvider, RoutingProvider},
 };
 
@@ -32,17 +32,21 @@ impl Planner {
             suggestions_out = tracing::field::Empty,
         )
     )]
-    pub async fn plan<C: CalendarProvider + Send + Sync>(
+    pub async fn plan(
         &self,
         ctx: &PlanningContext,
-        calendar: &C,
-    ) -> Result<Vec<ActivitySuggestion>> {
+        calendar: &dyn CalendarProvider,
+    ) -> Result<Suggestions> {
         let per_source = future::join_all(self.sources.iter().map(|s| s.suggest(ctx))).await;
 
         let mut raw: Vec<ActivitySuggestion> = Vec::new();
+        let mut errors = Vec::new();
         for r in per_source {
             match r {
-                Ok(mut v) => raw.append(&mut v),
+                Ok(mut v) => {
+                    raw.append(&mut v.suggestions);
+                    errors.append(&mut v.errors);
+                }
                 Err(e) => tracing::warn!(error = %e, "activity source failed"),
             }
         }
@@ -96,6 +100,8 @@ impl Planner {
             }
         }
 
+        drop_plan_b_on_flying_days(&mut out);
+
         out.sort_by(|a, b| {
             let av = a.score.as_ref().map(|s| s.value);
             let bv = b.score.as_ref().map(|s| s.value);
@@ -110,14 +116,33 @@ impl Planner {
         Span::current().record("suggestions_in", suggestions_in);
         Span::current().record("suggestions_out", out.len());
 
-        Ok(out)
+        Ok(Suggestions {
+            suggestions: out,
+            errors,
+        })
+    }
+}
+
+fn window_date(timing: &Timing) -> NaiveDate {
+    match timing {
+        Timing::Fixed { start, .. } => start.date_naive(),
+        Timing::Flexible { window, .. } => window.start.date_naive(),
     }
 }
 
-async fn slice_by_calendar<C: CalendarProvider + Send + Sync>(
+fn drop_plan_b_on_flying_days(suggestions: &mut Vec<ActivitySuggestion>) {
+    let flying_days: HashSet<NaiveDate> = suggestions
+        .iter()
+        .filter(|s| !s.kind.is_plan_b())
+        .map(|s| window_date(&s.timing))
+        .collect();
+    suggestions.retain(|s| !s.kind.is_plan_b() || !flying_days.contains(&window_date(&s.timing)));
+}
+
+async fn slice_by_calendar(
     window: TimeWindow,
-    conflict_calendars: &Vec<String>,
-    calendar: &C,
+    conflict_calendars: &[String],
+    calendar: &dyn CalendarProvider,
 ) -> Vec<TimeWindow> {
     let hour = TimeDelta::hours(1);
     let mut hours: Vec<DateTime<Utc>> = Vec::new();
@@ -170,10 +195,14 @@ fn run_to_window(run: &[DateTime<Utc>]) -> Option<TimeWindow> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{
-        activities::{ActivityKind, Score},
-        location::Location,
-        ports::{MockActivitySource, MockCalendarProvider, MockRoutingProvider},
+    use crate::{
+        adapters::in_memory_calendar::InMemoryCalendar,
+        domain::{
+            activities::{ActivityKind, Score},
+            calendar::CalendarEvent,
+            location::Location,
+            ports::{MockActivitySource, MockCalendarProvider, MockRoutingProvider},
+        },
     };
     use chrono::{TimeZone, Timelike};
 
@@ -213,6 +242,7 @@ mod tests {
             score: score.map(|v| Score {
                 value: v,
                 reasons: vec![],
+                breakdown: vec![],
             }),
         }
     }
@@ -249,8 +279,12 @@ mod tests {
 
     fn source_with(suggestions: Vec<ActivitySuggestion>) -> Arc<dyn ActivitySource> {
         let mut src = MockActivitySource::new();
-        src.expect_suggest()
-            .returning(move |_| Ok(suggestions.clone()));
+        src.expect_suggest().returning(move |_| {
+            Ok(Suggestions {
+                suggestions: suggestions.clone(),
+                errors: vec![],
+            })
+        });
         Arc::new(src)
     }
 
@@ -263,7 +297,7 @@ mod tests {
         let mut cal = MockCalendarProvider::new();
         cal.expect_is_busy().returning(|_, _, _| Ok(true));
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert!(out.is_empty());
     }
 
@@ -275,11 +309,41 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert_eq!(out.len(), 1);
         assert!(matches!(out[0].timing, Timing::Fixed { .. }));
     }
 
+    #[tokio::test]
+    async fn only_listed_calendars_block_suggestions() {
+        let busy = |title: &str, start, end| CalendarEvent {
+            title: title.into(),
+            start_time: ts(start),
+            end_time: ts(end),
+            is_all_day: false,
+            location: None,
+            body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: None,
+            site: None,
+        };
+        let planner = Planner::new(
+            vec![source_with(vec![
+                fixed_suggestion(10, 12, None),
+                fixed_suggestion(14, 16, None),
+            ])],
+            fixed_travel(),
+        );
+        let cal = InMemoryCalendar::new()
+            .with_event("work", busy("meeting", 11, 12))
+            .with_event("private", busy("dentist", 15, 16));
+
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].title, "fixed-14-16");
+    }
+
     #[tokio::test]
     async fn flexible_dropped_when_fully_busy() {
         let planner = Planner::new(
@@ -289,7 +353,7 @@ mod tests {
         let mut cal = MockCalendarProvider::new();
         cal.expect_is_busy().returning(|_, _, _| Ok(true));
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert!(out.is_empty());
     }
 
@@ -301,7 +365,7 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert_eq!(out.len(), 1);
         let Timing::Flexible { window, .. } = &out[0].timing else {
             panic!("expected Flexible");
@@ -318,8 +382,11 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
-        assert!(out.is_empty(), "2h window minus 60m travel < 2h min_duration");
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
+        assert!(
+            out.is_empty(),
+            "2h window minus 60m travel < 2h min_duration"
+        );
     }
 
     #[tokio::test]
@@ -330,7 +397,7 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert_eq!(
             out.len(),
             1,
@@ -350,7 +417,7 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert!(
             out.is_empty(),
             "2h window - 60m travel each side = adjusted.end == adjusted.start; nothing left to fly",
@@ -393,13 +460,41 @@ mod tests {
         );
         let cal = always_free_calendar();
 
-        let out = planner.plan(&ctx(), &cal).await.unwrap();
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
         assert_eq!(out.len(), 3);
         assert_eq!(out[0].score.as_ref().map(|s| s.value), Some(0.9));
         assert_eq!(out[1].score.as_ref().map(|s| s.value), Some(0.5));
         assert!(out[2].score.is_none());
     }
 
+    #[tokio::test]
+    async fn plan_b_dropped_only_on_days_with_flying() {
+        let mut plan_b_today = fixed_suggestion(10, 12, None);
+        plan_b_today.kind = ActivityKind::Hiking;
+        let mut plan_b_tomorrow = plan_b_today.clone();
+        plan_b_tomorrow.timing = Timing::Fixed {
+            start: ts(10) + TimeDelta::days(1),
+            end: ts(12) + TimeDelta::days(1),
+        };
+        let planner = Planner::new(
+            vec![source_with(vec![
+                fixed_suggestion(13, 15, None),
+                plan_b_today,
+                plan_b_tomorrow,
+            ])],
+            fixed_travel(),
+        );
+        let cal = always_free_calendar();
+
+        let out = planner.plan(&ctx(), &cal).await.unwrap().suggestions;
+        let kinds: Vec<ActivityKind> = out.iter().map(|s| s.kind).collect();
+        assert_eq!(kinds, vec![ActivityKind::Paragliding, ActivityKind::Hiking]);
+        assert_eq!(
+            window_date(&out[1].timing),
+            ts(0).date_naive() + TimeDelta::days(1)
+        );
+    }
+
     #[tokio::test]
     async fn slice_by_calendar_returns_one_window_when_all_free() {
         let cal = always_free_calendar();
@@ -0,0 +1,99 @@
+//! Retrying work that failed for a reason that may go away by itself, waiting
+//! as long as the failure's [`TravelAiError`] asks for. Untyped errors are
+//! never retried, since nothing says whether trying again could help.
+
+use std::time::Duration;
+
+use crate::domain::error::TravelAiError;
+
+/// Upper bound for a single wait, so a long requested delay can't stall a job.
+pub const MAX_DELAY: Duration = Duration::from_mins(5);
+
+/// Counts the attempts at one operation. Callers loop until the operation
+/// succeeds or [`Retry::wait`] says to give up.
+pub struct Retry {
+    what: &'static str,
+    attempts: u32,
+    attempt: u32,
+}
+
+impl Retry {
+    pub fn new(what: &'static str, attempts: u32) -> Self {
+        Self {
+            what,
+            attempts,
+            attempt: 1,
+        }
+    }
+
+    /// Waits before the next attempt and returns `true`, or returns `false`
+    /// right away if `error` isn't retryable or the attempts are used up.
+    pub async fn wait(&mut self, error: &anyhow::Error) -> bool {
+        let Some(delay) = self.delay(error) else {
+            return false;
+        };
+        tracing::warn!(
+            what = self.what,
+            attempt = self.attempt,
+            delay_secs = delay.as_secs(),
+            error = ?error,
+            "Retrying after a transient failure"
+        );
+        tokio::time::sleep(delay).await;
+        self.attempt += 1;
+        true
+    }
+
+    fn delay(&self, error: &anyhow::Error) -> Option<Duration> {
+        if self.attempt >= self.attempts {
+            return None;
+        }
+        let delay = TravelAiError::find(error)?.retry_after()?;
+        Some(delay.min(MAX_DELAY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::error::{ErrorCode, RETRY_AFTER_KEY};
+
+    fn error(code: ErrorCode) -> anyhow::Error {
+        TravelAiError::new(code, "failed").into()
+    }
+
+    #[test]
+    fn only_retryable_errors_get_a_delay() {
+        let retry = Retry::new("test", 3);
+        assert!(
+            retry
+                .delay(&error(ErrorCode::CalendarUnavailable))
+                .is_some()
+        );
+        assert!(
+            retry
+                .delay(&error(ErrorCode::CalendarAuthExpired))
+                .is_none()
+        );
+        assert!(retry.delay(&anyhow::anyhow!("disk full")).is_none());
+    }
+
+    #[tokio::test]
+    async fn last_attempt_is_not_retried() {
+        let mut retry = Retry::new("test", 2);
+        retry.attempt = 2;
+        assert!(!retry.wait(&error(ErrorCode::CalendarUnavailable)).await);
+    }
+
+    #[test]
+    fn delay_comes_from_the_innermost_error_and_is_capped() {
+        let retry = Retry::new("test", 3);
+        let wrapped = error(ErrorCode::WeatherUnavailable).context("Forecast refresh failed");
+        assert_eq!(retry.delay(&wrapped), Some(Duration::from_secs(30)));
+
+        let limited: anyhow::Error = TravelAiError::new(ErrorCode::RateLimited, "Slow down")
+            .with_context(RETRY_AFTER_KEY, 3600)
+            .into();
+        assert_eq!(retry.delay(&limited), Some(MAX_DELAY));
+    }
+}
@@ -0,0 +1,194 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`),
+//! evaluated in UTC.
+//!
+//! Fields take `*`, values, ranges (`1-5`), steps (`*/15`, `8-18/2`) and
+//! comma-separated lists of those. Day of week runs from 0 (Sunday) to 7
+//! (Sunday again). Like classic cron, a time matches when either day field
+//! matches if both are restricted.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc};
+
+/// How far ahead `next_after` searches before giving up, e.g. on `0 0 30 2 *`.
+const MAX_SEARCH_DAYS: i64 = 5 * 366;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl Cron {
+    /// First matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(MAX_SEARCH_DAYS);
+        while t <= limit {
+            if !bit(self.months, t.month()) || !self.matches_day(t) {
+                t = (t.date_naive() + Duration::days(1))
+                    .and_time(NaiveTime::MIN)
+                    .and_utc();
+            } else if !bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !bit(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, t: DateTime<Utc>) -> bool {
+        let dom = bit(self.days_of_month, t.day());
+        let dow = bit(self.days_of_week, t.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let expanded = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            bail!("Cron expression {s:?} needs five fields");
+        };
+
+        let mut days_of_week = field(dow, 0, 7).context("day of week")?;
+        // 7 is another name for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Cron {
+            source: s.trim().to_string(),
+            minutes: field(minute, 0, 59).context("minute")?,
+            hours: field(hour, 0, 23).context("hour")?,
+            days_of_month: field(dom, 1, 31).context("day of month")?,
+            months: field(month, 1, 12).context("month")?,
+            days_of_week,
+            any_day_of_month: dom == "*",
+            any_day_of_week: dow == "*",
+        })
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Bit set of the values `expr` selects within `min..=max`.
+fn field(expr: &str, min: u32, max: u32) -> Result<u64> {
+    let mut set = 0;
+    for part in expr.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>()?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("Step of {part:?} must be positive");
+        }
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse()?, end.parse()?),
+                // `5/15` means from 5 to the end in steps of 15
+                None if step > 1 => (range.parse()?, max),
+                None => {
+                    let value = range.parse()?;
+                    (value, value)
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            bail!("{part:?} is outside {min}-{max}");
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[rstest]
+    #[case("*/15 * * * *", at(2026, 6, 13, 10, 7), at(2026, 6, 13, 10, 15))]
+    #[case("0 */8 * * *", at(2026, 6, 13, 10, 0), at(2026, 6, 13, 16, 0))]
+    #[case("30 3 * * *", at(2026, 6, 13, 3, 30), at(2026, 6, 14, 3, 30))]
+    #[case("0 6 1 * *", at(2026, 12, 24, 0, 0), at(2027, 1, 1, 6, 0))]
+    // 2026-06-13 is a Saturday
+    #[case("0 7 * * 1-5", at(2026, 6, 13, 8, 0), at(2026, 6, 15, 7, 0))]
+    #[case("0 7 * * 7", at(2026, 6, 13, 8, 0), at(2026, 6, 14, 7, 0))]
+    #[case("@daily", at(2026, 6, 13, 8, 0), at(2026, 6, 14, 0, 0))]
+    fn next_after(
+        #[case] expr: &str,
+        #[case] after: DateTime<Utc>,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let cron: Cron = expr.parse().unwrap();
+        assert_eq!(cron.next_after(after), Some(expected));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 15th or any Monday
+        let cron: Cron = "0 0 15 * 1".parse().unwrap();
+        assert_eq!(
+            cron.next_after(at(2026, 6, 13, 0, 0)),
+            Some(at(2026, 6, 15, 0, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2026, 6, 15, 0, 0)),
+            Some(at(2026, 6, 22, 0, 0))
+        );
+    }
+
+    #[test]
+    fn impossible_date_never_fires() {
+        let cron: Cron = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(cron.next_after(at(2026, 1, 1, 0, 0)), None);
+    }
+
+    #[rstest]
+    #[case("* * * *")]
+    #[case("60 * * * *")]
+    #[case("*/0 * * * *")]
+    #[case("5-1 * * * *")]
+    #[case("0 0 0 * *")]
+    #[case("every hour")]
+    fn rejects_invalid_expressions(#[case] expr: &str) {
+        assert!(expr.parse::<Cron>().is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Area forecasts started by a request rather than a schedule. Each gets an
+//! id to poll for its progress and result, which is kept for an hour after it
+//! finished. Only a few run at once, since each may fetch the weather of
+//! hundreds of sites.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex, atomic::Ordering},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use rand::RngExt;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use super::{JOB_ATTEMPTS, failure};
+use crate::{
+    app_state::AppState,
+    application::{
+        area_forecast_job::{self, AreaForecastRequest, ParaglidingForecast, Progress},
+        retry::Retry,
+    },
+    domain::error::ErrorCode,
+};
+
+const MAX_RUNNING: u32 = 2;
+const KEEP_FINISHED: chrono::Duration = chrono::Duration::hours(1);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForecastJob {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    /// Null while running
+    pub duration_ms: Option<u64>,
+    pub sites_done: usize,
+    pub sites_total: usize,
+    #[serde(flatten)]
+    pub status: ForecastStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum ForecastStatus {
+    Running,
+    /// Sites that couldn't be scored are listed under `errors`
+    Ok(ParaglidingForecast),
+    Failed {
+        code: ErrorCode,
+        error: String,
+    },
+}
+
+/// As many forecasts as allowed are already running.
+#[derive(Debug)]
+pub struct Busy;
+
+struct Entry {
+    started_at: DateTime<Utc>,
+    progress: Arc<Progress>,
+    finished: Option<Finished>,
+}
+
+struct Finished {
+    at: DateTime<Utc>,
+    duration_ms: u64,
+    status: ForecastStatus,
+}
+
+#[derive(Clone)]
+pub(super) struct ForecastJobs {
+    entries: Arc<StdMutex<HashMap<String, Entry>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl ForecastJobs {
+    pub(super) fn new() -> Self {
+        Self {
+            entries: Arc::default(),
+            permits: Arc::new(Semaphore::new(MAX_RUNNING as usize)),
+        }
+    }
+
+    /// Starts the forecast in the background and returns its id.
+    pub(super) fn submit(
+        &self,
+        request: AreaForecastRequest,
+        state: &AppState,
+    ) -> Result<String, Busy> {
+        let permit = self.permits.clone().try_acquire_owned().map_err(|_| Busy)?;
+        let id = format!("{:016x}", rand::rng().random::<u64>());
+        let progress = Arc::new(Progress::default());
+        {
+            let mut entries = self.entries.lock().unwrap();
+            prune(&mut entries, Utc::now());
+            entries.insert(
+                id.clone(),
+                Entry {
+                    started_at: Utc::now(),
+                    progress: progress.clone(),
+                    finished: None,
+                },
+            );
+        }
+
+        let jobs = self.clone();
+        let state = state.clone();
+        let job_id = id.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let start = Instant::now();
+            let mut retry = Retry::new("area forecast", JOB_ATTEMPTS);
+            let outcome = loop {
+                match area_forecast_job::run(&state, &request, &progress).await {
+                    Err(e) if retry.wait(&e).await => continue,
+                    outcome => break outcome,
+                }
+            };
+            let status = match outcome {
+                Ok(forecast) => ForecastStatus::Ok(forecast),
+                Err(e) => {
+                    tracing::error!(id = job_id, error = ?e, "Area forecast failed");
+                    let (code, error) = failure(&e);
+                    ForecastStatus::Failed { code, error }
+                }
+            };
+            if let Some(entry) = jobs.entries.lock().unwrap().get_mut(&job_id) {
+                entry.finished = Some(Finished {
+                    at: Utc::now(),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    status,
+                });
+            }
+        });
+        Ok(id)
+    }
+
+    pub(super) fn get(&self, id: &str) -> Option<ForecastJob> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(id)?;
+        Some(ForecastJob {
+            id: id.to_string(),
+            started_at: entry.started_at,
+            duration_ms: entry.finished.as_ref().map(|f| f.duration_ms),
+            sites_done: entry.progress.sites_done.load(Ordering::Relaxed),
+            sites_total: entry.progress.sites_total.load(Ordering::Relaxed),
+            status: entry
+                .finished
+                .as_ref()
+                .map_or(ForecastStatus::Running, |f| f.status.clone()),
+        })
+    }
+
+    /// Waits up to `timeout` for running forecasts to finish.
+    pub(super) async fn drain(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, self.permits.acquire_many(MAX_RUNNING))
+            .await
+            .is_ok()
+    }
+}
+
+/// Drops forecasts that finished long enough ago.
+fn prune(entries: &mut HashMap<String, Entry>, now: DateTime<Utc>) {
+    entries.retain(|_, entry| {
+        entry
+            .finished
+            .as_ref()
+            .is_none_or(|f| now - f.at < KEEP_FINISHED)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(finished_minutes_ago: Option<i64>, now: DateTime<Utc>) -> Entry {
+        Entry {
+            started_at: now,
+            progress: Arc::default(),
+            finished: finished_minutes_ago.map(|minutes| Finished {
+                at: now - chrono::Duration::minutes(minutes),
+                duration_ms: 1,
+                status: ForecastStatus::Ok(ParaglidingForecast::default()),
+            }),
+        }
+    }
+
+    #[test]
+    fn finished_forecasts_are_kept_for_an_hour() {
+        let now = Utc::now();
+        let mut entries = HashMap::from([
+            ("running".to_string(), entry(None, now)),
+            ("recent".to_string(), entry(Some(59), now)),
+            ("old".to_string(), entry(Some(60), now)),
+        ]);
+
+        prune(&mut entries, now);
+
+        let mut ids: Vec<&str> = entries.keys().map(String::as_str).collect();
+        ids.sort();
+        assert_eq!(ids, ["recent", "running"]);
+    }
+
+    #[test]
+    fn running_forecast_serializes_its_progress() {
+        let job = ForecastJob {
+            id: "00ab".into(),
+            started_at: "2026-06-13T12:00:00Z".parse().unwrap(),
+            duration_ms: None,
+            sites_done: 40,
+            sites_total: 150,
+            status: ForecastStatus::Running,
+        };
+        assert_eq!(
+            serde_json::to_value(job).unwrap(),
+            serde_json::json!({
+                "id": "00ab",
+                "started_at": "2026-06-13T12:00:00Z",
+                "duration_ms": null,
+                "sites_done": 40,
+                "sites_total": 150,
+                "status": "running",
+            })
+        );
+    }
+}
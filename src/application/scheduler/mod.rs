@@ -0,0 +1,441 @@
+//! Runs the periodic jobs on their cron schedules, so no external cron is
+//! needed.
+//!
+//! Every run waits a random jitter first, so several instances don't hit the
+//! upstream APIs at the same second. A job whose previous run is still going
+//! skips its turn instead of running twice, whether it was started by its
+//! schedule or by hand. A run failing for a transient reason, e.g. Open-Meteo
+//! being down, is retried once after the delay its error asks for.
+//!
+//! Forecasts of a whole area are run here too when a request asks for them,
+//! see [`Scheduler::submit_forecast`].
+
+pub mod cron;
+pub mod forecasts;
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rand::RngExt;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::{
+    app_state::AppState,
+    application::{
+        area_forecast_job::AreaForecastRequest, calendar_job, chat_bot, club_job,
+        event_recheck_job, forecast_job, mqtt_job, retry::Retry, site_update_job, tandem_job,
+        token_check_job,
+    },
+    config::{ClubConfig, MqttConfig, SchedulerConfig, TandemConfig},
+    domain::{
+        activities::SiteError,
+        error::{ErrorCode, TravelAiError},
+    },
+};
+
+pub use cron::Cron;
+pub use forecasts::{Busy, ForecastJob, ForecastStatus};
+
+const JOB_ATTEMPTS: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    /// Fetches forecasts into the cache and publishes site states via MQTT
+    ForecastRefresh,
+    CalendarSync,
+    /// Imports new sites from DHV exports on disk
+    SiteUpdate,
+    /// Drops expired cache entries
+    CacheCleanup,
+    /// Fills the club calendar and tells members about tomorrow
+    ClubBroadcast,
+    /// Offers flyable time as bookable tandem slots
+    TandemSlots,
+    /// Cancels or shortens events about to start whose wind has turned
+    EventRecheck,
+    /// Emails a new sign-in link when the Google sign-in stopped working
+    TokenCheck,
+    /// Posts the outlook of the next days to the Discord channel and Matrix room
+    ChatUpdate,
+}
+
+impl Job {
+    pub const ALL: [Job; 9] = [
+        Job::ForecastRefresh,
+        Job::CalendarSync,
+        Job::SiteUpdate,
+        Job::CacheCleanup,
+        Job::ClubBroadcast,
+        Job::TandemSlots,
+        Job::EventRecheck,
+        Job::TokenCheck,
+        Job::ChatUpdate,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Job::ForecastRefresh => "forecast_refresh",
+            Job::CalendarSync => "calendar_sync",
+            Job::SiteUpdate => "site_update",
+            Job::CacheCleanup => "cache_cleanup",
+            Job::ClubBroadcast => "club_broadcast",
+            Job::TandemSlots => "tandem_slots",
+            Job::EventRecheck => "event_recheck",
+            Job::TokenCheck => "token_check",
+            Job::ChatUpdate => "chat_update",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Job> {
+        Job::ALL.into_iter().find(|job| job.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum RunResult {
+    Ok {
+        /// Sites the run left out because they couldn't be scored
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<SiteError>,
+    },
+    Failed {
+        /// The most specific code in the error's chain, else
+        /// `scheduler_job_failed`
+        code: ErrorCode,
+        error: String,
+    },
+}
+
+impl RunResult {
+    fn failed(error: &anyhow::Error) -> Self {
+        let (code, error) = failure(error);
+        RunResult::Failed { code, error }
+    }
+}
+
+/// The most specific code in the error's chain, else `scheduler_job_failed`,
+/// and the whole chain as text.
+fn failure(error: &anyhow::Error) -> (ErrorCode, String) {
+    (
+        TravelAiError::find(error).map_or(ErrorCode::SchedulerJobFailed, |e| e.code),
+        format!("{:#}", error),
+    )
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LastRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub result: RunResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    /// Cron expression; missing for jobs that only run when triggered
+    pub schedule: Option<String>,
+    pub running: bool,
+    pub last_run: Option<LastRun>,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// The previous run of the job is still in progress.
+#[derive(Debug)]
+pub struct AlreadyRunning;
+
+struct Slot {
+    job: Job,
+    cron: Option<Cron>,
+    running: Arc<Mutex<()>>,
+    last_run: StdMutex<Option<LastRun>>,
+    next_run: StdMutex<Option<DateTime<Utc>>>,
+}
+
+pub struct Scheduler {
+    slots: Vec<Slot>,
+    jitter: Duration,
+    site_import_dir: Option<PathBuf>,
+    mqtt: Option<MqttConfig>,
+    forecasts: forecasts::ForecastJobs,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig, mqtt: Option<MqttConfig>) -> Self {
+        let slots = Job::ALL
+            .into_iter()
+            .map(|job| Slot {
+                job,
+                cron: config
+                    .jobs
+                    .iter()
+                    .find(|(j, _)| *j == job)
+                    .map(|(_, cron)| cron.clone()),
+                running: Arc::new(Mutex::new(())),
+                last_run: StdMutex::new(None),
+                next_run: StdMutex::new(None),
+            })
+            .collect();
+        Self {
+            slots,
+            jitter: config.jitter,
+            site_import_dir: config.site_import_dir,
+            mqtt,
+            forecasts: forecasts::ForecastJobs::new(),
+        }
+    }
+
+    /// Runs every scheduled job until the process exits.
+    pub async fn run(self: Arc<Self>, state: AppState) {
+        let loops = self
+            .slots
+            .iter()
+            .filter_map(|slot| Some((slot.job, slot.cron.clone()?)))
+            .map(|(job, cron)| {
+                tracing::info!(job = job.name(), schedule = %cron, "Scheduled job");
+                self.clone().run_job_loop(job, cron, state.clone())
+            });
+        futures::future::join_all(loops).await;
+    }
+
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.slots
+            .iter()
+            .map(|slot| JobStatus {
+                name: slot.job.name(),
+                schedule: slot.cron.as_ref().map(Cron::to_string),
+                running: slot.running.try_lock().is_err(),
+                last_run: slot.last_run.lock().unwrap().clone(),
+                next_run: *slot.next_run.lock().unwrap(),
+            })
+            .collect()
+    }
+
+    /// Starts `job` in the background right away, scheduled or not.
+    pub fn trigger(self: &Arc<Self>, job: Job, state: &AppState) -> Result<(), AlreadyRunning> {
+        self.start(job, state.clone())
+    }
+
+    /// Starts scoring the sites of an area in the background and returns the
+    /// id to poll [`Scheduler::forecast`] with.
+    pub fn submit_forecast(
+        &self,
+        request: AreaForecastRequest,
+        state: &AppState,
+    ) -> Result<String, Busy> {
+        self.forecasts.submit(request, state)
+    }
+
+    /// Progress of a submitted forecast, and its sites once it is done.
+    pub fn forecast(&self, id: &str) -> Option<ForecastJob> {
+        self.forecasts.get(id)
+    }
+
+    /// Waits up to `timeout` for running jobs and forecasts to finish, for a
+    /// clean shutdown once the schedules and the web server have stopped.
+    /// Returns whether all of them did.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        let all_idle = futures::future::join_all(self.slots.iter().map(|slot| slot.running.lock()));
+        let jobs_idle = tokio::time::timeout(timeout, all_idle).await.is_ok();
+        jobs_idle && self.forecasts.drain(timeout).await
+    }
+
+    async fn run_job_loop(self: Arc<Self>, job: Job, cron: Cron, state: AppState) {
+        loop {
+            let now = Utc::now();
+            let next = cron.next_after(now);
+            *self.slot(job).next_run.lock().unwrap() = next;
+            let Some(next) = next else {
+                tracing::warn!(job = job.name(), schedule = %cron, "Schedule never fires again");
+                return;
+            };
+            let delay = (next - now).to_std().unwrap_or_default() + jitter(self.jitter);
+            tokio::time::sleep(delay).await;
+
+            if self.start(job, state.clone()).is_err() {
+                tracing::warn!(
+                    job = job.name(),
+                    "Previous run is still in progress, skipping this one"
+                );
+            }
+        }
+    }
+
+    fn start(self: &Arc<Self>, job: Job, state: AppState) -> Result<(), AlreadyRunning> {
+        let guard = self
+            .slot(job)
+            .running
+            .clone()
+            .try_lock_owned()
+            .map_err(|_| AlreadyRunning)?;
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            let started_at = Utc::now();
+            let start = Instant::now();
+            let mut retry = Retry::new(job.name(), JOB_ATTEMPTS);
+            let outcome = loop {
+                match scheduler.execute(job, &state).await {
+                    Err(e) if retry.wait(&e).await => continue,
+                    outcome => break outcome,
+                }
+            };
+            let result = match outcome {
+                Ok(errors) => RunResult::Ok { errors },
+                Err(e) => {
+                    tracing::error!(job = job.name(), error = ?e, "Job failed");
+                    RunResult::failed(&e)
+                }
+            };
+            *scheduler.slot(job).last_run.lock().unwrap() = Some(LastRun {
+                started_at,
+                duration_ms: start.elapsed().as_millis() as u64,
+                result,
+            });
+        });
+        Ok(())
+    }
+
+    fn slot(&self, job: Job) -> &Slot {
+        self.slots
+            .iter()
+            .find(|slot| slot.job == job)
+            .expect("every job has a slot")
+    }
+
+    /// Runs `job` once, returning the sites it couldn't score.
+    #[tracing::instrument(skip(self, state), fields(job = job.name()))]
+    async fn execute(&self, job: Job, state: &AppState) -> Result<Vec<SiteError>> {
+        match job {
+            Job::ForecastRefresh => {
+                forecast_job::run(state).await?;
+                if let Some(mqtt) = &self.mqtt {
+                    mqtt_job::run(state, mqtt)
+                        .await
+                        .context("Failed to publish site states via MQTT")?;
+                }
+            }
+            Job::CalendarSync => return calendar_job::run(state).await,
+            Job::SiteUpdate => {
+                let dir = self.site_import_dir.clone().ok_or_else(|| {
+                    TravelAiError::new(ErrorCode::JobNotConfigured, "SITE_IMPORT_DIR is not set")
+                })?;
+                site_update_job::run(state, dir).await?;
+            }
+            Job::CacheCleanup => {
+                let removed = state.cache.remove_expired().await?;
+                tracing::info!(removed, "Removed expired cache entries");
+            }
+            Job::ClubBroadcast => {
+                let club = ClubConfig::load()?.ok_or_else(|| {
+                    TravelAiError::new(ErrorCode::JobNotConfigured, "CLUB_MEMBERS is not set")
+                })?;
+                club_job::run(state, &club, self.mqtt.as_ref()).await?;
+            }
+            Job::TandemSlots => {
+                let tandem = TandemConfig::load()?.ok_or_else(|| {
+                    TravelAiError::new(
+                        ErrorCode::JobNotConfigured,
+                        "TANDEM_CALENDAR_NAME is not set",
+                    )
+                })?;
+                return tandem_job::run(state, &tandem).await;
+            }
+            Job::EventRecheck => event_recheck_job::run(state).await?,
+            Job::TokenCheck => token_check_job::run(state).await?,
+            Job::ChatUpdate => chat_bot::post_update(state).await?,
+        }
+        Ok(vec![])
+    }
+}
+
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    max.mul_f64(rand::rng().random_range(0.0..1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_below_the_maximum() {
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+        for _ in 0..100 {
+            assert!(jitter(Duration::from_secs(60)) < Duration::from_secs(60));
+        }
+    }
+
+    #[test]
+    fn job_names_round_trip() {
+        for job in Job::ALL {
+            assert_eq!(Job::from_name(job.name()), Some(job));
+        }
+        assert_eq!(Job::from_name("backup"), None);
+    }
+
+    #[test]
+    fn unscheduled_jobs_are_listed_without_schedule() {
+        let config = SchedulerConfig {
+            jobs: vec![(Job::CacheCleanup, "30 3 * * *".parse().unwrap())],
+            jitter: Duration::ZERO,
+            site_import_dir: None,
+        };
+        let statuses = Scheduler::new(config, None).statuses();
+
+        assert_eq!(statuses.len(), Job::ALL.len());
+        let cleanup = statuses.iter().find(|s| s.name == "cache_cleanup").unwrap();
+        assert_eq!(cleanup.schedule.as_deref(), Some("30 3 * * *"));
+        let sync = statuses.iter().find(|s| s.name == "calendar_sync").unwrap();
+        assert!(sync.schedule.is_none() && !sync.running && sync.last_run.is_none());
+    }
+
+    #[test]
+    fn failed_run_serializes_its_error() {
+        let json = serde_json::to_value(RunResult::failed(&anyhow::anyhow!("boom"))).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"status": "failed", "code": "scheduler_job_failed", "error": "boom"})
+        );
+    }
+
+    #[test]
+    fn ok_run_lists_sites_only_if_some_were_left_out() {
+        let ok = |errors| serde_json::to_value(RunResult::Ok { errors }).unwrap();
+        assert_eq!(ok(vec![]), serde_json::json!({"status": "ok"}));
+        let error = SiteError {
+            site: "Wank".into(),
+            code: ErrorCode::WeatherUnavailable,
+            error: "timed out".into(),
+        };
+        assert_eq!(
+            ok(vec![error])["errors"][0]["site"],
+            serde_json::json!("Wank")
+        );
+    }
+
+    #[test]
+    fn failed_run_keeps_the_code_of_a_typed_error() {
+        let error = anyhow::Error::new(TravelAiError::new(
+            ErrorCode::CalendarAuthExpired,
+            "Google access expired, sign in again",
+        ))
+        .context("Calendar sync failed");
+        let RunResult::Failed { code, error } = RunResult::failed(&error) else {
+            panic!("expected a failed run");
+        };
+        assert_eq!(code, ErrorCode::CalendarAuthExpired);
+        assert_eq!(
+            error,
+            "Calendar sync failed: Google access expired, sign in again"
+        );
+    }
+}
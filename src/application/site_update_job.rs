@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::{
+    adapters::activities::paragliding::dhv::DhvParaglidingSiteProvider, app_state::AppState,
+    domain::paragliding::ParaglidingSiteProvider,
+};
+
+/// Adds the sites of the DHV XML exports in `dir` that aren't stored yet.
+/// Stored sites are left alone, so edits made in the app survive.
+#[tracing::instrument(skip(state))]
+pub async fn run(state: &AppState, dir: PathBuf) -> Result<usize> {
+    let provider =
+        tokio::task::spawn_blocking(move || DhvParaglidingSiteProvider::new(dir)).await??;
+
+    let mut added = 0;
+    for site in provider.fetch_all_sites().await {
+        if state.site_repo.get_site(&site.name).await?.is_some() {
+            continue;
+        }
+        state.site_repo.save_site(site).await?;
+        added += 1;
+    }
+    tracing::info!(added, "Site update complete");
+    Ok(added)
+}
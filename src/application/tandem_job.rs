@@ -0,0 +1,536 @@
+//! Tandem mode: splits the flyable windows of the operator's sites into
+//! bookable slots with a buffer after each and offers them in a calendar of
+//! their own, leaving out the slots the operator is busy in privately.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::{
+    adapters::activities::paragliding::{
+        evaluation_cache::EvaluationCache, outlook, site_evaluator::DailySummary,
+    },
+    app_state::AppState,
+    application::calendar_job,
+    config::{CalendarConfig, TandemConfig},
+    domain::{
+        activities::SiteError,
+        calendar::CalendarEvent,
+        error::TravelAiError,
+        paragliding::{ParaglidingSite, ParaglidingSiteProvider},
+        ports::{CalendarProvider, WeatherProvider},
+    },
+};
+
+/// Days ahead slots are offered for
+const HORIZON_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Slot {
+    site: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+/// The tandem sites' days, and the sites left out because they couldn't be
+/// scored.
+#[derive(Debug, Default)]
+struct TandemDays {
+    flyable: Vec<(String, DailySummary)>,
+    /// Every site and day that was scored, flyable or not
+    scored: BTreeSet<(String, NaiveDate)>,
+    errors: Vec<SiteError>,
+}
+
+/// Offers the slots and returns the sites left out because they couldn't be
+/// scored. Fails without touching the calendar if no site could be scored.
+#[tracing::instrument(skip_all, fields(slot_count = tracing::field::Empty))]
+pub async fn run(state: &AppState, config: &TandemConfig) -> Result<Vec<SiteError>> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let days = tandem_days(
+        state.weather.as_ref(),
+        &state.evaluations,
+        state.site_repo.fetch_all_sites().await,
+        config,
+        settings.minimum_flyable_hours,
+    )
+    .await;
+    if days.scored.is_empty()
+        && let Some(first) = days.errors.first()
+    {
+        return Err(TravelAiError::new(
+            first.code,
+            format!(
+                "Failed to get the forecast of all {} tandem sites",
+                days.errors.len()
+            ),
+        )
+        .into());
+    }
+    let slots = plan_slots(days.flyable, config.slot, config.buffer, Utc::now());
+
+    let mut calendars = Vec::new();
+    let mut last_error = None;
+    let calendar_config = CalendarConfig::load()?;
+    for &backend in &calendar_config.backends {
+        match calendar_job::open_calendar(state, &calendar_config, backend).await {
+            Ok(cal) => calendars.push((backend, cal)),
+            Err(e) => {
+                tracing::error!(error = ?e, ?backend, "Failed to open calendar");
+                last_error = Some(e);
+            }
+        }
+    }
+    let Some((_, primary)) = calendars.first() else {
+        let error = last_error.unwrap_or_else(|| anyhow::anyhow!("No calendar backend"));
+        return Err(error.context("No calendar backend available"));
+    };
+
+    let busy_calendars = if config.busy_calendars.is_empty() {
+        let mut names = primary.get_calendar_names().await?;
+        names.retain(|n| *n != config.calendar_name && *n != settings.calendar_name);
+        names
+    } else {
+        config.busy_calendars.clone()
+    };
+    let events: Vec<CalendarEvent> = free_slots(primary.as_ref(), &busy_calendars, &slots)
+        .await?
+        .into_iter()
+        .map(slot_event)
+        .collect();
+    tracing::Span::current().record("slot_count", events.len());
+
+    let today = Utc::now().date_naive();
+    let mut written = 0;
+    for (backend, cal) in &mut calendars {
+        let offered = offer_slots(
+            cal.as_mut(),
+            &config.calendar_name,
+            &events,
+            &days.scored,
+            today,
+        )
+        .await;
+        match offered {
+            Ok(()) => written += 1,
+            Err(e) => {
+                tracing::error!(error = ?e, ?backend, "Failed to write tandem slots");
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if written == 0 => Err(e.context("Failed to write the tandem calendar")),
+        _ => {
+            tracing::info!(
+                slots = events.len(),
+                skipped = slots.len() - events.len(),
+                calendar = %config.calendar_name,
+                "Offered tandem slots"
+            );
+            Ok(days.errors)
+        }
+    }
+}
+
+/// The slots the operator isn't busy in, from one query spanning all of them.
+async fn free_slots<'a>(
+    cal: &dyn CalendarProvider,
+    busy_calendars: &[String],
+    slots: &'a [Slot],
+) -> Result<Vec<&'a Slot>> {
+    let (Some(start), Some(end)) = (
+        slots.iter().map(|s| s.start).min(),
+        slots.iter().map(|s| s.end).max(),
+    ) else {
+        return Ok(vec![]);
+    };
+    let busy = cal.busy_periods(busy_calendars, start, end).await?;
+    Ok(slots
+        .iter()
+        .filter(|slot| {
+            !busy
+                .iter()
+                .any(|(from, to)| slot.start < *to && slot.end > *from)
+        })
+        .collect())
+}
+
+/// Adds the slots not offered yet and withdraws upcoming ones that are no
+/// longer free. Offered slots are left as they are, so a booking noted in one
+/// survives, and so are past slots and events the operator added. Only slots
+/// of a site and day in `scored` are withdrawn, so one whose weather couldn't
+/// be fetched stays.
+async fn offer_slots(
+    cal: &mut dyn CalendarProvider,
+    calendar: &str,
+    events: &[CalendarEvent],
+    scored: &BTreeSet<(String, NaiveDate)>,
+    today: NaiveDate,
+) -> Result<()> {
+    cal.create_calendar(calendar).await?;
+    let offered: Vec<String> = cal
+        .event_keys(calendar)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+    let withdrawn: Vec<String> = offered
+        .iter()
+        .filter(|key| {
+            slot_day(key).is_some_and(|(site, date)| {
+                date >= today && scored.contains(&(site.to_string(), date))
+            })
+        })
+        .filter(|key| !events.iter().any(|e| e.key.as_ref() == Some(key)))
+        .cloned()
+        .collect();
+    cal.delete_events(calendar, &withdrawn).await?;
+    let new: Vec<CalendarEvent> = events
+        .iter()
+        .filter(|e| e.key.as_ref().is_some_and(|key| !offered.contains(key)))
+        .cloned()
+        .collect();
+    cal.upsert_events(calendar, new).await
+}
+
+/// Days of the tandem sites, or of the favorites if none are named.
+async fn tandem_days(
+    weather: &dyn WeatherProvider,
+    evaluations: &EvaluationCache,
+    sites: Vec<ParaglidingSite>,
+    config: &TandemConfig,
+    min_hours: u32,
+) -> TandemDays {
+    let mut days = TandemDays::default();
+    for site in sites {
+        let chosen = if config.sites.is_empty() {
+            site.favorite == Some(true)
+        } else {
+            config.sites.contains(&site.name)
+        };
+        if !chosen {
+            continue;
+        }
+        let Some(launch) = site.launches.first() else {
+            continue;
+        };
+        let forecast = match outlook::site_forecast(weather, &site, launch).await {
+            Ok(forecast) => forecast,
+            Err(e) => {
+                days.errors.push(e);
+                continue;
+            }
+        };
+        let eval = evaluations.evaluate(&site, &forecast);
+        for day in &eval.daily_summaries {
+            days.scored.insert((site.name.clone(), day.date));
+            if day.is_flyable_day(min_hours) {
+                days.flyable.push((site.name.clone(), day.clone()));
+            }
+        }
+    }
+    days
+}
+
+/// Slots in the windows of each day's best site, since the operator can only
+/// be at one. Slots already started or past the horizon are left out.
+fn plan_slots(
+    days: Vec<(String, DailySummary)>,
+    slot: Duration,
+    buffer: Duration,
+    now: DateTime<Utc>,
+) -> Vec<Slot> {
+    let mut best: BTreeMap<NaiveDate, (String, DailySummary)> = BTreeMap::new();
+    for (site, day) in days {
+        let better = best
+            .get(&day.date)
+            .is_none_or(|(_, current)| day.total_flyable_hours > current.total_flyable_hours);
+        if better {
+            best.insert(day.date, (site, day));
+        }
+    }
+
+    let horizon = now + Duration::days(HORIZON_DAYS);
+    best.into_values()
+        .flat_map(|(site, day)| {
+            day.ranges
+                .iter()
+                .flat_map(|range| {
+                    let window = range.window();
+                    split(window.start, window.end, slot, buffer)
+                })
+                .filter(|(start, _)| *start >= now && *start < horizon)
+                .map(|(start, end)| Slot {
+                    site: site.clone(),
+                    start,
+                    end,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Back to back slots of length `slot` from `start`, each followed by
+/// `buffer`, as long as the slot itself ends by `end`.
+fn split(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    slot: Duration,
+    buffer: Duration,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut slots = Vec::new();
+    let mut at = start;
+    while at + slot <= end {
+        slots.push((at, at + slot));
+        at = at + slot + buffer;
+    }
+    slots
+}
+
+fn slot_event(slot: &Slot) -> CalendarEvent {
+    CalendarEvent {
+        title: format!("Free tandem slot: {}", slot.site),
+        start_time: slot.start,
+        end_time: slot.end,
+        is_all_day: false,
+        location: Some(slot.site.clone()),
+        body: Some(format!(
+            "Bookable tandem flight at {}, {} - {} UTC",
+            slot.site,
+            slot.start.format("%H:%M"),
+            slot.end.format("%H:%M")
+        )),
+        color: None,
+        reminder_minutes: vec![],
+        key: Some(format!(
+            "{}/{}/{}",
+            slot.start.date_naive(),
+            slot.site,
+            slot.start.format("%H%M")
+        )),
+        site: Some(slot.site.clone()),
+    }
+}
+
+/// The site and day of a slot from its event key.
+fn slot_day(key: &str) -> Option<(&str, NaiveDate)> {
+    let (date, rest) = key.split_once('/')?;
+    let (site, _time) = rest.rsplit_once('/')?;
+    Some((site, date.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::{
+            activities::paragliding::site_evaluator::FlyableRange,
+            in_memory_calendar::InMemoryCalendar,
+        },
+        domain::{
+            error::ErrorCode,
+            location::Location,
+            paragliding::{ParaglidingLaunch, SiteType},
+            ports::{MockCalendarProvider, MockWeatherProvider},
+        },
+    };
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, day, hour, minute, 0).unwrap()
+    }
+
+    fn day(date: u32, first: u32, last: u32) -> DailySummary {
+        DailySummary {
+            date: NaiveDate::from_ymd_opt(2026, 6, date).unwrap(),
+            ranges: vec![FlyableRange {
+                start: at(date, first, 0),
+                end: at(date, last, 0),
+            }],
+            total_flyable_hours: (last - first + 1) as usize,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn slots_leave_a_buffer_and_fit_the_window() {
+        let slots = split(
+            at(13, 10, 0),
+            at(13, 14, 0),
+            Duration::minutes(60),
+            Duration::minutes(30),
+        );
+        assert_eq!(
+            slots,
+            vec![
+                (at(13, 10, 0), at(13, 11, 0)),
+                (at(13, 11, 30), at(13, 12, 30)),
+                (at(13, 13, 0), at(13, 14, 0)),
+            ]
+        );
+        assert!(
+            split(
+                at(13, 10, 0),
+                at(13, 10, 30),
+                Duration::minutes(60),
+                Duration::zero()
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn only_the_best_site_of_a_day_gets_slots() {
+        let days = vec![
+            ("Short".to_string(), day(13, 10, 11)),
+            ("Long".to_string(), day(13, 12, 15)),
+            ("Short".to_string(), day(14, 10, 10)),
+        ];
+        let slots = plan_slots(days, Duration::hours(1), Duration::zero(), at(13, 12, 30));
+
+        let planned: Vec<(&str, DateTime<Utc>)> =
+            slots.iter().map(|s| (s.site.as_str(), s.start)).collect();
+        assert_eq!(
+            planned,
+            vec![
+                ("Long", at(13, 13, 0)),
+                ("Long", at(13, 14, 0)),
+                ("Long", at(13, 15, 0)),
+                ("Short", at(14, 10, 0)),
+            ]
+        );
+        assert_eq!(
+            slot_event(&slots[0]).key.as_deref(),
+            Some("2026-06-13/Long/1300")
+        );
+    }
+
+    fn slot(day: u32, hour: u32) -> Slot {
+        Slot {
+            site: "Wank".into(),
+            start: at(day, hour, 0),
+            end: at(day, hour + 1, 0),
+        }
+    }
+
+    #[tokio::test]
+    async fn busy_times_are_queried_once_for_all_slots() {
+        let slots = vec![slot(13, 10), slot(13, 12), slot(14, 10)];
+        let mut cal = MockCalendarProvider::new();
+        cal.expect_busy_periods()
+            .times(1)
+            .withf(|_, start, end| *start == at(13, 10, 0) && *end == at(14, 11, 0))
+            .returning(|_, _, _| Ok(vec![(at(13, 11, 30), at(13, 12, 30))]));
+
+        let free = free_slots(&cal, &["Private".into()], &slots).await.unwrap();
+
+        assert_eq!(free, vec![&slots[0], &slots[2]]);
+    }
+
+    #[tokio::test]
+    async fn offered_slots_bookings_and_past_slots_are_kept() {
+        let booked = CalendarEvent {
+            title: "Booked: Anna".into(),
+            ..slot_event(&slot(14, 10))
+        };
+        let phone_booking = CalendarEvent {
+            key: None,
+            site: None,
+            title: "Tandem with Ben".into(),
+            ..slot_event(&slot(14, 14))
+        };
+        let mut cal = InMemoryCalendar::new()
+            .with_event("Tandem", slot_event(&slot(12, 10)))
+            .with_event("Tandem", booked.clone())
+            .with_event("Tandem", phone_booking.clone())
+            .with_event("Tandem", slot_event(&slot(15, 10)));
+
+        let events = vec![slot_event(&slot(14, 10)), slot_event(&slot(14, 12))];
+        let scored = (12..=15)
+            .map(|day| ("Wank".to_string(), at(day, 0, 0).date_naive()))
+            .collect();
+        offer_slots(
+            &mut cal,
+            "Tandem",
+            &events,
+            &scored,
+            at(13, 0, 0).date_naive(),
+        )
+        .await
+        .unwrap();
+
+        let titles: Vec<&str> = cal
+            .events("Tandem")
+            .iter()
+            .map(|e| e.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Free tandem slot: Wank",
+                "Booked: Anna",
+                "Tandem with Ben",
+                "Free tandem slot: Wank",
+            ]
+        );
+        assert_eq!(cal.events("Tandem")[0].start_time, at(12, 10, 0));
+        assert_eq!(cal.events("Tandem")[3].start_time, at(14, 12, 0));
+    }
+
+    #[tokio::test]
+    async fn slots_survive_a_weather_outage() {
+        let site = ParaglidingSite {
+            name: "Wank".into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(47.5, 11.1, "Wank".into(), "DE".into()),
+                direction_degrees_start: 180.0,
+                direction_degrees_stop: 270.0,
+                elevation: 1750.0,
+            }],
+            data_source: "test".into(),
+            favorite: Some(true),
+            ..Default::default()
+        };
+        let config = TandemConfig {
+            calendar_name: "Tandem".into(),
+            slot: Duration::hours(1),
+            buffer: Duration::zero(),
+            sites: vec![],
+            busy_calendars: vec![],
+        };
+        let mut weather = MockWeatherProvider::new();
+        weather.expect_get_forecast().returning(|_, _| {
+            Err(TravelAiError::new(ErrorCode::WeatherUnavailable, "Open-Meteo is down").into())
+        });
+
+        let days = tandem_days(
+            &weather,
+            &EvaluationCache::default(),
+            vec![site],
+            &config,
+            2,
+        )
+        .await;
+        assert!(days.scored.is_empty());
+        assert_eq!(days.errors.len(), 1);
+        assert_eq!(days.errors[0].site, "Wank");
+
+        let mut cal = InMemoryCalendar::new()
+            .with_event("Tandem", slot_event(&slot(14, 10)))
+            .with_event("Tandem", slot_event(&slot(15, 10)));
+        offer_slots(
+            &mut cal,
+            "Tandem",
+            &[],
+            &days.scored,
+            at(13, 0, 0).date_naive(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(cal.events("Tandem").len(), 2);
+    }
+}
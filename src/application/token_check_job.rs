@@ -0,0 +1,76 @@
+//! Watches the Google sign-in, since a revoked refresh token otherwise only
+//! shows as syncs failing. If signing in again is needed, the pilot is
+//! emailed a fresh authorization link, again once a day until they do.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    adapters::email,
+    app_state::AppState,
+    application::retry::Retry,
+    config::{self, CalendarBackend, CalendarConfig},
+};
+
+/// When the last sign-in link was sent, so it isn't sent every run
+const NOTIFIED_KEY: &str = "token_check_notified_at";
+const REMINDER_INTERVAL: Duration = Duration::hours(24);
+const EMAIL_ATTEMPTS: u32 = 3;
+
+#[tracing::instrument(skip_all, fields(health = tracing::field::Empty))]
+pub async fn run(state: &AppState) -> Result<()> {
+    // A service account signs itself in, there is nobody to send a link to
+    if state.service_account.is_some()
+        || !CalendarConfig::load()?
+            .backends
+            .contains(&CalendarBackend::Google)
+    {
+        return Ok(());
+    }
+    let health = state.auth.check().await?;
+    tracing::Span::current().record("health", tracing::field::debug(health));
+    let Some(problem) = health.problem() else {
+        return state.store.remove(NOTIFIED_KEY).await;
+    };
+
+    let now = Utc::now();
+    let notified_at: Option<DateTime<Utc>> = state.store.get(NOTIFIED_KEY).await?;
+    if !reminder_due(notified_at, now) {
+        tracing::info!(problem, "Google sign-in needed, link already sent");
+        return Ok(());
+    }
+
+    let to = config::var("NOTIFICATION_EMAIL").context("NOTIFICATION_EMAIL is not set")?;
+    let (url, _) = state.auth.build_authorization_url();
+    let body = format!(
+        "Calendar syncs will fail because {problem}. Sign in again here:\n\n{url}\n\nAfter granting access you'll be redirected back."
+    );
+    let mut retry = Retry::new("sign-in email", EMAIL_ATTEMPTS);
+    loop {
+        match email::send(&to, "Google Calendar needs signing in again", &body).await {
+            Err(e) if retry.wait(&e).await => continue,
+            result => break result,
+        }
+    }
+    .context("Failed to send sign-in link")?;
+    tracing::warn!(problem, "Sent a new Google sign-in link");
+    state.store.put(NOTIFIED_KEY, now).await
+}
+
+fn reminder_due(notified_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    notified_at.is_none_or(|at| now - at >= REMINDER_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn link_is_sent_again_once_a_day() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 13, 12, 0, 0).unwrap();
+        assert!(reminder_due(None, now));
+        assert!(!reminder_due(Some(now - Duration::hours(23)), now));
+        assert!(reminder_due(Some(now - Duration::hours(24)), now));
+    }
+}
@@ -0,0 +1,937 @@
+pub mod profile;
+pub mod schema;
+
+use std::{
+    collections::HashMap, env, ffi::OsString, fs, num::NonZeroU64, path::PathBuf, str::FromStr,
+    sync::OnceLock, time::Duration,
+};
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+
+use crate::{
+    application::scheduler::{Cron, Job},
+    config::profile::{ConfigFile, Settings},
+    domain::paragliding::{
+        ParaglidingSite, UserSettings,
+        closure::Closure,
+        club::{ClubMember, Contact, SkillLevel},
+    },
+};
+
+const DEFAULT_CONFIG_FILE: &str = "travelai.toml";
+
+static PROFILE: OnceLock<Settings> = OnceLock::new();
+
+/// Reads the setting `name` from the environment or else from the active
+/// profile of the config file.
+pub fn var(name: &str) -> Result<String, env::VarError> {
+    match env::var(name) {
+        Err(env::VarError::NotPresent) => PROFILE
+            .get()
+            .and_then(|settings| settings.get(name).cloned())
+            .ok_or(env::VarError::NotPresent),
+        result => result,
+    }
+}
+
+/// The config file named by `TRAVELAI_CONFIG`, else `travelai.toml` if there
+/// is one.
+pub fn config_file_path() -> Option<PathBuf> {
+    match env::var_os("TRAVELAI_CONFIG") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|p| p.exists()),
+    }
+}
+
+/// Makes `profile` (or `TRAVELAI_PROFILE`) of the config file the fallback
+/// for [`var`]. Must run before any other configuration is loaded. Returns
+/// the name of the active profile.
+pub fn load_profile(profile: Option<String>) -> Result<Option<String>> {
+    let profile = profile.or_else(|| env::var("TRAVELAI_PROFILE").ok());
+    let Some(path) = config_file_path() else {
+        if let Some(profile) = profile {
+            anyhow::bail!(
+                "Profile `{profile}` selected, but there is no config file; set TRAVELAI_CONFIG"
+            );
+        }
+        return Ok(None);
+    };
+    let settings = ConfigFile::read(&path)?.resolve(profile.as_deref())?;
+    PROFILE
+        .set(settings)
+        .map_err(|_| anyhow::anyhow!("Config profile loaded twice"))?;
+    Ok(profile)
+}
+
+/// Parses the setting `name` if it is set.
+fn parse_var<T: FromStr>(name: &str) -> Result<Option<T>> {
+    var(name)
+        .ok()
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid {name} {value:?}"))
+        })
+        .transpose()
+}
+
+fn list_var(name: &str) -> Option<Vec<String>> {
+    var(name).ok().map(|value| {
+        value
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect()
+    })
+}
+
+/// Reads the secret `name` from the environment or, as with Docker and systemd
+/// credentials, from the file `<name>_FILE` points to.
+pub fn secret(name: &str) -> Option<String> {
+    read_secret(
+        var(name).ok(),
+        var(&format!("{name}_FILE")).ok().map(OsString::from),
+    )
+}
+
+fn read_secret(value: Option<String>, file: Option<OsString>) -> Option<String> {
+    if value.is_some() {
+        return value;
+    }
+    let path = file?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => Some(contents.trim_end_matches(['\r', '\n']).to_string()),
+        Err(e) => {
+            tracing::error!(path = ?path, error = ?e, "Failed to read secret file");
+            None
+        }
+    }
+}
+
+pub struct WebConfig {
+    pub port: u16,
+    /// Origins allowed to call the API cross-origin; empty means any origin
+    pub cors_allowed_origins: Vec<String>,
+    #[cfg(feature = "tls")]
+    pub tls_config_path: (String, String),
+}
+
+impl WebConfig {
+    pub fn load() -> Result<Self> {
+        let port = var("PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(8080);
+
+        let cors_allowed_origins = var("CORS_ALLOWED_ORIGINS")
+            .map(|origins| {
+                origins
+                    .split(',')
+                    .map(|o| o.trim().to_string())
+                    .filter(|o| !o.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(WebConfig {
+            port: port,
+            cors_allowed_origins,
+            #[cfg(feature = "tls")]
+            tls_config_path: (var("TLS_CERT_PATH")?, var("TLS_KEY_PATH")?),
+        })
+    }
+}
+
+pub struct ForecastConfig {
+    /// Days of forecast to fetch; anything past a week comes from GFS
+    pub days: u8,
+}
+
+impl ForecastConfig {
+    pub fn load() -> Self {
+        let days = var("FORECAST_DAYS")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(7);
+
+        ForecastConfig { days }
+    }
+}
+
+/// Ensemble forecasts behind the chances of flyable weather, fetched only if
+/// a model is set, e.g. `icon_seamless` or `ecmwf_ifs025`.
+pub struct EnsembleConfig {
+    pub model: Option<String>,
+}
+
+impl EnsembleConfig {
+    pub fn load() -> Self {
+        EnsembleConfig {
+            model: var("ENSEMBLE_MODEL").ok().filter(|m| !m.is_empty()),
+        }
+    }
+}
+
+/// How flyable hours are joined into windows; both default to 1, which makes
+/// every run of flyable hours a window of its own.
+pub struct WindowConfig {
+    /// Flyable hours in a row that open a window
+    pub open_hours: u32,
+    /// Unflyable hours in a row that close one
+    pub close_hours: u32,
+}
+
+impl WindowConfig {
+    pub fn load() -> Self {
+        let hours = |name| {
+            var(name)
+                .ok()
+                .and_then(|h| h.parse::<u32>().ok())
+                .unwrap_or(1)
+                .max(1)
+        };
+        WindowConfig {
+            open_hours: hours("WINDOW_OPEN_HOURS"),
+            close_hours: hours("WINDOW_CLOSE_HOURS"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ApiLimitsConfig {
+    /// Largest search radius the API accepts
+    pub max_radius_km: f64,
+}
+
+impl ApiLimitsConfig {
+    pub fn load() -> Self {
+        let max_radius_km = var("MAX_SEARCH_RADIUS_KM")
+            .ok()
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(500.0);
+
+        ApiLimitsConfig { max_radius_km }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    /// Requests per minute for clients without a listed key; None disables
+    /// rate limiting
+    pub per_minute: Option<u32>,
+    /// Requests per minute for individual API keys
+    pub keys: HashMap<String, u32>,
+}
+
+impl RateLimitConfig {
+    /// `RATE_LIMIT_PER_MINUTE` (default 120, `off` to disable) and
+    /// `RATE_LIMIT_KEYS` as comma-separated `key=per_minute` pairs.
+    pub fn load() -> Result<Self> {
+        let per_minute = match var("RATE_LIMIT_PER_MINUTE") {
+            Ok(value) if value.trim() == "off" => None,
+            Ok(value) => Some(
+                value
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Invalid RATE_LIMIT_PER_MINUTE {value:?}"))?,
+            ),
+            Err(_) => Some(120),
+        };
+        let keys = match var("RATE_LIMIT_KEYS") {
+            Ok(value) => parse_key_limits(&value)?,
+            Err(_) => HashMap::new(),
+        };
+        Ok(RateLimitConfig { per_minute, keys })
+    }
+}
+
+fn parse_key_limits(value: &str) -> Result<HashMap<String, u32>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, limit) = pair
+                .split_once('=')
+                .with_context(|| format!("Invalid RATE_LIMIT_KEYS entry {pair:?}"))?;
+            let limit = limit
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid limit for API key {:?}", key.trim()))?;
+            Ok((key.trim().to_string(), limit))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[schemars(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per line, for log shippers like Loki or Vector
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    /// Filter directives such as `info,travelai=debug`; `RUST_LOG` wins if set
+    pub filter: String,
+    pub format: LogFormat,
+    /// Log to this file instead of stdout, rotating it once it grows too large
+    pub file: Option<PathBuf>,
+    pub max_size_bytes: u64,
+    /// Rotated files kept next to the current one
+    pub max_files: usize,
+}
+
+impl LoggingConfig {
+    /// `LOG_FILTER` (default `info`), `LOG_FORMAT` (`text` or `json`),
+    /// `LOG_FILE`, `LOG_MAX_SIZE_MB` (default 10) and `LOG_MAX_FILES` (default 5).
+    pub fn load() -> Result<Self> {
+        let format = match var("LOG_FORMAT").as_deref() {
+            Ok("json") => LogFormat::Json,
+            Ok("text") | Err(_) => LogFormat::Text,
+            Ok(other) => anyhow::bail!("Invalid LOG_FORMAT {other:?}, expected text or json"),
+        };
+        let max_size_mb: u64 = var("LOG_MAX_SIZE_MB")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10);
+        let max_files = var("LOG_MAX_FILES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5);
+
+        Ok(LoggingConfig {
+            filter: var("LOG_FILTER").unwrap_or_else(|_| "info".to_string()),
+            format,
+            file: var("LOG_FILE").ok().map(PathBuf::from),
+            max_size_bytes: max_size_mb * 1024 * 1024,
+            max_files,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// OTLP/HTTP collector such as Tempo or an OpenTelemetry Collector; `None`
+    /// disables the export
+    pub endpoint: Option<String>,
+    pub service_name: String,
+    /// `http/json` or `http/protobuf`
+    pub protocol: String,
+    /// Share of traces exported, from 0 to 1
+    pub sample_ratio: f64,
+}
+
+impl TelemetryConfig {
+    /// The standard `OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_SERVICE_NAME` (default
+    /// `travelai`), `OTEL_EXPORTER_OTLP_PROTOCOL` (default `http/json`) and
+    /// `OTEL_TRACES_SAMPLER_ARG` (default 1) variables.
+    pub fn load() -> Result<Self> {
+        let protocol =
+            var("OTEL_EXPORTER_OTLP_PROTOCOL").unwrap_or_else(|_| "http/json".to_string());
+        if !matches!(protocol.as_str(), "http/json" | "http/protobuf") {
+            anyhow::bail!("Unsupported OTEL_EXPORTER_OTLP_PROTOCOL {protocol:?}");
+        }
+        let sample_ratio = match var("OTEL_TRACES_SAMPLER_ARG") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|ratio| (0.0..=1.0).contains(ratio))
+                .with_context(|| format!("Invalid OTEL_TRACES_SAMPLER_ARG {value:?}"))?,
+            Err(_) => 1.0,
+        };
+
+        Ok(TelemetryConfig {
+            endpoint: var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .ok()
+                .filter(|e| !e.is_empty()),
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "travelai".to_string()),
+            protocol,
+            sample_ratio,
+        })
+    }
+}
+
+/// User settings a profile fixes instead of the ones saved in the UI, e.g. a
+/// larger radius while travelling. Each is the `UserSettings` field name in
+/// upper case, plus `WATCHED_SITES` for the sites treated as favorites and
+/// `BUSY_SITES` for those known to draw crowds.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsOverrides {
+    pub location_name: Option<String>,
+    pub location_latitude: Option<f64>,
+    pub location_longitude: Option<f64>,
+    pub search_radius_km: Option<f64>,
+    pub calendar_name: Option<String>,
+    pub minimum_flyable_hours: Option<u32>,
+    pub excluded_calendar_names: Option<Vec<String>>,
+    pub watched_sites: Option<Vec<String>>,
+    pub busy_sites: Option<Vec<String>>,
+    /// Closures added to sites, by site name
+    pub closures: Vec<(String, Closure)>,
+}
+
+impl SettingsOverrides {
+    pub fn load() -> Result<Self> {
+        Ok(SettingsOverrides {
+            location_name: var("LOCATION_NAME").ok(),
+            location_latitude: parse_var("LOCATION_LATITUDE")?,
+            location_longitude: parse_var("LOCATION_LONGITUDE")?,
+            search_radius_km: parse_var("SEARCH_RADIUS_KM")?,
+            calendar_name: var("CALENDAR_NAME").ok(),
+            minimum_flyable_hours: parse_var("MINIMUM_FLYABLE_HOURS")?,
+            excluded_calendar_names: list_var("EXCLUDED_CALENDAR_NAMES"),
+            watched_sites: list_var("WATCHED_SITES"),
+            busy_sites: list_var("BUSY_SITES"),
+            closures: var("SITE_CLOSURES")
+                .map(|value| parse_closures(&value))
+                .unwrap_or(Ok(vec![]))?,
+        })
+    }
+
+    fn overrides_settings(&self) -> bool {
+        self.location_name.is_some()
+            || self.location_latitude.is_some()
+            || self.location_longitude.is_some()
+            || self.search_radius_km.is_some()
+            || self.calendar_name.is_some()
+            || self.minimum_flyable_hours.is_some()
+            || self.excluded_calendar_names.is_some()
+    }
+
+    /// `stored` with the overridden fields replaced; `None` only if nothing
+    /// was stored and nothing is overridden.
+    pub fn apply(&self, stored: Option<UserSettings>) -> Option<UserSettings> {
+        if !self.overrides_settings() {
+            return stored;
+        }
+        let mut settings = stored.unwrap_or_default();
+        if let Some(name) = &self.location_name {
+            settings.location_name = name.clone();
+        }
+        if let Some(latitude) = self.location_latitude {
+            settings.location_latitude = latitude;
+        }
+        if let Some(longitude) = self.location_longitude {
+            settings.location_longitude = longitude;
+        }
+        if let Some(radius) = self.search_radius_km {
+            settings.search_radius_km = radius;
+        }
+        if let Some(name) = &self.calendar_name {
+            settings.calendar_name = name.clone();
+        }
+        if let Some(hours) = self.minimum_flyable_hours {
+            settings.minimum_flyable_hours = hours;
+        }
+        if let Some(names) = &self.excluded_calendar_names {
+            settings.excluded_calendar_names = names.clone();
+        }
+        Some(settings)
+    }
+
+    /// Marks exactly the watched sites as favorites and the busy sites as
+    /// busy, if the profile lists them, and adds the profile's closures of
+    /// `site` it doesn't have yet.
+    pub fn apply_to_site(&self, site: &mut ParaglidingSite) {
+        if let Some(watched) = &self.watched_sites {
+            site.favorite = Some(watched.contains(&site.name));
+        }
+        if let Some(busy) = &self.busy_sites {
+            site.busy = Some(busy.contains(&site.name));
+        }
+        for closure in self.closures_of(&site.name) {
+            if !site.closures.contains(closure) {
+                site.closures.push(closure.clone());
+            }
+        }
+    }
+
+    /// Takes the profile's closures back out of `site`, so a site read with
+    /// them is stored without.
+    pub fn remove_from_site(&self, site: &mut ParaglidingSite) {
+        let closures: Vec<&Closure> = self.closures_of(&site.name).collect();
+        site.closures.retain(|closure| !closures.contains(&closure));
+    }
+
+    fn closures_of(&self, site: &str) -> impl Iterator<Item = &Closure> {
+        self.closures
+            .iter()
+            .filter(move |(name, _)| name == site)
+            .map(|(_, closure)| closure)
+    }
+}
+
+#[cfg(feature = "grpc")]
+pub struct GrpcConfig {
+    pub port: u16,
+}
+
+#[cfg(feature = "grpc")]
+impl GrpcConfig {
+    pub fn load() -> Self {
+        let port = var("GRPC_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(50051);
+
+        GrpcConfig { port }
+    }
+}
+
+/// How the Google backend signs in, from `GOOGLE_AUTH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoogleAuthConfig {
+    /// A pilot signs in through the browser once and the refresh token is kept
+    OAuth,
+    /// A service account signs in with its key, so nobody has to
+    ServiceAccount {
+        /// JSON key downloaded from the Google Cloud console
+        key_file: PathBuf,
+        /// Workspace user the account acts as, which needs domain-wide
+        /// delegation; without one it uses its own calendars
+        subject: Option<String>,
+    },
+}
+
+impl GoogleAuthConfig {
+    /// `GOOGLE_AUTH` is `oauth` (default) or `service_account`, the latter
+    /// reading `GOOGLE_SERVICE_ACCOUNT_KEY` and `GOOGLE_IMPERSONATE`.
+    pub fn load() -> Result<Self> {
+        match var("GOOGLE_AUTH").as_deref() {
+            Err(_) | Ok("oauth") => Ok(GoogleAuthConfig::OAuth),
+            Ok("service_account") => Ok(GoogleAuthConfig::ServiceAccount {
+                key_file: var("GOOGLE_SERVICE_ACCOUNT_KEY")
+                    .map(PathBuf::from)
+                    .context("GOOGLE_AUTH=service_account needs GOOGLE_SERVICE_ACCOUNT_KEY")?,
+                subject: var("GOOGLE_IMPERSONATE").ok().filter(|s| !s.is_empty()),
+            }),
+            Ok(other) => anyhow::bail!("Unknown GOOGLE_AUTH {other}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[schemars(rename_all = "lowercase")]
+pub enum CalendarBackend {
+    Google,
+    /// One iCalendar file per calendar in `ICS_CALENDAR_DIR`
+    Ics,
+    /// Collections on the CalDAV server at `CALDAV_URL`
+    CalDav,
+}
+
+#[derive(Clone)]
+pub struct CalDavConfig {
+    /// Home of the user's calendar collections, with a trailing `/`
+    pub url: String,
+    pub credentials: Option<(String, String)>,
+}
+
+/// How many events the sync creates for flyable time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[schemars(rename_all = "lowercase")]
+pub enum EventGranularity {
+    /// One event per flyable window
+    Window,
+    /// One event per site and day, spanning all of its windows
+    Site,
+    /// One event per day, spanning all flyable sites
+    Day,
+}
+
+pub struct CalendarConfig {
+    /// Backends every sync writes to, the first one also supplies busy times
+    pub backends: Vec<CalendarBackend>,
+    /// Remind this many hours before suggestions rated good or better
+    pub reminder_hours_before: Option<u32>,
+    /// One all-day event per flyable day plus timed events for its top sites only
+    pub day_summaries: bool,
+    pub granularity: EventGranularity,
+    /// Where this app is reachable, used for links in event descriptions
+    pub public_base_url: Option<String>,
+    /// Secret path segment of the ICS feed; no token, no feed
+    pub feed_token: Option<String>,
+    /// Where the `ics` backend keeps its files
+    pub ics_dir: PathBuf,
+    pub caldav: Option<CalDavConfig>,
+}
+
+impl CalendarConfig {
+    pub fn load() -> Result<Self> {
+        let backends = match var("CALENDAR_PROVIDERS") {
+            Ok(names) => names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| match name {
+                    "google" => Ok(CalendarBackend::Google),
+                    "ics" => Ok(CalendarBackend::Ics),
+                    "caldav" => Ok(CalendarBackend::CalDav),
+                    other => Err(anyhow::anyhow!("Unknown calendar provider {other}")),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Err(_) => vec![CalendarBackend::Google],
+        };
+        if backends.is_empty() {
+            anyhow::bail!("CALENDAR_PROVIDERS lists no calendar provider");
+        }
+
+        let caldav = var("CALDAV_URL").ok().map(|url| CalDavConfig {
+            url: if url.ends_with('/') {
+                url
+            } else {
+                format!("{url}/")
+            },
+            credentials: var("CALDAV_USERNAME")
+                .ok()
+                .map(|user| (user, secret("CALDAV_PASSWORD").unwrap_or_default())),
+        });
+        if caldav.is_none() && backends.contains(&CalendarBackend::CalDav) {
+            anyhow::bail!("CALENDAR_PROVIDERS lists caldav but CALDAV_URL is not set");
+        }
+
+        let reminder_hours_before = var("CALENDAR_REMINDER_HOURS")
+            .ok()
+            .and_then(|h| h.parse().ok());
+
+        let day_summaries = var("CALENDAR_DAY_SUMMARIES")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let granularity = match var("CALENDAR_EVENT_GRANULARITY").as_deref() {
+            Err(_) | Ok("window") => EventGranularity::Window,
+            Ok("site") => EventGranularity::Site,
+            Ok("day") => EventGranularity::Day,
+            Ok(other) => anyhow::bail!("Unknown CALENDAR_EVENT_GRANULARITY {other}"),
+        };
+
+        Ok(CalendarConfig {
+            backends,
+            reminder_hours_before,
+            day_summaries,
+            granularity,
+            public_base_url: var("PUBLIC_BASE_URL").ok(),
+            feed_token: secret("ICS_FEED_TOKEN").filter(|t| !t.is_empty()),
+            ics_dir: var("ICS_CALENDAR_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("calendars")),
+            caldav,
+        })
+    }
+}
+
+pub struct MqttConfig {
+    /// Broker as `host:port`
+    pub broker: String,
+    pub client_id: String,
+    pub topic_prefix: String,
+    pub credentials: Option<(String, String)>,
+    /// Connect over TLS, on by default with credentials so they never cross
+    /// the network in clear text
+    pub tls: bool,
+}
+
+impl MqttConfig {
+    /// `None` unless `MQTT_BROKER` is set.
+    pub fn load() -> Option<Self> {
+        let broker = var("MQTT_BROKER").ok()?;
+        let client_id = var("MQTT_CLIENT_ID").unwrap_or_else(|_| "travelai".to_string());
+        let topic_prefix = var("MQTT_TOPIC_PREFIX").unwrap_or_else(|_| "paragliding".to_string());
+        let credentials = var("MQTT_USERNAME").ok().zip(secret("MQTT_PASSWORD"));
+        let tls = var("MQTT_TLS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(credentials.is_some());
+
+        Some(MqttConfig {
+            broker,
+            client_id,
+            topic_prefix,
+            credentials,
+            tls,
+        })
+    }
+}
+
+pub struct DiscordConfig {
+    pub token: String,
+    /// Channel the daily outlook is posted to
+    pub channel_id: Option<NonZeroU64>,
+}
+
+impl DiscordConfig {
+    /// `None` unless `DISCORD_TOKEN` is set.
+    pub fn load() -> Result<Option<Self>> {
+        let Some(token) = secret("DISCORD_TOKEN") else {
+            return Ok(None);
+        };
+        Ok(Some(DiscordConfig {
+            token,
+            channel_id: parse_var("DISCORD_CHANNEL_ID")?,
+        }))
+    }
+}
+
+pub struct MatrixConfig {
+    /// Base URL of the homeserver, e.g. `https://matrix.org`
+    pub homeserver: String,
+    pub access_token: String,
+    /// Room the outlook is posted to, e.g. `!abc123:matrix.org`
+    pub room_id: String,
+}
+
+impl MatrixConfig {
+    /// `None` unless `MATRIX_ROOM_ID` is set, which then needs
+    /// `MATRIX_HOMESERVER` and `MATRIX_ACCESS_TOKEN`.
+    pub fn load() -> Result<Option<Self>> {
+        let Ok(room_id) = var("MATRIX_ROOM_ID") else {
+            return Ok(None);
+        };
+        Ok(Some(MatrixConfig {
+            homeserver: var("MATRIX_HOMESERVER")
+                .context("MATRIX_ROOM_ID needs MATRIX_HOMESERVER")?
+                .trim_end_matches('/')
+                .to_string(),
+            access_token: secret("MATRIX_ACCESS_TOKEN")
+                .context("MATRIX_ROOM_ID needs MATRIX_ACCESS_TOKEN")?,
+            room_id,
+        }))
+    }
+}
+
+pub struct ClubConfig {
+    pub members: Vec<ClubMember>,
+    /// Sites with supervised training, the only ones beginners hear about
+    pub school_sites: Vec<String>,
+    /// Shared calendar every flyable day goes to
+    pub calendar_name: String,
+}
+
+impl ClubConfig {
+    /// `None` unless `CLUB_MEMBERS` is set, as comma-separated
+    /// `name=skill:contact` entries. `CLUB_SCHOOL_SITES` lists the school
+    /// sites and `CLUB_CALENDAR_NAME` (default `Club`) names the calendar.
+    pub fn load() -> Result<Option<Self>> {
+        let Ok(members) = var("CLUB_MEMBERS") else {
+            return Ok(None);
+        };
+        Ok(Some(ClubConfig {
+            members: parse_members(&members)?,
+            school_sites: list_var("CLUB_SCHOOL_SITES").unwrap_or_default(),
+            calendar_name: var("CLUB_CALENDAR_NAME").unwrap_or_else(|_| "Club".to_string()),
+        }))
+    }
+}
+
+/// `<site>=<closure>` entries, see [`Closure`] for the format.
+fn parse_closures(value: &str) -> Result<Vec<(String, Closure)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (site, closure) = entry
+                .split_once('=')
+                .with_context(|| format!("Invalid SITE_CLOSURES entry {entry:?}"))?;
+            Ok((site.trim().to_string(), closure.parse()?))
+        })
+        .collect()
+}
+
+fn parse_members(value: &str) -> Result<Vec<ClubMember>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, rest) = entry
+                .split_once('=')
+                .with_context(|| format!("Invalid CLUB_MEMBERS entry {entry:?}"))?;
+            let (skill, contact) = rest
+                .split_once(':')
+                .with_context(|| format!("Missing contact for club member {:?}", name.trim()))?;
+            Ok(ClubMember {
+                name: name.trim().to_string(),
+                skill: skill.parse::<SkillLevel>()?,
+                contact: contact.parse::<Contact>()?,
+            })
+        })
+        .collect()
+}
+
+pub struct TandemConfig {
+    /// Calendar the bookable slots go to
+    pub calendar_name: String,
+    pub slot: chrono::Duration,
+    /// Gap after each slot for landing, packing and the way back up
+    pub buffer: chrono::Duration,
+    /// Sites flown from; empty means the favorites
+    pub sites: Vec<String>,
+    /// The operator's personal calendars; a slot they are busy in is skipped
+    pub busy_calendars: Vec<String>,
+}
+
+impl TandemConfig {
+    /// `None` unless `TANDEM_CALENDAR_NAME` is set. `TANDEM_SLOT_MINUTES`
+    /// (default 60), `TANDEM_BUFFER_MINUTES` (default 30), `TANDEM_SITES` and
+    /// `TANDEM_BUSY_CALENDARS` (default every other calendar) shape the slots.
+    pub fn load() -> Result<Option<Self>> {
+        let Ok(calendar_name) = var("TANDEM_CALENDAR_NAME") else {
+            return Ok(None);
+        };
+        let slot_minutes: i64 = parse_var("TANDEM_SLOT_MINUTES")?.unwrap_or(60);
+        if slot_minutes <= 0 {
+            anyhow::bail!("TANDEM_SLOT_MINUTES must be positive");
+        }
+        let buffer_minutes: i64 = parse_var("TANDEM_BUFFER_MINUTES")?.unwrap_or(30);
+        Ok(Some(TandemConfig {
+            calendar_name,
+            slot: chrono::Duration::minutes(slot_minutes),
+            buffer: chrono::Duration::minutes(buffer_minutes.max(0)),
+            sites: list_var("TANDEM_SITES").unwrap_or_default(),
+            busy_calendars: list_var("TANDEM_BUSY_CALENDARS").unwrap_or_default(),
+        }))
+    }
+}
+
+pub struct SchedulerConfig {
+    /// Jobs that run and their schedules, in UTC
+    pub jobs: Vec<(Job, Cron)>,
+    /// Upper bound of the random delay before each run
+    pub jitter: Duration,
+    /// Directory with DHV XML exports the site update imports new sites from
+    pub site_import_dir: Option<PathBuf>,
+}
+
+impl SchedulerConfig {
+    /// Each job's schedule comes from `SCHEDULE_<JOB>`, where `off` disables
+    /// the job. The site update only runs by default if `SITE_IMPORT_DIR` is
+    /// set, the club broadcast if `CLUB_MEMBERS` is, the tandem slots if
+    /// `TANDEM_CALENDAR_NAME` is and the chat update if a Discord channel
+    /// (with the `discord` feature) or a Matrix room is configured.
+    pub fn load() -> Result<Self> {
+        let site_import_dir = var("SITE_IMPORT_DIR").ok().map(PathBuf::from);
+        let club = var("CLUB_MEMBERS").is_ok();
+        let tandem = var("TANDEM_CALENDAR_NAME").is_ok();
+        #[cfg(feature = "discord")]
+        let discord = DiscordConfig::load()?.is_some_and(|config| config.channel_id.is_some());
+        #[cfg(not(feature = "discord"))]
+        let discord = false;
+        let chat = discord || MatrixConfig::load()?.is_some();
+
+        let mut jobs = Vec::new();
+        for job in Job::ALL {
+            let name = format!("SCHEDULE_{}", job.name().to_uppercase());
+            let default = match job {
+                Job::ForecastRefresh => Some("0 */3 * * *"),
+                Job::CalendarSync => Some("0 */8 * * *"),
+                Job::SiteUpdate => site_import_dir.as_ref().map(|_| "0 4 * * 0"),
+                Job::CacheCleanup => Some("30 3 * * *"),
+                Job::ClubBroadcast => club.then_some("0 17 * * *"),
+                Job::TandemSlots => tandem.then_some("15 */8 * * *"),
+                Job::EventRecheck => Some("*/15 * * * *"),
+                Job::TokenCheck => Some("40 * * * *"),
+                Job::ChatUpdate => chat.then_some("0 18 * * *"),
+            };
+            let schedule = match var(&name) {
+                Ok(value) if value.trim() == "off" => None,
+                Ok(value) => Some(value),
+                Err(_) => default.map(str::to_string),
+            };
+            if let Some(schedule) = schedule {
+                let cron = schedule
+                    .parse()
+                    .with_context(|| format!("Invalid {name} {schedule:?}"))?;
+                jobs.push((job, cron));
+            }
+        }
+
+        let jitter = var("SCHEDULE_JITTER_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(120);
+
+        Ok(SchedulerConfig {
+            jobs,
+            jitter: Duration::from_secs(jitter),
+            site_import_dir,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_falls_back_to_file_without_trailing_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        fs::write(&path, "s3cret\n").unwrap();
+
+        assert_eq!(
+            read_secret(None, Some(path.clone().into())).as_deref(),
+            Some("s3cret")
+        );
+        assert_eq!(
+            read_secret(Some("direct".into()), Some(path.into())).as_deref(),
+            Some("direct")
+        );
+        assert_eq!(
+            read_secret(None, Some(dir.path().join("missing").into())),
+            None
+        );
+        assert_eq!(read_secret(None, None), None);
+    }
+
+    #[test]
+    fn key_limits_are_parsed_from_pairs() {
+        let limits = parse_key_limits("frontend=600, widget = 30,").unwrap();
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits["frontend"], 600);
+        assert_eq!(limits["widget"], 30);
+        assert!(parse_key_limits("frontend").is_err());
+        assert!(parse_key_limits("frontend=many").is_err());
+    }
+
+    #[test]
+    fn club_members_carry_skill_and_contact() {
+        let members =
+            parse_members("Anna=beginner:anna@example.org, Ben = advanced:mqtt,").unwrap();
+        assert_eq!(
+            members,
+            vec![
+                ClubMember {
+                    name: "Anna".into(),
+                    skill: SkillLevel::Beginner,
+                    contact: Contact::Email("anna@example.org".into()),
+                },
+                ClubMember {
+                    name: "Ben".into(),
+                    skill: SkillLevel::Advanced,
+                    contact: Contact::Mqtt,
+                },
+            ]
+        );
+        assert!(parse_members("Anna=beginner").is_err());
+        assert!(parse_members("Anna=expert:mqtt").is_err());
+        assert!(parse_members("Anna=beginner:sms").is_err());
+    }
+
+    #[test]
+    fn site_closures_are_keyed_by_site() {
+        let closures =
+            parse_closures("Wank=04-01..07-15 raptor nesting, Hochries=2026-05-01..2026-05-03")
+                .unwrap();
+        assert_eq!(closures.len(), 2);
+        assert_eq!(closures[0].0, "Wank");
+        assert_eq!(closures[0].1.reason, "raptor nesting");
+        assert_eq!(closures[1].1.reason, "closed");
+        assert!(parse_closures("Wank").is_err());
+        assert!(parse_closures("Wank=soon").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Named profiles in an optional TOML file, e.g. `home`, `travel` and
+//! `tandem-work`. Every key is one of the environment variables in lowercase:
+//!
+//! ```toml
+//! [base]
+//! search_radius_km = 150
+//! calendar_providers = ["google"]
+//!
+//! [profiles.travel]
+//! search_radius_km = 400
+//!
+//! [profiles.tandem-work]
+//! inherits = "travel"
+//! calendar_name = "Tandem"
+//! watched_sites = ["Wank", "Laber"]
+//! ```
+//!
+//! A profile starts from `[base]`, or from the profile it `inherits`, and
+//! overrides what it sets. Lists become comma-separated values.
+
+use std::{collections::BTreeMap, fs, ops::Range, path::Path};
+
+use anyhow::{Context, Result, bail};
+use toml_edit::{Document, Item, Table, Value};
+
+/// Setting values by environment variable name
+pub type Settings = BTreeMap<String, String>;
+
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    base: Settings,
+    profiles: BTreeMap<String, Profile>,
+    entries: Vec<Entry>,
+}
+
+/// One `key = value` of the file, for validation.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// `base` or `profiles.<name>`
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+#[derive(Debug)]
+struct Profile {
+    inherits: Option<String>,
+    settings: Settings,
+}
+
+impl ConfigFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let document = Document::parse(text)?;
+        let mut file = ConfigFile::default();
+        for (key, item) in document.as_table() {
+            match key {
+                "base" => file.base = settings(text, key, item, &mut file.entries)?.1,
+                "profiles" => {
+                    let profiles = table(text, key, item)?;
+                    for (name, item) in profiles {
+                        let section = format!("profiles.{name}");
+                        let (inherits, settings) =
+                            settings(text, &section, item, &mut file.entries)?;
+                        file.profiles
+                            .insert(name.to_string(), Profile { inherits, settings });
+                    }
+                }
+                other => bail!(
+                    "{}: unknown section `{other}`, expected [base] or [profiles.<name>]",
+                    line(text, item.span())
+                ),
+            }
+        }
+        Ok(file)
+    }
+
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Every setting in the file, in file order.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// `[base]` with everything `profile` and the profiles it inherits from
+    /// set on top, or just `[base]` without a profile.
+    pub fn resolve(&self, profile: Option<&str>) -> Result<Settings> {
+        let mut chain = Vec::new();
+        let mut next = profile;
+        while let Some(name) = next {
+            if chain.iter().any(|(n, _)| *n == name) {
+                bail!("Profile `{name}` inherits from itself");
+            }
+            let Some(profile) = self.profiles.get(name) else {
+                let known: Vec<&str> = self.profile_names().collect();
+                bail!(
+                    "Unknown profile `{name}`, the config file has: {}",
+                    known.join(", ")
+                );
+            };
+            chain.push((name, profile));
+            next = profile.inherits.as_deref();
+        }
+
+        let mut settings = self.base.clone();
+        for (_, profile) in chain.into_iter().rev() {
+            settings.extend(profile.settings.clone());
+        }
+        Ok(settings)
+    }
+}
+
+fn table<'a>(text: &str, name: &str, item: &'a Item) -> Result<&'a Table> {
+    item.as_table().with_context(|| {
+        format!(
+            "{}: `{name}` must be a section like [{name}]",
+            line(text, item.span())
+        )
+    })
+}
+
+/// The `inherits` key and the settings of one section.
+fn settings(
+    text: &str,
+    name: &str,
+    item: &Item,
+    entries: &mut Vec<Entry>,
+) -> Result<(Option<String>, Settings)> {
+    let mut inherits = None;
+    let mut settings = Settings::new();
+    for (key, item) in table(text, name, item)? {
+        let at = line(text, item.span());
+        let Some(value) = item.as_value() else {
+            bail!("{at}: `{key}` in [{name}] must be a value, not a section");
+        };
+        if key == "inherits" {
+            let parent = value
+                .as_str()
+                .with_context(|| format!("{at}: `inherits` must be a profile name"))?;
+            inherits = Some(parent.to_string());
+            continue;
+        }
+        let value = scalar(value)
+            .or_else(|| {
+                let values: Option<Vec<String>> = value.as_array()?.iter().map(scalar).collect();
+                Some(values?.join(","))
+            })
+            .with_context(|| {
+                format!("{at}: `{key}` must be a string, number, boolean or list of them")
+            })?;
+        entries.push(Entry {
+            section: name.to_string(),
+            key: key.to_string(),
+            value: value.clone(),
+            line: line_number(text, item.span()),
+        });
+        settings.insert(env_name(key), value);
+    }
+    Ok((inherits, settings))
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.value().clone()),
+        Value::Integer(i) => Some(i.value().to_string()),
+        Value::Float(f) => Some(f.value().to_string()),
+        Value::Boolean(b) => Some(b.value().to_string()),
+        _ => None,
+    }
+}
+
+/// `search-radius_km` and `search_radius_km` both become `SEARCH_RADIUS_KM`.
+pub fn env_name(key: &str) -> String {
+    key.to_uppercase().replace('-', "_")
+}
+
+/// `line <n>` of the start of `span`, for error messages.
+fn line(text: &str, span: Option<Range<usize>>) -> String {
+    format!("line {}", line_number(text, span))
+}
+
+fn line_number(text: &str, span: Option<Range<usize>>) -> usize {
+    let start = span.map_or(0, |span| span.start.min(text.len()));
+    text[..start].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+[base]
+search_radius_km = 150
+calendar_providers = ["google", "ics"]
+calendar_day_summaries = true
+
+[profiles.travel]
+search_radius_km = 400.5
+
+[profiles.tandem-work]
+inherits = "travel"
+calendar-name = "Tandem"
+"#;
+
+    #[test]
+    fn profiles_inherit_and_override() {
+        let file = ConfigFile::parse(FILE).unwrap();
+
+        let base = file.resolve(None).unwrap();
+        assert_eq!(base["SEARCH_RADIUS_KM"], "150");
+        assert_eq!(base["CALENDAR_PROVIDERS"], "google,ics");
+        assert_eq!(base["CALENDAR_DAY_SUMMARIES"], "true");
+
+        let tandem = file.resolve(Some("tandem-work")).unwrap();
+        assert_eq!(tandem["SEARCH_RADIUS_KM"], "400.5");
+        assert_eq!(tandem["CALENDAR_NAME"], "Tandem");
+        assert_eq!(tandem["CALENDAR_PROVIDERS"], "google,ics");
+    }
+
+    #[test]
+    fn unknown_and_circular_profiles_are_rejected() {
+        let file = ConfigFile::parse(FILE).unwrap();
+        let error = file.resolve(Some("home")).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown profile `home`, the config file has: tandem-work, travel"
+        );
+
+        let circular =
+            ConfigFile::parse("[profiles.a]\ninherits = \"b\"\n[profiles.b]\ninherits = \"a\"\n")
+                .unwrap();
+        assert!(circular.resolve(Some("a")).is_err());
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = ConfigFile::parse("[base]\nport = 8080\nlocation = { lat = 1 }\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "line 3: `location` must be a string, number, boolean or list of them"
+        );
+
+        let error = ConfigFile::parse("[base]\n\n[sites]\nwank = 1\n")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("line 3: unknown section `sites`"),
+            "{error}"
+        );
+    }
+}
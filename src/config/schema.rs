@@ -0,0 +1,620 @@
+//! Every setting the environment and config file take, so `travelai config
+//! validate` can catch typos and bad values that loading would silently replace
+//! with defaults, and `travelai config schema` can describe the file to
+//! editors. Both work from the JSON Schema derived from [`FileSettings`].
+
+use std::{borrow::Cow, collections::BTreeMap, marker::PhantomData, num::NonZeroU64};
+
+use schemars::{
+    JsonSchema, Schema, SchemaGenerator,
+    generate::SchemaSettings,
+    json_schema,
+    transform::{Transform, transform_subschemas},
+};
+use serde_json::Value;
+
+use crate::{
+    application::scheduler::Cron,
+    config::{
+        CalendarBackend, EventGranularity, LogFormat,
+        profile::{ConfigFile, env_name},
+    },
+};
+
+/// The keys of `[base]` and of each profile: the environment variables in
+/// lowercase, plus `<secret>_file` for secrets.
+#[derive(JsonSchema)]
+#[schemars(rename = "settings", deny_unknown_fields)]
+pub struct FileSettings {
+    /// Port of the web server
+    pub port: Option<u16>,
+    /// Origins allowed to call the API; empty allows any
+    pub cors_allowed_origins: Option<List>,
+    /// TLS certificate
+    pub tls_cert_path: Option<String>,
+    /// TLS private key
+    pub tls_key_path: Option<String>,
+    /// Port of the gRPC server
+    pub grpc_port: Option<u16>,
+    /// Days of forecast, up to 16
+    pub forecast_days: Option<u32>,
+    /// Open-Meteo ensemble model for chances of flyable weather
+    pub ensemble_model: Option<String>,
+    /// Flyable hours in a row that open a window
+    pub window_open_hours: Option<u32>,
+    /// Unflyable hours in a row that close a window
+    pub window_close_hours: Option<u32>,
+    /// Largest radius the API accepts
+    pub max_search_radius_km: Option<f64>,
+    /// API requests per minute and client address
+    pub rate_limit_per_minute: Option<IntegerOrOff>,
+    /// `key=per_minute` budgets for clients sending X-Api-Key
+    pub rate_limit_keys: Option<List>,
+    /// Log filter such as `info,travelai=debug`
+    pub log_filter: Option<String>,
+    /// Log format
+    pub log_format: Option<LogFormat>,
+    /// Log to this file instead of stdout
+    pub log_file: Option<String>,
+    /// Size at which the log file is rotated
+    pub log_max_size_mb: Option<u64>,
+    /// Rotated log files kept
+    pub log_max_files: Option<u32>,
+    /// OTLP/HTTP collector to export telemetry to
+    pub otel_exporter_otlp_endpoint: Option<String>,
+    /// Service name in telemetry
+    pub otel_service_name: Option<String>,
+    /// OTLP encoding
+    pub otel_exporter_otlp_protocol: Option<OtlpProtocol>,
+    /// Share of traces exported
+    #[schemars(range(min = 0, max = 1))]
+    pub otel_traces_sampler_arg: Option<f64>,
+    /// Google OAuth client
+    pub google_client_id: Option<String>,
+    /// Google OAuth client secret
+    pub google_client_secret: Option<String>,
+    /// File holding google_client_secret
+    pub google_client_secret_file: Option<String>,
+    /// Where Google redirects after sign-in
+    pub oauth_redirect_url: Option<String>,
+    /// How the Google backend signs in
+    pub google_auth: Option<GoogleAuth>,
+    /// JSON key file of the Google service account
+    pub google_service_account_key: Option<String>,
+    /// Workspace user the service account acts as
+    pub google_impersonate: Option<String>,
+    /// Gmail account sending notifications
+    pub gmail_address: Option<String>,
+    /// App password of the Gmail account
+    pub gmail_app_password: Option<String>,
+    /// File holding gmail_app_password
+    pub gmail_app_password_file: Option<String>,
+    /// Recipient of sign-in links
+    pub notification_email: Option<String>,
+    /// GraphHopper routing API key
+    pub graphhopper_api_key: Option<String>,
+    /// File holding graphhopper_api_key
+    pub graphhopper_api_key_file: Option<String>,
+    /// Calendar backends the sync writes to
+    pub calendar_providers: Option<List<CalendarBackend>>,
+    /// Reminder this many hours before good days
+    pub calendar_reminder_hours: Option<u32>,
+    /// One all-day event per flyable day
+    pub calendar_day_summaries: Option<bool>,
+    /// What one calendar event stands for
+    pub calendar_event_granularity: Option<EventGranularity>,
+    /// Base URL linked from calendar events
+    pub public_base_url: Option<String>,
+    /// Enables the subscribable calendar feed
+    pub ics_feed_token: Option<String>,
+    /// File holding ics_feed_token
+    pub ics_feed_token_file: Option<String>,
+    /// Directory of the `ics` calendar backend
+    pub ics_calendar_dir: Option<String>,
+    /// Home of the CalDAV calendars
+    pub caldav_url: Option<String>,
+    /// CalDAV user
+    pub caldav_username: Option<String>,
+    /// CalDAV password
+    pub caldav_password: Option<String>,
+    /// File holding caldav_password
+    pub caldav_password_file: Option<String>,
+    /// MQTT broker as `host:port`
+    pub mqtt_broker: Option<String>,
+    /// MQTT client id
+    pub mqtt_client_id: Option<String>,
+    /// Prefix of the published topics
+    pub mqtt_topic_prefix: Option<String>,
+    /// MQTT user
+    pub mqtt_username: Option<String>,
+    /// MQTT password
+    pub mqtt_password: Option<String>,
+    /// File holding mqtt_password
+    pub mqtt_password_file: Option<String>,
+    /// Connect to the MQTT broker over TLS
+    pub mqtt_tls: Option<bool>,
+    /// Token of the Discord bot
+    pub discord_token: Option<String>,
+    /// File holding discord_token
+    pub discord_token_file: Option<String>,
+    /// Discord channel the daily outlook goes to
+    pub discord_channel_id: Option<NonZeroU64>,
+    /// Base URL of the Matrix homeserver
+    pub matrix_homeserver: Option<String>,
+    /// Access token of the Matrix bot user
+    pub matrix_access_token: Option<String>,
+    /// File holding matrix_access_token
+    pub matrix_access_token_file: Option<String>,
+    /// Matrix room the daily outlook goes to
+    pub matrix_room_id: Option<String>,
+    /// `name=skill:contact` of each club member
+    pub club_members: Option<List>,
+    /// Sites where beginners fly supervised
+    pub club_school_sites: Option<List>,
+    /// Shared calendar of the club
+    pub club_calendar_name: Option<String>,
+    /// Calendar of bookable tandem slots
+    pub tandem_calendar_name: Option<String>,
+    /// Length of a tandem slot
+    pub tandem_slot_minutes: Option<u32>,
+    /// Gap after each tandem slot
+    pub tandem_buffer_minutes: Option<u32>,
+    /// Sites tandem flights start from
+    pub tandem_sites: Option<List>,
+    /// Personal calendars that block tandem slots
+    pub tandem_busy_calendars: Option<List>,
+    /// Directory with DHV XML exports
+    pub site_import_dir: Option<String>,
+    /// When forecasts are refreshed
+    pub schedule_forecast_refresh: Option<Schedule>,
+    /// When the calendar is synced
+    pub schedule_calendar_sync: Option<Schedule>,
+    /// When new sites are imported
+    pub schedule_site_update: Option<Schedule>,
+    /// When expired cache entries are dropped
+    pub schedule_cache_cleanup: Option<Schedule>,
+    /// When club members are told about tomorrow
+    pub schedule_club_broadcast: Option<Schedule>,
+    /// When tandem slots are generated
+    pub schedule_tandem_slots: Option<Schedule>,
+    /// When events about to start are checked again
+    pub schedule_event_recheck: Option<Schedule>,
+    /// When the Google sign-in is checked
+    pub schedule_token_check: Option<Schedule>,
+    /// When the outlook is posted to Discord and Matrix
+    pub schedule_chat_update: Option<Schedule>,
+    /// Largest random delay before a job
+    pub schedule_jitter_secs: Option<u32>,
+    /// Home location shown in the UI
+    pub location_name: Option<String>,
+    /// Home latitude
+    pub location_latitude: Option<f64>,
+    /// Home longitude
+    pub location_longitude: Option<f64>,
+    /// Radius around home searched for sites
+    pub search_radius_km: Option<f64>,
+    /// Calendar the events go to
+    pub calendar_name: Option<String>,
+    /// Flyable hours that make a day worth an event
+    pub minimum_flyable_hours: Option<u32>,
+    /// Calendars whose events don't count as busy
+    pub excluded_calendar_names: Option<List>,
+    /// Sites treated as favorites instead of the starred ones
+    pub watched_sites: Option<List>,
+    /// Sites known to draw crowds on good days
+    pub busy_sites: Option<List>,
+    /// Site closures, e.g. Wank=04-01..07-15 raptor nesting
+    pub site_closures: Option<List>,
+}
+
+/// The keys of `[base]`, plus the profile to start from.
+#[derive(JsonSchema)]
+#[schemars(rename = "profile", deny_unknown_fields)]
+pub struct ProfileSettings {
+    /// Profile to start from instead of [base]
+    pub inherits: Option<String>,
+    #[schemars(flatten)]
+    pub settings: FileSettings,
+}
+
+#[derive(JsonSchema)]
+#[schemars(title = "travelai config file", deny_unknown_fields)]
+pub struct ConfigFileSettings {
+    pub base: Option<FileSettings>,
+    pub profiles: Option<BTreeMap<String, ProfileSettings>>,
+}
+
+#[derive(JsonSchema)]
+pub enum OtlpProtocol {
+    #[schemars(rename = "http/json")]
+    Json,
+    #[schemars(rename = "http/protobuf")]
+    Protobuf,
+}
+
+#[derive(JsonSchema)]
+#[schemars(rename_all = "snake_case")]
+pub enum GoogleAuth {
+    #[schemars(rename = "oauth")]
+    OAuth,
+    ServiceAccount,
+}
+
+/// An array, or a comma-separated string
+pub struct List<T = String>(PhantomData<T>);
+
+impl<T: JsonSchema> JsonSchema for List<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("List_of_{}", T::schema_name()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": ["array", "string"],
+            "items": generator.subschema_for::<T>(),
+        })
+    }
+}
+
+/// A five-field cron expression or `off`
+pub struct Schedule;
+
+impl JsonSchema for Schedule {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "Schedule".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({"type": "string", "format": "cron"})
+    }
+}
+
+/// A whole number or `off`
+pub struct IntegerOrOff;
+
+impl JsonSchema for IntegerOrOff {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "IntegerOrOff".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({"oneOf": [{"type": "integer", "minimum": 0}, {"const": "off"}]})
+    }
+}
+
+/// Drops the `null` that optional settings allow, as TOML has none: a setting
+/// is either given or left out.
+#[derive(Clone)]
+struct NotNull;
+
+impl Transform for NotNull {
+    fn transform(&mut self, schema: &mut Schema) {
+        if let Some(obj) = schema.as_object_mut() {
+            if let Some(Value::Array(types)) = obj.get_mut("type") {
+                types.retain(|t| t != "null");
+                if types.len() == 1 {
+                    let single = types.remove(0);
+                    obj.insert("type".into(), single);
+                }
+            }
+            let not_null = |s: &Value| s.get("type").is_none_or(|t| t != "null");
+            if let Some(Value::Array(options)) = obj.remove("anyOf") {
+                let mut options: Vec<Value> = options.into_iter().filter(not_null).collect();
+                match options.len() {
+                    1 => obj.extend(options.remove(0).as_object().cloned().unwrap_or_default()),
+                    _ => {
+                        obj.insert("anyOf".into(), options.into());
+                    }
+                }
+            }
+        }
+        transform_subschemas(self, schema);
+    }
+}
+
+/// A JSON Schema of the config file, e.g. for editor completion.
+pub fn json_schema() -> Value {
+    SchemaSettings::draft2020_12()
+        .with_transform(NotNull)
+        .into_generator()
+        .into_root_schema_for::<ConfigFileSettings>()
+        .to_value()
+}
+
+/// The schema of each key in `[base]`.
+fn settings(schema: &Value) -> &serde_json::Map<String, Value> {
+    schema["$defs"]["settings"]["properties"]
+        .as_object()
+        .expect("settings are an object")
+}
+
+/// Everything wrong with `file`, one message per problem.
+pub fn validate(file: &ConfigFile) -> Vec<String> {
+    let schema = json_schema();
+    let mut problems = Vec::new();
+    for entry in file.entries() {
+        let at = format!("line {} in [{}]", entry.line, entry.section);
+        let key = env_name(&entry.key).to_lowercase();
+        let Some(setting) = settings(&schema).get(&key) else {
+            let hint = closest(&schema, &entry.key)
+                .map(|known| format!(", did you mean `{known}`?"))
+                .unwrap_or_default();
+            problems.push(format!("{at}: unknown setting `{}`{hint}", entry.key));
+            continue;
+        };
+        if let Err(expected) = check(&schema, setting, &entry.value) {
+            problems.push(format!(
+                "{at}: `{}` {expected}, got {:?}",
+                entry.key, entry.value
+            ));
+        }
+    }
+    for profile in file.profile_names() {
+        if let Err(e) = file.resolve(Some(profile)) {
+            problems.push(format!("[profiles.{profile}]: {e}"));
+        }
+    }
+    problems
+}
+
+/// Follows `$ref` into `$defs`.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema["$ref"].as_str() {
+        Some(path) => path
+            .strip_prefix("#/$defs/")
+            .map_or(schema, |name| &root["$defs"][name]),
+        None => schema,
+    }
+}
+
+/// The allowed values of an `enum` or of a `oneOf` of constants.
+fn choices(root: &Value, schema: &Value) -> Option<Vec<String>> {
+    let schema = resolve(root, schema);
+    if let Some(values) = schema["enum"].as_array() {
+        return Some(
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(Into::into)
+                .collect(),
+        );
+    }
+    schema["oneOf"]
+        .as_array()?
+        .iter()
+        .map(|option| match &option["enum"] {
+            Value::Array(values) if values.len() == 1 => values[0].as_str().map(Into::into),
+            _ => option["const"].as_str().map(Into::into),
+        })
+        .collect()
+}
+
+/// Checks `value` as the config file gives it, with lists joined by commas,
+/// against the schema of its setting.
+fn check(root: &Value, schema: &Value, value: &str) -> Result<(), String> {
+    let schema = resolve(root, schema);
+    if schema["format"] == "cron" {
+        return match value.trim() {
+            "off" => Ok(()),
+            cron => cron
+                .parse::<Cron>()
+                .map(|_| ())
+                .map_err(|e| format!("must be a cron expression or `off`: {e:#}")),
+        };
+    }
+    if let Some(items) = schema.get("items") {
+        let allowed = choices(root, items);
+        let mut listed = value.split(',').map(str::trim).filter(|v| !v.is_empty());
+        return match allowed {
+            Some(allowed) if !listed.all(|item| allowed.iter().any(|a| a == item)) => {
+                Err(format!("may only list {}", allowed.join(", ")))
+            }
+            _ => Ok(()),
+        };
+    }
+    if matches(root, schema, value) {
+        Ok(())
+    } else {
+        Err(format!("must be {}", describe(root, schema)))
+    }
+}
+
+fn matches(root: &Value, schema: &Value, value: &str) -> bool {
+    let schema = resolve(root, schema);
+    let in_range = |number: f64| {
+        schema["minimum"].as_f64().is_none_or(|min| number >= min)
+            && schema["maximum"].as_f64().is_none_or(|max| number <= max)
+    };
+    if let Some(options) = schema["oneOf"].as_array() {
+        return options.iter().any(|option| matches(root, option, value));
+    }
+    if let Some(constant) = schema.get("const") {
+        return constant == value;
+    }
+    if let Some(allowed) = schema["enum"].as_array() {
+        return allowed.iter().any(|a| a == value);
+    }
+    match schema["type"].as_str() {
+        Some("integer") => value
+            .trim()
+            .parse::<i64>()
+            .is_ok_and(|number| in_range(number as f64)),
+        Some("number") => value.trim().parse::<f64>().is_ok_and(in_range),
+        Some("boolean") => matches!(value, "true" | "false" | "1" | "0"),
+        _ => true,
+    }
+}
+
+/// What a value must be, e.g. `a whole number or \`off\``.
+fn describe(root: &Value, schema: &Value) -> String {
+    let schema = resolve(root, schema);
+    if let Some(allowed) = choices(root, schema) {
+        return format!("one of {}", allowed.join(", "));
+    }
+    if let Some(options) = schema["oneOf"].as_array() {
+        let options: Vec<String> = options.iter().map(|o| describe(root, o)).collect();
+        return options.join(" or ");
+    }
+    if let Some(constant) = schema["const"].as_str() {
+        return format!("`{constant}`");
+    }
+    let range = match (schema["minimum"].as_f64(), schema["maximum"].as_f64()) {
+        (Some(min), Some(max)) if schema["type"] == "number" => format!(" from {min} to {max}"),
+        (_, Some(max)) => format!(" up to {max}"),
+        _ => String::new(),
+    };
+    match schema["type"].as_str() {
+        Some("integer") => format!("a whole number{range}"),
+        Some("number") => format!("a number{range}"),
+        Some("boolean") => "true or false".into(),
+        _ => "text".into(),
+    }
+}
+
+/// A known key within two typos of `key`.
+fn closest(schema: &Value, key: &str) -> Option<String> {
+    settings(schema)
+        .keys()
+        .map(|known| (edit_distance(key, known), known))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::scheduler::Job;
+    use rstest::rstest;
+
+    #[test]
+    fn every_job_has_a_schedule_setting() {
+        let schema = json_schema();
+        for job in Job::ALL {
+            let name = format!("schedule_{}", job.name());
+            assert!(settings(&schema).contains_key(&name), "{name} is missing");
+        }
+    }
+
+    #[test]
+    fn every_variable_config_reads_is_a_setting() {
+        let schema = json_schema();
+        let source = include_str!("mod.rs");
+        let names = source
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .filter(|s| s.len() > 1 && s.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+            .filter(|name| !name.starts_with("TRAVELAI_"));
+        for name in names {
+            let key = name.to_lowercase();
+            assert!(settings(&schema).contains_key(&key), "{key} is missing");
+        }
+    }
+
+    #[test]
+    fn problems_name_the_line_and_setting() {
+        let file = ConfigFile::parse(
+            r#"
+[base]
+port = 8080
+serch_radius_km = 100
+log_format = "xml"
+gmail_app_password_file = "/run/secrets/gmail"
+
+[profiles.travel]
+inherits = "hom"
+schedule_calendar_sync = "every hour"
+calendar_providers = ["google", "outlook"]
+"#,
+        )
+        .unwrap();
+
+        let problems = validate(&file);
+        assert_eq!(problems.len(), 5, "{problems:#?}");
+        assert_eq!(
+            problems[0],
+            "line 4 in [base]: unknown setting `serch_radius_km`, did you mean `search_radius_km`?"
+        );
+        assert_eq!(
+            problems[1],
+            "line 5 in [base]: `log_format` must be one of text, json, got \"xml\""
+        );
+        assert!(problems[2].starts_with(
+            "line 10 in [profiles.travel]: `schedule_calendar_sync` must be a cron expression"
+        ));
+        assert_eq!(
+            problems[3],
+            "line 11 in [profiles.travel]: `calendar_providers` may only list google, ics, caldav, got \"google,outlook\""
+        );
+        assert!(problems[4].contains("Unknown profile `hom`"));
+    }
+
+    #[rstest]
+    #[case("port", "70000", Err("must be a whole number up to 65535"))]
+    #[case("otel_traces_sampler_arg", "2", Err("must be a number from 0 to 1"))]
+    #[case(
+        "rate_limit_per_minute",
+        "many",
+        Err("must be a whole number or `off`")
+    )]
+    #[case("rate_limit_per_minute", "off", Ok(()))]
+    #[case("mqtt_tls", "yes", Err("must be true or false"))]
+    #[case("google_auth", "service_account", Ok(()))]
+    #[case("calendar_providers", "ics, caldav", Ok(()))]
+    fn values_are_checked_against_the_schema(
+        #[case] key: &str,
+        #[case] value: &str,
+        #[case] expected: Result<(), &str>,
+    ) {
+        let schema = json_schema();
+        let result = check(&schema, &settings(&schema)[key], value);
+        assert_eq!(
+            result.as_ref().map_err(String::as_str),
+            expected.as_ref().map_err(|e| *e)
+        );
+    }
+
+    #[test]
+    fn schema_lists_settings_and_profiles() {
+        let schema = json_schema();
+        let settings = settings(&schema);
+        assert_eq!(
+            choices(&schema, &settings["log_format"]),
+            Some(vec!["text".to_string(), "json".to_string()])
+        );
+        assert_eq!(settings["mqtt_password_file"]["type"], "string");
+        assert_eq!(settings["port"]["type"], "integer");
+        assert!(settings.get("inherits").is_none());
+        assert_eq!(
+            schema["$defs"]["profile"]["properties"]["inherits"]["type"],
+            "string"
+        );
+    }
+}
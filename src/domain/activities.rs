@@ -1,10 +1,25 @@
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::domain::location::Location;
+use crate::domain::{
+    error::{ErrorCode, TravelAiError},
+    location::Location,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActivityKind {
     Paragliding,
+    /// Practising inflations on a flat field, in wind too strong to fly
+    Groundhandling,
+    Hiking,
+    Rest,
+}
+
+impl ActivityKind {
+    /// Fallbacks for days when flying is off.
+    pub fn is_plan_b(self) -> bool {
+        !matches!(self, ActivityKind::Paragliding)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -31,10 +46,115 @@ pub enum Timing {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Score {
     pub value: f32,
     pub reasons: Vec<String>,
+    /// How each factor moved the value, in the order they were applied
+    pub breakdown: Vec<ScoreFactor>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Factor {
+    ForecastStability,
+    SnowCover,
+    ForecastConfidence,
+    WindAlignment,
+    /// Share of the ensemble members that find the best part of the day flyable
+    FlyableChance,
+    /// Highest chance of precipitation during daylight
+    RainChance,
+    /// How high thermals reach above launch, from the soundings
+    ThermalHeight,
+    /// Air unstable enough for overdevelopment and thunderstorms
+    Instability,
+    /// Wind changing with height enough to make the air turbulent
+    WindShear,
+    /// Share of the hours in a part of the day that are flyable
+    FlyableShare,
+    /// Flyable hours of a day, against enough for a full day of flying
+    FlyableHours,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreFactor {
+    pub factor: Factor,
+    /// Exponent applied to `raw_score` before it scales the value
+    pub weight: f32,
+    /// The factor on its own, from 0 to 1
+    pub raw_score: f32,
+    /// Change of the value caused by this factor
+    pub contribution: f32,
+}
+
+impl Score {
+    /// Multiplies the factors' weighted raw scores, starting from 1. No factors, no score.
+    pub fn from_factors(factors: Vec<(Factor, f32, f32)>, reasons: Vec<String>) -> Option<Score> {
+        if factors.is_empty() {
+            return None;
+        }
+        let mut value = 1.0;
+        let breakdown = factors
+            .into_iter()
+            .map(|(factor, weight, raw_score)| {
+                let before = value;
+                value *= raw_score.powf(weight);
+                ScoreFactor {
+                    factor,
+                    weight,
+                    raw_score,
+                    contribution: value - before,
+                }
+            })
+            .collect();
+        Some(Score {
+            value,
+            reasons,
+            breakdown,
+        })
+    }
+
+    /// Lowers the value to at most `cap`, put down to `factor`.
+    pub fn capped(mut self, factor: Factor, cap: f32, reason: String) -> Score {
+        if self.value > cap {
+            self.breakdown.push(ScoreFactor {
+                factor,
+                weight: 1.0,
+                raw_score: cap / self.value,
+                contribution: cap - self.value,
+            });
+            self.value = cap;
+        }
+        self.reasons.push(reason);
+        self
+    }
+
+    pub fn rating(&self) -> DayRating {
+        match self.value {
+            v if v >= 0.8 => DayRating::Excellent,
+            v if v >= 0.5 => DayRating::Good,
+            _ => DayRating::Fair,
+        }
+    }
+}
+
+/// Coarse bucket of a score, ordered from worst to best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayRating {
+    Fair,
+    Good,
+    Excellent,
+}
+
+impl DayRating {
+    pub fn label(self) -> &'static str {
+        match self {
+            DayRating::Fair => "fair",
+            DayRating::Good => "good",
+            DayRating::Excellent => "excellent",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +167,33 @@ pub struct ActivitySuggestion {
     pub score: Option<Score>,
 }
 
+/// What a source suggests, and the sites it had to leave out.
+#[derive(Debug, Clone, Default)]
+pub struct Suggestions {
+    pub suggestions: Vec<ActivitySuggestion>,
+    pub errors: Vec<SiteError>,
+}
+
+/// A site that couldn't be scored, e.g. because its weather couldn't be
+/// fetched, so a ranking missing it says so.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SiteError {
+    pub site: String,
+    /// The most specific code in the error's chain, else `weather_unavailable`
+    pub code: ErrorCode,
+    pub error: String,
+}
+
+impl SiteError {
+    pub fn new(site: &str, error: &anyhow::Error) -> Self {
+        Self {
+            site: site.to_string(),
+            code: TravelAiError::find(error).map_or(ErrorCode::WeatherUnavailable, |e| e.code),
+            error: format!("{error:#}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PlanningContext {
     pub home: Location,
@@ -58,6 +205,61 @@ pub struct PlanningContext {
 mod tests {
     use super::*;
     use chrono::TimeZone;
+    use rstest::rstest;
+
+    #[test]
+    fn score_breakdown_contributions_add_up_to_the_value() {
+        let score = Score::from_factors(
+            vec![
+                (Factor::ForecastStability, 1.0, 0.8),
+                (Factor::ForecastConfidence, 1.0, 0.5),
+            ],
+            vec![],
+        )
+        .unwrap();
+        assert!((score.value - 0.4).abs() < 1e-6);
+        assert!((score.breakdown[0].contribution - -0.2).abs() < 1e-6);
+        assert!((score.breakdown[1].contribution - -0.4).abs() < 1e-6);
+        let total: f32 = score.breakdown.iter().map(|f| f.contribution).sum();
+        assert!((1.0 + total - score.value).abs() < 1e-6);
+    }
+
+    #[rstest]
+    #[case(1.0, DayRating::Excellent)]
+    #[case(0.8, DayRating::Excellent)]
+    #[case(0.6, DayRating::Good)]
+    #[case(0.3, DayRating::Fair)]
+    fn score_value_maps_to_rating(#[case] value: f32, #[case] expected: DayRating) {
+        let score = Score {
+            value,
+            reasons: vec![],
+            breakdown: vec![],
+        };
+        assert_eq!(score.rating(), expected);
+    }
+
+    #[test]
+    fn capping_lowers_only_higher_values_and_keeps_the_breakdown_summed() {
+        let score = Score::from_factors(vec![(Factor::ForecastConfidence, 1.0, 0.9)], vec![])
+            .unwrap()
+            .capped(Factor::WindShear, 0.4, "Shear".into());
+        assert!((score.value - 0.4).abs() < 1e-6);
+        assert_eq!(score.breakdown[1].factor, Factor::WindShear);
+        let total: f32 = score.breakdown.iter().map(|f| f.contribution).sum();
+        assert!((1.0 + total - score.value).abs() < 1e-6);
+
+        let low = Score::from_factors(vec![(Factor::SnowCover, 1.0, 0.3)], vec![])
+            .unwrap()
+            .capped(Factor::WindShear, 0.4, "Shear".into());
+        assert!((low.value - 0.3).abs() < 1e-6);
+        assert_eq!(low.breakdown.len(), 1);
+        assert_eq!(low.reasons, vec!["Shear"]);
+    }
+
+    #[test]
+    fn no_factors_means_no_score() {
+        assert!(Score::from_factors(vec![], vec![]).is_none());
+    }
 
     #[test]
     fn time_window_duration_is_end_minus_start() {
@@ -1,8 +1,11 @@
 use std::fmt::Display;
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::domain::activities::DayRating;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarEvent {
     pub title: String,
     pub start_time: DateTime<Utc>,
@@ -10,6 +13,31 @@ pub struct CalendarEvent {
     pub is_all_day: bool,
     pub location: Option<String>,
     pub body: Option<String>,
+    pub color: Option<EventColor>,
+    /// Reminders as minutes before the start
+    pub reminder_minutes: Vec<u32>,
+    /// Identifies the event across syncs, e.g. `2026-06-13/Site/1000`
+    pub key: Option<String>,
+    /// Site the event is about, so it can be re-checked before it starts
+    pub site: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventColor {
+    Green,
+    Yellow,
+    Orange,
+}
+
+impl From<DayRating> for EventColor {
+    fn from(rating: DayRating) -> Self {
+        match rating {
+            DayRating::Excellent => EventColor::Green,
+            DayRating::Good => EventColor::Yellow,
+            DayRating::Fair => EventColor::Orange,
+        }
+    }
 }
 
 impl CalendarEvent {
@@ -48,6 +76,10 @@ mod tests {
             is_all_day: false,
             location: None,
             body: None,
+            color: None,
+            reminder_minutes: vec![],
+            key: None,
+            site: None,
         }
     }
 
@@ -0,0 +1,211 @@
+//! Errors with a machine-readable code, so API clients can tell "site database
+//! unavailable" apart from "invalid coordinates" instead of guessing from the
+//! status.
+
+use std::{collections::BTreeMap, error::Error, fmt::Display, sync::Arc, time::Duration};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidInput,
+    InvalidCoordinates,
+    ValidationFailed,
+    NotFound,
+    Conflict,
+    PayloadTooLarge,
+    RateLimited,
+    UnparsableUpload,
+    WeatherUnavailable,
+    GeocoderUnavailable,
+    ElevationUnavailable,
+    SoundingUnavailable,
+    SiteDatabaseUnavailable,
+    Cache,
+    Configuration,
+    CalendarAuthExpired,
+    CalendarNotFound,
+    CalendarUnavailable,
+    CalendarSyncFailed,
+    SchedulerJobFailed,
+    JobAlreadyRunning,
+    JobNotConfigured,
+    NotificationUnavailable,
+    Internal,
+}
+
+/// Context key for how many seconds to wait before retrying, overriding
+/// [`ErrorCode::retry_after`], e.g. what a rate limit asked for.
+pub const RETRY_AFTER_KEY: &str = "retry_after_secs";
+
+impl ErrorCode {
+    /// How long to wait before trying again after a failure that may go away
+    /// by itself, such as an upstream outage. `None` for failures that need a
+    /// fix or the user, e.g. invalid input or an expired sign-in.
+    pub fn retry_after(self) -> Option<Duration> {
+        match self {
+            ErrorCode::RateLimited => Some(Duration::from_mins(1)),
+            ErrorCode::WeatherUnavailable
+            | ErrorCode::GeocoderUnavailable
+            | ErrorCode::ElevationUnavailable
+            | ErrorCode::SoundingUnavailable
+            | ErrorCode::SiteDatabaseUnavailable
+            | ErrorCode::CalendarUnavailable
+            | ErrorCode::NotificationUnavailable => Some(Duration::from_secs(30)),
+            ErrorCode::CalendarSyncFailed => Some(Duration::from_mins(5)),
+            ErrorCode::InvalidInput
+            | ErrorCode::InvalidCoordinates
+            | ErrorCode::ValidationFailed
+            | ErrorCode::NotFound
+            | ErrorCode::Conflict
+            | ErrorCode::PayloadTooLarge
+            | ErrorCode::UnparsableUpload
+            | ErrorCode::Cache
+            | ErrorCode::Configuration
+            | ErrorCode::CalendarAuthExpired
+            | ErrorCode::CalendarNotFound
+            | ErrorCode::SchedulerJobFailed
+            | ErrorCode::JobAlreadyRunning
+            | ErrorCode::JobNotConfigured
+            | ErrorCode::Internal => None,
+        }
+    }
+}
+
+/// An error meant to be shown to the user: `message` is safe to display and
+/// `context` names what it is about, e.g. the site or the coordinates.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct TravelAiError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub context: BTreeMap<String, String>,
+    /// What went wrong underneath, for logs; never sent to API clients
+    #[source]
+    pub source: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+impl TravelAiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            context: BTreeMap::new(),
+            source: None,
+        }
+    }
+
+    /// The innermost typed error in `error`'s chain, i.e. the most specific one.
+    pub fn find(error: &anyhow::Error) -> Option<&TravelAiError> {
+        error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<TravelAiError>())
+            .last()
+            .or_else(|| error.downcast_ref())
+    }
+
+    pub fn with_source(mut self, source: impl Into<anyhow::Error>) -> Self {
+        let source: Box<dyn Error + Send + Sync> = source.into().into();
+        self.source = Some(Arc::from(source));
+        self
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl Display) -> Self {
+        self.context.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.code.retry_after().is_some()
+    }
+
+    /// The code's delay, or the one given under [`RETRY_AFTER_KEY`].
+    pub fn retry_after(&self) -> Option<Duration> {
+        let default = self.code.retry_after()?;
+        let requested = self
+            .context
+            .get(RETRY_AFTER_KEY)
+            .and_then(|s| s.parse().ok());
+        Some(requested.map_or(default, Duration::from_secs))
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(what: &str, name: impl Display) -> Self {
+        Self::new(ErrorCode::NotFound, format!("{what} not found")).with_context("name", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_found_names_the_missing_thing() {
+        let error = TravelAiError::not_found("Site", "Wank");
+        assert_eq!(error.code, ErrorCode::NotFound);
+        assert_eq!(error.to_string(), "Site not found");
+        assert_eq!(error.context["name"], "Wank");
+    }
+
+    #[test]
+    fn find_returns_the_innermost_typed_error() {
+        let auth = TravelAiError::new(ErrorCode::CalendarAuthExpired, "Sign in again");
+        let error = anyhow::Error::new(auth)
+            .context("Failed to list calendars")
+            .context(TravelAiError::new(
+                ErrorCode::CalendarSyncFailed,
+                "Sync failed",
+            ));
+
+        let found = TravelAiError::find(&error).unwrap();
+        assert_eq!(found.code, ErrorCode::CalendarAuthExpired);
+        assert!(TravelAiError::find(&anyhow::anyhow!("untyped")).is_none());
+    }
+
+    #[test]
+    fn source_shows_in_the_error_chain() {
+        let error = anyhow::Error::new(
+            TravelAiError::new(
+                ErrorCode::CalendarUnavailable,
+                "Google Calendar unavailable",
+            )
+            .with_source(anyhow::anyhow!("connection reset")),
+        );
+        assert_eq!(
+            format!("{error:#}"),
+            "Google Calendar unavailable: connection reset"
+        );
+    }
+
+    #[test]
+    fn retryability_follows_the_code() {
+        let outage = TravelAiError::new(ErrorCode::WeatherUnavailable, "Open-Meteo is down");
+        assert!(outage.is_retryable());
+        assert_eq!(outage.retry_after(), Some(Duration::from_secs(30)));
+
+        let expired = TravelAiError::new(ErrorCode::CalendarAuthExpired, "Sign in again");
+        assert!(!expired.is_retryable());
+        assert_eq!(expired.retry_after(), None);
+    }
+
+    #[test]
+    fn requested_delay_overrides_the_default() {
+        let limited = TravelAiError::new(ErrorCode::RateLimited, "Too many requests")
+            .with_context(RETRY_AFTER_KEY, 7);
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(7)));
+
+        let invalid =
+            TravelAiError::invalid_input("Unknown model").with_context(RETRY_AFTER_KEY, 7);
+        assert_eq!(invalid.retry_after(), None);
+    }
+
+    #[test]
+    fn codes_serialize_in_snake_case() {
+        let json = serde_json::to_string(&ErrorCode::SiteDatabaseUnavailable).unwrap();
+        assert_eq!(json, "\"site_database_unavailable\"");
+    }
+}
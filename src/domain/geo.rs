@@ -0,0 +1,184 @@
+//! Great-circle math on (latitude, longitude) points in degrees, shared by site
+//! search, routing and the region outlines.
+
+use ::geo::{Bearing, Destination, Distance, Haversine, Point};
+
+/// Mean earth radius, as used by [`Haversine`]
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Great-circle distance in km.
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    Haversine.distance(point(from), point(to)) / 1000.0
+}
+
+/// Compass bearing in degrees (0 = north, 90 = east) to set off with from
+/// `from` towards `to`.
+pub fn initial_bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
+    Haversine.bearing(point(from), point(to)).rem_euclid(360.0)
+}
+
+/// The point `distance_km` away from `from` in direction `bearing_degrees`.
+pub fn destination(from: (f64, f64), bearing_degrees: f64, distance_km: f64) -> (f64, f64) {
+    let to = Haversine.destination(point(from), bearing_degrees, distance_km * 1000.0);
+    (to.y(), to.x())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, (latitude, longitude): (f64, f64)) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&latitude)
+            && (self.min_longitude..=self.max_longitude).contains(&longitude)
+    }
+}
+
+/// Smallest box holding every point within `radius_km` of `center`, for cheap
+/// prefiltering before exact distances. Near the poles or across the
+/// antimeridian it spans all longitudes.
+pub fn bbox_from_radius(center: (f64, f64), radius_km: f64) -> BoundingBox {
+    let (latitude, longitude) = center;
+    let angular = radius_km / EARTH_RADIUS_KM;
+    let min_latitude = latitude - angular.to_degrees();
+    let max_latitude = latitude + angular.to_degrees();
+    if min_latitude <= -90.0 || max_latitude >= 90.0 {
+        return BoundingBox {
+            min_latitude: min_latitude.max(-90.0),
+            max_latitude: max_latitude.min(90.0),
+            min_longitude: -180.0,
+            max_longitude: 180.0,
+        };
+    }
+
+    let spread = (angular.sin() / latitude.to_radians().cos())
+        .asin()
+        .to_degrees();
+    let (min_longitude, max_longitude) = (longitude - spread, longitude + spread);
+    let wraps = spread.is_nan() || min_longitude < -180.0 || max_longitude > 180.0;
+    BoundingBox {
+        min_latitude,
+        max_latitude,
+        min_longitude: if wraps { -180.0 } else { min_longitude },
+        max_longitude: if wraps { 180.0 } else { max_longitude },
+    }
+}
+
+/// Distance in km of `point` from the polyline `route` and how far along the
+/// route the closest spot lies. Each segment is measured on a plane tangent at
+/// the point, which is accurate enough for corridors of a few dozen km.
+pub fn distance_to_polyline(route: &[(f64, f64)], point: (f64, f64)) -> Option<(f64, f64)> {
+    let km_per_degree = EARTH_RADIUS_KM.to_radians();
+    let lon_scale = point.0.to_radians().cos();
+    let project = |(lat, lon): (f64, f64)| {
+        (
+            (lon - point.1) * lon_scale * km_per_degree,
+            (lat - point.0) * km_per_degree,
+        )
+    };
+
+    if let [only] = route {
+        let (x, y) = project(*only);
+        return Some((x.hypot(y), 0.0));
+    }
+    let mut best: Option<(f64, f64)> = None;
+    let mut travelled = 0.0;
+    for segment in route.windows(2) {
+        let (ax, ay) = project(segment[0]);
+        let (bx, by) = project(segment[1]);
+        let (dx, dy) = (bx - ax, by - ay);
+        let length_sq = dx * dx + dy * dy;
+        let t = if length_sq == 0.0 {
+            0.0
+        } else {
+            (-(ax * dx + ay * dy) / length_sq).clamp(0.0, 1.0)
+        };
+        let distance = (ax + t * dx).hypot(ay + t * dy);
+        let length = length_sq.sqrt();
+        if best.is_none_or(|(d, _)| distance < d) {
+            best = Some((distance, travelled + t * length));
+        }
+        travelled += length;
+    }
+    best
+}
+
+/// Even-odd ray casting over (latitude, longitude) corners; fine for outlines
+/// far smaller than a hemisphere.
+pub fn point_in_polygon(polygon: &[(f64, f64)], (latitude, longitude): (f64, f64)) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, &(lat_i, lon_i)) in polygon.iter().enumerate() {
+        let (lat_j, lon_j) = polygon[j];
+        if (lat_i > latitude) != (lat_j > latitude)
+            && longitude < (lon_j - lon_i) * (latitude - lat_i) / (lat_j - lat_i) + lon_i
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn point((latitude, longitude): (f64, f64)) -> Point {
+    Point::new(longitude, latitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const BERLIN: (f64, f64) = (52.520, 13.405);
+    const MUNICH: (f64, f64) = (48.137, 11.575);
+
+    #[test]
+    fn berlin_munich_distance_and_bearing() {
+        assert!((distance_km(BERLIN, MUNICH) - 504.0).abs() < 5.0);
+        let bearing = initial_bearing(BERLIN, MUNICH);
+        assert!((195.0..200.0).contains(&bearing), "{bearing}");
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(90.0)]
+    #[case(225.0)]
+    fn destination_is_the_given_distance_and_bearing_away(#[case] bearing: f64) {
+        let to = destination(BERLIN, bearing, 80.0);
+        assert!((distance_km(BERLIN, to) - 80.0).abs() < 0.01);
+        let back = initial_bearing(BERLIN, to);
+        let off = (back - bearing + 180.0).rem_euclid(360.0) - 180.0;
+        assert!(off.abs() < 0.5, "{back}");
+    }
+
+    #[test]
+    fn bbox_holds_every_point_on_the_circle() {
+        let bbox = bbox_from_radius(MUNICH, 150.0);
+        for step in 0..36 {
+            let edge = destination(MUNICH, f64::from(step * 10), 149.9);
+            assert!(bbox.contains(edge), "{edge:?}");
+        }
+        assert!(!bbox.contains(BERLIN));
+    }
+
+    #[test]
+    fn bbox_spans_all_longitudes_when_unbounded() {
+        let bbox = bbox_from_radius(MUNICH, f64::INFINITY);
+        assert!(bbox.contains((-45.0, -170.0)));
+        let polar = bbox_from_radius((89.5, 0.0), 100.0);
+        assert_eq!((polar.min_longitude, polar.max_longitude), (-180.0, 180.0));
+    }
+
+    #[test]
+    fn polyline_distance_and_progress() {
+        let route = [(50.0, 10.0), (50.0, 12.0)];
+        let (distance, along) = distance_to_polyline(&route, (50.05, 10.5)).unwrap();
+        assert!((distance - 5.56).abs() < 0.1);
+        assert!((along - 35.7).abs() < 0.5);
+        assert_eq!(distance_to_polyline(&[], (50.0, 10.0)), None);
+    }
+}
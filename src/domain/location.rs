@@ -1,6 +1,52 @@
-use haversine::{Location as HaversineLocation, Units, distance};
 use serde::{Deserialize, Serialize};
 
+use crate::domain::geo;
+
+/// Lower case, Latin diacritics folded to their base letter and separators
+/// turned into single spaces, so "Gornau/Erz", "gornau erz " and "Görnau-Erz"
+/// compare equal. Anything further apart is a different place: "Born" is not
+/// "Bern".
+pub fn normalize_place_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.trim().to_lowercase().chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => out.push('a'),
+            'ç' | 'ć' | 'č' => out.push('c'),
+            'ď' => out.push('d'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => out.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => out.push('i'),
+            'ł' => out.push('l'),
+            'ñ' | 'ń' | 'ň' => out.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ő' => out.push('o'),
+            'ř' => out.push('r'),
+            'ś' | 'š' => out.push('s'),
+            'ß' => out.push_str("ss"),
+            'ť' => out.push('t'),
+            'ù' | 'ú' | 'û' | 'ü' | 'ů' | 'ű' => out.push('u'),
+            'ý' | 'ÿ' => out.push('y'),
+            'ź' | 'ż' | 'ž' => out.push('z'),
+            c if c.is_whitespace() || matches!(c, '/' | '-' | ',' | '.' | '\'') => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Whether `latitude` is within ±90°.
+pub fn is_valid_latitude(latitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude)
+}
+
+/// Whether `longitude` is within ±180°.
+pub fn is_valid_longitude(longitude: f64) -> bool {
+    (-180.0..=180.0).contains(&longitude)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Location {
     pub latitude: f64,
@@ -9,6 +55,15 @@ pub struct Location {
     pub country: String,
 }
 
+/// One match for a place name. Equally named places are told apart by their
+/// administrative area.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GeocodeCandidate {
+    pub location: Location,
+    /// State or region, e.g. "Saxony"
+    pub admin_area: Option<String>,
+}
+
 impl Location {
     pub fn new(latitude: f64, longitude: f64, name: String, country: String) -> Self {
         Self {
@@ -28,15 +83,12 @@ impl Location {
     }
 
     pub fn calculate_distance(from: &Location, to: &Location) -> f64 {
-        let from_haversine = HaversineLocation {
-            latitude: from.latitude,
-            longitude: from.longitude,
-        };
-        let to_haversine = HaversineLocation {
-            latitude: to.latitude,
-            longitude: to.longitude,
-        };
-        distance(from_haversine, to_haversine, Units::Kilometers)
+        geo::distance_km(from.coordinates(), to.coordinates())
+    }
+
+    /// As (latitude, longitude), the form [`geo`] works with
+    pub fn coordinates(&self) -> (f64, f64) {
+        (self.latitude, self.longitude)
     }
 
     pub fn to_key(&self) -> String {
@@ -80,6 +132,14 @@ mod tests {
         assert_eq!(a.to_key(), "50700000_13000000_Test_DE");
     }
 
+    #[test]
+    fn normalize_place_name_folds_case_diacritics_and_separators() {
+        assert_eq!(normalize_place_name("  Gornau/Erz "), "gornau erz");
+        assert_eq!(normalize_place_name("Görnau - Erz."), "gornau erz");
+        assert_eq!(normalize_place_name("Zell  am\tSee"), "zell am see");
+        assert_eq!(normalize_place_name("Großglockner"), "grossglockner");
+    }
+
     #[test]
     fn to_key_distinguishes_distant_locations() {
         let a = Location::new(50.7, 13.0, "A".into(), "DE".into());
@@ -1,6 +1,11 @@
 pub mod activities;
 pub mod calendar;
+pub mod error;
+pub mod geo;
 pub mod location;
 pub mod paragliding;
 pub mod ports;
+pub mod regions;
+pub mod sounding;
+pub mod units;
 pub mod weather;
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::paragliding::closure::{self, Closure};
+
+/// Free text a site database publishes about a site.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SiteCharacteristics {
+    pub flight_rules: Option<String>,
+    pub going_there: Option<String>,
+    pub weather: Option<String>,
+}
+
+/// Restrictions recognised in a site's flight rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SiteRule {
+    RadioMandatory,
+    NoWeekendFlying,
+    MembershipRequired,
+    FeeRequired,
+    PermissionRequired,
+    /// Controlled or restricted airspace nearby, or a height limit
+    AirspaceRestriction,
+}
+
+// Lower case phrases in the languages site descriptions are usually written in.
+const RULE_KEYWORDS: &[(SiteRule, &[&str])] = &[
+    (
+        SiteRule::RadioMandatory,
+        &[
+            "radio mandatory",
+            "radio required",
+            "funk pflicht",
+            "funkpflicht",
+        ],
+    ),
+    (
+        SiteRule::NoWeekendFlying,
+        &[
+            "no weekend flying",
+            "no flying on weekends",
+            "not on weekends",
+            "closed on weekends",
+            "am wochenende kein",
+        ],
+    ),
+    (
+        SiteRule::MembershipRequired,
+        &["members only", "membership required", "nur für mitglieder"],
+    ),
+    (
+        SiteRule::FeeRequired,
+        &[
+            "landing fee",
+            "launch fee",
+            "day fee",
+            "tageskarte",
+            "gebühr",
+        ],
+    ),
+    (
+        SiteRule::PermissionRequired,
+        &[
+            "permission required",
+            "prior permission",
+            "anmeldung erforderlich",
+        ],
+    ),
+    (
+        SiteRule::AirspaceRestriction,
+        &[
+            "airspace",
+            "control zone",
+            "restricted area",
+            "height limit",
+            "luftraum",
+            "kontrollzone",
+            "flugbeschränkungsgebiet",
+            "höhenbegrenzung",
+        ],
+    ),
+];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+impl SiteRule {
+    pub fn warning(&self) -> &'static str {
+        match self {
+            SiteRule::RadioMandatory => "Radio is mandatory at this site",
+            SiteRule::NoWeekendFlying => "No flying on weekends at this site",
+            SiteRule::MembershipRequired => "Club membership required",
+            SiteRule::FeeRequired => "A site fee applies",
+            SiteRule::PermissionRequired => "Ask for permission before flying",
+            SiteRule::AirspaceRestriction => "Mind the airspace restrictions at this site",
+        }
+    }
+}
+
+impl SiteCharacteristics {
+    pub fn is_empty(&self) -> bool {
+        [&self.flight_rules, &self.going_there, &self.weather]
+            .iter()
+            .all(|text| text.as_deref().is_none_or(|t| t.trim().is_empty()))
+    }
+
+    /// Rules found by keyword in the flight rules text.
+    pub fn rules(&self) -> Vec<SiteRule> {
+        let Some(text) = self.flight_rules.as_deref() else {
+            return vec![];
+        };
+        let text = text.to_lowercase();
+        RULE_KEYWORDS
+            .iter()
+            .filter(|(_, keywords)| keywords.iter().any(|k| text.contains(k)))
+            .map(|(rule, _)| *rule)
+            .collect()
+    }
+
+    /// Yearly closures stated in the flight rules, such as "closed 1 Apr-15 Jul
+    /// for raptor nesting".
+    pub fn closures(&self) -> Vec<Closure> {
+        let Some(text) = self.flight_rules.as_deref() else {
+            return vec![];
+        };
+        text.to_lowercase()
+            .split([';', '\n'])
+            .filter_map(parse_closure)
+            .collect()
+    }
+}
+
+/// The first two day-month dates after "closed" as a yearly closure, with the
+/// rest of the sentence as the reason.
+fn parse_closure(text: &str) -> Option<Closure> {
+    let (_, rest) = text.split_once("closed")?;
+    let words: Vec<&str> = rest
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | ','))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let month = |word: &str| {
+        let word = word.trim_end_matches('.');
+        (word.len() >= 3)
+            .then(|| MONTHS.iter().position(|m| word.starts_with(m)))
+            .flatten()
+            .map(|i| i as u32 + 1)
+    };
+
+    let mut dates = Vec::new();
+    let mut i = 0;
+    while i + 1 < words.len() && dates.len() < 2 {
+        match (
+            words[i].trim_end_matches('.').parse::<u32>(),
+            month(words[i + 1]),
+        ) {
+            (Ok(day), Some(month)) => {
+                dates.push((month, day));
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    let [from, to] = dates[..] else {
+        return None;
+    };
+    let reason = words[i..].join(" ");
+    let reason = reason.split('.').next().unwrap_or_default();
+    let reason = reason.strip_prefix("for ").unwrap_or(reason).trim();
+    closure::yearly(from, to, if reason.is_empty() { "closed" } else { reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn with_rules(text: &str) -> SiteCharacteristics {
+        SiteCharacteristics {
+            flight_rules: Some(text.into()),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case("Radio mandatory, frequency 144.975", vec![SiteRule::RadioMandatory])]
+    #[case("NO WEEKEND FLYING in summer", vec![SiteRule::NoWeekendFlying])]
+    #[case("Funkpflicht! Tageskarte am Kiosk", vec![SiteRule::RadioMandatory, SiteRule::FeeRequired])]
+    #[case("Luftraum E beachten, max. 2500 m", vec![SiteRule::AirspaceRestriction])]
+    #[case("Be nice to the farmer", vec![])]
+    fn rules_are_found_by_keyword(#[case] text: &str, #[case] expected: Vec<SiteRule>) {
+        assert_eq!(with_rules(text).rules(), expected);
+    }
+
+    #[test]
+    fn closures_are_read_from_the_rules() {
+        let rules = with_rules("Radio required; Closed 1 Apr – 15 Jul for raptor nesting. Thanks!");
+        let closures = rules.closures();
+        assert_eq!(closures.len(), 1);
+        assert_eq!(closures[0].reason, "raptor nesting");
+        assert!(closures[0].covers(chrono::NaiveDate::from_ymd_opt(2026, 7, 15).unwrap()));
+        assert!(!closures[0].covers(chrono::NaiveDate::from_ymd_opt(2026, 7, 16).unwrap()));
+
+        assert!(with_rules("Closed on weekends").closures().is_empty());
+    }
+
+    #[test]
+    fn blank_texts_are_empty() {
+        assert!(SiteCharacteristics::default().is_empty());
+        assert!(with_rules("  ").is_empty());
+        assert!(!with_rules("Radio required").is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Periods a site may not be flown, such as maintenance, the hunting season or
+//! raptor nesting. Flying is ruled out on every closed day.
+
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+// Any leap year, so that yearly closures can start or end on 29 Feb.
+pub(crate) const YEARLY_BASE: i32 = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Closure {
+    /// First closed day
+    pub from: NaiveDate,
+    /// Last closed day
+    pub to: NaiveDate,
+    /// Recurs on the same days every year; the years of `from` and `to` are
+    /// then ignored and the period may wrap around the new year
+    pub yearly: bool,
+    pub reason: String,
+    /// Read from free text rather than configured, so it may be wrong
+    #[serde(default)]
+    pub needs_review: bool,
+}
+
+impl Closure {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        if !self.yearly {
+            return self.from <= date && date <= self.to;
+        }
+        let day_of_year = |d: NaiveDate| (d.month(), d.day());
+        let (from, to, day) = (
+            day_of_year(self.from),
+            day_of_year(self.to),
+            day_of_year(date),
+        );
+        if from <= to {
+            from <= day && day <= to
+        } else {
+            day >= from || day <= to
+        }
+    }
+}
+
+/// `<from>..<to> <reason>`, where dates are `YYYY-MM-DD` for a single period
+/// or `MM-DD` for one that recurs yearly, e.g. `04-01..07-15 raptor nesting`.
+impl FromStr for Closure {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (range, reason) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let (from, to) = range
+            .split_once("..")
+            .ok_or_else(|| anyhow::anyhow!("Invalid closure {s:?}, expected <from>..<to>"))?;
+        let (from, from_yearly) = parse_date(from)?;
+        let (to, to_yearly) = parse_date(to)?;
+        if from_yearly != to_yearly {
+            anyhow::bail!("Closure {s:?} mixes yearly and dated days");
+        }
+        if !from_yearly && to < from {
+            anyhow::bail!("Closure {s:?} ends before it starts");
+        }
+        let reason = reason.trim();
+        Ok(Closure {
+            from,
+            to,
+            yearly: from_yearly,
+            reason: if reason.is_empty() {
+                "closed".to_string()
+            } else {
+                reason.to_string()
+            },
+            needs_review: false,
+        })
+    }
+}
+
+/// A `YYYY-MM-DD` date, or a `MM-DD` one in the yearly base, flagged `true`.
+fn parse_date(s: &str) -> anyhow::Result<(NaiveDate, bool)> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok((date, false));
+    }
+    NaiveDate::parse_from_str(&format!("{YEARLY_BASE}-{s}"), "%Y-%m-%d")
+        .map(|date| (date, true))
+        .map_err(|_| anyhow::anyhow!("Invalid closure date {s:?}, expected YYYY-MM-DD or MM-DD"))
+}
+
+/// A closure recurring yearly from `from` to `to`, given as (month, day).
+pub fn yearly(from: (u32, u32), to: (u32, u32), reason: &str) -> Option<Closure> {
+    Some(Closure {
+        from: NaiveDate::from_ymd_opt(YEARLY_BASE, from.0, from.1)?,
+        to: NaiveDate::from_ymd_opt(YEARLY_BASE, to.0, to.1)?,
+        yearly: true,
+        reason: reason.to_string(),
+        needs_review: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[rstest]
+    #[case("04-01..07-15 raptor nesting", date(2027, 4, 1), true)]
+    #[case("04-01..07-15 raptor nesting", date(2026, 7, 16), false)]
+    #[case("11-15..02-28 hunting season", date(2027, 1, 10), true)]
+    #[case("11-15..02-28 hunting season", date(2026, 6, 1), false)]
+    #[case("2026-05-01..2026-05-03 maintenance", date(2026, 5, 3), true)]
+    #[case("2026-05-01..2026-05-03 maintenance", date(2027, 5, 2), false)]
+    fn closures_cover_their_days(
+        #[case] closure: &str,
+        #[case] date: NaiveDate,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(closure.parse::<Closure>().unwrap().covers(date), expected);
+    }
+
+    #[test]
+    fn reason_defaults_and_bad_ranges_fail() {
+        let closure: Closure = "2026-05-01..2026-05-03".parse().unwrap();
+        assert_eq!(closure.reason, "closed");
+        assert!(!closure.yearly);
+
+        assert!("04-01 raptor nesting".parse::<Closure>().is_err());
+        assert!("04-01..2026-07-15".parse::<Closure>().is_err());
+        assert!("2026-05-03..2026-05-01".parse::<Closure>().is_err());
+        assert!("13-01..07-15".parse::<Closure>().is_err());
+    }
+}
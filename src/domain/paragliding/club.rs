@@ -0,0 +1,73 @@
+//! Members of a club sharing one calendar, each told only about the days that
+//! suit their skill level.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillLevel {
+    /// Flies supervised at school sites only
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl SkillLevel {
+    pub const ALL: [SkillLevel; 3] = [
+        SkillLevel::Beginner,
+        SkillLevel::Intermediate,
+        SkillLevel::Advanced,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SkillLevel::Beginner => "beginner",
+            SkillLevel::Intermediate => "intermediate",
+            SkillLevel::Advanced => "advanced",
+        }
+    }
+}
+
+impl FromStr for SkillLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SkillLevel::ALL
+            .into_iter()
+            .find(|level| level.label() == s.trim())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown skill level {s:?}, expected beginner, intermediate or advanced"
+                )
+            })
+    }
+}
+
+/// Where a member hears about flyable days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contact {
+    Email(String),
+    /// A retained message on `<prefix>/club/<member>` of the MQTT broker
+    Mqtt,
+}
+
+impl FromStr for Contact {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "mqtt" => Ok(Contact::Mqtt),
+            address if address.contains('@') => Ok(Contact::Email(address.to_string())),
+            other => anyhow::bail!("Unknown contact {other:?}, expected an email address or mqtt"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClubMember {
+    pub name: String,
+    pub skill: SkillLevel,
+    pub contact: Contact,
+}
@@ -0,0 +1,102 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::paragliding::ParaglidingSite;
+
+// A long soarable day draws pilots from further away than a short window does.
+const GOOD_DAY_FLYABLE_HOURS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Crowding {
+    Low,
+    Moderate,
+    High,
+}
+
+/// Rough guess of how busy a launch will be. There is no live data behind this;
+/// it only combines what drives crowds in practice: weekends, well-known sites
+/// (a high rating or listed in `BUSY_SITES`) and good days.
+pub fn estimate_crowding(
+    site: &ParaglidingSite,
+    date: NaiveDate,
+    flyable_hours: usize,
+) -> Crowding {
+    if flyable_hours == 0 {
+        return Crowding::Low;
+    }
+
+    let mut points = 0;
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        points += 2;
+    }
+    points += match site.rating {
+        Some(5) => 2,
+        Some(4) => 1,
+        Some(0..=2) => -1,
+        _ => 0,
+    };
+    if site.busy == Some(true) {
+        points += 2;
+    }
+    if flyable_hours >= GOOD_DAY_FLYABLE_HOURS {
+        points += 1;
+    }
+
+    match points {
+        p if p >= 3 => Crowding::High,
+        p if p >= 1 => Crowding::Moderate,
+        _ => Crowding::Low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn site(rating: Option<u8>) -> ParaglidingSite {
+        ParaglidingSite {
+            name: "Hill".into(),
+            data_source: "test".into(),
+            rating,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn listed_busy_sites_draw_crowds_on_weekdays() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+        let busy = ParaglidingSite {
+            busy: Some(true),
+            ..site(None)
+        };
+        assert_eq!(
+            estimate_crowding(&site(None), wednesday, 8),
+            Crowding::Moderate
+        );
+        assert_eq!(estimate_crowding(&busy, wednesday, 8), Crowding::High);
+    }
+
+    // 2026-06-13 is a Saturday, 2026-06-10 a Wednesday.
+    #[rstest]
+    #[case(Some(5), 13, 8, Crowding::High)]
+    #[case(None, 13, 8, Crowding::High)]
+    #[case(None, 13, 3, Crowding::Moderate)]
+    #[case(Some(2), 13, 3, Crowding::Moderate)]
+    #[case(Some(5), 10, 8, Crowding::High)]
+    #[case(Some(4), 10, 3, Crowding::Moderate)]
+    #[case(None, 10, 3, Crowding::Low)]
+    #[case(Some(5), 13, 0, Crowding::Low)]
+    fn crowding_cases(
+        #[case] rating: Option<u8>,
+        #[case] day: u32,
+        #[case] flyable_hours: usize,
+        #[case] expected: Crowding,
+    ) {
+        let date = NaiveDate::from_ymd_opt(2026, 6, day).unwrap();
+        assert_eq!(
+            estimate_crowding(&site(rating), date, flyable_hours),
+            expected
+        );
+    }
+}
@@ -7,33 +7,8 @@ use std::{
 use chrono::{DateTime, Duration, Utc};
 use geo::{Bearing as _, Distance as GeoDistance};
 
-pub struct Distance(f64); //distance in meters
-
-impl Distance {
-    pub fn from_km(km: f64) -> Self {
-        Distance(km * 1000.0)
-    }
-    pub fn from_meters(m: f64) -> Self {
-        Distance(m)
-    }
-}
-
-impl Add for Distance {
-    type Output = Self;
-    fn add(self, other: Self) -> Self {
-        Distance(self.0 + other.0)
-    }
-}
-
-impl Display for Distance {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0 > 1000.0 {
-            write!(f, "{:.2}km", self.0 / 1000.0)
-        } else {
-            write!(f, "{:.0}m", self.0)
-        }
-    }
-}
+pub use crate::domain::units::Distance;
+use crate::domain::units::KMH_PER_MS;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Angle(f64);
@@ -156,7 +131,7 @@ impl Display for ScalarVelocity {
         if self.0 < 4.0 {
             write!(f, "{:.1} m/s", self.0)
         } else {
-            write!(f, "{:.1} km/h", self.0 * 3.6)
+            write!(f, "{:.1} km/h", self.0 * f64::from(KMH_PER_MS))
         }
     }
 }
@@ -179,7 +154,7 @@ impl Div<Duration> for Distance {
     type Output = ScalarVelocity;
 
     fn div(self, rhs: Duration) -> Self::Output {
-        ScalarVelocity(self.0 / rhs.as_seconds_f64())
+        ScalarVelocity(self.meters() / rhs.as_seconds_f64())
     }
 }
 
@@ -228,24 +203,42 @@ pub struct Track {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn angle_difference_stays_within_half_turn(
+            a in -720.0..720.0f64,
+            b in -720.0..720.0f64,
+        ) {
+            let d = (Angle(a) - Angle(b)).0;
+            prop_assert!((-180.0..=180.0).contains(&d), "got {d}");
+        }
 
-    #[test]
-    fn distance_from_km_and_from_meters_are_equivalent() {
-        let a = Distance::from_km(1.0);
-        let b = Distance::from_meters(1000.0);
-        assert_eq!(a.0, b.0);
-    }
-
-    #[test]
-    fn distance_display_switches_units_at_one_kilometer() {
-        assert_eq!(format!("{}", Distance::from_meters(800.0)), "800m");
-        assert_eq!(format!("{}", Distance::from_km(1.5)), "1.50km");
-    }
+        #[test]
+        fn angle_difference_is_antisymmetric(a in 0..360i32, b in 0..360i32) {
+            let ab = (Angle(f64::from(a)) - Angle(f64::from(b))).0;
+            let ba = (Angle(f64::from(b)) - Angle(f64::from(a))).0;
+            prop_assert_eq!(ab.abs(), ba.abs());
+            if ab.abs() != 180.0 {
+                prop_assert_eq!(ab, -ba);
+            }
+        }
 
-    #[test]
-    fn distance_addition_sums_meters() {
-        let s = Distance::from_meters(100.0) + Distance::from_meters(250.0);
-        assert_eq!(s.0, 350.0);
+        #[test]
+        fn angle_difference_ignores_full_turns(
+            a in 0..360i32,
+            b in 0..360i32,
+            turns in -3..3i32,
+        ) {
+            let plain = (Angle(f64::from(a)) - Angle(f64::from(b))).0;
+            let wrapped = (Angle(f64::from(a + 360 * turns)) - Angle(f64::from(b))).0;
+            // Half a turn may come out as either +180 or -180.
+            prop_assert_eq!(plain.abs(), wrapped.abs());
+            if plain.abs() != 180.0 {
+                prop_assert_eq!(plain, wrapped);
+            }
+        }
     }
 
     #[test]
@@ -318,7 +311,7 @@ mod tests {
             longitude: 13.0,
             height: 1000.0,
         };
-        assert_eq!(p.distance(&p).0, 0.0);
+        assert_eq!(p.distance(&p).meters(), 0.0);
     }
 
     #[test]
@@ -333,7 +326,7 @@ mod tests {
             longitude: 13.0,
             height: 100.0,
         };
-        let d = a.distance(&b).0;
+        let d = a.distance(&b).meters();
         assert!((d - 100.0).abs() < 0.001, "expected ~100m, got {d}");
     }
 }
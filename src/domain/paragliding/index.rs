@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rstar::{AABB, RTree, primitives::GeomWithData};
+use serde::Serialize;
+
+use crate::domain::{
+    geo::{self, BoundingBox},
+    location::Location,
+    paragliding::ParaglidingSite,
+};
+
+/// A launch as (longitude, latitude), pointing at its site.
+type LaunchPoint = GeomWithData<[f64; 2], usize>;
+
+/// Sites with an R-tree over their launches, so area searches only look at
+/// launches near the area instead of every site.
+#[derive(Debug, Default)]
+pub struct SiteIndex {
+    sites: Vec<ParaglidingSite>,
+    launches: RTree<LaunchPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteAlongRoute {
+    #[serde(flatten)]
+    pub site: ParaglidingSite,
+    /// Distance of the closest launch from the route
+    pub distance_km: f64,
+    /// How far along the route the site is passed
+    pub along_route_km: f64,
+}
+
+impl SiteIndex {
+    pub fn new(sites: Vec<ParaglidingSite>) -> Self {
+        let points = sites
+            .iter()
+            .enumerate()
+            .flat_map(|(i, site)| {
+                site.launches.iter().map(move |launch| {
+                    let (latitude, longitude) = launch.location.coordinates();
+                    LaunchPoint::new([longitude, latitude], i)
+                })
+            })
+            .collect();
+        Self {
+            sites,
+            launches: RTree::bulk_load(points),
+        }
+    }
+
+    pub fn sites(&self) -> &[ParaglidingSite] {
+        &self.sites
+    }
+
+    /// Launch coordinates inside `bbox`, grouped by site.
+    fn candidates(&self, bbox: &BoundingBox) -> BTreeMap<usize, Vec<(f64, f64)>> {
+        let envelope = AABB::from_corners(
+            [bbox.min_longitude, bbox.min_latitude],
+            [bbox.max_longitude, bbox.max_latitude],
+        );
+        let mut candidates: BTreeMap<usize, Vec<(f64, f64)>> = BTreeMap::new();
+        for launch in self.launches.locate_in_envelope(&envelope) {
+            let [longitude, latitude] = *launch.geom();
+            candidates
+                .entry(launch.data)
+                .or_default()
+                .push((latitude, longitude));
+        }
+        candidates
+    }
+
+    /// Every site with a launch within `radius_km` of `center`, paired with
+    /// the distance to its closest launch and sorted closest first.
+    pub fn within_radius(&self, center: &Location, radius_km: f64) -> Vec<(ParaglidingSite, f64)> {
+        let bbox = geo::bbox_from_radius(center.coordinates(), radius_km);
+        let mut results: Vec<(ParaglidingSite, f64)> = self
+            .candidates(&bbox)
+            .into_iter()
+            .filter_map(|(i, launches)| {
+                let min_distance = launches
+                    .into_iter()
+                    .map(|launch| geo::distance_km(center.coordinates(), launch))
+                    .fold(f64::INFINITY, f64::min);
+                (min_distance <= radius_km).then(|| (self.sites[i].clone(), min_distance))
+            })
+            .collect();
+
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results
+    }
+
+    /// Every site with a launch inside `polygon`, given as (latitude,
+    /// longitude) corners.
+    pub fn within_polygon(&self, polygon: &[(f64, f64)]) -> Vec<ParaglidingSite> {
+        let Some(bbox) = bounds(polygon.iter().copied()) else {
+            return vec![];
+        };
+        self.candidates(&bbox)
+            .into_iter()
+            .filter(|(_, launches)| {
+                launches
+                    .iter()
+                    .any(|launch| geo::point_in_polygon(polygon, *launch))
+            })
+            .map(|(i, _)| self.sites[i].clone())
+            .collect()
+    }
+
+    /// Every site with a launch within `corridor_km` of the polyline `route`,
+    /// given as (latitude, longitude) points, in the order they are passed.
+    /// Each segment is looked up by a box around both ends widened by the
+    /// corridor.
+    pub fn along_route(&self, route: &[(f64, f64)], corridor_km: f64) -> Vec<SiteAlongRoute> {
+        let mut candidates = BTreeSet::new();
+        let segments: Vec<_> = match route {
+            [point] => vec![(*point, *point)],
+            _ => route.windows(2).map(|w| (w[0], w[1])).collect(),
+        };
+        for (from, to) in segments {
+            let corners = [
+                geo::bbox_from_radius(from, corridor_km),
+                geo::bbox_from_radius(to, corridor_km),
+            ]
+            .into_iter()
+            .flat_map(|b| {
+                [
+                    (b.min_latitude, b.min_longitude),
+                    (b.max_latitude, b.max_longitude),
+                ]
+            });
+            let Some(bbox) = bounds(corners) else {
+                continue;
+            };
+            candidates.extend(self.candidates(&bbox).into_keys());
+        }
+
+        let mut results: Vec<SiteAlongRoute> = candidates
+            .into_iter()
+            .filter_map(|i| {
+                let (distance_km, along_route_km) = self.sites[i]
+                    .launches
+                    .iter()
+                    .filter_map(|launch| {
+                        geo::distance_to_polyline(route, launch.location.coordinates())
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0))?;
+                (distance_km <= corridor_km).then(|| SiteAlongRoute {
+                    site: self.sites[i].clone(),
+                    distance_km,
+                    along_route_km,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| a.along_route_km.total_cmp(&b.along_route_km));
+        results
+    }
+}
+
+/// Smallest box holding every (latitude, longitude) point.
+fn bounds(mut points: impl Iterator<Item = (f64, f64)>) -> Option<BoundingBox> {
+    let (latitude, longitude) = points.next()?;
+    let start = BoundingBox {
+        min_latitude: latitude,
+        max_latitude: latitude,
+        min_longitude: longitude,
+        max_longitude: longitude,
+    };
+    Some(points.fold(start, |b, (latitude, longitude)| BoundingBox {
+        min_latitude: b.min_latitude.min(latitude),
+        max_latitude: b.max_latitude.max(latitude),
+        min_longitude: b.min_longitude.min(longitude),
+        max_longitude: b.max_longitude.max(longitude),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::paragliding::{ParaglidingLaunch, SiteType, sites_within_radius};
+
+    fn site_at(name: &str, launches: &[(f64, f64)]) -> ParaglidingSite {
+        ParaglidingSite {
+            name: name.into(),
+            launches: launches
+                .iter()
+                .map(|&(lat, lon)| ParaglidingLaunch {
+                    site_type: SiteType::Hang,
+                    location: Location::new(lat, lon, name.into(), "DE".into()),
+                    direction_degrees_start: 0.0,
+                    direction_degrees_stop: 360.0,
+                    elevation: 500.0,
+                })
+                .collect(),
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            ..Default::default()
+        }
+    }
+
+    /// A grid of sites every 0.1° over 47-52°N, 8-15°E.
+    fn grid() -> Vec<ParaglidingSite> {
+        (0..50)
+            .flat_map(|y| (0..70).map(move |x| (47.0 + y as f64 * 0.1, 8.0 + x as f64 * 0.1)))
+            .map(|(lat, lon)| site_at(&format!("{lat:.1}/{lon:.1}"), &[(lat, lon)]))
+            .collect()
+    }
+
+    fn names(sites: &[ParaglidingSite]) -> Vec<&str> {
+        sites.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn within_radius_matches_a_scan_of_every_site() {
+        let sites = grid();
+        let index = SiteIndex::new(sites.clone());
+        let home = Location::new(50.03, 12.97, "Home".into(), "DE".into());
+
+        let scanned = sites_within_radius(&sites, &home, 40.0);
+        let indexed = index.within_radius(&home, 40.0);
+
+        assert!(!indexed.is_empty());
+        assert_eq!(
+            indexed
+                .iter()
+                .map(|(s, d)| (&s.name, *d))
+                .collect::<Vec<_>>(),
+            scanned
+                .iter()
+                .map(|(s, d)| (&s.name, *d))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn within_polygon_checks_launches() {
+        let index = SiteIndex::new(vec![
+            site_at("inside", &[(50.5, 13.5)]),
+            site_at("outside", &[(51.5, 13.5)]),
+            // Only the second launch is in the square
+            site_at("split", &[(49.5, 13.5), (50.2, 13.2)]),
+        ]);
+        let square = [(50.0, 13.0), (50.0, 14.0), (51.0, 14.0), (51.0, 13.0)];
+
+        assert_eq!(
+            names(&index.within_polygon(&square)),
+            vec!["inside", "split"]
+        );
+        assert!(index.within_polygon(&[]).is_empty());
+    }
+
+    #[test]
+    fn along_route_is_ordered_by_progress() {
+        // Route due east along 50°N, then north
+        let route = [(50.0, 10.0), (50.0, 12.0), (51.0, 12.0)];
+        let index = SiteIndex::new(vec![
+            site_at("late", &[(50.5, 12.1)]),
+            site_at("early", &[(50.05, 10.5)]),
+            site_at("far", &[(50.5, 11.0)]),
+        ]);
+
+        let found = index.along_route(&route, 10.0);
+        let names: Vec<&str> = found.iter().map(|s| s.site.name.as_str()).collect();
+        assert_eq!(names, vec!["early", "late"]);
+        assert!((found[0].distance_km - 5.56).abs() < 0.1);
+        assert!((found[0].along_route_km - 35.7).abs() < 0.5);
+        assert!(found[1].along_route_km > 143.0);
+    }
+
+    #[test]
+    fn along_route_finds_every_site_in_the_corridor_of_a_grid() {
+        let index = SiteIndex::new(grid());
+        let route = [(47.35, 8.35), (49.95, 12.05), (51.55, 12.25)];
+
+        let found = index.along_route(&route, 15.0);
+
+        let expected = index
+            .sites()
+            .iter()
+            .filter(|site| {
+                let launch = site.launches[0].location.coordinates();
+                geo::distance_to_polyline(&route, launch).is_some_and(|(d, _)| d <= 15.0)
+            })
+            .count();
+        assert!(expected > 50);
+        assert_eq!(found.len(), expected);
+    }
+}
@@ -1,9 +1,22 @@
+pub mod characteristics;
+pub mod closure;
+pub mod club;
+pub mod crowding;
 pub mod flight;
+pub mod index;
+pub mod remarks;
 
 use serde::{Deserialize, Serialize};
 
-use crate::domain::location::Location;
+use chrono::NaiveDate;
 
+use crate::domain::{
+    geo,
+    location::Location,
+    paragliding::{characteristics::SiteCharacteristics, closure::Closure},
+};
+
+#[allow(async_fn_in_trait)]
 pub trait ParaglidingSiteProvider {
     async fn fetch_all_sites(&self) -> Vec<ParaglidingSite>;
     async fn fetch_launches_within_radius(
@@ -13,7 +26,32 @@ pub trait ParaglidingSiteProvider {
     ) -> Vec<(ParaglidingSite, f64)>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Returns every site with a launch within `radius_km` of `center`, paired with the
+/// distance to its closest launch and sorted closest first.
+pub fn sites_within_radius(
+    sites: &[ParaglidingSite],
+    center: &Location,
+    radius_km: f64,
+) -> Vec<(ParaglidingSite, f64)> {
+    let bbox = geo::bbox_from_radius(center.coordinates(), radius_km);
+    let mut results: Vec<(ParaglidingSite, f64)> = sites
+        .iter()
+        .filter_map(|site| {
+            let min_distance = site
+                .launches
+                .iter()
+                .filter(|launch| bbox.contains(launch.location.coordinates()))
+                .map(|launch| center.distance_to(&launch.location))
+                .fold(f64::INFINITY, f64::min);
+            (min_distance <= radius_km).then(|| (site.clone(), min_distance))
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    results
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ParaglidingSite {
     pub name: String,
     pub launches: Vec<ParaglidingLaunch>,
@@ -24,6 +62,38 @@ pub struct ParaglidingSite {
     pub mute_alerts: Option<bool>,
     pub rating: Option<u8>,
     pub preferred_weather_model: Option<String>,
+    pub winter_operable: Option<bool>,
+    pub favorite: Option<bool>,
+    pub characteristics: Option<SiteCharacteristics>,
+    /// Page of the site at its data source
+    pub source_url: Option<String>,
+    /// Periods the site may not be flown, besides those its rules mention
+    #[serde(default)]
+    pub closures: Vec<Closure>,
+    /// Known to draw crowds on good days, whatever its rating
+    #[serde(default)]
+    pub busy: Option<bool>,
+}
+
+impl ParaglidingSite {
+    /// Whether the site lies in one of `countries`, given as ISO codes in any
+    /// case. An empty list matches every site.
+    pub fn in_countries(&self, countries: &[String]) -> bool {
+        countries.is_empty()
+            || self
+                .country
+                .as_ref()
+                .is_some_and(|country| countries.iter().any(|c| c.eq_ignore_ascii_case(country)))
+    }
+
+    /// The closure in force on `date`, from the site's own or its flight rules.
+    pub fn closure_on(&self, date: NaiveDate) -> Option<Closure> {
+        self.closures
+            .iter()
+            .cloned()
+            .chain(self.characteristics.iter().flat_map(|c| c.closures()))
+            .find(|closure| closure.covers(date))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +105,17 @@ pub struct ParaglidingLaunch {
     pub elevation: f64,
 }
 
+impl ParaglidingLaunch {
+    /// Wind direction the launch faces straight into, the middle of its
+    /// direction sector. None for launches open to every direction.
+    pub fn central_aspect(&self) -> Option<f64> {
+        travelai_core::sector::central_aspect(
+            self.direction_degrees_start,
+            self.direction_degrees_stop,
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParaglidingLanding {
     pub location: Location,
@@ -121,6 +202,72 @@ mod tests {
         assert_eq!(degrees_to_compass(deg), expected);
     }
 
+    fn site_at(name: &str, lat: f64, lon: f64) -> ParaglidingSite {
+        ParaglidingSite {
+            name: name.into(),
+            launches: vec![ParaglidingLaunch {
+                site_type: SiteType::Hang,
+                location: Location::new(lat, lon, name.into(), "DE".into()),
+                direction_degrees_start: 0.0,
+                direction_degrees_stop: 360.0,
+                elevation: 500.0,
+            }],
+            country: Some("DE".into()),
+            data_source: "test".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sites_within_radius_filters_and_sorts_by_distance() {
+        let sites = vec![
+            site_at("mid", 50.75, 13.0),
+            site_at("far", 52.5, 13.4),
+            site_at("near", 50.71, 13.0),
+        ];
+        let home = Location::new(50.7, 13.0, "Home".into(), "DE".into());
+
+        let result = sites_within_radius(&sites, &home, 50.0);
+        let names: Vec<&str> = result.iter().map(|(s, _)| s.name.as_str()).collect();
+        assert_eq!(names, vec!["near", "mid"]);
+    }
+
+    #[rstest]
+    #[case(225.0, 315.0, Some(270.0))]
+    #[case(315.0, 45.0, Some(0.0))]
+    #[case(0.0, 360.0, None)]
+    #[case(90.0, 90.0, None)]
+    fn central_aspect_is_the_middle_of_the_sector(
+        #[case] start: f64,
+        #[case] stop: f64,
+        #[case] expected: Option<f64>,
+    ) {
+        let mut launch = site_at("hill", 50.0, 13.0).launches.remove(0);
+        launch.direction_degrees_start = start;
+        launch.direction_degrees_stop = stop;
+        assert_eq!(launch.central_aspect(), expected);
+    }
+
+    #[test]
+    fn country_filter_ignores_case_and_matches_all_when_empty() {
+        let mut site = site_at("hill", 47.8, 13.0);
+        site.country = Some("at".into());
+
+        assert!(site.in_countries(&[]));
+        assert!(site.in_countries(&["DE".into(), "AT".into()]));
+        assert!(!site.in_countries(&["CH".into()]));
+        site.country = None;
+        assert!(!site.in_countries(&["AT".into()]));
+    }
+
+    #[test]
+    fn sites_within_radius_ignores_sites_without_launches() {
+        let mut site = site_at("empty", 50.7, 13.0);
+        site.launches.clear();
+        let home = Location::new(50.7, 13.0, "Home".into(), "DE".into());
+        assert!(sites_within_radius(&[site], &home, 50.0).is_empty());
+    }
+
     #[test]
     fn degrees_to_compass_normalizes_overflow() {
         assert_eq!(degrees_to_compass(370.0), degrees_to_compass(10.0));
@@ -0,0 +1,280 @@
+//! Seasonal restrictions in the free text site databases publish, mostly
+//! German prose such as "Vom 1. März bis 31. Juli Startverbot wegen
+//! Vogelschutz". A month range counts as a closure if a restriction keyword is
+//! close by. Prose is easy to misread, so every closure found needs review.
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::domain::paragliding::closure::{self, Closure, YEARLY_BASE};
+
+/// Words a restriction keyword may be away from the month range.
+const KEYWORD_DISTANCE: usize = 12;
+
+// Lower case keyword stems, each with the reason of closures it marks. Checked
+// in order, so the specific reasons come before the generic ones.
+const RESTRICTIONS: &[(&str, &[&str])] = &[
+    (
+        "bird protection",
+        &[
+            "vogelschutz",
+            "brut",
+            "horst",
+            "uhu",
+            "falke",
+            "adler",
+            "bird",
+            "raptor",
+            "nesting",
+        ],
+    ),
+    (
+        "wildlife protection",
+        &[
+            "wildschutz",
+            "wildruhe",
+            "wild",
+            "wildlife",
+            "jagd",
+            "hunting",
+        ],
+    ),
+    (
+        "crop protection",
+        &["mahd", "ernte", "getreide", "crop", "harvest", "mowing"],
+    ),
+    (
+        "seasonal restriction",
+        &[
+            "gesperrt",
+            "sperrung",
+            "verbot",
+            "kein flugbetrieb",
+            "nicht beflogen",
+            "closed",
+            "no flying",
+            "prohibited",
+        ],
+    ),
+];
+
+const MONTHS: &[(&str, u32)] = &[
+    ("jan", 1),
+    ("januar", 1),
+    ("january", 1),
+    ("feb", 2),
+    ("februar", 2),
+    ("february", 2),
+    ("mär", 3),
+    ("märz", 3),
+    ("maerz", 3),
+    ("mar", 3),
+    ("march", 3),
+    ("apr", 4),
+    ("april", 4),
+    ("mai", 5),
+    ("may", 5),
+    ("jun", 6),
+    ("juni", 6),
+    ("june", 6),
+    ("jul", 7),
+    ("juli", 7),
+    ("july", 7),
+    ("aug", 8),
+    ("august", 8),
+    ("sep", 9),
+    ("sept", 9),
+    ("september", 9),
+    ("okt", 10),
+    ("oktober", 10),
+    ("oct", 10),
+    ("october", 10),
+    ("nov", 11),
+    ("november", 11),
+    ("dez", 12),
+    ("dezember", 12),
+    ("dec", 12),
+    ("december", 12),
+];
+
+/// Words allowed between the two ends of a range.
+const RANGE_WORDS: &[&str] = &["bis", "-", "–", "to", "until", "till", "und", "and"];
+
+/// One end of a range; a bare month leaves the day open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DayOfYear {
+    month: u32,
+    day: Option<u32>,
+}
+
+/// Closures recurring yearly, found in `text`, all flagged for review.
+pub fn seasonal_closures(text: &str) -> Vec<Closure> {
+    let text = text.to_lowercase();
+    let spaced: String = text
+        .chars()
+        .flat_map(|c| match c {
+            '-' | '–' => vec![' ', c, ' '],
+            ',' | ';' | ':' | '(' | ')' | '!' | '?' => vec![' '],
+            c => vec![c],
+        })
+        .collect();
+    let words: Vec<&str> = spaced.split_whitespace().collect();
+
+    let mut closures = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let Some((from, after_from)) = day_of_year(&words, i) else {
+            i += 1;
+            continue;
+        };
+        let mut j = after_from;
+        while j < words.len() && RANGE_WORDS.contains(&words[j]) {
+            j += 1;
+        }
+        let Some((to, after_to)) = (j > after_from).then(|| day_of_year(&words, j)).flatten()
+        else {
+            i = after_from;
+            continue;
+        };
+        let nearby = words
+            [i.saturating_sub(KEYWORD_DISTANCE)..(after_to + KEYWORD_DISTANCE).min(words.len())]
+            .join(" ");
+        if let Some(closure) = restriction(&nearby).and_then(|reason| to_closure(from, to, reason))
+        {
+            closures.push(closure);
+        }
+        i = after_to;
+    }
+    closures
+}
+
+/// The date starting at word `i`, e.g. "1. märz", "1 apr", "15.02." or
+/// "juli", and the index of the word after it.
+fn day_of_year(words: &[&str], i: usize) -> Option<(DayOfYear, usize)> {
+    let word = words[i];
+    if let Some(month) = month(word) {
+        return Some((DayOfYear { month, day: None }, i + 1));
+    }
+    let numbers: Vec<&str> = word.split('.').filter(|n| !n.is_empty()).collect();
+    match numbers[..] {
+        [day, month] => {
+            let (day, month) = (day.parse().ok()?, month.parse().ok()?);
+            Some((
+                DayOfYear {
+                    month,
+                    day: Some(day),
+                },
+                i + 1,
+            ))
+        }
+        [day] => {
+            let day = day.parse().ok()?;
+            let month = month(words.get(i + 1)?)?;
+            Some((
+                DayOfYear {
+                    month,
+                    day: Some(day),
+                },
+                i + 2,
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn month(word: &str) -> Option<u32> {
+    let word = word.trim_end_matches('.');
+    MONTHS
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|(_, month)| *month)
+}
+
+fn restriction(text: &str) -> Option<&'static str> {
+    RESTRICTIONS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| text.contains(k)))
+        .map(|(reason, _)| *reason)
+}
+
+/// A yearly closure from the first day of `from` to the last of `to`.
+fn to_closure(from: DayOfYear, to: DayOfYear, reason: &str) -> Option<Closure> {
+    let to_day = match to.day {
+        Some(day) => day,
+        None => last_day_of_month(to.month)?,
+    };
+    let mut closure = closure::yearly(
+        (from.month, from.day.unwrap_or(1)),
+        (to.month, to_day),
+        reason,
+    )?;
+    closure.needs_review = true;
+    Some(closure)
+}
+
+fn last_day_of_month(month: u32) -> Option<u32> {
+    let first = NaiveDate::from_ymd_opt(YEARLY_BASE, month, 1)?;
+    let last = first.checked_add_months(Months::new(1))? - Days::new(1);
+    Some(last.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2000, month, day).unwrap()
+    }
+
+    #[rstest]
+    #[case(
+        "Vom 1. März bis 31. Juli Startverbot wegen Vogelschutz (Wanderfalke).",
+        (date(3, 1), date(7, 31)),
+        "bird protection"
+    )]
+    #[case(
+        "Brutzeit 15.02.-31.07. kein Flugbetrieb am Nordstart",
+        (date(2, 15), date(7, 31)),
+        "bird protection"
+    )]
+    #[case(
+        "Von Dezember bis April gesperrt, Wildruhezone.",
+        (date(12, 1), date(4, 30)),
+        "wildlife protection"
+    )]
+    #[case(
+        "Landeplatz zwischen Mai und Juni wegen Mahd nicht nutzbar",
+        (date(5, 1), date(6, 30)),
+        "crop protection"
+    )]
+    #[case(
+        "Closed 1 Apr – 15 Jul for raptor nesting",
+        (date(4, 1), date(7, 15)),
+        "bird protection"
+    )]
+    #[case(
+        "Von Februar bis März gesperrt",
+        (date(2, 1), date(3, 31)),
+        "seasonal restriction"
+    )]
+    fn restrictions_near_month_ranges_become_closures(
+        #[case] text: &str,
+        #[case] (from, to): (NaiveDate, NaiveDate),
+        #[case] reason: &str,
+    ) {
+        let closures = seasonal_closures(text);
+        assert_eq!(closures.len(), 1, "{closures:?}");
+        let closure = &closures[0];
+        assert_eq!((closure.from, closure.to), (from, to));
+        assert_eq!(closure.reason, reason);
+        assert!(closure.yearly && closure.needs_review);
+    }
+
+    #[rstest]
+    #[case("Beste Zeit: April bis Oktober, thermisch sehr gut.")]
+    #[case("Startverbot für Drachen")]
+    #[case("Mais und Weizen am Landeplatz beachten")]
+    fn ranges_or_keywords_alone_are_not_closures(#[case] text: &str) {
+        assert_eq!(seasonal_closures(text), vec![]);
+    }
+}
@@ -3,16 +3,18 @@ use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 
 use crate::domain::{
-    activities::{ActivitySuggestion, PlanningContext},
+    activities::{PlanningContext, Suggestions},
     calendar::CalendarEvent,
-    location::Location,
-    weather::{WeatherForecast, WeatherModel},
+    location::{GeocodeCandidate, Location},
+    paragliding::ParaglidingSite,
+    sounding::Sounding,
+    weather::{WeatherData, WeatherForecast, WeatherModel},
 };
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait ActivitySource: Send + Sync {
-    async fn suggest(&self, ctx: &PlanningContext) -> Result<Vec<ActivitySuggestion>>;
+    async fn suggest(&self, ctx: &PlanningContext) -> Result<Suggestions>;
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -27,6 +29,57 @@ pub trait WeatherProvider: Send + Sync {
     fn available_models(&self) -> Vec<WeatherModel>;
 }
 
+/// A source of flying sites that can fail, e.g. a remote site database.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SiteSearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Sites with a launch within `radius_km` of `center`, with the distance
+    /// to their closest launch.
+    async fn search_sites(
+        &self,
+        center: &Location,
+        radius_km: f64,
+    ) -> Result<Vec<(ParaglidingSite, f64)>>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WeatherHistoryProvider: Send + Sync {
+    /// Hourly weather of the last `days` days up to now, oldest first.
+    async fn get_past_weather(&self, location: &Location, days: u8) -> Result<WeatherForecast>;
+    /// The last `hours` hours up to now, oldest first, fetched fresh for
+    /// decisions that can't wait for a cached copy to expire.
+    async fn get_recent_weather(&self, location: &Location, hours: u8) -> Result<Vec<WeatherData>>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SoundingProvider: Send + Sync {
+    /// Hourly vertical profiles for the next days, oldest first.
+    async fn get_soundings(&self, location: &Location) -> Result<Vec<Sounding>>;
+}
+
+/// A chat the outlook is posted to, e.g. a Discord channel or Matrix room.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Posts `text`. `message_id` is the same on every attempt at one
+    /// message, for chats that drop a resend by it.
+    async fn notify(&self, text: &str, message_id: &str) -> Result<()>;
+}
+
+/// Runs of one model from slightly different starting points. Where they
+/// disagree, the weather is uncertain.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EnsembleProvider: Send + Sync {
+    /// One hourly forecast per ensemble member for the next days.
+    async fn get_ensemble(&self, location: &Location) -> Result<Vec<WeatherForecast>>;
+}
+
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait RoutingProvider: Send + Sync {
@@ -39,23 +92,65 @@ pub trait RoutingProvider: Send + Sync {
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
-pub trait CalendarProvider {
+pub trait CalendarProvider: Send + Sync {
+    /// Periods `calendars` are busy in that overlap `start` to `end`, so many
+    /// slots can be checked against one query.
+    async fn busy_periods(
+        &self,
+        calendars: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>>;
     async fn is_busy(
         &self,
-        calendars: &Vec<String>,
+        calendars: &[String],
         start: DateTime<Utc>,
         end: DateTime<Utc>,
-    ) -> Result<bool>;
+    ) -> Result<bool> {
+        let busy = self.busy_periods(calendars, start, end).await?;
+        Ok(busy.iter().any(|(from, to)| start < *to && end > *from))
+    }
     async fn get_calendar_names(&self) -> Result<Vec<String>>;
     async fn clear_calendar(&mut self, name: &str) -> Result<()>;
     async fn create_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()>;
+    /// Creates `events` in `calendar`, one at a time unless the backend can
+    /// do better. Stops at the first failure.
+    async fn create_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        for event in events {
+            self.create_event(calendar, event).await?;
+        }
+        Ok(())
+    }
     async fn create_calendar(&mut self, name: &str) -> Result<()>;
+    /// Keys of the events in `calendar`, `None` for events written without one.
+    async fn event_keys(&self, calendar: &str) -> Result<Vec<Option<String>>>;
+    /// Replaces the event in `calendar` with the key of `event`, or creates it.
+    async fn upsert_event(&mut self, calendar: &str, event: CalendarEvent) -> Result<()>;
+    /// Upserts `events` one at a time unless the backend can do better.
+    async fn upsert_events(&mut self, calendar: &str, events: Vec<CalendarEvent>) -> Result<()> {
+        for event in events {
+            self.upsert_event(calendar, event).await?;
+        }
+        Ok(())
+    }
+    /// Deletes the events with `keys` from `calendar`, ignoring unknown keys.
+    async fn delete_events(&mut self, calendar: &str, keys: &[String]) -> Result<()>;
 }
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait GeoProvider: Send + Sync {
-    async fn geocode(&self, location_name: &str) -> Result<Vec<Location>>;
+    /// All matches for `location_name`, best match first.
+    async fn geocode(&self, location_name: &str) -> Result<Vec<GeocodeCandidate>>;
 
     async fn fetch_elevation(&self, latitude: f64, longitude: f64) -> Result<f64>;
+
+    /// Elevations of (latitude, longitude) `points`, in the same order.
+    async fn fetch_elevations(&self, points: &[(f64, f64)]) -> Result<Vec<f64>>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ReverseGeoProvider: Send + Sync {
+    async fn reverse_geocode(&self, latitude: f64, longitude: f64) -> Result<Location>;
 }
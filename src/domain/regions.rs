@@ -0,0 +1,187 @@
+//! Well-known flying regions, so places like "Bassano" or "Alpes du Sud" can be
+//! used where a location is expected and their sites looked up together.
+
+use serde::Serialize;
+
+use crate::domain::{
+    geo,
+    location::{Location, normalize_place_name},
+    paragliding::{ParaglidingSite, index::SiteIndex},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Region {
+    pub name: &'static str,
+    /// ISO 3166-1 alpha-2 code
+    pub country: &'static str,
+    /// Representative point, e.g. the main take-off, as (latitude, longitude)
+    pub center: (f64, f64),
+    /// Outline as (latitude, longitude) corners
+    pub polygon: &'static [(f64, f64)],
+}
+
+pub const REGIONS: &[Region] = &[
+    Region {
+        name: "Bassano",
+        country: "IT",
+        center: (45.80, 11.73),
+        polygon: &[
+            (45.70, 11.55),
+            (45.72, 11.95),
+            (45.92, 11.95),
+            (45.92, 11.55),
+        ],
+    },
+    Region {
+        name: "Ölüdeniz",
+        country: "TR",
+        center: (36.55, 29.15),
+        polygon: &[
+            (36.45, 29.00),
+            (36.45, 29.28),
+            (36.65, 29.28),
+            (36.65, 29.00),
+        ],
+    },
+    Region {
+        name: "Alpes du Sud",
+        country: "FR",
+        center: (44.40, 6.30),
+        polygon: &[(43.80, 5.60), (43.90, 7.10), (44.90, 7.00), (44.95, 5.70)],
+    },
+    Region {
+        name: "Annecy",
+        country: "FR",
+        center: (45.81, 6.25),
+        polygon: &[(45.70, 6.00), (45.70, 6.40), (46.00, 6.40), (46.00, 6.00)],
+    },
+    Region {
+        name: "Chamonix",
+        country: "FR",
+        center: (45.92, 6.87),
+        polygon: &[(45.82, 6.70), (45.82, 7.05), (46.02, 7.05), (46.02, 6.70)],
+    },
+    Region {
+        name: "Berner Oberland",
+        country: "CH",
+        center: (46.68, 7.86),
+        polygon: &[(46.50, 7.40), (46.50, 8.20), (46.80, 8.20), (46.80, 7.40)],
+    },
+    Region {
+        name: "Fiesch",
+        country: "CH",
+        center: (46.41, 8.14),
+        polygon: &[(46.33, 7.95), (46.33, 8.30), (46.50, 8.30), (46.50, 7.95)],
+    },
+    Region {
+        name: "Zillertal",
+        country: "AT",
+        center: (47.23, 11.88),
+        polygon: &[
+            (47.05, 11.70),
+            (47.05, 12.05),
+            (47.40, 12.05),
+            (47.40, 11.70),
+        ],
+    },
+    Region {
+        name: "Drautal",
+        country: "AT",
+        center: (46.75, 13.18),
+        polygon: &[
+            (46.65, 12.80),
+            (46.65, 13.55),
+            (46.85, 13.55),
+            (46.85, 12.80),
+        ],
+    },
+    Region {
+        name: "Allgäu",
+        country: "DE",
+        center: (47.55, 10.30),
+        polygon: &[(47.30, 9.80), (47.30, 10.90), (47.80, 10.90), (47.80, 9.80)],
+    },
+    Region {
+        name: "Algodonales",
+        country: "ES",
+        center: (36.88, -5.40),
+        polygon: &[
+            (36.78, -5.55),
+            (36.78, -5.25),
+            (36.98, -5.25),
+            (36.98, -5.55),
+        ],
+    },
+    Region {
+        name: "Bir Billing",
+        country: "IN",
+        center: (32.05, 76.72),
+        polygon: &[
+            (31.95, 76.55),
+            (31.95, 76.90),
+            (32.20, 76.90),
+            (32.20, 76.55),
+        ],
+    },
+];
+
+/// The region called `name`, ignoring case and diacritics, so "oludeniz" finds
+/// "Ölüdeniz".
+pub fn find_region(name: &str) -> Option<&'static Region> {
+    let name = normalize_place_name(name);
+    REGIONS
+        .iter()
+        .find(|region| normalize_place_name(region.name) == name)
+}
+
+impl Region {
+    pub fn center_location(&self) -> Location {
+        Location::new(
+            self.center.0,
+            self.center.1,
+            self.name.to_string(),
+            self.country.to_string(),
+        )
+    }
+
+    /// Whether the point lies inside the region's outline.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        geo::point_in_polygon(self.polygon, (latitude, longitude))
+    }
+
+    /// Sites with at least one launch inside the region.
+    pub fn sites(&self, sites: &SiteIndex) -> Vec<ParaglidingSite> {
+        sites.within_polygon(self.polygon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_are_found_without_diacritics() {
+        assert_eq!(find_region("oludeniz").unwrap().country, "TR");
+        assert_eq!(find_region("  alpes du SUD").unwrap().name, "Alpes du Sud");
+        assert!(find_region("Atlantis").is_none());
+    }
+
+    #[test]
+    fn every_center_lies_inside_its_region() {
+        for region in REGIONS {
+            assert!(
+                region.contains(region.center.0, region.center.1),
+                "{}",
+                region.name
+            );
+        }
+    }
+
+    #[test]
+    fn points_outside_the_outline_are_excluded() {
+        let bassano = find_region("Bassano").unwrap();
+        assert!(bassano.contains(45.80, 11.73));
+        assert!(!bassano.contains(45.60, 11.73));
+        assert!(!bassano.contains(45.80, 12.10));
+    }
+}
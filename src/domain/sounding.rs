@@ -0,0 +1,426 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::weather::{
+    self, DRY_ADIABATIC_LAPSE_RATE_K_PER_M, moist_adiabatic_lapse_rate_k_per_m,
+};
+
+/// Model pressure levels requested for a sounding, surface to mid troposphere.
+pub const PRESSURE_LEVELS_HPA: [u16; 7] = [1000, 925, 850, 800, 700, 600, 500];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundingLevel {
+    pub pressure_hpa: f32,
+    /// Geopotential height in meters above sea level
+    pub height_m: f64,
+    /// Temperature in Celsius
+    pub temperature: f32,
+    /// Dew point in Celsius
+    pub dew_point: f32,
+    pub wind_speed_ms: f32,
+    pub wind_direction: u16,
+}
+
+/// A vertical profile of the atmosphere for one hour, as used for a Skew-T plot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sounding {
+    pub timestamp: DateTime<Utc>,
+    /// Ground elevation of the model grid cell in meters
+    pub surface_elevation_m: f64,
+    /// 2 m temperature in Celsius
+    pub surface_temperature: f32,
+    /// 2 m dew point in Celsius
+    pub surface_dew_point: f32,
+    /// 10 m wind
+    pub surface_wind_speed_ms: f32,
+    pub surface_wind_direction: u16,
+    /// Levels above the surface, lowest first
+    pub levels: Vec<SoundingLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inversion {
+    pub base_m: f64,
+    pub top_m: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundingAnalysis {
+    /// Cumulus base (lifting condensation level) in meters above sea level
+    pub cloud_base_m: f64,
+    /// Height where a dry thermal from the surface stops rising, in meters above sea level
+    pub thermal_top_m: Option<f64>,
+    /// Whether thermals reach the condensation level and mark themselves with cumulus
+    pub cumulus: bool,
+    /// Average lapse rate from the surface to the highest level, in K/km
+    pub lapse_rate_k_per_km: Option<f32>,
+    pub inversions: Vec<Inversion>,
+    /// Temperature at 500 hPa minus that of a surface parcel lifted to it, in
+    /// Kelvin. Below zero the air is unstable, below -3 thunderstorms are
+    /// likely. None without a 500 hPa level.
+    pub lifted_index: Option<f32>,
+}
+
+const LIFTED_INDEX_PRESSURE_HPA: f32 = 500.0;
+// Step of the moist ascent above cloud base
+const MOIST_ASCENT_STEP_M: f64 = 100.0;
+
+/// Wind interpolated to a height that need not be a model level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindAtHeight {
+    /// Meters above sea level
+    pub height_m: f64,
+    pub speed_ms: f32,
+    /// Meteorological "from" direction in degrees
+    pub direction: u16,
+}
+
+// Wind as (east, north) components of the direction the air is moving to.
+fn wind_components(speed_ms: f32, direction: u16) -> (f64, f64) {
+    let radians = f64::from(direction).to_radians();
+    let speed = f64::from(speed_ms);
+    (-speed * radians.sin(), -speed * radians.cos())
+}
+
+impl Sounding {
+    /// Wind at `height_m` above sea level, interpolated linearly between the
+    /// neighbouring levels by components so that veering winds average correctly.
+    /// `None` below the surface wind or above the highest level.
+    pub fn wind_at(&self, height_m: f64) -> Option<WindAtHeight> {
+        let surface = (
+            self.surface_elevation_m + 10.0,
+            self.surface_wind_speed_ms,
+            self.surface_wind_direction,
+        );
+        let profile: Vec<(f64, f32, u16)> = std::iter::once(surface)
+            .chain(
+                self.levels
+                    .iter()
+                    .map(|l| (l.height_m, l.wind_speed_ms, l.wind_direction)),
+            )
+            .collect();
+
+        let (below, above) = profile
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|(below, above)| height_m >= below.0 && height_m <= above.0)?;
+
+        let fraction = if above.0 > below.0 {
+            (height_m - below.0) / (above.0 - below.0)
+        } else {
+            0.0
+        };
+        let (bu, bv) = wind_components(below.1, below.2);
+        let (au, av) = wind_components(above.1, above.2);
+        let u = bu + fraction * (au - bu);
+        let v = bv + fraction * (av - bv);
+
+        Some(WindAtHeight {
+            height_m,
+            speed_ms: u.hypot(v) as f32,
+            direction: ((-u).atan2(-v).to_degrees().rem_euclid(360.0).round() as u16) % 360,
+        })
+    }
+
+    /// Winds every `step_m` from `from_m` to `to_m`, skipping heights the profile
+    /// does not cover.
+    pub fn wind_profile(&self, from_m: f64, to_m: f64, step_m: f64) -> Vec<WindAtHeight> {
+        if step_m <= 0.0 || to_m < from_m {
+            return vec![];
+        }
+        let steps = ((to_m - from_m) / step_m).floor() as usize;
+        (0..=steps)
+            .filter_map(|i| self.wind_at(from_m + i as f64 * step_m))
+            .collect()
+    }
+
+    pub fn analyze(&self) -> SoundingAnalysis {
+        let cloud_base_m = self.surface_elevation_m
+            + weather::cloud_base_agl_m(self.surface_temperature, self.surface_dew_point);
+        let thermal_top_m = self.thermal_top_m();
+
+        SoundingAnalysis {
+            cloud_base_m,
+            thermal_top_m,
+            cumulus: thermal_top_m.is_none_or(|top| top > cloud_base_m),
+            lapse_rate_k_per_km: self.lapse_rate_k_per_km(),
+            inversions: self.inversions(),
+            lifted_index: self.lifted_index(cloud_base_m),
+        }
+    }
+
+    /// Lifts a surface parcel dry-adiabatically to `cloud_base_m` and
+    /// moist-adiabatically from there to 500 hPa.
+    fn lifted_index(&self, cloud_base_m: f64) -> Option<f32> {
+        let top = self
+            .levels
+            .iter()
+            .find(|l| l.pressure_hpa == LIFTED_INDEX_PRESSURE_HPA)?;
+        if top.height_m <= cloud_base_m {
+            return Some(top.temperature - self.parcel_temperature(top.height_m));
+        }
+        let mut height = cloud_base_m;
+        let mut parcel = f64::from(self.parcel_temperature(cloud_base_m));
+        while height < top.height_m {
+            let step = MOIST_ASCENT_STEP_M.min(top.height_m - height);
+            let pressure = self.pressure_at(height)?;
+            parcel -= moist_adiabatic_lapse_rate_k_per_m(parcel, pressure) * step;
+            height += step;
+        }
+        Some(top.temperature - parcel as f32)
+    }
+
+    /// Pressure at `height_m`, interpolated logarithmically between the
+    /// levels and extrapolated below the lowest two.
+    fn pressure_at(&self, height_m: f64) -> Option<f64> {
+        let levels: Vec<&SoundingLevel> = self
+            .levels
+            .iter()
+            .filter(|l| l.pressure_hpa > 0.0)
+            .collect();
+        let pair = levels
+            .windows(2)
+            .find(|pair| height_m <= pair[1].height_m)
+            .or_else(|| levels.windows(2).last())?;
+        let (below, above) = (pair[0], pair[1]);
+        if above.height_m <= below.height_m {
+            return None;
+        }
+        let fraction = (height_m - below.height_m) / (above.height_m - below.height_m);
+        let (ln_below, ln_above) = (
+            f64::from(below.pressure_hpa).ln(),
+            f64::from(above.pressure_hpa).ln(),
+        );
+        Some((ln_below + fraction * (ln_above - ln_below)).exp())
+    }
+
+    fn parcel_temperature(&self, height_m: f64) -> f32 {
+        weather::temperature_at_altitude(
+            self.surface_temperature,
+            self.surface_elevation_m,
+            height_m,
+            DRY_ADIABATIC_LAPSE_RATE_K_PER_M,
+        )
+    }
+
+    /// First height where a parcel lifted dry-adiabatically from the surface is no
+    /// warmer than its surroundings, or `None` if it stays buoyant through the profile.
+    fn thermal_top_m(&self) -> Option<f64> {
+        let mut below = (self.surface_elevation_m, 0.0_f64);
+        for level in &self.levels {
+            let excess = f64::from(self.parcel_temperature(level.height_m) - level.temperature);
+            if excess <= 0.0 {
+                let (below_height, below_excess) = below;
+                let fraction = if below_excess > excess {
+                    below_excess / (below_excess - excess)
+                } else {
+                    0.0
+                };
+                return Some(below_height + fraction * (level.height_m - below_height));
+            }
+            below = (level.height_m, excess);
+        }
+        None
+    }
+
+    fn lapse_rate_k_per_km(&self) -> Option<f32> {
+        let top = self.levels.last()?;
+        let depth_km = (top.height_m - self.surface_elevation_m) / 1000.0;
+        (depth_km > 0.0)
+            .then(|| ((self.surface_temperature - top.temperature) as f64 / depth_km) as f32)
+    }
+
+    fn inversions(&self) -> Vec<Inversion> {
+        let mut inversions: Vec<Inversion> = Vec::new();
+        for pair in self.levels.windows(2) {
+            if pair[1].temperature <= pair[0].temperature {
+                continue;
+            }
+            match inversions.last_mut() {
+                // Consecutive warming layers are one inversion.
+                Some(last) if last.top_m == pair[0].height_m => last.top_m = pair[1].height_m,
+                _ => inversions.push(Inversion {
+                    base_m: pair[0].height_m,
+                    top_m: pair[1].height_m,
+                }),
+            }
+        }
+        inversions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn level(height_m: f64, temperature: f32) -> SoundingLevel {
+        SoundingLevel {
+            pressure_hpa: 0.0,
+            height_m,
+            temperature,
+            dew_point: temperature - 10.0,
+            wind_speed_ms: 5.0,
+            wind_direction: 270,
+        }
+    }
+
+    fn sounding(levels: Vec<SoundingLevel>) -> Sounding {
+        Sounding {
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 13, 12, 0, 0).unwrap(),
+            surface_elevation_m: 500.0,
+            surface_temperature: 20.0,
+            surface_dew_point: 10.0,
+            surface_wind_speed_ms: 2.0,
+            surface_wind_direction: 270,
+            levels,
+        }
+    }
+
+    #[test]
+    fn cloud_base_is_lcl_above_surface() {
+        let analysis = sounding(vec![level(1500.0, 12.0)]).analyze();
+        assert_eq!(analysis.cloud_base_m, 1750.0);
+    }
+
+    #[test]
+    fn thermal_top_interpolates_where_parcel_meets_environment() {
+        // Parcel: 10.2 °C at 1500 m (still 0.2 K warmer), 0.4 °C at 2500 m (4.6 K colder).
+        let s = sounding(vec![level(1500.0, 10.0), level(2500.0, 5.0)]);
+        let top = s.analyze().thermal_top_m.unwrap();
+        let expected = 1500.0 + 0.2 / 4.8 * 1000.0;
+        assert!((top - expected).abs() < 1.0, "got {top}");
+    }
+
+    #[test]
+    fn stable_low_top_means_blue_thermals() {
+        let analysis = sounding(vec![level(1000.0, 17.0)]).analyze();
+        assert!(analysis.thermal_top_m.unwrap() < analysis.cloud_base_m);
+        assert!(!analysis.cumulus);
+    }
+
+    #[test]
+    fn consecutive_warming_layers_form_one_inversion() {
+        let s = sounding(vec![
+            level(1000.0, 15.0),
+            level(1500.0, 16.0),
+            level(2000.0, 17.0),
+            level(3000.0, 10.0),
+            level(4000.0, 11.0),
+        ]);
+        let inversions = s.analyze().inversions;
+        assert_eq!(inversions.len(), 2);
+        assert_eq!(
+            (inversions[0].base_m, inversions[0].top_m),
+            (1000.0, 2000.0)
+        );
+        assert_eq!(
+            (inversions[1].base_m, inversions[1].top_m),
+            (3000.0, 4000.0)
+        );
+    }
+
+    fn pressure_level(pressure_hpa: f32, height_m: f64, temperature: f32) -> SoundingLevel {
+        SoundingLevel {
+            pressure_hpa,
+            ..level(height_m, temperature)
+        }
+    }
+
+    fn lifted_index(temperature_500hpa: f32) -> Option<f32> {
+        sounding(vec![
+            pressure_level(925.0, 800.0, 17.0),
+            pressure_level(850.0, 1500.0, 12.0),
+            pressure_level(700.0, 3100.0, 2.0),
+            pressure_level(500.0, 5700.0, temperature_500hpa),
+        ])
+        .analyze()
+        .lifted_index
+    }
+
+    #[test]
+    fn lifted_index_compares_the_parcel_with_the_500_hpa_level() {
+        let warm = lifted_index(-8.0).unwrap();
+        let cold = lifted_index(-24.0).unwrap();
+        assert!((warm - cold - 16.0).abs() < 1e-3);
+        // A 20 °C parcel with its base at 1750 m is around -17 °C at 5700 m
+        assert!(warm > 0.0, "got {warm}");
+        assert!(cold < -3.0, "got {cold}");
+    }
+
+    #[test]
+    fn lifted_index_needs_a_500_hpa_level() {
+        assert_eq!(
+            sounding(vec![pressure_level(850.0, 1500.0, 12.0)])
+                .analyze()
+                .lifted_index,
+            None
+        );
+    }
+
+    #[test]
+    fn lapse_rate_spans_surface_to_top_level() {
+        let s = sounding(vec![level(1500.0, 13.0), level(2500.0, 6.0)]);
+        let rate = s.analyze().lapse_rate_k_per_km.unwrap();
+        assert!((rate - 7.0).abs() < 1e-4, "got {rate}");
+    }
+
+    fn wind_level(height_m: f64, wind_speed_ms: f32, wind_direction: u16) -> SoundingLevel {
+        SoundingLevel {
+            wind_speed_ms,
+            wind_direction,
+            ..level(height_m, 0.0)
+        }
+    }
+
+    #[test]
+    fn wind_at_level_height_is_the_level_wind() {
+        let s = sounding(vec![wind_level(1500.0, 8.0, 250)]);
+        let wind = s.wind_at(1500.0).unwrap();
+        assert!((wind.speed_ms - 8.0).abs() < 1e-4);
+        assert_eq!(wind.direction, 250);
+    }
+
+    #[test]
+    fn wind_between_levels_interpolates_speed() {
+        let s = sounding(vec![
+            wind_level(1000.0, 4.0, 270),
+            wind_level(2000.0, 10.0, 270),
+        ]);
+        let wind = s.wind_at(1500.0).unwrap();
+        assert!((wind.speed_ms - 7.0).abs() < 1e-4, "got {}", wind.speed_ms);
+        assert_eq!(wind.direction, 270);
+    }
+
+    #[test]
+    fn veering_wind_interpolates_across_north() {
+        let s = sounding(vec![
+            wind_level(1000.0, 5.0, 350),
+            wind_level(2000.0, 5.0, 10),
+        ]);
+        assert_eq!(s.wind_at(1500.0).unwrap().direction, 0);
+    }
+
+    #[test]
+    fn wind_below_surface_wind_reference_is_none() {
+        let s = sounding(vec![wind_level(1500.0, 8.0, 250)]);
+        // Surface wind is at 510 m, top level at 1500 m.
+        assert!(s.wind_at(500.0).is_none());
+        assert!(s.wind_at(510.0).is_some());
+        assert!(s.wind_at(1600.0).is_none());
+    }
+
+    #[test]
+    fn wind_profile_covers_band_in_steps() {
+        let s = sounding(vec![
+            wind_level(1500.0, 8.0, 250),
+            wind_level(3000.0, 12.0, 260),
+        ]);
+        let heights: Vec<f64> = s
+            .wind_profile(800.0, 2500.0, 500.0)
+            .iter()
+            .map(|w| w.height_m)
+            .collect();
+        assert_eq!(heights, vec![800.0, 1300.0, 1800.0, 2300.0]);
+    }
+}
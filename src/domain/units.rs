@@ -0,0 +1,145 @@
+use std::{fmt::Display, ops::Add};
+
+use serde::{Deserialize, Serialize};
+
+pub const KMH_PER_MS: f32 = 3.6;
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// Wind speed, stored in m/s.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+pub struct WindSpeed(f32);
+
+impl WindSpeed {
+    pub const fn from_ms(ms: f32) -> Self {
+        WindSpeed(ms)
+    }
+
+    pub const fn from_kmh(kmh: f32) -> Self {
+        WindSpeed(kmh / KMH_PER_MS)
+    }
+
+    pub fn ms(self) -> f32 {
+        self.0
+    }
+
+    pub fn kmh(self) -> f32 {
+        self.0 * KMH_PER_MS
+    }
+}
+
+impl Display for WindSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0} km/h", self.kmh())
+    }
+}
+
+/// Air temperature, stored in °C.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
+pub struct Temperature(f32);
+
+impl Temperature {
+    pub const fn from_celsius(celsius: f32) -> Self {
+        Temperature(celsius)
+    }
+
+    pub const fn from_kelvin(kelvin: f32) -> Self {
+        Temperature(kelvin - KELVIN_OFFSET)
+    }
+
+    pub fn celsius(self) -> f32 {
+        self.0
+    }
+}
+
+impl Display for Temperature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0}°C", self.0)
+    }
+}
+
+/// Distance, stored in meters.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Distance(f64);
+
+impl Distance {
+    pub const fn from_km(km: f64) -> Self {
+        Distance(km * 1000.0)
+    }
+
+    pub const fn from_meters(m: f64) -> Self {
+        Distance(m)
+    }
+
+    pub fn meters(self) -> f64 {
+        self.0
+    }
+
+    pub fn km(self) -> f64 {
+        self.0 / 1000.0
+    }
+}
+
+impl Add for Distance {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Distance(self.0 + other.0)
+    }
+}
+
+impl Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 > 1000.0 {
+            write!(f, "{:.2}km", self.km())
+        } else {
+            write!(f, "{:.0}m", self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wind_speed_kmh_round_trips() {
+        let speed = WindSpeed::from_kmh(36.0);
+        assert!((speed.ms() - 10.0).abs() < 1e-5);
+        assert!((speed.kmh() - 36.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wind_speeds_compare_regardless_of_source_unit() {
+        assert!(WindSpeed::from_ms(7.0) > WindSpeed::from_kmh(25.0));
+        assert!(WindSpeed::from_ms(6.9) < WindSpeed::from_kmh(25.0));
+    }
+
+    #[test]
+    fn wind_speed_displays_in_kmh() {
+        assert_eq!(WindSpeed::from_ms(5.0).to_string(), "18 km/h");
+    }
+
+    #[test]
+    fn temperature_from_kelvin() {
+        assert!((Temperature::from_kelvin(273.15).celsius()).abs() < 1e-4);
+        assert_eq!(Temperature::from_celsius(-3.4).to_string(), "-3°C");
+    }
+
+    #[test]
+    fn distance_from_km_and_from_meters_are_equivalent() {
+        let a = Distance::from_km(1.0);
+        let b = Distance::from_meters(1000.0);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn distance_display_switches_units_at_one_kilometer() {
+        assert_eq!(format!("{}", Distance::from_meters(800.0)), "800m");
+        assert_eq!(format!("{}", Distance::from_km(1.5)), "1.50km");
+    }
+
+    #[test]
+    fn distance_addition_sums_meters() {
+        let s = Distance::from_meters(100.0) + Distance::from_meters(250.0);
+        assert_eq!(s.meters(), 350.0);
+    }
+}
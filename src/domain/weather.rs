@@ -1,9 +1,12 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sunrise::{Coordinates, SolarDay, SolarEvent};
 
-use crate::domain::location::Location;
+use crate::domain::{
+    location::Location,
+    units::{Temperature, WindSpeed},
+};
 
 pub fn get_sunrise_sunset(
     location: &Location,
@@ -32,18 +35,146 @@ pub fn get_sunrise_sunset(
     Ok((sunrise, sunset))
 }
 
+// Standard atmosphere lapse rate, used to shift model temperatures to launch height.
+pub const ENVIRONMENTAL_LAPSE_RATE_K_PER_M: f64 = 0.0065;
+// A rising thermal cools at the dry adiabatic rate until it condenses.
+pub const DRY_ADIABATIC_LAPSE_RATE_K_PER_M: f64 = 0.0098;
+const GRAVITY_M_PER_S2: f64 = 9.81;
+const SPECIFIC_HEAT_J_PER_KG_K: f64 = 1005.0;
+const LATENT_HEAT_J_PER_KG: f64 = 2.501e6;
+const DRY_AIR_GAS_CONSTANT_J_PER_KG_K: f64 = 287.05;
+// Ratio of the molar masses of water vapour and dry air
+const MOLAR_MASS_RATIO: f64 = 0.622;
+
+/// Rate at which saturated air cools as it rises, in K/m. Condensing water
+/// warms it, so it is well below the dry rate in warm air and approaches it
+/// in cold air.
+pub fn moist_adiabatic_lapse_rate_k_per_m(temperature: f64, pressure_hpa: f64) -> f64 {
+    let vapour_pressure = 6.112 * (17.67 * temperature / (temperature + 243.5)).exp();
+    let mixing_ratio = MOLAR_MASS_RATIO * vapour_pressure / (pressure_hpa - vapour_pressure);
+    let kelvin = temperature + 273.15;
+    GRAVITY_M_PER_S2
+        * (1.0 + LATENT_HEAT_J_PER_KG * mixing_ratio / (DRY_AIR_GAS_CONSTANT_J_PER_KG_K * kelvin))
+        / (SPECIFIC_HEAT_J_PER_KG_K
+            + LATENT_HEAT_J_PER_KG.powi(2) * mixing_ratio * MOLAR_MASS_RATIO
+                / (DRY_AIR_GAS_CONSTANT_J_PER_KG_K * kelvin.powi(2)))
+}
+
+// Espy's approximation: cloud base rises ~125 m per degree of dew point spread.
+const CLOUD_BASE_M_PER_K_SPREAD: f64 = 125.0;
+
+/// Cloud base above ground for a convective day, in meters.
+pub fn cloud_base_agl_m(temperature: f32, dew_point: f32) -> f64 {
+    (f64::from(temperature) - f64::from(dew_point)).max(0.0) * CLOUD_BASE_M_PER_K_SPREAD
+}
+
+/// Shifts a temperature measured at `from_m` to `to_m` using `lapse_rate`.
+pub fn temperature_at_altitude(temperature: f32, from_m: f64, to_m: f64, lapse_rate: f64) -> f32 {
+    (f64::from(temperature) - lapse_rate * (to_m - from_m)) as f32
+}
+
+/// Magnitude of the vector difference between two winds in m/s. Directions are the
+/// meteorological "from" direction in degrees.
+pub fn wind_vector_difference_ms(
+    speed_a: f32,
+    direction_a: u16,
+    speed_b: f32,
+    direction_b: u16,
+) -> f32 {
+    let components = |speed: f32, direction: u16| {
+        let radians = f32::from(direction).to_radians();
+        (speed * radians.sin(), speed * radians.cos())
+    };
+    let (ax, ay) = components(speed_a, direction_a);
+    let (bx, by) = components(speed_b, direction_b);
+    (ax - bx).hypot(ay - by)
+}
+
+// Pressure tendencies are judged on sea level pressure.
+const SEA_LEVEL_PRESSURE_HPA: f32 = 1013.25;
+const TENDENCY_HOURS: i64 = 24;
+
+/// Change of surface pressure over the 24 hours up to `hour`, in hPa scaled to
+/// sea level, so a fall counts the same at a high grid cell with less air
+/// above it as in the lowlands. None if `hours` lacks a reading 24 hours
+/// before or either pressure is a stand-in.
+pub fn pressure_tendency_hpa(hours: &[WeatherData], hour: &WeatherData) -> Option<f32> {
+    let has_pressure = |h: &WeatherData| !h.missing.contains(&WeatherField::Pressure);
+    let before = hours.iter().find(|h| {
+        h.timestamp == hour.timestamp - Duration::hours(TENDENCY_HOURS) && has_pressure(h)
+    })?;
+    if !has_pressure(hour) || hour.pressure <= 0.0 {
+        return None;
+    }
+    Some((hour.pressure - before.pressure) * SEA_LEVEL_PRESSURE_HPA / hour.pressure)
+}
+
+/// Temperature at 850 hPa minus that of surface air lifted dry adiabatically
+/// from `grid_elevation` to it, in Kelvin. Below zero the lifted air is warmer
+/// than its surroundings and keeps rising, so the lower the index the less
+/// stable the air. None if the 850 hPa level is missing or not above the grid cell.
+pub fn stability_index(hour: &WeatherData, grid_elevation: f64) -> Option<f32> {
+    let temperature_850hpa = hour.temperature_850hpa?;
+    let height = f64::from(hour.height_850hpa_m?);
+    if height <= grid_elevation {
+        return None;
+    }
+    let lifted = temperature_at_altitude(
+        hour.temperature,
+        grid_elevation,
+        height,
+        DRY_ADIABATIC_LAPSE_RATE_K_PER_M,
+    );
+    Some(temperature_850hpa - lifted)
+}
+
+/// A wind split relative to a slope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindComponents {
+    /// Straight up the slope; negative for tailwind
+    pub headwind: WindSpeed,
+    /// Across the slope, to either side
+    pub crosswind: WindSpeed,
+}
+
+/// Splits a wind blowing from `direction` into the parts along and across a
+/// slope that faces into wind from `aspect`, both in degrees.
+pub fn wind_components(speed: WindSpeed, direction: f64, aspect: f64) -> WindComponents {
+    let off_axis = (direction - aspect).to_radians();
+    WindComponents {
+        headwind: WindSpeed::from_ms(speed.ms() * off_axis.cos() as f32),
+        crosswind: WindSpeed::from_ms((speed.ms() * off_axis.sin() as f32).abs()),
+    }
+}
+
+/// Model that fills in the days past the regular forecast horizon. Its output is a
+/// tendency at best.
+pub const EXTENDED_RANGE_MODEL: &str = "gfs_seamless";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WeatherForecast {
     pub location: Location,
+    /// Elevation of the model grid cell the forecast refers to, in meters
+    #[serde(default)]
+    pub grid_elevation: Option<f64>,
+    /// When the provider was asked for the forecast, which tells model runs
+    /// apart; None for forecasts that didn't come from a provider
+    #[serde(default)]
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Id of the provider the forecast came from, e.g. `open_meteo`
+    #[serde(default)]
+    pub provider: Option<String>,
     pub forecast: Vec<WeatherData>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct WeatherData {
     /// Timestamp for this weather observation
     pub timestamp: DateTime<Utc>,
     /// Temperature in Celsius
     pub temperature: f32,
+    /// Dew point in Celsius
+    pub dew_point: f32,
     /// Wind speed in m/s
     pub wind_speed_ms: f32,
     /// Wind direction in degrees (0-360, where 0/360 is North)
@@ -52,6 +183,8 @@ pub struct WeatherData {
     pub wind_gust_ms: f32,
     /// Precipitation amount in mm
     pub precipitation: f32,
+    /// Chance of precipitation during the hour in percent, if the model provides it
+    pub precipitation_probability: Option<u8>,
     /// Cloud cover percentage (0-100, optional)
     pub cloud_cover: u8,
     /// Atmospheric pressure in hPa
@@ -60,11 +193,45 @@ pub struct WeatherData {
     pub visibility: f32,
     /// Human-readable description of weather conditions
     pub description: String,
+    /// Snow depth on the ground in meters
+    pub snow_depth_m: f32,
+    /// Snowfall during the preceding hour in centimeters
+    pub snowfall_cm: f32,
+    /// Height of the planetary boundary layer above ground in meters, if the model provides it
+    pub boundary_layer_height_m: Option<f32>,
+    /// Wind speed 80 m above ground in m/s, if the model provides it
+    pub wind_speed_80m_ms: Option<f32>,
+    /// Wind direction 80 m above ground in degrees
+    pub wind_direction_80m: Option<u16>,
+    /// Wind speed at 850 hPa (roughly 1500 m) in m/s, if the model provides it
+    pub wind_speed_850hpa_ms: Option<f32>,
+    /// Wind direction at 850 hPa in degrees
+    pub wind_direction_850hpa: Option<u16>,
+    /// Temperature at 850 hPa in Celsius, if the model provides it
+    pub temperature_850hpa: Option<f32>,
+    /// Height of the 850 hPa level above sea level in meters
+    pub height_850hpa_m: Option<f32>,
+    /// Convective available potential energy in J/kg, if the model provides it
+    pub cape: Option<f32>,
+    /// Height of the 0 °C isotherm above sea level in meters, if the model provides it
+    pub freezing_level_m: Option<f32>,
+    /// Weather model that produced this hour, if known
+    pub model: Option<String>,
+    /// Fields the model left out for this hour and that hold a stand-in value
+    pub missing: Vec<WeatherField>,
 }
 
 impl WeatherData {
     pub fn kelvin_to_celsius(kelvin: f32) -> f32 {
-        kelvin - 273.15
+        Temperature::from_kelvin(kelvin).celsius()
+    }
+
+    pub fn wind_speed(&self) -> WindSpeed {
+        WindSpeed::from_ms(self.wind_speed_ms)
+    }
+
+    pub fn wind_gust(&self) -> WindSpeed {
+        WindSpeed::from_ms(self.wind_gust_ms)
     }
 
     pub fn wind_direction_to_cardinal(degrees: u16) -> &'static str {
@@ -91,6 +258,65 @@ impl WeatherData {
 
 }
 
+/// Hourly fields a model grid cell may leave out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherField {
+    Temperature,
+    DewPoint,
+    WindSpeed,
+    WindDirection,
+    WindGust,
+    Precipitation,
+    CloudCover,
+    Pressure,
+    Visibility,
+}
+
+impl WeatherField {
+    pub const ALL: [WeatherField; 9] = [
+        WeatherField::Temperature,
+        WeatherField::DewPoint,
+        WeatherField::WindSpeed,
+        WeatherField::WindDirection,
+        WeatherField::WindGust,
+        WeatherField::Precipitation,
+        WeatherField::CloudCover,
+        WeatherField::Pressure,
+        WeatherField::Visibility,
+    ];
+}
+
+/// How many of the hours have a real value for `field`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldAvailability {
+    pub field: WeatherField,
+    pub available_hours: usize,
+    pub hours: usize,
+}
+
+/// Availability of every field over `hours`.
+pub fn field_availability(hours: &[WeatherData]) -> Vec<FieldAvailability> {
+    WeatherField::ALL
+        .into_iter()
+        .map(|field| FieldAvailability {
+            field,
+            available_hours: hours.iter().filter(|h| !h.missing.contains(&field)).count(),
+            hours: hours.len(),
+        })
+        .collect()
+}
+
+/// Share of all fields of `hours` that hold a real value, from 0 to 1.
+pub fn data_completeness(hours: &[WeatherData]) -> f32 {
+    let total = hours.len() * WeatherField::ALL.len();
+    if total == 0 {
+        return 1.0;
+    }
+    let missing: usize = hours.iter().map(|h| h.missing.len()).sum();
+    1.0 - missing as f32 / total as f32
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherModel {
     pub id: String,
@@ -100,8 +326,23 @@ pub struct WeatherModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use rstest::rstest;
 
+    #[rstest]
+    #[case::warm_and_low(20.0, 1000.0, 4.0, 4.6)]
+    #[case::cold_and_high(-30.0, 400.0, 8.0, 9.0)]
+    fn moist_lapse_rate_is_below_the_dry_one(
+        #[case] temperature: f64,
+        #[case] pressure_hpa: f64,
+        #[case] min_k_per_km: f64,
+        #[case] max_k_per_km: f64,
+    ) {
+        let rate = moist_adiabatic_lapse_rate_k_per_m(temperature, pressure_hpa) * 1000.0;
+        assert!((min_k_per_km..max_k_per_km).contains(&rate), "got {rate}");
+        assert!(rate < DRY_ADIABATIC_LAPSE_RATE_K_PER_M * 1000.0);
+    }
+
     #[test]
     fn kelvin_to_celsius_known_values() {
         assert!((WeatherData::kelvin_to_celsius(273.15) - 0.0).abs() < 0.001);
@@ -133,6 +374,121 @@ mod tests {
         assert_eq!(WeatherData::wind_direction_to_cardinal(deg), expected);
     }
 
+    #[test]
+    fn completeness_counts_missing_fields_per_hour() {
+        let hours = [
+            WeatherData::default(),
+            WeatherData {
+                missing: vec![WeatherField::WindGust, WeatherField::Visibility],
+                ..Default::default()
+            },
+            WeatherData {
+                missing: vec![WeatherField::Visibility],
+                ..Default::default()
+            },
+        ];
+        assert!((data_completeness(&hours) - 24.0 / 27.0).abs() < 1e-6);
+        assert_eq!(data_completeness(&[]), 1.0);
+
+        let availability = field_availability(&hours);
+        let of = |field| {
+            availability
+                .iter()
+                .find(|a| a.field == field)
+                .map(|a| a.available_hours)
+        };
+        assert_eq!(of(WeatherField::Temperature), Some(3));
+        assert_eq!(of(WeatherField::WindGust), Some(2));
+        assert_eq!(of(WeatherField::Visibility), Some(1));
+    }
+
+    #[test]
+    fn pressure_tendency_is_scaled_to_sea_level() {
+        let at = |hour: u32, pressure: f32| WeatherData {
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 13, hour, 0, 0).unwrap(),
+            pressure,
+            ..Default::default()
+        };
+        let hours = [at(0, 850.0), at(12, 846.0)];
+        let now = WeatherData {
+            timestamp: hours[0].timestamp + Duration::hours(24),
+            ..at(0, 844.0)
+        };
+        let tendency = pressure_tendency_hpa(&hours, &now).unwrap();
+        assert!(
+            (tendency - -6.0 * 1013.25 / 844.0).abs() < 1e-4,
+            "got {tendency}"
+        );
+        assert_eq!(pressure_tendency_hpa(&hours, &hours[1]), None);
+
+        let unknown = WeatherData {
+            missing: vec![WeatherField::Pressure],
+            ..now
+        };
+        assert_eq!(pressure_tendency_hpa(&hours, &unknown), None);
+    }
+
+    #[test]
+    fn stability_index_compares_lifted_air_with_850hpa() {
+        let hour = |temperature_850hpa| WeatherData {
+            temperature: 25.0,
+            temperature_850hpa: Some(temperature_850hpa),
+            height_850hpa_m: Some(1500.0),
+            ..Default::default()
+        };
+        // Lifted from 500 m, the air cools by 9.8 K to 15.2 °C.
+        let unstable = stability_index(&hour(13.0), 500.0).unwrap();
+        assert!((unstable - -2.2).abs() < 1e-4, "got {unstable}");
+        let stable = stability_index(&hour(18.0), 500.0).unwrap();
+        assert!(stable > 0.0);
+        assert_eq!(stability_index(&hour(13.0), 1600.0), None);
+    }
+
+    #[test]
+    fn cloud_base_follows_dew_point_spread() {
+        assert_eq!(cloud_base_agl_m(20.0, 12.0), 1000.0);
+        assert_eq!(cloud_base_agl_m(10.0, 12.0), 0.0);
+    }
+
+    #[rstest]
+    #[case(5.0, 270, 5.0, 270, 0.0)]
+    #[case(5.0, 270, 10.0, 270, 5.0)]
+    #[case(5.0, 0, 5.0, 180, 10.0)]
+    #[case(3.0, 90, 4.0, 180, 5.0)]
+    fn wind_vector_difference_cases(
+        #[case] speed_a: f32,
+        #[case] direction_a: u16,
+        #[case] speed_b: f32,
+        #[case] direction_b: u16,
+        #[case] expected: f32,
+    ) {
+        let d = wind_vector_difference_ms(speed_a, direction_a, speed_b, direction_b);
+        assert!((d - expected).abs() < 1e-4, "got {d}");
+    }
+
+    #[rstest]
+    #[case(270.0, 15.0, 0.0)]
+    #[case(290.0, 14.1, 5.1)]
+    #[case(180.0, 0.0, 15.0)]
+    #[case(90.0, -15.0, 0.0)]
+    fn wind_components_relative_to_a_west_facing_slope(
+        #[case] direction: f64,
+        #[case] headwind_kmh: f32,
+        #[case] crosswind_kmh: f32,
+    ) {
+        let c = wind_components(WindSpeed::from_kmh(15.0), direction, 270.0);
+        assert!((c.headwind.kmh() - headwind_kmh).abs() < 0.1, "{c:?}");
+        assert!((c.crosswind.kmh() - crosswind_kmh).abs() < 0.1, "{c:?}");
+    }
+
+    #[test]
+    fn temperature_at_altitude_cools_going_up_and_warms_going_down() {
+        let up = temperature_at_altitude(20.0, 0.0, 1000.0, DRY_ADIABATIC_LAPSE_RATE_K_PER_M);
+        assert!((up - 10.2).abs() < 1e-4, "got {up}");
+        let down = temperature_at_altitude(5.0, 1500.0, 500.0, ENVIRONMENTAL_LAPSE_RATE_K_PER_M);
+        assert!((down - 11.5).abs() < 1e-4, "got {down}");
+    }
+
     #[test]
     fn sunrise_sunset_returns_sunrise_before_sunset() {
         let loc = Location::new(50.7, 13.0, "Test".into(), "DE".into());
@@ -0,0 +1,242 @@
+//! A C ABI for mobile apps, so an iOS or Android wrapper can generate
+//! forecasts on the device instead of running the HTTP server. Build it with
+//! `cargo rustc --lib --release --no-default-features --features ffi
+//! --crate-type staticlib` (or `cdylib` for Android); `include/travelai.h`
+//! declares the functions.
+//!
+//! Every call returns a JSON string the caller frees with
+//! [`travelai_string_free`]: the result, or `{"error": {"code", "message"}}`
+//! with the same codes as the HTTP API.
+
+use std::{
+    ffi::{CStr, CString, c_char},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    ptr,
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::runtime::Runtime;
+
+use crate::{
+    adapters::{
+        activities::paragliding::site_evaluator::{self, Hysteresis, WindLimits},
+        cache::PersistentCache,
+        dwd_mosmix::DwdMosmixClient,
+        open_meteo::OpenMeteoClient,
+        weather_providers::WeatherProviders,
+    },
+    app_state::build_http_client,
+    config::{ForecastConfig, WindowConfig},
+    domain::{
+        error::{ErrorCode, TravelAiError},
+        location::Location,
+        paragliding::ParaglidingSite,
+        ports::WeatherProvider,
+    },
+};
+
+/// Opaque to C. Owns the runtime the blocking calls run on and the forecast
+/// cache on disk.
+pub struct TravelAi {
+    runtime: Runtime,
+    weather: Arc<dyn WeatherProvider>,
+    hysteresis: Hysteresis,
+    _db: fjall::Database,
+}
+
+impl TravelAi {
+    fn open(data_dir: &Path) -> Result<Self> {
+        // Fails if the app installed one already, which is just as good.
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let db = fjall::Database::builder(data_dir).open()?;
+        let cache_ks = db.keyspace("cache", fjall::KeyspaceCreateOptions::default)?;
+        let cache = Arc::new(PersistentCache::from_keyspace(cache_ks));
+        let http = build_http_client();
+        let open_meteo = Arc::new(OpenMeteoClient::new(
+            cache.clone(),
+            http.clone(),
+            ForecastConfig::load().days,
+        ));
+        let mosmix = Arc::new(DwdMosmixClient::new(cache, http));
+        Ok(Self {
+            runtime,
+            weather: Arc::new(WeatherProviders::new(vec![open_meteo, mosmix])),
+            hysteresis: (&WindowConfig::load()).into(),
+            _db: db,
+        })
+    }
+}
+
+/// Opens the forecast cache in `data_dir`, e.g. the app's cache directory.
+/// Returns null if it can't be opened.
+///
+/// # Safety
+///
+/// `data_dir` must be a valid NUL-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn travelai_open(data_dir: *const c_char) -> *mut TravelAi {
+    let opened = panic::catch_unwind(|| {
+        let data_dir = unsafe { string(data_dir) }?;
+        TravelAi::open(Path::new(&data_dir))
+    });
+    match opened {
+        Ok(Ok(travelai)) => Box::into_raw(Box::new(travelai)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `travelai` must come from [`travelai_open`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn travelai_close(travelai: *mut TravelAi) {
+    if !travelai.is_null() {
+        drop(unsafe { Box::from_raw(travelai) });
+    }
+}
+
+/// Hourly weather forecast for the coordinates, like `GET /forecast`.
+///
+/// # Safety
+///
+/// `travelai` must come from [`travelai_open`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn travelai_forecast(
+    travelai: *const TravelAi,
+    latitude: f64,
+    longitude: f64,
+) -> *mut c_char {
+    respond(|| {
+        let travelai = unsafe { travelai.as_ref() }.context("No TravelAi handle")?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(
+                TravelAiError::new(ErrorCode::InvalidCoordinates, "Invalid coordinates")
+                    .with_context("latitude", latitude)
+                    .with_context("longitude", longitude)
+                    .into(),
+            );
+        }
+        let location = Location::new(latitude, longitude, String::new(), String::new());
+        travelai
+            .runtime
+            .block_on(travelai.weather.get_forecast(location, None))
+    })
+}
+
+/// Daily flyability of a site given as JSON, forecast at its first launch,
+/// like `GET /sites/{name}/forecast`.
+///
+/// # Safety
+///
+/// `travelai` must come from [`travelai_open`] and `site_json` must be a
+/// valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn travelai_site_forecast(
+    travelai: *const TravelAi,
+    site_json: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let travelai = unsafe { travelai.as_ref() }.context("No TravelAi handle")?;
+        let site: ParaglidingSite = serde_json::from_str(&unsafe { string(site_json) }?)
+            .map_err(|e| TravelAiError::invalid_input(format!("Invalid site: {e}")))?;
+        let launch = site
+            .launches
+            .first()
+            .ok_or_else(|| TravelAiError::not_found("Launch", &site.name))?;
+        travelai.runtime.block_on(async {
+            let forecast = travelai
+                .weather
+                .get_forecast(
+                    launch.location.clone(),
+                    site.preferred_weather_model.clone(),
+                )
+                .await?;
+            Ok(site_evaluator::evaluate_site_with_limits(
+                &site,
+                &forecast,
+                &WindLimits::default(),
+                travelai.hysteresis,
+            ))
+        })
+    })
+}
+
+/// # Safety
+///
+/// `s` must be a string returned by this library, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn travelai_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// # Safety
+///
+/// `s` must be null or a valid NUL-terminated string.
+unsafe fn string(s: *const c_char) -> Result<String> {
+    if s.is_null() {
+        return Err(TravelAiError::invalid_input("Missing string argument").into());
+    }
+    let s = unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| TravelAiError::invalid_input("String argument is not UTF-8"))?;
+    Ok(s.to_string())
+}
+
+/// Runs `f` and serializes what it returns, turning errors and panics into
+/// error JSON since neither may cross the C boundary.
+fn respond<T: Serialize>(f: impl FnOnce() -> Result<T>) -> *mut c_char {
+    let json = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => serde_json::to_string(&value).map_err(anyhow::Error::from),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(anyhow::anyhow!("Panicked")),
+    }
+    .unwrap_or_else(|e| error_json(&e));
+    CString::new(json).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn error_json(error: &anyhow::Error) -> String {
+    let (code, message) = match TravelAiError::find(error) {
+        Some(typed) => (typed.code, typed.message.clone()),
+        None => (ErrorCode::Internal, format!("{error:#}")),
+    };
+    serde_json::json!({ "error": { "code": code, "message": message } }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(s: *mut c_char) -> serde_json::Value {
+        let json = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { travelai_string_free(s) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn invalid_input_is_reported_as_error_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let travelai = unsafe { travelai_open(data_dir.as_ptr()) };
+        assert!(!travelai.is_null());
+
+        let response = take(unsafe { travelai_forecast(travelai, 91.0, 13.0) });
+        assert_eq!(response["error"]["code"], "invalid_coordinates");
+
+        let site = CString::new("{\"name\": \"Wank\"}").unwrap();
+        let response = take(unsafe { travelai_site_forecast(travelai, site.as_ptr()) });
+        assert_eq!(response["error"]["code"], "invalid_input");
+
+        let response = take(unsafe { travelai_site_forecast(travelai, ptr::null()) });
+        assert_eq!(response["error"]["message"], "Missing string argument");
+
+        unsafe { travelai_close(travelai) };
+    }
+}
@@ -0,0 +1,10 @@
+pub mod adapters;
+pub mod app_state;
+pub mod application;
+pub mod config;
+pub mod domain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod logging;
+pub mod telemetry;
+pub mod web;
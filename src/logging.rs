@@ -0,0 +1,201 @@
+//! Sets up the tracing subscriber from [`LoggingConfig`]: text or JSON lines on
+//! stdout or in a size-rotated file, plus the OpenTelemetry export if
+//! [`TelemetryConfig`] names an endpoint.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt::writer::BoxMakeWriter, layer::SubscriberExt,
+    util::SubscriberInitExt,
+};
+
+use crate::{
+    config::{LogFormat, LoggingConfig, TelemetryConfig},
+    telemetry,
+};
+
+pub type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+pub fn init(config: &LoggingConfig, telemetry: &TelemetryConfig) -> Result<()> {
+    let mut layers = telemetry::layers(telemetry)?;
+    layers.push(output_layer(config)?);
+    tracing_subscriber::registry().with(layers).init();
+    Ok(())
+}
+
+/// Where log lines go and how they look. `RUST_LOG` overrides the configured
+/// filter, e.g. to debug one module without touching the config.
+fn output_layer(config: &LoggingConfig) -> Result<BoxedLayer> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(&config.filter)
+            .with_context(|| format!("Invalid LOG_FILTER {:?}", config.filter))?,
+    };
+    let writer = match &config.file {
+        Some(path) => BoxMakeWriter::new(Mutex::new(RollingFile::open(
+            path,
+            config.max_size_bytes,
+            config.max_files,
+        )?)),
+        None => BoxMakeWriter::new(io::stdout),
+    };
+    let layer = tracing_subscriber::fmt::layer()
+        .with_file(true)
+        .with_line_number(true)
+        .with_ansi(config.file.is_none())
+        .with_writer(writer);
+    let layer = match config.format {
+        LogFormat::Text => layer.with_filter(filter).boxed(),
+        // The spans an event happened in are listed outermost first
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_filter(filter)
+            .boxed(),
+    };
+    Ok(layer)
+}
+
+/// A log file that is renamed to `<name>.1` once it exceeds `max_size`,
+/// shifting older ones up to `<name>.<max_files>`, beyond which they are
+/// deleted.
+pub struct RollingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingFile {
+    pub fn open(path: &Path, max_size: u64, max_files: usize) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+        }
+        let file =
+            append(path).with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated(n);
+                if from.exists() {
+                    fs::rename(from, self.rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+            self.file = append(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        name.into()
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for RollingFile {
+    /// Rotates before a write that would exceed the size, so a log line is
+    /// never split across files.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    #[test]
+    fn file_is_rotated_once_it_grows_too_large() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/travelai.log");
+        let mut file = RollingFile::open(&path, 10, 2).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.path().join("logs").join(name)).unwrap();
+        assert_eq!(read("travelai.log"), "fourth\n");
+        assert_eq!(read("travelai.log.1"), "third\n");
+        assert_eq!(read("travelai.log.2"), "second\n");
+        assert!(!dir.path().join("logs/travelai.log.3").exists());
+    }
+
+    #[test]
+    fn events_are_written_as_json_lines() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_writer(move || SharedBuffer(writer.clone()))
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("forecast_refresh").entered();
+            tracing::warn!(site = "Wank", retries = 2, "Forecast unavailable");
+        });
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["spans"][0]["name"], "forecast_refresh");
+        assert_eq!(line["fields"]["message"], "Forecast unavailable");
+        assert_eq!(line["fields"]["site"], "Wank");
+        assert_eq!(line["fields"]["retries"], 2);
+    }
+
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
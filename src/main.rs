@@ -1,21 +1,92 @@
-use std::env;
+use std::{env, io::Write, path::PathBuf, time::Duration};
 
-use anyhow::Result;
-use tokio::time;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::{Parser, Subcommand, ValueEnum};
+use tokio::sync::watch;
+use travelai::{
+    adapters::{activities::paragliding::outlook, report, systemd},
+    app_state::AppState,
+    application::{self, geocoding},
+    config::{self, LoggingConfig, TelemetryConfig, profile::ConfigFile, schema},
+    domain::location::Location,
+    logging, web,
+};
 
-use crate::app_state::AppState;
+/// How long a shutdown waits for running jobs, e.g. a calendar sync.
+const JOB_DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
 
-mod adapters;
-mod app_state;
-mod application;
-mod config;
-mod domain;
-mod telemetry;
-mod web;
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Plan once against an in-memory calendar, print the events a sync would
+    /// create and exit without touching Google
+    #[arg(long)]
+    dry_run: bool,
+    /// Profile of the config file to use, e.g. `travel`; defaults to
+    /// `TRAVELAI_PROFILE`
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Also serve the built frontend in this directory, e.g. `frontend/dist`
+    #[arg(long, global = true, value_name = "DIR")]
+    serve_frontend: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the web server and the scheduler until SIGTERM or Ctrl-C (default)
+    Serve,
+    /// Check or describe the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Print the weekend outlook of the sites around home
+    Forecast {
+        #[arg(long, value_enum, default_value_t = ForecastFormat::Markdown)]
+        format: ForecastFormat,
+    },
+    /// List the places a name geocodes to, best match first, with the index to
+    /// pick one by and the closest known site
+    Geocode {
+        /// Place name, e.g. `Gornau/Erz`
+        name: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ForecastFormat {
+    /// Short text for club group chats
+    Markdown,
+    /// One-page briefing for the notice board
+    Pdf,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Report unknown settings and invalid values in the config file, which
+    /// would otherwise silently fall back to defaults
+    Validate {
+        /// Defaults to `TRAVELAI_CONFIG` or `travelai.toml`
+        path: Option<PathBuf>,
+    },
+    /// Print a JSON Schema of the config file
+    Schema,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    telemetry::init_telemetry()?;
+    let cli = Cli::parse();
+    if let Some(Command::Config { command }) = cli.command {
+        return config_command(command);
+    }
+    let profile = config::load_profile(cli.profile.clone())?;
+    logging::init(&LoggingConfig::load()?, &TelemetryConfig::load()?)?;
+    if let Some(profile) = profile {
+        tracing::info!(profile, "Using config profile");
+    }
 
     tracing::info!("Starting travelai application");
 
@@ -29,19 +100,165 @@ async fn main() -> Result<()> {
         .expect("Cache environment variable not set.");
     let db = fjall::Database::builder(&db_path).open()?;
     let state = AppState::new(&db)?;
+    state.site_repo.migrate().await?;
 
-    let job_state = state.clone();
-    tokio::join!(
-        async { web::run(state).await },
-        async move {
-            let mut interval = time::interval(time::Duration::from_hours(8));
-            loop {
-                interval.tick().await;
-                if let Err(e) = application::calendar_job::run(&job_state).await {
-                    tracing::error!(error = ?e, "Failed to create calendar entries");
-                }
+    if cli.dry_run {
+        for event in application::calendar_job::dry_run(&state).await? {
+            println!("{}", event);
+        }
+        return Ok(());
+    }
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(state, cli.serve_frontend).await?,
+        Command::Forecast { format } => forecast(&state, format).await?,
+        Command::Geocode { name } => geocode(&state, &name).await?,
+        Command::Config { .. } => unreachable!("handled before startup"),
+    }
+
+    // Everything written so far survives a power loss once this returns.
+    db.persist(fjall::PersistMode::SyncAll)?;
+    tracing::info!("Shutdown complete");
+    Ok(())
+}
+
+/// Runs without the database or logging, so a broken config can be checked
+/// before anything else reads it.
+fn config_command(command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Validate { path } => {
+            let path = path
+                .or_else(config::config_file_path)
+                .context("No config file; pass a path or set TRAVELAI_CONFIG")?;
+            let file = ConfigFile::read(&path)?;
+            let problems = schema::validate(&file);
+            for problem in &problems {
+                eprintln!("{}: {problem}", path.display());
+            }
+            if !problems.is_empty() {
+                anyhow::bail!("{} problem(s) in {}", problems.len(), path.display());
             }
+            let profiles: Vec<&str> = file.profile_names().collect();
+            println!(
+                "{} is valid, profiles: {}",
+                path.display(),
+                if profiles.is_empty() {
+                    "none".to_string()
+                } else {
+                    profiles.join(", ")
+                }
+            );
+        }
+        ConfigCommand::Schema => {
+            println!("{}", serde_json::to_string_pretty(&schema::json_schema())?);
         }
+    }
+    Ok(())
+}
+
+async fn forecast(state: &AppState, format: ForecastFormat) -> Result<()> {
+    let settings = state.site_repo.get_settings().await?.unwrap_or_default();
+    let home = Location::new(
+        settings.location_latitude,
+        settings.location_longitude,
+        settings.location_name,
+        String::new(),
     );
+    let days = report::weekend(Utc::now().date_naive());
+    let sites = outlook::days_in(
+        &state.site_repo,
+        state.weather.as_ref(),
+        &state.evaluations,
+        &home,
+        &days,
+    )
+    .await?;
+    match format {
+        ForecastFormat::Markdown => println!(
+            "{}",
+            report::markdown::render(&days, &sites.days, &sites.errors)
+        ),
+        ForecastFormat::Pdf => std::io::stdout().write_all(&report::render(
+            &home,
+            &days,
+            &sites.days,
+            &sites.errors,
+        ))?,
+    }
+    Ok(())
+}
+
+async fn geocode(state: &AppState, name: &str) -> Result<()> {
+    let candidates = geocoding::candidates(state, name).await?;
+    if candidates.is_empty() {
+        anyhow::bail!("No place called {name:?}");
+    }
+    for place in geocoding::describe(state, candidates).await {
+        let location = &place.location;
+        let area = place
+            .admin_area
+            .map(|area| format!("{area}, "))
+            .unwrap_or_default();
+        let nearest = place
+            .nearest_site
+            .map(|site| format!(", {:.0} km from {}", site.distance_km, site.name))
+            .unwrap_or_default();
+        println!(
+            "{}: {} ({}{}) {:.4}, {:.4}{}",
+            place.index,
+            location.name,
+            area,
+            location.country,
+            location.latitude,
+            location.longitude,
+            nearest
+        );
+    }
+    Ok(())
+}
+
+/// Runs until a shutdown signal, then stops taking requests and starting jobs
+/// and waits for the ones in flight.
+async fn serve(state: AppState, frontend: Option<PathBuf>) -> Result<()> {
+    #[cfg(feature = "grpc")]
+    tokio::spawn(travelai::adapters::grpc::run(state.clone()));
+    #[cfg(feature = "discord")]
+    if let Some(config) = config::DiscordConfig::load()? {
+        tokio::spawn(travelai::adapters::discord::run(state.clone(), config));
+    }
+
+    // Loaded here so a bad limit stops startup with its message
+    let rate_limit = config::RateLimitConfig::load()?;
+    let (stop_tx, mut stop_rx) = watch::channel(());
+    let web = tokio::spawn(web::run(state.clone(), frontend, rate_limit, async move {
+        let _ = stop_rx.changed().await;
+    }));
+    let schedules = tokio::spawn(state.scheduler.clone().run(state.clone()));
+
+    shutdown_signal().await?;
+    tracing::info!("Shutting down");
+    systemd::notify("STOPPING=1");
+
+    schedules.abort();
+    let _ = stop_tx.send(());
+    web.await?;
+    if !state.scheduler.drain(JOB_DRAIN_TIMEOUT).await {
+        tracing::warn!("Jobs still running after the shutdown timeout, stopping anyway");
+    }
+    Ok(())
+}
+
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
     Ok(())
 }
@@ -1,60 +1,77 @@
-use std::env;
+//! Exports traces, metrics and logs via OTLP, e.g. to Tempo or Jaeger, when
+//! an endpoint is configured and the `otel` feature is enabled.
 
+#[cfg(feature = "otel")]
+use anyhow::Context;
 use anyhow::Result;
-use opentelemetry::global;
-use opentelemetry::trace::TracerProvider;
+#[cfg(feature = "otel")]
+use opentelemetry::{global, trace::TracerProvider};
+#[cfg(feature = "otel")]
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+#[cfg(feature = "otel")]
 use opentelemetry_otlp::{Protocol, WithExportConfig};
-use opentelemetry_sdk::Resource;
-use opentelemetry_sdk::metrics::PeriodicReader;
-use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
-
-pub fn init_telemetry() -> Result<()> {
-    let otel_endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
-    let service_name = env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "travelai".to_string());
-
-    if otel_endpoint
-        .as_ref()
-        .map(|s| !s.is_empty())
-        .unwrap_or(false)
+#[cfg(feature = "otel")]
+use opentelemetry_sdk::{Resource, metrics::PeriodicReader, trace::Sampler};
+#[cfg(feature = "otel")]
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::{config::TelemetryConfig, logging::BoxedLayer};
+
+/// The layers exporting traces and logs, empty without an endpoint. Metrics
+/// are exported too.
+pub fn layers(config: &TelemetryConfig) -> Result<Vec<BoxedLayer>> {
+    let Some(endpoint) = &config.endpoint else {
+        return Ok(Vec::new());
+    };
+    #[cfg(feature = "otel")]
     {
-        eprintln!("Initializing OpenTelemetry for production");
-        init_production_telemetry(otel_endpoint.unwrap(), service_name)?;
-    } else {
-        eprintln!("Initializing stdout logging for development");
-        init_development_logging();
+        eprintln!("Exporting telemetry to {endpoint}");
+        otlp_layers(endpoint, config)
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        eprintln!("Not exporting telemetry to {endpoint}: built without the otel feature");
+        Ok(Vec::new())
     }
-
-    Ok(())
 }
 
-fn init_production_telemetry(otel_endpoint: String, service_name: String) -> Result<()> {
+#[cfg(feature = "otel")]
+fn otlp_layers(endpoint: &str, config: &TelemetryConfig) -> Result<Vec<BoxedLayer>> {
+    let protocol = match config.protocol.as_str() {
+        "http/protobuf" => Protocol::HttpBinary,
+        _ => Protocol::HttpJson,
+    };
     let resource = Resource::builder()
-        .with_service_name(service_name.clone())
+        .with_service_name(config.service_name.clone())
         .build();
 
     // Trace exporter
     let http_exporter = opentelemetry_otlp::SpanExporter::builder()
         .with_http()
-        .with_endpoint(otel_endpoint.clone())
-        .with_protocol(Protocol::HttpJson)
-        .build()?;
+        .with_endpoint(endpoint)
+        .with_protocol(protocol)
+        .build()
+        .context("Failed to build OTLP exporter")?;
 
     let tracer_provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
         .with_batch_exporter(http_exporter)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
         .with_resource(resource.clone())
         .build();
 
-    let tracer = tracer_provider.tracer(service_name);
+    let tracer = tracer_provider.tracer(config.service_name.clone());
     global::set_tracer_provider(tracer_provider);
     let trace_layer = tracing_opentelemetry::layer().with_tracer(tracer);
 
     // Metrics exporter
     let metrics_exporter = opentelemetry_otlp::MetricExporter::builder()
         .with_http()
-        .with_endpoint(otel_endpoint.clone())
-        .with_protocol(Protocol::HttpJson)
-        .build()?;
+        .with_endpoint(endpoint)
+        .with_protocol(protocol)
+        .build()
+        .context("Failed to build OTLP exporter")?;
 
     let meter_provider = opentelemetry_sdk::metrics::SdkMeterProvider::builder()
         .with_reader(PeriodicReader::builder(metrics_exporter).build())
@@ -65,9 +82,10 @@ fn init_production_telemetry(otel_endpoint: String, service_name: String) -> Res
     // Logs exporter (if supported)
     let logs_exporter = opentelemetry_otlp::LogExporter::builder()
         .with_http()
-        .with_endpoint(otel_endpoint)
-        .with_protocol(Protocol::HttpJson)
-        .build()?;
+        .with_endpoint(endpoint)
+        .with_protocol(protocol)
+        .build()
+        .context("Failed to build OTLP exporter")?;
 
     let logger_provider = opentelemetry_sdk::logs::SdkLoggerProvider::builder()
         .with_batch_exporter(logs_exporter)
@@ -96,32 +114,5 @@ fn init_production_telemetry(otel_endpoint: String, service_name: String) -> Res
         .add_directive("reqwest=off".parse().unwrap());
     let otel_layer = otel_layer.with_filter(filter_otel);
 
-    // Create a new tracing::Fmt layer to print the logs to stdout. It has a
-    // default filter of `info` level and above, and `debug` and above for logs
-    // from OpenTelemetry crates. The filter levels can be customized as needed.
-    let filter_fmt = EnvFilter::new("info").add_directive("opentelemetry=info".parse().unwrap());
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_thread_names(true)
-        .with_filter(filter_fmt);
-
-    // Initialize the tracing subscriber with the OpenTelemetry layer and the
-    // Fmt layer.
-    tracing_subscriber::registry()
-        .with(trace_layer)
-        .with(otel_layer)
-        .with(fmt_layer)
-        .init();
-    Ok(())
-}
-
-fn init_development_logging() {
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .event_format(
-            tracing_subscriber::fmt::format()
-                .with_file(true)
-                .with_line_number(true),
-        )
-        .init();
+    Ok(vec![trace_layer.boxed(), otel_layer.boxed()])
 }
@@ -1,14 +1,39 @@
-use axum::{Router, extract::Query, extract::State, routing::get};
+use axum::{
+    Router,
+    body::Body,
+    extract::Query,
+    extract::State,
+    http::{HeaderValue, Request, Response},
+    middleware,
+    routing::get,
+};
 #[cfg(feature = "tls")]
 use axum_server::tls_rustls::RustlsConfig;
-use std::collections::HashMap;
-use tower_http::cors::{Any, CorsLayer};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::limit::RequestBodyLimitLayer;
-use tower_http::services::ServeDir;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::services::{ServeDir, ServeFile};
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
+use tracing::Span;
 
-use crate::{adapters::http, app_state::AppState, config};
+use crate::{
+    adapters::{
+        http,
+        rate_limit::{self, RateLimiter},
+        systemd,
+    },
+    app_state::AppState,
+    config,
+};
 
 async fn oauth_callback(
     State(state): State<AppState>,
@@ -28,19 +53,89 @@ async fn oauth_callback(
     }
 }
 
-pub async fn run(state: AppState) {
-    let config = config::WebConfig::load().unwrap();
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
+fn request_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("-");
+    tracing::info_span!(
+        "http_request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    )
+}
+
+fn access_log(response: &Response<Body>, latency: Duration, span: &Span) {
+    let status = response.status().as_u16();
+    let latency_ms = latency.as_millis() as u64;
+    span.record("status", status);
+    span.record("latency_ms", latency_ms);
+    tracing::info!(status, latency_ms, "access");
+}
+
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    let allow_origin = if allowed_origins.is_empty() {
+        AllowOrigin::from(Any)
+    } else {
+        let origins: Vec<HeaderValue> = allowed_origins
+            .iter()
+            .filter_map(|origin| match HeaderValue::from_str(origin) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!(origin = %origin, "Ignoring invalid CORS origin");
+                    None
+                }
+            })
+            .collect();
+        AllowOrigin::list(origins)
+    };
+    CorsLayer::new()
+        .allow_origin(allow_origin)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+}
 
-    let app = Router::new()
+/// Unknown paths fall back to index.html so client-side routes survive a reload.
+fn frontend_service(dir: &Path) -> ServeDir<ServeFile> {
+    ServeDir::new(dir).fallback(ServeFile::new(dir.join("index.html")))
+}
+
+/// Serves until `shutdown` completes, then lets in-flight requests finish.
+/// With `frontend`, the built bundle in that directory is served besides the
+/// API. API requests are limited by `rate_limit`.
+pub async fn run(
+    state: AppState,
+    frontend: Option<PathBuf>,
+    rate_limit: config::RateLimitConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
+    let config = config::WebConfig::load().unwrap();
+    let cors = cors_layer(&config.cors_allowed_origins);
+
+    let limiter = Arc::new(RateLimiter::new(rate_limit));
+    let api = http::router().layer(middleware::from_fn_with_state(limiter, rate_limit::limit));
+
+    let mut app = Router::new()
         .route("/oauth/callback", get(oauth_callback))
-        .nest("/api", http::router())
-        .fallback_service(ServeDir::new("frontend/dist"))
-        .layer(TraceLayer::new_for_http())
+        .nest("/api", api);
+    if let Some(dir) = frontend {
+        tracing::info!(dir = %dir.display(), "Serving frontend");
+        app = app.fallback_service(frontend_service(&dir));
+    }
+    let app = app
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(request_span)
+                .on_response(access_log),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(cors)
+        .layer(CompressionLayer::new().gzip(true).br(true))
         .layer(TimeoutLayer::with_status_code(
             axum::http::StatusCode::REQUEST_TIMEOUT,
             std::time::Duration::from_secs(300),
@@ -59,8 +154,25 @@ pub async fn run(state: AppState) {
                 .await
                 .expect("Failed to load TLS config");
 
+            let handle = axum_server::Handle::new();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    shutdown.await;
+                    handle.graceful_shutdown(None);
+                }
+            });
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    if handle.listening().await.is_some() {
+                        systemd::notify("READY=1");
+                    }
+                }
+            });
             axum_server::bind_rustls(addr.parse().unwrap(), config)
-                .serve(app.into_make_service())
+                .handle(handle)
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>())
                 .await
                 .expect("HTTPS server error");
             return;
@@ -68,5 +180,12 @@ pub async fn run(state: AppState) {
     }
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    systemd::notify("READY=1");
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown)
+    .await
+    .unwrap();
 }
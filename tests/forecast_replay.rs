@@ -0,0 +1,71 @@
+//! Runs the forecast pipeline against recorded Open-Meteo responses in
+//! `tests/fixtures/http`. Record new fixtures with
+//! `HTTP_FIXTURES=record:tests/fixtures/http` and the `record-replay` feature.
+
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use travelai::{
+    adapters::{
+        activities::paragliding::site_evaluator,
+        cache::PersistentCache,
+        open_meteo::OpenMeteoClient,
+        record_replay::{Mode, RecordReplay},
+    },
+    domain::{
+        location::Location,
+        paragliding::{ParaglidingLaunch, ParaglidingSite, SiteType},
+        ports::WeatherProvider,
+    },
+};
+
+fn replaying_client(cache_dir: &std::path::Path) -> OpenMeteoClient {
+    let db = fjall::Database::builder(cache_dir).open().unwrap();
+    let ks = db
+        .keyspace("cache", fjall::KeyspaceCreateOptions::default)
+        .unwrap();
+    let http = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(RecordReplay::new(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/http"),
+            Mode::Replay,
+        ))
+        .build();
+    OpenMeteoClient::new(Arc::new(PersistentCache::from_keyspace(ks)), http, 7)
+}
+
+fn site(location: &Location) -> ParaglidingSite {
+    ParaglidingSite {
+        name: "Replay".into(),
+        launches: vec![ParaglidingLaunch {
+            site_type: SiteType::Hang,
+            location: location.clone(),
+            direction_degrees_start: 90.0,
+            direction_degrees_stop: 180.0,
+            elevation: 520.0,
+        }],
+        country: Some("DE".into()),
+        data_source: "test".into(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn recorded_forecast_yields_the_expected_flyable_window() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = replaying_client(dir.path());
+    let location = Location::new(50.7, 13.0, "Replay".into(), "DE".into());
+
+    let forecast = client.get_forecast(location.clone(), None).await.unwrap();
+    assert_eq!(forecast.forecast.len(), 24);
+    assert_eq!(forecast.grid_elevation, Some(520.0));
+
+    let eval = site_evaluator::evaluate_site(&site(&location), &forecast).await;
+    let day = &eval.daily_summaries[0];
+    assert_eq!(day.total_flyable_hours, 5);
+    assert_eq!(day.ranges.len(), 1);
+    let hour = |h| Utc.with_ymd_and_hms(2026, 6, 13, h, 0, 0).unwrap();
+    assert_eq!(
+        (day.ranges[0].start, day.ranges[0].end),
+        (hour(10), hour(14))
+    );
+}